@@ -0,0 +1,129 @@
+//! Engine hot paths, measured with criterion.
+//!
+//! - `add_memory`: indexing one memory into an engine that already holds the corpus
+//! - `recall_weighted`: single-cue queries from rare to corpus-wide cues, and a
+//!   mixed query that intersects them
+//! - `scan_limit`: a broad query under different per-cue scan budgets (the
+//!   consolidated search path)
+//! - `snapshot`: writing and reading a snapshot, raw and zstd-compressed
+//!
+//! Compare a branch against a saved baseline (see `benches/BASELINE.md`):
+//!
+//! ```text
+//! cargo bench --bench engine -- --save-baseline main
+//! cargo bench --bench engine -- --baseline main
+//! ```
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use cuemap::engine::{CueMapEngine, RecallFilter, RecallParams};
+use cuemap::persistence::{PersistenceManager, SnapshotCodec, SnapshotCompression};
+use cuemap::structures::MainStats;
+use std::hint::black_box;
+
+const MEMORIES: usize = 20_000;
+
+/// Cues by the share of the corpus they tag
+const SELECTIVITY: [(&str, usize); 4] = [
+    ("rare", 1_000),  // 0.1%
+    ("uncommon", 50), // 2%
+    ("common", 4),    // 25%
+    ("everywhere", 1),
+];
+
+fn cues_for(i: usize) -> Vec<String> {
+    let mut cues = vec![format!("id:{}", i), format!("bucket:{}", i % 100)];
+    for (cue, every) in SELECTIVITY {
+        if i.is_multiple_of(every) {
+            cues.push(cue.to_string());
+        }
+    }
+    cues
+}
+
+fn build_engine(memories: usize) -> CueMapEngine<MainStats> {
+    let engine = CueMapEngine::new();
+    for i in 0..memories {
+        engine.add_memory(format!("memory {}", i), cues_for(i), None, MainStats::default(), true);
+    }
+    engine
+}
+
+fn recall(engine: &CueMapEngine<MainStats>, cues: &[&str], filter: &RecallFilter) -> usize {
+    let query = cues.iter().map(|c| (c.to_string(), 1.0)).collect();
+    engine
+        .recall_weighted(query, 10, &RecallParams { disable_pattern_completion: true, filter: Some(filter), ..RecallParams::default() })
+        .len()
+}
+
+fn bench_add_memory(c: &mut Criterion) {
+    let engine = build_engine(MEMORIES);
+    let mut next = MEMORIES;
+    let mut group = c.benchmark_group("add_memory");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("into_20k", |b| {
+        b.iter(|| {
+            next += 1;
+            black_box(engine.add_memory(format!("memory {}", next), cues_for(next), None, MainStats::default(), true))
+        })
+    });
+    group.finish();
+}
+
+fn bench_recall_selectivity(c: &mut Criterion) {
+    let engine = build_engine(MEMORIES);
+    let filter = RecallFilter::default();
+    let mut group = c.benchmark_group("recall_weighted");
+    for (cue, _) in SELECTIVITY {
+        group.bench_with_input(BenchmarkId::new("single_cue", cue), &cue, |b, cue| {
+            b.iter(|| black_box(recall(&engine, &[cue], &filter)))
+        });
+    }
+    group.bench_function("mixed", |b| {
+        b.iter(|| black_box(recall(&engine, &["rare", "uncommon", "common", "everywhere"], &filter)))
+    });
+    group.finish();
+}
+
+fn bench_scan_limit(c: &mut Criterion) {
+    let engine = build_engine(MEMORIES);
+    let mut group = c.benchmark_group("scan_limit");
+    for limit in [Some(1_000), Some(5_000), None] {
+        let filter = RecallFilter { scan_limit: limit, ..RecallFilter::default() };
+        let label = limit.map_or("default".to_string(), |l| l.to_string());
+        group.bench_with_input(BenchmarkId::from_parameter(label), &filter, |b, filter| {
+            b.iter(|| black_box(recall(&engine, &["common", "everywhere"], filter)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_snapshot(c: &mut Criterion) {
+    let engine = build_engine(MEMORIES);
+    let dir = tempfile::tempdir().unwrap();
+    let mut group = c.benchmark_group("snapshot");
+    group.sample_size(10);
+    for (label, compression) in [
+        ("raw", SnapshotCompression::default()),
+        ("zstd", SnapshotCompression::new(SnapshotCodec::Zstd, 3)),
+    ] {
+        let path = dir.path().join(format!("{}.bin", label));
+        group.bench_function(BenchmarkId::new("save", label), |b| {
+            b.iter(|| PersistenceManager::save_to_path_with_options(&engine, &path, None, compression).unwrap())
+        });
+        // The loaded engine is dropped outside the timed section
+        group.bench_function(BenchmarkId::new("load", label), |b| {
+            b.iter_batched(
+                || (),
+                |_| {
+                    let (memories, cue_index) = PersistenceManager::load_from_path::<MainStats>(&path).unwrap();
+                    black_box(CueMapEngine::<MainStats>::from_state(memories, cue_index))
+                },
+                BatchSize::PerIteration,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_add_memory, bench_recall_selectivity, bench_scan_limit, bench_snapshot);
+criterion_main!(benches);
@@ -0,0 +1,68 @@
+//! Top-k selection in weighted recall, measured with criterion.
+//!
+//! Runs the same broad-cue query through the bounded-heap path and through the
+//! full collect-and-sort path. `min_intersection: Some(1)` keeps every
+//! candidate (they all match at least one cue), so it returns the same results
+//! while forcing the unbounded path.
+//!
+//! ```text
+//! cargo bench --bench recall_topk
+//! cargo bench --bench recall_topk -- --save-baseline main
+//! ```
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use cuemap::engine::{CueMapEngine, RecallFilter, RecallParams};
+use cuemap::structures::MainStats;
+use std::hint::black_box;
+
+const MEMORIES: usize = 50_000;
+
+fn build_engine() -> CueMapEngine<MainStats> {
+    let engine = CueMapEngine::new();
+    for i in 0..MEMORIES {
+        // Three broad cues shared by a third to all of the corpus, plus a unique one
+        let mut cues = vec!["service".to_string(), format!("id:{}", i)];
+        if i % 2 == 0 {
+            cues.push("error".to_string());
+        }
+        if i % 3 == 0 {
+            cues.push("timeout".to_string());
+        }
+        engine.add_memory(format!("memory {}", i), cues, None, MainStats::default(), true);
+    }
+    engine
+}
+
+fn recall(engine: &CueMapEngine<MainStats>, limit: usize, min_intersection: Option<usize>, filter: &RecallFilter) -> usize {
+    let query = vec![
+        ("service".to_string(), 1.0),
+        ("error".to_string(), 1.0),
+        ("timeout".to_string(), 1.0),
+    ];
+    engine
+        .recall_weighted(query, limit, &RecallParams { min_intersection, disable_pattern_completion: true, filter: Some(filter), ..RecallParams::default() })
+        .len()
+}
+
+fn bench_recall_topk(c: &mut Criterion) {
+    let engine = build_engine();
+    let mut group = c.benchmark_group("recall_topk");
+    for (label, filter) in [
+        ("default", RecallFilter::default()),
+        ("scan_limit_20000", RecallFilter { scan_limit: Some(20_000), ..RecallFilter::default() }),
+    ] {
+        for limit in [10, 100] {
+            let parameter = format!("{}/limit_{}", label, limit);
+            group.bench_with_input(BenchmarkId::new("bounded_heap", &parameter), &filter, |b, filter| {
+                b.iter(|| black_box(recall(&engine, limit, None, filter)))
+            });
+            group.bench_with_input(BenchmarkId::new("full_sort", &parameter), &filter, |b, filter| {
+                b.iter(|| black_box(recall(&engine, limit, Some(1), filter)))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_recall_topk);
+criterion_main!(benches);
@@ -0,0 +1,91 @@
+//! Per-memory recall statistics: how often recall returned a memory and when
+//! it last did. Unlike reinforcement these never change scoring; they tell
+//! which memories are dead weight before pruning (`GET /memories/analytics`).
+//!
+//! Every recall that gets a `recall_id` counts its returned memories. The
+//! statistics are saved with the project (`{project}_access.bin`).
+
+use ahash::RandomState;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemoryAccess {
+    /// Recalls that returned the memory
+    pub recall_count: u64,
+    /// Unix seconds of the last of them
+    pub last_recalled_at: Option<u64>,
+}
+
+/// On-disk form of `AccessStats` (`{project}_access.bin`)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AccessSnapshot {
+    pub entries: HashMap<String, MemoryAccess>,
+}
+
+#[derive(Default)]
+pub struct AccessStats {
+    entries: DashMap<String, MemoryAccess, RandomState>,
+}
+
+impl AccessStats {
+    pub fn from_snapshot(snapshot: AccessSnapshot) -> Self {
+        let stats = Self::default();
+        for (id, access) in snapshot.entries {
+            stats.entries.insert(id, access);
+        }
+        stats
+    }
+
+    pub fn to_snapshot(&self) -> AccessSnapshot {
+        AccessSnapshot { entries: self.entries.iter().map(|e| (e.key().clone(), *e.value())).collect() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Count one recall returning `memory_ids`
+    pub fn record(&self, memory_ids: &[String], now: u64) {
+        for id in memory_ids {
+            let mut access = self.entries.entry(id.clone()).or_default();
+            access.recall_count += 1;
+            access.last_recalled_at = Some(now);
+        }
+    }
+
+    pub fn get(&self, memory_id: &str) -> MemoryAccess {
+        self.entries.get(memory_id).map(|a| *a).unwrap_or_default()
+    }
+
+    pub fn remove(&self, memory_id: &str) {
+        self.entries.remove(memory_id);
+    }
+
+    pub fn rename(&self, from: &str, to: &str) {
+        if let Some((_, access)) = self.entries.remove(from) {
+            self.entries.insert(to.to_string(), access);
+        }
+    }
+}
+
+/// A memory no recall has returned
+#[derive(Debug, Clone, Serialize)]
+pub struct IdleMemory {
+    pub memory_id: String,
+    pub created_at: f64,
+    pub salience: f64,
+    pub cues: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MemoryAnalytics {
+    pub total_memories: usize,
+    /// Memories returned by at least one recall
+    pub recalled_count: usize,
+    /// Memories older than `min_age_secs` never returned by a recall
+    pub never_recalled_count: usize,
+    /// Up to `limit` of them, oldest first
+    pub never_recalled: Vec<IdleMemory>,
+}
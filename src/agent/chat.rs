@@ -0,0 +1,298 @@
+//! Chat transcripts: ChatGPT exports, Slack exports and plain `Name: message`
+//! logs.
+//!
+//! A transcript is parsed into speaker turns, and consecutive turns are
+//! grouped into windows: a window closes when it reaches `MAX_WINDOW_TURNS` or
+//! `MAX_WINDOW_CHARS`, or when the conversation pauses for `TOPIC_GAP_SECS`
+//! (a pause usually means a new topic). A long turn is a window of its own.
+//! Each chunk carries a `speaker:` cue per participant and a `turn:` cue with
+//! the number of its first turn; `start_line`/`end_line` hold the turn range.
+
+use super::chunker::{Chunk, ChunkCategory};
+use serde_json::Value;
+use std::path::Path;
+
+/// Turns per chunk
+pub const MAX_WINDOW_TURNS: usize = 8;
+
+/// Characters per chunk (a longer single turn stays whole)
+pub const MAX_WINDOW_CHARS: usize = 1500;
+
+/// A pause this long starts a new chunk
+pub const TOPIC_GAP_SECS: i64 = 30 * 60;
+
+/// Words that precede a colon in logs and notes but aren't speakers
+const NOT_SPEAKERS: &[&str] = &[
+    "info", "warn", "warning", "error", "debug", "trace", "fatal", "note", "todo", "fixme", "see", "example",
+    "re", "fwd", "subject", "from", "to", "date", "step", "q", "a",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Turn {
+    pub speaker: String,
+    pub text: String,
+    /// Unix seconds, when the export has them
+    pub time: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conversation {
+    pub title: Option<String>,
+    /// `chatgpt`, `slack` or `chat`
+    pub platform: &'static str,
+    pub turns: Vec<Turn>,
+}
+
+/// Conversations in a chat export or log; None if `content` isn't one
+pub fn parse_transcript(path: &Path, content: &str) -> Option<Vec<Conversation>> {
+    let trimmed = content.trim_start();
+    if trimmed.starts_with('[') || trimmed.starts_with('{') {
+        if let Some(conversations) = parse_json_transcript(path, content) {
+            return Some(conversations);
+        }
+        // Not a chat export; a plain log may still start with a `[09:00]` timestamp
+    }
+    parse_plain(content).map(|conversation| vec![conversation])
+}
+
+/// Conversations in a JSON chat export (ChatGPT, role/content messages or Slack)
+fn parse_json_transcript(path: &Path, content: &str) -> Option<Vec<Conversation>> {
+    // Cheap checks before parsing a possibly large JSON file
+    if !(content.contains("\"mapping\"") || content.contains("\"role\"") || content.contains("\"ts\"")) {
+        return None;
+    }
+    let value: Value = serde_json::from_str(content).ok()?;
+    let conversations = parse_chatgpt(&value)
+        .or_else(|| parse_role_messages(&value))
+        .or_else(|| parse_slack(&value, slack_channel(path)))?;
+    (!conversations.is_empty()).then_some(conversations)
+}
+
+/// ChatGPT's `conversations.json`: each conversation's message tree, read
+/// along the branch that ends at `current_node`
+pub fn parse_chatgpt(value: &Value) -> Option<Vec<Conversation>> {
+    let items: Vec<&Value> = match value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(_) => vec![value],
+        _ => return None,
+    };
+    if items.is_empty() || !items.iter().all(|c| c["mapping"].is_object()) {
+        return None;
+    }
+
+    let conversations = items
+        .into_iter()
+        .map(|conversation| {
+            let mapping = &conversation["mapping"];
+            let mut branch = Vec::new();
+            let mut node = conversation["current_node"].as_str();
+            while let Some(id) = node {
+                let entry = &mapping[id];
+                if entry.is_null() || branch.len() > 100_000 {
+                    break;
+                }
+                branch.push(entry);
+                node = entry["parent"].as_str();
+            }
+            branch.reverse();
+
+            let turns = branch
+                .into_iter()
+                .filter_map(|entry| {
+                    let message = &entry["message"];
+                    let role = message["author"]["role"].as_str()?;
+                    if !matches!(role, "user" | "assistant") {
+                        return None;
+                    }
+                    let content = &message["content"];
+                    let text = match content["parts"].as_array() {
+                        Some(parts) => parts.iter().filter_map(Value::as_str).collect::<Vec<_>>().join("\n"),
+                        None => content["text"].as_str().unwrap_or("").to_string(),
+                    };
+                    let speaker = message["author"]["name"].as_str().map(str::to_string).unwrap_or_else(|| role_name(role));
+                    Some(Turn {
+                        speaker,
+                        text: text.trim().to_string(),
+                        time: message["create_time"].as_f64().map(|t| t as i64),
+                    })
+                })
+                .filter(|turn| !turn.text.is_empty())
+                .collect();
+            Conversation {
+                title: conversation["title"].as_str().map(str::to_string),
+                platform: "chatgpt",
+                turns,
+            }
+        })
+        .filter(|c| !c.turns.is_empty())
+        .collect();
+    Some(conversations)
+}
+
+/// `[{"role": ..., "content": ...}]`, bare or under `"messages"` (the OpenAI chat format)
+pub fn parse_role_messages(value: &Value) -> Option<Vec<Conversation>> {
+    let messages = value.as_array().or_else(|| value["messages"].as_array())?;
+    if messages.is_empty() || !messages.iter().all(|m| m["role"].is_string()) {
+        return None;
+    }
+    let turns: Vec<Turn> = messages
+        .iter()
+        .filter(|m| m["role"].as_str() != Some("system"))
+        .filter_map(|m| {
+            let text = m["content"].as_str()?.trim().to_string();
+            let speaker = m["name"].as_str().map(str::to_string).unwrap_or_else(|| role_name(m["role"].as_str().unwrap_or("")));
+            (!text.is_empty()).then_some(Turn { speaker, text, time: None })
+        })
+        .collect();
+    Some(vec![Conversation { title: value["title"].as_str().map(str::to_string), platform: "chat", turns }])
+}
+
+/// One day of a Slack channel export (`<channel>/<YYYY-MM-DD>.json`)
+pub fn parse_slack(value: &Value, channel: Option<String>) -> Option<Vec<Conversation>> {
+    let messages = value.as_array()?;
+    if messages.is_empty() || !messages.iter().all(|m| m["type"].as_str() == Some("message") && m["ts"].is_string()) {
+        return None;
+    }
+    let turns = messages
+        .iter()
+        // Joins, leaves, topic changes
+        .filter(|m| !m["subtype"].as_str().is_some_and(|s| s.contains("join") || s.contains("leave") || s.starts_with("channel_")))
+        .filter_map(|m| {
+            let text = m["text"].as_str()?.trim().to_string();
+            let profile = &m["user_profile"];
+            let speaker = profile["real_name"]
+                .as_str()
+                .or_else(|| profile["display_name"].as_str())
+                .or_else(|| m["user_name"].as_str())
+                .or_else(|| m["username"].as_str())
+                .or_else(|| m["user"].as_str())
+                .filter(|s| !s.is_empty())
+                .unwrap_or("unknown")
+                .to_string();
+            let time = m["ts"].as_str().and_then(|ts| ts.parse::<f64>().ok()).map(|t| t as i64);
+            (!text.is_empty()).then_some(Turn { speaker, text, time })
+        })
+        .collect();
+    Some(vec![Conversation { title: channel, platform: "slack", turns }])
+}
+
+/// `Name: message` lines, optionally prefixed with a `[timestamp]`. Lines
+/// that don't start a turn continue the previous one. None unless most lines
+/// are turns and at least two people speak.
+pub fn parse_plain(content: &str) -> Option<Conversation> {
+    let line_re = regex::Regex::new(r"^(?:\[[^\]]{1,40}\]\s*)?([^\s:\[][^:]{0,39}?):\s+(.*\S)\s*$").unwrap();
+    let mut turns: Vec<Turn> = Vec::new();
+    let (mut lines, mut turn_lines) = (0, 0);
+
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        lines += 1;
+        let speaker = line_re
+            .captures(line)
+            .filter(|caps| is_speaker(&caps[1]))
+            .map(|caps| (caps[1].trim().to_string(), caps[2].to_string()));
+        match speaker {
+            Some((speaker, text)) => {
+                turn_lines += 1;
+                turns.push(Turn { speaker, text, time: None });
+            }
+            None => {
+                if let Some(last) = turns.last_mut() {
+                    last.text.push('\n');
+                    last.text.push_str(line.trim());
+                }
+            }
+        }
+    }
+
+    let mut speakers: Vec<&str> = turns.iter().map(|t| t.speaker.as_str()).collect();
+    speakers.sort_unstable();
+    speakers.dedup();
+    if turns.len() < 3 || speakers.len() < 2 || turn_lines * 2 < lines {
+        return None;
+    }
+    Some(Conversation { title: None, platform: "chat", turns })
+}
+
+/// Chunks of a conversation's turns, grouped into windows
+pub fn chunk_conversation(conversation: &Conversation) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < conversation.turns.len() {
+        let mut end = start + 1;
+        let mut chars = conversation.turns[start].text.len();
+        while end < conversation.turns.len() && end - start < MAX_WINDOW_TURNS {
+            let turn = &conversation.turns[end];
+            let gap = turn.time.zip(conversation.turns[end - 1].time).map(|(t, prev)| t - prev);
+            if chars + turn.text.len() > MAX_WINDOW_CHARS || gap.is_some_and(|g| g >= TOPIC_GAP_SECS) {
+                break;
+            }
+            chars += turn.text.len();
+            end += 1;
+        }
+        chunks.push(window_chunk(conversation, start, end));
+        start = end;
+    }
+    chunks
+}
+
+fn window_chunk(conversation: &Conversation, start: usize, end: usize) -> Chunk {
+    let turns = &conversation.turns[start..end];
+    let content = turns.iter().map(|t| format!("{}: {}", t.speaker, t.text)).collect::<Vec<_>>().join("\n");
+
+    let mut cues = vec!["type:chat".to_string()];
+    if conversation.platform != "chat" {
+        cues.push(format!("platform:{}", conversation.platform));
+    }
+    for turn in turns {
+        let cue = format!("speaker:{}", speaker_cue(&turn.speaker));
+        if !cues.contains(&cue) {
+            cues.push(cue);
+        }
+    }
+    cues.push(format!("turn:{}", start + 1));
+    if let Some(date) = turns[0].time.and_then(|t| chrono::DateTime::from_timestamp(t, 0)) {
+        cues.push(format!("date:{}", date.format("%Y-%m-%d")));
+    }
+    if let Some(title) = &conversation.title {
+        cues.push(format!("title:{}", title));
+    }
+
+    Chunk {
+        content,
+        start_line: start + 1,
+        end_line: end,
+        context: format!("{} turns {}-{}", conversation.title.as_deref().unwrap_or("chat"), start + 1, end),
+        structural_cues: cues,
+        category: ChunkCategory::Conversation,
+    }
+}
+
+/// Lowercased, whitespace as `_`, other punctuation dropped
+pub fn speaker_cue(speaker: &str) -> String {
+    speaker
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("_")
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '_')
+        .collect()
+}
+
+fn is_speaker(name: &str) -> bool {
+    let name = name.trim();
+    name.split_whitespace().count() <= 4
+        && name.chars().all(|c| c.is_alphanumeric() || matches!(c, ' ' | '.' | '\'' | '-' | '_'))
+        && name.chars().next().is_some_and(char::is_alphabetic)
+        && !NOT_SPEAKERS.contains(&name.to_lowercase().as_str())
+}
+
+fn role_name(role: &str) -> String {
+    let mut chars = role.chars();
+    chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+}
+
+/// Channel of a Slack export file: its directory
+fn slack_channel(path: &Path) -> Option<String> {
+    path.parent().and_then(|p| p.file_name()).and_then(|s| s.to_str()).map(str::to_string)
+}
@@ -1,4 +1,5 @@
 use tree_sitter::Parser;
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::cell::RefCell;
 use unicode_segmentation::UnicodeSegmentation;
@@ -20,6 +21,8 @@ struct Parsers {
     css: Option<Parser>,
     php: Option<Parser>,
     java: Option<Parser>,
+    #[cfg(feature = "cpp")]
+    cpp: Option<Parser>,
 }
 
 impl Parsers {
@@ -34,6 +37,8 @@ impl Parsers {
             css: None,
             php: None,
             java: None,
+            #[cfg(feature = "cpp")]
+            cpp: None,
         }
     }
 
@@ -108,6 +113,15 @@ impl Parsers {
             parser
         })
     }
+
+    #[cfg(feature = "cpp")]
+    fn get_cpp(&mut self) -> &mut Parser {
+        self.cpp.get_or_insert_with(|| {
+            let mut parser = Parser::new();
+            parser.set_language(&tree_sitter_cpp::LANGUAGE.into()).expect("Error loading C++ grammar");
+            parser
+        })
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -144,6 +158,7 @@ pub enum ChunkerType {
     Css,
     Php,
     Java,
+    Cpp,    // C and C++ (`--features cpp`)
     Markdown,
     Csv,
     Json,
@@ -151,7 +166,10 @@ pub enum ChunkerType {
     Xml,
     Pdf,
     Office, // DOCX, XLSX, PPTX
+    Image,  // Screenshots and scans, read with OCR (`--features ocr`)
+    Audio,  // Audio and video, transcribed by a Whisper-compatible API
     Text,
+    Chat,   // Chat transcripts: ChatGPT/Slack exports, `Name: message` logs
     ApiSpec,        // ApiSpec/Swagger specs
     SocialExport   // Generic social media export (auto-detected format)
 }
@@ -176,9 +194,180 @@ impl Default for SegmenterConfig {
     }
 }
 
+/// Bytes per token when sizing chunks, as the grounding budget counts them
+/// without a model (`tokenizer::CharTokenizer`)
+const BYTES_PER_TOKEN: usize = 4;
+
+/// Chunk size limits, applied to the output of every chunker. Set per project
+/// (`PUT /projects/:id/chunking`) and per ingest request; unset fields keep
+/// each chunker's own sizing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChunkingConfig {
+    /// Longer chunks are split at sentence (prose) or line (code, data) boundaries
+    #[serde(default)]
+    pub max_tokens: Option<usize>,
+    /// Tokens at the end of a split piece repeated at the start of the next one
+    #[serde(default)]
+    pub overlap_tokens: Option<usize>,
+    /// Shorter chunks are merged into a neighbour of the same category
+    #[serde(default)]
+    pub min_chunk_chars: Option<usize>,
+}
+
+impl ChunkingConfig {
+    /// This config with the fields `overrides` sets replaced
+    pub fn merged(&self, overrides: &ChunkingConfig) -> ChunkingConfig {
+        ChunkingConfig {
+            max_tokens: overrides.max_tokens.or(self.max_tokens),
+            overlap_tokens: overrides.overlap_tokens.or(self.overlap_tokens),
+            min_chunk_chars: overrides.min_chunk_chars.or(self.min_chunk_chars),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_tokens == Some(0) {
+            return Err("max_tokens must be at least 1".to_string());
+        }
+        if let (Some(max), Some(overlap)) = (self.max_tokens, self.overlap_tokens) {
+            if overlap * 2 > max {
+                return Err(format!("overlap_tokens ({}) must be at most half of max_tokens ({})", overlap, max));
+            }
+        }
+        Ok(())
+    }
+}
+
 pub struct Chunker;
 
 impl Chunker {
+    /// Enforce `config` on the chunks of any chunker: chunks over `max_tokens`
+    /// are split into overlapping pieces, then chunks under `min_chunk_chars`
+    /// are merged into their neighbour when the result still fits.
+    pub fn apply_limits(chunks: Vec<Chunk>, config: &ChunkingConfig) -> Vec<Chunk> {
+        let max_bytes = config.max_tokens.map(|t| t.max(1) * BYTES_PER_TOKEN);
+        let chunks: Vec<Chunk> = match max_bytes {
+            Some(max_bytes) => {
+                let overlap_bytes = (config.overlap_tokens.unwrap_or(0) * BYTES_PER_TOKEN).min(max_bytes / 2);
+                chunks.into_iter().flat_map(|chunk| Self::split_chunk(chunk, max_bytes, overlap_bytes)).collect()
+            }
+            None => chunks,
+        };
+
+        let Some(min_chars) = config.min_chunk_chars.filter(|m| *m > 0) else {
+            return chunks;
+        };
+        let small = |chunk: &Chunk| chunk.content.trim().chars().count() < min_chars;
+        let mut merged: Vec<Chunk> = Vec::with_capacity(chunks.len());
+        for chunk in chunks {
+            match merged.last_mut() {
+                Some(prev)
+                    if (small(prev) || small(&chunk))
+                        && prev.category == chunk.category
+                        && prev.content.len() + 1 + chunk.content.len() <= max_bytes.unwrap_or(usize::MAX) =>
+                {
+                    prev.content.push('\n');
+                    prev.content.push_str(&chunk.content);
+                    prev.end_line = prev.end_line.max(chunk.end_line);
+                    for cue in chunk.structural_cues {
+                        if !prev.structural_cues.contains(&cue) {
+                            prev.structural_cues.push(cue);
+                        }
+                    }
+                }
+                _ => merged.push(chunk),
+            }
+        }
+        merged
+    }
+
+    /// Pieces of a chunk of at most `max_bytes`, each starting with up to
+    /// `overlap_bytes` of the previous one. Prose is cut between sentences,
+    /// everything else between lines; a longer sentence or line is cut at a space.
+    fn split_chunk(chunk: Chunk, max_bytes: usize, overlap_bytes: usize) -> Vec<Chunk> {
+        let content = chunk.content.as_str();
+        if content.len() <= max_bytes {
+            return vec![chunk];
+        }
+
+        let mut bounds: Vec<(usize, usize)> = Vec::new();
+        if matches!(chunk.category, ChunkCategory::Prose | ChunkCategory::WebContent) {
+            for (start, sentence) in content.split_sentence_bound_indices() {
+                bounds.push((start, start + sentence.len()));
+            }
+        } else {
+            let mut start = 0;
+            for line in content.split_inclusive('\n') {
+                bounds.push((start, start + line.len()));
+                start += line.len();
+            }
+        }
+        let mut units = Vec::with_capacity(bounds.len());
+        for (mut start, end) in bounds {
+            while end - start > max_bytes {
+                let mut cut = start + max_bytes;
+                while !content.is_char_boundary(cut) {
+                    cut -= 1;
+                }
+                if let Some((i, c)) = content[start..cut].char_indices().rev().find(|(_, c)| c.is_whitespace()) {
+                    if i > max_bytes / 2 {
+                        cut = start + i + c.len_utf8();
+                    }
+                }
+                units.push((start, cut));
+                start = cut;
+            }
+            units.push((start, end));
+        }
+
+        // Greedy pieces of whole units; the next piece backs up over the
+        // trailing units that fit in the overlap (and still leave room for a new unit)
+        let mut ranges = Vec::new();
+        let mut first = 0;
+        while first < units.len() {
+            let start = units[first].0;
+            let mut last = first;
+            while last + 1 < units.len() && units[last + 1].1 - start <= max_bytes {
+                last += 1;
+            }
+            ranges.push((start, units[last].1));
+            if last + 1 == units.len() {
+                break;
+            }
+            let mut next = last + 1;
+            while next > first + 1
+                && units[last].1 - units[next - 1].0 <= overlap_bytes
+                && units[last + 1].1 - units[next - 1].0 <= max_bytes
+            {
+                next -= 1;
+            }
+            first = next;
+        }
+
+        let line_at = |offset: usize| chunk.start_line + content[..offset].matches('\n').count();
+        ranges
+            .into_iter()
+            .filter_map(|(start, end)| {
+                let piece = &content[start..end];
+                let text = piece.trim();
+                if text.is_empty() {
+                    return None;
+                }
+                let text_start = start + (piece.len() - piece.trim_start().len());
+                Some((text_start, text_start + text.len(), text.to_string()))
+            })
+            .enumerate()
+            .map(|(i, (start, end, text))| Chunk {
+                content: text,
+                // 0 = no line information
+                start_line: if chunk.start_line == 0 { 0 } else { line_at(start) },
+                end_line: if chunk.start_line == 0 { 0 } else { line_at(end) },
+                context: format!("{} (part {})", chunk.context, i + 1),
+                structural_cues: chunk.structural_cues.clone(),
+                category: chunk.category,
+            })
+            .collect()
+    }
+
     /// Chunk a binary file from disk. Used for PDF/Office files that require file-based extraction.
     /// For text files, falls back to reading content and using chunk_file.
     pub fn chunk_binary_file(path: &Path) -> Vec<Chunk> {
@@ -190,6 +379,8 @@ impl Chunker {
         match file_type {
             ChunkerType::Pdf => Self::chunk_pdf(path),
             ChunkerType::Office => Self::chunk_office(path),
+            ChunkerType::Image => Self::chunk_image(path),
+            ChunkerType::Audio => Self::chunk_audio(path),
             _ => {
                 // For non-binary types, read as text and use standard chunking
                 if let Ok(content) = std::fs::read_to_string(path) {
@@ -227,14 +418,22 @@ impl Chunker {
             ChunkerType::Css => Self::chunk_css(content),
             ChunkerType::Php => Self::chunk_php(content),
             ChunkerType::Java => Self::chunk_java(content),
+            ChunkerType::Cpp => Self::chunk_cpp(content),
             ChunkerType::Markdown => Self::chunk_markdown(content),
             ChunkerType::Csv => Self::chunk_csv(content),
-            ChunkerType::Json => Self::chunk_json(content),
+            ChunkerType::Json => Self::chunk_chat(path, content).unwrap_or_else(|| Self::chunk_json(content)),
             ChunkerType::Yaml => Self::chunk_yaml(content),
             ChunkerType::Xml => Self::chunk_xml(content),
             ChunkerType::Pdf => Self::chunk_pdf(path),
             ChunkerType::Office => Self::chunk_office(path),
+            ChunkerType::Image => Self::chunk_image(path),
+            ChunkerType::Audio => Self::chunk_audio(path),
+            // `.log` files are full of `LEVEL: message` lines; only `.txt` may be a chat log
+            ChunkerType::Text if path.extension().is_some_and(|e| e == "txt") => {
+                Self::chunk_chat(path, content).unwrap_or_else(|| Self::chunk_text(content))
+            }
             ChunkerType::Text => Self::chunk_text(content),
+            ChunkerType::Chat => Self::chunk_chat(path, content).unwrap_or_else(|| Self::chunk_text(content)),
             ChunkerType::ApiSpec => Self::chunk_json(content),
             ChunkerType::SocialExport => Self::chunk_social_export(path, content),
         }
@@ -271,6 +470,14 @@ impl Chunker {
         None // Not a recognized social export
     }
 
+    /// Chunk a chat transcript by speaker turns (see `chat`); None if `content`
+    /// isn't one
+    fn chunk_chat(path: &Path, content: &str) -> Option<Vec<Chunk>> {
+        let conversations = super::chat::parse_transcript(path, content)?;
+        let chunks: Vec<Chunk> = conversations.iter().flat_map(super::chat::chunk_conversation).collect();
+        (!chunks.is_empty()).then_some(chunks)
+    }
+
     /// Detect social export type from content and route to appropriate parser
     fn chunk_social_export(path: &Path, content: &str) -> Vec<Chunk> {
         let filename = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
@@ -311,6 +518,12 @@ impl Chunker {
             || (path_lower.contains("chrome") && path_lower.contains("history")) {
             return Some(ChunkerType::SocialExport);
         }
+
+        // ChatGPT's data export, Slack's workspace export
+        let json = path.extension().is_some_and(|e| e == "json");
+        if json && (path.file_name().is_some_and(|n| n == "conversations.json") || path_lower.contains("slack")) {
+            return Some(ChunkerType::Chat);
+        }
         
         match path.extension().and_then(|s| s.to_str()) {
             Some("py") => Some(ChunkerType::Python),
@@ -322,6 +535,7 @@ impl Chunker {
             Some("css") => Some(ChunkerType::Css),
             Some("php") => Some(ChunkerType::Php),
             Some("java") => Some(ChunkerType::Java),
+            Some("c" | "h" | "cc" | "cpp" | "cxx" | "hh" | "hpp" | "hxx") if cfg!(feature = "cpp") => Some(ChunkerType::Cpp),
             Some("md") => Some(ChunkerType::Markdown),
             Some("csv") => Some(ChunkerType::Csv),
             Some("json") => Some(ChunkerType::Json),
@@ -330,6 +544,8 @@ impl Chunker {
             Some("pdf") => Some(ChunkerType::Pdf),
             Some("docx" | "xlsx" | "pptx") => Some(ChunkerType::Office),
             Some("txt" | "log") => Some(ChunkerType::Text),
+            _ if super::ocr::is_image(path) && super::ocr::active_config().is_some() => Some(ChunkerType::Image),
+            _ if super::transcribe::is_media(path) && super::transcribe::active_config().is_some() => Some(ChunkerType::Audio),
             _ => None,
         }
     }
@@ -349,7 +565,7 @@ impl Chunker {
             let mut parsers = parsers.borrow_mut();
             let parser = parsers.get_rust();
             Self::chunk_treesitter_with_names(content, parser, 
-                &["function_item", "struct_item", "enum_item", "trait_item", 
+                &["function_item", "impl_item", "struct_item", "enum_item", "trait_item", "macro_definition",
                   "if_expression", "match_expression", "match_arm", "for_expression", "while_expression", "loop_expression"], 
                 "lang:rust", ChunkCategory::Code)
         })
@@ -360,7 +576,7 @@ impl Chunker {
             let mut parsers = parsers.borrow_mut();
             let parser = parsers.get_typescript();
             Self::chunk_treesitter_with_names(content, parser, 
-                &["function_declaration", "class_declaration", "interface_declaration", "lexical_declaration", "method_definition", "constructor_declaration", "if_statement", "for_statement", "while_statement", "expression_statement", "call_expression", "comment", "jsx_element", "jsx_self_closing_element"], 
+                &["function_declaration", "class_declaration", "abstract_class_declaration", "interface_declaration", "enum_declaration", "type_alias_declaration", "lexical_declaration", "method_definition", "constructor_declaration", "if_statement", "for_statement", "while_statement", "expression_statement", "call_expression", "comment", "jsx_element", "jsx_self_closing_element"], 
                 "lang:typescript", ChunkCategory::Code)
         })
     }
@@ -510,6 +726,24 @@ impl Chunker {
         })
     }
 
+    #[cfg(feature = "cpp")]
+    pub fn chunk_cpp(content: &str) -> Vec<Chunk> {
+        PARSERS.with(|parsers| {
+            let mut parsers = parsers.borrow_mut();
+            let parser = parsers.get_cpp();
+            Self::chunk_treesitter_with_names(content, parser,
+                &["function_definition", "template_declaration", "class_specifier", "struct_specifier", "union_specifier", "enum_specifier",
+                  "if_statement", "for_statement", "for_range_loop", "while_statement", "switch_statement", "try_statement"],
+                "lang:cpp", ChunkCategory::Code)
+        })
+    }
+
+    /// Without the grammar C/C++ files are never detected as such
+    #[cfg(not(feature = "cpp"))]
+    pub fn chunk_cpp(content: &str) -> Vec<Chunk> {
+        Self::chunk_text(content)
+    }
+
     fn chunk_treesitter_with_names(
         content: &str, 
         parser: &mut Parser, 
@@ -532,7 +766,8 @@ impl Chunker {
                 &mut chunks, 
                 lang_tag, 
                 category,
-                max_chars
+                max_chars,
+                &[]
             );
             tracing::info!("[CHUNKER DEBUG] After visit: {} chunks", chunks.len());
         }
@@ -567,6 +802,7 @@ impl Chunker {
         chunks
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn visit_nodes_recursive(
         node: tree_sitter::Node, 
         content: &str, 
@@ -574,9 +810,12 @@ impl Chunker {
         chunks: &mut Vec<Chunk>, 
         lang_tag: &str, 
         category: ChunkCategory,
-        max_chars: usize
+        max_chars: usize,
+        scope: &[String]
     ) {
         let kind = node.kind();
+        let own_symbol = Self::symbol_name(node, content);
+        let separator = Self::symbol_separator(lang_tag);
         let start_row = node.start_position().row;
         let end_row = node.end_position().row;
         // let line_count = end_row - start_row;
@@ -602,7 +841,7 @@ impl Chunker {
         
         if should_chunk_here {
             // --- EXTRACT IDENTIFIER ---
-            let name = node.child_by_field_name("name")
+            let name = own_symbol.clone().unwrap_or_else(|| node.child_by_field_name("name")
                 .or_else(|| node.child_by_field_name("identifier"))
                 .or_else(|| node.child_by_field_name("selectors"))
                 .or_else(|| {
@@ -620,7 +859,7 @@ impl Chunker {
                      else if kind.contains("expression") { "expr" }
                      else if kind.contains("comment") { "comment" }
                      else { "anon" }
-                });
+                }).to_string());
 
             let text = node.utf8_text(content.as_bytes()).unwrap_or("").to_string();
             
@@ -629,7 +868,8 @@ impl Chunker {
                  .replace("_definition", "")
                  .replace("_item", "")
                  .replace("_rule", "")
-                 .replace("_set", "");
+                 .replace("_set", "")
+                 .replace("_specifier", "");
 
             let name_label = if lang_tag == "lang:css" { "selector" } else { "name" };
 
@@ -641,11 +881,21 @@ impl Chunker {
                 start_line: start_row + 1,
                 end_line: end_row + 1,
                 context: format!("{}:{}", kind, name),
-                structural_cues: vec![
-                    lang_tag.to_string(),
-                    format!("type:{}", type_cue),
-                    format!("{}:{}", name_label, name),
-                ],
+                structural_cues: {
+                    let mut cues = vec![
+                        lang_tag.to_string(),
+                        format!("type:{}", type_cue),
+                        format!("{}:{}", name_label, name),
+                    ];
+                    // The definition and everything defined inside it, e.g. a class and its methods
+                    let mut symbols = Vec::new();
+                    Self::collect_symbols(node, content, scope, separator, &mut symbols);
+                    if symbols.is_empty() && !scope.is_empty() {
+                        symbols.push(scope.join(separator));
+                    }
+                    cues.extend(symbols.into_iter().map(|s| format!("symbol:{}", s)));
+                    cues
+                },
                 category,
             });
             
@@ -657,12 +907,19 @@ impl Chunker {
 
         // 4. If we didn't chunk it (because it wasn't a target OR it was too big),
         // we recurse into children to find smaller, manageable pieces.
+        // Definitions are the scope of what they contain: pieces of `Parser::parse`
+        // split up below carry `symbol:Parser::parse`
+        let inner_scope: std::borrow::Cow<[String]> = match own_symbol.filter(|_| kind != "template_declaration") {
+            Some(name) => std::borrow::Cow::Owned(scope.iter().cloned().chain([name]).collect()),
+            None => std::borrow::Cow::Borrowed(scope),
+        };
+
         if is_target_node && byte_len > max_chars {
             let initial_chunk_count = chunks.len();
             
             let mut cursor = node.walk();
             for child in node.children(&mut cursor) {
-                Self::visit_nodes_recursive(child, content, node_kinds, chunks, lang_tag, category, max_chars);
+                Self::visit_nodes_recursive(child, content, node_kinds, chunks, lang_tag, category, max_chars, &inner_scope);
             }
             
             // If we drilled down but got nothing (e.g. big linear function), force line-based segmentation
@@ -687,11 +944,17 @@ impl Chunker {
                         start_line: chunk_start,
                         end_line: chunk_end,
                         context: format!("{} (part {})", kind, chunk_idx + 1),
-                        structural_cues: vec![
-                            lang_tag.to_string(),
-                            format!("type:{}", kind),
-                            format!("parent:{}", kind),
-                        ],
+                        structural_cues: {
+                            let mut cues = vec![
+                                lang_tag.to_string(),
+                                format!("type:{}", kind),
+                                format!("parent:{}", kind),
+                            ];
+                            if !inner_scope.is_empty() {
+                                cues.push(format!("symbol:{}", inner_scope.join(separator)));
+                            }
+                            cues
+                        },
                         category,
                     });
                 }
@@ -700,9 +963,69 @@ impl Chunker {
             // Standard recursion for non-target nodes
             let mut cursor = node.walk();
             for child in node.children(&mut cursor) {
-                Self::visit_nodes_recursive(child, content, node_kinds, chunks, lang_tag, category, max_chars);
+                Self::visit_nodes_recursive(child, content, node_kinds, chunks, lang_tag, category, max_chars, &inner_scope);
+            }
+        }
+    }
+
+    /// Name of a definition node (function, method, type, impl, module,
+    /// namespace), None for anything else
+    fn symbol_name(node: tree_sitter::Node, content: &str) -> Option<String> {
+        let kind = node.kind();
+        let definition = ["_item", "_declaration", "_definition", "_specifier"].iter().any(|s| kind.ends_with(s))
+            || kind == "internal_module";
+        if !definition {
+            return None;
+        }
+        let name_node = match kind {
+            // `impl<T> Display for Parser<T>` is named after `Parser`
+            "impl_item" => node.child_by_field_name("type"),
+            // `template <typename T> T max(T a, T b)`: the templated definition's name
+            "template_declaration" => {
+                let last = node.named_child_count().checked_sub(1)?;
+                return Self::symbol_name(node.named_child(last as u32)?, content);
+            }
+            _ => node.child_by_field_name("name").or_else(|| {
+                // C/C++: the name sits inside nested declarators (`*parse(const char *s)`)
+                let mut declarator = node.child_by_field_name("declarator")?;
+                while let Some(inner) = declarator.child_by_field_name("declarator") {
+                    declarator = inner;
+                }
+                if declarator.kind().contains("identifier") || declarator.kind().ends_with("_name") {
+                    Some(declarator)
+                } else {
+                    declarator.child_by_field_name("name")
+                }
+            }),
+        }?;
+        let name = name_node.utf8_text(content.as_bytes()).ok()?;
+        let name = name.split('<').next().unwrap_or(name).trim();
+        (!name.is_empty()).then(|| name.to_string())
+    }
+
+    /// Qualified names of the definitions in a subtree, outermost first
+    fn collect_symbols(node: tree_sitter::Node, content: &str, scope: &[String], separator: &str, symbols: &mut Vec<String>) {
+        let mut inner_scope = scope.to_vec();
+        if let Some(name) = Self::symbol_name(node, content) {
+            let symbol = scope.iter().map(String::as_str).chain([name.as_str()]).collect::<Vec<_>>().join(separator);
+            if !symbols.contains(&symbol) {
+                symbols.push(symbol);
+            }
+            if node.kind() != "template_declaration" {
+                inner_scope.push(name);
             }
         }
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            Self::collect_symbols(child, content, &inner_scope, separator, symbols);
+        }
+    }
+
+    fn symbol_separator(lang_tag: &str) -> &'static str {
+        match lang_tag {
+            "lang:rust" | "lang:cpp" | "lang:php" => "::",
+            _ => ".",
+        }
     }
 
 
@@ -944,11 +1267,91 @@ impl Chunker {
         Self::chunk_text(content)
     }
 
+    /// Chunk a PDF by detected sections and tables, with `page:` cues
     fn chunk_pdf(path: &Path) -> Vec<Chunk> {
-        if let Ok(content) = pdf_extract::extract_text(path) {
-            return Self::chunk_text(&content);
+        let Ok(pages) = pdf_extract::extract_text_by_pages(path) else {
+            return Vec::new();
+        };
+        let file = path.file_stem().and_then(|s| s.to_str()).unwrap_or("document").to_lowercase();
+
+        // No text layer: a scan
+        if pages.iter().all(|page| page.trim().is_empty()) {
+            let Some(config) = super::ocr::active_config() else {
+                return Vec::new();
+            };
+            return match super::ocr::pdf_pages_text(&config, path) {
+                Ok(pages) => Self::with_ocr_cue(Self::chunk_pdf_pages(&pages, &file), &config),
+                Err(e) => {
+                    tracing::warn!("OCR failed for {}: {}", path.display(), e);
+                    Vec::new()
+                }
+            };
+        }
+
+        let chunks = Self::chunk_pdf_pages(&pages, &file);
+        if chunks.is_empty() {
+            return Self::chunk_text(&pages.join("\n"));
         }
-        Vec::new()
+        chunks
+    }
+
+    /// Chunk the text OCR finds in an image
+    fn chunk_image(path: &Path) -> Vec<Chunk> {
+        let Some(config) = super::ocr::active_config() else {
+            return Vec::new();
+        };
+        let text = match super::ocr::image_text(&config, path) {
+            Ok(text) => text,
+            Err(e) => {
+                tracing::warn!("OCR failed for {}: {}", path.display(), e);
+                return Vec::new();
+            }
+        };
+        let file = path.file_stem().and_then(|s| s.to_str()).unwrap_or("image").to_lowercase();
+        Self::with_ocr_cue(Self::chunk_image_text(&text, &file), &config)
+    }
+
+    /// Chunks of text recognized in an image: paragraphs grouped like PDF pages
+    pub fn chunk_image_text(text: &str, file: &str) -> Vec<Chunk> {
+        let mut chunks = Self::chunk_pdf_pages(&[text.to_string()], file);
+        for chunk in &mut chunks {
+            chunk.structural_cues.retain(|cue| !cue.starts_with("page:"));
+            for cue in &mut chunk.structural_cues {
+                if let Some(kind) = cue.strip_prefix("type:pdf_") {
+                    *cue = format!("type:image_{}", kind);
+                }
+            }
+        }
+        chunks
+    }
+
+    /// Chunk a recording's transcript by time window
+    fn chunk_audio(path: &Path) -> Vec<Chunk> {
+        let Some(config) = super::transcribe::active_config() else {
+            return Vec::new();
+        };
+        match super::transcribe::transcribe(&config, path) {
+            Ok(segments) => {
+                let file = path.file_stem().and_then(|s| s.to_str()).unwrap_or("recording").to_lowercase();
+                super::transcribe::chunk_segments(&segments, &file, config.window_seconds)
+            }
+            Err(e) => {
+                tracing::warn!("Transcription failed for {}: {}", path.display(), e);
+                Vec::new()
+            }
+        }
+    }
+
+    fn with_ocr_cue(mut chunks: Vec<Chunk>, config: &super::ocr::OcrConfig) -> Vec<Chunk> {
+        for chunk in &mut chunks {
+            chunk.structural_cues.push(format!("ocr:{}", config.engine()));
+        }
+        chunks
+    }
+
+    /// Layout-aware chunks of a PDF's extracted per-page text
+    pub fn chunk_pdf_pages(pages: &[String], file: &str) -> Vec<Chunk> {
+        super::layout::chunk_blocks(&super::layout::pdf_blocks(pages), "pdf", file)
     }
 
     fn chunk_office(path: &Path) -> Vec<Chunk> {
@@ -965,128 +1368,20 @@ impl Chunker {
         }
     }
     
-    /// Chunk DOCX files using sentence-based segmentation
+    /// Chunk DOCX files by heading-styled sections, with tables as structured rows
     fn chunk_docx(path: &Path, filename: &str) -> Vec<Chunk> {
-        use docx_rs::*;
-        
-        // Read the DOCX file
         let bytes = match std::fs::read(path) {
             Ok(b) => b,
             Err(_) => return Vec::new(),
         };
         
-        // Parse the DOCX
-        let docx = match read_docx(&bytes) {
+        let docx = match docx_rs::read_docx(&bytes) {
             Ok(d) => d,
             Err(_) => return Vec::new(),
         };
         
-        // Extract all text from paragraphs
-        let mut all_text = String::new();
-        
-        for child in docx.document.children {
-            if let DocumentChild::Paragraph(para) = child {
-                let mut para_text = String::new();
-                for child in para.children {
-                    if let ParagraphChild::Run(run) = child {
-                        for child in run.children {
-                            if let RunChild::Text(text) = child {
-                                para_text.push_str(&text.text);
-                            }
-                        }
-                    }
-                }
-                let para_text = para_text.trim();
-                if !para_text.is_empty() {
-                    all_text.push_str(para_text);
-                    all_text.push(' ');
-                }
-            }
-        }
-        
-        let all_text = all_text.trim();
-        if all_text.is_empty() {
-            return Vec::new();
-        }
-        
-        // Segment using unicode sentences (same strategy as URL chunking)
-        let sentences: Vec<&str> = all_text.unicode_sentences().collect();
-        let mut chunks = Vec::new();
-        
-        const MIN_CHUNK_CHARS: usize = 80;
-        const MAX_CHUNK_CHARS: usize = 500;
-        const TARGET_SENTENCES: usize = 3;
-        
-        let mut current_chunk = String::new();
-        let mut current_sentence_count = 0;
-        let mut chunk_idx = 0;
-        
-        for sentence in sentences {
-            let sentence = sentence.trim();
-            if sentence.is_empty() {
-                continue;
-            }
-            
-            let would_be_length = current_chunk.len() + sentence.len() + 1;
-            
-            if !current_chunk.is_empty() && 
-               (would_be_length > MAX_CHUNK_CHARS || current_sentence_count >= TARGET_SENTENCES) {
-                if current_chunk.len() >= MIN_CHUNK_CHARS {
-                    chunks.push(Chunk {
-                        content: current_chunk.trim().to_string(),
-                        start_line: chunk_idx,
-                        end_line: chunk_idx,
-                        context: format!("docx:{}:{}", filename, chunk_idx),
-                        structural_cues: vec![
-                            "type:docx".to_string(),
-                            format!("file:{}", filename.replace(".docx", "").to_lowercase()),
-                        ],
-                        category: ChunkCategory::Prose,
-                    });
-                    chunk_idx += 1;
-                }
-                current_chunk.clear();
-                current_sentence_count = 0;
-            }
-            
-            if !current_chunk.is_empty() {
-                current_chunk.push(' ');
-            }
-            current_chunk.push_str(sentence);
-            current_sentence_count += 1;
-        }
-        
-        // Don't forget the last chunk
-        if current_chunk.len() >= MIN_CHUNK_CHARS {
-            chunks.push(Chunk {
-                content: current_chunk.trim().to_string(),
-                start_line: chunk_idx,
-                end_line: chunk_idx,
-                context: format!("docx:{}:{}", filename, chunk_idx),
-                structural_cues: vec![
-                    "type:docx".to_string(),
-                    format!("file:{}", filename.replace(".docx", "").to_lowercase()),
-                ],
-                category: ChunkCategory::Prose,
-            });
-        }
-        
-        // If no chunks from sentences, return full text as one chunk
-        if chunks.is_empty() && !all_text.is_empty() {
-            chunks.push(Chunk {
-                content: all_text.to_string(),
-                start_line: 0,
-                end_line: 0,
-                context: format!("docx:{}", filename),
-                structural_cues: vec![
-                    "type:docx".to_string(),
-                    format!("file:{}", filename.replace(".docx", "").to_lowercase()),
-                ],
-                category: ChunkCategory::Prose,
-            });
-        }
-        
-        chunks
+        let file = filename.replace(".docx", "").to_lowercase();
+        super::layout::chunk_blocks(&super::layout::docx_blocks(&docx), "docx", &file)
     }
     
     /// Chunk Excel files with smart filtering - same logic as CSV
@@ -1414,16 +1709,17 @@ impl Chunker {
         match file_type {
             Some(ChunkerType::Python) | Some(ChunkerType::Rust) | Some(ChunkerType::TypeScript) |
             Some(ChunkerType::JavaScript) | Some(ChunkerType::Go) | Some(ChunkerType::Html) |
-            Some(ChunkerType::Css) | Some(ChunkerType::Php) | Some(ChunkerType::Java) => ChunkCategory::Code,
+            Some(ChunkerType::Css) | Some(ChunkerType::Php) | Some(ChunkerType::Java) |
+            Some(ChunkerType::Cpp) => ChunkCategory::Code,
             
             Some(ChunkerType::Csv) | Some(ChunkerType::Json) | Some(ChunkerType::Yaml) |
             Some(ChunkerType::Xml) => ChunkCategory::Structured,
             
             Some(ChunkerType::ApiSpec) => ChunkCategory::ApiSpec,
-            Some(ChunkerType::SocialExport) => ChunkCategory::Conversation,
+            Some(ChunkerType::SocialExport) | Some(ChunkerType::Chat) => ChunkCategory::Conversation,
             
             Some(ChunkerType::Markdown) | Some(ChunkerType::Pdf) | Some(ChunkerType::Office) |
-            Some(ChunkerType::Text) => ChunkCategory::Prose,
+            Some(ChunkerType::Image) | Some(ChunkerType::Audio) | Some(ChunkerType::Text) => ChunkCategory::Prose,
             
             None => ChunkCategory::Prose, // Default to Prose for unknown if we somehow get here
         }
@@ -0,0 +1,151 @@
+//! Sitemaps and RSS/Atom feeds as ingestion sources.
+//!
+//! A feed URL given to `/ingest/url` is expanded into its entries (sitemap
+//! `<loc>`s, RSS `<item>` links, Atom `<entry>` links), and each entry page is
+//! ingested. The entry's `lastmod`/`pubDate`/`updated` stamp is remembered in
+//! the project's `FeedSubscription`, so a later refresh only re-fetches pages
+//! whose stamp changed. Subscriptions with a refresh interval are re-read by
+//! the scheduler.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Entries fetched per refresh, across nested sitemaps
+pub const MAX_FEED_ENTRIES: usize = 1000;
+
+/// Nested sitemaps followed from a sitemap index
+pub const MAX_NESTED_SITEMAPS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedKind {
+    Sitemap,
+    /// A sitemap listing other sitemaps
+    SitemapIndex,
+    Rss,
+    Atom,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedEntry {
+    pub url: String,
+    /// `lastmod`, `pubDate` or `updated`, as written in the feed
+    pub updated: Option<String>,
+    pub title: Option<String>,
+}
+
+/// A feed ingested into a project, stored in its meta file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FeedSubscription {
+    pub url: String,
+    /// Seconds between scheduled refreshes (None = only when re-posted)
+    #[serde(default)]
+    pub refresh_interval_secs: Option<u64>,
+    /// Unix seconds of the last refresh
+    #[serde(default)]
+    pub last_refreshed: Option<u64>,
+    /// Entry URL -> stamp seen when it was last ingested ("" if the feed has none)
+    #[serde(default)]
+    pub entries: BTreeMap<String, String>,
+}
+
+/// Whether a URL names a sitemap or feed rather than a page
+pub fn looks_like_feed_url(url: &str) -> bool {
+    let path = url::Url::parse(url)
+        .map(|u| u.path().to_lowercase())
+        .unwrap_or_else(|_| url.to_lowercase());
+    let file = path.rsplit('/').find(|s| !s.is_empty()).unwrap_or("");
+    (file.starts_with("sitemap") && (file.ends_with(".xml") || file == "sitemap"))
+        || file.ends_with(".rss")
+        || file.ends_with(".atom")
+        || matches!(file, "feed" | "rss" | "atom" | "feed.xml" | "rss.xml" | "atom.xml" | "index.xml")
+}
+
+/// Entries of a sitemap, sitemap index, RSS or Atom document. None if `xml`
+/// is none of these.
+pub fn parse_feed(xml: &str) -> Option<(FeedKind, Vec<FeedEntry>)> {
+    let doc = roxmltree::Document::parse(xml).ok()?;
+    let root = doc.root_element();
+    let child_text = |node: roxmltree::Node, name: &str| -> Option<String> {
+        node.children()
+            .find(|c| c.is_element() && c.tag_name().name() == name)
+            .and_then(|c| c.text())
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+    };
+
+    let (kind, entries) = match root.tag_name().name() {
+        "urlset" | "sitemapindex" => {
+            let kind = if root.tag_name().name() == "urlset" { FeedKind::Sitemap } else { FeedKind::SitemapIndex };
+            let entries = root
+                .children()
+                .filter(|n| n.is_element() && matches!(n.tag_name().name(), "url" | "sitemap"))
+                .filter_map(|n| {
+                    Some(FeedEntry {
+                        url: child_text(n, "loc")?,
+                        updated: child_text(n, "lastmod"),
+                        title: None,
+                    })
+                })
+                .collect();
+            (kind, entries)
+        }
+        "rss" | "RDF" => {
+            let entries = root
+                .descendants()
+                .filter(|n| n.is_element() && n.tag_name().name() == "item")
+                .filter_map(|n| {
+                    Some(FeedEntry {
+                        url: child_text(n, "link").or_else(|| child_text(n, "guid").filter(|g| g.starts_with("http")))?,
+                        updated: child_text(n, "pubDate").or_else(|| child_text(n, "date")),
+                        title: child_text(n, "title"),
+                    })
+                })
+                .collect();
+            (FeedKind::Rss, entries)
+        }
+        "feed" => {
+            let entries = root
+                .children()
+                .filter(|n| n.is_element() && n.tag_name().name() == "entry")
+                .filter_map(|n| {
+                    // The alternate link, or the first one
+                    let links: Vec<_> = n.children().filter(|c| c.is_element() && c.tag_name().name() == "link").collect();
+                    let link = links
+                        .iter()
+                        .find(|l| l.attribute("rel").is_none_or(|rel| rel == "alternate"))
+                        .or(links.first())?;
+                    Some(FeedEntry {
+                        url: link.attribute("href")?.to_string(),
+                        updated: child_text(n, "updated").or_else(|| child_text(n, "published")),
+                        title: child_text(n, "title"),
+                    })
+                })
+                .collect();
+            (FeedKind::Atom, entries)
+        }
+        _ => return None,
+    };
+    Some((kind, entries))
+}
+
+/// Entries whose stamp differs from the one recorded in `seen` (new entries included)
+pub fn changed_entries<'a>(entries: &'a [FeedEntry], seen: &BTreeMap<String, String>) -> Vec<&'a FeedEntry> {
+    entries
+        .iter()
+        .filter(|e| seen.get(&e.url).map(String::as_str) != Some(e.updated.as_deref().unwrap_or("")))
+        .collect()
+}
+
+/// Outcome of ingesting or refreshing a feed
+#[derive(Debug, Clone)]
+pub struct FeedRefresh {
+    pub kind: FeedKind,
+    pub entries_found: usize,
+    /// Entry pages fetched and ingested this time
+    pub ingested: Vec<String>,
+    /// Entries skipped because their stamp hadn't changed
+    pub unchanged: usize,
+    pub memory_ids: Vec<String>,
+    pub errors: Vec<(String, String)>,
+}
@@ -0,0 +1,106 @@
+//! Include/exclude rules deciding which watched files the agent ingests.
+//!
+//! Globs are matched against the path relative to the watch root
+//! (`src/**/*.rs`, `*.lock`, `target/**`); `*` also crosses directories, so
+//! `*.lock` excludes lockfiles at any depth. Exclusions win over inclusions,
+//! and an empty include list admits every file. These rules apply on top of
+//! `.gitignore` and hidden-file skipping.
+//!
+//! Server-wide defaults (`[agent]`, `--agent-exclude`, `PUT /agent/config`)
+//! are merged into each agent's own rules with `IngestFilterConfig::merged`.
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct IngestFilterConfig {
+    /// Only files matching one of these globs are ingested (empty = all)
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Files matching any of these globs are never ingested
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Files larger than this many bytes are skipped
+    #[serde(default)]
+    pub max_file_size: Option<u64>,
+}
+
+impl IngestFilterConfig {
+    /// `self` as defaults under `overrides`: the override's includes replace
+    /// the defaults when set, excludes add up, and its size limit wins
+    pub fn merged(&self, overrides: &IngestFilterConfig) -> IngestFilterConfig {
+        let include = if overrides.include.is_empty() { self.include.clone() } else { overrides.include.clone() };
+        let mut exclude = self.exclude.clone();
+        for pattern in &overrides.exclude {
+            if !exclude.contains(pattern) {
+                exclude.push(pattern.clone());
+            }
+        }
+        IngestFilterConfig {
+            include,
+            exclude,
+            max_file_size: overrides.max_file_size.or(self.max_file_size),
+        }
+    }
+
+    /// Check that every glob compiles
+    pub fn validate(&self) -> Result<(), String> {
+        build_set(&self.include)?;
+        build_set(&self.exclude)?;
+        Ok(())
+    }
+}
+
+pub struct PathFilter {
+    root: PathBuf,
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+    max_file_size: Option<u64>,
+}
+
+fn build_set(patterns: &[String]) -> Result<Option<GlobSet>, String> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).map_err(|e| format!("Invalid glob '{}': {}", pattern, e))?;
+        builder.add(glob);
+    }
+    builder.build().map(Some).map_err(|e| e.to_string())
+}
+
+impl PathFilter {
+    pub fn new(root: &Path, config: &IngestFilterConfig) -> Result<Self, String> {
+        Ok(Self {
+            root: root.to_path_buf(),
+            include: build_set(&config.include)?,
+            exclude: build_set(&config.exclude)?,
+            max_file_size: config.max_file_size,
+        })
+    }
+
+    /// A filter that admits every file
+    pub fn allow_all(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            include: None,
+            exclude: None,
+            max_file_size: None,
+        }
+    }
+
+    pub fn allows(&self, path: &Path) -> bool {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        if self.exclude.as_ref().is_some_and(|set| set.is_match(relative)) {
+            return false;
+        }
+        self.include.as_ref().is_none_or(|set| set.is_match(relative))
+    }
+
+    /// Whether a file of `len` bytes is within the size limit
+    pub fn allows_size(&self, len: u64) -> bool {
+        self.max_file_size.is_none_or(|max| len <= max)
+    }
+}
@@ -0,0 +1,192 @@
+//! Git metadata for watched repositories, read with the `git` CLI.
+//!
+//! Chunks of tracked files get the branch, the author and short sha of the
+//! last commit touching the file, and that commit's subject (as
+//! `git:message:` for the cue pipeline to tokenize). Optionally the commit
+//! history itself is ingested, one memory per commit carrying `path:` cues of
+//! the files it changed, so "who changed X and why" is recallable.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Field and record separators for `git log --format`
+const FIELD: char = '\x1f';
+const RECORD: char = '\x1e';
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GitIngestConfig {
+    /// Attach branch/author/commit cues to chunks of files in a git repository
+    #[serde(default = "default_true")]
+    pub metadata: bool,
+    /// Also ingest commit history as memories when the agent starts
+    #[serde(default)]
+    pub history: bool,
+    /// Newest commits ingested with `history`
+    #[serde(default = "default_history_limit")]
+    pub history_limit: usize,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_history_limit() -> usize {
+    500
+}
+
+impl Default for GitIngestConfig {
+    fn default() -> Self {
+        Self {
+            metadata: true,
+            history: false,
+            history_limit: default_history_limit(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommitInfo {
+    pub sha: String,
+    pub author: String,
+    pub email: String,
+    /// Unix seconds
+    pub timestamp: u64,
+    pub subject: String,
+    pub body: String,
+    /// Paths relative to the repository root (history only)
+    pub files: Vec<String>,
+}
+
+impl CommitInfo {
+    pub fn short_sha(&self) -> &str {
+        &self.sha[..self.sha.len().min(7)]
+    }
+
+    /// `git:author:`, `commit:` and `git:message:` cues, plus `git:branch:` when known
+    pub fn cues(&self, branch: Option<&str>) -> Vec<String> {
+        let mut cues = Vec::new();
+        if let Some(branch) = branch {
+            cues.push(format!("git:branch:{}", branch.to_lowercase()));
+        }
+        cues.push(format!("git:author:{}", cue_value(&self.author)));
+        cues.push(format!("commit:{}", self.short_sha()));
+        if !self.subject.is_empty() {
+            cues.push(format!("git:message:{}", self.subject));
+        }
+        cues
+    }
+
+    /// Memory content for a history commit
+    pub fn render(&self) -> String {
+        let date = chrono::DateTime::from_timestamp(self.timestamp as i64, 0)
+            .map(|d| d.to_rfc3339())
+            .unwrap_or_default();
+        let mut text = format!("commit {}\nAuthor: {} <{}>\nDate: {}\n\n{}\n", self.sha, self.author, self.email, date, self.subject);
+        if !self.body.is_empty() {
+            text.push('\n');
+            text.push_str(&self.body);
+            text.push('\n');
+        }
+        if !self.files.is_empty() {
+            text.push_str("\nFiles:\n");
+            for file in &self.files {
+                text.push_str("  ");
+                text.push_str(file);
+                text.push('\n');
+            }
+        }
+        text
+    }
+}
+
+/// Lowercased, with whitespace runs as `_`
+fn cue_value(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join("_").to_lowercase()
+}
+
+pub struct GitRepo {
+    root: PathBuf,
+    branch: Option<String>,
+}
+
+impl GitRepo {
+    /// The repository containing `dir`, if any and if `git` is installed
+    pub fn discover(dir: &Path) -> Option<Self> {
+        let root = git(dir, &["rev-parse", "--show-toplevel"])?;
+        let root = PathBuf::from(root.trim());
+        let branch = git(&root, &["rev-parse", "--abbrev-ref", "HEAD"])
+            .map(|b| b.trim().to_string())
+            .filter(|b| !b.is_empty() && b != "HEAD");
+        Some(Self { root, branch })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Current branch (None when detached)
+    pub fn branch(&self) -> Option<&str> {
+        self.branch.as_deref()
+    }
+
+    /// The last commit touching `path`, None for untracked files
+    pub fn last_commit(&self, path: &Path) -> Option<CommitInfo> {
+        let format = format!("--format={}", log_format());
+        let path = path.to_string_lossy();
+        let out = git(&self.root, &["log", "-1", &format, "--", &path])?;
+        parse_log(&out).into_iter().next()
+    }
+
+    /// The newest `limit` commits of HEAD with the files each changed
+    pub fn history(&self, limit: usize) -> Vec<CommitInfo> {
+        let format = format!("--format={}", log_format());
+        let limit = format!("-n{}", limit);
+        git(&self.root, &["log", &limit, &format, "--name-only"])
+            .map(|out| parse_log(&out))
+            .unwrap_or_default()
+    }
+}
+
+/// One record per commit: sha, author, email, time, subject and body, with
+/// `--name-only` file lists following the last separator
+fn log_format() -> String {
+    format!("{r}%H{f}%an{f}%ae{f}%at{f}%s{f}%b{f}", r = "%x1e", f = "%x1f")
+}
+
+/// Parse `git log` output written with `log_format`
+pub fn parse_log(output: &str) -> Vec<CommitInfo> {
+    output
+        .split(RECORD)
+        .filter_map(|record| {
+            let mut fields = record.splitn(7, FIELD);
+            let sha = fields.next()?.trim().to_string();
+            if sha.is_empty() {
+                return None;
+            }
+            let author = fields.next()?.to_string();
+            let email = fields.next()?.to_string();
+            let timestamp = fields.next()?.trim().parse().unwrap_or(0);
+            let subject = fields.next()?.trim().to_string();
+            let body = fields.next()?.trim().to_string();
+            let files = fields
+                .next()
+                .unwrap_or("")
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(str::to_string)
+                .collect();
+            Some(CommitInfo { sha, author, email, timestamp, subject, body, files })
+        })
+        .collect()
+}
+
+/// Run `git -C dir args...`, None on failure or if git is missing
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let out = Command::new("git").arg("-C").arg(dir).args(args).output().ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    String::from_utf8(out.stdout).ok()
+}
@@ -1,10 +1,15 @@
 use crate::agent::chunker::Chunker;
+use crate::agent::feeds::{self, FeedEntry, FeedKind, FeedRefresh, FeedSubscription};
+use crate::agent::filter::PathFilter;
+use crate::agent::git::GitRepo;
 use crate::agent::AgentConfig;
+use crate::connectors::{self, Connector, ConnectorConfig, SyncReport};
+use crate::content_index::content_hash;
 use crate::jobs::{Job, JobQueue};
-use sha2::{Digest, Sha256};
+use crate::structures::Provenance;
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::time::{sleep, Duration};
 use tracing::{info, warn, debug};
@@ -17,6 +22,8 @@ pub struct Ingester {
     job_queue: Arc<JobQueue>,
     file_hashes: HashMap<String, String>, // path -> sha256
     gitignore: Option<Gitignore>,
+    filter: PathFilter,
+    git: Option<GitRepo>,
     memory_hashes: HashMap<String, String>,    // memory_id -> content_hash
     path_to_memories: HashMap<String, HashSet<String>>, // path -> set of current memory_ids
 }
@@ -65,6 +72,21 @@ impl Ingester {
             debug!("No .gitignore files found in {:?} or its parents", watch_path);
         }
 
+        let filter = PathFilter::new(&watch_path, &config.filter).unwrap_or_else(|e| {
+            warn!("Ignoring agent include/exclude globs: {}", e);
+            PathFilter::allow_all(&watch_path)
+        });
+
+        // API-driven ingesters have no watch dir; `git -C ""` would use the cwd
+        let git = if (config.git.metadata || config.git.history) && !config.watch_dir.is_empty() && watch_path.is_dir() {
+            GitRepo::discover(&watch_path)
+        } else {
+            None
+        };
+        if let Some(repo) = &git {
+            debug!("Watch root is in git repository {:?} (branch {:?})", repo.root(), repo.branch());
+        }
+
         let mut config = config;
         config.watch_dir = watch_path.to_string_lossy().to_string();
 
@@ -73,6 +95,8 @@ impl Ingester {
             job_queue,
             file_hashes: HashMap::new(),
             gitignore,
+            filter,
+            git,
             memory_hashes: HashMap::new(),
             path_to_memories: HashMap::new(),
         }
@@ -119,76 +143,213 @@ impl Ingester {
         
         let path_str = self.config.watch_dir.clone();
         
-        // Use ignore crate to respect .gitignore
+        // Use ignore crate to respect .gitignore. Listed up front so
+        // /jobs/status can show files remaining and an ETA.
         let walker = WalkBuilder::new(&path_str)
             .hidden(true)
             .git_ignore(true)
             .build();
 
+        let mut files = Vec::new();
         for result in walker {
             match result {
                 Ok(entry) => {
                     let path = entry.path();
                     if path.is_file() {
-                        if let Err(_e) = self.process_file_path(path.to_path_buf()).await {
-                            // warn!("Failed to process {:?}: {}", path, e);
-                        }
-                        // Throttle
-                        if self.config.throttle_ms > 0 {
-                            sleep(Duration::from_millis(self.config.throttle_ms)).await;
-                        }
+                        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                        files.push((path.to_path_buf(), size));
                     }
                 }
                 Err(err) => warn!("Walk error: {}", err),
             }
         }
+
+        let session = self.job_queue.session_manager.get_or_create(&self.config.project_id);
+        session.start_scan(files.len(), files.iter().map(|(_, size)| size).sum());
+
+        for (path, size) in files {
+            session.scanning_file(&path.to_string_lossy());
+            let result = self.process_file_path(path).await;
+            session.file_scanned(size, result.is_err());
+            // Throttle
+            if self.config.throttle_ms > 0 {
+                sleep(Duration::from_millis(self.config.throttle_ms)).await;
+            }
+        }
+        session.finish_scan();
         
         debug!("Scan complete. Tracking {} files.", self.file_hashes.len());
         Ok(())
     }
 
-    pub async fn process_file_path(&mut self, path: PathBuf) -> Result<(), String> {
-        let path = fs::canonicalize(&path)
-            .map_err(|e| format!("Failed to canonicalize path {:?}: {}", path, e))?;
-        let path_str = path.to_string_lossy().to_string();
-        
-        // 0. Ignore state file
+    /// Why the agent would not ingest `path` (canonical), if it would not
+    fn skip_reason(&self, path: &Path) -> Option<String> {
+        // Agent state file
         if let Some(ref state_path) = self.config.state_file {
-            if let Ok(abs_path) = std::fs::canonicalize(&path) {
-                if let Ok(abs_state) = std::fs::canonicalize(state_path) {
-                    if abs_path == abs_state {
-                        debug!("Skipping agent state file: {}", path_str);
-                        return Ok(());
-                    }
-                } else {
-                     // If state file doesn't exist yet but paths match string-wise
-                     if path == *state_path {
-                         debug!("Skipping agent state file: {}", path_str);
-                         return Ok(());
-                     }
-                }
+            match fs::canonicalize(state_path) {
+                Ok(abs_state) if abs_state == path => return Some("agent state file".to_string()),
+                // If state file doesn't exist yet but paths match string-wise
+                Err(_) if path == state_path.as_path() => return Some("agent state file".to_string()),
+                _ => {}
             }
         }
 
-        // 0.1 Hidden file check (matches behavior of scan_all)
+        // Hidden file check (matches behavior of scan_all)
         // Check if any component starts with a dot (excluding '.' and '..')
         if path.components().any(|c| {
             let s = c.as_os_str().to_string_lossy();
             s.starts_with('.') && s != "." && s != ".."
         }) {
-            debug!("Skipping hidden path: {}", path_str);
-            return Ok(());
+            return Some("hidden path".to_string());
         }
 
-        // 0.1 Check Gitignore
+        // Gitignore
         if let Some(gi) = &self.gitignore {
             // gi.matched handles absolute paths by making them relative to the builder's root.
-            if gi.matched(&path, path.is_dir()).is_ignore() {
-                debug!("Skipping gitignored file: {}", path_str);
-                return Ok(());
+            if gi.matched(path, path.is_dir()).is_ignore() {
+                return Some("gitignored file".to_string());
             }
         }
 
+        // Include/exclude globs
+        if !self.filter.allows(path) {
+            return Some("filtered file".to_string());
+        }
+
+        // Size limit, checked before reading the file
+        let len = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        if !self.filter.allows_size(len) {
+            return Some(format!("oversized file ({} bytes)", len));
+        }
+
+        None
+    }
+
+    /// Change the chunk size limits for files ingested from now on
+    pub fn set_chunking(&mut self, chunking: crate::agent::chunker::ChunkingConfig) {
+        self.config.chunking = chunking;
+    }
+
+    /// Chunk a file the way `process_file_path` would, without ingesting it.
+    /// `Err` carries the reason when the agent would skip the file.
+    pub fn preview_file(&self, path: &Path) -> Result<Vec<PreviewChunk>, String> {
+        let path = fs::canonicalize(path)
+            .map_err(|e| format!("Failed to canonicalize path {:?}: {}", path, e))?;
+        if let Some(reason) = self.skip_reason(&path) {
+            return Err(format!("Skipped by the agent: {}", reason));
+        }
+
+        let bytes = fs::read(&path).map_err(|e| format!("Read error: {}", e))?;
+        let content_str = String::from_utf8(bytes).ok();
+        let chunks = Chunker::chunk_file(&path, content_str.as_deref().unwrap_or(""));
+        let mut chunks = Chunker::apply_limits(chunks, &self.config.chunking);
+        self.add_git_cues(&path, &mut chunks);
+        let path_norm = path.to_string_lossy().to_lowercase();
+
+        let memory_ids = file_memory_ids(&path_norm, &chunks);
+        Ok(chunks
+            .into_iter()
+            .zip(memory_ids)
+            .map(|(chunk, memory_id)| PreviewChunk { memory_id, file_path: path_norm.clone(), chunk })
+            .collect())
+    }
+
+    /// Branch, author, commit and message cues of the last commit touching `path`
+    fn add_git_cues(&self, path: &Path, chunks: &mut [crate::agent::chunker::Chunk]) {
+        if !self.config.git.metadata {
+            return;
+        }
+        let Some(repo) = &self.git else { return };
+        if let Some(commit) = repo.last_commit(path) {
+            let cues = commit.cues(repo.branch());
+            for chunk in chunks.iter_mut() {
+                chunk.structural_cues.extend(cues.iter().cloned());
+            }
+        }
+    }
+
+    /// Ingest the repository's newest commits as memories (`commit:<sha>`),
+    /// each with `path:` cues of the files it changed. Commits ingested
+    /// before are skipped.
+    pub async fn ingest_git_history(&mut self) -> Result<usize, String> {
+        use crate::agent::chunker::ChunkCategory;
+
+        if !self.config.git.history {
+            return Ok(0);
+        }
+        let Some(repo) = &self.git else { return Ok(0) };
+
+        let project_id = self.config.project_id.clone();
+        let source = format!("git:{}", repo.root().to_string_lossy().to_lowercase());
+        let branch = repo.branch().map(str::to_string);
+        let root = repo.root().to_path_buf();
+        let commits = repo.history(self.config.git.history_limit);
+        let session = self.job_queue.session_manager.get_or_create(&project_id);
+
+        let mut ingested = 0;
+        for commit in commits {
+            let memory_id = format!("commit:{}", commit.sha);
+            if self.memory_hashes.contains_key(&memory_id) {
+                continue;
+            }
+
+            let content = commit.render();
+            let mut structural_cues = commit.cues(branch.as_deref());
+            for file in &commit.files {
+                structural_cues.push(format!("path:{}", root.join(file).to_string_lossy().to_lowercase()));
+            }
+
+            self.memory_hashes.insert(memory_id.clone(), commit.sha.clone());
+            session.expect_write();
+            self.job_queue.enqueue(Job::ExtractAndIngest {
+                project_id: project_id.clone(),
+                memory_id: memory_id.clone(),
+                content: content.clone(),
+                file_path: source.clone(),
+                structural_cues,
+                category: ChunkCategory::Prose,
+                provenance: source_provenance(&source, &content),
+            }).await;
+            self.job_queue.buffer(&project_id, Job::TrainLexiconFromMemory {
+                project_id: project_id.clone(),
+                memory_id: memory_id.clone(),
+            }).await;
+            self.job_queue.buffer(&project_id, Job::UpdateGraph {
+                project_id: project_id.clone(),
+                memory_id,
+            }).await;
+            ingested += 1;
+        }
+
+        if ingested > 0 {
+            info!("Ingested {} commits from {:?}", ingested, root);
+        }
+        Ok(ingested)
+    }
+
+    /// Queue fact extraction for a chunk, if this agent or request asked for it
+    async fn queue_fact_extraction(&self, project_id: &str, memory_id: &str, content: &str, source: &str) {
+        if self.config.extract_facts {
+            self.job_queue.enqueue(Job::ExtractFacts {
+                project_id: project_id.to_string(),
+                memory_id: memory_id.to_string(),
+                content: content.to_string(),
+                file_path: source.to_string(),
+            }).await;
+        }
+    }
+
+    pub async fn process_file_path(&mut self, path: PathBuf) -> Result<(), String> {
+        let path = fs::canonicalize(&path)
+            .map_err(|e| format!("Failed to canonicalize path {:?}: {}", path, e))?;
+        let path_str = path.to_string_lossy().to_string();
+        
+        // 0. State file, hidden, gitignored, filtered and oversized files
+        if let Some(reason) = self.skip_reason(&path) {
+            debug!("Skipping {}: {}", reason, path_str);
+            return Ok(());
+        }
+
         // Standardize casing for case-insensitive filesystems (MacOS/Windows)
         let path_norm = path_str.to_lowercase();
         
@@ -197,9 +358,7 @@ impl Ingester {
             .map_err(|e| format!("Read error: {}", e))?;
             
         // 2. Hash check
-        let mut hasher = Sha256::new();
-        hasher.update(&bytes);
-        let hash = format!("{:x}", hasher.finalize());
+        let hash = content_hash(&bytes);
         
         if let Some(old_hash) = self.file_hashes.get(&path_norm) {
             if old_hash == &hash {
@@ -215,6 +374,8 @@ impl Ingester {
         // 3. Chunk
         let content_str = String::from_utf8(bytes).ok();
         let chunks = Chunker::chunk_file(&path, content_str.as_deref().unwrap_or(""));
+        let mut chunks = Chunker::apply_limits(chunks, &self.config.chunking);
+        self.add_git_cues(&path, &mut chunks);
         
         // 4. Send to Job Queue
         let project_id = self.config.project_id.clone();
@@ -224,31 +385,66 @@ impl Ingester {
         
         // Track which memories are new/updated vs unchanged
         let old_memories = self.path_to_memories.get(&path_norm).cloned().unwrap_or_default();
-        let mut new_memories = HashSet::new();
+        let memory_ids = file_memory_ids(&path_norm, &chunks);
+        let new_memories: HashSet<String> = memory_ids.iter().cloned().collect();
+        let chunk_hashes: Vec<String> = chunks.iter().map(|chunk| content_hash(&chunk.content)).collect();
 
-        for chunk in chunks.iter() {
-            let mut memory_id = format!("file:{}:{}-{}", path_norm, chunk.start_line, chunk.end_line);
-            let mut suffix = 1;
-            while new_memories.contains(&memory_id) {
-                memory_id = format!("file:{}:{}-{}:{}", path_norm, chunk.start_line, chunk.end_line, suffix);
-                suffix += 1;
+        // Old memories whose content no longer sits at their id, by content hash
+        let mut vacated: HashMap<String, Vec<String>> = HashMap::new();
+        for (old_id, old_hash) in old_memories.iter().filter_map(|id| Some((id, self.memory_hashes.get(id)?))) {
+            let kept = memory_ids
+                .iter()
+                .position(|id| id == old_id)
+                .is_some_and(|i| &chunk_hashes[i] == old_hash);
+            if !kept {
+                vacated.entry(old_hash.clone()).or_default().push(old_id.clone());
             }
-            new_memories.insert(memory_id.clone());
+        }
 
-            let mut chunk_hasher = Sha256::new();
-            chunk_hasher.update(chunk.content.as_bytes());
-            let chunk_hash = format!("{:x}", chunk_hasher.finalize());
-            
+        // Unchanged content under a new line range is moved instead of
+        // re-ingested, so its reinforcement stats survive the edit
+        let mut moves = Vec::new();
+        let mut to_ingest = Vec::new();
+        for (i, memory_id) in memory_ids.iter().enumerate() {
             // Optimization: Skip ingestion if ID and content haven't changed
-            if let Some(old_hash) = self.memory_hashes.get(&memory_id) {
-                if old_hash == &chunk_hash {
-                    debug!("Skipping unchanged memory: {}", memory_id);
-                    valid_memory_ids.push(memory_id);
-                    continue;
-                }
+            if self.memory_hashes.get(memory_id) == Some(&chunk_hashes[i]) {
+                debug!("Skipping unchanged memory: {}", memory_id);
+            } else if let Some(from) = vacated.get_mut(&chunk_hashes[i]).and_then(|ids| ids.pop()) {
+                debug!("Moving unchanged memory: {} -> {}", from, memory_id);
+                moves.push((from, memory_id.clone()));
+            } else {
+                to_ingest.push(i);
             }
+            valid_memory_ids.push(memory_id.clone());
+        }
+        let moved: HashSet<String> = moves.iter().map(|(from, _)| from.clone()).collect();
+
+        for from in &moved {
+            self.memory_hashes.remove(from);
+        }
+        for (_, to) in &moves {
+            let i = memory_ids.iter().position(|id| id == to).unwrap_or_default();
+            self.memory_hashes.insert(to.clone(), chunk_hashes[i].clone());
+        }
+        if !moves.is_empty() {
+            let provenance = moves
+                .iter()
+                .map(|(_, to)| {
+                    let i = memory_ids.iter().position(|id| id == to).unwrap_or_default();
+                    (to.clone(), chunk_provenance(&path_norm, &chunks[i]))
+                })
+                .collect();
+            // Runs before the ingests below, which may reuse the vacated ids
+            self.job_queue.enqueue(Job::MoveMemories {
+                project_id: project_id.clone(),
+                moves,
+                provenance,
+            }).await;
+        }
 
-            self.memory_hashes.insert(memory_id.clone(), chunk_hash);
+        for i in to_ingest {
+            let (chunk, memory_id) = (&chunks[i], &memory_ids[i]);
+            self.memory_hashes.insert(memory_id.clone(), chunk_hashes[i].clone());
             session.expect_write();
 
             self.job_queue.enqueue(Job::ExtractAndIngest {
@@ -258,7 +454,9 @@ impl Ingester {
                 file_path: path_norm.clone(),
                 structural_cues: chunk.structural_cues.clone(),
                 category: chunk.category,
+                provenance: chunk_provenance(&path_norm, chunk),
             }).await;
+            self.queue_fact_extraction(&project_id, memory_id, &chunk.content, &path_norm).await;
             
             self.job_queue.buffer(&project_id, Job::ProposeCues {
                 project_id: project_id.clone(),
@@ -275,13 +473,11 @@ impl Ingester {
                 project_id: project_id.clone(),
                 memory_id: memory_id.clone(),
             }).await;
-
-            valid_memory_ids.push(memory_id);
         }
         
         // Cleanup memories that no longer exist in this file (e.g. after code shift or deletion)
         for old_id in old_memories {
-            if !new_memories.contains(&old_id) {
+            if !new_memories.contains(&old_id) && !moved.contains(&old_id) {
                 self.memory_hashes.remove(&old_id);
                 // Explicitly delete from engine
                 self.job_queue.enqueue(Job::DeleteMemory {
@@ -336,6 +532,134 @@ impl Ingester {
         self.process_chunks(chunks, project_id, &source).await
     }
 
+    /// Ingest the entries of a sitemap or RSS/Atom feed whose stamp changed
+    /// since `subscription` last saw them, then record the new stamps.
+    /// Re-ingested pages drop the chunks of their previous version.
+    pub async fn process_feed(
+        &mut self,
+        subscription: &mut FeedSubscription,
+        project_id: &str,
+    ) -> Result<FeedRefresh, String> {
+        let client = reqwest::Client::builder()
+            .user_agent("CueMap/0.6 (https://cuemap.dev; bot)")
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        let fetch = |url: String| {
+            let client = client.clone();
+            async move {
+                let response = client.get(&url).send().await.map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+                if !response.status().is_success() {
+                    return Err(format!("Failed to fetch {}: {}", url, response.status()));
+                }
+                response.text().await.map_err(|e| format!("Failed to read {}: {}", url, e))
+            }
+        };
+
+        let body = fetch(subscription.url.clone()).await?;
+        let (kind, mut entries) = feeds::parse_feed(&body)
+            .ok_or_else(|| format!("{} is not a sitemap or RSS/Atom feed", subscription.url))?;
+        let mut errors = Vec::new();
+
+        // A sitemap index lists sitemaps; read those for the pages
+        if kind == FeedKind::SitemapIndex {
+            let sitemaps = std::mem::take(&mut entries);
+            for sitemap in sitemaps.iter().take(feeds::MAX_NESTED_SITEMAPS) {
+                match fetch(sitemap.url.clone()).await.map(|body| feeds::parse_feed(&body)) {
+                    Ok(Some((_, nested))) => entries.extend(nested),
+                    Ok(None) => errors.push((sitemap.url.clone(), "Not a sitemap".to_string())),
+                    Err(e) => errors.push((sitemap.url.clone(), e)),
+                }
+                if entries.len() >= feeds::MAX_FEED_ENTRIES {
+                    break;
+                }
+            }
+        }
+        entries.truncate(feeds::MAX_FEED_ENTRIES);
+
+        let changed: Vec<FeedEntry> = feeds::changed_entries(&entries, &subscription.entries).into_iter().cloned().collect();
+        let mut refresh = FeedRefresh {
+            kind,
+            entries_found: entries.len(),
+            ingested: Vec::new(),
+            unchanged: entries.len() - changed.len(),
+            memory_ids: Vec::new(),
+            errors,
+        };
+
+        for entry in changed {
+            match self.process_url(&entry.url, project_id).await {
+                Ok(memory_ids) => {
+                    // Drop chunks of the page's previous version
+                    self.job_queue.enqueue(Job::VerifyFile {
+                        project_id: project_id.to_string(),
+                        file_path: format!("url:{}", entry.url),
+                        valid_memory_ids: memory_ids.clone(),
+                    }).await;
+                    subscription.entries.insert(entry.url.clone(), entry.updated.clone().unwrap_or_default());
+                    refresh.memory_ids.extend(memory_ids);
+                    refresh.ingested.push(entry.url);
+                }
+                // Not recorded, so the next refresh retries it
+                Err(e) => refresh.errors.push((entry.url, e)),
+            }
+        }
+
+        subscription.last_refreshed = Some(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        );
+        Ok(refresh)
+    }
+
+    /// Ingest the documents `connector` lists as changed since `config`'s
+    /// cursor, advancing the cursor past each one. Re-ingested documents drop
+    /// the chunks of their previous version.
+    pub async fn process_connector(
+        &mut self,
+        connector: &dyn Connector,
+        config: &mut ConnectorConfig,
+        project_id: &str,
+    ) -> Result<SyncReport, String> {
+        let documents = connector.list(config.cursor.as_deref(), connectors::MAX_SYNC_DOCUMENTS).await?;
+        let mut report = SyncReport {
+            more: documents.len() >= connectors::MAX_SYNC_DOCUMENTS,
+            ..Default::default()
+        };
+        debug!("Syncing {} documents from {}", documents.len(), connector.name());
+
+        for document in documents {
+            match connector.fetch(&document).await {
+                Ok(fetched) => {
+                    let source = config.document_source(&fetched.id);
+                    let chunks = connectors::document_chunks(&config.id, &fetched);
+                    let memory_ids = self.process_chunks(chunks, project_id, &source).await?;
+                    // Drop chunks of the document's previous version
+                    self.job_queue.enqueue(Job::VerifyFile {
+                        project_id: project_id.to_string(),
+                        file_path: source,
+                        valid_memory_ids: memory_ids.clone(),
+                    }).await;
+                    report.memory_ids.extend(memory_ids);
+                    report.synced.push(fetched.id);
+                }
+                // Skipped rather than retried forever; it is picked up again when it next changes
+                Err(e) => report.errors.push((document.id.clone(), e)),
+            }
+            config.cursor = Some(document.cursor);
+        }
+
+        config.last_synced = Some(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        );
+        Ok(report)
+    }
+
     /// Process URL with recursive crawling up to specified depth
     /// Uses BFS traversal, extracts links only from main content (not nav/footer)
     /// 
@@ -407,7 +731,7 @@ impl Ingester {
             match Chunker::chunk_url(&current_url, false).await {
                 Ok(chunks) => {
                     let source = format!("url:{}", current_url);
-                    for chunk in chunks {
+                    for chunk in Chunker::apply_limits(chunks, &self.config.chunking) {
                         all_chunks.push((source.clone(), chunk));
                     }
                     result.pages_crawled += 1;
@@ -478,10 +802,7 @@ impl Ingester {
         
         // Write all chunks
         for (source, chunk) in &all_chunks {
-            let mut chunk_hasher = Sha256::new();
-            chunk_hasher.update(chunk.content.as_bytes());
-            let chunk_hash = format!("{:x}", chunk_hasher.finalize());
-            let memory_id = format!("{}:{}", source, chunk_hash);
+            let memory_id = chunk_memory_id(source, &chunk.content);
             
             // Write immediately
             self.job_queue.enqueue(Job::ExtractAndIngest {
@@ -491,7 +812,9 @@ impl Ingester {
                 file_path: source.clone(),
                 structural_cues: chunk.structural_cues.clone(),
                 category: chunk.category,
+                provenance: chunk_provenance(source, chunk),
             }).await;
+            self.queue_fact_extraction(project_id, &memory_id, &chunk.content, source).await;
             
             result.memory_ids.push(memory_id);
         }
@@ -502,10 +825,7 @@ impl Ingester {
         debug!("Crawl Phase 3: Buffering {} background jobs...", all_chunks.len() * 3);
         
         for (source, chunk) in all_chunks {
-            let mut chunk_hasher = Sha256::new();
-            chunk_hasher.update(chunk.content.as_bytes());
-            let chunk_hash = format!("{:x}", chunk_hasher.finalize());
-            let memory_id = format!("{}:{}", source, chunk_hash);
+            let memory_id = chunk_memory_id(&source, &chunk.content);
             
             // Buffer downstream jobs for phased processing
             self.job_queue.buffer(project_id, Job::ProposeCues {
@@ -585,6 +905,7 @@ impl Ingester {
         project_id: &str, 
         source: &str
     ) -> Result<Vec<String>, String> {
+        let chunks = Chunker::apply_limits(chunks, &self.config.chunking);
         let mut memory_ids = Vec::new();
         
         // Track session for progress reporting
@@ -594,12 +915,8 @@ impl Ingester {
         }
         
         for chunk in chunks.iter() {
-            let mut chunk_hasher = Sha256::new();
-            chunk_hasher.update(chunk.content.as_bytes());
-            let chunk_hash = format!("{:x}", chunk_hasher.finalize());
-            
             // Use source for ID consistency
-            let memory_id = format!("{}:{}", source, chunk_hash);
+            let memory_id = chunk_memory_id(source, &chunk.content);
             
             // ExtractAndIngest does the write - enqueue immediately  
             self.job_queue.enqueue(Job::ExtractAndIngest {
@@ -609,7 +926,9 @@ impl Ingester {
                 file_path: source.to_string(),
                 structural_cues: chunk.structural_cues.clone(),
                 category: chunk.category,
+                provenance: chunk_provenance(source, chunk),
             }).await;
+            self.queue_fact_extraction(project_id, &memory_id, &chunk.content, source).await;
             
             // Buffer downstream jobs for phased processing
             self.job_queue.buffer(project_id, Job::ProposeCues {
@@ -645,6 +964,86 @@ impl Ingester {
     }
 }
 
+/// A chunk as it would be ingested, with the memory id and `path:` cue value it would get
+#[derive(Debug, Clone)]
+pub struct PreviewChunk {
+    pub memory_id: String,
+    pub file_path: String,
+    pub chunk: crate::agent::chunker::Chunk,
+}
+
+/// Memory id of a chunk from a URL or API content: the source plus the content hash
+pub fn chunk_memory_id(source: &str, content: &str) -> String {
+    format!("{}:{}", source, content_hash(content))
+}
+
+/// Provenance of content ingested from `source`: a watched file's path, or
+/// `url:`, `api:` (posted content), `file:` (upload), `connector:` or `git:`
+/// followed by where it came from
+pub fn source_provenance(source: &str, content: &str) -> Provenance {
+    let (source_type, uri) = match source.split_once(':') {
+        Some(("url", uri)) => ("url", uri),
+        Some(("api", uri)) => ("content", uri),
+        Some(("file", uri)) => ("upload", uri),
+        Some(("connector", uri)) => ("connector", uri),
+        Some(("git", uri)) => ("git", uri),
+        _ => ("file", source),
+    };
+    Provenance {
+        source_type: source_type.to_string(),
+        uri: uri.to_string(),
+        line_range: None,
+        page_range: None,
+        ingest_time: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        content_hash: content_hash(content),
+    }
+}
+
+/// Provenance of a chunk ingested from `source`, with its line range and
+/// the pages of its `page:` cues. Chat turn numbers, transcript seconds and
+/// web page chunk indexes are not lines and are left out.
+pub fn chunk_provenance(source: &str, chunk: &crate::agent::chunker::Chunk) -> Provenance {
+    use crate::agent::chunker::ChunkCategory;
+
+    let mut provenance = source_provenance(source, &chunk.content);
+    let has_lines = chunk.start_line > 0
+        && !matches!(chunk.category, ChunkCategory::Conversation | ChunkCategory::WebContent)
+        && !chunk.structural_cues.iter().any(|c| c.starts_with("timestamp:"));
+    if has_lines {
+        provenance.line_range = Some((chunk.start_line, chunk.end_line.max(chunk.start_line)));
+    }
+    let pages: Vec<usize> = chunk
+        .structural_cues
+        .iter()
+        .filter_map(|c| c.strip_prefix("page:")?.parse().ok())
+        .collect();
+    if let (Some(first), Some(last)) = (pages.iter().min(), pages.iter().max()) {
+        provenance.page_range = Some((*first, *last));
+    }
+    provenance
+}
+
+/// Memory ids of a watched file's chunks, keyed by line range and suffixed
+/// when two chunks share a range
+fn file_memory_ids(path_norm: &str, chunks: &[crate::agent::chunker::Chunk]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut ids = Vec::with_capacity(chunks.len());
+    for chunk in chunks {
+        let mut memory_id = format!("file:{}:{}-{}", path_norm, chunk.start_line, chunk.end_line);
+        let mut suffix = 1;
+        while seen.contains(&memory_id) {
+            memory_id = format!("file:{}:{}-{}:{}", path_norm, chunk.start_line, chunk.end_line, suffix);
+            suffix += 1;
+        }
+        seen.insert(memory_id.clone());
+        ids.push(memory_id);
+    }
+    ids
+}
+
 /// Result of a recursive URL crawl
 #[derive(Debug, Clone)]
 pub struct CrawlResult {
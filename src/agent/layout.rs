@@ -0,0 +1,429 @@
+//! Layout-aware chunking for PDF and DOCX documents.
+//!
+//! Extracted text is first turned into blocks (headings, paragraphs, tables),
+//! then grouped into one chunk per section, so a document is split where its
+//! author split it rather than every few sentences. Tables become their own
+//! chunks with one structured line per row (`Header: value | Header: value`)
+//! and a `table:` cue naming the section they sit in. PDF chunks carry the
+//! `page:` cues of every page they span so answers can cite them.
+//!
+//! PDFs have no markup left after extraction, so headings and tables are
+//! guessed from the text: numbered (`2.1 Scope`) or all-caps lines and short
+//! standalone title lines are headings, and runs of lines splitting into the
+//! same number of columns (tabs, `|` or wide gaps) are tables.
+
+use super::chunker::{Chunk, ChunkCategory};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Sections longer than this are split at paragraph boundaries
+const MAX_SECTION_CHARS: usize = 2000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockKind {
+    Heading(String),
+    Paragraph(String),
+    /// Rows of cells, header row first
+    Table(Vec<Vec<String>>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    pub kind: BlockKind,
+    /// 1-based page, when the format has pages
+    pub page: Option<usize>,
+    /// 1-based position in the extracted text (line for PDF, paragraph for DOCX)
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+fn numbered_heading_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^(\d+(\.\d+)*\.?|[IVX]+\.|[A-Z]\.)\s+\p{Lu}").unwrap())
+}
+
+fn column_gap_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\t+| {2,}").unwrap())
+}
+
+/// Cells of a table-looking line, None for prose
+fn split_cells(line: &str) -> Option<Vec<String>> {
+    let line = line.trim();
+    let cells: Vec<String> = if line.contains('|') {
+        line.trim_matches('|').split('|').map(|c| c.trim().to_string()).collect()
+    } else {
+        column_gap_re().split(line).map(|c| c.trim().to_string()).collect()
+    };
+    // Markdown-style separator rows (|---|---|) belong to the table but carry nothing
+    if cells.len() >= 2 && cells.iter().all(|c| !c.is_empty() && c.chars().all(|ch| matches!(ch, '-' | ':' | '='))) {
+        return Some(Vec::new());
+    }
+    (cells.len() >= 2 && cells.iter().filter(|c| !c.is_empty()).count() >= 2).then_some(cells)
+}
+
+/// Whether a standalone PDF line reads like a heading
+fn is_heading(line: &str) -> bool {
+    let line = line.trim();
+    let words = line.split_whitespace().count();
+    if line.is_empty() || line.len() > 80 || words > 12 {
+        return false;
+    }
+    if line.ends_with(['.', ',', ';', ':', '?', '!']) && !numbered_heading_re().is_match(line) {
+        return false;
+    }
+    if numbered_heading_re().is_match(line) {
+        return true;
+    }
+    let letters: Vec<char> = line.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.len() >= 3 && letters.iter().all(|c| c.is_uppercase()) {
+        return true;
+    }
+    // Short Title Case line, e.g. "Payment Retries"
+    words <= 8 && line.split_whitespace().all(|w| {
+        w.chars().next().is_some_and(|c| !c.is_alphabetic() || c.is_uppercase())
+            || matches!(w, "a" | "an" | "and" | "of" | "the" | "to" | "in" | "for" | "on" | "or" | "with")
+    })
+}
+
+/// Blocks of a PDF's per-page text
+pub fn pdf_blocks(pages: &[String]) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut offset = 0;
+
+    for (page_idx, page) in pages.iter().enumerate() {
+        let page_no = Some(page_idx + 1);
+        let lines: Vec<&str> = page.lines().collect();
+        let cells: Vec<Option<Vec<String>>> = lines.iter().map(|line| split_cells(line)).collect();
+        let mut paragraph: Vec<&str> = Vec::new();
+        let mut paragraph_start = 0;
+        let mut i = 0;
+
+        while i < lines.len() {
+            let line_no = offset + i + 1;
+            let end = table_end(&cells, i);
+            if end > i {
+                push_paragraph(&mut blocks, &mut paragraph, page_no, paragraph_start, line_no - 1);
+                let rows = cells[i..end].iter().flatten().filter(|row| !row.is_empty()).cloned().collect();
+                blocks.push(Block {
+                    kind: BlockKind::Table(rows),
+                    page: page_no,
+                    start_line: line_no,
+                    end_line: offset + end,
+                });
+                i = end;
+                continue;
+            }
+
+            let line = lines[i].trim();
+            let blank_before = i == 0 || lines[i - 1].trim().is_empty();
+            let blank_after = i + 1 == lines.len() || lines[i + 1].trim().is_empty();
+            i += 1;
+
+            if line.is_empty() {
+                push_paragraph(&mut blocks, &mut paragraph, page_no, paragraph_start, line_no - 1);
+                continue;
+            }
+            // Bare page numbers
+            if line.chars().all(|c| c.is_ascii_digit()) && blank_before && blank_after {
+                continue;
+            }
+            if blank_before && (blank_after || numbered_heading_re().is_match(line)) && is_heading(line) {
+                push_paragraph(&mut blocks, &mut paragraph, page_no, paragraph_start, line_no - 1);
+                blocks.push(Block {
+                    kind: BlockKind::Heading(line.to_string()),
+                    page: page_no,
+                    start_line: line_no,
+                    end_line: line_no,
+                });
+                continue;
+            }
+            if paragraph.is_empty() {
+                paragraph_start = line_no;
+            }
+            paragraph.push(line);
+        }
+
+        offset += lines.len();
+        push_paragraph(&mut blocks, &mut paragraph, page_no, paragraph_start, offset);
+    }
+
+    blocks
+}
+
+/// End (exclusive) of a table starting at line `start`: two or more
+/// consecutive rows with the same number of columns. `start` if there is none.
+fn table_end(cells: &[Option<Vec<String>>], start: usize) -> usize {
+    let width = match &cells[start] {
+        Some(row) if !row.is_empty() => row.len(),
+        _ => return start,
+    };
+    let mut end = start + 1;
+    let mut rows = 1;
+    while let Some(Some(row)) = cells.get(end) {
+        if !row.is_empty() {
+            if row.len() != width {
+                break;
+            }
+            rows += 1;
+        }
+        end += 1;
+    }
+    if rows >= 2 { end } else { start }
+}
+
+fn push_paragraph(blocks: &mut Vec<Block>, lines: &mut Vec<&str>, page: Option<usize>, start: usize, end: usize) {
+    if !lines.is_empty() {
+        blocks.push(Block {
+            kind: BlockKind::Paragraph(lines.join(" ")),
+            page,
+            start_line: start,
+            end_line: end,
+        });
+        lines.clear();
+    }
+}
+
+/// Blocks of a DOCX body: `Heading N`/`Title` styled paragraphs are headings
+pub fn docx_blocks(docx: &docx_rs::Docx) -> Vec<Block> {
+    use docx_rs::{DocumentChild, TableCellContent, TableChild, TableRowChild};
+
+    let mut blocks = Vec::new();
+    for (idx, child) in docx.document.children.iter().enumerate() {
+        let position = idx + 1;
+        match child {
+            DocumentChild::Paragraph(para) => {
+                let text = paragraph_text(para);
+                if text.is_empty() {
+                    continue;
+                }
+                let style = para.property.style.as_ref().map(|s| s.val.to_lowercase()).unwrap_or_default();
+                let kind = if style.starts_with("heading") || style == "title" {
+                    BlockKind::Heading(text)
+                } else {
+                    BlockKind::Paragraph(text)
+                };
+                blocks.push(Block { kind, page: None, start_line: position, end_line: position });
+            }
+            DocumentChild::Table(table) => {
+                let rows: Vec<Vec<String>> = table
+                    .rows
+                    .iter()
+                    .map(|TableChild::TableRow(row)| {
+                        row.cells
+                            .iter()
+                            .map(|TableRowChild::TableCell(cell)| {
+                                cell.children
+                                    .iter()
+                                    .filter_map(|content| match content {
+                                        TableCellContent::Paragraph(p) => Some(paragraph_text(p)),
+                                        _ => None,
+                                    })
+                                    .filter(|t| !t.is_empty())
+                                    .collect::<Vec<_>>()
+                                    .join(" ")
+                            })
+                            .collect()
+                    })
+                    .filter(|cells: &Vec<String>| cells.iter().any(|c| !c.is_empty()))
+                    .collect();
+                if !rows.is_empty() {
+                    blocks.push(Block { kind: BlockKind::Table(rows), page: None, start_line: position, end_line: position });
+                }
+            }
+            _ => {}
+        }
+    }
+    blocks
+}
+
+/// Text of a DOCX paragraph's runs
+pub fn paragraph_text(para: &docx_rs::Paragraph) -> String {
+    use docx_rs::{ParagraphChild, RunChild};
+
+    let mut text = String::new();
+    for child in &para.children {
+        if let ParagraphChild::Run(run) = child {
+            for child in &run.children {
+                if let RunChild::Text(t) = child {
+                    text.push_str(&t.text);
+                }
+            }
+        }
+    }
+    text.trim().to_string()
+}
+
+/// Lowercased cue value with spaces as `_`
+fn cue_value(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join("_").to_lowercase()
+}
+
+struct Section {
+    heading: Option<String>,
+    text: Vec<String>,
+    /// Whether `text` holds more than headings
+    has_body: bool,
+    pages: Vec<usize>,
+    start_line: usize,
+    end_line: usize,
+    /// Chunks of tables inside the section, emitted after it
+    tables: Vec<Chunk>,
+}
+
+/// Group blocks into section chunks and table chunks. `doc_type` is `pdf` or
+/// `docx`; `file` is the `file:` cue value.
+pub fn chunk_blocks(blocks: &[Block], doc_type: &str, file: &str) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    let mut section = Section {
+        heading: None,
+        text: Vec::new(),
+        has_body: false,
+        pages: Vec::new(),
+        start_line: 1,
+        end_line: 1,
+        tables: Vec::new(),
+    };
+
+    let flush = |section: &mut Section, chunks: &mut Vec<Chunk>| {
+        if !section.text.is_empty() {
+            let mut cues = vec![format!("type:{}_section", doc_type), format!("file:{}", file)];
+            if let Some(heading) = &section.heading {
+                cues.push(format!("header:{}", heading));
+            }
+            cues.extend(section.pages.iter().map(|p| format!("page:{}", p)));
+            chunks.push(Chunk {
+                content: section.text.join("\n\n"),
+                start_line: section.start_line,
+                end_line: section.end_line,
+                context: section.heading.clone().unwrap_or_else(|| file.to_string()),
+                structural_cues: cues,
+                category: ChunkCategory::Prose,
+            });
+        }
+        chunks.append(&mut section.tables);
+        section.text.clear();
+        section.has_body = false;
+        section.pages.clear();
+    };
+
+    for block in blocks {
+        match &block.kind {
+            BlockKind::Heading(heading) => {
+                // A title directly above a heading stays with it
+                if section.has_body || !section.tables.is_empty() {
+                    flush(&mut section, &mut chunks);
+                }
+                if section.text.is_empty() {
+                    section.start_line = block.start_line;
+                }
+                section.heading = Some(heading.clone());
+                section.text.push(heading.clone());
+            }
+            BlockKind::Paragraph(text) => {
+                let length: usize = section.text.iter().map(|t| t.len() + 2).sum();
+                if section.has_body && length + text.len() > MAX_SECTION_CHARS {
+                    flush(&mut section, &mut chunks);
+                }
+                if section.text.is_empty() {
+                    section.start_line = block.start_line;
+                }
+                section.text.push(text.clone());
+                section.has_body = true;
+            }
+            BlockKind::Table(rows) => {
+                let tables = table_chunks(rows, block, section.heading.as_deref(), doc_type, file);
+                section.tables.extend(tables);
+                continue;
+            }
+        }
+        section.end_line = block.end_line;
+        if let Some(page) = block.page {
+            if !section.pages.contains(&page) {
+                section.pages.push(page);
+            }
+        }
+    }
+    flush(&mut section, &mut chunks);
+
+    chunks
+}
+
+/// One chunk per group of rows, each row as `Header: value | Header: value`
+fn table_chunks(rows: &[Vec<String>], block: &Block, heading: Option<&str>, doc_type: &str, file: &str) -> Vec<Chunk> {
+    if rows.is_empty() {
+        return Vec::new();
+    }
+    let (header, body) = match rows.split_first() {
+        Some((header, body)) if !body.is_empty() => (header, body),
+        _ => (&rows[0], rows),
+    };
+    let has_header = body.len() < rows.len();
+    let table_name = heading.unwrap_or(file);
+
+    let mut cues = vec![
+        format!("type:{}_table", doc_type),
+        format!("file:{}", file),
+        format!("table:{}", cue_value(table_name)),
+    ];
+    if let Some(heading) = heading {
+        cues.push(format!("header:{}", heading));
+    }
+    if has_header {
+        for column in header.iter().filter(|c| !c.is_empty()) {
+            let cue = format!("column:{}", cue_value(column));
+            if !cues.contains(&cue) {
+                cues.push(cue);
+            }
+        }
+    }
+    if let Some(page) = block.page {
+        cues.push(format!("page:{}", page));
+    }
+
+    let render = |row: &Vec<String>| -> String {
+        row.iter()
+            .enumerate()
+            .filter(|(_, cell)| !cell.is_empty())
+            .map(|(i, cell)| match header.get(i).filter(|h| has_header && !h.is_empty()) {
+                Some(name) => format!("{}: {}", name, cell),
+                None => cell.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+    };
+
+    let mut chunks = Vec::new();
+    let mut lines: Vec<String> = Vec::new();
+    let mut length = 0;
+    let mut part = 0;
+    let mut push = |lines: &mut Vec<String>, part: &mut usize| {
+        if lines.is_empty() {
+            return;
+        }
+        *part += 1;
+        chunks.push(Chunk {
+            content: format!("Table: {}\n{}", table_name, lines.join("\n")),
+            start_line: block.start_line,
+            end_line: block.end_line,
+            context: format!("{}:table:{}:{}", file, table_name, part),
+            structural_cues: cues.clone(),
+            category: ChunkCategory::Structured,
+        });
+        lines.clear();
+    };
+    for row in body {
+        let line = render(row);
+        if line.is_empty() {
+            continue;
+        }
+        if length + line.len() > MAX_SECTION_CHARS {
+            push(&mut lines, &mut part);
+            length = 0;
+        }
+        length += line.len() + 1;
+        lines.push(line);
+    }
+    push(&mut lines, &mut part);
+    chunks
+}
@@ -3,20 +3,40 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{info, error};
 
+use crate::agent::filter::IngestFilterConfig;
 use crate::agent::{Agent, AgentConfig};
 use crate::jobs::{JobQueue, ProjectProvider};
 
+/// Agent key (and state file stem) for a server-wide root. Per-project agents
+/// started from project meta are keyed by the bare project id; roots add a
+/// path hash so one project can be fed by several directories.
+pub fn root_agent_key(project_id: &str, path: &str) -> String {
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(path.as_bytes());
+    format!("{}_{}", project_id, &hex::encode(digest)[..8])
+}
+
 /// Manages dynamic per-project Agent instances
 pub struct AgentManager {
     agents: RwLock<HashMap<String, Arc<Agent>>>,
+    /// Each agent's config as given to `start_agent`, before defaults are merged
+    configs: RwLock<HashMap<String, AgentConfig>>,
+    /// Server-wide include/exclude/size rules applied under every agent's own
+    defaults: RwLock<IngestFilterConfig>,
     job_queue: Arc<JobQueue>,
     provider: Arc<dyn ProjectProvider>,
 }
 
 impl AgentManager {
     pub fn new(job_queue: Arc<JobQueue>, provider: Arc<dyn ProjectProvider>) -> Self {
+        Self::with_defaults(job_queue, provider, IngestFilterConfig::default())
+    }
+
+    pub fn with_defaults(job_queue: Arc<JobQueue>, provider: Arc<dyn ProjectProvider>, defaults: IngestFilterConfig) -> Self {
         Self {
             agents: RwLock::new(HashMap::new()),
+            configs: RwLock::new(HashMap::new()),
+            defaults: RwLock::new(defaults),
             job_queue,
             provider,
         }
@@ -28,14 +48,18 @@ impl AgentManager {
         self.stop_agent(project_id).await;
 
         info!("AgentManager: Spawning new Agent for project '{}'", project_id);
+
+        let mut effective = config.clone();
+        effective.filter = self.defaults.read().await.merged(&config.filter);
         
-        match Agent::new(config, self.job_queue.clone(), self.provider.clone()) {
+        match Agent::new(effective, self.job_queue.clone(), self.provider.clone()) {
             Ok(agent) => {
                 let agent = Arc::new(agent);
                 agent.start().await;
                 
                 let mut locked = self.agents.write().await;
                 locked.insert(project_id.to_string(), agent);
+                self.configs.write().await.insert(project_id.to_string(), config);
                 info!("AgentManager: Successfully spawned Agent for '{}'", project_id);
             }
             Err(e) => {
@@ -50,6 +74,7 @@ impl AgentManager {
         if locked.remove(project_id).is_some() {
             info!("AgentManager: Stopped Agent for '{}'", project_id);
         }
+        self.configs.write().await.remove(project_id);
     }
 
     /// Retrieve the running agent if it exists
@@ -57,4 +82,36 @@ impl AgentManager {
         let locked = self.agents.read().await;
         locked.get(project_id).cloned()
     }
+
+    /// Server-wide filter defaults
+    pub async fn defaults(&self) -> IngestFilterConfig {
+        self.defaults.read().await.clone()
+    }
+
+    /// Replace the server-wide filter defaults and restart every running agent
+    /// under them. Already-ingested files that are now excluded stay in memory.
+    pub async fn set_defaults(&self, defaults: IngestFilterConfig) -> Result<(), String> {
+        defaults.validate()?;
+        *self.defaults.write().await = defaults;
+
+        let configs: Vec<(String, AgentConfig)> = self.configs.read().await
+            .iter()
+            .map(|(key, config)| (key.clone(), config.clone()))
+            .collect();
+        for (key, config) in configs {
+            self.start_agent(&key, config).await;
+        }
+        Ok(())
+    }
+
+    /// Running agents with their effective (merged) configs, sorted by key
+    pub async fn list_agents(&self) -> Vec<(String, AgentConfig)> {
+        let locked = self.agents.read().await;
+        let mut agents: Vec<(String, AgentConfig)> = locked
+            .iter()
+            .map(|(key, agent)| (key.clone(), agent.config().clone()))
+            .collect();
+        agents.sort_by(|a, b| a.0.cmp(&b.0));
+        agents
+    }
 }
@@ -1,4 +1,11 @@
+pub mod chat;
 pub mod chunker;
+pub mod feeds;
+pub mod filter;
+pub mod git;
+pub mod layout;
+pub mod ocr;
+pub mod transcribe;
 pub mod watcher;
 pub mod ingester;
 pub mod search;
@@ -16,6 +23,14 @@ pub struct AgentConfig {
     pub watch_dir: String,
     pub throttle_ms: u64,
     pub state_file: Option<std::path::PathBuf>,
+    /// Include/exclude globs relative to `watch_dir`
+    pub filter: filter::IngestFilterConfig,
+    /// Commit cues on chunks and commit history ingestion
+    pub git: git::GitIngestConfig,
+    /// Chunk size limits (the project's, with any overrides of the ingest request)
+    pub chunking: chunker::ChunkingConfig,
+    /// Also store the facts the project's LLM finds in each chunk as memories
+    pub extract_facts: bool,
 }
 
 pub struct Agent {
@@ -37,6 +52,9 @@ impl Agent {
         
         info!("Initializing Self-Learning Agent watching `{}` for project '{}'", config.watch_dir, config.project_id);
 
+        // Reject bad globs up front instead of silently ingesting everything
+        filter::PathFilter::new(std::path::Path::new(&config.watch_dir), &config.filter)?;
+
         let mut ingester_obj = ingester::Ingester::new(
             config.clone(),
             job_queue,
@@ -60,6 +78,11 @@ impl Agent {
         })
     }
 
+    /// The config this agent runs with (canonical watch dir, merged filter)
+    pub fn config(&self) -> &AgentConfig {
+        &self._config
+    }
+
     pub async fn start(&self) {
         info!("Agent started.");
         // Watcher runs in its own thread/task locally managed
@@ -71,6 +94,9 @@ impl Agent {
             if let Err(e) = ingester.scan_all().await {
                 warn!("Initial scan failed: {}", e);
             }
+            if let Err(e) = ingester.ingest_git_history().await {
+                warn!("Git history ingestion failed: {}", e);
+            }
             
             // Save state after initial scan
             if let Some(path) = state_file {
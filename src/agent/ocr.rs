@@ -0,0 +1,180 @@
+//! OCR for screenshots and scanned PDFs (build with `--features ocr`).
+//!
+//! Text is read with the `tesseract` CLI, or posted to an external OCR service
+//! when `service_url` is set. Scanned PDFs (no text layer) are rasterized with
+//! `pdftoppm` first, one image per page. Chunks built from OCR text carry an
+//! `ocr:<engine>` cue so recall can tell recognized text from extracted text.
+//!
+//! Without the feature, image files are not recognized by the chunker and
+//! scanned PDFs produce no chunks, as before.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::RwLock;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OcrConfig {
+    /// Run OCR on images and scanned PDFs (only with `--features ocr`)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Tesseract binary
+    #[serde(default = "default_command")]
+    pub command: String,
+    /// Tesseract languages, e.g. `eng+deu`
+    #[serde(default = "default_languages")]
+    pub languages: String,
+    /// OCR service to use instead of tesseract: the image is POSTed as the
+    /// request body and the service answers with `{"text": "..."}` or plain text
+    #[serde(default)]
+    pub service_url: Option<String>,
+    /// Resolution scanned PDF pages are rendered at
+    #[serde(default = "default_dpi")]
+    pub dpi: u32,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_command() -> String {
+    "tesseract".to_string()
+}
+
+fn default_languages() -> String {
+    "eng".to_string()
+}
+
+fn default_dpi() -> u32 {
+    300
+}
+
+impl Default for OcrConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            command: default_command(),
+            languages: default_languages(),
+            service_url: None,
+            dpi: default_dpi(),
+        }
+    }
+}
+
+impl OcrConfig {
+    /// The `ocr:` cue value for text this config recognizes
+    pub fn engine(&self) -> &'static str {
+        if self.service_url.is_some() { "service" } else { "tesseract" }
+    }
+}
+
+static CONFIG: RwLock<Option<OcrConfig>> = RwLock::new(None);
+
+/// Set the server-wide OCR settings (from `[ocr]`)
+pub fn configure(config: OcrConfig) {
+    *CONFIG.write().unwrap() = Some(config);
+}
+
+/// The OCR settings in use, None when OCR is compiled out or disabled
+pub fn active_config() -> Option<OcrConfig> {
+    if !cfg!(feature = "ocr") {
+        return None;
+    }
+    let config = CONFIG.read().unwrap().clone().unwrap_or_default();
+    config.enabled.then_some(config)
+}
+
+/// Image formats OCR can read
+pub fn is_image(path: &Path) -> bool {
+    let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("").to_lowercase();
+    matches!(ext.as_str(), "png" | "jpg" | "jpeg" | "tif" | "tiff" | "bmp" | "gif" | "webp")
+}
+
+/// Text in an image file
+pub fn image_text(config: &OcrConfig, path: &Path) -> Result<String, String> {
+    match &config.service_url {
+        Some(url) => service_text(url, path),
+        None => tesseract_text(config, path),
+    }
+}
+
+/// Text of each page of a PDF without a text layer, rendered with `pdftoppm`
+pub fn pdf_pages_text(config: &OcrConfig, path: &Path) -> Result<Vec<String>, String> {
+    let dir = std::env::temp_dir().join(format!("cuemap-ocr-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let result: Result<Vec<String>, String> = (|| {
+        let out = std::process::Command::new("pdftoppm")
+            .arg("-r")
+            .arg(config.dpi.to_string())
+            .arg("-png")
+            .arg(path)
+            .arg(dir.join("page"))
+            .output()
+            .map_err(|e| format!("Failed to run pdftoppm: {}", e))?;
+        if !out.status.success() {
+            return Err(format!("pdftoppm failed: {}", String::from_utf8_lossy(&out.stderr).trim()));
+        }
+
+        // page-1.png, page-2.png, ... (zero-padded for long documents, so sort by number)
+        let mut images: Vec<(usize, std::path::PathBuf)> = std::fs::read_dir(&dir)
+            .map_err(|e| e.to_string())?
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let stem = path.file_stem()?.to_str()?;
+                let number = stem.rsplit('-').next()?.parse().ok()?;
+                Some((number, path))
+            })
+            .collect();
+        images.sort();
+        images.iter().map(|(_, image)| image_text(config, image)).collect()
+    })();
+
+    let _ = std::fs::remove_dir_all(&dir);
+    result
+}
+
+fn tesseract_text(config: &OcrConfig, path: &Path) -> Result<String, String> {
+    let out = std::process::Command::new(&config.command)
+        .arg(path)
+        .arg("stdout")
+        .arg("-l")
+        .arg(&config.languages)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", config.command, e))?;
+    if !out.status.success() {
+        return Err(format!("{} failed: {}", config.command, String::from_utf8_lossy(&out.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).to_string())
+}
+
+fn service_text(url: &str, path: &Path) -> Result<String, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Read error: {}", e))?;
+    let mime = mime_guess::from_path(path).first_or_octet_stream().to_string();
+    let url = url.to_string();
+
+    // The blocking client can't run on a runtime thread, and chunking is
+    // called from async handlers
+    std::thread::spawn(move || {
+        let response = reqwest::blocking::Client::new()
+            .post(&url)
+            .header(reqwest::header::CONTENT_TYPE, mime)
+            .body(bytes)
+            .send()
+            .map_err(|e| format!("OCR service request failed: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("OCR service returned {}", response.status()));
+        }
+        let body = response.text().map_err(|e| e.to_string())?;
+        Ok(parse_service_response(&body))
+    })
+    .join()
+    .map_err(|_| "OCR service thread panicked".to_string())?
+}
+
+/// `{"text": "..."}` or the body itself
+pub fn parse_service_response(body: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| v.get("text").and_then(|t| t.as_str()).map(str::to_string))
+        .unwrap_or_else(|| body.to_string())
+}
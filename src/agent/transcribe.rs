@@ -0,0 +1,197 @@
+//! Audio and video transcripts from a Whisper-compatible API.
+//!
+//! Recordings are posted to `<url>/v1/audio/transcriptions` (OpenAI's API,
+//! also served by faster-whisper-server, LocalAI, whisper.cpp's server and
+//! others) with `response_format=verbose_json`. The returned segments are
+//! grouped into fixed time windows, one chunk per window, each carrying a
+//! `timestamp:HH:MM:SS` cue of where the window starts so recall can point
+//! back into the recording.
+
+use super::chunker::{Chunk, ChunkCategory};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::RwLock;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptionConfig {
+    /// Transcribe audio and video files (off unless configured)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Base URL of the Whisper-compatible API
+    #[serde(default = "default_url")]
+    pub url: String,
+    #[serde(default = "default_model")]
+    pub model: String,
+    /// Falls back to `OPENAI_API_KEY`
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Spoken language hint (ISO-639-1), empty = detect
+    #[serde(default)]
+    pub language: String,
+    /// Length of the time window each chunk covers
+    #[serde(default = "default_window_seconds")]
+    pub window_seconds: u64,
+}
+
+fn default_url() -> String {
+    "https://api.openai.com".to_string()
+}
+
+fn default_model() -> String {
+    "whisper-1".to_string()
+}
+
+fn default_window_seconds() -> u64 {
+    60
+}
+
+impl Default for TranscriptionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: default_url(),
+            model: default_model(),
+            api_key: None,
+            language: String::new(),
+            window_seconds: default_window_seconds(),
+        }
+    }
+}
+
+static CONFIG: RwLock<Option<TranscriptionConfig>> = RwLock::new(None);
+
+/// Set the server-wide transcription settings (from `[transcription]`)
+pub fn configure(config: TranscriptionConfig) {
+    *CONFIG.write().unwrap() = Some(config);
+}
+
+/// The transcription settings in use, None when disabled
+pub fn active_config() -> Option<TranscriptionConfig> {
+    CONFIG.read().unwrap().clone().filter(|config| config.enabled)
+}
+
+/// Audio and video formats Whisper accepts
+pub fn is_media(path: &Path) -> bool {
+    let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("").to_lowercase();
+    matches!(ext.as_str(), "mp3" | "mp4" | "mpeg" | "mpga" | "m4a" | "wav" | "webm" | "ogg" | "flac" | "mov")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    /// Seconds from the start of the recording
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+/// Transcribe a recording
+pub fn transcribe(config: &TranscriptionConfig, path: &Path) -> Result<Vec<Segment>, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Read error: {}", e))?;
+    let filename = path.file_name().and_then(|s| s.to_str()).unwrap_or("audio").to_string();
+    let mime = mime_guess::from_path(path).first_or_octet_stream().to_string();
+    let url = format!("{}/v1/audio/transcriptions", config.url.trim_end_matches('/'));
+    let api_key = config.api_key.clone().or_else(|| std::env::var("OPENAI_API_KEY").ok());
+    let (model, language) = (config.model.clone(), config.language.clone());
+
+    // The blocking client can't run on a runtime thread, and chunking is
+    // called from async handlers
+    let body = std::thread::spawn(move || {
+        let file = reqwest::blocking::multipart::Part::bytes(bytes)
+            .file_name(filename)
+            .mime_str(&mime)
+            .map_err(|e| e.to_string())?;
+        let mut form = reqwest::blocking::multipart::Form::new()
+            .part("file", file)
+            .text("model", model)
+            .text("response_format", "verbose_json");
+        if !language.is_empty() {
+            form = form.text("language", language);
+        }
+        let mut request = reqwest::blocking::Client::new().post(&url).multipart(form);
+        if let Some(key) = api_key {
+            request = request.bearer_auth(key);
+        }
+        let response = request.send().map_err(|e| format!("Transcription request failed: {}", e))?;
+        let status = response.status();
+        let body = response.text().map_err(|e| e.to_string())?;
+        if !status.is_success() {
+            return Err(format!("Transcription API returned {}: {}", status, body));
+        }
+        Ok(body)
+    })
+    .join()
+    .map_err(|_| "Transcription thread panicked".to_string())??;
+
+    parse_transcript(&body)
+}
+
+/// Segments of a `verbose_json` response; a bare `{"text": ...}` is one segment
+pub fn parse_transcript(body: &str) -> Result<Vec<Segment>, String> {
+    let value: serde_json::Value = serde_json::from_str(body).map_err(|e| format!("Malformed transcript: {}", e))?;
+    if let Some(segments) = value["segments"].as_array() {
+        return Ok(segments
+            .iter()
+            .filter_map(|s| {
+                Some(Segment {
+                    start: s["start"].as_f64()?,
+                    end: s["end"].as_f64().unwrap_or(0.0),
+                    text: s["text"].as_str()?.trim().to_string(),
+                })
+            })
+            .filter(|s| !s.text.is_empty())
+            .collect());
+    }
+    let text = value["text"].as_str().ok_or("Transcript has no text")?.trim().to_string();
+    let end = value["duration"].as_f64().unwrap_or(0.0);
+    Ok(if text.is_empty() { Vec::new() } else { vec![Segment { start: 0.0, end, text }] })
+}
+
+/// `HH:MM:SS`
+pub fn format_timestamp(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    format!("{:02}:{:02}:{:02}", total / 3600, total / 60 % 60, total % 60)
+}
+
+/// One chunk per `window_seconds` of the recording, by segment start.
+/// `start_line`/`end_line` hold the window's first and last second.
+pub fn chunk_segments(segments: &[Segment], file: &str, window_seconds: u64) -> Vec<Chunk> {
+    let window = window_seconds.max(1);
+    let mut chunks: Vec<Chunk> = Vec::new();
+    let mut current: Option<(u64, Vec<&Segment>)> = None;
+
+    let flush = |(index, segments): (u64, Vec<&Segment>), chunks: &mut Vec<Chunk>| {
+        let start = segments[0].start;
+        let end = segments.iter().map(|s| s.end).fold(start, f64::max);
+        let text = segments.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join(" ");
+        let stamp = format_timestamp((index * window) as f64);
+        chunks.push(Chunk {
+            content: format!("[{}] {}", format_timestamp(start), text),
+            start_line: start as usize,
+            end_line: end.ceil() as usize,
+            context: format!("{}@{}", file, stamp),
+            structural_cues: vec![
+                "type:transcript".to_string(),
+                format!("file:{}", file),
+                format!("timestamp:{}", stamp),
+            ],
+            category: ChunkCategory::Prose,
+        });
+    };
+
+    for segment in segments {
+        let index = segment.start.max(0.0) as u64 / window;
+        match &mut current {
+            Some((current_index, group)) if *current_index == index => group.push(segment),
+            _ => {
+                if let Some(done) = current.take() {
+                    flush(done, &mut chunks);
+                }
+                current = Some((index, vec![segment]));
+            }
+        }
+    }
+    if let Some(done) = current {
+        flush(done, &mut chunks);
+    }
+    chunks
+}
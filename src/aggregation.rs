@@ -0,0 +1,91 @@
+//! Count-only recall (`POST /recall/count`): how many memories a query
+//! matches, optionally broken down per value of a cue namespace and per day
+//! of creation, for dashboard widgets such as "memories mentioning X over
+//! time".
+//!
+//! Matching memories are counted straight from the cue index, without
+//! scoring them or materializing results. Query cues are not expanded by
+//! aliases, pattern completion or fuzzy matching.
+
+use crate::engine::{MetadataFilter, RecallFilter};
+use serde::{Deserialize, Serialize};
+
+/// Buckets of `by_cue` returned unless requested otherwise
+pub const DEFAULT_COUNT_BUCKETS: usize = 50;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountRequest {
+    /// Without cues (or query text) every memory matches
+    #[serde(default)]
+    pub cues: Vec<String>,
+    #[serde(default)]
+    pub query_text: Option<String>,
+    /// Query cues a memory must carry (default 1)
+    #[serde(default)]
+    pub min_intersection: Option<usize>,
+    #[serde(default)]
+    pub created_after: Option<f64>,
+    #[serde(default)]
+    pub created_before: Option<f64>,
+    #[serde(default)]
+    pub metadata_filters: Vec<MetadataFilter>,
+    #[serde(default)]
+    pub exclude_cues: Vec<String>,
+    #[serde(default)]
+    pub phrase: Option<String>,
+    #[serde(default)]
+    pub phrase_window: usize,
+    /// Count matching memories per value of this cue namespace (e.g. `path`)
+    #[serde(default)]
+    pub by_cue: Option<String>,
+    /// Count matching memories per day (UTC) of `created_at`
+    #[serde(default)]
+    pub by_day: bool,
+    /// Largest `by_cue` buckets returned
+    #[serde(default = "default_count_buckets")]
+    pub limit: usize,
+}
+
+fn default_count_buckets() -> usize {
+    DEFAULT_COUNT_BUCKETS
+}
+
+impl CountRequest {
+    pub fn recall_filter(&self) -> RecallFilter {
+        RecallFilter {
+            created_after: self.created_after,
+            created_before: self.created_before,
+            metadata_filters: self.metadata_filters.clone(),
+            exclude_cues: self.exclude_cues.clone(),
+            phrase: self.phrase.clone(),
+            phrase_window: self.phrase_window,
+            scan_limit: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CountBucket {
+    /// Cue value, or day as `YYYY-MM-DD`
+    pub key: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CountReport {
+    pub count: usize,
+    /// Largest buckets first; a memory with several values in the namespace
+    /// counts towards each
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_cue: Option<Vec<CountBucket>>,
+    /// Oldest day first; days without matches are left out
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_day: Option<Vec<CountBucket>>,
+}
+
+/// `YYYY-MM-DD` (UTC) of a day number since the epoch
+pub fn day_key(day: i64) -> String {
+    chrono::DateTime::from_timestamp(day * 86_400, 0)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| day.to_string())
+}
@@ -1,26 +1,50 @@
 use crate::auth::AuthConfig;
-use crate::structures::{MainStats, LexiconStats, MemoryStats};
-use crate::multi_tenant::{MultiTenantEngine, validate_project_id};
-use crate::normalization::normalize_cue;
-use crate::taxonomy::validate_cues;
+use crate::audit::{actor_for_key, AuditAction, AuditEntry, AuditQuery};
+use crate::structures::{MainStats, LexiconStats, MemoryStats, PINNED_METADATA_KEY};
+use crate::multi_tenant::{MultiTenantEngine, ProjectQuota, QuotaExceeded, validate_project_id};
+use crate::normalization::NormalizationConfig;
+use crate::taxonomy::{validate_cues, Taxonomy};
 use crate::jobs::{Job, JobQueue};
-use crate::metrics::MetricsCollector;
+use crate::metrics::{project_series, MetricsCollector, ProjectSeries};
+use crate::slow_queries::{SlowQuery, StageTimer};
+use crate::query_log::LoggedQuery;
+use crate::index_stats::{CueIndexReport, LIST_LENGTH_BUCKETS};
 use crate::persistence::CloudBackupManager;
+use crate::scheduler::ProjectSchedule;
+use crate::agent::chunker::ChunkingConfig;
+use crate::engine::{ExplainLevel, MemoryRecord, MetadataFilter, RecallFilter, RecallParams, RecallProfile, RecallResult, RecallTrace, CUE_PREFIX_MAX_MATCHES, RECALL_FEEDBACK_PENALTY};
+use crate::projects::ProjectContext;
+use crate::vector::HybridQuery;
+use crate::fuzzy::FuzzyCorrection;
+use crate::query_rewrite::{rewrite_query, QueryRewrite};
+use crate::lexicon_quality::{prune_lexicon, LexiconPruneRequest};
+use crate::heatmap::{now_secs, HeatmapUpdateRequest, MAX_HEATMAP_WEIGHT};
+use crate::trending::{parse_window, DEFAULT_TRENDING_WINDOW_MINS};
+use crate::maintenance::{prune_memories, DecayRequest, PruneRequest};
+use crate::grouping::{group_results, parse_namespace, GroupScore, GROUP_CANDIDATE_FACTOR};
+use crate::aggregation::CountRequest;
+use crate::snippets::{snippets, SnippetTerms, DEFAULT_MAX_SNIPPETS, DEFAULT_SNIPPET_WINDOW};
+use crate::vocabulary::{AliasFile, ImportMode, LexiconFile};
+use crate::fusion::{fuse, FusionStrategy};
+use crate::payload_store::StorageBackend;
+use crate::config::{EmbeddingConfig, LlmConfig, RerankConfig};
+use crate::multilingual::TextLanguage;
 use axum::{
     extract::{Path, State},
     http::{StatusCode, HeaderMap},
     middleware,
     response::IntoResponse,
-    routing::{get, patch, post, delete},
+    routing::{get, patch, post, put, delete},
     Json, Router,
 };
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
+use tracing::Instrument;
 
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct AddMemoryRequest {
     pub content: String,
     pub cues: Vec<String>,
@@ -30,6 +54,23 @@ pub struct AddMemoryRequest {
     pub disable_temporal_chunking: bool,
     #[serde(default)]
     pub async_ingest: bool,
+    /// Client-supplied embedding for hybrid recall
+    #[serde(default)]
+    pub embedding: Option<Vec<f32>>,
+    /// Exempt the memory from pruning and consolidation
+    #[serde(default)]
+    pub pinned: bool,
+    /// Reinforce a recent near-duplicate instead of storing a copy (exact
+    /// duplicates are merged regardless, see `tuning.dedup_identical`)
+    #[serde(default)]
+    pub dedup: bool,
+    /// Append the memory to an episode opened with `POST /sessions`
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Temporal chunking only links memories sharing this key (defaults to the
+    /// caller's API key, or the whole project without one)
+    #[serde(default)]
+    pub chunking_key: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -52,6 +93,13 @@ pub struct RecallRequest {
     pub auto_reinforce: bool,
     #[serde(default)]
     pub projects: Option<Vec<String>>,
+    /// With `projects`: return one list ranked across projects, each result
+    /// tagged with its `project_id`, instead of a block per project
+    #[serde(default)]
+    pub merge: bool,
+    /// How `merge` makes scores of different projects comparable
+    #[serde(default)]
+    pub fusion: FusionStrategy,
     #[serde(default)]
     pub min_intersection: Option<usize>,
     #[serde(default)]
@@ -66,6 +114,468 @@ pub struct RecallRequest {
     pub disable_alias_expansion: bool,
     #[serde(default = "default_depth")]
     pub depth: usize,
+    /// Only return memories created at or after this unix timestamp (seconds)
+    #[serde(default)]
+    pub created_after: Option<f64>,
+    /// Only return memories created at or before this unix timestamp (seconds)
+    #[serde(default)]
+    pub created_before: Option<f64>,
+    #[serde(default)]
+    pub metadata_filters: Vec<MetadataFilter>,
+    /// Negative cues: memories carrying any of these are never returned
+    #[serde(default)]
+    pub exclude_cues: Vec<String>,
+    /// "full" adds a score trace for every candidate (implies `explain`)
+    #[serde(default)]
+    pub explain_level: ExplainLevel,
+    /// Hybrid mode: weight of cosine similarity (0-1) blended with the cue score
+    #[serde(default)]
+    pub hybrid_alpha: Option<f64>,
+    /// Query embedding for hybrid mode (defaults to embedding the query with the project's provider)
+    #[serde(default)]
+    pub query_vector: Option<Vec<f32>>,
+    /// Typo tolerance: query cues with no exact match also hit indexed cues within
+    /// `tuning.fuzzy_max_edit_distance` edits
+    #[serde(default)]
+    pub fuzzy: bool,
+    /// Prefix/wildcard cue patterns (`path:src/agent/*`, `error:*timeout*`), each
+    /// expanded to the matching indexed cues
+    #[serde(default)]
+    pub cue_prefix: Vec<String>,
+    /// Only return memories whose content contains these words in this order
+    #[serde(default)]
+    pub phrase: Option<String>,
+    /// Words allowed between consecutive `phrase` words (0 = verbatim)
+    #[serde(default)]
+    pub phrase_window: usize,
+    /// Append memories linked from the results (see `POST /memories/:id/links`)
+    #[serde(default)]
+    pub follow_links: bool,
+    /// Link types to follow (empty = all)
+    #[serde(default)]
+    pub link_types: Vec<String>,
+    /// Recall path: `fast`, `balanced` or `thorough` (defaults to the project's profile)
+    #[serde(default)]
+    pub profile: Option<RecallProfile>,
+    /// Entries scanned per query cue (overrides the adaptive budget)
+    #[serde(default)]
+    pub scan_limit: Option<usize>,
+    /// Rescore the top candidates with the project's reranker (defaults to the
+    /// project's rerank setting; single-project recall only)
+    #[serde(default)]
+    pub rerank: Option<bool>,
+    /// Expand acronyms and correct misspelled words of `query_text` before tokenizing it
+    #[serde(default = "default_true")]
+    pub rewrite_query: bool,
+    /// Group results by this cue namespace (e.g. `path`), returning the best
+    /// memory per value (single-project recall only)
+    #[serde(default)]
+    pub group_by: Option<String>,
+    /// Rank groups by their best score (`max`) or the sum of their scores (`sum`)
+    #[serde(default)]
+    pub group_score: GroupScore,
+    /// Add `snippets` metadata: content around the words matching the query cues
+    #[serde(default)]
+    pub snippets: bool,
+    /// Characters of context on each side of a match
+    #[serde(default = "default_snippet_window")]
+    pub snippet_window: usize,
+    /// Snippets per result
+    #[serde(default = "default_max_snippets")]
+    pub max_snippets: usize,
+}
+
+const DEFAULT_HYBRID_ALPHA: f64 = 0.5;
+
+/// Deepest hop count `/graph/neighborhood` will follow
+const GRAPH_NEIGHBORHOOD_MAX_DEPTH: usize = 4;
+
+/// Memories materialized per blocking task while an export streams
+const EXPORT_CHUNK_SIZE: usize = 256;
+
+/// Events `/recall/stream` buffers before scoring waits for the client
+const RECALL_STREAM_BUFFER: usize = 64;
+
+/// Same defaults as an empty JSON body
+impl Default for RecallRequest {
+    fn default() -> Self {
+        Self {
+            cues: Vec::new(),
+            query_text: None,
+            limit: default_limit(),
+            auto_reinforce: default_auto_reinforce(),
+            projects: None,
+            merge: false,
+            fusion: FusionStrategy::default(),
+            min_intersection: None,
+            explain: false,
+            disable_pattern_completion: false,
+            disable_salience_bias: false,
+            disable_systems_consolidation: false,
+            disable_alias_expansion: default_true(),
+            depth: default_depth(),
+            created_after: None,
+            created_before: None,
+            metadata_filters: Vec::new(),
+            exclude_cues: Vec::new(),
+            explain_level: ExplainLevel::default(),
+            hybrid_alpha: None,
+            query_vector: None,
+            fuzzy: false,
+            cue_prefix: Vec::new(),
+            phrase: None,
+            phrase_window: 0,
+            follow_links: false,
+            link_types: Vec::new(),
+            profile: None,
+            scan_limit: None,
+            rerank: None,
+            rewrite_query: default_true(),
+            group_by: None,
+            group_score: GroupScore::default(),
+            snippets: false,
+            snippet_window: default_snippet_window(),
+            max_snippets: default_max_snippets(),
+        }
+    }
+}
+
+impl RecallRequest {
+    fn recall_filter(&self) -> RecallFilter {
+        RecallFilter {
+            created_after: self.created_after,
+            created_before: self.created_before,
+            metadata_filters: self.metadata_filters.clone(),
+            exclude_cues: self.exclude_cues.clone(),
+            phrase: self.phrase.clone(),
+            phrase_window: self.phrase_window,
+            scan_limit: self.scan_limit,
+        }
+    }
+}
+
+/// Hybrid scoring for a recall request. Enabled by `hybrid_alpha` or `query_vector`;
+/// without a `query_vector` the query text (or cues) is embedded with the project's provider.
+async fn hybrid_query(
+    req: &RecallRequest,
+    mt_engine: &MultiTenantEngine,
+    project_id: &str,
+    ctx: &ProjectContext,
+) -> Result<Option<HybridQuery>, String> {
+    let alpha = match (req.hybrid_alpha, &req.query_vector) {
+        (Some(alpha), _) => alpha,
+        (None, Some(_)) => DEFAULT_HYBRID_ALPHA,
+        (None, None) => return Ok(None),
+    };
+    if !(0.0..=1.0).contains(&alpha) {
+        return Err("hybrid_alpha must be between 0 and 1".to_string());
+    }
+
+    let vector = match &req.query_vector {
+        Some(v) => v.clone(),
+        None => {
+            let provider = mt_engine
+                .embedding_provider(project_id)?
+                .ok_or("Hybrid recall needs a query_vector (no embedding provider configured for this project)")?;
+            let text = req.query_text.clone().unwrap_or_else(|| req.cues.join(" "));
+            provider
+                .embed(&[text])
+                .await?
+                .pop()
+                .filter(|v| !v.is_empty())
+                .ok_or("Could not embed the query")?
+        }
+    };
+    ctx.main.vector_index().validate(&vector)?;
+
+    Ok(Some(HybridQuery { vector, alpha }))
+}
+
+/// A reranker and how many candidates it rescores
+type RecallRerank = (Arc<dyn crate::rerank::Reranker>, usize);
+
+/// Reranker for a recall request and how many candidates it rescores:
+/// `rerank`, else the project's `enabled` setting. Asking for a rerank in a
+/// project without a strategy is an error.
+fn recall_reranker(req: &RecallRequest, mt_engine: &MultiTenantEngine, project_id: &str) -> Result<Option<RecallRerank>, String> {
+    let config = mt_engine.get_project_rerank(project_id);
+    if !req.rerank.unwrap_or(config.enabled) {
+        return Ok(None);
+    }
+    let reranker = mt_engine
+        .reranker(project_id)?
+        .ok_or("No rerank strategy configured for this project (see PUT /projects/:id/rerank)")?;
+    Ok(Some((reranker, config.top_n.max(1))))
+}
+
+/// Recall path for a request: its `profile`, else the project default. The
+/// `fast` and `balanced` paths don't apply filters, hybrid scoring, explain or
+/// `min_intersection`, so requests using any of them run `thorough`.
+pub(crate) fn recall_profile(req: &RecallRequest, ctx: &ProjectContext, filter: &RecallFilter, hybrid: Option<&HybridQuery>) -> RecallProfile {
+    let profile = req.profile.unwrap_or_else(|| ctx.recall_profile.read().map(|p| *p).unwrap_or_default());
+    let needs_scoring = req.explain || req.min_intersection.is_some() || hybrid.is_some() || !filter.is_empty();
+    if needs_scoring {
+        RecallProfile::Thorough
+    } else {
+        profile
+    }
+}
+
+/// One recall pass over `cues` on the path `profile` selects, with the score
+/// trace when the request asks for `explain_level: full`
+pub(crate) fn recall_pass(
+    ctx: &ProjectContext,
+    req: &RecallRequest,
+    profile: RecallProfile,
+    cues: &[(String, f64)],
+    limit: usize,
+    filter: &RecallFilter,
+    hybrid: Option<&HybridQuery>,
+) -> (Vec<RecallResult>, Option<RecallTrace>) {
+    let _span = tracing::debug_span!("recall_pass", profile = ?profile, cues = cues.len(), limit).entered();
+    ctx.main.record_cue_hits(cues);
+    match profile {
+        RecallProfile::Fast => (ctx.main.recall_fast(cues.iter().map(|(c, _)| c.clone()).collect(), limit), None),
+        RecallProfile::Balanced => (ctx.main.recall_intersection(cues.to_vec(), limit), None),
+        RecallProfile::Thorough => {
+            let heatmap = ctx.market_heatmap.read().ok();
+            let params = RecallParams {
+                auto_reinforce: false,
+                min_intersection: req.min_intersection,
+                explain: req.explain,
+                disable_pattern_completion: req.disable_pattern_completion,
+                disable_salience_bias: req.disable_salience_bias,
+                disable_systems_consolidation: req.disable_systems_consolidation,
+                heatmap: heatmap.as_ref().map(|h| h.weights()),
+                filter: Some(filter),
+                hybrid,
+            };
+
+            if req.explain_level == ExplainLevel::Full {
+                let (results, trace) = ctx.main.recall_weighted_traced(cues.to_vec(), limit, &params);
+                (results, Some(trace))
+            } else {
+                let results = ctx.main.recall_weighted(cues.to_vec(), limit, &params);
+                (results, None)
+            }
+        }
+    }
+}
+
+/// Query cues of a recall request, from its cues and text to the weighted
+/// cues recall runs with
+pub(crate) struct PreparedRecall {
+    /// Cues given and resolved from the text, before normalization
+    pub(crate) query_cues: Vec<String>,
+    /// `query_cues` normalized, before expansion
+    pub(crate) normalized_cues: Vec<String>,
+    pub(crate) expanded_cues: Vec<(String, f64)>,
+    /// Lexicon entries that resolved the text
+    pub(crate) lexicon_memory_ids: Vec<String>,
+    pub(crate) fuzzy_corrections: Vec<FuzzyCorrection>,
+    pub(crate) prefix_matches: PrefixMatches,
+}
+
+/// Tokenize, normalize and expand a request's query (its `query_text` is
+/// expected to be rewritten already), timing each stage on `timer`
+pub(crate) fn prepare_recall(req: &RecallRequest, ctx: &ProjectContext, timer: &mut StageTimer) -> PreparedRecall {
+    // Collect cues
+    let mut query_cues = req.cues.clone();
+    let mut lexicon_memory_ids: Vec<String> = Vec::new();
+    let mut tokens_from_text = Vec::new();
+    if let Some(ref text) = req.query_text {
+         let _span = tracing::debug_span!("tokenize").entered();
+         // 1. Lexicon Recall
+         let (resolved, lex_mids, tokens) = ctx.resolve_cues_from_text(text, false);
+         query_cues.extend(resolved);
+         lexicon_memory_ids = lex_mids;
+
+         // 2. Raw Token Fallback
+         tokens_from_text = tokens;
+         for token in &tokens_from_text {
+             if !query_cues.contains(token) {
+                 query_cues.push(token.clone());
+             }
+         }
+    }
+    timer.lap("tokenize");
+
+    // Normalize query cues
+    let normalized_cues: Vec<String> = tracing::debug_span!("normalize", cues = query_cues.len())
+        .in_scope(|| query_cues.iter().map(|cue| ctx.normalize_cue(cue)).collect());
+    timer.lap("normalize");
+
+    // Expand aliases
+    let (expanded_cues, fuzzy_corrections, prefix_matches) = tracing::debug_span!("expand").in_scope(|| {
+        let expanded_cues = if req.disable_alias_expansion {
+            normalized_cues.iter().map(|c| (c.clone(), 1.0)).collect()
+        } else {
+            let original_tokens = if req.query_text.is_some() {
+                tokens_from_text // Reuse tokens computed earlier
+            } else {
+                req.cues.clone()
+            };
+            ctx.expand_query_cues(normalized_cues.clone(), &original_tokens)
+        };
+        let (expanded_cues, fuzzy_corrections) = apply_fuzzy(req, ctx, expanded_cues);
+        let (expanded_cues, prefix_matches) = apply_cue_prefix(req, ctx, expanded_cues);
+        (expanded_cues, fuzzy_corrections, prefix_matches)
+    });
+    timer.lap("expand");
+
+    PreparedRecall { query_cues, normalized_cues, expanded_cues, lexicon_memory_ids, fuzzy_corrections, prefix_matches }
+}
+
+/// The request's hops (`depth`), each a `recall_pass` for a shrinking share of
+/// `limit`. After a hop, the best result not yet used as a pivot adds its cues
+/// to `expanded_cues` at half the previous hop's weight. `on_hop` gets each
+/// hop's new results (tagged with their `hop`) and returns false to stop early.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn recall_hops(
+    ctx: &ProjectContext,
+    req: &RecallRequest,
+    profile: RecallProfile,
+    expanded_cues: &mut Vec<(String, f64)>,
+    limit: usize,
+    filter: &RecallFilter,
+    hybrid: Option<&HybridQuery>,
+    traces: &mut Vec<serde_json::Value>,
+    mut on_hop: impl FnMut(&[RecallResult]) -> bool,
+) -> Vec<RecallResult> {
+    let mut all_results: Vec<RecallResult> = Vec::new();
+    let mut used_pivot_memory_ids = std::collections::HashSet::new();
+    let depth = req.depth.max(1);
+
+    for hop in 1..=depth {
+        let current_limit = (limit as f64 / hop as f64).ceil() as usize;
+        
+        let (results, trace) = recall_pass(ctx, req, profile, expanded_cues, current_limit, filter, hybrid);
+        if let Some(trace) = trace {
+            traces.push(serde_json::json!({"hop": hop, "trace": trace}));
+        }
+        
+        // Merge results, avoiding duplicates, with hop metadata
+        let first_new = all_results.len();
+        for mut r in results {
+            if all_results.iter().any(|existing| existing.memory_id == r.memory_id) {
+                continue;
+            }
+            r.metadata.entry("hop".to_string()).or_insert_with(|| serde_json::json!(hop));
+            all_results.push(r);
+        }
+        if !on_hop(&all_results[first_new..]) || hop == depth {
+            break;
+        }
+        
+        let mut pivot_memory = None;
+        for r in &all_results {
+            if !used_pivot_memory_ids.contains(&r.memory_id) {
+                pivot_memory = ctx.main.get_memory(&r.memory_id);
+                if pivot_memory.is_some() {
+                    used_pivot_memory_ids.insert(r.memory_id.clone());
+                    break;
+                }
+            }
+        }
+        
+        let Some(mem) = pivot_memory else {
+            break;
+        };
+        let existing_cues: std::collections::HashSet<String> = expanded_cues.iter().map(|(c, _)| c.clone()).collect();
+        for cue in mem.cues {
+            if !existing_cues.contains(&cue) {
+                expanded_cues.push((cue, 0.5f64.powi(hop as i32)));
+            }
+        }
+    }
+    all_results
+}
+
+/// `explain` block of a recall response
+fn recall_explain(
+    req: &RecallRequest,
+    ctx: &ProjectContext,
+    recall: &PreparedRecall,
+    limit: usize,
+    traces: Vec<serde_json::Value>,
+    rewrite: Option<&QueryRewrite>,
+) -> serde_json::Value {
+    let mut explain = serde_json::json!({
+        "query_cues": recall.query_cues,
+        "expanded_cues": recall.expanded_cues,
+        "scan_budget": ctx.main.scan_budgets(&recall.expanded_cues, limit, req.scan_limit)
+    });
+    if req.explain_level == ExplainLevel::Full {
+        explain["trace"] = serde_json::json!(traces);
+    }
+    if req.fuzzy {
+        explain["fuzzy_corrections"] = serde_json::json!(recall.fuzzy_corrections);
+    }
+    if !req.cue_prefix.is_empty() {
+        explain["prefix_matches"] = serde_json::json!(recall.prefix_matches);
+    }
+    if let Some(rewrite) = rewrite {
+        explain["query_rewrite"] = serde_json::json!(rewrite);
+    }
+    explain
+}
+
+/// Typo-tolerant expansion of the query cues when the request asks for it
+fn apply_fuzzy(req: &RecallRequest, ctx: &ProjectContext, cues: Vec<(String, f64)>) -> (Vec<(String, f64)>, Vec<FuzzyCorrection>) {
+    if req.fuzzy {
+        ctx.main.expand_fuzzy(cues, ctx.tuning.fuzzy_max_edit_distance)
+    } else {
+        (cues, Vec::new())
+    }
+}
+
+/// Attach the requested snippets to each result's metadata
+fn add_snippets(req: &RecallRequest, ctx: &ProjectContext, expanded_cues: &[(String, f64)], results: &mut [RecallResult]) {
+    if !req.snippets {
+        return;
+    }
+    let terms = SnippetTerms::from_cues(expanded_cues.iter().map(|(c, _)| c.as_str()), ctx.text_language());
+    for result in results {
+        let found = snippets(&result.content, &terms, req.snippet_window, req.max_snippets);
+        result.metadata.insert("snippets".to_string(), serde_json::json!(found));
+    }
+}
+
+/// `query_text` with acronyms expanded and misspellings corrected (None when
+/// disabled or nothing changed)
+pub(crate) fn rewrite_recall_query(req: &RecallRequest, ctx: &ProjectContext) -> Option<QueryRewrite> {
+    let text = req.query_text.as_ref().filter(|_| req.rewrite_query)?;
+    let rewrite = rewrite_query(ctx, text);
+    rewrite.changed().then_some(rewrite)
+}
+
+/// Cues matched by each `cue_prefix` pattern, keyed by pattern
+type PrefixMatches = HashMap<String, Vec<String>>;
+
+/// Cues matched by each `cue_prefix` pattern, added at full weight
+fn apply_cue_prefix(req: &RecallRequest, ctx: &ProjectContext, mut cues: Vec<(String, f64)>) -> (Vec<(String, f64)>, PrefixMatches) {
+    let mut matches = HashMap::new();
+    for pattern in &req.cue_prefix {
+        let matched = ctx.main.match_cue_pattern(pattern, CUE_PREFIX_MAX_MATCHES);
+        for cue in &matched {
+            if !cues.iter().any(|(c, _)| c == cue) {
+                cues.push((cue.clone(), 1.0));
+            }
+        }
+        matches.insert(pattern.clone(), matched);
+    }
+    (cues, matches)
+}
+
+/// Exclusion cues go through the project's normalization, same as query cues
+pub(crate) fn normalize_exclusions(filter: &RecallFilter, ctx: &ProjectContext) -> RecallFilter {
+    let mut filter = filter.clone();
+    filter.exclude_cues = filter
+        .exclude_cues
+        .iter()
+        .map(|cue| ctx.normalize_cue(cue))
+        .collect();
+    filter
 }
 
 fn default_depth() -> usize {
@@ -77,6 +587,9 @@ pub struct RecallGroundedRequest {
     pub query_text: String,
     #[serde(default = "default_token_budget")]
     pub token_budget: u32,
+    /// Model the context is for; picks the token counter (e.g. `gpt-4o`)
+    #[serde(default)]
+    pub model: Option<String>,
     #[serde(default = "default_limit")]
     pub limit: usize,
     #[serde(default)]
@@ -106,6 +619,9 @@ fn default_token_budget() -> u32 {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RecallGroundedResponse {
     pub verified_context: String,
+    /// Same as `proof.trace_id`; resolves citations and accepts feedback
+    #[serde(default)]
+    pub recall_id: String,
     pub proof: crate::grounding::GroundingProof,
     pub engine_latency_ms: f64,
     pub signature: String,
@@ -119,11 +635,33 @@ fn default_limit() -> usize {
     10
 }
 
+fn default_snippet_window() -> usize {
+    DEFAULT_SNIPPET_WINDOW
+}
+
+fn default_max_snippets() -> usize {
+    DEFAULT_MAX_SNIPPETS
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ReinforceRequest {
     pub cues: Vec<String>,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DemoteRequest {
+    /// Dynamic salience to remove
+    #[serde(default = "default_demote_amount")]
+    pub amount: f64,
+    /// Cues to detach from the memory
+    #[serde(default)]
+    pub detach_cues: Vec<String>,
+}
+
+fn default_demote_amount() -> f64 {
+    0.5
+}
+
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct AddAliasRequest {
@@ -137,7 +675,14 @@ pub struct GetAliasRequest {
     pub cue: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ApproveAliasRequest {
+    /// Weight of the alias target in expanded recalls; defaults to the proposal's score
+    #[serde(default)]
+    pub downweight: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct MergeAliasRequest {
     pub cues: Vec<String>,
     pub to: String,
@@ -186,11 +731,39 @@ pub struct IngestUrlRequest {
     /// Only follow links within the same domain (default: true)
     #[serde(default = "default_true")]
     pub same_domain_only: bool,
+    /// Treat `url` as a sitemap or RSS/Atom feed (default: detect from the URL)
+    #[serde(default)]
+    pub feed: Option<bool>,
+    /// Re-read the feed every this many seconds (0 stops scheduled refreshes)
+    #[serde(default)]
+    pub refresh_interval_secs: Option<u64>,
+    /// Overrides of the project's chunk size limits
+    #[serde(default)]
+    pub chunking: ChunkingConfig,
+    /// Also store the facts the project's LLM finds in each chunk as memories
+    #[serde(default)]
+    pub extract_facts: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CreateProjectRequest {
     pub project_id: String,
+    /// `disk` keeps memory payloads in an on-disk log (fixed at creation)
+    #[serde(default)]
+    pub storage: StorageBackend,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RestoreGenerationRequest {
+    /// A `generation` from `GET /projects/:id/snapshots`
+    pub generation: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetSnapshotEncryptionRequest {
+    /// true = always encrypt, false = never encrypt, null = follow server keyring
+    #[serde(default)]
+    pub enabled: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -265,30 +838,101 @@ pub fn routes(
 ) -> Router {
     let mut router = Router::new()
         .route("/", get(root))
+        .route("/openapi.json", get(openapi_spec))
         .route("/memories", post(add_memory))
+        .route("/memories/search/export", post(export_matching_memories))
+        .route("/memories/analytics", get(memory_analytics))
         .route("/recall", post(recall))
+        .route("/recall/stream", post(recall_stream))
+        .route("/recall/count", post(recall_count))
+        .route("/recall/:recall_id/feedback", post(recall_feedback))
+        .route("/recall/:recall_id/citations/:citation", get(resolve_citation))
         .route("/recall/web", post(recall_web))
+        .route("/answer", post(answer))
         .route("/memories/:id/reinforce", patch(reinforce_memory))
+        .route("/memories/:id/demote", patch(demote_memory))
         .route("/memories/:id", get(get_memory).delete(delete_memory))
+        .route("/memories/:id/embedding", put(set_memory_embedding))
+        .route("/memories/:id/pin", put(set_memory_pinned))
+        .route("/memories/:id/history", get(memory_history))
+        .route("/memories/:id/rollback", post(rollback_memory))
+        .route("/memories/:id/links", get(get_memory_links).post(add_memory_link).delete(delete_memory_link))
+        .route("/memories/:id/embed", post(embed_memory))
+        .route("/sessions", get(list_sessions).post(create_session))
+        .route("/sessions/:id/timeline", get(session_timeline))
         .route("/stats", get(get_stats))
+        .route("/stats/memory-breakdown", get(memory_breakdown))
+        .route("/cues/analytics", get(cue_analytics))
+        .route("/maintenance/compact", post(compact_indexes))
+        .route("/maintenance/decay", post(maintenance_decay))
+        .route("/maintenance/prune", post(maintenance_prune))
         .route("/projects", get(list_projects).post(create_project))
         .route("/recall/grounded", post(recall_grounded))
         .route("/projects/:id", delete(delete_project))
+        .route("/projects/:id/unload", post(unload_project))
+        .route("/projects/:id/snapshots", get(list_project_generations))
+        .route("/projects/:id/restore", post(restore_project_generation))
         .route("/projects/:id/watch-dir", post(set_project_watch_dir))
+        .route("/agent/config", get(get_agent_config).put(set_agent_config))
+        .route("/projects/:id/snapshot-encryption", post(set_project_snapshot_encryption))
+        .route("/projects/:id/schedule", get(get_project_schedule).put(set_project_schedule))
+        .route("/projects/:id/quota", get(get_project_quota).put(set_project_quota))
+        .route("/projects/:id/embeddings", get(get_project_embeddings).put(set_project_embeddings))
+        .route("/projects/:id/llm", get(get_project_llm).put(set_project_llm))
+        .route("/projects/:id/rerank", get(get_project_rerank).put(set_project_rerank))
+        .route("/projects/:id/taxonomy", get(get_project_taxonomy).put(set_project_taxonomy))
+        .route("/projects/:id/taxonomy/validate", post(validate_project_cues))
+        .route("/projects/:id/normalization", get(get_project_normalization).put(set_project_normalization))
+        .route("/projects/:id/acronyms", get(get_project_acronyms).post(add_project_acronym))
+        .route("/projects/:id/acronyms/:acronym", delete(remove_project_acronym))
+        .route("/projects/:id/language", get(get_project_language).put(set_project_language))
+        .route("/projects/:id/recall-profile", get(get_project_recall_profile).put(set_project_recall_profile))
+        .route("/projects/:id/chunking", get(get_project_chunking).put(set_project_chunking))
+        .route("/projects/:id/embed", post(embed_project))
+        .route("/projects/:id/export", get(export_project))
+        .route("/projects/:id/import", post(import_project))
+        .route("/projects/:id/archive", get(dump_project).post(restore_project))
         .route("/aliases", post(add_alias).get(get_aliases))
         .route("/aliases/merge", post(merge_aliases))
+        .route("/aliases/proposed", get(get_proposed_aliases))
+        .route("/aliases/export", get(export_aliases_yaml).put(import_aliases_yaml))
+        .route("/aliases/:id/approve", post(approve_alias))
+        .route("/aliases/:id/reject", post(reject_alias))
         .route("/graph", get(get_graph))
+        .route("/graph/neighborhood", get(get_graph_neighborhood))
+        .route("/ws", get(live_updates))
         .route("/lexicon/inspect/:cue", get(lexicon_inspect))
         .route("/lexicon/entry/:id", delete(lexicon_delete))
         .route("/lexicon/graph", get(lexicon_graph))
         .route("/lexicon/wire", post(lexicon_wire))
         .route("/lexicon/synonyms/:cue", get(lexicon_synonyms))
+        .route("/lexicon/prune", post(lexicon_prune))
+        .route("/heatmap", get(get_heatmap).put(set_heatmap))
+        .route("/trending", get(get_trending))
+        .route("/lexicon/export", get(export_lexicon_yaml).put(import_lexicon_yaml))
+        .route("/org/lexicon", get(get_org_lexicon))
+        .route("/org/lexicon/wire", post(org_lexicon_wire))
+        .route("/org/lexicon/entry/:id", delete(org_lexicon_delete))
+        .route("/org/lexicon/export", get(export_org_lexicon_yaml).put(import_org_lexicon_yaml))
         .route("/ingest/url", post(ingest_url))
         .route("/ingest/content", post(ingest_content))
         .route("/ingest/file", post(ingest_file))
+        .route("/ingest/preview", post(ingest_preview))
+        .route("/ingest/sessions", post(begin_ingest_session))
+        .route("/ingest/sessions/:id/commit", post(commit_ingest_session))
+        .route("/ingest/sessions/:id/abort", post(abort_ingest_session))
+        .route("/ingest/feeds", get(list_feeds).delete(delete_feed))
+        .route("/connectors", get(list_connectors))
+        .route("/connectors/:id", put(set_connector).delete(delete_connector))
+        .route("/connectors/:id/sync", post(sync_connector))
         .route("/jobs/status", get(jobs_status))
+        .route("/jobs/failed", get(failed_jobs))
+        .route("/jobs/:id/retry", post(retry_job))
+        .route("/jobs/:id", delete(cancel_job))
+        .route("/audit", get(audit_log))
         .route("/context/expand", post(context_expand))
         .route("/metrics", get(prometheus_metrics))
+        .route("/debug/slow-queries", get(slow_queries))
         // Cloud backup endpoints
         .route("/backup/upload", post(backup_upload))
         .route("/backup/download", post(backup_download))
@@ -323,6 +967,10 @@ async fn root() -> impl IntoResponse {
     }))
 }
 
+async fn openapi_spec() -> Json<serde_json::Value> {
+    Json(crate::openapi::spec())
+}
+
 async fn get_graph(
     State(state): State<EngineState>,
     headers: HeaderMap,
@@ -331,26 +979,13 @@ async fn get_graph(
     let limit = params.get("limit")
         .and_then(|v| v.parse().ok())
         .unwrap_or(100);
+    let cursor = params.get("cursor").cloned();
 
     let EngineState { mt_engine, .. } = state;
     
-    let project_id = match extract_project_id(&headers) {
+    let project_id = match graph_project_id(&headers, &params) {
         Ok(id) => id,
-        Err(_) => {
-            // Fallback: try to get from query param "project"
-            if let Some(p) = params.get("project") {
-                if validate_project_id(p) {
-                    p.to_string()
-                } else {
-                    return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format in query param"})));
-                }
-            } else {
-                 return (
-                    StatusCode::BAD_REQUEST, 
-                    Json(serde_json::json!({"error": "Missing X-Project-ID header or project query param"}))
-                );
-            }
-        }
+        Err(e) => return e,
     };
 
     let ctx = match mt_engine.get_or_create_project(project_id.clone()) {
@@ -361,11 +996,81 @@ async fn get_graph(
     let limit_clone = limit;
     let ctx_clone = ctx.clone();
     
+    let page = tokio::task::spawn_blocking(move || {
+        ctx_clone.main.get_graph_page(limit_clone, cursor.as_deref())
+    }).await.unwrap();
+
+    match page {
+        Ok((mut graph, next_cursor)) => {
+            graph["next_cursor"] = serde_json::json!(next_cursor);
+            (StatusCode::OK, Json(graph))
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+/// Local subgraph around one node: `?node=cue:kubernetes&depth=2&limit=200`
+/// (`node` may also be a memory id)
+async fn get_graph_neighborhood(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let Some(node) = params.get("node").cloned() else {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Missing node query param"})));
+    };
+    let depth = params.get("depth")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1usize)
+        .min(GRAPH_NEIGHBORHOOD_MAX_DEPTH);
+    let limit = params.get("limit")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(100);
+
+    let project_id = match graph_project_id(&headers, &params) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let EngineState { mt_engine, .. } = state;
+    let ctx = match mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+
     let graph = tokio::task::spawn_blocking(move || {
-        ctx_clone.main.get_graph_data(limit_clone)
+        ctx.main.graph_neighborhood(&node, depth, limit)
     }).await.unwrap();
 
-    (StatusCode::OK, Json(graph))
+    match graph {
+        Some(graph) => (StatusCode::OK, Json(graph)),
+        None => (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Node not found"}))),
+    }
+}
+
+/// Graph endpoints accept the project from X-Project-ID or a `project` query param
+fn graph_project_id(
+    headers: &HeaderMap,
+    params: &HashMap<String, String>,
+) -> Result<String, (StatusCode, Json<serde_json::Value>)> {
+    match extract_project_id(headers) {
+        Ok(id) => Ok(id),
+        Err(_) => {
+            // Fallback: try to get from query param "project"
+            if let Some(p) = params.get("project") {
+                if validate_project_id(p) {
+                    Ok(p.to_string())
+                } else {
+                    Err((StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format in query param"}))))
+                }
+            } else {
+                Err((
+                    StatusCode::BAD_REQUEST, 
+                    Json(serde_json::json!({"error": "Missing X-Project-ID header or project query param"}))
+                ))
+            }
+        }
+    }
 }
 
 // Handlers
@@ -390,6 +1095,54 @@ fn extract_project_id(headers: &HeaderMap) -> Result<String, (StatusCode, Json<s
     Ok(project_id.to_string())
 }
 
+/// Audit actor for the caller (fingerprint of the X-API-Key header)
+fn audit_actor(headers: &HeaderMap) -> String {
+    actor_for_key(headers.get("X-API-Key").and_then(|v| v.to_str().ok()))
+}
+
+/// Group that temporal chunking links a new memory within: the explicit key,
+/// else the caller's API key (hashed, as in the audit log), else none
+fn temporal_chunking_key(headers: &HeaderMap, explicit: Option<&str>) -> Option<String> {
+    match explicit.map(str::trim).filter(|k| !k.is_empty()) {
+        Some(key) => Some(key.to_string()),
+        None if headers.contains_key("X-API-Key") => Some(audit_actor(headers)),
+        None => None,
+    }
+}
+
+fn audit_memories_created(mt_engine: &MultiTenantEngine, headers: &HeaderMap, project_id: &str, memory_ids: &[String]) {
+    let actor = audit_actor(headers);
+    for memory_id in memory_ids {
+        mt_engine.audit().record(
+            AuditEntry::new(actor.clone(), AuditAction::MemoryCreated, project_id).with_memory(memory_id),
+        );
+    }
+}
+
+/// 413 response carrying the limit that was hit
+fn quota_exceeded_response(e: QuotaExceeded) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::PAYLOAD_TOO_LARGE,
+        Json(serde_json::json!({
+            "error": e.to_string(),
+            "quota": e
+        })),
+    )
+}
+
+/// The project's chunk size limits with the request's overrides
+fn request_chunking(
+    mt_engine: &MultiTenantEngine,
+    project_id: &str,
+    overrides: &ChunkingConfig,
+) -> Result<ChunkingConfig, (StatusCode, Json<serde_json::Value>)> {
+    let chunking = mt_engine.get_project_chunking(project_id).unwrap_or_default().merged(overrides);
+    chunking
+        .validate()
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))))?;
+    Ok(chunking)
+}
+
 fn extract_project_id_optional(headers: &HeaderMap) -> Option<String> {
     headers
         .get("X-Project-ID")
@@ -426,40 +1179,136 @@ async fn add_memory(
         Ok(c) => c,
         Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
     };
+
+    if let Err(e) = mt_engine.check_quota(&project_id, 1, req.content.len() as u64) {
+        return quota_exceeded_response(e);
+    }
+
+    if let Some(vector) = &req.embedding {
+        if let Err(e) = ctx.main.vector_index().validate(vector) {
+            return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e})));
+        }
+    }
+
+    if let Some(session_id) = &req.session_id {
+        if !ctx.sessions.contains_key(session_id) {
+            return (StatusCode::NOT_FOUND, Json(serde_json::json!({
+                "error": "Session not found",
+                "session_id": session_id
+            })));
+        }
+    }
     
     // 1. Cue Preparation Strategy
     // If cues are empty, bootstrap from content
     let mut initial_cues = req.cues;
     if initial_cues.is_empty() {
          // Bootstrap from content tokens
-         let tokens = crate::nl::tokenize_to_cues(&req.content);
+         let tokens = ctx.tokenize(&req.content);
          initial_cues.extend(tokens);
     }
     
     // 2. Normalize cues
     let mut normalized_cues = Vec::new();
     for cue in initial_cues {
-        let (normalized, _) = normalize_cue(&cue, &ctx.normalization);
+        let normalized = ctx.normalize_cue(&cue);
         normalized_cues.push(normalized);
     }
     
     // 3. Validate cues
-    let report = validate_cues(normalized_cues, &ctx.taxonomy);
+    let report = ctx.validate_cues(normalized_cues);
     let _accepted_count = report.accepted.len();
     
-    let memory_id = ctx.main.add_memory(
-        req.content.clone(), 
-        report.accepted.clone(), 
-        req.metadata, 
-        MainStats::default(),
-        req.disable_temporal_chunking
-    );
-
-    // Buffer background jobs (will be processed after ingestion completes)
-    let session = job_queue.session_manager.get_or_create(&project_id);
-    session.expect_write();
+    let mut metadata = req.metadata;
+    if req.pinned {
+        metadata.get_or_insert_with(HashMap::new)
+            .insert(PINNED_METADATA_KEY.to_string(), serde_json::json!(true));
+    }
     
-    job_queue.buffer(&project_id, Job::ProposeCues {
+    // Byte-identical content merges into the memory already holding it (session
+    // turns may repeat unless `dedup` is set); `dedup` also catches near duplicates
+    let identical = (req.dedup || (ctx.tuning.dedup_identical && req.session_id.is_none()))
+        .then(|| ctx.main.find_identical(&req.content))
+        .flatten();
+    if let Some(existing_id) = identical {
+        ctx.main.attach_cues(&existing_id, report.accepted.clone());
+        ctx.main.reinforce_memory(&existing_id, report.accepted.clone());
+        metrics.record_ingestion();
+        return (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "id": existing_id,
+                "status": "duplicate",
+                "similarity": 1.0,
+                "cues": report.accepted,
+                "latency_ms": start.elapsed().as_secs_f64() * 1000.0,
+            })),
+        );
+    }
+
+    if req.dedup {
+        if let Some((existing_id, similarity)) = ctx.main.find_near_duplicate(&req.content, &report.accepted) {
+            ctx.main.reinforce_memory(&existing_id, report.accepted.clone());
+            metrics.record_ingestion();
+            return (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "id": existing_id,
+                    "status": "duplicate",
+                    "similarity": similarity,
+                    "cues": report.accepted,
+                    "latency_ms": start.elapsed().as_secs_f64() * 1000.0,
+                })),
+            );
+        }
+    }
+    
+    let chunking_key = match &req.session_id {
+        Some(session_id) => Some(format!("session:{}", session_id)),
+        None => temporal_chunking_key(&headers, req.chunking_key.as_deref()),
+    };
+    let memory_id = match &req.session_id {
+        Some(session_id) => ctx.main.add_memory_to_session(
+            session_id,
+            req.content.clone(),
+            report.accepted.clone(),
+            metadata,
+            MainStats::default(),
+        ),
+        None => ctx.main.add_memory_in_group(
+            req.content.clone(), 
+            report.accepted.clone(), 
+            metadata, 
+            MainStats::default(),
+            req.disable_temporal_chunking,
+            chunking_key.as_deref(),
+        ),
+    };
+    match req.embedding {
+        Some(vector) => {
+            if let Err(e) = ctx.main.set_embedding(&memory_id, vector) {
+                tracing::warn!("Failed to index embedding for {}: {}", memory_id, e);
+            }
+        }
+        None => {
+            if let Ok(Some(_)) = mt_engine.embedding_provider(&project_id) {
+                job_queue.enqueue(Job::EmbedMemories {
+                    project_id: project_id.clone(),
+                    memory_ids: vec![memory_id.clone()],
+                    force: false,
+                }).await;
+            }
+        }
+    }
+    mt_engine.audit().record(
+        AuditEntry::new(audit_actor(&headers), AuditAction::MemoryCreated, &project_id).with_memory(&memory_id),
+    );
+
+    // Buffer background jobs (will be processed after ingestion completes)
+    let session = job_queue.session_manager.get_or_create(&project_id);
+    session.expect_write();
+    
+    job_queue.buffer(&project_id, Job::ProposeCues {
         project_id: project_id.clone(),
         memory_id: memory_id.clone(),
         content: req.content.clone(),
@@ -491,6 +1340,7 @@ async fn add_memory(
             "status": "stored",
             "cues": report.accepted,
             "rejected_cues": report.rejected,
+            "chunking_key": chunking_key,
             "latency_ms": latency_ms
         })),
     )
@@ -500,29 +1350,48 @@ async fn add_memory(
 async fn recall(
     State(state): State<EngineState>,
     headers: HeaderMap,
-    Json(req): Json<RecallRequest>,
+    Json(mut req): Json<RecallRequest>,
 ) -> (StatusCode, Json<serde_json::Value>) {
     use std::time::Instant;
     let start = Instant::now();
     let EngineState { ref mt_engine, ref job_queue, .. } = &state;
+    if req.explain_level == ExplainLevel::Full {
+        req.explain = true;
+    }
+    let filter = req.recall_filter();
     
     // --- Path 1: Cross-domain query ---
-    if let Some(projects) = req.projects {
+    if let Some(projects) = req.projects.take() {
         let start = Instant::now();
+        let mut timer = StageTimer::new();
+
+        // Query embeddings are computed up front (providers are async)
+        let mut hybrids = HashMap::new();
+        for project_id in &projects {
+            if let Ok(ctx) = mt_engine.get_or_create_project(project_id.clone()) {
+                hybrids.insert(project_id.clone(), hybrid_query(&req, mt_engine, project_id, &ctx).await);
+            }
+        }
+        timer.lap("embed");
         
-        // Query all projects in parallel using rayon
+        // Query all projects in parallel using rayon; rayon threads don't inherit the request span
+        let request_span = tracing::Span::current();
         let (all_results, reinforce_tasks): (Vec<serde_json::Value>, Vec<Option<(String, Vec<String>, Vec<String>)>>) = projects
             .par_iter()
             .map(|project_id| {
+                let _span = tracing::debug_span!(parent: &request_span, "project_recall", project_id = %project_id).entered();
                 let ctx = match mt_engine.get_or_create_project(project_id.clone()) {
                     Ok(c) => c,
-                    Err(_) => return (serde_json::json!({"project_id": project_id, "error": "Capacity reached"}), None),
+                    Err(e) => return (serde_json::json!({"project_id": project_id, "error": e}), None),
                 };
                 
                 // Collect cues
                 let mut cues_to_process = req.cues.clone();
+                let rewrite = rewrite_recall_query(&req, &ctx);
+                let query_text = rewrite.as_ref().map(|r| r.rewritten.clone()).or_else(|| req.query_text.clone());
                 
-                let (original_tokens, _lexicon_mids) = if let Some(text) = &req.query_text {
+                let (original_tokens, _lexicon_mids) = if let Some(text) = &query_text {
+                     let _span = tracing::debug_span!("tokenize").entered();
                      let (resolved, lex_mids, tokens) = ctx.resolve_cues_from_text(text, false);
                      cues_to_process.extend(resolved);
                      (tokens, lex_mids)
@@ -531,84 +1400,40 @@ async fn recall(
                 };
                 
                 // Normalize query cues
+                let normalize_span = tracing::debug_span!("normalize", cues = cues_to_process.len()).entered();
                 let mut normalized_cues = Vec::new();
                 for cue in &cues_to_process {
-                    let (normalized, _) = normalize_cue(cue, &ctx.normalization);
+                    let normalized = ctx.normalize_cue(cue);
                     normalized_cues.push(normalized);
                 }
+                drop(normalize_span);
+                let filter = normalize_exclusions(&filter, &ctx);
+                let hybrid = match hybrids.get(project_id).cloned().unwrap_or(Ok(None)) {
+                    Ok(h) => h,
+                    Err(e) => return (serde_json::json!({"project_id": project_id, "error": e}), None),
+                };
                 
                 // Expand aliases
-                let mut expanded_cues = if req.disable_alias_expansion {
+                let expand_span = tracing::debug_span!("expand").entered();
+                let expanded_cues = if req.disable_alias_expansion {
                     normalized_cues.into_iter().map(|c| (c, 1.0)).collect()
                 } else {
                     ctx.expand_query_cues(normalized_cues, &original_tokens)
                 };
+                let (expanded_cues, fuzzy_corrections) = apply_fuzzy(&req, &ctx, expanded_cues);
+                let (mut expanded_cues, prefix_matches) = apply_cue_prefix(&req, &ctx, expanded_cues);
+                drop(expand_span);
+                let profile = recall_profile(&req, &ctx, &filter, hybrid.as_ref());
                 
-                let mut all_results: Vec<crate::engine::RecallResult> = Vec::new();
-                let mut used_pivot_memory_ids = std::collections::HashSet::new();
                 let limit = req.limit.max(1);
-                let depth = req.depth.max(1);
+                let mut traces: Vec<serde_json::Value> = Vec::new();
+                let mut all_results = recall_hops(&ctx, &req, profile, &mut expanded_cues, limit, &filter, hybrid.as_ref(), &mut traces, |_| true);
                 
-                for hop in 1..=depth {
-                    let current_limit = (limit as f64 / hop as f64).ceil() as usize;
-                    
-                    let mut results = {
-                        let heatmap = ctx.market_heatmap.read().ok();
-                        let heatmap_ref = heatmap.as_deref();
-
-                        ctx.main.recall_weighted(
-                            expanded_cues.clone(), 
-                            current_limit, 
-                            false,
-                            req.min_intersection,
-                            req.explain,
-                            req.disable_pattern_completion,
-                            req.disable_salience_bias,
-                            req.disable_systems_consolidation,
-                            heatmap_ref
-                        )
-                    };
-                    
-                    // Add hop metadata
-                    for r in &mut results {
-                        if !r.metadata.contains_key("hop") {
-                            r.metadata.insert("hop".to_string(), serde_json::json!(hop));
-                        }
-                    }
-                    
-                    // Merge results, avoiding duplicates
-                    for r in results {
-                        if !all_results.iter().any(|existing| existing.memory_id == r.memory_id) {
-                            all_results.push(r);
-                        }
-                    }
-                    
-                    if hop < depth {
-                        let mut pivot_memory = None;
-                        for r in &all_results {
-                            if !used_pivot_memory_ids.contains(&r.memory_id) {
-                                pivot_memory = ctx.main.get_memory(&r.memory_id);
-                                if pivot_memory.is_some() {
-                                    used_pivot_memory_ids.insert(r.memory_id.clone());
-                                    break;
-                                }
-                            }
-                        }
-                        
-                        if let Some(mem) = pivot_memory {
-                            let existing_cues: std::collections::HashSet<String> = expanded_cues.iter().map(|(c, _)| c.clone()).collect();
-                            for cue in mem.cues {
-                                if !existing_cues.contains(&cue) {
-                                    expanded_cues.push((cue, 0.5f64.powi(hop as i32)));
-                                }
-                            }
-                        } else {
-                            break;
-                        }
-                    }
+                if req.follow_links {
+                    ctx.main.follow_links(&mut all_results, &req.link_types, limit, &filter);
                 }
-                
                 all_results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+                add_snippets(&req, &ctx, &expanded_cues, &mut all_results);
                 let results = all_results;
                 
                 let json_results: Vec<serde_json::Value> = results
@@ -624,18 +1449,38 @@ async fn recall(
                     }))
                     .collect();
                 
+                let recall_id = ctx.log_recall(
+                    results.iter().map(|r| r.memory_id.clone()).collect(),
+                    expanded_cues.iter().map(|(c, _)| c.clone()).collect(),
+                );
                 let mut response_block = serde_json::json!({
                     "project_id": project_id,
+                    "recall_id": recall_id,
+                    "profile": profile,
                     "results": json_results
                 });
                 
                 if req.explain {
+                    let mut explain = serde_json::json!({
+                        "query_cues": cues_to_process,
+                        "expanded_cues": expanded_cues,
+                        "scan_budget": ctx.main.scan_budgets(&expanded_cues, limit, req.scan_limit)
+                    });
+                    if req.explain_level == ExplainLevel::Full {
+                        explain["trace"] = serde_json::json!(traces);
+                    }
+                    if req.fuzzy {
+                        explain["fuzzy_corrections"] = serde_json::json!(fuzzy_corrections);
+                    }
+                    if !req.cue_prefix.is_empty() {
+                        explain["prefix_matches"] = serde_json::json!(prefix_matches);
+                    }
+                    if let Some(rewrite) = &rewrite {
+                        explain["query_rewrite"] = serde_json::json!(rewrite);
+                    }
                     response_block.as_object_mut().unwrap().insert(
                         "explain".to_string(), 
-                        serde_json::json!({
-                            "query_cues": cues_to_process,
-                            "expanded_cues": expanded_cues
-                        })
+                        explain
                     );
                 }
                 
@@ -651,6 +1496,7 @@ async fn recall(
                 (response_block, task)
             })
             .unzip();
+        timer.lap("search");
 
         // Enqueue reinforcement tasks
         for task in reinforce_tasks {
@@ -666,7 +1512,53 @@ async fn recall(
         let elapsed = start.elapsed();
         let engine_latency_ms = elapsed.as_secs_f64() * 1000.0;
         state.metrics.record_recall(engine_latency_ms);
+        for project_id in &projects {
+            state.metrics.record_project_recall(project_id, engine_latency_ms);
+        }
+        if state.metrics.slow_queries.is_slow(engine_latency_ms) {
+            state.metrics.slow_queries.record(SlowQuery {
+                projects: projects.clone(),
+                query_text: req.query_text.as_deref().map(SlowQuery::clip_query_text),
+                cue_count: req.cues.len(),
+                result_count: all_results.iter().map(|b| b["results"].as_array().map_or(0, Vec::len)).sum(),
+                ..SlowQuery::new(engine_latency_ms, timer.into_stages())
+            });
+        }
+
+        if req.merge {
+            let mut blocks = all_results;
+            let lists: Vec<Vec<serde_json::Value>> = blocks
+                .iter_mut()
+                .map(|block| match block.as_object_mut().and_then(|b| b.remove("results")) {
+                    Some(serde_json::Value::Array(results)) => results,
+                    _ => Vec::new(),
+                })
+                .collect();
+            let scores: Vec<Vec<f64>> = lists
+                .iter()
+                .map(|results| results.iter().map(|r| r["score"].as_f64().unwrap_or(0.0)).collect())
+                .collect();
+            let merged: Vec<serde_json::Value> = fuse(&scores, req.fusion, req.limit.max(1))
+                .into_iter()
+                .map(|hit| {
+                    let mut result = lists[hit.list][hit.rank].clone();
+                    result["project_id"] = blocks[hit.list]["project_id"].clone();
+                    result["fused_score"] = serde_json::json!(hit.fused_score);
+                    result
+                })
+                .collect();
+            crate::telemetry::record_results(merged.len());
+
+            // Blocks keep each project's recall_id (for feedback), profile, explain and errors
+            return (StatusCode::OK, Json(serde_json::json!({
+                "results": merged,
+                "fusion": req.fusion,
+                "projects": blocks,
+                "engine_latency": engine_latency_ms
+            })));
+        }
         
+        crate::telemetry::record_results(all_results.iter().map(|b| b["results"].as_array().map_or(0, Vec::len)).sum());
         return (StatusCode::OK, Json(serde_json::json!({ 
             "results": all_results,
             "engine_latency": engine_latency_ms
@@ -683,112 +1575,67 @@ async fn recall(
         Ok(c) => c,
         Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
     };
-    
-    // Collect cues
-    let mut cues_to_process = req.cues.clone();
-    
-    let mut lexicon_memory_ids: Vec<String> = Vec::new();
-    let mut tokens_from_text = Vec::new();
-    if let Some(ref text) = req.query_text {
-         // 1. Lexicon Recall
-         let (resolved, lex_mids, tokens) = ctx.resolve_cues_from_text(text, false);
-         cues_to_process.extend(resolved);
-         lexicon_memory_ids = lex_mids;
-
-         // 2. Raw Token Fallback
-         tokens_from_text = tokens;
-         for token in &tokens_from_text {
-             if !cues_to_process.contains(token) {
-                 cues_to_process.push(token.clone());
-             }
-         }
-    }
-    
-    // Normalize query cues
-    let mut normalized_cues = Vec::new();
-    for cue in &cues_to_process {
-        let (normalized, _) = normalize_cue(cue, &ctx.normalization);
-        normalized_cues.push(normalized);
+    let mut timer = StageTimer::new();
+    let rewrite = rewrite_recall_query(&req, &ctx);
+    if let Some(rewrite) = &rewrite {
+        req.query_text = Some(rewrite.rewritten.clone());
     }
     
-    // Expand aliases
-    let mut expanded_cues = if req.disable_alias_expansion {
-        normalized_cues.into_iter().map(|c| (c, 1.0)).collect()
-    } else {
-        let original_tokens = if req.query_text.is_some() {
-            tokens_from_text // Reuse tokens computed earlier
-        } else {
-            req.cues.clone()
-        };
-        ctx.expand_query_cues(normalized_cues, &original_tokens)
+    let filter = normalize_exclusions(&filter, &ctx);
+    let hybrid = match hybrid_query(&req, mt_engine, &project_id, &ctx).await {
+        Ok(h) => h,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    };
+    timer.lap("embed");
+    let mut recall = prepare_recall(&req, &ctx, &mut timer);
+    let logged_cues = state.metrics.query_log.as_ref().map(|_| recall.normalized_cues.clone());
+    let profile = recall_profile(&req, &ctx, &filter, hybrid.as_ref());
+    let reranker = match recall_reranker(&req, mt_engine, &project_id) {
+        Ok(r) => r,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    };
+    let group_by = match req.group_by.as_deref().map(parse_namespace).transpose() {
+        Ok(g) => g,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
     };
 
-    let mut all_results: Vec<crate::engine::RecallResult> = Vec::new();
-    let mut used_pivot_memory_ids = std::collections::HashSet::new();
     let limit = req.limit.max(1);
-    let depth = req.depth.max(1);
-
-    for hop in 1..=depth {
-        let current_limit = (limit as f64 / hop as f64).ceil() as usize;
-        
-        let mut results = {
-            let heatmap = ctx.market_heatmap.read().ok();
-            let heatmap_ref = heatmap.as_deref();
-
-            ctx.main.recall_weighted(
-                expanded_cues.clone(), 
-                current_limit, 
-                false, 
-                req.min_intersection,
-                req.explain,
-                req.disable_pattern_completion,
-                req.disable_salience_bias,
-                req.disable_systems_consolidation,
-                heatmap_ref
-            )
-        }; 
-        
-        // Add hop metadata
-        for r in &mut results {
-            if !r.metadata.contains_key("hop") {
-                r.metadata.insert("hop".to_string(), serde_json::json!(hop));
-            }
-        }
-        
-        // Merge results, avoiding duplicates
-        for r in results {
-            if !all_results.iter().any(|existing| existing.memory_id == r.memory_id) {
-                all_results.push(r);
-            }
-        }
-        
-        if hop < depth {
-            let mut pivot_memory = None;
-            for r in &all_results {
-                if !used_pivot_memory_ids.contains(&r.memory_id) {
-                    pivot_memory = ctx.main.get_memory(&r.memory_id);
-                    if pivot_memory.is_some() {
-                        used_pivot_memory_ids.insert(r.memory_id.clone());
-                        break;
-                    }
-                }
-            }
-            
-            if let Some(mem) = pivot_memory {
-                let existing_cues: std::collections::HashSet<String> = expanded_cues.iter().map(|(c, _)| c.clone()).collect();
-                for cue in mem.cues {
-                    if !existing_cues.contains(&cue) {
-                        expanded_cues.push((cue, 0.5f64.powi(hop as i32)));
-                    }
-                }
-            } else {
-                break;
-            }
-        }
-    }
+    let mut traces: Vec<serde_json::Value> = Vec::new();
+    // Grouping keeps the best of several chunks per group
+    let fetch = if group_by.is_some() { limit * GROUP_CANDIDATE_FACTOR } else { limit };
+    // The reranker picks the `fetch` best out of its `top_n` candidates
+    let candidates = reranker.as_ref().map(|(_, top_n)| fetch.max(*top_n)).unwrap_or(fetch);
+    let mut all_results = recall_hops(&ctx, &req, profile, &mut recall.expanded_cues, candidates, &filter, hybrid.as_ref(), &mut traces, |_| true);
     
+    if req.follow_links {
+        // Rerank and grouping still pick from the full candidate pool
+        ctx.main.follow_links(&mut all_results, &req.link_types, candidates, &filter);
+    }
     all_results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
-    let results = all_results;
+    timer.lap("search");
+
+    let mut reranked_by = None;
+    if let Some((reranker, top_n)) = reranker {
+        let query = req.query_text.clone().unwrap_or_else(|| req.cues.join(" "));
+        match crate::rerank::rerank(reranker.as_ref(), &query, &mut all_results, top_n)
+            .instrument(tracing::debug_span!("rerank", top_n))
+            .await
+        {
+            Ok(()) => reranked_by = Some(reranker.name()),
+            // A failing scorer shouldn't fail the recall
+            Err(e) => tracing::warn!("Rerank failed for project '{}', keeping recall order: {}", project_id, e),
+        }
+        all_results.truncate(fetch);
+        timer.lap("rerank");
+    }
+    add_snippets(&req, &ctx, &recall.expanded_cues, &mut all_results);
+    let (results, groups) = match &group_by {
+        Some(namespace) => {
+            let groups = group_results(all_results, limit, req.group_score, |id| ctx.main.cue_value(id, namespace));
+            (groups.iter().map(|g| g.best.clone()).collect(), Some(groups))
+        }
+        None => (all_results, None),
+    };
     
     let elapsed = start.elapsed();    
     let engine_latency_ms = elapsed.as_secs_f64() * 1000.0;
@@ -796,7 +1643,7 @@ async fn recall(
     // Async reinforcement via background job (doesn't block response)
     if req.auto_reinforce && !results.is_empty() {
         let memory_ids: Vec<String> = results.iter().map(|r| r.memory_id.clone()).collect();
-        let cues: Vec<String> = expanded_cues.iter().map(|(c, _)| c.clone()).collect();
+        let cues: Vec<String> = recall.expanded_cues.iter().map(|(c, _)| c.clone()).collect();
         // This await was causing the conflict because `heatmap` was seen as potentially live
         job_queue.enqueue(crate::jobs::Job::ReinforceMemories {
             project_id: project_id.clone(),
@@ -806,37 +1653,292 @@ async fn recall(
     }
     
     // Reinforce Lexicon memories (async)
-    if req.auto_reinforce && !lexicon_memory_ids.is_empty() {
+    if req.auto_reinforce && !recall.lexicon_memory_ids.is_empty() {
         let tokens = if let Some(ref text) = req.query_text {
-            crate::nl::tokenize_to_cues(text)
+            ctx.tokenize(text)
         } else {
             Vec::new()
         };
         job_queue.enqueue(crate::jobs::Job::ReinforceLexicon {
             project_id: project_id.clone(),
-            memory_ids: lexicon_memory_ids,
+            memory_ids: std::mem::take(&mut recall.lexicon_memory_ids),
             cues: tokens,
+            result_ids: results.iter().map(|r| r.memory_id.clone()).collect(),
         }).await;
     }
 
     // Record metrics
     state.metrics.record_recall(engine_latency_ms);
-    
-    if req.explain {
-        return (StatusCode::OK, Json(serde_json::json!({ 
-            "results": results,
-            "engine_latency": engine_latency_ms,
-            "explain": {
-                "query_cues": cues_to_process,
-                "expanded_cues": expanded_cues
-            }
-        })));
+    state.metrics.record_project_recall(&project_id, engine_latency_ms);
+    if state.metrics.slow_queries.is_slow(engine_latency_ms) {
+        state.metrics.slow_queries.record(SlowQuery {
+            project_id: Some(project_id.clone()),
+            profile: Some(profile),
+            query_text: req.query_text.as_deref().map(SlowQuery::clip_query_text),
+            cue_count: recall.query_cues.len(),
+            expanded_cue_count: recall.expanded_cues.len(),
+            candidate_count: ctx.main.scan_budgets(&recall.expanded_cues, limit, req.scan_limit).iter().map(|b| b.budget).sum(),
+            result_count: results.len(),
+            ..SlowQuery::new(engine_latency_ms, timer.into_stages())
+        });
+    }
+    if let (Some(log), Some(cues)) = (&state.metrics.query_log, logged_cues) {
+        log.record(&LoggedQuery {
+            limit,
+            min_intersection: req.min_intersection,
+            disable_pattern_completion: req.disable_pattern_completion,
+            disable_salience_bias: req.disable_salience_bias,
+            disable_systems_consolidation: req.disable_systems_consolidation,
+            disable_alias_expansion: req.disable_alias_expansion,
+            fuzzy: req.fuzzy,
+            result_count: results.len(),
+            latency_ms: engine_latency_ms,
+            ..LoggedQuery::new(project_id.clone(), cues)
+        });
     }
 
-    (StatusCode::OK, Json(serde_json::json!({ 
+    let recall_id = ctx.log_recall(
+        results.iter().map(|r| r.memory_id.clone()).collect(),
+        recall.expanded_cues.iter().map(|(c, _)| c.clone()).collect(),
+    );
+    crate::telemetry::record_results(results.len());
+
+    let mut response = serde_json::json!({ 
         "results": results,
+        "recall_id": recall_id,
+        "profile": profile,
+        "reranked_by": reranked_by,
         "engine_latency": engine_latency_ms
-    })))
+    });
+    if let Some(groups) = groups {
+        response["groups"] = serde_json::json!(groups);
+    }
+    
+    if req.explain {
+        response["explain"] = recall_explain(&req, &ctx, &recall, limit, traces, rewrite.as_ref());
+    }
+
+    (StatusCode::OK, Json(response))
+}
+
+/// Query cues of a request that bypasses scoring: the given cues plus those
+/// resolved from the text, normalized but not expanded
+fn unexpanded_query_cues(ctx: &ProjectContext, cues: &[String], query_text: Option<&str>) -> Vec<String> {
+    let mut cues = cues.to_vec();
+    if let Some(text) = query_text {
+        let (resolved, _, tokens) = ctx.resolve_cues_from_text(text, false);
+        cues.extend(resolved);
+        cues.extend(tokens);
+    }
+    let mut normalized: Vec<String> = Vec::new();
+    for cue in &cues {
+        let cue = ctx.normalize_cue(cue);
+        if !normalized.contains(&cue) {
+            normalized.push(cue);
+        }
+    }
+    normalized
+}
+
+/// Count the memories a query matches, optionally per cue value and per
+/// day, without scoring them
+async fn recall_count(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Json(req): Json<CountRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+    let by_cue = match req.by_cue.as_deref().map(parse_namespace).transpose() {
+        Ok(n) => n,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    };
+    let ctx = match state.mt_engine.get_or_create_project(project_id.clone()) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+
+    let normalized = unexpanded_query_cues(&ctx, &req.cues, req.query_text.as_deref());
+    let filter = normalize_exclusions(&req.recall_filter(), &ctx);
+    let report = ctx.main.count_matching(
+        &normalized,
+        req.min_intersection.unwrap_or(1),
+        &filter,
+        by_cue.as_deref(),
+        req.by_day,
+        req.limit,
+    );
+
+    let mut response = serde_json::json!({
+        "project_id": project_id,
+        "cues": normalized,
+        "count": report.count
+    });
+    if let Some(by_cue) = report.by_cue {
+        response["by_cue"] = serde_json::json!(by_cue);
+    }
+    if let Some(by_day) = report.by_day {
+        response["by_day"] = serde_json::json!(by_day);
+    }
+    (StatusCode::OK, Json(response))
+}
+
+/// Streaming variant of `/recall` (Server-Sent Events).
+/// Each hop's results are emitted as `result` events as soon as that hop is scored,
+/// so clients can consume the top matches before deeper hops finish. Results within
+/// a hop are ranked together, so with `depth: 1` nothing is sent before the whole
+/// recall has been scored.
+/// The stream ends with a single `done` event carrying latency (and explain data if requested).
+/// Query preparation and hops are the same as `/recall`'s and run on the blocking pool.
+async fn recall_stream(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Json(mut req): Json<RecallRequest>,
+) -> axum::response::Response {
+    use axum::response::sse::{Event, KeepAlive, Sse};
+    use futures::SinkExt;
+    use std::time::Instant;
+
+    let start = Instant::now();
+    let EngineState { mt_engine, job_queue, metrics, .. } = state;
+    if req.explain_level == ExplainLevel::Full {
+        req.explain = true;
+    }
+
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+
+    let ctx = match mt_engine.get_or_create_project(project_id.clone()) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))).into_response(),
+    };
+    let rewrite = rewrite_recall_query(&req, &ctx);
+    if let Some(rewrite) = &rewrite {
+        req.query_text = Some(rewrite.rewritten.clone());
+    }
+    let hybrid = match hybrid_query(&req, &mt_engine, &project_id, &ctx).await {
+        Ok(h) => h,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))).into_response(),
+    };
+
+    // Bounded, so a slow client holds up scoring instead of buffering every result
+    let (mut tx, rx) = futures::channel::mpsc::channel::<Result<Event, std::convert::Infallible>>(RECALL_STREAM_BUFFER);
+    let send_result = |tx: &mut futures::channel::mpsc::Sender<Result<Event, std::convert::Infallible>>, r: &RecallResult| {
+        let event = Event::default()
+            .event("result")
+            .json_data(r)
+            .unwrap_or_else(|_| Event::default().event("result").data("{}"));
+        futures::executor::block_on(tx.send(Ok(event))).is_ok()
+    };
+
+    tokio::spawn(async move {
+        let req = Arc::new(req);
+        let hop_req = req.clone();
+        let hop_ctx = ctx.clone();
+        let hop_tx = tx.clone();
+        let searched = tokio::task::spawn_blocking(move || {
+            let (req, ctx, mut tx) = (hop_req, hop_ctx, hop_tx);
+            let filter = normalize_exclusions(&req.recall_filter(), &ctx);
+            let mut recall = prepare_recall(&req, &ctx, &mut StageTimer::new());
+            let profile = recall_profile(&req, &ctx, &filter, hybrid.as_ref());
+            let limit = req.limit.max(1);
+            let mut traces: Vec<serde_json::Value> = Vec::new();
+            let mut emitted_ids: Vec<String> = Vec::new();
+            let mut client_gone = false;
+
+            // Emit each hop's new results immediately (already sorted by score)
+            let mut emitted = recall_hops(&ctx, &req, profile, &mut recall.expanded_cues, limit, &filter, hybrid.as_ref(), &mut traces, |results| {
+                for r in results {
+                    if !send_result(&mut tx, r) {
+                        client_gone = true;
+                        return false;
+                    }
+                    emitted_ids.push(r.memory_id.clone());
+                }
+                true
+            });
+
+            // Linked memories that make the top `limit`, after the results they were reached from
+            if req.follow_links && !client_gone {
+                ctx.main.follow_links(&mut emitted, &req.link_types, limit, &filter);
+                let linked: Vec<&RecallResult> = emitted
+                    .iter()
+                    .filter(|r| !emitted_ids.contains(&r.memory_id))
+                    .collect();
+                for r in linked {
+                    if !send_result(&mut tx, r) {
+                        client_gone = true;
+                        break;
+                    }
+                    emitted_ids.push(r.memory_id.clone());
+                }
+            }
+            (recall, profile, traces, emitted_ids, client_gone)
+        })
+        .await;
+        let Ok((mut recall, profile, traces, emitted_ids, client_gone)) = searched else {
+            return;
+        };
+        let limit = req.limit.max(1);
+
+        let engine_latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        // Async reinforcement via background job (same semantics as /recall)
+        if req.auto_reinforce && !emitted_ids.is_empty() {
+            let cues: Vec<String> = recall.expanded_cues.iter().map(|(c, _)| c.clone()).collect();
+            job_queue.enqueue(Job::ReinforceMemories {
+                project_id: project_id.clone(),
+                memory_ids: emitted_ids.clone(),
+                cues,
+            }).await;
+        }
+
+        if req.auto_reinforce && !recall.lexicon_memory_ids.is_empty() {
+            let tokens = if let Some(ref text) = req.query_text {
+                ctx.tokenize(text)
+            } else {
+                Vec::new()
+            };
+            job_queue.enqueue(Job::ReinforceLexicon {
+                project_id: project_id.clone(),
+                memory_ids: std::mem::take(&mut recall.lexicon_memory_ids),
+                cues: tokens,
+                result_ids: emitted_ids.clone(),
+            }).await;
+        }
+
+        metrics.record_recall(engine_latency_ms);
+        metrics.record_project_recall(&project_id, engine_latency_ms);
+
+        if client_gone {
+            return;
+        }
+
+        let recall_id = ctx.log_recall(
+            emitted_ids.clone(),
+            recall.expanded_cues.iter().map(|(c, _)| c.clone()).collect(),
+        );
+        let mut summary = serde_json::json!({
+            "count": emitted_ids.len(),
+            "recall_id": recall_id,
+            "profile": profile,
+            "engine_latency": engine_latency_ms
+        });
+        if req.explain {
+            summary["explain"] = recall_explain(&req, &ctx, &recall, limit, traces, rewrite.as_ref());
+        }
+        let done = Event::default()
+            .event("done")
+            .json_data(&summary)
+            .unwrap_or_else(|_| Event::default().event("done").data("{}"));
+        let _ = tx.send(Ok(done)).await;
+    });
+
+    Sse::new(rx).keep_alive(KeepAlive::default()).into_response()
 }
 
 async fn reinforce_memory(
@@ -865,7 +1967,7 @@ async fn reinforce_memory(
             }
         } else {
             for cue in req.cues {
-                let (normalized, _) = normalize_cue(&cue, &ctx.normalization);
+                let normalized = ctx.normalize_cue(&cue);
                 normalized_cues.push(normalized);
             }
         }
@@ -891,6 +1993,52 @@ async fn reinforce_memory(
     }
 }
 
+/// Push a stale or wrong memory down without deleting it
+async fn demote_memory(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Path(memory_id): Path<String>,
+    Json(req): Json<DemoteRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+    if !req.amount.is_finite() || req.amount < 0.0 {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "amount must be a non-negative number"})));
+    }
+
+    let EngineState { mt_engine, read_only, .. } = state;
+    if read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+    let ctx = match mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+
+    let cues: Vec<String> = req.detach_cues
+        .iter()
+        .map(|cue| ctx.normalize_cue(cue))
+        .collect();
+
+    match ctx.main.demote_memory(&memory_id, req.amount, &cues) {
+        Some(detached) => {
+            let salience = ctx.main.get_memories().get(&memory_id).map(|m| m.stats.salience());
+            (StatusCode::OK, Json(serde_json::json!({
+                "status": "demoted",
+                "memory_id": memory_id,
+                "salience": salience,
+                "detached_cues": detached,
+            })))
+        }
+        None => (StatusCode::NOT_FOUND, Json(serde_json::json!({
+            "status": "not_found",
+            "memory_id": memory_id
+        }))),
+    }
+}
+
 async fn get_memory(
     State(state): State<EngineState>,
     headers: HeaderMap,
@@ -907,7 +2055,13 @@ async fn get_memory(
         Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
     };
     match ctx.main.get_memory(&memory_id) {
-        Some(memory) => (StatusCode::OK, Json(serde_json::json!(memory))),
+        Some(memory) => {
+            let access = ctx.main.memory_access(&memory_id);
+            let mut body = serde_json::json!(memory);
+            body["recall_count"] = serde_json::json!(access.recall_count);
+            body["last_recalled_at"] = serde_json::json!(access.last_recalled_at);
+            (StatusCode::OK, Json(body))
+        }
         None => (
             StatusCode::NOT_FOUND,
             Json(serde_json::json!({"error": "Memory not found"})),
@@ -931,12 +2085,15 @@ async fn delete_memory(
         return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
     }
     
-    let ctx = match mt_engine.get_or_create_project(project_id) {
+    let ctx = match mt_engine.get_or_create_project(project_id.clone()) {
         Ok(c) => c,
         Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
     };
     let deleted = ctx.main.delete_memory(&memory_id);
     if deleted {
+        mt_engine.audit().record(
+            AuditEntry::new(audit_actor(&headers), AuditAction::MemoryDeleted, &project_id).with_memory(&memory_id),
+        );
         (StatusCode::OK, Json(serde_json::json!({
             "status": "deleted",
             "memory_id": memory_id
@@ -949,271 +2106,225 @@ async fn delete_memory(
     }
 }
 
-async fn get_stats(
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetEmbeddingRequest {
+    pub embedding: Vec<f32>,
+}
+
+/// Attach (or replace) the embedding of an existing memory, e.g. one computed
+/// asynchronously by an external model
+async fn set_memory_embedding(
     State(state): State<EngineState>,
     headers: HeaderMap,
+    Path(memory_id): Path<String>,
+    Json(req): Json<SetEmbeddingRequest>,
 ) -> (StatusCode, Json<serde_json::Value>) {
-    let project_id_opt = extract_project_id_optional(&headers);
-    let EngineState { mt_engine, .. } = state;
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
 
-    if let Some(project_id) = project_id_opt {
-        let ctx = match mt_engine.get_or_create_project(project_id) {
-            Ok(c) => c,
-            Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
-        };
-        let stats = ctx.main.get_stats();
-        (StatusCode::OK, Json(serde_json::Value::Object(stats.into_iter().collect())))
-    } else {
-        // Global stats
-        let stats = mt_engine.get_global_stats();
-        (StatusCode::OK, Json(serde_json::json!(stats)))
+    let EngineState { mt_engine, read_only, .. } = state;
+    if read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+
+    let ctx = match mt_engine.get_or_create_project(project_id.clone()) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+    if ctx.main.get_memory(&memory_id).is_none() {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({
+            "error": "Memory not found",
+            "memory_id": memory_id
+        })));
+    }
+
+    let dimensions = req.embedding.len();
+    match ctx.main.set_embedding(&memory_id, req.embedding) {
+        Ok(()) => (StatusCode::OK, Json(serde_json::json!({
+            "status": "stored",
+            "memory_id": memory_id,
+            "dimensions": dimensions
+        }))),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
     }
 }
 
-/// Get job/ingestion progress for a project or globally
-async fn jobs_status(
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PinMemoryRequest {
+    #[serde(default = "default_pinned")]
+    pub pinned: bool,
+}
+
+fn default_pinned() -> bool {
+    true
+}
+
+/// Pin (or unpin) a memory so pruning and consolidation leave it alone
+async fn set_memory_pinned(
     State(state): State<EngineState>,
     headers: HeaderMap,
+    Path(memory_id): Path<String>,
+    Json(req): Json<PinMemoryRequest>,
 ) -> (StatusCode, Json<serde_json::Value>) {
-    let project_id_opt = extract_project_id_optional(&headers);
-    let EngineState { job_queue, .. } = state;
-    
-    if let Some(project_id) = project_id_opt {
-        if let Some(session) = job_queue.get_session(&project_id) {
-            let progress = session.get_progress();
-            (StatusCode::OK, Json(serde_json::json!(progress)))
-        } else {
-            // No active session - return idle status
-            (StatusCode::OK, Json(serde_json::json!({
-                "phase": "idle",
-                "writes_completed": 0,
-                "writes_total": 0,
-                "propose_cues_completed": 0,
-                "propose_cues_total": 0,
-                "train_lexicon_completed": 0,
-                "train_lexicon_total": 0,
-                "update_graph_completed": 0,
-                "update_graph_total": 0
-            })))
-        }
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let EngineState { mt_engine, read_only, .. } = state;
+    if read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+
+    let ctx = match mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+    if ctx.main.set_pinned(&memory_id, req.pinned) {
+        (StatusCode::OK, Json(serde_json::json!({
+            "status": if req.pinned { "pinned" } else { "unpinned" },
+            "memory_id": memory_id
+        })))
     } else {
-        // Global progress
-        let progress = job_queue.get_global_progress();
-        (StatusCode::OK, Json(serde_json::json!(progress)))
+        (StatusCode::NOT_FOUND, Json(serde_json::json!({
+            "error": "Memory not found",
+            "memory_id": memory_id
+        })))
     }
 }
 
-async fn recall_grounded(
+/// Replaced versions of a memory, oldest first (see `tuning.max_memory_versions`)
+async fn memory_history(
     State(state): State<EngineState>,
     headers: HeaderMap,
-    Json(req): Json<RecallGroundedRequest>,
+    Path(memory_id): Path<String>,
 ) -> (StatusCode, Json<serde_json::Value>) {
-    use std::time::Instant;
-    use crate::grounding::{GroundingEngine, create_grounding_proof};
-
-    let project_id = if let Some(ref projects) = req.projects {
-        projects.first().cloned().unwrap_or_else(|| {
-             headers.get("X-Project-ID").and_then(|v| v.to_str().ok()).unwrap_or("default").to_string()
-        })
-    } else {
-        match extract_project_id(&headers) {
-            Ok(id) => id,
-            Err(e) => return e,
-        }
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
     };
 
     let EngineState { mt_engine, .. } = state;
-        let start = Instant::now();
-        let ctx = match mt_engine.get_or_create_project(project_id) {
-            Ok(c) => c,
-            Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
-        };
-        
-        // 1. Standard CueMap Recall
-        let (resolved, _lexicon_memory_ids, tokens) = ctx.resolve_cues_from_text(&req.query_text, false);
-        let mut normalized_cues = Vec::new();
-        for cue in &resolved {
-            let (normalized, _) = crate::normalization::normalize_cue(cue, &ctx.normalization);
-            normalized_cues.push(normalized);
-        }
+    let ctx = match mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+    if ctx.main.get_memory(&memory_id).is_none() {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({
+            "error": "Memory not found",
+            "memory_id": memory_id
+        })));
+    }
 
-        let expanded_cues = if req.disable_alias_expansion {
-            normalized_cues.into_iter().map(|c| (c, 1.0)).collect()
-        } else {
-            // tokens were computed in step 1, reuse them!
-            ctx.expand_query_cues(normalized_cues, &tokens)
-        };
-        
-        let heatmap = ctx.market_heatmap.read().ok();
-        let heatmap_ref = heatmap.as_deref();
-
-        let results = ctx.main.recall_weighted(
-            expanded_cues.clone(), 
-            req.limit.max(20),
-            req.auto_reinforce, 
-            req.min_intersection,
-            true,
-            req.disable_pattern_completion,
-            req.disable_salience_bias,
-            req.disable_systems_consolidation,
-            heatmap_ref
-        );
-        drop(heatmap); // Guard must be dropped before async return to satisfy Send (even if implicit)
-        
-        // 2. Apply Budgeting Logic
-        let (selected, excluded, context_block) = GroundingEngine::select_memories(
-            req.query_text.clone(),
-            resolved.clone(),
-            expanded_cues.clone(),
-            results,
-            req.token_budget,
-        );
-        
-        // 3. Create Proof
-        let proof = create_grounding_proof(
-            uuid::Uuid::new_v4().to_string(),
-            req.query_text,
-            resolved,
-            expanded_cues,
-            req.token_budget,
-            selected,
-            excluded,
-        );
-        
-        let elapsed = start.elapsed();
-        
-        // 4. Sign Context
-        let signature = if let Some(key) = state.signing_key {
-            let crypto = crate::crypto::CryptoEngine::new(key.as_ref().clone());
-            crypto.sign(&context_block)
-        } else {
-             "error: CUEMAP_SECRET_KEY not set".to_string()
-        };
-        
-        (StatusCode::OK, Json(serde_json::json!({ 
-            "verified_context": context_block,
-            "proof": proof,
-            "engine_latency_ms": elapsed.as_secs_f64() * 1000.0,
-            "signature": signature
-        })))
+    let history = ctx.main.history();
+    let versions: Vec<serde_json::Value> = history
+        .versions(&memory_id)
+        .into_iter()
+        .map(|v| serde_json::json!({
+            "version": v.version,
+            "replaced_at": v.replaced_at,
+            "content": ctx.main.version_content(&v).unwrap_or_else(|_| "<decryption failed>".to_string()),
+            "cues": v.cues,
+            "metadata": v.metadata,
+        }))
+        .collect();
+
+    (StatusCode::OK, Json(serde_json::json!({
+        "memory_id": memory_id,
+        "current_version": history.current_version(&memory_id),
+        "max_versions": history.max_versions(),
+        "versions": versions
+    })))
 }
 
-async fn list_projects(
-    State(state): State<EngineState>,
-) -> (StatusCode, Json<serde_json::Value>) {
-    let EngineState { mt_engine, .. } = state;
-    let projects = mt_engine.list_projects();
-    (StatusCode::OK, Json(serde_json::json!(projects)))
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RollbackRequest {
+    pub version: u64,
 }
 
-async fn create_project(
+/// Restore a stored version of a memory (the replaced state is kept as a new version)
+async fn rollback_memory(
     State(state): State<EngineState>,
-    Json(req): Json<CreateProjectRequest>,
+    headers: HeaderMap,
+    Path(memory_id): Path<String>,
+    Json(req): Json<RollbackRequest>,
 ) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
     let EngineState { mt_engine, read_only, .. } = state;
     if read_only {
         return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
     }
 
-    if !validate_project_id(&req.project_id) {
-        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
-    }
-
-    // Check if exists first to return 409 Conflict logic if desired, or just idempotent OK
-    // get_or_create_project is idempotent, but we might want to be explicit.
-    // For now, let's just use get_or_create_project and return 200 OK or 201 Created.
-    // Actually, if we want to mimic "create", 201 is good.
-    
-    match mt_engine.get_or_create_project(req.project_id.clone()) {
-        Ok(_) => {
-            (
-                StatusCode::CREATED,
-                Json(serde_json::json!({
-                    "status": "created", 
-                    "project_id": req.project_id 
-                })),
-            )
-        },
-        Err(e) => (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({"error": e})),
-        ),
+    let ctx = match mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+    match ctx.main.rollback_memory(&memory_id, req.version) {
+        Ok(current_version) => (StatusCode::OK, Json(serde_json::json!({
+            "status": "rolled_back",
+            "memory_id": memory_id,
+            "restored_version": req.version,
+            "current_version": current_version
+        }))),
+        Err(e) => (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": e}))),
     }
 }
 
-async fn delete_project(
-    State(state): State<EngineState>,
-    Path(project_id): Path<String>,
-) -> (StatusCode, Json<serde_json::Value>) {
-    let EngineState { mt_engine, .. } = state;
-    let deleted = mt_engine.delete_project(&project_id);
-    if deleted {
-        (
-            StatusCode::OK,
-            Json(serde_json::json!({"status": "deleted", "project_id": project_id})),
-        )
-    } else {
-        (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({"error": "Project not found"})),
-        )
-    }
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MemoryLinkRequest {
+    /// Relationship, e.g. `caused_by`, `supersedes`, `part_of` (optional when deleting)
+    #[serde(rename = "type", default)]
+    pub kind: Option<String>,
+    pub target: String,
 }
 
-
-async fn set_project_watch_dir(
+/// Outgoing links of a memory and the links pointing at it
+async fn get_memory_links(
     State(state): State<EngineState>,
-    Path(project_id): Path<String>,
-    Json(req): Json<SetWatchDirRequest>,
+    headers: HeaderMap,
+    Path(memory_id): Path<String>,
 ) -> (StatusCode, Json<serde_json::Value>) {
-    let EngineState { mt_engine, read_only, agent_manager, .. } = state;
-    
-    if read_only {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({
-                "error": "Read-only mode: modifications are not allowed"
-            })),
-        );
-    }
-    
-    match mt_engine.set_project_watch_dir(&project_id, Some(req.watch_dir.clone())) {
-        Ok(_) => {
-            // Immediately start/update the agent
-            let agent_config = crate::agent::AgentConfig {
-                project_id: project_id.clone(),
-                watch_dir: req.watch_dir.clone(),
-                throttle_ms: 100, // Small throttle to prevent CPU pinning
-                state_file: Some(std::path::PathBuf::from(format!("./snapshots/{}_agent_state.json", project_id))),
-            };
-            
-            // Spawn the starting of the agent securely
-            let project_id_clone = project_id.clone();
-            tokio::spawn(async move {
-                agent_manager.start_agent(&project_id_clone, agent_config).await;
-            });
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
 
-            (
-                StatusCode::OK,
-                Json(serde_json::json!({
-                    "status": "updated",
-                    "project_id": project_id
-                })),
-            )
-        },
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": e})),
-        ),
+    let EngineState { mt_engine, .. } = state;
+    let ctx = match mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+    if ctx.main.get_memory(&memory_id).is_none() {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({
+            "error": "Memory not found",
+            "memory_id": memory_id
+        })));
     }
-}
 
-// Multi-tenant Alias Handlers
+    let backlinks: Vec<serde_json::Value> = ctx.main
+        .backlinks(&memory_id)
+        .into_iter()
+        .map(|(source, kind)| serde_json::json!({"type": kind, "source": source}))
+        .collect();
+    (StatusCode::OK, Json(serde_json::json!({
+        "memory_id": memory_id,
+        "links": ctx.main.links(&memory_id),
+        "backlinks": backlinks
+    })))
+}
 
-async fn add_alias(
+/// Add a typed link from this memory to another
+async fn add_memory_link(
     State(state): State<EngineState>,
     headers: HeaderMap,
-    Json(req): Json<AddAliasRequest>,
+    Path(memory_id): Path<String>,
+    Json(req): Json<MemoryLinkRequest>,
 ) -> (StatusCode, Json<serde_json::Value>) {
     let project_id = match extract_project_id(&headers) {
         Ok(id) => id,
@@ -1222,48 +2333,90 @@ async fn add_alias(
 
     let EngineState { mt_engine, read_only, .. } = state;
     if read_only {
-        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only"})));
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
     }
 
     let ctx = match mt_engine.get_or_create_project(project_id) {
         Ok(c) => c,
         Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
     };
-    
-    let alias_id = uuid::Uuid::new_v4().to_string();
-    let content = serde_json::json!({
-        "from": req.from,
-        "to": req.to,
-        "downweight": req.weight.unwrap_or(0.85),
-        "status": "active",
-        "reason": "manual"
-    }).to_string();
+    let Some(kind) = req.kind else {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Missing link type"})));
+    };
+    if ctx.main.get_memory(&memory_id).is_none() || ctx.main.get_memory(&req.target).is_none() {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Memory not found"})));
+    }
+    match ctx.main.add_link(&memory_id, &kind, &req.target) {
+        Ok(added) => (StatusCode::OK, Json(serde_json::json!({
+            "status": if added { "linked" } else { "exists" },
+            "memory_id": memory_id,
+            "links": ctx.main.links(&memory_id)
+        }))),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    }
+}
 
-    let cues = vec![
-        "type:alias".to_string(),
-        format!("from:{}", req.from),
-        format!("to:{}", req.to),
-        "status:active".to_string(),
-        "reason:manual".to_string(),
-    ];
+/// Remove links to `target` (of `type`, or of every type when omitted)
+async fn delete_memory_link(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Path(memory_id): Path<String>,
+    Json(req): Json<MemoryLinkRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
 
-    ctx.aliases.upsert_memory_with_id(
-        alias_id.clone(),
-        content,
-        cues,
-        None,
-        Some(MainStats::default()),
-        false,
-        false
-    );
+    let EngineState { mt_engine, read_only, .. } = state;
+    if read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
 
-    (StatusCode::OK, Json(serde_json::json!({"id": alias_id, "status": "created"})))
+    let ctx = match mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+    let kind = req.kind.map(|k| k.trim().to_lowercase());
+    if ctx.main.remove_link(&memory_id, kind.as_deref(), &req.target) {
+        (StatusCode::OK, Json(serde_json::json!({"status": "unlinked", "memory_id": memory_id})))
+    } else {
+        (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Link not found", "memory_id": memory_id})))
+    }
 }
 
-async fn get_aliases(
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateSessionRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Open a named episode; pass its id as `session_id` when adding memories
+async fn create_session(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Json(req): Json<CreateSessionRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let EngineState { mt_engine, read_only, .. } = state;
+    if read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+
+    match mt_engine.create_session(&project_id, req.name) {
+        Ok(session) => (StatusCode::CREATED, Json(serde_json::json!(session))),
+        Err(e) => (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+/// Sessions of a project, oldest first, with their memory counts
+async fn list_sessions(
     State(state): State<EngineState>,
     headers: HeaderMap,
-    axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>,
 ) -> (StatusCode, Json<serde_json::Value>) {
     let project_id = match extract_project_id(&headers) {
         Ok(id) => id,
@@ -1271,155 +2424,3048 @@ async fn get_aliases(
     };
 
     let EngineState { mt_engine, .. } = state;
-        let ctx = match mt_engine.get_or_create_project(project_id) {
-            Ok(c) => c,
-            Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
-        };
-        
-        let cue = params.get("cue").cloned().unwrap_or_default();
-        if cue.is_empty() {
-            return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Missing 'cue' query param"})));
-        }
-        
-        let query_cues = vec![
-            "type:alias".to_string(), 
-            format!("to:{}", cue),
-            "status:active".to_string()
-        ];
-        
-        let results = ctx.aliases.recall(query_cues, 50, false, None);
-        let mut aliases = Vec::new();
-        
-        for res in results {
-            if let Ok(data) = serde_json::from_str::<serde_json::Value>(&res.content) {
-                let from_match = data.get("from").and_then(|v| v.as_str()).map(|v| v == cue).unwrap_or(false);
-                let to_match = data.get("to").and_then(|v| v.as_str()).map(|v| v == cue).unwrap_or(false);
-                
-                if from_match || to_match {
-                    aliases.push(data);
-                }
-            }
-        }
-        
-    (StatusCode::OK, Json(serde_json::json!({"aliases": aliases})))
+    let ctx = match mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+    let mut sessions: Vec<_> = ctx.sessions.iter().map(|e| e.value().clone()).collect();
+    sessions.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
+    let sessions: Vec<serde_json::Value> = sessions
+        .into_iter()
+        .map(|s| serde_json::json!({
+            "id": s.id,
+            "name": s.name,
+            "created_at": s.created_at,
+            "memory_count": ctx.main.session_timeline(&s.id).len(),
+        }))
+        .collect();
+    (StatusCode::OK, Json(serde_json::json!({"sessions": sessions})))
 }
 
-/// Lexicon Surgeon (Multi-tenant): Inspect a cue in the Lexicon
-async fn lexicon_inspect(
+/// Memories of a session in the order they were added
+async fn session_timeline(
     State(state): State<EngineState>,
     headers: HeaderMap,
-    axum::extract::Path(cue): axum::extract::Path<String>,
+    Path(session_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let EngineState { mt_engine, .. } = state;
+    let ctx = match mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+    let Some(session) = ctx.sessions.get(&session_id).map(|s| s.value().clone()) else {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({
+            "error": "Session not found",
+            "session_id": session_id
+        })));
+    };
+
+    let master_key = ctx.main.get_master_key();
+    let memories: Vec<serde_json::Value> = ctx.main
+        .session_timeline(&session_id)
+        .into_iter()
+        .map(|m| serde_json::json!({
+            "id": m.id,
+            "content": m.access_content(master_key.as_deref()).unwrap_or_else(|_| "<decryption failed>".to_string()),
+            "cues": m.cues,
+            "created_at": m.created_at,
+            "metadata": m.metadata,
+        }))
+        .collect();
+
+    (StatusCode::OK, Json(serde_json::json!({
+        "session": session,
+        "memories": memories
+    })))
+}
+
+/// Queue a (re-)embedding of one memory with the project's embedding provider
+async fn embed_memory(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Path(memory_id): Path<String>,
 ) -> (StatusCode, Json<serde_json::Value>) {
     let project_id = match extract_project_id(&headers) {
         Ok(id) => id,
         Err(e) => return e,
     };
 
+    let EngineState { mt_engine, read_only, job_queue, .. } = state;
+    if read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+
+    let ctx = match mt_engine.get_or_create_project(project_id.clone()) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+    if ctx.main.get_memory(&memory_id).is_none() {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({
+            "error": "Memory not found",
+            "memory_id": memory_id
+        })));
+    }
+
+    let provider = match mt_engine.embedding_provider(&project_id) {
+        Ok(Some(p)) => p,
+        Ok(None) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+            "error": "No embedding provider configured for this project"
+        }))),
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+
+    job_queue.enqueue(Job::EmbedMemories {
+        project_id,
+        memory_ids: vec![memory_id.clone()],
+        force: true,
+    }).await;
+
+    (StatusCode::ACCEPTED, Json(serde_json::json!({
+        "status": "queued",
+        "memory_id": memory_id,
+        "provider": provider.name()
+    })))
+}
+
+/// Backfill embeddings for every memory of a project (`?force=true` re-embeds all)
+async fn embed_project(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+    axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, job_queue, .. } = state;
+    if read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    let provider = match mt_engine.embedding_provider(&project_id) {
+        Ok(Some(p)) => p,
+        Ok(None) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+            "error": "No embedding provider configured for this project"
+        }))),
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+    let ctx = match mt_engine.get_or_create_project(project_id.clone()) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+
+    let force = params.get("force").map(|v| v == "true").unwrap_or(false);
+    let memory_ids: Vec<String> = ctx.main.memory_ids_by_age()
+        .into_iter()
+        .filter(|id| force || !ctx.main.vector_index().contains(id))
+        .collect();
+    let queued = memory_ids.len();
+
+    job_queue.enqueue(Job::EmbedMemories {
+        project_id: project_id.clone(),
+        memory_ids,
+        force,
+    }).await;
+
+    (StatusCode::ACCEPTED, Json(serde_json::json!({
+        "status": "queued",
+        "project_id": project_id,
+        "memories": queued,
+        "provider": provider.name()
+    })))
+}
+
+async fn get_stats(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id_opt = extract_project_id_optional(&headers);
     let EngineState { mt_engine, .. } = state;
+
+    if let Some(project_id) = project_id_opt {
         let ctx = match mt_engine.get_or_create_project(project_id) {
             Ok(c) => c,
             Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
         };
-        let cue_lower = cue.to_lowercase();
-        
-        // Helper: count main memories that have token in cues but NOT canonical
-        let count_affected = |token: &str, canonical: &str| -> usize {
-            let token_lower = token.to_lowercase();
-            let canonical_lower = canonical.to_lowercase();
-            let mut count = 0;
-            
-            for ref_multi in ctx.main.get_memories().iter() {
-                let memory = ref_multi.value();
-                let cues_lower: Vec<String> = memory.cues.iter().map(|c| c.to_lowercase()).collect();
-                if cues_lower.contains(&token_lower) && !cues_lower.contains(&canonical_lower) {
-                    count += 1;
-                }
-            }
-            count
-        };
-        
-        // 1. OUTGOING: What does this token trigger?
-        let outgoing_results = ctx.lexicon.recall_fast(vec![cue_lower.clone()], 100);
-        let outgoing: Vec<LexiconEntry> = outgoing_results.iter().map(|r| {
-            let token = r.metadata.get("cues")
-                .and_then(|v| v.as_array())
-                .and_then(|arr| arr.first())
-                .and_then(|v| v.as_str())
-                .unwrap_or(&cue_lower)
-                .to_string();
-            
-            let affected = count_affected(&token, &r.content);
-            
-            LexiconEntry {
-                memory_id: r.memory_id.clone(),
-                content: r.content.clone(),
-                token,
-                reinforcement_score: r.reinforcement_score,
-                created_at: r.created_at,
-                affected_memories_count: affected,
-            }
-        }).collect();
-        
-        // 2. INCOMING: What tokens map to this canonical cue?
-        let mut incoming: Vec<LexiconEntry> = Vec::new();
-        let key_lex = ctx.lexicon.get_master_key();
-        for ref_multi in ctx.lexicon.get_memories().iter() {
-            let memory = ref_multi.value();
-            let content = memory.access_content(key_lex.as_deref()).unwrap_or_default();
-            if content.to_lowercase() == cue_lower {
-                for token in &memory.cues {
-                    let affected = count_affected(token, &content);
-                    incoming.push(LexiconEntry {
-                        memory_id: memory.id.clone(),
-                        content: content.clone(),
-                        token: token.clone(),
-                        reinforcement_score: memory.stats.get_reinforcement_count() as f64,
-                        created_at: memory.created_at,
-                        affected_memories_count: affected,
-                    });
-                }
-            }
-        }
-        
-        (StatusCode::OK, Json(serde_json::json!(LexiconInspectResponse {
-            cue: cue_lower,
-            outgoing,
+        let stats = ctx.main.get_stats();
+        (StatusCode::OK, Json(serde_json::Value::Object(stats.into_iter().collect())))
+    } else {
+        // Global stats
+        let stats = mt_engine.get_global_stats();
+        (StatusCode::OK, Json(serde_json::json!(stats)))
+    }
+}
+
+/// Approximate memory footprint of a project, with the heaviest cues listed first
+async fn memory_breakdown(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+    let limit = params.get("limit")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(50);
+
+    let EngineState { mt_engine, .. } = state;
+
+    let ctx = match mt_engine.get_or_create_project(project_id.clone()) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+
+    let breakdown = tokio::task::spawn_blocking(move || {
+        let engine_summary = |memories: usize, payload: usize, index: usize, co: usize| serde_json::json!({
+            "memories": memories,
+            "payload_bytes": payload,
+            "index_bytes": index,
+            "co_occurrence_entries": co,
+        });
+
+        serde_json::json!({
+            "project_id": project_id,
+            "main": engine_summary(
+                ctx.main.total_memories(),
+                ctx.main.payload_heap_bytes(),
+                ctx.main.index_heap_bytes(),
+                ctx.main.co_occurrence_entries(),
+            ),
+            "aliases": engine_summary(
+                ctx.aliases.total_memories(),
+                ctx.aliases.payload_heap_bytes(),
+                ctx.aliases.index_heap_bytes(),
+                ctx.aliases.co_occurrence_entries(),
+            ),
+            "lexicon": engine_summary(
+                ctx.lexicon.total_memories(),
+                ctx.lexicon.payload_heap_bytes(),
+                ctx.lexicon.index_heap_bytes(),
+                ctx.lexicon.co_occurrence_entries(),
+            ),
+            "top_cues": ctx.main.cue_memory_breakdown(limit),
+        })
+    }).await.unwrap();
+
+    (StatusCode::OK, Json(breakdown))
+}
+
+/// Memories recall has never returned, oldest first
+async fn memory_analytics(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+    let limit = params.get("limit")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(50);
+    let min_age_secs = params.get("min_age_secs")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+
+    let EngineState { mt_engine, .. } = state;
+
+    let ctx = match mt_engine.get_or_create_project(project_id.clone()) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+
+    let analytics = tokio::task::spawn_blocking(move || ctx.main.memory_analytics(limit, min_age_secs)).await.unwrap();
+
+    let mut body = serde_json::json!(analytics);
+    body["project_id"] = serde_json::json!(project_id);
+    (StatusCode::OK, Json(body))
+}
+
+/// Top cues, singleton and never-recalled cues, and the IDF histogram of a project
+async fn cue_analytics(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+    let limit = params.get("limit")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(50);
+
+    let EngineState { mt_engine, .. } = state;
+
+    let ctx = match mt_engine.get_or_create_project(project_id.clone()) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+
+    let analytics = tokio::task::spawn_blocking(move || ctx.main.cue_analytics(limit)).await.unwrap();
+
+    let mut body = serde_json::json!(analytics);
+    body["project_id"] = serde_json::json!(project_id);
+    (StatusCode::OK, Json(body))
+}
+
+/// Compact inverted indexes for one project (X-Project-ID) or for every loaded project
+async fn compact_indexes(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id_opt = extract_project_id_optional(&headers);
+    let EngineState { mt_engine, .. } = state;
+
+    let reports = tokio::task::spawn_blocking(move || {
+        match project_id_opt {
+            Some(project_id) => match mt_engine.get_project(&project_id) {
+                Some(ctx) => {
+                    let mut reports = HashMap::new();
+                    reports.insert(project_id, ctx.compact_indexes());
+                    Ok(serde_json::json!(reports))
+                }
+                None => Err(format!("Project '{}' is not loaded", project_id)),
+            },
+            None => Ok(serde_json::json!(mt_engine.compact_all())),
+        }
+    }).await.unwrap();
+
+    match reports {
+        Ok(projects) => (StatusCode::OK, Json(serde_json::json!({ "status": "compacted", "projects": projects }))),
+        Err(e) => (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+/// Query the audit log. Scoped to the X-Project-ID project when no `project_id` is given.
+async fn audit_log(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    axum::extract::Query(mut query): axum::extract::Query<AuditQuery>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, .. } = state;
+
+    if query.project_id.is_none() {
+        query.project_id = extract_project_id_optional(&headers);
+    }
+    query.limit = Some(query.limit.unwrap_or(1000));
+
+    match mt_engine.audit().query(&query) {
+        Ok(entries) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "count": entries.len(),
+                "entries": entries
+            })),
+        ),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+/// Get job/ingestion progress for a project or globally
+async fn jobs_status(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id_opt = extract_project_id_optional(&headers);
+    let EngineState { job_queue, .. } = state;
+    
+    let (status, Json(mut body)) = if let Some(project_id) = project_id_opt {
+        if let Some(session) = job_queue.get_session(&project_id) {
+            let progress = session.get_progress();
+            (StatusCode::OK, Json(serde_json::json!(progress)))
+        } else {
+            // No active session - return idle status
+            (StatusCode::OK, Json(serde_json::json!({
+                "phase": "idle",
+                "writes_completed": 0,
+                "writes_total": 0,
+                "propose_cues_completed": 0,
+                "propose_cues_total": 0,
+                "train_lexicon_completed": 0,
+                "train_lexicon_total": 0,
+                "update_graph_completed": 0,
+                "update_graph_total": 0
+            })))
+        }
+    } else {
+        // Global progress
+        let progress = job_queue.get_global_progress();
+        (StatusCode::OK, Json(serde_json::json!(progress)))
+    };
+    // The worker pool is shared, so its queues are server-wide either way
+    body["queues"] = serde_json::json!(job_queue.queue_depths());
+    body["failed"] = serde_json::json!(job_queue.failed_jobs().len());
+    (status, Json(body))
+}
+
+/// Open an explicit ingestion session: the project's buffered background
+/// jobs wait for commit or abort instead of the auto-flush
+async fn begin_ingest_session(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<serde_json::Value>) {
+    if state.read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    match state.job_queue.session_manager.begin(&project_id).await {
+        Ok(id) => (
+            StatusCode::CREATED,
+            Json(serde_json::json!({"id": id, "project_id": project_id, "status": "open"})),
+        ),
+        Err(e) => (StatusCode::CONFLICT, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+/// Close an ingestion session and run its buffered jobs. Poll
+/// `GET /jobs/status` for their progress.
+async fn commit_ingest_session(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    if state.read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    if state.job_queue.session_manager.commit(&project_id, &session_id) {
+        (StatusCode::ACCEPTED, Json(serde_json::json!({"id": session_id, "status": "committed"})))
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": format!("No open ingestion session '{}' for project '{}'", session_id, project_id)})),
+        )
+    }
+}
+
+/// Close an ingestion session and drop its buffered jobs. Memories it wrote
+/// stay.
+async fn abort_ingest_session(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    if state.read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    match state.job_queue.session_manager.abort(&project_id, &session_id).await {
+        Some(discarded) => (
+            StatusCode::OK,
+            Json(serde_json::json!({"id": session_id, "status": "aborted", "discarded_jobs": discarded})),
+        ),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": format!("No open ingestion session '{}' for project '{}'", session_id, project_id)})),
+        ),
+    }
+}
+
+/// Jobs that failed on every attempt, for one project or all of them
+async fn failed_jobs(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id_opt = extract_project_id_optional(&headers);
+    let failed: Vec<_> = state
+        .job_queue
+        .failed_jobs()
+        .into_iter()
+        .filter(|job| project_id_opt.is_none() || project_id_opt.as_deref() == Some(job.project_id.as_str()))
+        .collect();
+    (StatusCode::OK, Json(serde_json::json!({"count": failed.len(), "jobs": failed})))
+}
+
+/// Recent recalls over the slow-query threshold with their stage timings, newest first
+async fn slow_queries(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id_opt = extract_project_id_optional(&headers);
+    let limit = params.get("limit")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(usize::MAX);
+    let log = &state.metrics.slow_queries;
+    let queries = log.recent(project_id_opt.as_deref(), limit);
+    (StatusCode::OK, Json(serde_json::json!({
+        "threshold_ms": log.threshold_ms(),
+        "capacity": log.capacity(),
+        "count": queries.len(),
+        "queries": queries
+    })))
+}
+
+/// Queue a dead-lettered job again with a fresh set of attempts
+async fn retry_job(
+    State(state): State<EngineState>,
+    Path(job_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    if state.read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+    if state.job_queue.retry_job(&job_id) {
+        (StatusCode::OK, Json(serde_json::json!({"status": "queued", "id": job_id})))
+    } else {
+        (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": format!("No failed job '{}'", job_id)})))
+    }
+}
+
+/// Cancel a job that hasn't started, or discard a failed one
+async fn cancel_job(
+    State(state): State<EngineState>,
+    Path(job_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    use crate::jobs::CancelOutcome;
+
+    if state.read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+    match state.job_queue.cancel_job(&job_id) {
+        CancelOutcome::Cancelled => (StatusCode::OK, Json(serde_json::json!({"status": "cancelled", "id": job_id}))),
+        CancelOutcome::Discarded => (StatusCode::OK, Json(serde_json::json!({"status": "discarded", "id": job_id}))),
+        CancelOutcome::Running => (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({"error": format!("Job '{}' is already running", job_id)})),
+        ),
+        CancelOutcome::NotFound => (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": format!("No job '{}'", job_id)}))),
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RecallFeedbackRequest {
+    /// Returned memories that actually helped
+    #[serde(default)]
+    pub useful: Vec<String>,
+    /// Apply a small, decaying penalty to the other returned memories
+    #[serde(default = "default_true")]
+    pub penalize_rest: bool,
+}
+
+/// Supervised reinforcement: reinforce the memories of a recall that were useful
+/// and demote the rest. Each recall accepts feedback once, within an hour.
+async fn recall_feedback(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Path(recall_id): Path<String>,
+    Json(req): Json<RecallFeedbackRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let EngineState { mt_engine, read_only, .. } = state;
+    if read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+    let ctx = match mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+
+    let Some(record) = ctx.take_recall(&recall_id) else {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Recall not found, expired or already rated"})));
+    };
+    let penalty = if req.penalize_rest { RECALL_FEEDBACK_PENALTY } else { 0.0 };
+    let (reinforced, penalized) = ctx.main.apply_recall_feedback(&record.memory_ids, &record.cues, &req.useful, penalty);
+    let ignored: Vec<&String> = req.useful.iter().filter(|id| !record.memory_ids.contains(id)).collect();
+
+    (StatusCode::OK, Json(serde_json::json!({
+        "recall_id": recall_id,
+        "reinforced": reinforced,
+        "penalized": penalized,
+        "ignored": ignored,
+    })))
+}
+
+/// Resolve a citation marker of a grounded recall to the memory it cites.
+/// `citation` is the number inside the marker (`3` or `[3]`).
+async fn resolve_citation(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Path((recall_id, citation)): Path<(String, String)>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let Ok(number) = citation.trim_start_matches('[').trim_end_matches(']').parse::<usize>() else {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Citation must be a number such as 1 or [1]"})));
+    };
+
+    let EngineState { mt_engine, .. } = state;
+    let ctx = match mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+
+    let Some(record) = ctx.get_recall(&recall_id) else {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Recall not found or expired"})));
+    };
+    let Some(memory_id) = number.checked_sub(1).and_then(|idx| record.memory_ids.get(idx)) else {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({
+            "error": "Citation not found",
+            "citations": record.memory_ids.len()
+        })));
+    };
+    match ctx.main.get_memory(memory_id) {
+        Some(memory) => (StatusCode::OK, Json(serde_json::json!({
+            "recall_id": recall_id,
+            "citation": crate::grounding::GroundingEngine::citation_marker(number),
+            "memory_id": memory_id,
+            "memory": memory
+        }))),
+        None => (StatusCode::NOT_FOUND, Json(serde_json::json!({
+            "error": "Cited memory no longer exists",
+            "memory_id": memory_id
+        }))),
+    }
+}
+
+async fn recall_grounded(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Json(req): Json<RecallGroundedRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = if let Some(ref projects) = req.projects {
+        projects.first().cloned().unwrap_or_else(|| {
+             headers.get("X-Project-ID").and_then(|v| v.to_str().ok()).unwrap_or("default").to_string()
+        })
+    } else {
+        match extract_project_id(&headers) {
+            Ok(id) => id,
+            Err(e) => return e,
+        }
+    };
+
+    let ctx = match state.mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+    let response = grounded_recall(&ctx, req, state.signing_key.as_ref().map(|k| k.as_slice()));
+    (StatusCode::OK, Json(serde_json::json!(response)))
+}
+
+/// Recall, pack the results into the token budget and sign the context block
+fn grounded_recall(ctx: &ProjectContext, req: RecallGroundedRequest, signing_key: Option<&[u8]>) -> RecallGroundedResponse {
+    use std::time::Instant;
+    use crate::grounding::{GroundingEngine, create_grounding_proof};
+    use crate::tokenizer::tokenizer_for_model;
+
+    let start = Instant::now();
+
+    // 1. Standard CueMap Recall
+    let (resolved, _lexicon_memory_ids, tokens) = ctx.resolve_cues_from_text(&req.query_text, false);
+    let mut normalized_cues = Vec::new();
+    for cue in &resolved {
+        let normalized = ctx.normalize_cue(cue);
+        normalized_cues.push(normalized);
+    }
+
+    let expanded_cues = if req.disable_alias_expansion {
+        normalized_cues.into_iter().map(|c| (c, 1.0)).collect()
+    } else {
+        // tokens were computed in step 1, reuse them!
+        ctx.expand_query_cues(normalized_cues, &tokens)
+    };
+
+    let heatmap = ctx.market_heatmap.read().ok();
+
+    ctx.main.record_cue_hits(&expanded_cues);
+    let results = ctx.main.recall_weighted(
+        expanded_cues.clone(), 
+        req.limit.max(20),
+        &RecallParams {
+            auto_reinforce: req.auto_reinforce,
+            min_intersection: req.min_intersection,
+            explain: true,
+            disable_pattern_completion: req.disable_pattern_completion,
+            disable_salience_bias: req.disable_salience_bias,
+            disable_systems_consolidation: req.disable_systems_consolidation,
+            heatmap: heatmap.as_ref().map(|h| h.weights()),
+            ..RecallParams::default()
+        },
+    );
+    drop(heatmap);
+
+    // 2. Apply Budgeting Logic
+    let tokenizer = tokenizer_for_model(req.model.as_deref());
+    let (selected, excluded, context_block) = GroundingEngine::select_memories(
+        req.query_text.clone(),
+        resolved.clone(),
+        expanded_cues.clone(),
+        results,
+        req.token_budget,
+        tokenizer.as_ref(),
+    );
+    crate::telemetry::record_results(selected.len());
+
+    // 3. Create Proof (the trace id doubles as the recall id for citations and feedback)
+    let recall_id = ctx.log_recall(
+        selected.iter().map(|item| item.memory_id.clone()).collect(),
+        expanded_cues.iter().map(|(c, _)| c.clone()).collect(),
+    );
+    let mut proof = create_grounding_proof(
+        recall_id.clone(),
+        req.query_text,
+        resolved,
+        expanded_cues,
+        req.token_budget,
+        selected,
+        excluded,
+    );
+    proof.tokenizer = tokenizer.name().to_string();
+    proof.tokens_used = if context_block.is_empty() { 0 } else { tokenizer.count_tokens(&context_block) };
+
+    let elapsed = start.elapsed();
+
+    // 4. Sign Context
+    let signature = if let Some(key) = signing_key {
+        let crypto = crate::crypto::CryptoEngine::new(key.to_vec());
+        crypto.sign(&context_block)
+    } else {
+         "error: CUEMAP_SECRET_KEY not set".to_string()
+    };
+
+    RecallGroundedResponse {
+        verified_context: context_block,
+        recall_id,
+        proof,
+        engine_latency_ms: elapsed.as_secs_f64() * 1000.0,
+        signature,
+    }
+}
+
+/// Request for POST /answer
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AnswerRequest {
+    pub question: String,
+    /// Tokens of recalled context in the prompt
+    #[serde(default = "default_token_budget")]
+    pub token_budget: u32,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+    /// Longest answer, in tokens
+    #[serde(default = "default_answer_max_tokens")]
+    pub max_tokens: usize,
+    #[serde(default = "default_true")]
+    pub auto_reinforce: bool,
+}
+
+fn default_answer_max_tokens() -> usize {
+    512
+}
+
+/// Grounded recall, then an answer from the project's LLM written from
+/// exactly the context block the proof describes
+async fn answer(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Json(req): Json<AnswerRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+    if req.question.trim().is_empty() {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "question must not be empty"})));
+    }
+    let llm = state.mt_engine.llm_client(&project_id);
+    if !llm.config().enabled {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+            "error": format!("No LLM enabled for project '{}' (see PUT /projects/{}/llm)", project_id, project_id)
+        })));
+    }
+    let ctx = match state.mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+
+    // The context is counted with the answering model's tokenizer
+    let recall = RecallGroundedRequest {
+        query_text: req.question.clone(),
+        token_budget: req.token_budget,
+        model: Some(llm.config().model.clone()),
+        limit: req.limit,
+        projects: None,
+        auto_reinforce: req.auto_reinforce,
+        disable_pattern_completion: false,
+        disable_salience_bias: false,
+        disable_systems_consolidation: false,
+        min_intersection: None,
+        disable_alias_expansion: true,
+    };
+    let grounded = grounded_recall(&ctx, recall, state.signing_key.as_ref().map(|k| k.as_slice()));
+    if grounded.proof.selected.is_empty() {
+        return (StatusCode::OK, Json(serde_json::json!({
+            "answer": null,
+            "reason": "No memories matched the question",
+            "recall_id": grounded.recall_id,
+            "proof": grounded.proof,
+        })));
+    }
+
+    let start = std::time::Instant::now();
+    let answer = match llm.answer(&req.question, &grounded.verified_context, req.max_tokens).await {
+        Ok(answer) => answer,
+        Err(e) => return (StatusCode::BAD_GATEWAY, Json(serde_json::json!({
+            "error": format!("LLM call failed: {}", e),
+            "recall_id": grounded.recall_id,
+        }))),
+    };
+
+    (StatusCode::OK, Json(serde_json::json!({
+        "answer": answer,
+        "cited": grounded.proof.citations_in(&answer),
+        "model": llm.name(),
+        "recall_id": grounded.recall_id,
+        "verified_context": grounded.verified_context,
+        "signature": grounded.signature,
+        "prompt": {
+            "system": crate::llm::ANSWER_PROMPT,
+            "user": crate::llm::answer_prompt(&req.question, &grounded.verified_context),
+        },
+        "proof": grounded.proof,
+        "engine_latency_ms": grounded.engine_latency_ms,
+        "llm_latency_ms": start.elapsed().as_secs_f64() * 1000.0,
+    })))
+}
+
+async fn list_projects(
+    State(state): State<EngineState>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, .. } = state;
+    let mut projects = mt_engine.list_projects();
+    projects.extend(mt_engine.archived_projects());
+    (StatusCode::OK, Json(serde_json::json!(projects)))
+}
+
+async fn create_project(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Json(req): Json<CreateProjectRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, .. } = state;
+    if read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+
+    if !validate_project_id(&req.project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    // Creating is idempotent, except that an existing project keeps its storage backend
+    if let Some(existing) = mt_engine.project_storage(&req.project_id) {
+        if existing != req.storage {
+            return (
+                StatusCode::CONFLICT,
+                Json(serde_json::json!({
+                    "error": "Project already exists with another storage backend",
+                    "storage": existing,
+                })),
+            );
+        }
+    }
+    
+    match mt_engine.create_project(req.project_id.clone(), req.storage) {
+        Ok(_) => {
+            mt_engine.audit().record(AuditEntry::new(audit_actor(&headers), AuditAction::ProjectCreated, &req.project_id));
+            (
+                StatusCode::CREATED,
+                Json(serde_json::json!({
+                    "status": "created", 
+                    "project_id": req.project_id,
+                    "storage": req.storage,
+                })),
+            )
+        },
+        Err(e) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({"error": e})),
+        ),
+    }
+}
+
+async fn delete_project(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Path(project_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, .. } = state;
+    let deleted = mt_engine.delete_project(&project_id);
+    if deleted {
+        mt_engine.audit().record(AuditEntry::new(audit_actor(&headers), AuditAction::ProjectDeleted, &project_id));
+        (
+            StatusCode::OK,
+            Json(serde_json::json!({"status": "deleted", "project_id": project_id})),
+        )
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Project not found"})),
+        )
+    }
+}
+
+/// Save a project and free its RAM; it is reloaded from the snapshot on next access
+async fn unload_project(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, .. } = state;
+    if read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+    if mt_engine.get_project(&project_id).is_none() {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Project not loaded"})));
+    }
+
+    let id = project_id.clone();
+    let engine = mt_engine.clone();
+    match tokio::task::spawn_blocking(move || engine.unload_project(&id)).await {
+        Ok(Ok(true)) => (StatusCode::OK, Json(serde_json::json!({"status": "unloaded", "project_id": project_id}))),
+        Ok(Ok(false)) => (StatusCode::CONFLICT, Json(serde_json::json!({
+            "error": "Project is in use; try again once its requests and jobs finish",
+            "project_id": project_id
+        }))),
+        Ok(Err(e)) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))),
+    }
+}
+
+/// Snapshot generations retained for point-in-time restore, newest first
+async fn list_project_generations(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, .. } = state;
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+    let generations = mt_engine.list_generations(&project_id);
+    if generations.is_empty() && mt_engine.project_storage(&project_id).is_none() {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Project not found"})));
+    }
+    (StatusCode::OK, Json(serde_json::json!({
+        "project_id": project_id,
+        "generations": generations,
+    })))
+}
+
+/// Roll a project back to one of its snapshot generations
+async fn restore_project_generation(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Path(project_id): Path<String>,
+    Json(req): Json<RestoreGenerationRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, .. } = state;
+    if read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+    if !mt_engine.list_generations(&project_id).iter().any(|g| g.generation == req.generation) {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({
+            "error": format!("Snapshot generation '{}' not found", req.generation),
+            "project_id": project_id
+        })));
+    }
+
+    let engine = mt_engine.clone();
+    let id = project_id.clone();
+    let generation = req.generation.clone();
+    let (ctx, previous) = match tokio::task::spawn_blocking(move || engine.restore_generation(&id, &generation)).await {
+        Ok(Ok(restored)) => restored,
+        Ok(Err(e)) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))),
+    };
+
+    mt_engine.audit().record(
+        AuditEntry::new(audit_actor(&headers), AuditAction::ProjectImported, &project_id)
+            .with_detail(serde_json::json!({"format": "generation", "generation": req.generation})),
+    );
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "status": "restored",
+            "project_id": project_id,
+            "generation": req.generation,
+            "previous_generation": previous,
+            "total_memories": ctx.main.total_memories(),
+            "total_cues": ctx.main.total_cues()
+        })),
+    )
+}
+
+async fn set_project_watch_dir(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+    Json(req): Json<SetWatchDirRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, agent_manager, .. } = state;
+    
+    if read_only {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Read-only mode: modifications are not allowed"
+            })),
+        );
+    }
+    
+    match mt_engine.set_project_watch_dir(&project_id, Some(req.watch_dir.clone())) {
+        Ok(_) => {
+            // Immediately start/update the agent
+            let agent_config = crate::agent::AgentConfig {
+                project_id: project_id.clone(),
+                watch_dir: req.watch_dir.clone(),
+                throttle_ms: 100, // Small throttle to prevent CPU pinning
+                state_file: Some(std::path::PathBuf::from(format!("./snapshots/{}_agent_state.json", project_id))),
+                filter: Default::default(),
+                git: Default::default(),
+                chunking: mt_engine.get_project_chunking(&project_id).unwrap_or_default(),
+                extract_facts: false,
+            };
+            
+            // Spawn the starting of the agent securely
+            let project_id_clone = project_id.clone();
+            tokio::spawn(async move {
+                agent_manager.start_agent(&project_id_clone, agent_config).await;
+            });
+
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "status": "updated",
+                    "project_id": project_id
+                })),
+            )
+        },
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": e})),
+        ),
+    }
+}
+
+/// Server-wide agent filter defaults and every running agent's effective rules
+async fn agent_config_body(agent_manager: &crate::agent::manager::AgentManager) -> serde_json::Value {
+    let agents: Vec<serde_json::Value> = agent_manager
+        .list_agents()
+        .await
+        .into_iter()
+        .map(|(key, config)| serde_json::json!({
+            "agent": key,
+            "project_id": config.project_id,
+            "watch_dir": config.watch_dir,
+            "filter": config.filter,
+        }))
+        .collect();
+    serde_json::json!({
+        "defaults": agent_manager.defaults().await,
+        "agents": agents,
+    })
+}
+
+async fn get_agent_config(
+    State(state): State<EngineState>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    (StatusCode::OK, Json(agent_config_body(&state.agent_manager).await))
+}
+
+async fn set_agent_config(
+    State(state): State<EngineState>,
+    Json(req): Json<crate::agent::filter::IngestFilterConfig>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { read_only, agent_manager, .. } = state;
+
+    if read_only {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Read-only mode: modifications are not allowed"
+            })),
+        );
+    }
+
+    // Running agents are restarted under the new rules
+    match agent_manager.set_defaults(req).await {
+        Ok(()) => (StatusCode::OK, Json(agent_config_body(&agent_manager).await)),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+// Multi-tenant Alias Handlers
+
+async fn set_project_snapshot_encryption(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+    Json(req): Json<SetSnapshotEncryptionRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, .. } = state;
+    
+    if read_only {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Read-only mode: modifications are not allowed"
+            })),
+        );
+    }
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+    
+    match mt_engine.set_project_snapshot_encryption(&project_id, req.enabled) {
+        Ok(_) => {
+            // Persist immediately so the on-disk format matches the new setting
+            if mt_engine.get_project(&project_id).is_some() {
+                if let Err(e) = mt_engine.save_project(&project_id) {
+                    return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e})));
+                }
+            }
+
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "status": "updated",
+                    "project_id": project_id,
+                    "encrypt_snapshots": req.enabled
+                })),
+            )
+        },
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": e})),
+        ),
+    }
+}
+
+async fn get_project_schedule(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, .. } = state;
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    match mt_engine.get_project_schedule(&project_id) {
+        Ok(schedule) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "project_id": project_id,
+                "schedule": schedule
+            })),
+        ),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+async fn set_project_schedule(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+    Json(schedule): Json<ProjectSchedule>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, .. } = state;
+
+    if read_only {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Read-only mode: modifications are not allowed"
+            })),
+        );
+    }
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    match mt_engine.set_project_schedule(&project_id, schedule.clone()) {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "status": "updated",
+                "project_id": project_id,
+                "schedule": schedule
+            })),
+        ),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+async fn get_project_quota(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, .. } = state;
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    match mt_engine.get_project_quota(&project_id) {
+        Ok(quota) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "project_id": project_id,
+                "quota": quota,
+                "usage": mt_engine.quota_usage(&project_id)
+            })),
+        ),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+async fn set_project_quota(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+    Json(quota): Json<ProjectQuota>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, .. } = state;
+
+    if read_only {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Read-only mode: modifications are not allowed"
+            })),
+        );
+    }
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    match mt_engine.set_project_quota(&project_id, quota.clone()) {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "status": "updated",
+                "project_id": project_id,
+                "quota": quota
+            })),
+        ),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+/// Embedding settings in effect for a project; `overridden` is false when it follows the server default
+async fn get_project_embeddings(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, .. } = state;
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    let overridden = mt_engine.load_project_meta(&project_id)
+        .map(|meta| meta.embeddings.is_some())
+        .unwrap_or(false);
+    let mut config = mt_engine.get_project_embeddings(&project_id);
+    if config.api_key.is_some() {
+        config.api_key = Some("********".to_string());
+    }
+    let ctx = mt_engine.get_project(&project_id);
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "project_id": project_id,
+            "embeddings": config,
+            "overridden": overridden,
+            "vectors": ctx.as_ref().map(|c| c.main.vector_index().len()).unwrap_or(0),
+            "dimensions": ctx.as_ref().map(|c| c.main.vector_index().dims()).unwrap_or(0)
+        })),
+    )
+}
+
+/// Override the embedding provider of a project (`null` restores the server default)
+async fn set_project_embeddings(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+    Json(config): Json<Option<EmbeddingConfig>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, .. } = state;
+
+    if read_only {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Read-only mode: modifications are not allowed"
+            })),
+        );
+    }
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    match mt_engine.set_project_embeddings(&project_id, config) {
+        Ok(effective) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "status": "updated",
+                "project_id": project_id,
+                "strategy": effective.strategy
+            })),
+        ),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+/// LLM settings in effect for a project; `overridden` is false when it follows the server default
+async fn get_project_llm(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, .. } = state;
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    let overridden = mt_engine.load_project_meta(&project_id)
+        .map(|meta| meta.llm.is_some())
+        .unwrap_or(false);
+    let client = mt_engine.llm_client(&project_id);
+    let mut config = client.config().clone();
+    if config.api_key.is_some() {
+        config.api_key = Some("********".to_string());
+    }
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "project_id": project_id,
+            "llm": config,
+            "overridden": overridden,
+            "name": client.name()
+        })),
+    )
+}
+
+/// Override the LLM of a project (`null` restores the server default)
+async fn set_project_llm(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+    Json(config): Json<Option<LlmConfig>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, .. } = state;
+
+    if read_only {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Read-only mode: modifications are not allowed"
+            })),
+        );
+    }
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    match mt_engine.set_project_llm(&project_id, config) {
+        Ok(effective) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "status": "updated",
+                "project_id": project_id,
+                "provider": effective.provider,
+                "model": effective.model
+            })),
+        ),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+async fn get_project_rerank(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, .. } = state;
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "project_id": project_id,
+            "rerank": mt_engine.get_project_rerank(&project_id)
+        })),
+    )
+}
+
+/// Set the rerank stage of a project's recalls
+async fn set_project_rerank(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+    Json(config): Json<RerankConfig>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, .. } = state;
+
+    if read_only {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Read-only mode: modifications are not allowed"
+            })),
+        );
+    }
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    match mt_engine.set_project_rerank(&project_id, config) {
+        Ok(rerank) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "status": "updated",
+                "project_id": project_id,
+                "rerank": rerank
+            })),
+        ),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+async fn get_project_taxonomy(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, .. } = state;
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    match mt_engine.get_project_taxonomy(&project_id) {
+        Ok(taxonomy) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "project_id": project_id,
+                "taxonomy": taxonomy
+            })),
+        ),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+/// Replace the taxonomy new cues of a project are checked against
+async fn set_project_taxonomy(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+    Json(taxonomy): Json<Taxonomy>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, .. } = state;
+
+    if read_only {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Read-only mode: modifications are not allowed"
+            })),
+        );
+    }
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    match mt_engine.set_project_taxonomy(&project_id, taxonomy) {
+        Ok(taxonomy) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "status": "updated",
+                "project_id": project_id,
+                "taxonomy": taxonomy
+            })),
+        ),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+async fn get_project_normalization(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, .. } = state;
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    match mt_engine.get_project_normalization(&project_id) {
+        Ok(normalization) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "project_id": project_id,
+                "normalization": normalization
+            })),
+        ),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+/// Replace the cue normalization pipeline of a project and re-normalize the
+/// cues of its memories
+async fn set_project_normalization(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+    Json(normalization): Json<NormalizationConfig>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, .. } = state;
+
+    if read_only {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Read-only mode: modifications are not allowed"
+            })),
+        );
+    }
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    let id = project_id.clone();
+    let result = tokio::task::spawn_blocking(move || mt_engine.set_project_normalization(&id, normalization)).await.unwrap();
+    match result {
+        Ok((normalization, renormalized)) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "status": "updated",
+                "project_id": project_id,
+                "normalization": normalization,
+                "renormalized_memories": renormalized
+            })),
+        ),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ValidateCuesRequest {
+    pub cues: Vec<String>,
+    /// Check against this taxonomy instead of the project's, e.g. before a PUT
+    #[serde(default)]
+    pub taxonomy: Option<Taxonomy>,
+}
+
+/// Dry-run: which cues a write would keep or drop. Nothing is stored.
+async fn validate_project_cues(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+    Json(req): Json<ValidateCuesRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, .. } = state;
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    let taxonomy = match req.taxonomy {
+        Some(mut taxonomy) => match taxonomy.validate() {
+            Ok(()) => {
+                for pattern in &mut taxonomy.forbidden_patterns {
+                    *pattern = pattern.trim().to_lowercase();
+                }
+                taxonomy
+            }
+            Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+        },
+        None => match mt_engine.get_project_taxonomy(&project_id) {
+            Ok(taxonomy) => taxonomy,
+            Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+        },
+    };
+
+    // Same normalization as a write, without loading or creating the project
+    let ctx = mt_engine.get_project_or_scratch(&project_id);
+    let normalized_cues: Vec<String> = req
+        .cues
+        .iter()
+        .map(|cue| ctx.normalize_cue(cue))
+        .collect();
+    let report = validate_cues(normalized_cues, &taxonomy);
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "project_id": project_id,
+            "accepted": report.accepted,
+            "rejected": report.rejected
+        })),
+    )
+}
+
+/// Acronyms used to rewrite recall queries, learned from ingested text or added by hand
+async fn get_project_acronyms(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, .. } = state;
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    match mt_engine.get_project_acronyms(&project_id) {
+        Ok(acronyms) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "project_id": project_id,
+                "acronyms": acronyms
+            })),
+        ),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AddAcronymRequest {
+    pub acronym: String,
+    pub expansion: String,
+}
+
+async fn add_project_acronym(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+    Json(req): Json<AddAcronymRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, .. } = state;
+
+    if read_only {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Read-only mode: modifications are not allowed"
+            })),
+        );
+    }
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    match mt_engine.set_project_acronym(&project_id, &req.acronym, &req.expansion) {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "status": "updated",
+                "project_id": project_id,
+                "acronym": req.acronym.trim().to_lowercase()
+            })),
+        ),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+async fn remove_project_acronym(
+    State(state): State<EngineState>,
+    Path((project_id, acronym)): Path<(String, String)>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, .. } = state;
+
+    if read_only {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Read-only mode: modifications are not allowed"
+            })),
+        );
+    }
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    match mt_engine.remove_project_acronym(&project_id, &acronym) {
+        Ok(true) => (StatusCode::OK, Json(serde_json::json!({"status": "deleted", "acronym": acronym}))),
+        Ok(false) => (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": format!("Acronym '{}' not found", acronym)}))),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ProjectLanguageRequest {
+    pub language: TextLanguage,
+}
+
+async fn get_project_language(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, .. } = state;
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    match mt_engine.get_project_language(&project_id) {
+        Ok(language) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "project_id": project_id,
+                "language": language
+            })),
+        ),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+/// Set the language used to tokenize a project's text (`auto` detects it per text)
+async fn set_project_language(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+    Json(req): Json<ProjectLanguageRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, .. } = state;
+
+    if read_only {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Read-only mode: modifications are not allowed"
+            })),
+        );
+    }
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    match mt_engine.set_project_language(&project_id, req.language) {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "status": "updated",
+                "project_id": project_id,
+                "language": req.language
+            })),
+        ),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ProjectRecallProfileRequest {
+    pub profile: RecallProfile,
+}
+
+async fn get_project_recall_profile(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, .. } = state;
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    match mt_engine.get_project_recall_profile(&project_id) {
+        Ok(profile) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "project_id": project_id,
+                "profile": profile
+            })),
+        ),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+/// Set the recall path used when a request doesn't specify `profile`
+async fn set_project_recall_profile(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+    Json(req): Json<ProjectRecallProfileRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, .. } = state;
+
+    if read_only {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Read-only mode: modifications are not allowed"
+            })),
+        );
+    }
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    match mt_engine.set_project_recall_profile(&project_id, req.profile) {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "status": "updated",
+                "project_id": project_id,
+                "profile": req.profile
+            })),
+        ),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+async fn get_project_chunking(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, .. } = state;
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    match mt_engine.get_project_chunking(&project_id) {
+        Ok(chunking) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "project_id": project_id,
+                "chunking": chunking
+            })),
+        ),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+/// Set the chunk size limits used when an ingest request doesn't override
+/// them. Running agents of the project pick them up for their next file.
+async fn set_project_chunking(
+    State(state): State<EngineState>,
+    Path(project_id): Path<String>,
+    Json(chunking): Json<ChunkingConfig>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, agent_manager, read_only, .. } = state;
+
+    if read_only {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Read-only mode: modifications are not allowed"
+            })),
+        );
+    }
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+    if let Err(e) = chunking.validate() {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e})));
+    }
+
+    if let Err(e) = mt_engine.set_project_chunking(&project_id, chunking) {
+        return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e})));
+    }
+    for (key, config) in agent_manager.list_agents().await {
+        if config.project_id != project_id {
+            continue;
+        }
+        if let Some(agent) = agent_manager.get_agent(&key).await {
+            agent.get_ingester().lock().await.set_chunking(chunking);
+        }
+    }
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "status": "updated",
+            "project_id": project_id,
+            "chunking": chunking
+        })),
+    )
+}
+
+/// NDJSON body of the export records of `ids` (skipping those `filter`'s phrase
+/// rejects). Records are read, decrypted and serialized a chunk at a time on the
+/// blocking pool, only as the body is polled.
+fn export_body(ctx: Arc<ProjectContext>, ids: Vec<String>, filter: Option<RecallFilter>) -> axum::body::Body {
+    use futures::StreamExt;
+
+    let filter = Arc::new(filter);
+    let chunks: Vec<Vec<String>> = ids.chunks(EXPORT_CHUNK_SIZE).map(<[String]>::to_vec).collect();
+    let lines = futures::stream::iter(chunks).then(move |chunk| {
+        let (ctx, filter) = (ctx.clone(), filter.clone());
+        async move {
+            tokio::task::spawn_blocking(move || {
+                let mut lines = String::new();
+                for record in chunk.iter().filter_map(|id| ctx.main.export_record(id)) {
+                    if !filter.iter().all(|f| f.matches_phrase(&record.content)) {
+                        continue;
+                    }
+                    if let Ok(line) = serde_json::to_string(&record) {
+                        lines.push_str(&line);
+                        lines.push('\n');
+                    }
+                }
+                lines
+            })
+            .await
+            .map_err(std::io::Error::other)
+        }
+    });
+    axum::body::Body::from_stream(lines)
+}
+
+async fn export_project(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Path(project_id): Path<String>,
+) -> axum::response::Response {
+    let EngineState { mt_engine, .. } = state;
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"}))).into_response();
+    }
+
+    let ctx = match mt_engine.get_or_load_project(&project_id) {
+        Some(c) => c,
+        None => return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Project not found"}))).into_response(),
+    };
+
+    // Ids are captured up front; each line is materialized (and decrypted) only when the body is polled
+    let ids = ctx.main.memory_ids_by_age();
+    mt_engine.audit().record(
+        AuditEntry::new(audit_actor(&headers), AuditAction::ProjectExported, &project_id)
+            .with_detail(serde_json::json!({"memories": ids.len()})),
+    );
+
+    (
+        StatusCode::OK,
+        [
+            (axum::http::header::CONTENT_TYPE, "application/x-ndjson".to_string()),
+            (axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}.jsonl\"", project_id)),
+        ],
+        export_body(ctx, ids, None),
+    ).into_response()
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MemoryExportRequest {
+    /// Without cues (or query text) every memory is exported
+    #[serde(default)]
+    pub cues: Vec<String>,
+    #[serde(default)]
+    pub query_text: Option<String>,
+    /// Query cues a memory must carry (default 1)
+    #[serde(default)]
+    pub min_intersection: Option<usize>,
+    #[serde(default)]
+    pub created_after: Option<f64>,
+    #[serde(default)]
+    pub created_before: Option<f64>,
+    #[serde(default)]
+    pub metadata_filters: Vec<MetadataFilter>,
+    #[serde(default)]
+    pub exclude_cues: Vec<String>,
+    #[serde(default)]
+    pub phrase: Option<String>,
+    #[serde(default)]
+    pub phrase_window: usize,
+}
+
+impl MemoryExportRequest {
+    fn recall_filter(&self) -> RecallFilter {
+        RecallFilter {
+            created_after: self.created_after,
+            created_before: self.created_before,
+            metadata_filters: self.metadata_filters.clone(),
+            exclude_cues: self.exclude_cues.clone(),
+            phrase: self.phrase.clone(),
+            phrase_window: self.phrase_window,
+            scan_limit: None,
+        }
+    }
+}
+
+/// Stream every memory matching a cue filter as NDJSON (the format of
+/// `GET /projects/:id/export`), oldest first, without scoring or a limit
+async fn export_matching_memories(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Json(req): Json<MemoryExportRequest>,
+) -> axum::response::Response {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+    // An export never creates the project it names
+    let ctx = match state.mt_engine.get_or_load_project(&project_id) {
+        Some(c) => c,
+        None => return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Project not found"}))).into_response(),
+    };
+
+    // Matching ids are collected up front (an index walk, so off the runtime);
+    // each line is materialized and checked against the phrase only when the body is polled
+    let matching = tokio::task::spawn_blocking(move || {
+        let cues = unexpanded_query_cues(&ctx, &req.cues, req.query_text.as_deref());
+        let filter = normalize_exclusions(&req.recall_filter(), &ctx);
+        let ids = ctx.main.matching_memory_ids(&cues, req.min_intersection.unwrap_or(1), &filter);
+        (ctx, cues, filter, ids)
+    })
+    .await;
+    let (ctx, cues, filter, ids) = match matching {
+        Ok(matching) => matching,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    };
+    state.mt_engine.audit().record(
+        AuditEntry::new(audit_actor(&headers), AuditAction::ProjectExported, &project_id)
+            .with_detail(serde_json::json!({"memories": ids.len(), "cues": cues})),
+    );
+
+    (
+        StatusCode::OK,
+        [(axum::http::header::CONTENT_TYPE, "application/x-ndjson")],
+        export_body(ctx, ids, Some(filter)),
+    ).into_response()
+}
+
+async fn import_project(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Path(project_id): Path<String>,
+    body: String,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, job_queue, .. } = state;
+
+    if read_only {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Read-only mode: modifications are not allowed"
+            })),
+        );
+    }
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    let ctx = match mt_engine.get_or_create_project(project_id.clone()) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+
+    let mut records = Vec::new();
+    let mut errors = Vec::new();
+
+    for (line_no, line) in body.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<MemoryRecord<MainStats>>(line) {
+            Ok(record) => records.push(record),
+            Err(e) => errors.push(serde_json::json!({"line": line_no + 1, "error": e.to_string()})),
+        }
+    }
+
+    // Imported cues go through the project's normalization and taxonomy, like any other write
+    let mut rejected_cues = Vec::new();
+    for record in &mut records {
+        let normalized_cues = record.cues.iter().map(|cue| ctx.normalize_cue(cue)).collect();
+        let report = ctx.validate_cues(normalized_cues);
+        record.cues = report.accepted;
+        rejected_cues.extend(report.rejected.into_iter().map(|r| serde_json::json!({"id": record.id, "cue": r.cue, "code": r.code})));
+    }
+
+    // Check the whole import up front so a rejected import leaves the project untouched
+    let (new_count, new_bytes) = records
+        .iter()
+        .filter(|r| ctx.main.get_memory(&r.id).is_none())
+        .fold((0usize, 0u64), |(n, b), r| (n + 1, b + r.content.len() as u64));
+    if let Err(e) = mt_engine.check_quota(&project_id, new_count, new_bytes) {
+        return quota_exceeded_response(e);
+    }
+
+    let mut imported_ids = Vec::new();
+    let mut skipped = 0;
+    for record in records {
+        let id = record.id.clone();
+        if ctx.main.import_record(record) {
+            imported_ids.push(id);
+        } else {
+            skipped += 1;
+        }
+    }
+
+    mt_engine.audit().record(
+        AuditEntry::new(audit_actor(&headers), AuditAction::ProjectImported, &project_id)
+            .with_detail(serde_json::json!({"imported": imported_ids.len(), "skipped_existing": skipped})),
+    );
+
+    // The lexicon needs to learn about the new memories
+    if !imported_ids.is_empty() {
+        let session = job_queue.session_manager.get_or_create(&project_id);
+        session.expect_write();
+        for memory_id in &imported_ids {
+            job_queue.buffer(&project_id, Job::TrainLexiconFromMemory {
+                project_id: project_id.clone(),
+                memory_id: memory_id.clone(),
+            }).await;
+        }
+        session.write_complete();
+    }
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "project_id": project_id,
+            "imported": imported_ids.len(),
+            "skipped_existing": skipped,
+            "rejected_cues": rejected_cues,
+            "errors": errors
+        })),
+    )
+}
+
+async fn dump_project(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Path(project_id): Path<String>,
+) -> axum::response::Response {
+    let EngineState { mt_engine, .. } = state;
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"}))).into_response();
+    }
+    if mt_engine.get_or_load_project(&project_id).is_none() {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Project not found"}))).into_response();
+    }
+
+    let engine = mt_engine.clone();
+    let id = project_id.clone();
+    let data = match tokio::task::spawn_blocking(move || engine.dump_project(&id)).await {
+        Ok(Ok(data)) => data,
+        Ok(Err(e)) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))).into_response(),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    };
+
+    mt_engine.audit().record(
+        AuditEntry::new(audit_actor(&headers), AuditAction::ProjectExported, &project_id)
+            .with_detail(serde_json::json!({"format": "archive", "bytes": data.len()})),
+    );
+
+    (
+        StatusCode::OK,
+        [
+            (axum::http::header::CONTENT_TYPE, "application/octet-stream".to_string()),
+            (axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}.archive\"", project_id)),
+        ],
+        data,
+    ).into_response()
+}
+
+async fn restore_project(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Path(project_id): Path<String>,
+    body: bytes::Bytes,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, .. } = state;
+
+    if read_only {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Read-only mode: modifications are not allowed"
+            })),
+        );
+    }
+
+    if !validate_project_id(&project_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format"})));
+    }
+
+    let engine = mt_engine.clone();
+    let id = project_id.clone();
+    let ctx = match tokio::task::spawn_blocking(move || engine.restore_project(&id, &body)).await {
+        Ok(Ok(ctx)) => ctx,
+        Ok(Err(e)) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))),
+    };
+
+    mt_engine.audit().record(
+        AuditEntry::new(audit_actor(&headers), AuditAction::ProjectImported, &project_id)
+            .with_detail(serde_json::json!({"format": "archive", "memories": ctx.main.total_memories()})),
+    );
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "status": "restored",
+            "project_id": project_id,
+            "total_memories": ctx.main.total_memories(),
+            "total_cues": ctx.main.total_cues()
+        })),
+    )
+}
+
+async fn add_alias(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Json(req): Json<AddAliasRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let EngineState { mt_engine, read_only, .. } = state;
+    if read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only"})));
+    }
+
+    let ctx = match mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+    
+    let alias_id = uuid::Uuid::new_v4().to_string();
+    let content = serde_json::json!({
+        "from": req.from,
+        "to": req.to,
+        "downweight": req.weight.unwrap_or(0.85),
+        "status": "active",
+        "reason": "manual"
+    }).to_string();
+
+    let cues = vec![
+        "type:alias".to_string(),
+        format!("from:{}", req.from),
+        format!("to:{}", req.to),
+        "status:active".to_string(),
+        "reason:manual".to_string(),
+    ];
+
+    ctx.aliases.upsert_memory_with_id(
+        alias_id.clone(),
+        content,
+        cues,
+        None,
+        Some(MainStats::default()),
+        false,
+        false
+    );
+
+    (StatusCode::OK, Json(serde_json::json!({"id": alias_id, "status": "created"})))
+}
+
+async fn get_aliases(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let EngineState { mt_engine, .. } = state;
+        let ctx = match mt_engine.get_or_create_project(project_id) {
+            Ok(c) => c,
+            Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+        };
+        
+        let cue = params.get("cue").cloned().unwrap_or_default();
+        if cue.is_empty() {
+            return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Missing 'cue' query param"})));
+        }
+        
+        let query_cues = vec![
+            "type:alias".to_string(), 
+            format!("to:{}", cue),
+            "status:active".to_string()
+        ];
+        
+        let results = ctx.aliases.recall(query_cues, 50, false, None);
+        let mut aliases = Vec::new();
+        
+        for res in results {
+            if let Ok(data) = serde_json::from_str::<serde_json::Value>(&res.content) {
+                if data.get("status").and_then(|v| v.as_str()).is_some_and(|status| status != "active") {
+                    continue;
+                }
+                let from_match = data.get("from").and_then(|v| v.as_str()).map(|v| v == cue).unwrap_or(false);
+                let to_match = data.get("to").and_then(|v| v.as_str()).map(|v| v == cue).unwrap_or(false);
+                
+                if from_match || to_match {
+                    aliases.push(data);
+                }
+            }
+        }
+        
+    (StatusCode::OK, Json(serde_json::json!({"aliases": aliases})))
+}
+
+/// Aliases proposed by the `propose_aliases` job, awaiting approve or reject
+async fn get_proposed_aliases(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let EngineState { mt_engine, .. } = state;
+    let ctx = match mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+
+    let aliases = ctx.proposed_aliases();
+    (StatusCode::OK, Json(serde_json::json!({"count": aliases.len(), "aliases": aliases})))
+}
+
+async fn approve_alias(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Path(alias_id): Path<String>,
+    Json(req): Json<ApproveAliasRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    review_alias(state, headers, alias_id, Some(req.downweight))
+}
+
+async fn reject_alias(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Path(alias_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    review_alias(state, headers, alias_id, None)
+}
+
+/// Approve (`Some(downweight)`) or reject (`None`) a proposed alias
+fn review_alias(
+    state: EngineState,
+    headers: HeaderMap,
+    alias_id: String,
+    approve: Option<Option<f64>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let EngineState { mt_engine, read_only, .. } = state;
+    if read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only"})));
+    }
+
+    let ctx = match mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+    if ctx.alias(&alias_id).is_none() {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Alias not found", "id": alias_id})));
+    }
+
+    let result = match approve {
+        Some(downweight) => ctx.approve_alias(&alias_id, downweight),
+        None => ctx.reject_alias(&alias_id),
+    };
+    match result {
+        Ok(alias) => (StatusCode::OK, Json(serde_json::json!({"status": alias["status"], "alias": alias}))),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+/// WebSocket feed of incremental graph changes plus periodic stats.
+/// `?project=<id>` limits the feed to one project; without it every project is streamed.
+/// Messages are JSON objects tagged by `type` (see `crate::live::LiveEvent`);
+/// a `lagged` message means events were dropped and the client should refetch `/graph`.
+async fn live_updates(
+    State(state): State<EngineState>,
+    ws: axum::extract::ws::WebSocketUpgrade,
+    axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>,
+) -> axum::response::Response {
+    let project_filter = params.get("project").cloned();
+    if let Some(p) = &project_filter {
+        if !validate_project_id(p) {
+            return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project ID format in query param"}))).into_response();
+        }
+    }
+    let stats_interval = params.get("stats_interval_secs")
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(5)
+        .max(1);
+
+    ws.on_upgrade(move |socket| live_updates_session(socket, state.mt_engine, project_filter, stats_interval))
+}
+
+async fn live_updates_session(
+    mut socket: axum::extract::ws::WebSocket,
+    mt_engine: Arc<MultiTenantEngine>,
+    project_filter: Option<String>,
+    stats_interval: u64,
+) {
+    use axum::extract::ws::Message;
+    use crate::live::LiveEvent;
+    use tokio::sync::broadcast::error::RecvError;
+
+    let mut events = mt_engine.subscribe_live();
+    let mut ticker = tokio::time::interval(std::time::Duration::from_secs(stats_interval));
+
+    loop {
+        let outgoing: Vec<serde_json::Value> = tokio::select! {
+            event = events.recv() => match event {
+                Ok(e) => {
+                    if project_filter.as_deref().is_some_and(|p| p != e.project_id()) {
+                        continue;
+                    }
+                    vec![serde_json::json!(e)]
+                }
+                Err(RecvError::Lagged(skipped)) => vec![serde_json::json!({"type": "lagged", "skipped": skipped})],
+                Err(RecvError::Closed) => break,
+            },
+            _ = ticker.tick() => {
+                let projects = mt_engine.list_projects();
+                projects
+                    .into_iter()
+                    .filter(|p| project_filter.as_deref().is_none_or(|f| f == p.project_id))
+                    .map(|p| serde_json::json!(LiveEvent::Stats {
+                        project_id: p.project_id,
+                        total_memories: p.total_memories,
+                        total_cues: p.total_cues,
+                    }))
+                    .collect()
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                Some(Ok(_)) => continue,
+            },
+        };
+
+        for msg in outgoing {
+            if socket.send(Message::Text(msg.to_string())).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Lexicon Surgeon (Multi-tenant): Inspect a cue in the Lexicon
+async fn lexicon_inspect(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    axum::extract::Path(cue): axum::extract::Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let EngineState { mt_engine, .. } = state;
+        let ctx = match mt_engine.get_or_create_project(project_id) {
+            Ok(c) => c,
+            Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+        };
+        let cue_lower = cue.to_lowercase();
+        
+        // Helper: count main memories that have token in cues but NOT canonical
+        let count_affected = |token: &str, canonical: &str| -> usize {
+            let token_lower = token.to_lowercase();
+            let canonical_lower = canonical.to_lowercase();
+            let mut count = 0;
+            
+            for ref_multi in ctx.main.get_memories().iter() {
+                let memory = ref_multi.value();
+                let cues_lower: Vec<String> = memory.cues.iter().map(|c| c.to_lowercase()).collect();
+                if cues_lower.contains(&token_lower) && !cues_lower.contains(&canonical_lower) {
+                    count += 1;
+                }
+            }
+            count
+        };
+        
+        // 1. OUTGOING: What does this token trigger?
+        let outgoing_results = ctx.lexicon.recall_fast(vec![cue_lower.clone()], 100);
+        let outgoing: Vec<LexiconEntry> = outgoing_results.iter().map(|r| {
+            let token = r.metadata.get("cues")
+                .and_then(|v| v.as_array())
+                .and_then(|arr| arr.first())
+                .and_then(|v| v.as_str())
+                .unwrap_or(&cue_lower)
+                .to_string();
+            
+            let affected = count_affected(&token, &r.content);
+            
+            LexiconEntry {
+                memory_id: r.memory_id.clone(),
+                content: r.content.clone(),
+                token,
+                reinforcement_score: r.reinforcement_score,
+                created_at: r.created_at,
+                affected_memories_count: affected,
+            }
+        }).collect();
+        
+        // 2. INCOMING: What tokens map to this canonical cue?
+        let mut incoming: Vec<LexiconEntry> = Vec::new();
+        let key_lex = ctx.lexicon.get_master_key();
+        for ref_multi in ctx.lexicon.get_memories().iter() {
+            let memory = ref_multi.value();
+            let content = memory.access_content(key_lex.as_deref()).unwrap_or_default();
+            if content.to_lowercase() == cue_lower {
+                for token in &memory.cues {
+                    let affected = count_affected(token, &content);
+                    incoming.push(LexiconEntry {
+                        memory_id: memory.id.clone(),
+                        content: content.clone(),
+                        token: token.clone(),
+                        reinforcement_score: memory.stats.get_reinforcement_count() as f64,
+                        created_at: memory.created_at,
+                        affected_memories_count: affected,
+                    });
+                }
+            }
+        }
+        
+        (StatusCode::OK, Json(serde_json::json!(LexiconInspectResponse {
+            cue: cue_lower,
+            outgoing,
             incoming,
         })))
 }
 
-/// Delete a lexicon entry (multi-tenant)
-async fn lexicon_delete(
+/// Delete a lexicon entry (multi-tenant)
+async fn lexicon_delete(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    axum::extract::Path(memory_id): axum::extract::Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let EngineState { mt_engine, read_only, .. } = state;
+        if read_only {
+            return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+        }
+        
+        let ctx = match mt_engine.get_or_create_project(project_id) {
+            Ok(c) => c,
+            Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+        };
+        let deleted = ctx.lexicon.delete_memory(&memory_id);
+        ctx.lexicon_quality.remove(&memory_id);
+        if deleted {
+            (StatusCode::OK, Json(serde_json::json!({
+                "status": "deleted",
+                "memory_id": memory_id
+            })))
+        } else {
+            (StatusCode::NOT_FOUND, Json(serde_json::json!({
+                "error": "Entry not found",
+                "memory_id": memory_id
+            })))
+        }
+}
+
+/// Score lexicon entries by recall hits vs. false expansions and prune the weak
+/// or unused ones. `dry_run` reports the candidates right away; otherwise the
+/// prune runs as a background job.
+async fn lexicon_prune(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Json(req): Json<LexiconPruneRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let EngineState { mt_engine, read_only, job_queue, .. } = state;
+    if read_only && !req.dry_run {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+    if let Err(e) = req.validate() {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e})));
+    }
+
+    let ctx = match mt_engine.get_or_create_project(project_id.clone()) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+
+    if req.dry_run {
+        return match tokio::task::spawn_blocking(move || prune_lexicon(&ctx, &req)).await {
+            Ok(report) => (StatusCode::OK, Json(serde_json::json!(report))),
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))),
+        };
+    }
+
+    let job_id = job_queue.enqueue(Job::PruneLexicon { project_id: project_id.clone(), request: req }).await;
+    (StatusCode::ACCEPTED, Json(serde_json::json!({
+        "status": "queued",
+        "project_id": project_id,
+        "job_id": job_id,
+        "entries": ctx.lexicon.total_memories()
+    })))
+}
+
+/// The project's market heatmap, strongest lift first
+async fn get_heatmap(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+    let limit = params.get("limit")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(usize::MAX);
+
+    let ctx = match state.mt_engine.get_or_create_project(project_id.clone()) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+    let heatmap = ctx.market_heatmap.read().map(|h| h.clone()).unwrap_or_default();
+    let now = now_secs();
+    let mut entries: Vec<_> = heatmap.entries().iter()
+        .map(|(cue, entry)| (cue, entry, entry.effective_weight(now)))
+        .collect();
+    entries.sort_by(|a, b| b.2.abs().total_cmp(&a.2.abs()).then_with(|| a.0.cmp(b.0)));
+    let total = entries.len();
+    let entries: Vec<_> = entries.into_iter().take(limit).map(|(cue, entry, weight)| serde_json::json!({
+        "cue": cue,
+        "weight": weight,
+        "base_weight": entry.weight,
+        "source": entry.source,
+        "set_at": entry.set_at,
+        "expires_at": entry.expires_at,
+    })).collect();
+
+    (StatusCode::OK, Json(serde_json::json!({
+        "project_id": project_id,
+        "total": total,
+        "entries": entries
+    })))
+}
+
+/// Cues reinforced most in the last `window` (e.g. `15m`, at most `1h`), with
+/// their velocity and daily baseline
+async fn get_trending(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+    let window_mins = match params.get("window").map(|w| parse_window(w)).transpose() {
+        Ok(w) => w.unwrap_or(DEFAULT_TRENDING_WINDOW_MINS),
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    };
+    let limit = params.get("limit")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(50);
+
+    let ctx = match state.mt_engine.get_or_create_project(project_id.clone()) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+    let trending = ctx.lexicon.trending_cues(window_mins, limit);
+
+    (StatusCode::OK, Json(serde_json::json!({
+        "project_id": project_id,
+        "window_minutes": window_mins,
+        "count": trending.len(),
+        "cues": trending
+    })))
+}
+
+/// Set market heatmap lifts from an external trend signal. Cues are
+/// normalized like recall cues; a weight of 0 removes the cue.
+async fn set_heatmap(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Json(req): Json<HeatmapUpdateRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let EngineState { mt_engine, read_only, .. } = state;
+    if read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+    if let Some((cue, weight)) = req.weights.iter().find(|(_, w)| !w.is_finite() || w.abs() > MAX_HEATMAP_WEIGHT) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+            "error": format!("Weight of '{}' must be between -{} and {} (got {})", cue, MAX_HEATMAP_WEIGHT, MAX_HEATMAP_WEIGHT, weight)
+        })));
+    }
+
+    let ctx = match mt_engine.get_or_create_project(project_id.clone()) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+    let weights = req.weights.iter()
+        .map(|(cue, weight)| (ctx.normalize_cue(cue), *weight))
+        .filter(|(cue, _)| !cue.is_empty())
+        .collect();
+    let mut heatmap = ctx.market_heatmap.write().unwrap();
+    let (updated, removed) = heatmap.apply(weights, req.ttl_seconds, req.replace, now_secs());
+
+    (StatusCode::OK, Json(serde_json::json!({
+        "project_id": project_id,
+        "updated": updated,
+        "removed": removed,
+        "total": heatmap.entries().len()
+    })))
+}
+
+/// Decay the dynamic salience of the project's memories now, or with
+/// `dry_run` report how many it would change
+async fn maintenance_decay(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Json(req): Json<DecayRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let EngineState { mt_engine, read_only, .. } = state;
+    if read_only && !req.dry_run {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+    let schedule = match mt_engine.get_project_schedule(&project_id) {
+        Ok(s) => s,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    };
+    let rate = match req.rate(&schedule) {
+        Ok(r) => r,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    };
+
+    let ctx = match mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+    match tokio::task::spawn_blocking(move || ctx.main.decay_salience_with(rate, req.dry_run)).await {
+        Ok(report) => (StatusCode::OK, Json(serde_json::json!(report))),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))),
+    }
+}
+
+/// Delete the project's idle low-salience memories now, or with `dry_run`
+/// list the ones that would go
+async fn maintenance_prune(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Json(req): Json<PruneRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let EngineState { mt_engine, read_only, .. } = state;
+    if read_only && !req.dry_run {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+    let schedule = match mt_engine.get_project_schedule(&project_id) {
+        Ok(s) => s,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    };
+    let (threshold, max_idle_secs) = match req.resolve(&schedule) {
+        Ok(r) => r,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    };
+
+    let ctx = match mt_engine.get_or_create_project(project_id.clone()) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+    let report = match tokio::task::spawn_blocking(move || prune_memories(&ctx.main, threshold, max_idle_secs, req.dry_run)).await {
+        Ok(report) => report,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))),
+    };
+    if !report.dry_run {
+        for memory_id in &report.memory_ids {
+            mt_engine.audit().record(
+                AuditEntry::new(audit_actor(&headers), AuditAction::MemoryDeleted, &project_id)
+                    .with_memory(memory_id)
+                    .with_detail(serde_json::json!({"reason": "low_salience"})),
+            );
+        }
+    }
+    (StatusCode::OK, Json(serde_json::json!(report)))
+}
+
+fn yaml_response<T: Serialize>(value: &T) -> axum::response::Response {
+    match serde_yaml::to_string(value) {
+        Ok(yaml) => (StatusCode::OK, [(axum::http::header::CONTENT_TYPE, "application/yaml")], yaml).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+}
+
+/// The project's lexicon as editable YAML: canonical cue -> tokens
+async fn export_lexicon_yaml(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+) -> axum::response::Response {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+
+    let EngineState { mt_engine, .. } = state;
+    let ctx = match mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))).into_response(),
+    };
+    yaml_response(&crate::vocabulary::export_lexicon(&ctx))
+}
+
+/// Load a lexicon YAML file (`?mode=replace` drops the current lexicon first)
+async fn import_lexicon_yaml(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>,
+    body: String,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let EngineState { mt_engine, read_only, .. } = state;
+    if read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+
+    let mode = match ImportMode::parse(params.get("mode").map(String::as_str)) {
+        Ok(mode) => mode,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    };
+    let file: LexiconFile = match serde_yaml::from_str(&body) {
+        Ok(file) => file,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": format!("Invalid lexicon YAML: {}", e)}))),
+    };
+    let ctx = match mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+
+    match crate::vocabulary::import_lexicon(&ctx, file, mode) {
+        Ok(count) => (StatusCode::OK, Json(serde_json::json!({
+            "status": "imported",
+            "entries": count,
+            "total_entries": ctx.lexicon.total_memories()
+        }))),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+/// The project's aliases as editable YAML, proposed and rejected ones included
+async fn export_aliases_yaml(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+) -> axum::response::Response {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+
+    let EngineState { mt_engine, .. } = state;
+    let ctx = match mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))).into_response(),
+    };
+    yaml_response(&crate::vocabulary::export_aliases(&ctx))
+}
+
+/// Load an alias YAML file (`?mode=replace` drops the current aliases first)
+async fn import_aliases_yaml(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>,
+    body: String,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let EngineState { mt_engine, read_only, .. } = state;
+    if read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+
+    let mode = match ImportMode::parse(params.get("mode").map(String::as_str)) {
+        Ok(mode) => mode,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    };
+    let file: AliasFile = match serde_yaml::from_str(&body) {
+        Ok(file) => file,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": format!("Invalid alias YAML: {}", e)}))),
+    };
+    let ctx = match mt_engine.get_or_create_project(project_id) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
+    };
+
+    match crate::vocabulary::import_aliases(&ctx, file, mode) {
+        Ok(count) => (StatusCode::OK, Json(serde_json::json!({
+            "status": "imported",
+            "aliases": count,
+            "total_aliases": ctx.aliases.total_memories()
+        }))),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+/// The organization lexicon shared by every project: canonical cue -> tokens
+async fn get_org_lexicon(State(state): State<EngineState>) -> (StatusCode, Json<serde_json::Value>) {
+    let org_lexicon = state.mt_engine.org_lexicon();
+    let file = crate::vocabulary::export_lexicon_engine(org_lexicon);
+    (StatusCode::OK, Json(serde_json::json!({
+        "lexicon": file.lexicon,
+        "total_entries": org_lexicon.total_memories()
+    })))
+}
+
+/// Wire a token to a canonical cue for every project
+async fn org_lexicon_wire(
+    State(state): State<EngineState>,
+    Json(req): Json<WireLexiconRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, .. } = state;
+    if read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+
+    let token = req.token.trim().to_lowercase();
+    let canonical = req.canonical.trim().to_lowercase();
+    if token.is_empty() || canonical.is_empty() {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "token and canonical are required"})));
+    }
+    let lex_id = format!("cue:{}", canonical);
+    mt_engine.org_lexicon().upsert_memory_with_id(
+        lex_id.clone(),
+        canonical.clone(),
+        vec![token.clone()],
+        None,
+        Some(LexiconStats::default()),
+        false,
+        false,
+    );
+    mt_engine.org_lexicon_changed();
+
+    (StatusCode::OK, Json(serde_json::json!({
+        "status": "wired",
+        "memory_id": lex_id,
+        "token": token,
+        "canonical": canonical
+    })))
+}
+
+/// Delete an organization lexicon entry
+async fn org_lexicon_delete(
+    State(state): State<EngineState>,
+    axum::extract::Path(memory_id): axum::extract::Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let EngineState { mt_engine, read_only, .. } = state;
+    if read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+
+    if mt_engine.org_lexicon().delete_memory(&memory_id) {
+        mt_engine.org_lexicon_changed();
+        (StatusCode::OK, Json(serde_json::json!({
+            "status": "deleted",
+            "memory_id": memory_id
+        })))
+    } else {
+        (StatusCode::NOT_FOUND, Json(serde_json::json!({
+            "error": "Entry not found",
+            "memory_id": memory_id
+        })))
+    }
+}
+
+/// The organization lexicon as editable YAML
+async fn export_org_lexicon_yaml(State(state): State<EngineState>) -> axum::response::Response {
+    yaml_response(&crate::vocabulary::export_lexicon_engine(state.mt_engine.org_lexicon()))
+}
+
+/// Load an organization lexicon YAML file (`?mode=replace` drops the current one first)
+async fn import_org_lexicon_yaml(
     State(state): State<EngineState>,
-    headers: HeaderMap,
-    axum::extract::Path(memory_id): axum::extract::Path<String>,
+    axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>,
+    body: String,
 ) -> (StatusCode, Json<serde_json::Value>) {
-    let project_id = match extract_project_id(&headers) {
-        Ok(id) => id,
-        Err(e) => return e,
+    let EngineState { mt_engine, read_only, .. } = state;
+    if read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+
+    let mode = match ImportMode::parse(params.get("mode").map(String::as_str)) {
+        Ok(mode) => mode,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    };
+    let file: LexiconFile = match serde_yaml::from_str(&body) {
+        Ok(file) => file,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": format!("Invalid lexicon YAML: {}", e)}))),
     };
 
-    let EngineState { mt_engine, read_only, .. } = state;
-        if read_only {
-            return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
-        }
-        
-        let ctx = match mt_engine.get_or_create_project(project_id) {
-            Ok(c) => c,
-            Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e}))),
-        };
-        let deleted = ctx.lexicon.delete_memory(&memory_id);
-        if deleted {
+    match crate::vocabulary::import_lexicon_engine(mt_engine.org_lexicon(), file, mode) {
+        Ok(count) => {
+            mt_engine.org_lexicon_changed();
             (StatusCode::OK, Json(serde_json::json!({
-                "status": "deleted",
-                "memory_id": memory_id
-            })))
-        } else {
-            (StatusCode::NOT_FOUND, Json(serde_json::json!({
-                "error": "Entry not found",
-                "memory_id": memory_id
+                "status": "imported",
+                "entries": count,
+                "total_entries": mt_engine.org_lexicon().total_memories()
             })))
         }
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    }
 }
 
 /// Get full Lexicon as graph data (multi-tenant)
@@ -1704,11 +5750,16 @@ async fn recall_web(
     }
 
     // Create an ingester for this request
+    let chunking = state.mt_engine.get_project_chunking(&project_id).unwrap_or_default();
     let config = AgentConfig {
         project_id: project_id.clone(),
         watch_dir: String::new(),
         throttle_ms: 0,
         state_file: None,
+        filter: Default::default(),
+        git: Default::default(),
+        chunking,
+        extract_facts: false,
     };
     let ingester = Ingester::new(config.clone(), job_queue.clone());
     let ingester = std::sync::Arc::new(ingester); // Arc for sharing across tasks
@@ -1716,13 +5767,18 @@ async fn recall_web(
     let start_time = Instant::now();
     let mut chunks = Vec::new();
     let mut urls_processed = Vec::new();
+    // Chunk count per fetched page, in `chunks` order, so persisted chunks keep their URL
+    let mut pages: Vec<(String, usize)> = Vec::new();
 
     // 1. Determine targets: specific URL or Search
     if let Some(url) = &req.url {
         // Direct URL Mode
         urls_processed.push(url.clone());
         match ingester.fetch_and_chunk_url(url).await {
-            Ok(c) => chunks.extend(c),
+            Ok(c) => {
+                pages.push((url.clone(), c.len()));
+                chunks.extend(c);
+            }
             Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": format!("Failed to fetch URL: {}", e)}))),
         };
     } else {
@@ -1748,7 +5804,10 @@ async fn recall_web(
         while let Some(res) = set.join_next().await {
             if let Ok((url, result)) = res {
                 match result {
-                    Ok(c) => chunks.extend(c),
+                    Ok(c) => {
+                        pages.push((url, c.len()));
+                        chunks.extend(c);
+                    }
                     Err(e) => tracing::warn!("Failed to fetch search result {}: {}", url, e),
                 }
             }
@@ -1772,184 +5831,518 @@ async fn recall_web(
         intersection: usize,
     }
 
-    let mut scored_chunks: Vec<ScoredChunk> = chunks.iter().map(|chunk| {
-        let chunk_cues = crate::nl::tokenize_to_cues(&chunk.content);
-        
-        let intersection = chunk_cues.iter().filter(|c| query_cues.contains(c)).count();
-        
-        // Simple scoring: intersection count * 10 
-        let mut score = (intersection as f64) * 10.0;
-        
-        // Boost if query terms appear in structural cues (e.g. title, header)
-        for q in &query_cues {
-            for s in &chunk.structural_cues {
-                if s.to_lowercase().contains(q) {
-                    score += 5.0;
-                }
-            }
-        }
-        
-        ScoredChunk {
-            content: chunk.content.clone(),
-            score,
-            intersection,
+    let mut scored_chunks: Vec<ScoredChunk> = chunks.iter().map(|chunk| {
+        let chunk_cues = crate::nl::tokenize_to_cues(&chunk.content);
+        
+        let intersection = chunk_cues.iter().filter(|c| query_cues.contains(c)).count();
+        
+        // Simple scoring: intersection count * 10 
+        let mut score = (intersection as f64) * 10.0;
+        
+        // Boost if query terms appear in structural cues (e.g. title, header)
+        for q in &query_cues {
+            for s in &chunk.structural_cues {
+                if s.to_lowercase().contains(q) {
+                    score += 5.0;
+                }
+            }
+        }
+        
+        ScoredChunk {
+            content: chunk.content.clone(),
+            score,
+            intersection,
+        }
+    }).collect();
+
+    // Sort by score desc
+    scored_chunks.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    
+    // Take top results (more for search mode to have variety)
+    let limit = if req.url.is_none() { 10 } else { 5 };
+    
+    let results: Vec<serde_json::Value> = scored_chunks.into_iter()
+        .take(limit)
+        .filter(|r| r.score > 0.0)
+        .map(|r| serde_json::json!({
+            "content": r.content,
+            "score": r.score,
+            "intersection": r.intersection,
+            // "url": r.url // Optional source info
+        }))
+        .collect();
+        
+    let processing_ms = processing_start.elapsed().as_secs_f64() * 1000.0;
+
+    // 3. Optional Persistence (Async)
+    if req.persist {
+        let project_id_clone = project_id.clone();
+        let chunks_clone = chunks.clone();
+        
+        // Fix: Use local job_queue variable, not state.job_queue (which is moved)
+        let job_queue_clone = job_queue.clone(); 
+        
+        tokio::spawn(async move {
+             let config = AgentConfig {
+                project_id: project_id_clone.clone(),
+                watch_dir: String::new(),
+                throttle_ms: 0,
+                state_file: None,
+                filter: Default::default(),
+                git: Default::default(),
+                chunking,
+                extract_facts: false,
+            };
+            let mut async_ingester = Ingester::new(config, job_queue_clone);
+
+            // Each page under its own `url:` source, as /ingest/url stores it
+            let mut remaining = chunks_clone.into_iter();
+            for (url, count) in pages {
+                let page_chunks: Vec<_> = remaining.by_ref().take(count).collect();
+                let source = format!("url:{}", url);
+                if let Err(e) = async_ingester.process_chunks(page_chunks, &project_id_clone, &source).await {
+                    tracing::error!("Async persistence failed for web recall of {}: {}", url, e);
+                }
+            }
+        });
+    }
+
+    let total_ms = start_time.elapsed().as_secs_f64() * 1000.0;
+    crate::telemetry::record_results(results.len());
+    
+    (StatusCode::OK, Json(serde_json::json!({
+        "urls": urls_processed,
+        "results": results,
+        "latency_ms": total_ms,
+        "timings": {
+            "fetch_chunk": fetch_ms,
+            "search_overhead": if req.url.is_none() { true } else { false },
+            "processing": processing_ms
+        }
+    })))
+}
+
+/// Ingest content from a URL using the Agent's Ingester
+/// Supports recursive crawling when depth > 0
+async fn ingest_url(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Json(req): Json<IngestUrlRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    use crate::agent::ingester::Ingester;
+    use crate::agent::AgentConfig;
+    
+    let EngineState { read_only, job_queue, .. } = state;
+    if read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+    
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+    
+    // Ensure project exists (auto-create)
+    if let Err(e) = state.mt_engine.get_or_create_project(project_id.clone()) {
+        return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e})));
+    }
+
+    // Page size is unknown until fetched; the job worker enforces the quota per chunk
+    if let Err(e) = state.mt_engine.check_quota(&project_id, 1, 0) {
+        return quota_exceeded_response(e);
+    }
+    let chunking = match request_chunking(&state.mt_engine, &project_id, &req.chunking) {
+        Ok(chunking) => chunking,
+        Err(e) => return e,
+    };
+    
+    // Create an ingester for this request
+    let config = AgentConfig {
+        project_id: project_id.clone(),
+        watch_dir: String::new(), // Not used for API-driven ingestion
+        throttle_ms: 0,
+        state_file: None,
+        filter: Default::default(),
+        git: Default::default(),
+        chunking,
+        extract_facts: req.extract_facts,
+    };
+    let mut ingester = Ingester::new(config, job_queue);
+
+    // Sitemaps and feeds: ingest the entries that changed since the last read
+    if req.feed.unwrap_or_else(|| crate::agent::feeds::looks_like_feed_url(&req.url)) {
+        let mut subscription = match state.mt_engine.get_project_feed(&project_id, &req.url) {
+            Ok(existing) => existing.unwrap_or_else(|| crate::agent::feeds::FeedSubscription {
+                url: req.url.clone(),
+                ..Default::default()
+            }),
+            Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+        };
+        if let Some(secs) = req.refresh_interval_secs {
+            subscription.refresh_interval_secs = (secs > 0).then_some(secs);
+        }
+
+        return match ingester.process_feed(&mut subscription, &project_id).await {
+            Ok(refresh) => {
+                if let Err(e) = state.mt_engine.set_project_feed(&project_id, subscription.clone()) {
+                    return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e})));
+                }
+                audit_memories_created(&state.mt_engine, &headers, &project_id, &refresh.memory_ids);
+                (StatusCode::OK, Json(serde_json::json!({
+                    "status": "feed",
+                    "url": req.url,
+                    "kind": refresh.kind,
+                    "entries": refresh.entries_found,
+                    "ingested": refresh.ingested.len(),
+                    "unchanged": refresh.unchanged,
+                    "total_chunks": refresh.memory_ids.len(),
+                    "memory_ids": refresh.memory_ids,
+                    "refresh_interval_secs": subscription.refresh_interval_secs,
+                    "errors": refresh.errors.iter().map(|(url, err)| {
+                        serde_json::json!({"url": url, "error": err})
+                    }).collect::<Vec<_>>()
+                })))
+            }
+            Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+                "error": format!("Failed to ingest feed: {}", e)
+            }))),
+        };
+    }
+    
+    // Check if recursive crawling is requested
+    if req.depth > 0 {
+        // Recursive crawl
+        match ingester.process_url_recursive(
+            &req.url, 
+            &project_id, 
+            req.depth, 
+            req.same_domain_only,
+        ).await {
+            Ok(result) => {
+                audit_memories_created(&state.mt_engine, &headers, &project_id, &result.memory_ids);
+                (StatusCode::OK, Json(serde_json::json!({
+                    "status": "crawled",
+                    "url": req.url,
+                    "depth": req.depth,
+                    "pages_crawled": result.pages_crawled,
+                    "total_chunks": result.memory_ids.len(),
+                    "links_found": result.links_found,
+                    "links_skipped": result.links_skipped,
+                    "memory_ids": result.memory_ids,
+                    "errors": result.errors.iter().map(|(url, err)| {
+                        serde_json::json!({"url": url, "error": err})
+                    }).collect::<Vec<_>>()
+                })))
+            }
+            Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+                "error": format!("Failed to crawl URL: {}", e)
+            }))),
+        }
+    } else {
+        // Single page ingestion (original behavior)
+        match ingester.process_url(&req.url, &project_id).await {
+            Ok(memory_ids) => {
+                audit_memories_created(&state.mt_engine, &headers, &project_id, &memory_ids);
+                (StatusCode::OK, Json(serde_json::json!({
+                    "status": "ingested",
+                    "url": req.url,
+                    "chunks": memory_ids.len(),
+                    "memory_ids": memory_ids
+                })))
+            }
+            Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+                "error": format!("Failed to ingest URL: {}", e)
+            }))),
+        }
+    }
+}
+
+/// Sitemaps and feeds tracked for the project
+async fn list_feeds(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    match state.mt_engine.get_project_feeds(&project_id) {
+        Ok(feeds) => (StatusCode::OK, Json(serde_json::json!({
+            "project_id": project_id,
+            "feeds": feeds.iter().map(|feed| serde_json::json!({
+                "url": feed.url,
+                "refresh_interval_secs": feed.refresh_interval_secs,
+                "last_refreshed": feed.last_refreshed,
+                "entries": feed.entries.len(),
+            })).collect::<Vec<_>>()
+        }))),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+/// Stop tracking a feed; the pages ingested from it stay
+async fn delete_feed(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    if state.read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+    let Some(url) = params.get("url") else {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Missing 'url' query parameter"})));
+    };
+
+    match state.mt_engine.remove_project_feed(&project_id, url) {
+        Ok(true) => (StatusCode::OK, Json(serde_json::json!({"status": "removed", "url": url}))),
+        Ok(false) => (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": format!("Feed '{}' not found", url)}))),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+/// Request for PUT /connectors/:id
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConnectorRequest {
+    pub kind: crate::connectors::ConnectorKind,
+    /// GitHub: `owner/repo`
+    #[serde(default)]
+    pub repo: String,
+    /// Notion: only sync the pages of this database
+    #[serde(default)]
+    pub database_id: Option<String>,
+    /// mbox: file under one of the project's watch directories
+    #[serde(default)]
+    pub path: String,
+    /// API base URL, or the IMAP mailbox URL
+    #[serde(default)]
+    pub api_url: String,
+    /// IMAP: login name
+    #[serde(default)]
+    pub username: String,
+    /// Environment variable holding the token or password
+    #[serde(default)]
+    pub token_env: String,
+    /// Seconds between scheduled syncs (None or 0 = only on request)
+    #[serde(default)]
+    pub sync_interval_secs: Option<u64>,
+}
+
+async fn list_connectors(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    match state.mt_engine.get_project_connectors(&project_id) {
+        Ok(connectors) => (StatusCode::OK, Json(serde_json::json!({
+            "project_id": project_id,
+            "connectors": connectors,
+        }))),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    }
+}
+
+/// Add or reconfigure a connector. Its sync cursor is kept unless it now
+/// reads a different source.
+async fn set_connector(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Path(connector_id): Path<String>,
+    Json(req): Json<ConnectorRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    if state.read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
+    }
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+    if !crate::connectors::validate_connector_id(&connector_id) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+            "error": "Connector id must be 1-64 letters, digits, '-' or '_'"
+        })));
+    }
+    if let Err(e) = state.mt_engine.get_or_create_project(project_id.clone()) {
+        return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e})));
+    }
+
+    // mbox files are read from the server's disk: only those the project's agents could ingest
+    let mut path = req.path;
+    if req.kind == crate::connectors::ConnectorKind::Mbox && !path.is_empty() {
+        let canonical = match std::fs::canonicalize(&path) {
+            Ok(p) => p,
+            Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+                "error": format!("Cannot open {}: {}", path, e)
+            }))),
+        };
+        let watched = state
+            .agent_manager
+            .list_agents()
+            .await
+            .into_iter()
+            .any(|(_, config)| config.project_id == project_id && canonical.starts_with(&config.watch_dir));
+        if !watched {
+            return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+                "error": format!("{} is not under a watch directory of project '{}'", path, project_id)
+            })));
         }
-    }).collect();
+        path = canonical.to_string_lossy().to_string();
+    }
 
-    // Sort by score desc
-    scored_chunks.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
-    
-    // Take top results (more for search mode to have variety)
-    let limit = if req.url.is_none() { 10 } else { 5 };
-    
-    let results: Vec<serde_json::Value> = scored_chunks.into_iter()
-        .take(limit)
-        .filter(|r| r.score > 0.0)
-        .map(|r| serde_json::json!({
-            "content": r.content,
-            "score": r.score,
-            "intersection": r.intersection,
-            // "url": r.url // Optional source info
-        }))
-        .collect();
-        
-    let processing_ms = processing_start.elapsed().as_secs_f64() * 1000.0;
+    let mut connector = crate::connectors::ConnectorConfig {
+        id: connector_id.clone(),
+        kind: req.kind,
+        repo: req.repo,
+        database_id: req.database_id,
+        path,
+        api_url: req.api_url,
+        username: req.username,
+        token_env: req.token_env,
+        sync_interval_secs: req.sync_interval_secs.filter(|secs| *secs > 0),
+        cursor: None,
+        last_synced: None,
+        last_error: None,
+    };
+    // Reject missing tokens and malformed settings now rather than at the first sync
+    if let Err(e) = crate::connectors::build_connector(&connector) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e})));
+    }
+    match state.mt_engine.get_project_connector(&project_id, &connector_id) {
+        Ok(Some(existing)) if existing.same_source(&connector) => {
+            connector.cursor = existing.cursor;
+            connector.last_synced = existing.last_synced;
+            connector.last_error = existing.last_error;
+        }
+        Ok(_) => {}
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    }
 
-    // 3. Optional Persistence (Async)
-    if req.persist {
-        let project_id_clone = project_id.clone();
-        let chunks_clone = chunks.clone();
-        let urls_processed_clone = urls_processed.clone();
-        
-        // Fix: Use local job_queue variable, not state.job_queue (which is moved)
-        let job_queue_clone = job_queue.clone(); 
-        
-        tokio::spawn(async move {
-             let config = AgentConfig {
-                project_id: project_id_clone.clone(),
-                watch_dir: String::new(),
-                throttle_ms: 0,
-                state_file: None,
-            };
-            let mut async_ingester = Ingester::new(config, job_queue_clone);
-            
-            // For search results, we have mixed sources. `process_chunks` expects a single source?
-            // `process_chunks` takes `source: &str`.
-            // We should arguably just say "web_search" or iterate and group by source?
-            // Actually `process_chunks` uses source to generate ID. If we pass "web_search", valid.
-            // But we should differentiate URLs if possible. 
-            // Ingester implementation:
-            // let memory_id = format!("{}:{}", source, chunk_hash);
-            // If all share "web_search", they dedup by content hash, which is fine.
-            
-            let source = if urls_processed_clone.len() == 1 {
-                 format!("url:{}", urls_processed_clone[0]) 
-            } else {
-                 "web_search".to_string()
-            };
+    match state.mt_engine.set_project_connector(&project_id, connector.clone()) {
+        Ok(()) => (StatusCode::OK, Json(serde_json::json!({
+            "project_id": project_id,
+            "connector": connector,
+        }))),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    }
+}
 
-            if let Err(e) = async_ingester.process_chunks(chunks_clone, &project_id_clone, &source).await {
-                tracing::error!("Async persistence failed for web recall: {}", e);
-            }
-        });
+/// Remove a connector; the documents it ingested stay
+async fn delete_connector(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Path(connector_id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    if state.read_only {
+        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
     }
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
 
-    let total_ms = start_time.elapsed().as_secs_f64() * 1000.0;
-    
-    (StatusCode::OK, Json(serde_json::json!({
-        "urls": urls_processed,
-        "results": results,
-        "latency_ms": total_ms,
-        "timings": {
-            "fetch_chunk": fetch_ms,
-            "search_overhead": if req.url.is_none() { true } else { false },
-            "processing": processing_ms
-        }
-    })))
+    match state.mt_engine.remove_project_connector(&project_id, &connector_id) {
+        Ok(true) => (StatusCode::OK, Json(serde_json::json!({"status": "removed", "id": connector_id}))),
+        Ok(false) => (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": format!("Connector '{}' not found", connector_id)}))),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    }
 }
 
-/// Ingest content from a URL using the Agent's Ingester
-/// Supports recursive crawling when depth > 0
-async fn ingest_url(
+/// Sync a connector now. `?full=true` starts over from the beginning.
+async fn sync_connector(
     State(state): State<EngineState>,
     headers: HeaderMap,
-    Json(req): Json<IngestUrlRequest>,
+    Path(connector_id): Path<String>,
+    axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>,
 ) -> (StatusCode, Json<serde_json::Value>) {
     use crate::agent::ingester::Ingester;
     use crate::agent::AgentConfig;
-    
-    let EngineState { read_only, job_queue, .. } = state;
-    if read_only {
+
+    if state.read_only {
         return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Read-only mode"})));
     }
-    
     let project_id = match extract_project_id(&headers) {
         Ok(id) => id,
         Err(e) => return e,
     };
-    
-    // Ensure project exists (auto-create)
+    let mut connector = match state.mt_engine.get_project_connector(&project_id, &connector_id) {
+        Ok(Some(connector)) => connector,
+        Ok(None) => return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": format!("Connector '{}' not found", connector_id)}))),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e}))),
+    };
     if let Err(e) = state.mt_engine.get_or_create_project(project_id.clone()) {
         return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e})));
     }
-    
-    // Create an ingester for this request
+    if let Err(e) = state.mt_engine.check_quota(&project_id, 1, 0) {
+        return quota_exceeded_response(e);
+    }
+    if params.get("full").is_some_and(|v| v == "true") {
+        connector.cursor = None;
+    }
+
+    let source = match crate::connectors::build_connector(&connector) {
+        Ok(source) => source,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+    };
     let config = AgentConfig {
         project_id: project_id.clone(),
-        watch_dir: String::new(), // Not used for API-driven ingestion
+        watch_dir: String::new(),
         throttle_ms: 0,
         state_file: None,
+        filter: Default::default(),
+        git: Default::default(),
+        chunking: state.mt_engine.get_project_chunking(&project_id).unwrap_or_default(),
+        extract_facts: false,
     };
-    let mut ingester = Ingester::new(config, job_queue);
-    
-    // Check if recursive crawling is requested
-    if req.depth > 0 {
-        // Recursive crawl
-        match ingester.process_url_recursive(
-            &req.url, 
-            &project_id, 
-            req.depth, 
-            req.same_domain_only,
-        ).await {
-            Ok(result) => (StatusCode::OK, Json(serde_json::json!({
-                "status": "crawled",
-                "url": req.url,
-                "depth": req.depth,
-                "pages_crawled": result.pages_crawled,
-                "total_chunks": result.memory_ids.len(),
-                "links_found": result.links_found,
-                "links_skipped": result.links_skipped,
-                "memory_ids": result.memory_ids,
-                "errors": result.errors.iter().map(|(url, err)| {
-                    serde_json::json!({"url": url, "error": err})
+    let mut ingester = Ingester::new(config, state.job_queue.clone());
+    let result = ingester.process_connector(source.as_ref(), &mut connector, &project_id).await;
+    connector.last_error = result.as_ref().err().cloned();
+    if let Err(e) = state.mt_engine.set_project_connector(&project_id, connector.clone()) {
+        return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e})));
+    }
+
+    match result {
+        Ok(report) => {
+            audit_memories_created(&state.mt_engine, &headers, &project_id, &report.memory_ids);
+            (StatusCode::OK, Json(serde_json::json!({
+                "status": "synced",
+                "id": connector_id,
+                "connector": source.name(),
+                "documents": report.synced.len(),
+                "total_chunks": report.memory_ids.len(),
+                "memory_ids": report.memory_ids,
+                "cursor": connector.cursor,
+                "more": report.more,
+                "errors": report.errors.iter().map(|(id, err)| {
+                    serde_json::json!({"document": id, "error": err})
                 }).collect::<Vec<_>>()
-            }))),
-            Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({
-                "error": format!("Failed to crawl URL: {}", e)
-            }))),
-        }
-    } else {
-        // Single page ingestion (original behavior)
-        match ingester.process_url(&req.url, &project_id).await {
-            Ok(memory_ids) => (StatusCode::OK, Json(serde_json::json!({
-                "status": "ingested",
-                "url": req.url,
-                "chunks": memory_ids.len(),
-                "memory_ids": memory_ids
-            }))),
-            Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({
-                "error": format!("Failed to ingest URL: {}", e)
-            }))),
+            })))
         }
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+            "error": format!("Failed to sync connector: {}", e)
+        }))),
     }
 }
 
 /// Request for POST /ingest/content - ingest raw content
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct IngestContentRequest {
     pub content: String,
     #[serde(default = "default_filename")]
     pub filename: String, // Used to determine content type (e.g. "notes.md", "data.json")
+    /// Overrides of the project's chunk size limits
+    #[serde(default)]
+    pub chunking: ChunkingConfig,
+    /// Also store the facts the project's LLM finds in each chunk as memories
+    #[serde(default)]
+    pub extract_facts: bool,
 }
 
 fn default_filename() -> String {
@@ -1979,6 +6372,14 @@ async fn ingest_content(
     if let Err(e) = state.mt_engine.get_or_create_project(project_id.clone()) {
         return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({"error": e})));
     }
+
+    if let Err(e) = state.mt_engine.check_quota(&project_id, 1, req.content.len() as u64) {
+        return quota_exceeded_response(e);
+    }
+    let chunking = match request_chunking(&state.mt_engine, &project_id, &req.chunking) {
+        Ok(chunking) => chunking,
+        Err(e) => return e,
+    };
     
     // Create an ingester for this request
     let config = AgentConfig {
@@ -1986,23 +6387,172 @@ async fn ingest_content(
         watch_dir: String::new(),
         throttle_ms: 0,
         state_file: None,
+        filter: Default::default(),
+        git: Default::default(),
+        chunking,
+        extract_facts: req.extract_facts,
     };
     let mut ingester = Ingester::new(config, job_queue);
     
     // Use the Ingester's process_content method
     match ingester.process_content(&req.content, &req.filename, &project_id).await {
-        Ok(memory_ids) => (StatusCode::OK, Json(serde_json::json!({
-            "status": "ingested",
-            "filename": req.filename,
-            "chunks": memory_ids.len(),
-            "memory_ids": memory_ids
-        }))),
+        Ok(memory_ids) => {
+            audit_memories_created(&state.mt_engine, &headers, &project_id, &memory_ids);
+            (StatusCode::OK, Json(serde_json::json!({
+                "status": "ingested",
+                "filename": req.filename,
+                "chunks": memory_ids.len(),
+                "memory_ids": memory_ids
+            })))
+        }
         Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({
             "error": format!("Failed to ingest content: {}", e)
         }))),
     }
 }
 
+/// Request for POST /ingest/preview - exactly one of `url`, `content` or `path`
+#[derive(Debug, Deserialize, Serialize)]
+pub struct IngestPreviewRequest {
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+    /// Determines the content type of `content`, as for /ingest/content
+    #[serde(default = "default_filename")]
+    pub filename: String,
+    /// A file under one of the project's agent watch directories
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Overrides of the project's chunk size limits
+    #[serde(default)]
+    pub chunking: ChunkingConfig,
+}
+
+/// Run the chunker and cue pipeline without writing anything
+async fn ingest_preview(
+    State(state): State<EngineState>,
+    headers: HeaderMap,
+    Json(req): Json<IngestPreviewRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    use crate::agent::chunker::Chunker;
+    use crate::agent::ingester::{chunk_memory_id, Ingester, PreviewChunk};
+
+    let EngineState { mt_engine, job_queue, agent_manager, .. } = state;
+
+    let project_id = match extract_project_id(&headers) {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let given = [req.url.is_some(), req.content.is_some(), req.path.is_some()].iter().filter(|g| **g).count();
+    if given != 1 {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+            "error": "Provide exactly one of url, content or path"
+        })));
+    }
+
+    let from_source = |chunks: Vec<crate::agent::chunker::Chunk>, source: String| -> Vec<PreviewChunk> {
+        chunks
+            .into_iter()
+            .map(|chunk| PreviewChunk {
+                memory_id: chunk_memory_id(&source, &chunk.content),
+                file_path: source.clone(),
+                chunk,
+            })
+            .collect()
+    };
+
+    let chunking = match request_chunking(&mt_engine, &project_id, &req.chunking) {
+        Ok(chunking) => chunking,
+        Err(e) => return e,
+    };
+
+    let previews = if let Some(url) = &req.url {
+        match Chunker::chunk_url(url, false).await {
+            Ok(chunks) => from_source(Chunker::apply_limits(chunks, &chunking), format!("url:{}", url)),
+            Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+                "error": format!("Failed to fetch URL: {}", e)
+            }))),
+        }
+    } else if let Some(content) = &req.content {
+        let chunks = Chunker::chunk_file(&std::path::PathBuf::from(&req.filename), content);
+        from_source(Chunker::apply_limits(chunks, &chunking), format!("api:{}", req.filename))
+    } else {
+        let path = req.path.clone().unwrap_or_default();
+        let canonical = match std::fs::canonicalize(&path) {
+            Ok(p) => p,
+            Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+                "error": format!("Cannot open {}: {}", path, e)
+            }))),
+        };
+        // Only files the project's agents could ingest, with their filters
+        let agent = agent_manager
+            .list_agents()
+            .await
+            .into_iter()
+            .map(|(_, config)| config)
+            .find(|config| config.project_id == project_id && canonical.starts_with(&config.watch_dir));
+        let Some(mut config) = agent else {
+            return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+                "error": format!("{} is not under a watch directory of project '{}'", path, project_id)
+            })));
+        };
+        // The agent's limits were read when it started
+        config.chunking = chunking;
+        match Ingester::new(config, job_queue).preview_file(&canonical) {
+            Ok(previews) => previews,
+            Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))),
+        }
+    };
+
+    // Cue resolution only reads the project (or a scratch one if it doesn't exist)
+    let ctx = mt_engine.get_project_or_scratch(&project_id);
+    let chunks = tokio::task::spawn_blocking(move || {
+        previews
+            .into_iter()
+            .map(|preview| {
+                let cues = crate::jobs::agent_chunk_cues(
+                    &ctx,
+                    &preview.chunk.content,
+                    &preview.file_path,
+                    &preview.chunk.structural_cues,
+                    preview.chunk.category,
+                );
+                serde_json::json!({
+                    "memory_id": preview.memory_id,
+                    "exists": ctx.main.get_memory(&preview.memory_id).is_some(),
+                    "category": format!("{:?}", preview.chunk.category).to_lowercase(),
+                    "start_line": preview.chunk.start_line,
+                    "end_line": preview.chunk.end_line,
+                    "content": preview.chunk.content,
+                    "structural_cues": preview.chunk.structural_cues,
+                    "cues": cues,
+                })
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .unwrap_or_default();
+
+    let estimated_memories = chunks
+        .iter()
+        .filter_map(|c| c["memory_id"].as_str())
+        .collect::<std::collections::HashSet<_>>()
+        .len();
+    let new_memories = chunks.iter().filter(|c| c["exists"] == false).count();
+    let total_bytes: usize = chunks.iter().filter_map(|c| c["content"].as_str()).map(str::len).sum();
+
+    (StatusCode::OK, Json(serde_json::json!({
+        "project_id": project_id,
+        "total_chunks": chunks.len(),
+        "estimated_memories": estimated_memories,
+        "new_memories": new_memories,
+        "total_bytes": total_bytes,
+        "chunks": chunks,
+    })))
+}
+
 /// Ingest a binary file via multipart upload (for PDFs, Office docs, etc.)
 async fn ingest_file(
     State(state): State<EngineState>,
@@ -2010,8 +6560,8 @@ async fn ingest_file(
     mut multipart: axum_extra::extract::Multipart,
 ) -> (StatusCode, Json<serde_json::Value>) {
     use crate::agent::chunker::Chunker;
+    use crate::agent::ingester::{chunk_memory_id, chunk_provenance};
     use crate::jobs::Job;
-    use sha2::{Sha256, Digest};
     use std::io::Write;
     
     let EngineState { read_only, job_queue, .. } = state;
@@ -2032,6 +6582,7 @@ async fn ingest_file(
         // Extract file from multipart
         let mut filename = String::new();
         let mut file_bytes: Vec<u8> = Vec::new();
+        let mut overrides = ChunkingConfig::default();
         
         while let Ok(Some(field)) = multipart.next_field().await {
             let name = field.name().unwrap_or("").to_string();
@@ -2045,8 +6596,21 @@ async fn ingest_file(
                 if let Ok(text) = field.text().await {
                     filename = text;
                 }
+            } else if name == "chunking" {
+                // Same JSON object as the `chunking` field of /ingest/content
+                let text = field.text().await.unwrap_or_default();
+                overrides = match serde_json::from_str(&text) {
+                    Ok(overrides) => overrides,
+                    Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+                        "error": format!("Invalid chunking: {}", e)
+                    }))),
+                };
             }
         }
+        let chunking = match request_chunking(&state.mt_engine, &project_id, &overrides) {
+            Ok(chunking) => chunking,
+            Err(e) => return e,
+        };
         
         if file_bytes.is_empty() {
             return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
@@ -2073,7 +6637,7 @@ async fn ingest_file(
         drop(temp_file);
         
         // Chunk the file
-        let chunks = Chunker::chunk_binary_file(&temp_path);
+        let chunks = Chunker::apply_limits(Chunker::chunk_binary_file(&temp_path), &chunking);
         
         // Clean up temp file
         let _ = std::fs::remove_file(&temp_path);
@@ -2083,6 +6647,11 @@ async fn ingest_file(
                 "error": "Failed to extract content from file (0 chunks)"
             })));
         }
+
+        let incoming_bytes: usize = chunks.iter().map(|c| c.content.len()).sum();
+        if let Err(e) = state.mt_engine.check_quota(&project_id, chunks.len(), incoming_bytes as u64) {
+            return quota_exceeded_response(e);
+        }
         
         // Track session for progress reporting
         let session = job_queue.session_manager.get_or_create(&project_id);
@@ -2095,10 +6664,7 @@ async fn ingest_file(
         let mut memory_ids = Vec::new();
         
         for chunk in chunks.iter() {
-            let mut chunk_hasher = Sha256::new();
-            chunk_hasher.update(chunk.content.as_bytes());
-            let chunk_hash = format!("{:x}", chunk_hasher.finalize());
-            let memory_id = format!("{}:{}", source, chunk_hash);
+            let memory_id = chunk_memory_id(&source, &chunk.content);
             
             // ExtractAndIngest does the write - enqueue immediately
             job_queue.enqueue(Job::ExtractAndIngest {
@@ -2108,6 +6674,7 @@ async fn ingest_file(
                 file_path: source.clone(),
                 structural_cues: chunk.structural_cues.clone(),
                 category: chunk.category,
+                provenance: chunk_provenance(&source, chunk),
             }).await;
             
             // Buffer downstream jobs for phased processing
@@ -2131,6 +6698,8 @@ async fn ingest_file(
             
             memory_ids.push(memory_id);
         }
+
+        audit_memories_created(&state.mt_engine, &headers, &project_id, &memory_ids);
         
         (StatusCode::OK, Json(serde_json::json!({
             "status": "ingested",
@@ -2167,7 +6736,7 @@ async fn context_expand(
     };
     
     // 1. Tokenize query into cues
-    let query_cues = crate::nl::tokenize_to_cues(&req.query);
+    let query_cues = ctx.tokenize(&req.query);
     
     if query_cues.is_empty() {
         return (StatusCode::OK, Json(serde_json::json!({
@@ -2181,7 +6750,7 @@ async fn context_expand(
     let normalized_cues: Vec<String> = query_cues
         .iter()
         .map(|cue| {
-            let (normalized, _) = normalize_cue(cue, &ctx.normalization);
+            let normalized = ctx.normalize_cue(cue);
             normalized
         })
         .collect();
@@ -2248,9 +6817,17 @@ async fn prometheus_metrics(
     
     // Get active jobs count
     let active_jobs = job_queue.pending_count();
+
+    // Cue index shards and posting lists, summed over loaded projects
+    let mut index_stats = CueIndexReport::default();
+    for project_id in mt_engine.loaded_project_ids() {
+        if let Some(ctx) = mt_engine.get_project(&project_id) {
+            index_stats.merge(&ctx.main.cue_index_stats());
+        }
+    }
     
     // Build Prometheus format output
-    let output = format!(
+    let mut output = format!(
         "# HELP cuemap_ingestion_rate Total memory ingestions since startup
 # TYPE cuemap_ingestion_rate counter
 cuemap_ingestion_rate {}
@@ -2297,6 +6874,9 @@ cuemap_active_jobs {}
         total_projects,
         active_jobs,
     );
+    output.push_str(&job_queue_metrics(&job_queue.queue_depths(), job_queue.failed_jobs().len()));
+    output.push_str(&cue_index_metrics(&index_stats));
+    output.push_str(&project_metrics(&per_project_series(&mt_engine, &metrics, &job_queue)));
     
     (
         StatusCode::OK,
@@ -2308,11 +6888,130 @@ cuemap_active_jobs {}
     )
 }
 
+/// Prometheus lines for the worker pool's queued and running jobs per kind
+/// and its dead-letter list
+fn job_queue_metrics(
+    depths: &std::collections::BTreeMap<crate::jobs::JobKind, crate::jobs::QueueDepth>,
+    failed: usize,
+) -> String {
+    use std::fmt::Write;
+
+    let mut out = String::new();
+    out.push_str("\n# HELP cuemap_job_queue_depth Jobs waiting for a worker, per kind\n");
+    out.push_str("# TYPE cuemap_job_queue_depth gauge\n");
+    for (kind, depth) in depths {
+        let _ = writeln!(out, "cuemap_job_queue_depth{{kind=\"{}\"}} {}", kind.name(), depth.queued);
+    }
+    out.push_str("\n# HELP cuemap_jobs_running Jobs being processed, per kind\n");
+    out.push_str("# TYPE cuemap_jobs_running gauge\n");
+    for (kind, depth) in depths {
+        let _ = writeln!(out, "cuemap_jobs_running{{kind=\"{}\"}} {}", kind.name(), depth.running);
+    }
+    out.push_str("\n# HELP cuemap_jobs_failed Jobs in the dead-letter list\n");
+    out.push_str("# TYPE cuemap_jobs_failed gauge\n");
+    let _ = writeln!(out, "cuemap_jobs_failed {}", failed);
+    out
+}
+
+/// Per-project rows for `/metrics`: loaded and archived projects, capped at the
+/// collector's `max_project_series`
+fn per_project_series(mt_engine: &MultiTenantEngine, metrics: &MetricsCollector, job_queue: &JobQueue) -> Vec<ProjectSeries> {
+    if metrics.max_project_series() == 0 {
+        return Vec::new();
+    }
+    let recalls = metrics.project_recalls();
+    let backlog = job_queue.project_backlog();
+    let mut rows: HashMap<String, ProjectSeries> = HashMap::new();
+    for stats in mt_engine.list_projects().into_iter().chain(mt_engine.archived_projects()) {
+        let recall = recalls.get(&stats.project_id).copied().unwrap_or_default();
+        rows.insert(stats.project_id.clone(), ProjectSeries {
+            project: stats.project_id.clone(),
+            memories: stats.total_memories as u64,
+            recalls: recall.recalls,
+            recall_latency_p99: Some(recall.p99_latency_ms),
+            job_backlog: backlog.get(&stats.project_id).copied().unwrap_or(0) as u64,
+        });
+    }
+    project_series(rows.into_values().collect(), metrics.max_project_series())
+}
+
+/// Prometheus lines for the per-project breakdown
+fn project_metrics(rows: &[ProjectSeries]) -> String {
+    use std::fmt::Write;
+
+    if rows.is_empty() {
+        return String::new();
+    }
+    let mut out = String::new();
+    out.push_str("\n# HELP cuemap_project_memories Memories per project\n");
+    out.push_str("# TYPE cuemap_project_memories gauge\n");
+    for row in rows {
+        let _ = writeln!(out, "cuemap_project_memories{{project=\"{}\"}} {}", row.project, row.memories);
+    }
+    out.push_str("\n# HELP cuemap_project_recall_requests_total Recall requests per project since startup\n");
+    out.push_str("# TYPE cuemap_project_recall_requests_total counter\n");
+    for row in rows {
+        let _ = writeln!(out, "cuemap_project_recall_requests_total{{project=\"{}\"}} {}", row.project, row.recalls);
+    }
+    out.push_str("\n# HELP cuemap_project_recall_latency_p99 P99 recall latency per project in milliseconds\n");
+    out.push_str("# TYPE cuemap_project_recall_latency_p99 gauge\n");
+    for row in rows {
+        if let Some(p99) = row.recall_latency_p99 {
+            let _ = writeln!(out, "cuemap_project_recall_latency_p99{{project=\"{}\"}} {:.2}", row.project, p99);
+        }
+    }
+    out.push_str("\n# HELP cuemap_project_job_backlog Background jobs waiting per project\n");
+    out.push_str("# TYPE cuemap_project_job_backlog gauge\n");
+    for row in rows {
+        let _ = writeln!(out, "cuemap_project_job_backlog{{project=\"{}\"}} {}", row.project, row.job_backlog);
+    }
+    out
+}
+
+/// Prometheus lines for cue index shard contention and posting-list lengths
+fn cue_index_metrics(report: &CueIndexReport) -> String {
+    use std::fmt::Write;
+
+    let mut out = String::new();
+    out.push_str("\n# HELP cuemap_cue_index_shard_writes_total Cue index writes per shard\n");
+    out.push_str("# TYPE cuemap_cue_index_shard_writes_total counter\n");
+    for shard in &report.shards {
+        let _ = writeln!(out, "cuemap_cue_index_shard_writes_total{{shard=\"{}\"}} {}", shard.shard, shard.writes);
+    }
+    out.push_str("\n# HELP cuemap_cue_index_shard_contended_total Cue index writes that found the shard lock held\n");
+    out.push_str("# TYPE cuemap_cue_index_shard_contended_total counter\n");
+    for shard in &report.shards {
+        let _ = writeln!(out, "cuemap_cue_index_shard_contended_total{{shard=\"{}\"}} {}", shard.shard, shard.contended_writes);
+    }
+    out.push_str("\n# HELP cuemap_cue_index_shard_cues Cues stored per shard\n");
+    out.push_str("# TYPE cuemap_cue_index_shard_cues gauge\n");
+    for shard in &report.shards {
+        let _ = writeln!(out, "cuemap_cue_index_shard_cues{{shard=\"{}\"}} {}", shard.shard, shard.cues);
+    }
+    out.push_str("\n# HELP cuemap_cue_list_length Memories per cue posting list\n");
+    out.push_str("# TYPE cuemap_cue_list_length histogram\n");
+    for (i, count) in report.list_length_buckets.iter().enumerate() {
+        match LIST_LENGTH_BUCKETS.get(i) {
+            Some(bound) => { let _ = writeln!(out, "cuemap_cue_list_length_bucket{{le=\"{}\"}} {}", bound, count); }
+            None => { let _ = writeln!(out, "cuemap_cue_list_length_bucket{{le=\"+Inf\"}} {}", count); }
+        }
+    }
+    let _ = writeln!(out, "cuemap_cue_list_length_sum {}", report.total_list_length);
+    let _ = writeln!(out, "cuemap_cue_list_length_count {}", report.list_length_buckets.last().copied().unwrap_or(0));
+    out.push_str("\n# HELP cuemap_cue_list_length_max Longest cue posting list\n");
+    out.push_str("# TYPE cuemap_cue_list_length_max gauge\n");
+    let _ = writeln!(out, "cuemap_cue_list_length_max {}", report.max_list_length);
+    out.push_str("\n# HELP cuemap_cue_index_segmented_cues Cues whose posting list is split into segments\n");
+    out.push_str("# TYPE cuemap_cue_index_segmented_cues gauge\n");
+    let _ = writeln!(out, "cuemap_cue_index_segmented_cues {}", report.segmented_cues);
+    out
+}
+
 // ============================================================================
 // Cloud Backup Endpoints
 // ============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct BackupRequest {
     pub project_id: String,
 }
@@ -0,0 +1,190 @@
+//! Append-only audit log of data-changing and data-exporting operations.
+//!
+//! Each entry records who (a fingerprint of the caller's API key), what
+//! (action, project, memory) and when. Entries are appended as JSON lines to
+//! `audit.log` in the snapshots directory and are never rewritten, so the file
+//! survives restarts and is backed up together with the snapshots.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    MemoryCreated,
+    MemoryDeleted,
+    ProjectCreated,
+    ProjectDeleted,
+    ProjectExported,
+    ProjectImported,
+    /// Idle or unhelpful lexicon entries removed; `detail` has the count
+    LexiconPruned,
+}
+
+/// Actor of changes the server makes on its own (scheduled tasks, agent jobs)
+pub const SYSTEM_ACTOR: &str = "system";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: f64,
+    /// `key:<fingerprint>` for authenticated callers, `anonymous` otherwise,
+    /// `system` for the server's own changes
+    pub actor: String,
+    pub action: AuditAction,
+    pub project_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<serde_json::Value>,
+}
+
+impl AuditEntry {
+    pub fn new(actor: String, action: AuditAction, project_id: &str) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs_f64(),
+            actor,
+            action,
+            project_id: project_id.to_string(),
+            memory_id: None,
+            detail: None,
+        }
+    }
+
+    pub fn with_memory(mut self, memory_id: &str) -> Self {
+        self.memory_id = Some(memory_id.to_string());
+        self
+    }
+
+    pub fn with_detail(mut self, detail: serde_json::Value) -> Self {
+        self.detail = Some(detail);
+        self
+    }
+}
+
+/// Filters for `AuditLog::query`. Unset fields match everything.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuditQuery {
+    #[serde(default)]
+    pub project_id: Option<String>,
+    #[serde(default)]
+    pub memory_id: Option<String>,
+    #[serde(default)]
+    pub action: Option<AuditAction>,
+    #[serde(default)]
+    pub actor: Option<String>,
+    #[serde(default)]
+    pub since: Option<f64>,
+    #[serde(default)]
+    pub until: Option<f64>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl AuditQuery {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        self.project_id.as_ref().is_none_or(|p| &entry.project_id == p)
+            && self.memory_id.as_ref().is_none_or(|m| entry.memory_id.as_ref() == Some(m))
+            && self.action.is_none_or(|a| entry.action == a)
+            && self.actor.as_ref().is_none_or(|a| &entry.actor == a)
+            && self.since.is_none_or(|t| entry.timestamp >= t)
+            && self.until.is_none_or(|t| entry.timestamp <= t)
+    }
+}
+
+/// Identify a caller without writing their API key to disk
+pub fn actor_for_key(api_key: Option<&str>) -> String {
+    match api_key {
+        Some(key) => {
+            let digest = Sha256::digest(key.as_bytes());
+            format!("key:{}", &hex::encode(digest)[..12])
+        }
+        None => "anonymous".to_string(),
+    }
+}
+
+pub struct AuditLog {
+    path: PathBuf,
+    file: Mutex<Option<File>>,
+}
+
+impl AuditLog {
+    pub fn open<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let file = match OpenOptions::new().create(true).append(true).open(&path) {
+            Ok(f) => Some(f),
+            Err(e) => {
+                error!("Audit log: failed to open {:?}: {}", path, e);
+                None
+            }
+        };
+        Self {
+            path,
+            file: Mutex::new(file),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append one entry. Failures are logged rather than failing the operation being audited.
+    pub fn record(&self, entry: AuditEntry) {
+        let mut line = match serde_json::to_string(&entry) {
+            Ok(l) => l,
+            Err(e) => {
+                error!("Audit log: failed to serialize entry: {}", e);
+                return;
+            }
+        };
+        line.push('\n');
+
+        let mut guard = self.file.lock().unwrap();
+        if let Some(file) = guard.as_mut() {
+            if let Err(e) = file.write_all(line.as_bytes()).and_then(|_| file.flush()) {
+                error!("Audit log: failed to append to {:?}: {}", self.path, e);
+            }
+        }
+    }
+
+    /// Matching entries in chronological order. With a `limit`, the most recent
+    /// `limit` matches are returned.
+    pub fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, String> {
+        // Hold the lock so a concurrent append can't be read half-written
+        let _guard = self.file.lock().unwrap();
+
+        let file = match File::open(&self.path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Failed to open audit log: {}", e)),
+        };
+
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| format!("Failed to read audit log: {}", e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<AuditEntry>(&line) {
+                Ok(entry) if query.matches(&entry) => entries.push(entry),
+                Ok(_) => {}
+                Err(e) => error!("Audit log: skipping unreadable entry: {}", e),
+            }
+        }
+
+        if let Some(limit) = query.limit {
+            if entries.len() > limit {
+                entries.drain(..entries.len() - limit);
+            }
+        }
+        Ok(entries)
+    }
+}
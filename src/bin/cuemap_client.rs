@@ -0,0 +1,37 @@
+//! Writes the generated TypeScript and Python SDKs (see `cuemap::client_gen`).
+
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(name = "cuemap-client", about = "Generate CueMap TypeScript and Python clients")]
+struct Args {
+    /// Output directory; SDKs go to `typescript/` and `python/` below it
+    #[arg(long, default_value = "clients")]
+    out: PathBuf,
+}
+
+fn main() {
+    let args = Args::parse();
+    let files = match cuemap::client_gen::generate() {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    for (relative, contents) in files {
+        let path = args.out.join(relative);
+        if let Some(parent) = path.parent() {
+            if let Err(e) = std::fs::create_dir_all(parent) {
+                eprintln!("Error: cannot create {}: {}", parent.display(), e);
+                std::process::exit(1);
+            }
+        }
+        if let Err(e) = std::fs::write(&path, contents) {
+            eprintln!("Error: cannot write {}: {}", path.display(), e);
+            std::process::exit(1);
+        }
+        println!("Wrote {}", path.display());
+    }
+}
@@ -32,7 +32,7 @@ fn main() {
                 // Preview content (first 50 chars)
                 // Preview content type
                 // Use the crypto helper to guess type
-                let preview = if cuemap::crypto::is_compressed(&memory.content) {
+                let preview = if cuemap::crypto::is_compressed(&memory.content.bytes().unwrap_or_default()) {
                     "[Compressed Zstd]"
                 } else {
                     "[Encrypted/Binary]"
@@ -0,0 +1,391 @@
+//! Minimal TypeScript and Python SDKs generated from the OpenAPI spec.
+//!
+//! Covers the memory, recall, grounding and ingest endpoints listed in
+//! `CLIENT_OPERATIONS`. Request types come from `openapi::request_schemas`,
+//! which `tests/openapi_test.rs` checks against the structs in `src/api.rs`,
+//! so regenerating after an API change keeps the SDKs in step:
+//!
+//! ```text
+//! cargo run --features cuemap-client --bin cuemap-client -- --out clients
+//! ```
+
+use crate::openapi::{self, Body, Operation, OPERATIONS};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// (method, path, client method name) of every endpoint the SDKs wrap
+pub const CLIENT_OPERATIONS: &[(&str, &str, &str)] = &[
+    ("post", "/memories", "addMemory"),
+    ("get", "/memories/:id", "getMemory"),
+    ("delete", "/memories/:id", "deleteMemory"),
+    ("patch", "/memories/:id/reinforce", "reinforceMemory"),
+    ("patch", "/memories/:id/demote", "demoteMemory"),
+    ("put", "/memories/:id/pin", "pinMemory"),
+    ("get", "/memories/:id/history", "memoryHistory"),
+    ("post", "/memories/:id/rollback", "rollbackMemory"),
+    ("post", "/recall", "recall"),
+    ("post", "/recall/grounded", "recallGrounded"),
+    ("post", "/recall/:recall_id/feedback", "recallFeedback"),
+    ("get", "/recall/:recall_id/citations/:citation", "resolveCitation"),
+    ("post", "/answer", "answer"),
+    ("post", "/context/expand", "expandContext"),
+    ("post", "/ingest/url", "ingestUrl"),
+    ("post", "/ingest/content", "ingestContent"),
+];
+
+/// A wrapped endpoint with its spec entry
+pub struct ClientOperation {
+    pub name: &'static str,
+    pub operation: &'static Operation,
+}
+
+/// `CLIENT_OPERATIONS` resolved against `openapi::OPERATIONS`
+pub fn client_operations() -> Result<Vec<ClientOperation>, String> {
+    CLIENT_OPERATIONS
+        .iter()
+        .map(|&(method, path, name)| {
+            OPERATIONS
+                .iter()
+                .find(|op| op.method == method && op.path == path)
+                .map(|operation| ClientOperation { name, operation })
+                .ok_or_else(|| format!("{} {} is not in the OpenAPI spec", method.to_uppercase(), path))
+        })
+        .collect()
+}
+
+/// Request schemas the SDK needs: every body plus the schemas they reference
+pub fn client_schemas(operations: &[ClientOperation]) -> BTreeSet<String> {
+    let schemas = openapi::request_schemas();
+    let mut pending: Vec<String> = operations
+        .iter()
+        .filter_map(|op| match op.operation.body {
+            Body::Json(name) => Some(name.to_string()),
+            _ => None,
+        })
+        .collect();
+    let mut needed = BTreeSet::new();
+    while let Some(name) = pending.pop() {
+        if needed.insert(name.clone()) {
+            if let Some(schema) = schemas.get(&name) {
+                collect_refs(schema, &mut pending);
+            }
+        }
+    }
+    needed
+}
+
+fn collect_refs(schema: &Value, out: &mut Vec<String>) {
+    match schema {
+        Value::Object(map) => {
+            if let Some(name) = map.get("$ref").and_then(|r| r.as_str()) {
+                out.push(ref_name(name).to_string());
+            }
+            map.values().for_each(|v| collect_refs(v, out));
+        }
+        Value::Array(items) => items.iter().for_each(|v| collect_refs(v, out)),
+        _ => {}
+    }
+}
+
+fn ref_name(reference: &str) -> &str {
+    reference.trim_start_matches("#/components/schemas/")
+}
+
+fn path_params(path: &str) -> Vec<&str> {
+    path.split('/').filter_map(|s| s.strip_prefix(':')).collect()
+}
+
+fn properties(schema: &Value) -> (Vec<(&String, &Value)>, BTreeSet<&str>) {
+    let props = schema["properties"].as_object().map(|p| p.iter().collect()).unwrap_or_default();
+    let required = schema["required"]
+        .as_array()
+        .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+    (props, required)
+}
+
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            out.push('_');
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn ts_type(schema: &Value) -> String {
+    let base = if let Some(name) = schema["$ref"].as_str() {
+        ref_name(name).to_string()
+    } else if let Some(values) = schema["enum"].as_array() {
+        values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(" | ")
+    } else {
+        match schema["type"].as_str() {
+            Some("string") => "string".to_string(),
+            Some("integer") | Some("number") => "number".to_string(),
+            Some("boolean") => "boolean".to_string(),
+            Some("array") => {
+                let item = ts_type(&schema["items"]);
+                if item.contains(' ') { format!("({})[]", item) } else { format!("{}[]", item) }
+            }
+            Some("object") => "Record<string, unknown>".to_string(),
+            _ => "unknown".to_string(),
+        }
+    };
+    if schema["nullable"] == Value::Bool(true) {
+        format!("{} | null", base)
+    } else {
+        base
+    }
+}
+
+fn py_type(schema: &Value) -> String {
+    let base = if let Some(name) = schema["$ref"].as_str() {
+        ref_name(name).to_string()
+    } else if let Some(values) = schema["enum"].as_array() {
+        format!("Literal[{}]", values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", "))
+    } else {
+        match schema["type"].as_str() {
+            Some("string") => "str".to_string(),
+            Some("integer") => "int".to_string(),
+            Some("number") => "float".to_string(),
+            Some("boolean") => "bool".to_string(),
+            Some("array") => format!("List[{}]", py_type(&schema["items"])),
+            Some("object") => "Dict[str, Any]".to_string(),
+            _ => "Any".to_string(),
+        }
+    };
+    if schema["nullable"] == Value::Bool(true) {
+        format!("Optional[{}]", base)
+    } else {
+        base
+    }
+}
+
+const GENERATED: &str = "Generated by `cuemap-client` from the CueMap OpenAPI spec. Do not edit.";
+
+/// `cuemap.ts`: request interfaces and a `fetch`-based `CueMapClient`
+pub fn typescript_client() -> Result<String, String> {
+    let operations = client_operations()?;
+    let schemas = openapi::request_schemas();
+    let mut out = format!("// {}\n// CueMap API {}\n\n", GENERATED, env!("CARGO_PKG_VERSION"));
+
+    for name in client_schemas(&operations) {
+        let (props, required) = properties(&schemas[&name]);
+        out.push_str(&format!("export interface {} {{\n", name));
+        for (field, schema) in props {
+            let optional = if required.contains(field.as_str()) { "" } else { "?" };
+            out.push_str(&format!("  {}{}: {};\n", field, optional, ts_type(schema)));
+        }
+        out.push_str("}\n\n");
+    }
+
+    out.push_str(
+        r#"export interface CueMapOptions {
+  url?: string;
+  apiKey?: string;
+  /** Sent as X-Project-ID */
+  projectId?: string;
+}
+
+export class CueMapError extends Error {
+  constructor(public status: number, message: string) {
+    super(message);
+  }
+}
+
+export class CueMapClient {
+  private url: string;
+  private apiKey?: string;
+  projectId?: string;
+
+  constructor(options: CueMapOptions = {}) {
+    this.url = (options.url ?? 'http://localhost:8080').replace(/\/$/, '');
+    this.apiKey = options.apiKey;
+    this.projectId = options.projectId;
+  }
+
+  private async request(method: string, path: string, body?: unknown, query?: Record<string, unknown>): Promise<Record<string, any>> {
+    const url = new URL(this.url + path);
+    for (const [key, value] of Object.entries(query ?? {})) {
+      if (value !== undefined && value !== null) url.searchParams.set(key, String(value));
+    }
+    const headers: Record<string, string> = {};
+    if (body !== undefined) headers['Content-Type'] = 'application/json';
+    if (this.apiKey) headers['X-API-Key'] = this.apiKey;
+    if (this.projectId) headers['X-Project-ID'] = this.projectId;
+    const res = await fetch(url, { method, headers, body: body === undefined ? undefined : JSON.stringify(body) });
+    const data = await res.json().catch(() => ({}));
+    if (!res.ok) throw new CueMapError(res.status, data?.error ?? res.statusText);
+    return data;
+  }
+"#,
+    );
+
+    for op in &operations {
+        let params = path_params(op.operation.path);
+        let mut args: Vec<String> = params.iter().map(|p| format!("{}: string", p)).collect();
+        if let Body::Json(schema) = op.operation.body {
+            args.push(format!("body: {}", schema));
+        }
+        if !op.operation.query.is_empty() {
+            let fields: Vec<String> = op.operation.query.iter()
+                .map(|q| format!("{}?: {}", q.name, ts_type(&serde_json::json!({"type": q.kind}))))
+                .collect();
+            args.push(format!("query: {{ {} }} = {{}}", fields.join("; ")));
+        }
+        let path = params.iter().fold(op.operation.path.to_string(), |path, p| {
+            path.replace(&format!(":{}", p), &format!("${{encodeURIComponent({})}}", p))
+        });
+        let body = if matches!(op.operation.body, Body::Json(_)) { "body" } else { "undefined" };
+        let query = if op.operation.query.is_empty() { "" } else { ", query" };
+        out.push_str(&format!(
+            "\n  /** {} */\n  {}({}): Promise<Record<string, any>> {{\n    return this.request('{}', `{}`, {}{});\n  }}\n",
+            op.operation.summary,
+            op.name,
+            args.join(", "),
+            op.operation.method.to_uppercase(),
+            path,
+            body,
+            query,
+        ));
+    }
+    out.push_str("}\n\nexport default CueMapClient;\n");
+    Ok(out)
+}
+
+/// `cuemap_client.py`: `TypedDict` requests and a stdlib-only `CueMapClient` (Python 3.11+)
+pub fn python_client() -> Result<String, String> {
+    let operations = client_operations()?;
+    let schemas = openapi::request_schemas();
+    let mut out = format!(
+        "\"\"\"{}\n\nCueMap API {}\n\"\"\"\n\n",
+        GENERATED,
+        env!("CARGO_PKG_VERSION")
+    );
+    out.push_str(
+        r#"from __future__ import annotations
+
+import json
+import urllib.error
+import urllib.parse
+import urllib.request
+from typing import Any, Dict, List, Literal, Optional, Required, TypedDict
+
+"#,
+    );
+
+    for name in client_schemas(&operations) {
+        let (props, required) = properties(&schemas[&name]);
+        out.push_str(&format!("\nclass {}(TypedDict, total=False):\n", name));
+        if props.is_empty() {
+            out.push_str("    pass\n");
+        }
+        for (field, schema) in props {
+            let ty = py_type(schema);
+            let ty = if required.contains(field.as_str()) { format!("Required[{}]", ty) } else { ty };
+            out.push_str(&format!("    {}: {}\n", field, ty));
+        }
+        out.push('\n');
+    }
+
+    out.push_str(
+        r#"
+class CueMapError(Exception):
+    def __init__(self, status: int, message: str):
+        super().__init__(message)
+        self.status = status
+
+
+class CueMapClient:
+    def __init__(self, url: str = "http://localhost:8080", api_key: Optional[str] = None, project_id: Optional[str] = None):
+        self.url = url.rstrip("/")
+        self.api_key = api_key
+        self.project_id = project_id
+
+    def _request(self, method: str, path: str, body: Any = None, query: Optional[Dict[str, Any]] = None) -> Dict[str, Any]:
+        url = self.url + path
+        params = {k: v for k, v in (query or {}).items() if v is not None}
+        if params:
+            url += "?" + urllib.parse.urlencode(params)
+        headers = {}
+        data = None
+        if body is not None:
+            headers["Content-Type"] = "application/json"
+            data = json.dumps(body).encode()
+        if self.api_key:
+            headers["X-API-Key"] = self.api_key
+        if self.project_id:
+            headers["X-Project-ID"] = self.project_id
+        request = urllib.request.Request(url, data=data, headers=headers, method=method)
+        try:
+            with urllib.request.urlopen(request) as response:
+                payload = response.read()
+        except urllib.error.HTTPError as e:
+            try:
+                message = json.loads(e.read()).get("error", e.reason)
+            except ValueError:
+                message = e.reason
+            raise CueMapError(e.code, message) from None
+        return json.loads(payload) if payload else {}
+"#,
+    );
+
+    for op in &operations {
+        let params = path_params(op.operation.path);
+        let mut args: Vec<String> = vec!["self".to_string()];
+        args.extend(params.iter().map(|p| format!("{}: str", p)));
+        if let Body::Json(schema) = op.operation.body {
+            args.push(format!("body: {}", schema));
+        }
+        if !op.operation.query.is_empty() {
+            args.push("*".to_string());
+            args.extend(op.operation.query.iter().map(|q| {
+                let ty = py_type(&serde_json::json!({"type": q.kind}));
+                format!("{}: Optional[{}] = None", q.name, ty)
+            }));
+        }
+        let path = params.iter().fold(op.operation.path.to_string(), |path, p| {
+            path.replace(&format!(":{}", p), &format!("{{urllib.parse.quote({}, safe='')}}", p))
+        });
+        let body = if matches!(op.operation.body, Body::Json(_)) { "body" } else { "None" };
+        let query = if op.operation.query.is_empty() {
+            String::new()
+        } else {
+            let pairs: Vec<String> = op.operation.query.iter().map(|q| format!("\"{0}\": {0}", q.name)).collect();
+            format!(", {{{}}}", pairs.join(", "))
+        };
+        out.push_str(&format!(
+            "\n    def {}({}) -> Dict[str, Any]:\n        \"\"\"{}\"\"\"\n        return self._request(\"{}\", f\"{}\", {}{})\n",
+            snake_case(op.name),
+            args.join(", "),
+            op.operation.summary,
+            op.operation.method.to_uppercase(),
+            path,
+            body,
+            query,
+        ));
+    }
+    Ok(out)
+}
+
+/// Both SDKs as (relative path, contents)
+pub fn generate() -> Result<Vec<(&'static str, String)>, String> {
+    Ok(vec![
+        ("typescript/cuemap.ts", typescript_client()?),
+        ("python/cuemap_client.py", python_client()?),
+    ])
+}
+
+/// Field names of a request schema, for checks against generated code
+pub fn schema_fields(name: &str) -> Vec<String> {
+    let schemas: Map<String, Value> = openapi::request_schemas();
+    schemas
+        .get(name)
+        .and_then(|s| s["properties"].as_object())
+        .map(|p| p.keys().cloned().collect())
+        .unwrap_or_default()
+}
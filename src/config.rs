@@ -1,3 +1,6 @@
+use crate::nl::EmojiMode;
+use crate::structures::CueField;
+use crate::persistence::{SnapshotCodec, SnapshotCompression};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::env;
@@ -22,6 +25,26 @@ pub enum CueGenStrategy {
     Ollama   // Local Ollama with Mistral (+ WordNet)
 }
 
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingStrategy {
+    #[default]
+    None,     // No embeddings computed server-side (client-supplied vectors only)
+    Glove,    // Mean GloVe vector (bundled word vectors)
+    Onnx,     // Bundled all-MiniLM-L6-v2 via ONNX Runtime (`onnx` feature)
+    Ollama,   // Ollama /api/embed
+    Openai,   // OpenAI-compatible /v1/embeddings
+}
+
+/// Server log output
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Text,  // Human-readable lines
+    Json,  // One JSON object per line (Loki, ELK), with the request span's fields
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ServerConfig {
     #[serde(default)]
@@ -40,6 +63,19 @@ pub struct ServerConfig {
     pub search: SearchConfig,
     #[serde(default)]
     pub tuning: TuningConfig,
+    #[serde(default)]
+    pub embeddings: EmbeddingConfig,
+    #[serde(default)]
+    pub ocr: crate::agent::ocr::OcrConfig,
+    #[serde(default)]
+    pub transcription: crate::agent::transcribe::TranscriptionConfig,
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    /// Maintenance schedule of projects that have not set their own
+    #[serde(default)]
+    pub schedule: crate::scheduler::ProjectSchedule,
 }
 
 impl Default for ServerConfig {
@@ -53,6 +89,12 @@ impl Default for ServerConfig {
             llm: LlmConfig::default(),
             search: SearchConfig::default(),
             tuning: TuningConfig::default(),
+            embeddings: EmbeddingConfig::default(),
+            ocr: crate::agent::ocr::OcrConfig::default(),
+            transcription: crate::agent::transcribe::TranscriptionConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            metrics: MetricsConfig::default(),
+            schedule: crate::scheduler::ProjectSchedule::default(),
         }
     }
 }
@@ -98,12 +140,35 @@ impl ServerConfig {
         if let Ok(port) = env::var("CUEMAP_PORT") {
             if let Ok(p) = port.parse() { config.server.port = p; }
         }
+        if let Ok(format) = env::var("CUEMAP_LOG_FORMAT") {
+            if let Ok(f) = <LogFormat as clap::ValueEnum>::from_str(&format, true) { config.server.log_format = f; }
+        }
         if let Ok(key) = env::var("CUEMAP_SECRET_KEY") {
             config.security.secret_key = Some(key);
         }
         if let Ok(key) = env::var("CUEMAP_MASTER_KEY") {
             config.security.master_key = Some(key);
         }
+        if let Ok(key) = env::var("CUEMAP_SNAPSHOT_KEY") {
+            config.security.snapshot_key = Some(key);
+        }
+        if let Ok(keys) = env::var("CUEMAP_PREVIOUS_SNAPSHOT_KEYS") {
+            config.security.previous_snapshot_keys = keys
+                .split(',')
+                .map(|k| k.trim().to_string())
+                .filter(|k| !k.is_empty())
+                .collect();
+        }
+        // Standard OpenTelemetry variables
+        if let Ok(endpoint) = env::var("OTEL_EXPORTER_OTLP_ENDPOINT") {
+            config.telemetry.otlp_endpoint = Some(endpoint);
+        }
+        if let Ok(name) = env::var("OTEL_SERVICE_NAME") {
+            config.telemetry.service_name = name;
+        }
+        if let Ok(ratio) = env::var("OTEL_TRACES_SAMPLER_ARG") {
+            if let Ok(r) = ratio.parse() { config.telemetry.sample_ratio = r; }
+        }
         
         Ok(config)
     }
@@ -140,6 +205,8 @@ pub struct ServerSettings {
     pub data_dir: String,
     pub assets_dir: Option<String>,
     pub log_level: String,
+    #[serde(default)]
+    pub log_format: LogFormat,
     pub read_only: bool,
 }
 
@@ -151,6 +218,7 @@ impl Default for ServerSettings {
             data_dir: get_base_dir().join("data").to_string_lossy().to_string(),
             assets_dir: None,
             log_level: "info".to_string(),
+            log_format: LogFormat::Text,
             read_only: false,
         }
     }
@@ -162,15 +230,71 @@ pub struct SecurityConfig {
     pub api_keys: Vec<String>,
     pub master_key: Option<String>,
     pub secret_key: Option<String>,
+    /// Passphrase for snapshot encryption-at-rest (keys are derived per project)
+    #[serde(default)]
+    pub snapshot_key: Option<String>,
+    /// Retired snapshot passphrases, still accepted on load during key rotation
+    #[serde(default)]
+    pub previous_snapshot_keys: Vec<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PersistenceConfig {
     pub snapshot_interval_seconds: u64,
     pub enabled: bool,
+    /// False turns off `snapshot_codec`. True on its own compresses nothing: configs
+    /// carried this flag long before snapshots were compressed.
     pub compress_snapshots: bool,
+    /// Codec for snapshot files (and raw snapshots uploaded to cloud backup):
+    /// `none` (default), `zstd` or `lz4`, recorded in each file's header.
+    /// Releases before snapshot compression can't load compressed files.
+    #[serde(default)]
+    pub snapshot_codec: SnapshotCodec,
+    /// zstd level, 1 (fastest) to 22 (smallest)
+    #[serde(default = "default_snapshot_compression_level")]
+    pub snapshot_compression_level: i32,
     #[serde(default)]
     pub cloud: CloudConfig,
+    /// Unload projects idle this many seconds, keeping only their snapshots;
+    /// they are reloaded on next access (0 = keep every project loaded)
+    #[serde(default)]
+    pub archive_idle_seconds: u64,
+    /// Most projects held in RAM at once (0 = no limit). The least recently
+    /// used ones are unloaded to their snapshots to make room.
+    #[serde(default)]
+    pub max_resident_projects: usize,
+    /// Payloads each disk-backed project keeps cached in RAM
+    #[serde(default = "default_payload_cache_entries")]
+    pub payload_cache_entries: usize,
+    /// Earlier snapshots kept per project for point-in-time restore (0 = none)
+    #[serde(default)]
+    pub snapshot_generations: usize,
+    /// Least time between two kept generations of a project
+    #[serde(default = "default_snapshot_generation_interval")]
+    pub snapshot_generation_interval_seconds: u64,
+}
+
+fn default_snapshot_generation_interval() -> u64 {
+    3600
+}
+
+fn default_payload_cache_entries() -> usize {
+    crate::payload_store::DEFAULT_PAYLOAD_CACHE_ENTRIES
+}
+
+fn default_snapshot_compression_level() -> i32 {
+    3
+}
+
+impl PersistenceConfig {
+    /// Compression for snapshot files and cloud uploads (none unless a `snapshot_codec` is set)
+    pub fn snapshot_compression(&self) -> SnapshotCompression {
+        if self.compress_snapshots {
+            SnapshotCompression::new(self.snapshot_codec, self.snapshot_compression_level)
+        } else {
+            SnapshotCompression::default()
+        }
+    }
 }
 
 impl Default for PersistenceConfig {
@@ -179,7 +303,14 @@ impl Default for PersistenceConfig {
             snapshot_interval_seconds: 60,
             enabled: true,
             compress_snapshots: true,
+            snapshot_codec: SnapshotCodec::None,
+            snapshot_compression_level: default_snapshot_compression_level(),
             cloud: CloudConfig::default(),
+            archive_idle_seconds: 0,
+            max_resident_projects: 0,
+            payload_cache_entries: default_payload_cache_entries(),
+            snapshot_generations: 0,
+            snapshot_generation_interval_seconds: default_snapshot_generation_interval(),
         }
     }
 }
@@ -192,13 +323,34 @@ pub struct CloudConfig {
     pub endpoint: Option<String>,
     pub prefix: String,
     pub auto_backup: bool,
+    /// Upload changed projects to the bucket every N seconds (0 = disabled)
+    #[serde(default)]
+    pub replicate_interval_seconds: u64,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct JobsConfig {
     pub background_processing: bool,
     pub consolidation_enabled: bool,
+    /// Sync trending cues into each project's market heatmap every N seconds (0 = disabled)
     pub market_heatmap_interval_seconds: u64,
+    /// Minutes of lexicon activity the heatmap sync looks back over (1-60)
+    #[serde(default = "default_market_heatmap_window_minutes")]
+    pub market_heatmap_window_minutes: u32,
+    /// How often the per-project maintenance scheduler checks for due tasks (0 = disabled)
+    #[serde(default = "default_scheduler_tick_seconds")]
+    pub scheduler_tick_seconds: u64,
+    /// Worker pool size and per-kind concurrency (`[jobs.workers]`)
+    #[serde(default)]
+    pub workers: crate::jobs::WorkerPoolConfig,
+}
+
+fn default_scheduler_tick_seconds() -> u64 {
+    30
+}
+
+fn default_market_heatmap_window_minutes() -> u32 {
+    crate::trending::DEFAULT_TRENDING_WINDOW_MINS
 }
 
 impl Default for JobsConfig {
@@ -207,15 +359,138 @@ impl Default for JobsConfig {
             background_processing: true,
             consolidation_enabled: false,
             market_heatmap_interval_seconds: 60,
+            market_heatmap_window_minutes: default_market_heatmap_window_minutes(),
+            scheduler_tick_seconds: default_scheduler_tick_seconds(),
+            workers: Default::default(),
+        }
+    }
+}
+
+/// Prometheus `/metrics` output
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    /// Projects with their own labeled series; the rest share one `(other)` series (0 = none)
+    #[serde(default = "default_max_project_series")]
+    pub max_project_series: usize,
+    /// Recalls taking at least this many milliseconds go to the slow-query log
+    #[serde(default = "default_slow_query_ms")]
+    pub slow_query_ms: u64,
+    /// Slow queries kept, newest replacing oldest (0 = log off)
+    #[serde(default = "default_slow_query_log_size")]
+    pub slow_query_log_size: usize,
+    /// Append each recall's normalized cues and options to `recall_queries.jsonl`
+    /// in the data directory, for `cuemap replay` (no query text or caller identity)
+    #[serde(default)]
+    pub query_log: bool,
+    /// Share of recalls written to the query log (0-1)
+    #[serde(default = "default_query_log_sample_rate")]
+    pub query_log_sample_rate: f64,
+    /// The query log is rotated to `.1` at this size (0 = never)
+    #[serde(default = "default_query_log_max_mb")]
+    pub query_log_max_mb: u64,
+}
+
+fn default_max_project_series() -> usize {
+    crate::metrics::DEFAULT_MAX_PROJECT_SERIES
+}
+
+fn default_slow_query_ms() -> u64 {
+    crate::slow_queries::DEFAULT_SLOW_QUERY_MS
+}
+
+fn default_slow_query_log_size() -> usize {
+    crate::slow_queries::DEFAULT_SLOW_QUERY_LOG_SIZE
+}
+
+fn default_query_log_sample_rate() -> f64 {
+    1.0
+}
+
+fn default_query_log_max_mb() -> u64 {
+    crate::query_log::DEFAULT_QUERY_LOG_MAX_MB
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            max_project_series: default_max_project_series(),
+            slow_query_ms: default_slow_query_ms(),
+            slow_query_log_size: default_slow_query_log_size(),
+            query_log: false,
+            query_log_sample_rate: default_query_log_sample_rate(),
+            query_log_max_mb: default_query_log_max_mb(),
+        }
+    }
+}
+
+/// OpenTelemetry export of traces and metrics over OTLP (`otel` feature)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    /// OTLP gRPC collector, e.g. `http://localhost:4317` (unset = no export)
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+    /// Fraction of new traces sampled; requests carrying a `traceparent` follow the caller
+    #[serde(default = "default_sample_ratio")]
+    pub sample_ratio: f64,
+    /// Export the `/metrics` values every N seconds (0 = traces only)
+    #[serde(default = "default_metrics_interval_seconds")]
+    pub metrics_interval_seconds: u64,
+}
+
+fn default_service_name() -> String {
+    "cuemap".to_string()
+}
+
+fn default_sample_ratio() -> f64 {
+    1.0
+}
+
+fn default_metrics_interval_seconds() -> u64 {
+    60
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: None,
+            service_name: default_service_name(),
+            sample_ratio: default_sample_ratio(),
+            metrics_interval_seconds: default_metrics_interval_seconds(),
         }
     }
 }
 
+impl TelemetryConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.sample_ratio) {
+            return Err("telemetry.sample_ratio must be between 0 and 1".to_string());
+        }
+        if self.service_name.trim().is_empty() {
+            return Err("telemetry.service_name must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AgentConfig {
     pub enabled: bool,
     pub watch_dir: Option<String>, // Deprecated in favor of project meta, but kept for global agent
     pub throttle_ms: u64,
+    /// Directories to learn from, each into its own project (`[[agent.roots]]`)
+    #[serde(default)]
+    pub roots: Vec<AgentRoot>,
+    /// Default include/exclude globs and size limit for every agent
+    #[serde(flatten)]
+    pub filter: crate::agent::filter::IngestFilterConfig,
+    /// Commit metadata and history ingestion (`[agent.git]`)
+    #[serde(default)]
+    pub git: crate::agent::git::GitIngestConfig,
+    /// Also store the facts the LLM finds in each chunk as memories
+    #[serde(default)]
+    pub extract_facts: bool,
 }
 
 impl Default for AgentConfig {
@@ -224,27 +499,120 @@ impl Default for AgentConfig {
             enabled: false,
             watch_dir: None,
             throttle_ms: 100,
+            roots: Vec::new(),
+            filter: Default::default(),
+            git: Default::default(),
+            extract_facts: false,
         }
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// One directory watched by the agent and the project it ingests into
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AgentRoot {
+    pub path: String,
+    #[serde(default = "default_agent_project")]
+    pub project: String,
+    #[serde(flatten)]
+    pub filter: crate::agent::filter::IngestFilterConfig,
+}
+
+fn default_agent_project() -> String {
+    "default".to_string()
+}
+
+impl AgentRoot {
+    /// Parse `PATH` or `PATH=PROJECT` (the `--agent-root` flag)
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (path, project) = match spec.rsplit_once('=') {
+            Some((path, project)) => (path.trim(), project.trim()),
+            None => (spec.trim(), "default"),
+        };
+        if path.is_empty() || project.is_empty() {
+            return Err(format!("Invalid agent root '{}': expected PATH or PATH=PROJECT", spec));
+        }
+        Ok(Self {
+            path: path.to_string(),
+            project: project.to_string(),
+            filter: Default::default(),
+        })
+    }
+}
+
+impl AgentConfig {
+    /// Configured roots, plus the legacy `watch_dir` as a root of the `default` project
+    pub fn all_roots(&self) -> Vec<AgentRoot> {
+        let mut roots = self.roots.clone();
+        if let Some(dir) = &self.watch_dir {
+            if !roots.iter().any(|r| &r.path == dir) {
+                roots.push(AgentRoot {
+                    path: dir.clone(),
+                    project: default_agent_project(),
+                    filter: Default::default(),
+                });
+            }
+        }
+        roots
+    }
+}
+
+/// LLM used for cue proposal (server default, overridable per project)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct LlmConfig {
+    #[serde(default)]
     pub enabled: bool,
+    /// `ollama`, `openai`, `vllm` or `anthropic`
+    #[serde(default = "default_llm_provider")]
     pub provider: String,
+    #[serde(default = "default_llm_model")]
     pub model: String,
+    /// Base URL (empty = provider default)
+    #[serde(default)]
     pub url: String,
+    #[serde(default)]
     pub api_key: Option<String>,
+    /// Requests started per minute (None = unlimited)
+    #[serde(default)]
+    pub requests_per_minute: Option<u32>,
+    /// Requests in flight at once (None = unlimited)
+    #[serde(default)]
+    pub max_concurrent: Option<usize>,
+    /// Longest summary of consolidated memories, in tokens
+    #[serde(default = "default_summary_max_tokens")]
+    pub summary_max_tokens: usize,
+    /// Text of consolidated memories sent for summarization, in tokens; the rest is cut
+    #[serde(default = "default_summary_input_tokens")]
+    pub summary_input_tokens: usize,
+}
+
+fn default_llm_provider() -> String {
+    "ollama".to_string()
+}
+
+fn default_llm_model() -> String {
+    "mistral".to_string()
+}
+
+fn default_summary_max_tokens() -> usize {
+    256
+}
+
+fn default_summary_input_tokens() -> usize {
+    3000
 }
 
 impl Default for LlmConfig {
     fn default() -> Self {
         Self {
             enabled: false,
-            provider: "ollama".to_string(),
-            model: "mistral".to_string(),
-            url: "http://localhost:11434".to_string(),
+            provider: default_llm_provider(),
+            model: default_llm_model(),
+            url: String::new(),
             api_key: None,
+            requests_per_minute: None,
+            max_concurrent: None,
+            summary_max_tokens: default_summary_max_tokens(),
+            summary_input_tokens: default_summary_input_tokens(),
         }
     }
 }
@@ -256,8 +624,83 @@ impl LlmConfig {
             provider: self.provider.clone(),
             model: self.model.clone(),
             api_key: self.api_key.clone(),
-            ollama_url: self.url.clone(),
+            ollama_url: if self.url.is_empty() { crate::llm::DEFAULT_OLLAMA_URL.to_string() } else { self.url.clone() },
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.model.trim().is_empty() {
+            return Err("model must not be empty".to_string());
+        }
+        crate::llm::build_provider(self).map(|_| ())
+    }
+}
+
+/// Embedding provider for hybrid recall (server default, overridable per project)
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EmbeddingConfig {
+    #[serde(default)]
+    pub strategy: EmbeddingStrategy,
+    /// Model name (empty = provider default)
+    #[serde(default)]
+    pub model: String,
+    /// Base URL for Ollama / OpenAI-compatible providers (empty = provider default)
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub api_key: Option<String>,
+}
+
+/// Scorer used by the rerank stage of `/recall`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RerankStrategy {
+    #[default]
+    None,  // Recall order is final
+    Onnx,  // Local cross-encoder (ms-marco-MiniLM-L-6-v2) via ONNX Runtime (`onnx` feature)
+    Llm,   // The project's LLM rates each candidate
+}
+
+/// Rerank stage run over the top recall candidates (per project)
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RerankConfig {
+    /// Rerank recalls that don't set `rerank`
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub strategy: RerankStrategy,
+    /// Cross-encoder model directory for `onnx` (empty = downloaded default)
+    #[serde(default)]
+    pub model: String,
+    /// Candidates rescored per recall
+    #[serde(default = "default_rerank_top_n")]
+    pub top_n: usize,
+}
+
+fn default_rerank_top_n() -> usize {
+    20
+}
+
+impl Default for RerankConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            strategy: RerankStrategy::None,
+            model: String::new(),
+            top_n: default_rerank_top_n(),
+        }
+    }
+}
+
+impl RerankConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.top_n == 0 {
+            return Err("top_n must be at least 1".to_string());
         }
+        if self.enabled && self.strategy == RerankStrategy::None {
+            return Err("enabled needs a strategy (onnx or llm)".to_string());
+        }
+        Ok(())
     }
 }
 
@@ -291,11 +734,102 @@ pub struct TuningConfig {
     pub idf_min_count: usize,
     pub adaptive_scan_factor: usize,
     pub adaptive_scan_max: usize,
+    /// Share of the rarest query cue's list scanned when it exceeds the base budget
+    #[serde(default = "default_adaptive_scan_coverage")]
+    pub adaptive_scan_coverage: f64,
+    /// Hard cap on entries scanned per cue once the budget has been widened
+    #[serde(default = "default_adaptive_scan_ceiling")]
+    pub adaptive_scan_ceiling: usize,
 
     // Expansion
     pub expansion_threshold: f64,
     pub expansion_limit: usize,
     pub max_proposed_cues: usize,
+
+    // Field weighting
+    #[serde(default)]
+    pub field_boosts: FieldBoosts,
+
+    // Fuzzy matching
+    /// Upper bound on edits for `fuzzy` recall (short cues allow fewer)
+    #[serde(default = "default_fuzzy_max_edit_distance")]
+    pub fuzzy_max_edit_distance: usize,
+
+    // Pinning
+    /// Minimum recall score for pinned memories that match the query (off when unset)
+    #[serde(default)]
+    pub pinned_score_floor: Option<f64>,
+
+    // Deduplication on ingest
+    /// Cue-set Jaccard similarity at which a new memory counts as a near duplicate
+    #[serde(default = "default_dedup_jaccard_threshold")]
+    pub dedup_jaccard_threshold: f64,
+    /// Word-set Jaccard similarity a near duplicate's content must also reach
+    #[serde(default = "default_dedup_content_threshold")]
+    pub dedup_content_threshold: f64,
+    /// Most recent memories per cue compared against a new memory
+    #[serde(default = "default_dedup_window")]
+    pub dedup_window: usize,
+    /// Merge writes whose content is byte-identical to an existing memory into
+    /// it instead of storing a copy (API writes and agent-ingested URLs, uploads
+    /// and posted content)
+    #[serde(default = "default_dedup_identical")]
+    pub dedup_identical: bool,
+
+    // Versioning
+    /// Replaced versions kept per memory when it is upserted in place (0 disables history)
+    #[serde(default)]
+    pub max_memory_versions: usize,
+
+    // Co-occurrence
+    /// Neighbours kept per cue in the co-occurrence matrix (0 = unbounded).
+    /// A full list halves its counts and drops the weakest to admit a new neighbour.
+    #[serde(default = "default_co_occurrence_max_neighbors")]
+    pub co_occurrence_max_neighbors: usize,
+
+    // Hot cues
+    /// Ids per posting-list segment; longer lists are split so writes to a hot
+    /// cue only shift within its newest segment (0 keeps every list whole)
+    #[serde(default)]
+    pub hot_cue_segment_size: usize,
+
+    // Tokenization
+    /// What deriving cues from text does with emoji: `shortcode` (`🚀` ->
+    /// `emoji:rocket`), `keep` (the emoji is the cue) or `drop`
+    #[serde(default)]
+    pub emoji_mode: EmojiMode,
+}
+
+fn default_adaptive_scan_coverage() -> f64 {
+    0.1
+}
+
+fn default_adaptive_scan_ceiling() -> usize {
+    20000
+}
+
+fn default_co_occurrence_max_neighbors() -> usize {
+    1000
+}
+
+fn default_fuzzy_max_edit_distance() -> usize {
+    2
+}
+
+fn default_dedup_jaccard_threshold() -> f64 {
+    0.9
+}
+
+fn default_dedup_content_threshold() -> f64 {
+    0.5
+}
+
+fn default_dedup_window() -> usize {
+    50
+}
+
+fn default_dedup_identical() -> bool {
+    true
 }
 
 impl Default for TuningConfig {
@@ -311,10 +845,70 @@ impl Default for TuningConfig {
             idf_min_count: 20,
             adaptive_scan_factor: 100,
             adaptive_scan_max: 2000,
+            adaptive_scan_coverage: default_adaptive_scan_coverage(),
+            adaptive_scan_ceiling: default_adaptive_scan_ceiling(),
             
             expansion_threshold: 0.65,
             expansion_limit: 3,
             max_proposed_cues: 10,
+
+            field_boosts: FieldBoosts::default(),
+
+            fuzzy_max_edit_distance: default_fuzzy_max_edit_distance(),
+
+            pinned_score_floor: None,
+
+            dedup_jaccard_threshold: default_dedup_jaccard_threshold(),
+            dedup_content_threshold: default_dedup_content_threshold(),
+            dedup_window: default_dedup_window(),
+            dedup_identical: default_dedup_identical(),
+
+            max_memory_versions: 0,
+
+            co_occurrence_max_neighbors: default_co_occurrence_max_neighbors(),
+
+            hot_cue_segment_size: 0,
+
+            emoji_mode: EmojiMode::default(),
         }
     }
 }
+
+/// Multipliers on a cue's weight by the field it matched in (BM25F-style).
+/// A query cue matches a field when it is one of the tokens of a structural
+/// cue on the memory (e.g. `payments` in `title:Payments Service`).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FieldBoosts {
+    pub title: f64,
+    pub header: f64,
+    pub path: f64,
+    pub body: f64,
+}
+
+impl Default for FieldBoosts {
+    fn default() -> Self {
+        Self {
+            title: 2.0,
+            header: 1.5,
+            path: 1.2,
+            body: 1.0,
+        }
+    }
+}
+
+impl FieldBoosts {
+    pub fn get(&self, field: CueField) -> f64 {
+        match field {
+            CueField::Title => self.title,
+            CueField::Header => self.header,
+            CueField::Path => self.path,
+            CueField::Body => self.body,
+        }
+    }
+
+    /// All fields weigh the same (field lookup can be skipped)
+    pub fn is_uniform(&self) -> bool {
+        self.title == self.body && self.header == self.body && self.path == self.body
+    }
+}
@@ -0,0 +1,477 @@
+//! Email from mbox exports and IMAP mailboxes.
+//!
+//! Each message becomes one document carrying `from:`, `to:` (To and Cc),
+//! `subject:` (without `Re:`/`Fwd:`) and `thread:` cues. The thread is the
+//! first message of its `References` chain, so a reply and the message it
+//! answers share it. Quoted text of earlier messages is dropped from replies,
+//! and attachments are skipped.
+//!
+//! - `mbox`: a file on the server; the cursor is the number of messages synced,
+//!   so messages appended to the file are picked up by the next sync
+//! - `imap`: a mailbox read with the `curl` CLI; the cursor is the last UID
+//!   synced. A mailbox whose UIDs were reset needs a full sync.
+
+use super::{cue_value, Connector, Document, DocumentRef};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// A parsed message
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Email {
+    /// Without the angle brackets
+    pub message_id: Option<String>,
+    pub subject: String,
+    pub from: Vec<String>,
+    /// To and Cc addresses
+    pub to: Vec<String>,
+    pub date: Option<String>,
+    pub in_reply_to: Option<String>,
+    pub references: Vec<String>,
+    /// Plain text body without quoted replies
+    pub body: String,
+}
+
+impl Email {
+    /// Message id of the conversation's first message
+    pub fn thread_id(&self) -> Option<&str> {
+        self.references
+            .first()
+            .or(self.in_reply_to.as_ref())
+            .or(self.message_id.as_ref())
+            .map(String::as_str)
+    }
+}
+
+/// Parse a raw RFC 5322 message
+pub fn parse_email(raw: &str) -> Email {
+    let (headers, body) = split_message(raw);
+    let header = |name: &str| header_value(&headers, name).map(|v| decode_words(&v));
+    let ids = |name: &str| header_value(&headers, name).map(|v| message_ids(&v)).unwrap_or_default();
+
+    let mut to = header("to").map(|v| addresses(&v)).unwrap_or_default();
+    to.extend(header("cc").map(|v| addresses(&v)).unwrap_or_default());
+
+    Email {
+        message_id: ids("message-id").into_iter().next(),
+        subject: header("subject").unwrap_or_default().trim().to_string(),
+        from: header("from").map(|v| addresses(&v)).unwrap_or_default(),
+        to,
+        date: header("date").map(|d| d.trim().to_string()),
+        in_reply_to: ids("in-reply-to").into_iter().next(),
+        references: ids("references"),
+        body: strip_quoted(&part_text(&headers, &body).unwrap_or_default()),
+    }
+}
+
+/// The document for a message; `fallback_id` names messages without a Message-ID
+pub fn email_document(email: &Email, fallback_id: &str) -> Document {
+    let id = email.message_id.clone().unwrap_or_else(|| fallback_id.to_string());
+    let mut content = String::new();
+    if !email.from.is_empty() {
+        content.push_str(&format!("From: {}\n", email.from.join(", ")));
+    }
+    if !email.to.is_empty() {
+        content.push_str(&format!("To: {}\n", email.to.join(", ")));
+    }
+    if let Some(date) = &email.date {
+        content.push_str(&format!("Date: {}\n", date));
+    }
+    content.push('\n');
+    content.push_str(&email.body);
+
+    let mut cues = vec!["document:email".to_string()];
+    cues.extend(email.from.iter().map(|a| format!("from:{}", a)));
+    cues.extend(email.to.iter().map(|a| format!("to:{}", a)));
+    let subject = thread_subject(&email.subject);
+    if !subject.is_empty() {
+        cues.push(format!("subject:{}", cue_value(&subject)));
+    }
+    cues.push(format!("thread:{}", email.thread_id().unwrap_or(&id).to_lowercase()));
+
+    Document {
+        title: if email.subject.is_empty() { "(no subject)".to_string() } else { email.subject.clone() },
+        url: format!("mid:{}", id),
+        id,
+        content,
+        cues,
+    }
+}
+
+/// A subject without `Re:`, `Fwd:` and similar prefixes
+pub fn thread_subject(subject: &str) -> String {
+    let mut subject = subject.trim();
+    loop {
+        let lower = subject.to_lowercase();
+        let Some(prefix) = ["re:", "fwd:", "fw:", "aw:", "wg:", "sv:"].iter().find(|p| lower.starts_with(*p)) else {
+            return subject.to_string();
+        };
+        subject = subject[prefix.len()..].trim_start();
+    }
+}
+
+/// Messages of an mbox file, with `>From ` escaping undone
+pub fn split_mbox(content: &str) -> Vec<String> {
+    let mut messages = Vec::new();
+    let mut current: Option<Vec<&str>> = None;
+    let mut previous_blank = true;
+    for line in content.lines() {
+        if line.starts_with("From ") && previous_blank {
+            if let Some(lines) = current.take() {
+                messages.push(lines.join("\n"));
+            }
+            current = Some(Vec::new());
+        } else if let Some(lines) = &mut current {
+            let escaped = line.trim_start_matches('>').starts_with("From ") && line.starts_with('>');
+            lines.push(if escaped { &line[1..] } else { line });
+        }
+        previous_blank = line.trim().is_empty();
+    }
+    if let Some(lines) = current {
+        messages.push(lines.join("\n"));
+    }
+    messages
+}
+
+// =============================================================================
+// MIME
+// =============================================================================
+
+/// Unfolded headers (lowercased name, raw value) and the body
+fn split_message(raw: &str) -> (Vec<(String, String)>, String) {
+    let raw = raw.replace("\r\n", "\n");
+    let (head, body) = raw.split_once("\n\n").unwrap_or((raw.as_str(), ""));
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in head.lines() {
+        if line.starts_with(' ') || line.starts_with('\t') {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+        }
+    }
+    (headers, body.to_string())
+}
+
+fn header_value(headers: &[(String, String)], name: &str) -> Option<String> {
+    headers.iter().find(|(n, _)| n == name).map(|(_, v)| v.clone())
+}
+
+/// A `key=value` parameter of a header like Content-Type
+fn header_param(value: &str, key: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|param| {
+        let (k, v) = param.split_once('=')?;
+        (k.trim().eq_ignore_ascii_case(key)).then(|| v.trim().trim_matches('"').to_string())
+    })
+}
+
+/// The readable text of a (possibly multipart) body: text/plain preferred,
+/// text/html converted, attachments skipped
+fn part_text(headers: &[(String, String)], body: &str) -> Option<String> {
+    let content_type = header_value(headers, "content-type").unwrap_or_else(|| "text/plain".to_string());
+    let mime = content_type.split(';').next().unwrap_or("").trim().to_lowercase();
+    let attachment = header_value(headers, "content-disposition")
+        .is_some_and(|d| d.trim().to_lowercase().starts_with("attachment"));
+    if attachment {
+        return None;
+    }
+
+    if mime.starts_with("multipart/") {
+        let boundary = header_param(&content_type, "boundary")?;
+        let delimiter = format!("--{}", boundary);
+        let parts: Vec<(Vec<(String, String)>, String)> = body
+            .split(&delimiter)
+            .skip(1)
+            .take_while(|part| !part.starts_with("--"))
+            .map(|part| split_message(part.trim_start_matches('\n')))
+            .collect();
+        let texts: Vec<(bool, String)> = parts
+            .iter()
+            .filter_map(|(headers, body)| {
+                let html = header_value(headers, "content-type").is_some_and(|t| t.to_lowercase().starts_with("text/html"));
+                part_text(headers, body).map(|text| (html, text))
+            })
+            .collect();
+        // Alternatives carry the same text; prefer the plain one
+        if mime == "multipart/alternative" {
+            return texts.iter().find(|(html, _)| !html).or(texts.first()).map(|(_, t)| t.clone());
+        }
+        let joined: Vec<String> = texts.into_iter().map(|(_, t)| t).filter(|t| !t.trim().is_empty()).collect();
+        return (!joined.is_empty()).then(|| joined.join("\n\n"));
+    }
+
+    if !mime.starts_with("text/") {
+        return None;
+    }
+    let encoding = header_value(headers, "content-transfer-encoding").unwrap_or_default().to_lowercase();
+    let bytes = match encoding.trim() {
+        "base64" => STANDARD
+            .decode(body.chars().filter(|c| !c.is_whitespace()).collect::<String>())
+            .unwrap_or_else(|_| body.as_bytes().to_vec()),
+        "quoted-printable" => decode_quoted_printable(body, false),
+        _ => body.as_bytes().to_vec(),
+    };
+    let text = decode_charset(&bytes, header_param(&content_type, "charset").as_deref());
+    Some(if mime == "text/html" { html_text(&text) } else { text })
+}
+
+fn decode_charset(bytes: &[u8], charset: Option<&str>) -> String {
+    let latin = charset.is_some_and(|c| {
+        let c = c.to_lowercase();
+        c.starts_with("iso-8859") || c.starts_with("windows-125") || c == "latin1"
+    });
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        // Close enough for the Western code pages
+        Err(_) if latin => bytes.iter().map(|&b| b as char).collect(),
+        Err(_) => String::from_utf8_lossy(bytes).to_string(),
+    }
+}
+
+fn decode_quoted_printable(text: &str, underscore_space: bool) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'=' if bytes.get(i + 1) == Some(&b'\n') => i += 2,
+            b'=' if bytes.get(i + 1) == Some(&b'\r') && bytes.get(i + 2) == Some(&b'\n') => i += 3,
+            b'=' => match std::str::from_utf8(bytes.get(i + 1..i + 3).unwrap_or(&[])).ok().and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                Some(byte) => {
+                    out.push(byte);
+                    i += 3;
+                }
+                None => {
+                    out.push(b'=');
+                    i += 1;
+                }
+            },
+            b'_' if underscore_space => {
+                out.push(b' ');
+                i += 1;
+            }
+            byte => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Decode RFC 2047 encoded words (`=?UTF-8?B?...?=`) in a header
+pub fn decode_words(value: &str) -> String {
+    let word = regex::Regex::new(r"=\?([^?]+)\?([bBqQ])\?([^?]*)\?=").unwrap();
+    // Whitespace between two encoded words is not part of the text
+    let joined = regex::Regex::new(r"\?=\s+=\?").unwrap().replace_all(value, "?==?");
+    word.replace_all(&joined, |caps: &regex::Captures| {
+        let bytes = if caps[2].eq_ignore_ascii_case("b") {
+            STANDARD.decode(&caps[3]).unwrap_or_default()
+        } else {
+            decode_quoted_printable(&caps[3], true)
+        };
+        decode_charset(&bytes, Some(&caps[1]))
+    })
+    .to_string()
+}
+
+fn html_text(html: &str) -> String {
+    let fragment = scraper::Html::parse_fragment(html);
+    let text: Vec<&str> = fragment.root_element().text().map(str::trim).filter(|t| !t.is_empty()).collect();
+    text.join("\n")
+}
+
+/// Lowercased email addresses in an address list
+fn addresses(value: &str) -> Vec<String> {
+    let address = regex::Regex::new(r"[A-Za-z0-9._%+\-]+@[A-Za-z0-9.\-]+[A-Za-z0-9]").unwrap();
+    let mut found: Vec<String> = Vec::new();
+    for m in address.find_iter(value) {
+        let a = m.as_str().to_lowercase();
+        if !found.contains(&a) {
+            found.push(a);
+        }
+    }
+    found
+}
+
+/// `<id>` tokens of Message-ID, In-Reply-To or References
+fn message_ids(value: &str) -> Vec<String> {
+    let ids: Vec<String> = value
+        .split('<')
+        .skip(1)
+        .filter_map(|s| s.split_once('>').map(|(id, _)| id.trim().to_string()))
+        .filter(|id| !id.is_empty())
+        .collect();
+    if ids.is_empty() && !value.trim().is_empty() {
+        return vec![value.trim().to_string()];
+    }
+    ids
+}
+
+/// A reply without the quoted message below it
+fn strip_quoted(body: &str) -> String {
+    let lines: Vec<&str> = body.lines().collect();
+    let mut kept: Vec<&str> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("-----Original Message-----") {
+            break;
+        }
+        if trimmed.starts_with('>') {
+            continue;
+        }
+        // "On Tue, Bob wrote:" introducing a quote
+        let next = lines[i + 1..].iter().find(|l| !l.trim().is_empty());
+        if trimmed.ends_with("wrote:") && next.is_some_and(|l| l.trim().starts_with('>')) {
+            continue;
+        }
+        kept.push(line);
+    }
+    kept.join("\n").trim().to_string()
+}
+
+// =============================================================================
+// mbox
+// =============================================================================
+
+pub struct MboxConnector {
+    path: PathBuf,
+    /// Messages read by the last `list`
+    messages: Mutex<Vec<String>>,
+}
+
+impl MboxConnector {
+    pub fn new(path: &str) -> Self {
+        Self { path: PathBuf::from(path), messages: Mutex::new(Vec::new()) }
+    }
+
+    async fn read(&self) -> Result<Vec<String>, String> {
+        let bytes = tokio::fs::read(&self.path)
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", self.path.display(), e))?;
+        Ok(split_mbox(&String::from_utf8_lossy(&bytes)))
+    }
+}
+
+#[async_trait]
+impl Connector for MboxConnector {
+    fn name(&self) -> String {
+        format!("mbox:{}", self.path.display())
+    }
+
+    async fn list(&self, cursor: Option<&str>, limit: usize) -> Result<Vec<DocumentRef>, String> {
+        let messages = self.read().await?;
+        let mut synced: usize = cursor.and_then(|c| c.parse().ok()).unwrap_or(0);
+        // Fewer messages than already synced: the file was replaced, start over
+        if synced > messages.len() {
+            synced = 0;
+        }
+        let documents = (synced..messages.len())
+            .take(limit)
+            .map(|index| DocumentRef { id: index.to_string(), cursor: (index + 1).to_string() })
+            .collect();
+        *self.messages.lock().unwrap() = messages;
+        Ok(documents)
+    }
+
+    async fn fetch(&self, document: &DocumentRef) -> Result<Document, String> {
+        let index: usize = document.id.parse().map_err(|_| format!("Bad message index {}", document.id))?;
+        let loaded = self.messages.lock().unwrap().len();
+        if loaded <= index {
+            let messages = self.read().await?;
+            *self.messages.lock().unwrap() = messages;
+        }
+        let raw = self.messages.lock().unwrap().get(index).cloned().ok_or_else(|| format!("No message {}", index))?;
+        Ok(email_document(&parse_email(&raw), &format!("mbox-{}", index)))
+    }
+}
+
+// =============================================================================
+// IMAP
+// =============================================================================
+
+pub struct ImapConnector {
+    /// `imaps://host[:port]/MAILBOX`
+    mailbox_url: String,
+    username: String,
+    password: String,
+}
+
+impl ImapConnector {
+    pub fn new(mailbox_url: &str, username: &str, password: String) -> Result<Self, String> {
+        let url = url::Url::parse(mailbox_url).map_err(|e| format!("Invalid IMAP URL '{}': {}", mailbox_url, e))?;
+        if !matches!(url.scheme(), "imap" | "imaps") || url.path().trim_matches('/').is_empty() {
+            return Err(format!("IMAP URL must look like imaps://host/INBOX, got '{}'", mailbox_url));
+        }
+        Ok(Self {
+            mailbox_url: mailbox_url.trim_end_matches('/').to_string(),
+            username: username.to_string(),
+            password,
+        })
+    }
+
+    /// Run curl against the mailbox; credentials are passed on stdin, not argv
+    async fn curl(&self, url: &str, request: Option<&str>) -> Result<Vec<u8>, String> {
+        use tokio::io::AsyncWriteExt;
+
+        let mut command = tokio::process::Command::new("curl");
+        command.args(["--silent", "--show-error", "--config", "-", "--url", url]);
+        if let Some(request) = request {
+            command.arg("--request").arg(request);
+        }
+        let mut child = command
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run curl: {}", e))?;
+
+        let quote = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let config = format!("user = \"{}:{}\"\n", quote(&self.username), quote(&self.password));
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(config.as_bytes()).await.map_err(|e| format!("Failed to run curl: {}", e))?;
+        }
+        let out = child.wait_with_output().await.map_err(|e| format!("Failed to run curl: {}", e))?;
+        if !out.status.success() {
+            return Err(format!("IMAP request failed: {}", String::from_utf8_lossy(&out.stderr).trim()));
+        }
+        Ok(out.stdout)
+    }
+}
+
+#[async_trait]
+impl Connector for ImapConnector {
+    fn name(&self) -> String {
+        format!("imap:{}", self.mailbox_url)
+    }
+
+    async fn list(&self, cursor: Option<&str>, limit: usize) -> Result<Vec<DocumentRef>, String> {
+        let last: u64 = cursor.and_then(|c| c.parse().ok()).unwrap_or(0);
+        let out = self.curl(&self.mailbox_url, Some(&format!("UID SEARCH UID {}:*", last + 1))).await?;
+        // `n:*` always matches the newest message, even when it is older than n
+        let mut uids: Vec<u64> = parse_search(&String::from_utf8_lossy(&out)).into_iter().filter(|uid| *uid > last).collect();
+        uids.sort_unstable();
+        Ok(uids
+            .into_iter()
+            .take(limit)
+            .map(|uid| DocumentRef { id: uid.to_string(), cursor: uid.to_string() })
+            .collect())
+    }
+
+    async fn fetch(&self, document: &DocumentRef) -> Result<Document, String> {
+        let raw = self.curl(&format!("{};UID={}", self.mailbox_url, document.id), None).await?;
+        Ok(email_document(&parse_email(&String::from_utf8_lossy(&raw)), &format!("uid-{}", document.id)))
+    }
+}
+
+/// UIDs in the `* SEARCH` response of an IMAP server
+pub fn parse_search(output: &str) -> Vec<u64> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("* SEARCH"))
+        .flat_map(|rest| rest.split_whitespace().filter_map(|uid| uid.parse().ok()).collect::<Vec<_>>())
+        .collect()
+}
@@ -0,0 +1,160 @@
+//! GitHub issues and pull requests, via the REST API.
+//!
+//! Listing pages through `/repos/{repo}/issues` sorted by update time, so the
+//! cursor is the `updated_at` of the last synced issue. A fetched issue is its
+//! description followed by its comments, one section per comment.
+
+use super::{cue_value, http_client, Connector, Document, DocumentRef};
+use async_trait::async_trait;
+use serde_json::Value;
+
+const DEFAULT_API_URL: &str = "https://api.github.com";
+
+/// Comments fetched per issue
+const MAX_COMMENTS: usize = 100;
+
+pub struct GithubConnector {
+    client: reqwest::Client,
+    api_url: String,
+    repo: String,
+    token: Option<String>,
+}
+
+impl GithubConnector {
+    pub fn new(api_url: &str, repo: &str, token: Option<String>) -> Result<Self, String> {
+        let api_url = if api_url.is_empty() { DEFAULT_API_URL } else { api_url };
+        Ok(Self {
+            client: http_client()?,
+            api_url: api_url.trim_end_matches('/').to_string(),
+            repo: repo.trim_matches('/').to_string(),
+            token,
+        })
+    }
+
+    /// A JSON response and the `rel="next"` page link, if any
+    async fn get(&self, url: &str) -> Result<(Value, Option<String>), String> {
+        let mut request = self
+            .client
+            .get(url)
+            .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", "2022-11-28");
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await.map_err(|e| format!("GitHub request failed: {}", e))?;
+        let status = response.status();
+        let next = response
+            .headers()
+            .get(reqwest::header::LINK)
+            .and_then(|link| link.to_str().ok())
+            .and_then(next_link);
+        let body = response.text().await.map_err(|e| e.to_string())?;
+        if !status.is_success() {
+            return Err(format!("GitHub API returned {}: {}", status, body));
+        }
+        let value = serde_json::from_str(&body).map_err(|e| format!("Malformed GitHub response: {}", e))?;
+        Ok((value, next))
+    }
+}
+
+#[async_trait]
+impl Connector for GithubConnector {
+    fn name(&self) -> String {
+        format!("github:{}", self.repo)
+    }
+
+    async fn list(&self, cursor: Option<&str>, limit: usize) -> Result<Vec<DocumentRef>, String> {
+        let mut url = format!(
+            "{}/repos/{}/issues?state=all&sort=updated&direction=asc&per_page=100",
+            self.api_url, self.repo
+        );
+        if let Some(since) = cursor {
+            url.push_str(&format!("&since={}", since));
+        }
+
+        let mut documents = Vec::new();
+        let mut next = Some(url);
+        while let Some(url) = next.take() {
+            let (page, next_page) = self.get(&url).await?;
+            for issue in page.as_array().ok_or("Expected a list of issues")? {
+                let (Some(number), Some(updated)) = (issue["number"].as_u64(), issue["updated_at"].as_str()) else {
+                    continue;
+                };
+                // `since` is inclusive; the issue at the cursor was already synced
+                if cursor.is_some_and(|since| updated <= since) {
+                    continue;
+                }
+                documents.push(DocumentRef { id: number.to_string(), cursor: updated.to_string() });
+                if documents.len() >= limit {
+                    return Ok(documents);
+                }
+            }
+            next = next_page;
+        }
+        Ok(documents)
+    }
+
+    async fn fetch(&self, document: &DocumentRef) -> Result<Document, String> {
+        let url = format!("{}/repos/{}/issues/{}", self.api_url, self.repo, document.id);
+        let (issue, _) = self.get(&url).await?;
+        let comments = if issue["comments"].as_u64().unwrap_or(0) > 0 {
+            let (comments, _) = self.get(&format!("{}/comments?per_page={}", url, MAX_COMMENTS)).await?;
+            comments.as_array().cloned().unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        parse_issue(&issue, &comments).ok_or_else(|| format!("Malformed issue {}", document.id))
+    }
+}
+
+/// A document from an issue (or pull request) and its comments, as returned
+/// by the REST API
+pub fn parse_issue(issue: &Value, comments: &[Value]) -> Option<Document> {
+    let number = issue["number"].as_u64()?;
+    let title = issue["title"].as_str().unwrap_or("").trim();
+    let author = issue["user"]["login"].as_str();
+    let kind = if issue.get("pull_request").is_some_and(|pr| !pr.is_null()) { "pull_request" } else { "issue" };
+
+    let mut content = issue["body"].as_str().unwrap_or("").trim().to_string();
+    for comment in comments {
+        let body = comment["body"].as_str().unwrap_or("").trim();
+        if body.is_empty() {
+            continue;
+        }
+        let who = comment["user"]["login"].as_str().unwrap_or("someone");
+        let when = comment["created_at"].as_str().unwrap_or("").split('T').next().unwrap_or("");
+        content.push_str(&format!("\n\n## {} commented on {}\n\n{}", who, when, body));
+    }
+
+    let mut cues = vec![format!("document:{}", kind)];
+    if let Some(state) = issue["state"].as_str() {
+        cues.push(format!("state:{}", state));
+    }
+    if let Some(author) = author {
+        cues.push(format!("author:{}", author.to_lowercase()));
+    }
+    for label in issue["labels"].as_array().into_iter().flatten() {
+        if let Some(name) = label["name"].as_str().or_else(|| label.as_str()) {
+            cues.push(format!("label:{}", cue_value(name)));
+        }
+    }
+
+    Some(Document {
+        id: number.to_string(),
+        title: format!("#{} {}", number, title),
+        url: issue["html_url"].as_str().unwrap_or("").to_string(),
+        content,
+        cues,
+    })
+}
+
+/// The `rel="next"` URL of a `Link` header
+pub fn next_link(header: &str) -> Option<String> {
+    header.split(',').find_map(|part| {
+        let (url, params) = part.split_once(';')?;
+        params
+            .split(';')
+            .any(|p| p.trim() == "rel=\"next\"")
+            .then(|| url.trim().trim_start_matches('<').trim_end_matches('>').to_string())
+    })
+}
@@ -0,0 +1,215 @@
+//! Connectors pull documents from external services into a project.
+//!
+//! A `Connector` lists the documents changed since a sync cursor and fetches
+//! each one; the ingester chunks them and feeds the chunks through the regular
+//! ingestion jobs. A project's connectors are stored in its meta file together
+//! with their cursor, so a sync only fetches what changed since the last one,
+//! and connectors with a sync interval are run by the scheduler.
+//!
+//! - `github`: issues and pull requests of a repository, with their comments
+//! - `notion`: pages shared with an integration, or the pages of one database
+//! - `mbox`, `imap`: email, one document per message
+
+pub mod email;
+pub mod github;
+pub mod notion;
+
+use crate::agent::chunker::{Chunk, Chunker};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Documents fetched per sync; the next sync continues from there
+pub const MAX_SYNC_DOCUMENTS: usize = 500;
+
+/// Cue prefixes of document metadata kept on connector chunks
+pub const DOCUMENT_CUE_PREFIXES: &[&str] = &[
+    "connector:", "document:", "label:", "author:", "state:", "from:", "to:", "subject:", "thread:",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectorKind {
+    /// Issues and pull requests of a GitHub repository
+    Github,
+    /// Notion pages
+    Notion,
+    /// Messages of an mbox file on the server
+    Mbox,
+    /// Messages of an IMAP mailbox
+    Imap,
+}
+
+/// A connector configured on a project, stored in its meta file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConnectorConfig {
+    pub id: String,
+    pub kind: ConnectorKind,
+    /// GitHub: `owner/repo`
+    #[serde(default)]
+    pub repo: String,
+    /// Notion: only sync the pages of this database
+    #[serde(default)]
+    pub database_id: Option<String>,
+    /// mbox: path of the file
+    #[serde(default)]
+    pub path: String,
+    /// API base URL, e.g. for GitHub Enterprise (empty = the service's).
+    /// IMAP: the mailbox, `imaps://host/INBOX`
+    #[serde(default)]
+    pub api_url: String,
+    /// IMAP: login name
+    #[serde(default)]
+    pub username: String,
+    /// Environment variable holding the access token or password (empty =
+    /// `GITHUB_TOKEN`, `NOTION_TOKEN` or `IMAP_PASSWORD`). Secrets themselves
+    /// are never stored.
+    #[serde(default)]
+    pub token_env: String,
+    /// Seconds between scheduled syncs (None = only on request)
+    #[serde(default)]
+    pub sync_interval_secs: Option<u64>,
+    /// Where the next sync resumes (None = from the beginning)
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// Unix seconds of the last sync
+    #[serde(default)]
+    pub last_synced: Option<u64>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+impl ConnectorConfig {
+    /// Whether `other` reads the same documents, so its cursor still applies
+    pub fn same_source(&self, other: &ConnectorConfig) -> bool {
+        self.kind == other.kind
+            && self.repo == other.repo
+            && self.database_id == other.database_id
+            && self.path == other.path
+            && self.api_url == other.api_url
+            && self.username == other.username
+    }
+
+    /// The memory source of one of this connector's documents
+    pub fn document_source(&self, document_id: &str) -> String {
+        format!("connector:{}:{}", self.id, document_id)
+    }
+}
+
+/// Connector ids: 1-64 letters, digits, `-` or `_`
+pub fn validate_connector_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= 64 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// A document changed since the cursor a listing started from
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentRef {
+    pub id: String,
+    /// Cursor that resumes the sync after this document
+    pub cursor: String,
+}
+
+/// A fetched document
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    pub id: String,
+    pub title: String,
+    pub url: String,
+    /// Markdown
+    pub content: String,
+    /// Metadata cues, e.g. `label:bug` (see `DOCUMENT_CUE_PREFIXES`)
+    pub cues: Vec<String>,
+}
+
+#[async_trait]
+pub trait Connector: Send + Sync {
+    /// Service and scope, e.g. `github:owner/repo`
+    fn name(&self) -> String;
+
+    /// Up to `limit` documents changed since `cursor` (all documents for
+    /// None), oldest change first
+    async fn list(&self, cursor: Option<&str>, limit: usize) -> Result<Vec<DocumentRef>, String>;
+
+    /// The current version of a listed document
+    async fn fetch(&self, document: &DocumentRef) -> Result<Document, String>;
+}
+
+/// The connector for a config. Fails if a required token is missing.
+pub fn build_connector(config: &ConnectorConfig) -> Result<Box<dyn Connector>, String> {
+    let token_env = |default: &str| {
+        let name = if config.token_env.is_empty() { default.to_string() } else { config.token_env.clone() };
+        std::env::var(&name).ok().filter(|t| !t.is_empty()).ok_or(name)
+    };
+    match config.kind {
+        ConnectorKind::Github => {
+            if config.repo.split('/').filter(|s| !s.is_empty()).count() != 2 {
+                return Err(format!("GitHub connector needs `repo` as owner/repo, got '{}'", config.repo));
+            }
+            // Public repositories can be read without a token, at a lower rate limit
+            let token = token_env("GITHUB_TOKEN").ok();
+            Ok(Box::new(github::GithubConnector::new(&config.api_url, &config.repo, token)?))
+        }
+        ConnectorKind::Notion => {
+            let token = token_env("NOTION_TOKEN")
+                .map_err(|name| format!("Notion connector needs an integration token in ${}", name))?;
+            Ok(Box::new(notion::NotionConnector::new(&config.api_url, token, config.database_id.clone())?))
+        }
+        ConnectorKind::Mbox => {
+            if config.path.is_empty() {
+                return Err("mbox connector needs `path`".to_string());
+            }
+            Ok(Box::new(email::MboxConnector::new(&config.path)))
+        }
+        ConnectorKind::Imap => {
+            if config.username.is_empty() {
+                return Err("IMAP connector needs `username`".to_string());
+            }
+            let password = token_env("IMAP_PASSWORD")
+                .map_err(|name| format!("IMAP connector needs the password in ${}", name))?;
+            Ok(Box::new(email::ImapConnector::new(&config.api_url, &config.username, password)?))
+        }
+    }
+}
+
+/// Chunks of a fetched document: its Markdown sections, each carrying the
+/// document's title, `connector:` and metadata cues
+pub fn document_chunks(connector_id: &str, document: &Document) -> Vec<Chunk> {
+    let content = format!("# {}\n\n{}", document.title, document.content.trim());
+    let mut chunks = Chunker::chunk_file(Path::new("document.md"), &content);
+    for chunk in &mut chunks {
+        chunk.context = format!("{} ({})", document.title, document.url);
+        chunk.structural_cues.push(format!("title:{}", document.title));
+        chunk.structural_cues.push(format!("connector:{}", connector_id));
+        for cue in &document.cues {
+            if !chunk.structural_cues.contains(cue) {
+                chunk.structural_cues.push(cue.clone());
+            }
+        }
+    }
+    chunks
+}
+
+/// Outcome of a sync
+#[derive(Debug, Clone, Default)]
+pub struct SyncReport {
+    /// Ids of the documents ingested
+    pub synced: Vec<String>,
+    pub memory_ids: Vec<String>,
+    /// (document id, error)
+    pub errors: Vec<(String, String)>,
+    /// The listing hit `MAX_SYNC_DOCUMENTS`; the next sync continues
+    pub more: bool,
+}
+
+/// Lowercased, with whitespace runs as `_`
+fn cue_value(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join("_").to_lowercase()
+}
+
+fn http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .user_agent("CueMap/0.6 (https://cuemap.dev; connector)")
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
@@ -0,0 +1,214 @@
+//! Notion pages, via the public API.
+//!
+//! Listing queries the configured database, or searches every page shared
+//! with the integration, sorted by `last_edited_time`, so the cursor is the
+//! edit time of the last synced page. Notion stamps edits to the minute, so
+//! pages edited in the cursor's minute are fetched again on the next sync.
+//! A fetched page is its blocks rendered as Markdown; nested blocks (toggles,
+//! list children, columns) are read down to `MAX_DEPTH`.
+
+use super::{http_client, Connector, Document, DocumentRef};
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use serde_json::{json, Value};
+
+const DEFAULT_API_URL: &str = "https://api.notion.com";
+const NOTION_VERSION: &str = "2022-06-28";
+
+/// Levels of nested blocks read below the page
+const MAX_DEPTH: usize = 3;
+
+pub struct NotionConnector {
+    client: reqwest::Client,
+    api_url: String,
+    token: String,
+    database_id: Option<String>,
+}
+
+impl NotionConnector {
+    pub fn new(api_url: &str, token: String, database_id: Option<String>) -> Result<Self, String> {
+        let api_url = if api_url.is_empty() { DEFAULT_API_URL } else { api_url };
+        Ok(Self {
+            client: http_client()?,
+            api_url: api_url.trim_end_matches('/').to_string(),
+            token,
+            database_id: database_id.filter(|id| !id.is_empty()),
+        })
+    }
+
+    async fn request(&self, method: reqwest::Method, path: &str, body: Option<Value>) -> Result<Value, String> {
+        let mut request = self
+            .client
+            .request(method, format!("{}/v1/{}", self.api_url, path))
+            .bearer_auth(&self.token)
+            .header("Notion-Version", NOTION_VERSION);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let response = request.send().await.map_err(|e| format!("Notion request failed: {}", e))?;
+        let status = response.status();
+        let body = response.text().await.map_err(|e| e.to_string())?;
+        if !status.is_success() {
+            return Err(format!("Notion API returned {}: {}", status, body));
+        }
+        serde_json::from_str(&body).map_err(|e| format!("Malformed Notion response: {}", e))
+    }
+
+    /// Markdown lines of a block's children, indented by depth
+    fn children_lines<'a>(&'a self, block_id: &'a str, depth: usize) -> BoxFuture<'a, Result<Vec<String>, String>> {
+        Box::pin(async move {
+            let mut lines = Vec::new();
+            let mut start_cursor: Option<String> = None;
+            loop {
+                let mut path = format!("blocks/{}/children?page_size=100", block_id);
+                if let Some(cursor) = &start_cursor {
+                    path.push_str(&format!("&start_cursor={}", cursor));
+                }
+                let page = self.request(reqwest::Method::GET, &path, None).await?;
+                for block in page["results"].as_array().into_iter().flatten() {
+                    if let Some(line) = block_markdown(block) {
+                        lines.push(format!("{}{}", "  ".repeat(depth), line));
+                    }
+                    let nested = block["has_children"].as_bool().unwrap_or(false)
+                        && block["type"].as_str() != Some("child_page");
+                    if nested && depth + 1 < MAX_DEPTH {
+                        if let Some(id) = block["id"].as_str() {
+                            lines.extend(self.children_lines(id, depth + 1).await?);
+                        }
+                    }
+                }
+                match page["next_cursor"].as_str() {
+                    Some(next) if page["has_more"].as_bool() == Some(true) => start_cursor = Some(next.to_string()),
+                    _ => return Ok(lines),
+                }
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl Connector for NotionConnector {
+    fn name(&self) -> String {
+        match &self.database_id {
+            Some(id) => format!("notion:database:{}", id),
+            None => "notion".to_string(),
+        }
+    }
+
+    async fn list(&self, cursor: Option<&str>, limit: usize) -> Result<Vec<DocumentRef>, String> {
+        let mut documents = Vec::new();
+        let mut start_cursor: Option<String> = None;
+        loop {
+            let (path, mut body) = match &self.database_id {
+                Some(id) => {
+                    let mut body = json!({
+                        "sorts": [{"timestamp": "last_edited_time", "direction": "ascending"}],
+                        "page_size": 100,
+                    });
+                    if let Some(since) = cursor {
+                        body["filter"] = json!({"timestamp": "last_edited_time", "last_edited_time": {"on_or_after": since}});
+                    }
+                    (format!("databases/{}/query", id), body)
+                }
+                None => ("search".to_string(), json!({
+                    "filter": {"property": "object", "value": "page"},
+                    "sort": {"timestamp": "last_edited_time", "direction": "ascending"},
+                    "page_size": 100,
+                })),
+            };
+            if let Some(next) = &start_cursor {
+                body["start_cursor"] = json!(next);
+            }
+
+            let page = self.request(reqwest::Method::POST, &path, Some(body)).await?;
+            for result in page["results"].as_array().into_iter().flatten() {
+                let (Some(id), Some(edited)) = (result["id"].as_str(), result["last_edited_time"].as_str()) else {
+                    continue;
+                };
+                // Search can't filter by time
+                if result["archived"].as_bool() == Some(true) || cursor.is_some_and(|since| edited < since) {
+                    continue;
+                }
+                documents.push(DocumentRef { id: id.to_string(), cursor: edited.to_string() });
+                if documents.len() >= limit {
+                    return Ok(documents);
+                }
+            }
+            match page["next_cursor"].as_str() {
+                Some(next) if page["has_more"].as_bool() == Some(true) => start_cursor = Some(next.to_string()),
+                _ => return Ok(documents),
+            }
+        }
+    }
+
+    async fn fetch(&self, document: &DocumentRef) -> Result<Document, String> {
+        let page = self.request(reqwest::Method::GET, &format!("pages/{}", document.id), None).await?;
+        let lines = self.children_lines(&document.id, 0).await?;
+
+        let mut cues = vec!["document:notion_page".to_string()];
+        if let Some(database) = page["parent"]["database_id"].as_str() {
+            cues.push(format!("document:notion_database:{}", database));
+        }
+        Ok(Document {
+            id: document.id.clone(),
+            title: page_title(&page).unwrap_or_else(|| "Untitled".to_string()),
+            url: page["url"].as_str().unwrap_or("").to_string(),
+            content: lines.join("\n"),
+            cues,
+        })
+    }
+}
+
+/// Plain text of a rich text array
+pub fn rich_text(value: &Value) -> String {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|t| t["plain_text"].as_str())
+        .collect()
+}
+
+/// The text of a page's title property
+pub fn page_title(page: &Value) -> Option<String> {
+    page["properties"]
+        .as_object()?
+        .values()
+        .find(|p| p["type"].as_str() == Some("title"))
+        .map(|p| rich_text(&p["title"]).trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+/// One block as a line of Markdown; None for blocks without text
+pub fn block_markdown(block: &Value) -> Option<String> {
+    let kind = block["type"].as_str()?;
+    let data = &block[kind];
+    let text = rich_text(&data["rich_text"]);
+    if text.trim().is_empty() && !matches!(kind, "child_page" | "table_row") {
+        return None;
+    }
+    let line = match kind {
+        "heading_1" => format!("# {}", text),
+        "heading_2" => format!("## {}", text),
+        "heading_3" => format!("### {}", text),
+        "bulleted_list_item" | "toggle" => format!("- {}", text),
+        "numbered_list_item" => format!("1. {}", text),
+        "to_do" => format!("- [{}] {}", if data["checked"].as_bool() == Some(true) { "x" } else { " " }, text),
+        "quote" | "callout" => format!("> {}", text),
+        "code" => format!("```{}\n{}\n```", data["language"].as_str().unwrap_or(""), text),
+        "child_page" => format!("Subpage: {}", data["title"].as_str().unwrap_or("")),
+        "table_row" => data["cells"]
+            .as_array()?
+            .iter()
+            .map(rich_text)
+            .collect::<Vec<_>>()
+            .join(" | "),
+        _ => text,
+    };
+    (!line.trim().is_empty()).then_some(line)
+}
+
+/// Markdown of a list of sibling blocks
+pub fn blocks_markdown(blocks: &[Value]) -> String {
+    blocks.iter().filter_map(block_markdown).collect::<Vec<_>>().join("\n")
+}
@@ -0,0 +1,76 @@
+//! Content hashes of a project's memories, so every ingestion path (`POST
+//! /memories`, watched files, URLs, uploads) can tell whether byte-identical
+//! content is already stored and merge into it instead of storing a copy.
+//!
+//! The index is derived from the memories and rebuilt when a project loads;
+//! it is not persisted.
+
+use ahash::RandomState;
+use dashmap::DashMap;
+use sha2::{Digest, Sha256};
+
+/// SHA-256 of `bytes` as lowercase hex, the hash every ingestion path uses
+pub fn content_hash(bytes: impl AsRef<[u8]>) -> String {
+    format!("{:x}", Sha256::digest(bytes.as_ref()))
+}
+
+#[derive(Default)]
+pub struct ContentIndex {
+    // Hash -> memories with that content, oldest first
+    by_hash: DashMap<String, Vec<String>, RandomState>,
+    by_id: DashMap<String, String, RandomState>,
+}
+
+impl ContentIndex {
+    /// Index `memory_id` under `hash`, replacing the hash it had
+    pub fn insert(&self, memory_id: &str, hash: String) {
+        if self.by_id.get(memory_id).is_some_and(|h| *h == hash) {
+            return;
+        }
+        self.remove(memory_id);
+        self.by_hash.entry(hash.clone()).or_default().push(memory_id.to_string());
+        self.by_id.insert(memory_id.to_string(), hash);
+    }
+
+    pub fn remove(&self, memory_id: &str) {
+        let Some((_, hash)) = self.by_id.remove(memory_id) else {
+            return;
+        };
+        if let dashmap::mapref::entry::Entry::Occupied(mut ids) = self.by_hash.entry(hash) {
+            ids.get_mut().retain(|id| id != memory_id);
+            if ids.get().is_empty() {
+                ids.remove();
+            }
+        }
+    }
+
+    pub fn rename(&self, from: &str, to: &str) {
+        if let Some(hash) = self.by_id.get(from).map(|h| h.clone()) {
+            self.remove(from);
+            self.insert(to, hash);
+        }
+    }
+
+    /// The oldest memory with content hashing to `hash`
+    pub fn find(&self, hash: &str) -> Option<String> {
+        self.by_hash.get(hash).and_then(|ids| ids.first().cloned())
+    }
+
+    pub fn hash_of(&self, memory_id: &str) -> Option<String> {
+        self.by_id.get(memory_id).map(|h| h.clone())
+    }
+
+    pub fn clear(&self) {
+        self.by_hash.clear();
+        self.by_id.clear();
+    }
+
+    /// Distinct contents indexed
+    pub fn len(&self) -> usize {
+        self.by_hash.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_hash.is_empty()
+    }
+}
@@ -103,3 +103,70 @@ impl CryptoEngine {
         hex::encode(result.into_bytes())
     }
 }
+
+/// Short, non-secret identifier for a key (first 8 bytes of its SHA-256).
+/// Stored in encrypted snapshot headers so the right key can be picked on load.
+pub fn key_fingerprint(key: &EncryptionKey) -> [u8; 8] {
+    use sha2::Digest;
+
+    let digest = Sha256::digest(key.as_bytes());
+    let mut fingerprint = [0u8; 8];
+    fingerprint.copy_from_slice(&digest[..8]);
+    fingerprint
+}
+
+/// Passphrase keyring for snapshot encryption-at-rest.
+///
+/// Keys are derived per project (the project ID is part of the PBKDF2 salt), so the
+/// same passphrase never yields the same key for two projects. `previous` passphrases
+/// are only used for decryption, which allows rotating the snapshot key without
+/// losing access to snapshots written under the old one.
+pub struct SnapshotKeyring {
+    current: String,
+    previous: Vec<String>,
+    derived: std::sync::Mutex<std::collections::HashMap<String, Vec<EncryptionKey>>>,
+}
+
+impl SnapshotKeyring {
+    pub fn new(current: String, previous: Vec<String>) -> Self {
+        Self {
+            current,
+            previous: previous.into_iter().filter(|p| !p.is_empty()).collect(),
+            derived: std::sync::Mutex::new(std::collections::HashMap::new()),
+        }
+    }
+
+    /// True if older passphrases are configured (i.e. a rotation is in progress)
+    pub fn has_previous_keys(&self) -> bool {
+        !self.previous.is_empty()
+    }
+
+    /// Key used for writing new snapshots of a project
+    pub fn current_key(&self, project_id: &str) -> EncryptionKey {
+        self.keys_for(project_id)
+            .into_iter()
+            .next()
+            .expect("keyring always holds a current key")
+    }
+
+    /// All keys accepted when reading a project's snapshots (current first)
+    pub fn keys_for(&self, project_id: &str) -> Vec<EncryptionKey> {
+        if let Ok(cache) = self.derived.lock() {
+            if let Some(keys) = cache.get(project_id) {
+                return keys.clone();
+            }
+        }
+
+        // PBKDF2 is deliberately slow, so derive once per project and cache
+        let salt = format!("cuemap-snapshot:{}", project_id);
+        let keys: Vec<EncryptionKey> = std::iter::once(&self.current)
+            .chain(self.previous.iter())
+            .map(|pass| EncryptionKey::from_passphrase(pass, salt.as_bytes()))
+            .collect();
+
+        if let Ok(mut cache) = self.derived.lock() {
+            cache.insert(project_id.to_string(), keys.clone());
+        }
+        keys
+    }
+}
@@ -0,0 +1,479 @@
+//! Embedded (in-process) API for Rust applications linking the crate directly.
+//!
+//! `Client` wraps a `MultiTenantEngine` and its `JobQueue` without any HTTP layer.
+//! Persistence (snapshot directory, periodic snapshots, encryption keys) is wired up
+//! by `ClientBuilder`, mirroring what `cuemap start` does for the server.
+//!
+//! ```no_run
+//! # async fn demo() -> Result<(), String> {
+//! let client = cuemap::Client::builder()
+//!     .data_dir("./cuemap-data")
+//!     .build()?;
+//!
+//! client.add_memory("default", "Payments service timed out", vec!["service:payments".into()], None).await?;
+//! let results = client.recall("default", "payments timeout", 5).await?;
+//! # Ok(()) }
+//! ```
+//!
+//! `ClientBuilder::build` spawns background tasks and must be called inside a Tokio runtime.
+
+use crate::agent::ingester::Ingester;
+use crate::agent::chunker::Chunker;
+use crate::config::{CueGenStrategy, EmbeddingConfig, LlmConfig, TuningConfig};
+use crate::embeddings::embed_memories;
+use crate::crypto::{EncryptionKey, SnapshotKeyring};
+use crate::api::{normalize_exclusions, prepare_recall, recall_hops, recall_profile, rewrite_recall_query, RecallRequest};
+use crate::engine::{RecallFilter, RecallResult};
+use crate::jobs::{Job, JobQueue, ProjectProvider};
+use crate::metrics::MetricsCollector;
+use crate::multi_tenant::{validate_project_id, MultiTenantEngine};
+use crate::semantic::SemanticEngine;
+use crate::slow_queries::StageTimer;
+use crate::structures::MainStats;
+use crate::vector::HybridQuery;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Options for `Client::recall_with`. Mirrors the fields of the HTTP `RecallRequest`.
+#[derive(Debug, Clone)]
+pub struct RecallOptions {
+    pub cues: Vec<String>,
+    pub query_text: Option<String>,
+    pub limit: usize,
+    pub auto_reinforce: bool,
+    pub min_intersection: Option<usize>,
+    pub explain: bool,
+    pub disable_pattern_completion: bool,
+    pub disable_salience_bias: bool,
+    pub disable_systems_consolidation: bool,
+    pub disable_alias_expansion: bool,
+    /// Replace query cues with no exact hit by indexed cues within a few edits
+    pub fuzzy: bool,
+    /// Expand acronyms and correct misspelled words of `query_text` first
+    pub rewrite_query: bool,
+    /// Prefix/wildcard cue patterns (`path:src/agent/*`), expanded to matching cues
+    pub cue_prefix: Vec<String>,
+    /// Time-range and metadata constraints applied before scoring
+    pub filter: RecallFilter,
+    /// Blend cosine similarity against this query vector into the cue score
+    pub hybrid: Option<HybridQuery>,
+    /// Hops of spreading activation: each hop adds the cues of the best result so far
+    pub depth: usize,
+}
+
+impl Default for RecallOptions {
+    fn default() -> Self {
+        Self {
+            cues: Vec::new(),
+            query_text: None,
+            limit: 10,
+            auto_reinforce: true,
+            min_intersection: None,
+            explain: false,
+            disable_pattern_completion: false,
+            disable_salience_bias: false,
+            disable_systems_consolidation: false,
+            disable_alias_expansion: true,
+            fuzzy: false,
+            rewrite_query: true,
+            cue_prefix: Vec::new(),
+            filter: RecallFilter::default(),
+            hybrid: None,
+            depth: 1,
+        }
+    }
+}
+
+/// Builder for an embedded `Client`
+pub struct ClientBuilder {
+    data_dir: Option<PathBuf>,
+    assets_dir: Option<PathBuf>,
+    snapshot_interval: Option<Duration>,
+    load_snapshots: bool,
+    background_jobs: bool,
+    cuegen_strategy: CueGenStrategy,
+    tuning: TuningConfig,
+    llm_config: LlmConfig,
+    embeddings: EmbeddingConfig,
+    master_key: Option<Arc<EncryptionKey>>,
+    snapshot_keyring: Option<Arc<SnapshotKeyring>>,
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self {
+            data_dir: None,
+            assets_dir: None,
+            snapshot_interval: Some(Duration::from_secs(60)),
+            load_snapshots: true,
+            background_jobs: true,
+            cuegen_strategy: CueGenStrategy::Default,
+            tuning: TuningConfig::default(),
+            llm_config: LlmConfig::default(),
+            embeddings: EmbeddingConfig::default(),
+            master_key: None,
+            snapshot_keyring: None,
+        }
+    }
+}
+
+impl ClientBuilder {
+    /// Directory for snapshots. Without it the client is purely in-memory.
+    pub fn data_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.data_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Directory with semantic assets (WordNet, GloVe). Defaults to `data_dir`.
+    pub fn assets_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.assets_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Periodic snapshot interval (None disables periodic snapshots; `save_all` still works)
+    pub fn snapshot_interval(mut self, interval: Option<Duration>) -> Self {
+        self.snapshot_interval = interval;
+        self
+    }
+
+    /// Load existing snapshots from `data_dir` on build (default: true)
+    pub fn load_snapshots(mut self, enabled: bool) -> Self {
+        self.load_snapshots = enabled;
+        self
+    }
+
+    /// Run background jobs (cue proposal, lexicon training, graph updates). Default: true
+    pub fn background_jobs(mut self, enabled: bool) -> Self {
+        self.background_jobs = enabled;
+        self
+    }
+
+    pub fn cuegen_strategy(mut self, strategy: CueGenStrategy) -> Self {
+        self.cuegen_strategy = strategy;
+        self
+    }
+
+    pub fn tuning(mut self, tuning: TuningConfig) -> Self {
+        self.tuning = tuning;
+        self
+    }
+
+    pub fn llm_config(mut self, llm_config: LlmConfig) -> Self {
+        self.llm_config = llm_config;
+        self
+    }
+
+    /// Embedding provider used to embed new memories for hybrid recall
+    pub fn embeddings(mut self, config: EmbeddingConfig) -> Self {
+        self.embeddings = config;
+        self
+    }
+
+    /// Encrypt memory payloads with this master key
+    pub fn master_key(mut self, key: EncryptionKey) -> Self {
+        self.master_key = Some(Arc::new(key));
+        self
+    }
+
+    /// Encrypt snapshot files (see `SnapshotKeyring`)
+    pub fn snapshot_keyring(mut self, keyring: SnapshotKeyring) -> Self {
+        self.snapshot_keyring = Some(Arc::new(keyring));
+        self
+    }
+
+    /// Build the client. Must be called from within a Tokio runtime.
+    pub fn build(self) -> Result<Client, String> {
+        let persist = self.data_dir.is_some();
+        let snapshots_dir = match &self.data_dir {
+            Some(dir) => dir.join("snapshots"),
+            None => std::env::temp_dir().join(format!("cuemap-embedded-{}", uuid::Uuid::new_v4())),
+        };
+
+        let assets_dir = self.assets_dir.clone().or_else(|| self.data_dir.clone());
+        let semantic_engine = SemanticEngine::new(assets_dir.as_deref());
+
+        let mut engine = MultiTenantEngine::with_snapshots_dir(
+            &snapshots_dir,
+            self.cuegen_strategy,
+            semantic_engine,
+            self.tuning,
+            self.llm_config,
+        );
+        engine.set_master_key(self.master_key);
+        engine.set_snapshot_keyring(self.snapshot_keyring);
+        engine.set_embedding_config(self.embeddings);
+        let engine = Arc::new(engine);
+
+        if persist && self.load_snapshots {
+            for (project_id, result) in engine.load_all() {
+                if let Err(e) = result {
+                    tracing::warn!("Embedded client: failed to load project '{}': {}", project_id, e);
+                }
+            }
+        }
+
+        if persist {
+            if let Some(interval) = self.snapshot_interval {
+                engine.start_periodic_snapshots(interval);
+            }
+        }
+
+        let metrics = Arc::new(MetricsCollector::new());
+        let provider: Arc<dyn ProjectProvider> = engine.clone();
+        let job_queue = Arc::new(JobQueue::new(provider.clone(), Some(metrics.clone()), !self.background_jobs));
+        if self.background_jobs {
+            job_queue.start_heatmap_sync(provider, std::time::Duration::from_secs(60), crate::trending::DEFAULT_TRENDING_WINDOW_MINS);
+        }
+
+        Ok(Client {
+            engine,
+            job_queue,
+            metrics,
+            persist,
+        })
+    }
+}
+
+/// In-process CueMap client (no HTTP)
+#[derive(Clone)]
+pub struct Client {
+    engine: Arc<MultiTenantEngine>,
+    job_queue: Arc<JobQueue>,
+    metrics: Arc<MetricsCollector>,
+    persist: bool,
+}
+
+impl Client {
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    /// Underlying engine, for lower-level access
+    pub fn engine(&self) -> &Arc<MultiTenantEngine> {
+        &self.engine
+    }
+
+    pub fn job_queue(&self) -> &Arc<JobQueue> {
+        &self.job_queue
+    }
+
+    pub fn metrics(&self) -> &Arc<MetricsCollector> {
+        &self.metrics
+    }
+
+    /// Add a memory. Empty `cues` bootstraps cues from the content, like `POST /memories`.
+    pub async fn add_memory(
+        &self,
+        project_id: &str,
+        content: &str,
+        cues: Vec<String>,
+        metadata: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<String, String> {
+        let ctx = self.project(project_id)?;
+        self.engine
+            .check_quota(project_id, 1, content.len() as u64)
+            .map_err(|e| e.to_string())?;
+
+        let mut initial_cues = cues;
+        if initial_cues.is_empty() {
+            initial_cues.extend(ctx.tokenize(content));
+        }
+
+        let normalized_cues: Vec<String> = initial_cues
+            .iter()
+            .map(|cue| ctx.normalize_cue(cue))
+            .collect();
+        let report = ctx.validate_cues(normalized_cues);
+
+        let memory_id = ctx.main.add_memory(
+            content.to_string(),
+            report.accepted,
+            metadata,
+            MainStats::default(),
+            false,
+        );
+        if let Some(provider) = self.engine.embedding_provider(project_id)? {
+            if let Err(e) = embed_memories(&ctx, provider.as_ref(), std::slice::from_ref(&memory_id), false).await {
+                tracing::warn!("Embedded client: failed to embed {}: {}", memory_id, e);
+            }
+        }
+
+        let session = self.job_queue.session_manager.get_or_create(project_id);
+        session.expect_write();
+
+        self.job_queue.buffer(project_id, Job::ProposeCues {
+            project_id: project_id.to_string(),
+            memory_id: memory_id.clone(),
+            content: content.to_string(),
+        }).await;
+
+        self.job_queue.buffer(project_id, Job::TrainLexiconFromMemory {
+            project_id: project_id.to_string(),
+            memory_id: memory_id.clone(),
+        }).await;
+
+        self.job_queue.buffer(project_id, Job::UpdateGraph {
+            project_id: project_id.to_string(),
+            memory_id: memory_id.clone(),
+        }).await;
+
+        session.write_complete();
+        self.metrics.record_ingestion();
+
+        Ok(memory_id)
+    }
+
+    /// Attach (or replace) the embedding used by hybrid recall for a memory
+    pub fn set_embedding(&self, project_id: &str, memory_id: &str, vector: Vec<f32>) -> Result<(), String> {
+        self.project(project_id)?.main.set_embedding(memory_id, vector)
+    }
+
+    /// Pin or unpin a memory (pinned memories survive pruning and consolidation)
+    pub fn set_pinned(&self, project_id: &str, memory_id: &str, pinned: bool) -> Result<bool, String> {
+        Ok(self.project(project_id)?.main.set_pinned(memory_id, pinned))
+    }
+
+    /// Natural-language recall with default options
+    pub async fn recall(&self, project_id: &str, query_text: &str, limit: usize) -> Result<Vec<RecallResult>, String> {
+        self.recall_with(project_id, RecallOptions {
+            query_text: Some(query_text.to_string()),
+            limit,
+            ..RecallOptions::default()
+        }).await
+    }
+
+    /// Recall with full control over the options. Runs the same pipeline as
+    /// `POST /recall` (rewrite, expansion, profile and hops).
+    pub async fn recall_with(&self, project_id: &str, opts: RecallOptions) -> Result<Vec<RecallResult>, String> {
+        let start = std::time::Instant::now();
+        let ctx = self.project(project_id)?;
+        let mut req = RecallRequest {
+            cues: opts.cues,
+            query_text: opts.query_text,
+            limit: opts.limit,
+            auto_reinforce: opts.auto_reinforce,
+            min_intersection: opts.min_intersection,
+            explain: opts.explain,
+            disable_pattern_completion: opts.disable_pattern_completion,
+            disable_salience_bias: opts.disable_salience_bias,
+            disable_systems_consolidation: opts.disable_systems_consolidation,
+            disable_alias_expansion: opts.disable_alias_expansion,
+            fuzzy: opts.fuzzy,
+            rewrite_query: opts.rewrite_query,
+            cue_prefix: opts.cue_prefix,
+            depth: opts.depth,
+            ..RecallRequest::default()
+        };
+        if let Some(rewrite) = rewrite_recall_query(&req, &ctx) {
+            req.query_text = Some(rewrite.rewritten);
+        }
+
+        let filter = normalize_exclusions(&opts.filter, &ctx);
+        let hybrid = opts.hybrid.as_ref();
+        let mut recall = prepare_recall(&req, &ctx, &mut StageTimer::new());
+        let profile = recall_profile(&req, &ctx, &filter, hybrid);
+        let limit = req.limit.max(1);
+        let mut results = recall_hops(&ctx, &req, profile, &mut recall.expanded_cues, limit, &filter, hybrid, &mut Vec::new(), |_| true);
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+
+        if req.auto_reinforce && !results.is_empty() {
+            self.job_queue.enqueue(Job::ReinforceMemories {
+                project_id: project_id.to_string(),
+                memory_ids: results.iter().map(|r| r.memory_id.clone()).collect(),
+                cues: recall.expanded_cues.iter().map(|(c, _)| c.clone()).collect(),
+            }).await;
+        }
+
+        if req.auto_reinforce && !recall.lexicon_memory_ids.is_empty() {
+            let tokens = req.query_text.as_deref().map(|text| ctx.tokenize(text)).unwrap_or_default();
+            self.job_queue.enqueue(Job::ReinforceLexicon {
+                project_id: project_id.to_string(),
+                memory_ids: recall.lexicon_memory_ids,
+                cues: tokens,
+                result_ids: results.iter().map(|r| r.memory_id.clone()).collect(),
+            }).await;
+        }
+
+        self.metrics.record_recall(start.elapsed().as_secs_f64() * 1000.0);
+        Ok(results)
+    }
+
+    /// Chunk and ingest a file from disk (code, docs, PDFs, Office files, ...).
+    /// Returns the memory IDs of the enqueued chunks; writes complete asynchronously
+    /// (see `wait_for_ingestion`).
+    pub async fn ingest_file(&self, project_id: &str, path: impl AsRef<Path>) -> Result<Vec<String>, String> {
+        self.project(project_id)?;
+
+        let path = path.as_ref();
+        let bytes = tokio::fs::read(path)
+            .await
+            .map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+        let content = String::from_utf8(bytes).ok();
+
+        let chunks = Chunker::chunk_file(path, content.as_deref().unwrap_or(""));
+        if chunks.is_empty() {
+            return Err(format!("Failed to extract content from {:?} (0 chunks)", path));
+        }
+        let incoming_bytes: usize = chunks.iter().map(|c| c.content.len()).sum();
+        self.engine
+            .check_quota(project_id, chunks.len(), incoming_bytes as u64)
+            .map_err(|e| e.to_string())?;
+
+        let mut ingester = Ingester::new(
+            crate::agent::AgentConfig {
+                project_id: project_id.to_string(),
+                watch_dir: String::new(),
+                throttle_ms: 0,
+                state_file: None,
+                filter: Default::default(),
+                git: Default::default(),
+                chunking: self.engine.get_project_chunking(project_id).unwrap_or_default(),
+                extract_facts: false,
+            },
+            self.job_queue.clone(),
+        );
+
+        let source = format!("file:{}", path.to_string_lossy());
+        ingester.process_chunks(chunks, project_id, &source).await
+    }
+
+    /// Wait until all enqueued writes for a project are applied, then run the
+    /// buffered background jobs (cue proposal, lexicon, graph) immediately.
+    pub async fn wait_for_ingestion(&self, project_id: &str) {
+        if let Some(session) = self.job_queue.get_session(project_id) {
+            loop {
+                let progress = session.get_progress();
+                if progress.writes_completed >= progress.writes_total {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }
+        self.job_queue.session_manager.flush_session(project_id).await;
+    }
+
+    /// Save one project's snapshot (no-op error if the client has no data_dir)
+    pub fn save_project(&self, project_id: &str) -> Result<PathBuf, String> {
+        if !self.persist {
+            return Err("Client was built without a data_dir".to_string());
+        }
+        self.engine.save_project(&project_id.to_string())
+    }
+
+    /// Save all project snapshots
+    pub fn save_all(&self) -> HashMap<String, Result<PathBuf, String>> {
+        if !self.persist {
+            return HashMap::new();
+        }
+        self.engine.save_all()
+    }
+
+    fn project(&self, project_id: &str) -> Result<Arc<crate::projects::ProjectContext>, String> {
+        if !validate_project_id(project_id) {
+            return Err(format!("Invalid project ID format: '{}'", project_id));
+        }
+        self.engine.get_or_create_project(project_id.to_string())
+    }
+}
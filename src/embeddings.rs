@@ -0,0 +1,355 @@
+//! Embedding providers for hybrid recall.
+//!
+//! A provider turns text into vectors for the project's `VectorIndex`. The
+//! strategy is configured server-wide under `[embeddings]` and can be
+//! overridden per project (`PUT /projects/:id/embeddings`):
+//! - `glove`: mean GloVe vector (bundled word vectors, 50 dimensions)
+//! - `onnx`: all-MiniLM-L6-v2 run in-process with ONNX Runtime (`onnx` feature)
+//! - `ollama`: Ollama's `/api/embed`
+//! - `openai`: any OpenAI-compatible `/v1/embeddings` endpoint
+
+use crate::config::{EmbeddingConfig, EmbeddingStrategy};
+use crate::projects::ProjectContext;
+use crate::semantic::SemanticEngine;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::json;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tracing::debug;
+
+/// Texts sent to a provider per request
+pub const EMBED_BATCH_SIZE: usize = 32;
+
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    /// Provider and model, e.g. `ollama/nomic-embed-text`
+    fn name(&self) -> String;
+
+    /// One vector per input text, in order. An empty vector means the text
+    /// could not be embedded (e.g. no known words for GloVe).
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String>;
+}
+
+/// Build the provider for a config; `Ok(None)` for `strategy = "none"`
+pub fn build_provider(config: &EmbeddingConfig, semantic_engine: &SemanticEngine) -> Result<Option<Arc<dyn EmbeddingProvider>>, String> {
+    let provider: Arc<dyn EmbeddingProvider> = match config.strategy {
+        EmbeddingStrategy::None => return Ok(None),
+        EmbeddingStrategy::Glove => Arc::new(GloveProvider { semantic_engine: semantic_engine.clone() }),
+        EmbeddingStrategy::Ollama => Arc::new(OllamaProvider {
+            url: or_default(&config.url, "http://localhost:11434"),
+            model: or_default(&config.model, "nomic-embed-text"),
+        }),
+        EmbeddingStrategy::Openai => Arc::new(OpenAiProvider {
+            url: or_default(&config.url, "https://api.openai.com"),
+            model: or_default(&config.model, "text-embedding-3-small"),
+            api_key: config.api_key.clone().or_else(|| std::env::var("OPENAI_API_KEY").ok()),
+        }),
+        #[cfg(feature = "onnx")]
+        EmbeddingStrategy::Onnx => Arc::new(onnx::OnnxProvider::load(&config.model)?),
+        #[cfg(not(feature = "onnx"))]
+        EmbeddingStrategy::Onnx => {
+            return Err("The onnx embedding strategy requires a build with `--features onnx`".to_string())
+        }
+    };
+    Ok(Some(provider))
+}
+
+/// Embed the content of `memory_ids` and store the vectors. Memories that are
+/// gone, or already have a vector (unless `force`), are skipped.
+/// Returns how many vectors were stored.
+pub async fn embed_memories(
+    ctx: &ProjectContext,
+    provider: &dyn EmbeddingProvider,
+    memory_ids: &[String],
+    force: bool,
+) -> Result<usize, String> {
+    let key = ctx.main.get_master_key();
+    let pending: Vec<(String, String)> = memory_ids
+        .iter()
+        .filter(|id| force || !ctx.main.vector_index().contains(id))
+        .filter_map(|id| {
+            let memory = ctx.main.get_memory(id)?;
+            let content = memory.access_content(key.as_deref()).ok()?;
+            Some((id.clone(), content))
+        })
+        .collect();
+
+    let mut stored = 0;
+    for batch in pending.chunks(EMBED_BATCH_SIZE) {
+        let texts: Vec<String> = batch.iter().map(|(_, content)| content.clone()).collect();
+        let vectors = provider.embed(&texts).await?;
+        if vectors.len() != batch.len() {
+            return Err(format!("{} returned {} vectors for {} texts", provider.name(), vectors.len(), batch.len()));
+        }
+        for ((memory_id, _), vector) in batch.iter().zip(vectors) {
+            if vector.is_empty() {
+                continue;
+            }
+            match ctx.main.set_embedding(memory_id, vector) {
+                Ok(()) => stored += 1,
+                Err(e) => debug!("Skipping embedding for {}: {}", memory_id, e),
+            }
+        }
+    }
+    Ok(stored)
+}
+
+fn or_default(value: &str, default: &str) -> String {
+    if value.is_empty() { default.to_string() } else { value.trim_end_matches('/').to_string() }
+}
+
+fn http_client() -> &'static Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        Client::builder()
+            .timeout(Duration::from_secs(60))
+            .build()
+            .unwrap_or_else(|_| Client::new())
+    })
+}
+
+async fn post_json(request: reqwest::RequestBuilder, body: serde_json::Value, name: &str) -> Result<serde_json::Value, String> {
+    let response = request
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("{}: request failed: {}", name, e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("{}: HTTP {}: {}", name, status, text));
+    }
+    response.json().await.map_err(|e| format!("{}: invalid response: {}", name, e))
+}
+
+fn parse_vector(value: &serde_json::Value) -> Option<Vec<f32>> {
+    value.as_array()?.iter().map(|x| x.as_f64().map(|f| f as f32)).collect()
+}
+
+/// Mean GloVe vector of the text
+pub struct GloveProvider {
+    semantic_engine: SemanticEngine,
+}
+
+#[async_trait]
+impl EmbeddingProvider for GloveProvider {
+    fn name(&self) -> String {
+        "glove/50d".to_string()
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        Ok(texts
+            .iter()
+            .map(|t| self.semantic_engine.get_context_vector(t).map(|v| v.to_vec()).unwrap_or_default())
+            .collect())
+    }
+}
+
+/// Ollama `/api/embed`
+pub struct OllamaProvider {
+    url: String,
+    model: String,
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaProvider {
+    fn name(&self) -> String {
+        format!("ollama/{}", self.model)
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        let name = self.name();
+        let request = http_client().post(format!("{}/api/embed", self.url));
+        let body = post_json(request, json!({ "model": self.model, "input": texts }), &name).await?;
+        body["embeddings"]
+            .as_array()
+            .ok_or_else(|| format!("{}: response has no embeddings", name))?
+            .iter()
+            .map(|v| parse_vector(v).ok_or_else(|| format!("{}: malformed embedding", name)))
+            .collect()
+    }
+}
+
+/// OpenAI-compatible `/v1/embeddings` (OpenAI, Azure-style gateways, vLLM, LM Studio, ...)
+pub struct OpenAiProvider {
+    url: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAiProvider {
+    fn name(&self) -> String {
+        format!("openai/{}", self.model)
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        let name = self.name();
+        let mut request = http_client().post(format!("{}/v1/embeddings", self.url));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let body = post_json(request, json!({ "model": self.model, "input": texts }), &name).await?;
+        let data = body["data"]
+            .as_array()
+            .ok_or_else(|| format!("{}: response has no data", name))?;
+
+        let mut vectors = vec![Vec::new(); texts.len()];
+        for (position, item) in data.iter().enumerate() {
+            let index = item["index"].as_u64().map(|i| i as usize).unwrap_or(position);
+            let vector = parse_vector(&item["embedding"]).ok_or_else(|| format!("{}: malformed embedding", name))?;
+            if let Some(slot) = vectors.get_mut(index) {
+                *slot = vector;
+            }
+        }
+        Ok(vectors)
+    }
+}
+
+#[cfg(feature = "onnx")]
+pub(crate) mod onnx {
+    use super::*;
+    use crate::config::get_base_dir;
+    use ort::session::Session;
+    use ort::value::Tensor;
+    use std::path::{Path, PathBuf};
+    use tokenizers::{PaddingParams, Tokenizer, TruncationParams};
+
+    const MODEL_URL: &str = "https://huggingface.co/sentence-transformers/all-MiniLM-L6-v2/resolve/main/onnx/model.onnx";
+    const TOKENIZER_URL: &str = "https://huggingface.co/sentence-transformers/all-MiniLM-L6-v2/resolve/main/tokenizer.json";
+    const MAX_TOKENS: usize = 256;
+
+    /// all-MiniLM-L6-v2 (384 dimensions), mean-pooled over the attention mask
+    #[derive(Clone)]
+    pub struct OnnxProvider {
+        session: Arc<Session>,
+        tokenizer: Arc<Tokenizer>,
+    }
+
+    impl OnnxProvider {
+        /// Load `model.onnx` + `tokenizer.json` from `model_dir`
+        /// (default `~/.cuemap/models/all-MiniLM-L6-v2`, downloaded on first use)
+        pub fn load(model_dir: &str) -> Result<Self, String> {
+            let dir = if model_dir.is_empty() {
+                get_base_dir().join("models").join("all-MiniLM-L6-v2")
+            } else {
+                PathBuf::from(model_dir)
+            };
+            let model_path = dir.join("model.onnx");
+            let tokenizer_path = dir.join("tokenizer.json");
+            if model_dir.is_empty() {
+                download_if_missing(&model_path, MODEL_URL)?;
+                download_if_missing(&tokenizer_path, TOKENIZER_URL)?;
+            }
+
+            let session = Session::builder()
+                .and_then(|b| b.commit_from_file(&model_path))
+                .map_err(|e| format!("Failed to load ONNX model {:?}: {}", model_path, e))?;
+            let mut tokenizer = Tokenizer::from_file(&tokenizer_path)
+                .map_err(|e| format!("Failed to load tokenizer {:?}: {}", tokenizer_path, e))?;
+            tokenizer.with_padding(Some(PaddingParams::default()));
+            tokenizer
+                .with_truncation(Some(TruncationParams { max_length: MAX_TOKENS, ..Default::default() }))
+                .map_err(|e| e.to_string())?;
+
+            Ok(Self { session: Arc::new(session), tokenizer: Arc::new(tokenizer) })
+        }
+
+        fn embed_blocking(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, String> {
+            let encodings = self.tokenizer.encode_batch(texts, true).map_err(|e| e.to_string())?;
+            let batch = encodings.len();
+            let seq = encodings.first().map(|e| e.len()).unwrap_or(0);
+            if batch == 0 || seq == 0 {
+                return Ok(vec![Vec::new(); batch]);
+            }
+
+            let mut ids = Vec::with_capacity(batch * seq);
+            let mut mask = Vec::with_capacity(batch * seq);
+            let mut types = Vec::with_capacity(batch * seq);
+            for encoding in &encodings {
+                ids.extend(encoding.get_ids().iter().map(|&x| x as i64));
+                mask.extend(encoding.get_attention_mask().iter().map(|&x| x as i64));
+                types.extend(encoding.get_type_ids().iter().map(|&x| x as i64));
+            }
+
+            let shape = vec![batch as i64, seq as i64];
+            let tensor = |data: Vec<i64>| Tensor::from_array((shape.clone(), data)).map_err(|e| e.to_string());
+            let inputs = ort::inputs![
+                "input_ids" => tensor(ids)?,
+                "attention_mask" => tensor(mask.clone())?,
+                "token_type_ids" => tensor(types)?,
+            ]
+            .map_err(|e| e.to_string())?;
+            let outputs = self.session.run(inputs).map_err(|e| format!("ONNX inference failed: {}", e))?;
+            let (out_shape, hidden) = outputs["last_hidden_state"]
+                .try_extract_raw_tensor::<f32>()
+                .map_err(|e| e.to_string())?;
+            let dims = *out_shape.last().unwrap_or(&0) as usize;
+
+            // Mean pooling over real (non-padding) tokens
+            let mut vectors = Vec::with_capacity(batch);
+            for b in 0..batch {
+                let mut pooled = vec![0f32; dims];
+                let mut count = 0f32;
+                for t in 0..seq {
+                    if mask[b * seq + t] == 0 {
+                        continue;
+                    }
+                    let offset = (b * seq + t) * dims;
+                    for (acc, x) in pooled.iter_mut().zip(&hidden[offset..offset + dims]) {
+                        *acc += x;
+                    }
+                    count += 1.0;
+                }
+                if count > 0.0 {
+                    pooled.iter_mut().for_each(|x| *x /= count);
+                }
+                vectors.push(pooled);
+            }
+            Ok(vectors)
+        }
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for OnnxProvider {
+        fn name(&self) -> String {
+            "onnx/all-MiniLM-L6-v2".to_string()
+        }
+
+        async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+            let provider = self.clone();
+            let texts = texts.to_vec();
+            tokio::task::spawn_blocking(move || provider.embed_blocking(texts))
+                .await
+                .map_err(|e| e.to_string())?
+        }
+    }
+
+    pub(crate) fn download_if_missing(path: &Path, url: &'static str) -> Result<(), String> {
+        if path.exists() {
+            return Ok(());
+        }
+        tracing::info!("Downloading {} ...", url);
+        let path = path.to_path_buf();
+        // Blocking client must not run on a runtime thread
+        std::thread::spawn(move || -> Result<(), String> {
+            if let Some(parent) = path.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            let mut response = reqwest::blocking::get(url).map_err(|e| format!("Failed to download {}: {}", url, e))?;
+            if !response.status().is_success() {
+                return Err(format!("Failed to download {}: HTTP {}", url, response.status()));
+            }
+            let tmp = path.with_extension("part");
+            let mut file = std::fs::File::create(&tmp).map_err(|e| e.to_string())?;
+            std::io::copy(&mut response, &mut file).map_err(|e| {
+                let _ = std::fs::remove_file(&tmp);
+                format!("Failed to write {:?}: {}", path, e)
+            })?;
+            std::fs::rename(&tmp, &path).map_err(|e| e.to_string())
+        })
+        .join()
+        .map_err(|_| "Model download thread panicked".to_string())?
+    }
+}
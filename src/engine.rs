@@ -1,11 +1,22 @@
-use crate::structures::{Memory, OrderedSet, MainStats, LexiconStats, MemoryStats};
-use crate::config::TuningConfig;
+use crate::structures::{decode_payload, CueField, Memory, MemoryLink, OrderedSet, MainStats, LexiconStats, MemoryStats, Provenance, PINNED_METADATA_KEY, PROVENANCE_METADATA_KEY};
+use crate::config::{FieldBoosts, TuningConfig};
 use crate::crypto::EncryptionKey;
+use crate::live::{LiveEvent, LiveFeed};
+use crate::payload_store::{Payload, PayloadStore};
+use crate::vector::{HybridQuery, VectorIndex};
+use crate::history::{MemoryHistory, MemoryVersion};
+use crate::access::{AccessStats, IdleMemory, MemoryAccess, MemoryAnalytics};
+use crate::content_index::{content_hash, ContentIndex};
+use crate::index_stats::{CueIndexReport, IndexShardStats, ShardStats};
+use crate::fuzzy::{allowed_distance, osa_distance, FuzzyCorrection, FUZZY_MAX_CORRECTIONS, FUZZY_WEIGHT_DECAY};
+use crate::trending::{TrendingCue, BASELINE_DAYS};
+use crate::maintenance::DecayReport;
+use crate::aggregation::{day_key, CountBucket, CountReport};
 use dashmap::DashMap;
 use serde::{Serialize, Deserialize};
-use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 use ahash::RandomState;
 
@@ -27,6 +38,348 @@ pub struct RecallResult {
 
 }
 
+/// Metadata predicate for recall filtering.
+/// `equals` requires an exact JSON match, `prefix` a string prefix; with neither set
+/// the key only has to be present.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataFilter {
+    pub key: String,
+    #[serde(default)]
+    pub equals: Option<serde_json::Value>,
+    #[serde(default)]
+    pub prefix: Option<String>,
+}
+
+impl MetadataFilter {
+    pub fn matches(&self, metadata: &HashMap<String, serde_json::Value>) -> bool {
+        let value = match metadata.get(&self.key) {
+            Some(v) => v,
+            None => return false,
+        };
+
+        if let Some(expected) = &self.equals {
+            if value != expected {
+                return false;
+            }
+        }
+
+        if let Some(prefix) = &self.prefix {
+            match value.as_str() {
+                Some(s) if s.starts_with(prefix.as_str()) => {}
+                _ => return false,
+            }
+        }
+
+        true
+    }
+}
+
+/// Switches and inputs of `recall_weighted` besides the query and limit.
+/// The default runs every stage, without filters or hybrid scoring.
+#[derive(Clone, Copy, Default)]
+pub struct RecallParams<'a> {
+    pub auto_reinforce: bool,
+    pub min_intersection: Option<usize>,
+    pub explain: bool,
+    pub disable_pattern_completion: bool,
+    pub disable_salience_bias: bool,
+    pub disable_systems_consolidation: bool,
+    pub heatmap: Option<&'a HashMap<String, f32>>,
+    pub filter: Option<&'a RecallFilter>,
+    pub hybrid: Option<&'a HybridQuery>,
+}
+
+/// Candidate filters applied inside consolidated_search, before scoring.
+/// Timestamps are unix seconds (same unit as `Memory::created_at`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecallFilter {
+    #[serde(default)]
+    pub created_after: Option<f64>,
+    #[serde(default)]
+    pub created_before: Option<f64>,
+    #[serde(default)]
+    pub metadata_filters: Vec<MetadataFilter>,
+    /// Negative cues: memories carrying any of these are dropped
+    #[serde(default)]
+    pub exclude_cues: Vec<String>,
+    /// Words that must appear in order in the (decrypted) content. Checked after
+    /// scoring, on the best candidates only.
+    #[serde(default)]
+    pub phrase: Option<String>,
+    /// Words allowed between consecutive phrase words (0 = verbatim)
+    #[serde(default)]
+    pub phrase_window: usize,
+    /// Overrides the adaptive per-cue scan budget. Not a filter: `is_empty`
+    /// ignores it.
+    #[serde(default)]
+    pub scan_limit: Option<usize>,
+}
+
+impl RecallFilter {
+    pub fn is_empty(&self) -> bool {
+        self.created_after.is_none()
+            && self.created_before.is_none()
+            && self.metadata_filters.is_empty()
+            && self.exclude_cues.is_empty()
+            && self.phrase_words().is_empty()
+    }
+
+    fn phrase_words(&self) -> Vec<String> {
+        self.phrase.as_deref().map(content_words).unwrap_or_default()
+    }
+
+    /// Whether `content` contains the phrase words in order, each at most
+    /// `phrase_window` words after the previous one. True when there is no phrase.
+    pub fn matches_phrase(&self, content: &str) -> bool {
+        self.matches_phrase_words(&self.phrase_words(), content)
+    }
+
+    /// `matches_phrase` with the phrase already split into words, for callers
+    /// checking many memories
+    fn matches_phrase_words(&self, phrase: &[String], content: &str) -> bool {
+        if phrase.is_empty() {
+            return true;
+        }
+        let words = content_words(content);
+        // Positions where the phrase matched up to the current word
+        let mut reachable: Vec<usize> = (0..words.len()).filter(|&i| words[i] == phrase[0]).collect();
+        for word in &phrase[1..] {
+            let mut next = Vec::new();
+            for &i in &reachable {
+                let end = (i + 2 + self.phrase_window).min(words.len());
+                for (j, candidate) in words.iter().enumerate().take(end).skip(i + 1) {
+                    if candidate == word {
+                        next.push(j);
+                    }
+                }
+            }
+            next.sort_unstable();
+            next.dedup();
+            if next.is_empty() {
+                return false;
+            }
+            reachable = next;
+        }
+        !reachable.is_empty()
+    }
+
+    pub fn matches<T>(&self, memory: &Memory<T>) -> bool {
+        if let Some(after) = self.created_after {
+            if memory.created_at < after {
+                return false;
+            }
+        }
+        if let Some(before) = self.created_before {
+            if memory.created_at > before {
+                return false;
+            }
+        }
+        if !self.exclude_cues.is_empty() && memory.cues.iter().any(|c| self.excludes_cue(c)) {
+            return false;
+        }
+        self.metadata_filters.iter().all(|f| f.matches(&memory.metadata))
+    }
+
+    /// Whether a memory cue is excluded, as written or by its value: the index
+    /// also files `tool:kubernetes` under `kubernetes`, so excluding
+    /// `kubernetes` drops it just as recalling `kubernetes` finds it
+    fn excludes_cue(&self, cue: &str) -> bool {
+        let cue = cue.to_lowercase();
+        let cue = cue.trim();
+        let value = cue.split_once(':').map(|(_, value)| value).filter(|value| !value.is_empty());
+        self.exclude_cues.iter().any(|excluded| excluded == cue || Some(excluded.as_str()) == value)
+    }
+}
+
+/// Complete state of one engine, including the structures plain snapshots
+/// rebuild on load (co-occurrence counts, temporal chunking chains).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineArchive<T> {
+    pub memories: HashMap<String, Memory<T>>,
+    /// Cue -> memory ids, oldest first
+    pub cue_index: HashMap<String, Vec<String>>,
+    pub co_occurrence: HashMap<String, HashMap<String, u64>>,
+    /// Temporal chunking: last event per chain (memory id, timestamp, cues)
+    pub last_events: HashMap<String, (String, f64, Vec<String>)>,
+}
+
+/// Persisted cue co-occurrence matrix, so a restart does not rebuild it from every memory
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CoOccurrenceSnapshot {
+    /// Memories in the engine when the matrix was saved; a mismatch on load means
+    /// the snapshot is stale and the matrix is rebuilt instead
+    pub memory_count: usize,
+    pub neighbours: HashMap<String, HashMap<String, u64>>,
+}
+
+/// Portable form of a memory used by JSONL export/import.
+/// Content is plaintext; it is compressed (and encrypted) again on import.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryRecord<T> {
+    pub id: String,
+    pub content: String,
+    #[serde(default)]
+    pub cues: Vec<String>,
+    #[serde(default)]
+    pub metadata: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub stats: T,
+    #[serde(default)]
+    pub created_at: Option<f64>,
+    #[serde(default)]
+    pub last_accessed: Option<f64>,
+    #[serde(default)]
+    pub links: Vec<MemoryLink>,
+}
+
+/// How much detail `explain` returns. `Full` adds a score trace for every candidate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExplainLevel {
+    #[default]
+    Basic,
+    Full,
+}
+
+/// Recall path chosen per query (or per project). The cheaper paths skip the
+/// scoring pipeline, so they trade ranking quality for latency:
+/// - `Fast` (`recall_fast`): memories matching any cue, ordered by
+///   reinforcement, salience and recency. Suited to lookups by a known key.
+/// - `Balanced` (`recall_intersection`): scans only the most selective cue's
+///   recent memories and scores them by matched cue weight; typically an order
+///   of magnitude cheaper than `Thorough` on common cues.
+/// - `Thorough` (`recall_weighted`): IDF weighting, pattern completion,
+///   salience and recency scoring. The default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecallProfile {
+    Fast,
+    Balanced,
+    #[default]
+    Thorough,
+}
+
+/// IDF adjustment applied to one query cue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CueWeightTrace {
+    pub cue: String,
+    pub weight: f64,
+    pub document_frequency: usize,
+    pub idf: f64,
+    pub adjusted_weight: f64,
+    /// Newest entries of the cue's list that were scanned for candidates
+    pub scan_budget: usize,
+}
+
+/// How much of one query cue's posting list recall scans
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanBudget {
+    pub cue: String,
+    pub document_frequency: usize,
+    pub budget: usize,
+}
+
+/// Score breakdown for one candidate, kept or pruned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CandidateTrace {
+    pub memory_id: String,
+    pub matched_cues: Vec<String>,
+    pub score: Option<f64>,
+    pub intersection_weighted: Option<f64>,
+    pub intersection_count: usize,
+    pub match_integrity: Option<f64>,
+    pub recency_score: Option<f64>,
+    pub reinforcement_score: Option<f64>,
+    pub salience_score: Option<f64>,
+    pub included: bool,
+    /// Why the candidate was dropped: "filtered", "summary_disabled",
+    /// "phrase_mismatch", "below_min_intersection" or "below_limit"
+    pub excluded_reason: Option<String>,
+}
+
+impl CandidateTrace {
+    fn pruned(memory_id: &str, reason: &str) -> Self {
+        Self {
+            memory_id: memory_id.to_string(),
+            matched_cues: Vec::new(),
+            score: None,
+            intersection_weighted: None,
+            intersection_count: 0,
+            match_integrity: None,
+            recency_score: None,
+            reinforcement_score: None,
+            salience_score: None,
+            included: false,
+            excluded_reason: Some(reason.to_string()),
+        }
+    }
+}
+
+/// Full score trace of a single recall_weighted call
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecallTrace {
+    pub active_cues: Vec<(String, f64)>,
+    pub pattern_completion_cues: Vec<String>,
+    pub cue_weights: Vec<CueWeightTrace>,
+    pub candidates: Vec<CandidateTrace>,
+}
+
+/// Outcome of an inverted-index compaction pass
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompactionReport {
+    pub cues_scanned: usize,
+    pub stale_ids_removed: usize,
+    pub empty_cues_removed: usize,
+    pub co_occurrence_entries_removed: usize,
+    pub index_bytes_before: usize,
+    pub index_bytes_after: usize,
+}
+
+/// Approximate heap footprint of one cue's posting list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CueFootprint {
+    pub cue: String,
+    pub memory_count: usize,
+    pub capacity: usize,
+    pub bytes: usize,
+}
+
+/// Upper bounds of the `CueAnalytics` IDF histogram buckets (the last bucket is open)
+pub const IDF_HISTOGRAM_BOUNDS: &[f64] = &[0.5, 1.0, 2.0, 3.0, 4.0, 6.0, 8.0];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CueUsage {
+    pub cue: String,
+    pub memory_count: usize,
+    pub recall_hits: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdfBucket {
+    pub min: f64,
+    /// None for the open last bucket
+    pub max: Option<f64>,
+    pub cues: usize,
+}
+
+/// Cue statistics for taxonomy curation: the heaviest cues, cues on a single
+/// memory, cues no recall has used, and how selective the vocabulary is
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CueAnalytics {
+    pub total_cues: usize,
+    pub total_memories: usize,
+    /// Largest cues by memory count
+    pub top_cues: Vec<CueUsage>,
+    pub singleton_cue_count: usize,
+    /// Sample of cues attached to exactly one memory
+    pub singleton_cues: Vec<String>,
+    pub never_recalled_count: usize,
+    /// Sample of cues no recall has matched since the project was loaded, largest first
+    pub never_recalled: Vec<CueUsage>,
+    /// Cues per IDF range, using the same IDF as weighted recall
+    pub idf_histogram: Vec<IdfBucket>,
+}
+
 #[derive(Debug, Clone)]
 
 pub struct ScoredMemoryCandidate {
@@ -43,6 +396,43 @@ pub struct ScoredMemoryCandidate {
     pub match_count: f64,
 }
 
+/// Candidates scored per requested result when a phrase constraint is set
+const PHRASE_CANDIDATE_FACTOR: usize = 5;
+
+/// Candidate ordered by score alone, for the top-k heap in
+/// `score_consolidated_candidates` (wrapped in `Reverse`, the lowest score is
+/// at the top and gets evicted first)
+struct ByScore(ScoredMemoryCandidate);
+
+impl PartialEq for ByScore {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for ByScore {}
+
+impl PartialOrd for ByScore {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ByScore {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.score.total_cmp(&other.0.score)
+    }
+}
+
+/// Indexed cues one prefix/wildcard pattern may expand to
+pub const CUE_PREFIX_MAX_MATCHES: usize = 256;
+
+/// Score of a memory reached by following a link, relative to the result it is linked from
+pub const LINK_SCORE_DECAY: f64 = 0.5;
+
+/// Dynamic heat removed from returned memories a client marks as not useful
+pub const RECALL_FEEDBACK_PENALTY: f64 = 0.1;
+
 #[derive(Clone)]
 pub struct CueMapEngine<T>
 where
@@ -50,6 +440,8 @@ where
 {
     memories: Arc<DashMap<String, Memory<T>, RandomState>>,
     cue_index: Arc<DashMap<String, OrderedSet, RandomState>>,
+    // Sorted cue_index keys, for prefix/wildcard cue queries
+    cue_keys: Arc<RwLock<BTreeSet<String>>>,
     // Pattern Completion: cue co-occurrence matrix
     cue_co_occurrence: Arc<DashMap<String, DashMap<String, u64, RandomState>, RandomState>>,
     // Temporal Chunking: track last event per session/project
@@ -57,8 +449,31 @@ where
     
     memory_count: Arc<AtomicUsize>,
     cue_count: Arc<AtomicUsize>,
+    // Stored (compressed/encrypted) payload bytes, for storage quotas
+    content_bytes: Arc<AtomicUsize>,
+    // Bumped on every write; lets replication skip unchanged engines
+    generation: Arc<AtomicU64>,
+    // Optional embeddings for hybrid recall
+    vectors: Arc<VectorIndex>,
+    history: Arc<MemoryHistory>,
+    // Recalls that returned each memory
+    access: Arc<AccessStats>,
+    // Content hash of each memory, for exact-duplicate detection on ingest
+    content_index: Arc<ContentIndex>,
+    // Tokens of the structural cues (title/header/path) of memories that have any,
+    // for field boosts without tokenizing during recall
+    field_tokens: Arc<DashMap<String, FieldTokens, RandomState>>,
+    // Per-shard write and lock contention counters for cue_index
+    index_stats: Arc<IndexShardStats>,
+    // Recall passes each indexed cue took part in, since the engine was loaded
+    cue_hits: Arc<DashMap<String, u64, RandomState>>,
     master_key: Option<Arc<EncryptionKey>>,
     tuning: Arc<TuningConfig>,
+    live_feed: Option<LiveFeed>,
+    // On-disk log for payloads of disk-backed projects (None = payloads in RAM)
+    payload_store: Option<Arc<PayloadStore>>,
+    // Unix seconds dynamic salience has been decayed through (0 = never)
+    salience_decayed_at: Arc<AtomicU64>,
 }
 
 
@@ -68,15 +483,29 @@ where
     T: Serialize + for<'de> Deserialize<'de> + Clone + Default + Send + Sync + MemoryStats + 'static
 {
     pub fn new() -> Self {
+        let cue_index: DashMap<String, OrderedSet, RandomState> = DashMap::with_hasher(RandomState::new());
         Self {
             memories: Arc::new(DashMap::with_hasher(RandomState::new())),
-            cue_index: Arc::new(DashMap::with_hasher(RandomState::new())),
+            index_stats: Arc::new(IndexShardStats::new(cue_index.shards().len())),
+            cue_hits: Arc::new(DashMap::with_hasher(RandomState::new())),
+            cue_index: Arc::new(cue_index),
+            cue_keys: Arc::new(RwLock::new(BTreeSet::new())),
             cue_co_occurrence: Arc::new(DashMap::with_hasher(RandomState::new())),
             last_events: Arc::new(DashMap::with_hasher(RandomState::new())),
             memory_count: Arc::new(AtomicUsize::new(0)),
             cue_count: Arc::new(AtomicUsize::new(0)),
+            content_bytes: Arc::new(AtomicUsize::new(0)),
+            generation: Arc::new(AtomicU64::new(0)),
+            vectors: Arc::new(VectorIndex::new()),
+            history: Arc::new(MemoryHistory::default()),
+            access: Arc::new(AccessStats::default()),
+            content_index: Arc::new(ContentIndex::default()),
+            field_tokens: Arc::new(DashMap::with_hasher(RandomState::new())),
             master_key: None,
             tuning: Arc::new(TuningConfig::default()),
+            live_feed: None,
+            payload_store: None,
+            salience_decayed_at: Arc::new(AtomicU64::new(0)),
         }
     }
 
@@ -104,33 +533,314 @@ where
         self.master_key.clone()
     }
 
+    /// Publish add/attach/reinforce/delete events for `/ws` subscribers
+    pub fn set_live_feed(&mut self, feed: Option<LiveFeed>) {
+        self.live_feed = feed;
+    }
+
+    /// Keep payloads written from now on in `store` instead of RAM (see `offload_payloads`)
+    pub fn set_payload_store(&mut self, store: Option<Arc<PayloadStore>>) {
+        self.payload_store = store;
+    }
+
+    pub fn payload_store(&self) -> Option<&Arc<PayloadStore>> {
+        self.payload_store.as_ref()
+    }
+
+    /// Unix seconds `decay_salience` has decayed dynamic salience through (0 = never)
+    pub fn salience_decayed_at(&self) -> u64 {
+        self.salience_decayed_at.load(Ordering::Relaxed)
+    }
+
+    /// Restore the decay clock saved with the project, so decay resumes where it stopped
+    pub fn set_salience_decayed_at(&self, secs: u64) {
+        self.salience_decayed_at.store(secs, Ordering::Relaxed);
+    }
+
+    /// Move payloads still held in RAM to the payload store. Returns the number moved.
+    pub fn offload_payloads(&self) -> usize {
+        if self.payload_store.is_none() {
+            return 0;
+        }
+        let mut moved = 0;
+        for mut memory in self.memories.iter_mut() {
+            if let Payload::Inline(bytes) = &mut memory.content {
+                let bytes = std::mem::take(bytes);
+                memory.content = self.store_payload(bytes);
+                moved += memory.content.is_stored() as usize;
+            }
+        }
+        moved
+    }
+
+    /// Wrap new payload bytes, writing them to the payload store if there is one.
+    /// A failed write keeps the payload in RAM.
+    fn store_payload(&self, bytes: Vec<u8>) -> Payload {
+        let Some(store) = &self.payload_store else {
+            return Payload::Inline(bytes);
+        };
+        match store.put_payload(&bytes) {
+            Ok(payload) => payload,
+            Err(e) => {
+                tracing::error!("Keeping payload in memory: {}", e);
+                Payload::Inline(bytes)
+            }
+        }
+    }
+
+    pub fn set_vector_index(&mut self, index: VectorIndex) {
+        self.vectors = Arc::new(index);
+    }
+
+    pub fn vector_index(&self) -> &Arc<VectorIndex> {
+        &self.vectors
+    }
+
+    pub fn set_history(&mut self, history: MemoryHistory) {
+        self.history = Arc::new(history);
+    }
+
+    /// Versions kept by `upsert_memory_with_id` (disabled unless `max_versions` > 0)
+    pub fn history(&self) -> &Arc<MemoryHistory> {
+        &self.history
+    }
+
+    pub fn set_access_stats(&mut self, access: AccessStats) {
+        self.access = Arc::new(access);
+    }
+
+    pub fn access_stats(&self) -> &Arc<AccessStats> {
+        &self.access
+    }
+
+    /// Re-hash the content of every memory. Run once the master key is set on
+    /// a restored engine; memories whose content can't be read are left out.
+    pub fn rebuild_content_index(&self) {
+        self.content_index.clear();
+        for memory in self.memories.iter() {
+            match memory.access_content(self.master_key.as_deref()) {
+                Ok(content) => self.content_index.insert(memory.key(), content_hash(content.as_bytes())),
+                Err(e) => tracing::warn!("Failed to hash content of {}: {}", memory.key(), e),
+            }
+        }
+    }
+
+    /// The oldest memory whose content is byte-identical to `content`
+    pub fn find_identical(&self, content: &str) -> Option<String> {
+        self.content_index.find(&content_hash(content))
+    }
+
+    /// Content of a stored version
+    pub fn version_content(&self, version: &MemoryVersion) -> Result<String, String> {
+        decode_payload(&version.content, self.master_key.as_deref())
+    }
+
+    /// Restore a stored version of a memory. The state being replaced becomes a
+    /// version itself, so a rollback can be undone. Returns the new current version.
+    pub fn rollback_memory(&self, memory_id: &str, version: u64) -> Result<u64, String> {
+        if !self.memories.contains_key(memory_id) {
+            return Err(format!("Memory '{}' not found", memory_id));
+        }
+        let target = self.history.get(memory_id, version)
+            .ok_or_else(|| format!("Version {} of memory '{}' not found", version, memory_id))?;
+        let content = self.version_content(&target)?;
+        self.upsert_memory_with_id(memory_id.to_string(), content, target.cues, Some(target.metadata), None, false, true);
+        Ok(self.history.current_version(memory_id))
+    }
+
+    /// Attach an embedding to an existing memory (replaces any previous one)
+    pub fn set_embedding(&self, memory_id: &str, vector: Vec<f32>) -> Result<(), String> {
+        if !self.memories.contains_key(memory_id) {
+            return Err(format!("Memory '{}' not found", memory_id));
+        }
+        self.vectors.insert(memory_id, vector)?;
+        self.mark_changed();
+        Ok(())
+    }
+
+    /// Drop every embedding (e.g. before re-embedding with a different model)
+    pub fn clear_embeddings(&self) {
+        if !self.vectors.is_empty() {
+            self.vectors.clear();
+            self.mark_changed();
+        }
+    }
+
+    fn publish(&self, build: impl FnOnce(String) -> LiveEvent) {
+        if let Some(feed) = &self.live_feed {
+            feed.publish(build);
+        }
+    }
+
+    fn publish_reinforced(&self, memory_id: &str) {
+        if self.live_feed.is_none() {
+            return;
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        if let Some(memory) = self.memories.get(memory_id) {
+            let reinforcement_count = memory.stats.get_reinforcement_count();
+            let salience = memory.stats.get_effective_salience(now);
+            drop(memory);
+            self.publish(|project_id| LiveEvent::MemoryReinforced {
+                project_id,
+                memory_id: memory_id.to_string(),
+                reinforcement_count,
+                salience,
+            });
+        }
+    }
+
 
     
+    /// Restore an engine from a snapshot, reusing a persisted co-occurrence matrix
+    /// when it matches the snapshot; otherwise the matrix is rebuilt as in `from_state`
+    pub fn from_state_with_co_occurrence(
+        memories: DashMap<String, Memory<T>, RandomState>,
+        cue_index: DashMap<String, OrderedSet, RandomState>,
+        co_occurrence: Option<CoOccurrenceSnapshot>,
+    ) -> Self {
+        match co_occurrence {
+            Some(snapshot) if snapshot.memory_count == memories.len() => {
+                let engine = Self::from_parts(memories, cue_index);
+                engine.restore_co_occurrence(snapshot.neighbours);
+                engine
+            }
+            _ => Self::from_state(memories, cue_index),
+        }
+    }
+
     pub fn from_state(
         memories: DashMap<String, Memory<T>, RandomState>,
         cue_index: DashMap<String, OrderedSet, RandomState>,
+    ) -> Self {
+        let engine = Self::from_parts(memories, cue_index);
+
+        // Rehydrate co-occurrence matrix from existing memories
+        // This ensures the graph and pattern completion work after restart
+        for r in engine.memories.iter() {
+            let memory = r.value();
+            engine.update_cue_co_occurrence(&memory.cues);
+        }
+
+        engine
+    }
+
+    /// Restore an engine exactly as it was archived; nothing is recomputed
+    pub fn from_archive(archive: EngineArchive<T>) -> Self {
+        let memories = DashMap::with_hasher(RandomState::new());
+        for (id, memory) in archive.memories {
+            memories.insert(id, memory);
+        }
+
+        let cue_index = DashMap::with_hasher(RandomState::new());
+        for (cue, memory_ids) in archive.cue_index {
+            let mut ordered_set = OrderedSet::new();
+            for memory_id in memory_ids {
+                ordered_set.add(memory_id);
+            }
+            cue_index.insert(cue, ordered_set);
+        }
+
+        let engine = Self::from_parts(memories, cue_index);
+        engine.restore_co_occurrence(archive.co_occurrence);
+        for (chain, event) in archive.last_events {
+            engine.last_events.insert(chain, event);
+        }
+
+        engine
+    }
+
+    /// Capture the full engine state for `from_archive`
+    pub fn to_archive(&self) -> EngineArchive<T> {
+        EngineArchive {
+            memories: self.memories
+                .iter()
+                .map(|e| (e.key().clone(), e.value().clone()))
+                .collect(),
+            cue_index: self.cue_index
+                .iter()
+                .map(|e| {
+                    let mut ids = e.value().get_recent_owned(None);
+                    ids.reverse();
+                    (e.key().clone(), ids)
+                })
+                .collect(),
+            co_occurrence: self.co_occurrence_map(),
+            last_events: self.last_events
+                .iter()
+                .map(|e| (e.key().clone(), e.value().clone()))
+                .collect(),
+        }
+    }
+
+    /// Capture the co-occurrence matrix for `from_state_with_co_occurrence`
+    pub fn co_occurrence_snapshot(&self) -> CoOccurrenceSnapshot {
+        CoOccurrenceSnapshot {
+            memory_count: self.memories.len(),
+            neighbours: self.co_occurrence_map(),
+        }
+    }
+
+    fn co_occurrence_map(&self) -> HashMap<String, HashMap<String, u64>> {
+        self.cue_co_occurrence
+            .iter()
+            .map(|e| {
+                let neighbours = e.value().iter().map(|n| (n.key().clone(), *n.value())).collect();
+                (e.key().clone(), neighbours)
+            })
+            .collect()
+    }
+
+    fn restore_co_occurrence(&self, co_occurrence: HashMap<String, HashMap<String, u64>>) {
+        for (cue, neighbours) in co_occurrence {
+            let inner = DashMap::with_hasher(RandomState::new());
+            for (other, count) in neighbours {
+                inner.insert(other, count);
+            }
+            self.cue_co_occurrence.insert(cue, inner);
+        }
+    }
+
+    fn from_parts(
+        memories: DashMap<String, Memory<T>, RandomState>,
+        cue_index: DashMap<String, OrderedSet, RandomState>,
     ) -> Self {
         let count = memories.len();
-        let engine = Self {
+        let content_bytes: usize = memories.iter().map(|m| m.content.len()).sum();
+        let cue_keys: BTreeSet<String> = cue_index.iter().map(|e| e.key().clone()).collect();
+        let field_tokens = DashMap::with_hasher(RandomState::new());
+        for m in memories.iter() {
+            let fields = structural_field_tokens(&m.cues);
+            if !fields.is_empty() {
+                field_tokens.insert(m.key().clone(), fields);
+            }
+        }
+        Self {
             memories: Arc::new(memories),
+            index_stats: Arc::new(IndexShardStats::new(cue_index.shards().len())),
+            cue_hits: Arc::new(DashMap::with_hasher(RandomState::new())),
             cue_index: Arc::new(cue_index),
+            cue_keys: Arc::new(RwLock::new(cue_keys)),
             cue_co_occurrence: Arc::new(DashMap::with_hasher(RandomState::new())), 
             last_events: Arc::new(DashMap::with_hasher(RandomState::new())),
             memory_count: Arc::new(AtomicUsize::new(count)),
             cue_count: Arc::new(AtomicUsize::new(0)), // Cues will be lazy counted or we need to pass it
+            content_bytes: Arc::new(AtomicUsize::new(content_bytes)),
+            generation: Arc::new(AtomicU64::new(0)),
+            vectors: Arc::new(VectorIndex::new()),
+            history: Arc::new(MemoryHistory::default()),
+            access: Arc::new(AccessStats::default()),
+            content_index: Arc::new(ContentIndex::default()),
+            field_tokens: Arc::new(field_tokens),
             master_key: None,
             tuning: Arc::new(TuningConfig::default()),
-        };
-
-
-        // Rehydrate co-occurrence matrix from existing memories
-        // This ensures the graph and pattern completion work after restart
-        for r in engine.memories.iter() {
-            let memory = r.value();
-            engine.update_cue_co_occurrence(&memory.cues);
+            live_feed: None,
+            payload_store: None,
+            salience_decayed_at: Arc::new(AtomicU64::new(0)),
         }
-
-        engine
     }
     
     // Expose internal state for persistence
@@ -141,7 +851,153 @@ where
     pub fn get_cue_index(&self) -> &Arc<DashMap<String, OrderedSet, RandomState>> {
         &self.cue_index
     }
+
+    /// Typo tolerance: add indexed cues within edit distance of query cues that have
+    /// no exact match (`kuberentes` -> `kubernetes`). A correction gets the query cue's
+    /// weight scaled by `FUZZY_WEIGHT_DECAY` per edit. Returns the expanded cues and
+    /// the corrections made.
+    pub fn expand_fuzzy(&self, query_cues: Vec<(String, f64)>, max_distance: usize) -> (Vec<(String, f64)>, Vec<FuzzyCorrection>) {
+        // (query cue, weight, edit budget) for cues that miss the index
+        let missing: Vec<(String, f64, usize)> = query_cues
+            .iter()
+            .filter(|(cue, _)| !self.cue_index.contains_key(cue))
+            .map(|(cue, weight)| (cue.clone(), *weight, allowed_distance(cue.chars().count(), max_distance)))
+            .filter(|(_, _, budget)| *budget > 0)
+            .collect();
+        if missing.is_empty() {
+            return (query_cues, Vec::new());
+        }
+
+        // (distance, -df, cue) per missing query cue
+        let mut matches: Vec<Vec<(usize, isize, String)>> = vec![Vec::new(); missing.len()];
+        for entry in self.cue_index.iter() {
+            for (i, (query_cue, _, budget)) in missing.iter().enumerate() {
+                if let Some(distance) = osa_distance(query_cue, entry.key(), *budget) {
+                    matches[i].push((distance, -(entry.value().len() as isize), entry.key().clone()));
+                }
+            }
+        }
+
+        let mut expanded = query_cues;
+        let mut corrections = Vec::new();
+        for ((query_cue, weight, _), mut found) in missing.into_iter().zip(matches) {
+            found.sort();
+            for (distance, _, cue) in found.into_iter().take(FUZZY_MAX_CORRECTIONS) {
+                if expanded.iter().any(|(c, _)| *c == cue) {
+                    continue;
+                }
+                expanded.push((cue.clone(), weight * FUZZY_WEIGHT_DECAY.powi(distance as i32)));
+                corrections.push(FuzzyCorrection { query_cue: query_cue.clone(), cue, distance });
+            }
+        }
+        (expanded, corrections)
+    }
     
+    /// Closest indexed plain word (no `:` or space) to `word` within
+    /// `max_distance` edits: fewest edits first, then most used
+    pub fn closest_cue(&self, word: &str, max_distance: usize) -> Option<(String, usize)> {
+        let mut best: Option<(usize, isize, String)> = None;
+        for entry in self.cue_index.iter() {
+            let cue = entry.key();
+            if cue.contains(':') || cue.contains(' ') || cue.chars().count() < 3 {
+                continue;
+            }
+            if let Some(distance) = osa_distance(word, cue, max_distance) {
+                let candidate = (distance, -(entry.value().len() as isize), cue.clone());
+                let better = match &best {
+                    Some(current) => candidate < *current,
+                    None => true,
+                };
+                if better {
+                    best = Some(candidate);
+                }
+            }
+        }
+        best.map(|(distance, _, cue)| (cue, distance))
+    }
+
+    /// Indexed cues matching a prefix/wildcard pattern, in key order. `*` matches any
+    /// run of characters; a pattern without `*` is a prefix (`path:src/agent/` is
+    /// `path:src/agent/*`). The literal text before the first `*` selects a range of the
+    /// sorted keys, so `error:*timeout*` only scans `error:` cues.
+    pub fn match_cue_pattern(&self, pattern: &str, limit: usize) -> Vec<String> {
+        let pattern = pattern.to_lowercase().trim().to_string();
+        if pattern.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+        let pattern = if pattern.contains('*') { pattern } else { format!("{}*", pattern) };
+        let literal = &pattern[..pattern.find('*').unwrap_or(pattern.len())];
+
+        let keys = self.cue_keys.read().unwrap_or_else(|e| e.into_inner());
+        keys.range::<str, _>((std::ops::Bound::Included(literal), std::ops::Bound::Unbounded))
+            .take_while(|key| key.starts_with(literal))
+            .filter(|key| wildcard_match(&pattern, key))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    fn index_cue(&self, cue: String, memory_id: String) {
+        let mut created = false;
+        let shard = self.cue_index.determine_map(&cue);
+        // try_entry fails only when the shard lock is held elsewhere
+        let entry = match self.cue_index.try_entry(cue.clone()) {
+            Some(entry) => {
+                self.index_stats.record(shard, false);
+                entry
+            }
+            None => {
+                self.index_stats.record(shard, true);
+                self.cue_index.entry(cue.clone())
+            }
+        };
+        let mut set = entry.or_insert_with(|| {
+            created = true;
+            OrderedSet::new()
+        });
+        set.add(memory_id);
+        set.seal_if_longer(self.tuning.hot_cue_segment_size);
+        drop(set);
+        if created {
+            self.cue_count.fetch_add(1, Ordering::Relaxed);
+            self.cue_keys.write().unwrap_or_else(|e| e.into_inner()).insert(cue);
+        }
+    }
+
+    fn unindex_cue(&self, cue: &str, memory_id: &str) {
+        if let Some(mut entry) = self.cue_index.get_mut(cue) {
+            entry.remove(memory_id);
+            if entry.is_empty() {
+                drop(entry); // Release RefMut to allow removal
+                self.remove_empty_cue(cue);
+            }
+        }
+    }
+
+    /// Record the structural field tokens of a memory whose cues were set or changed
+    fn index_fields(&self, memory_id: &str, cues: &[String]) {
+        let fields = structural_field_tokens(cues);
+        if fields.is_empty() {
+            self.field_tokens.remove(memory_id);
+        } else {
+            self.field_tokens.insert(memory_id.to_string(), fields);
+        }
+    }
+
+    fn remove_empty_cue(&self, cue: &str) -> bool {
+        if self.cue_index.remove_if(cue, |_, set| set.is_empty()).is_none() {
+            return false;
+        }
+        self.cue_count.fetch_sub(1, Ordering::Relaxed);
+        self.cue_hits.remove(cue);
+        let mut keys = self.cue_keys.write().unwrap_or_else(|e| e.into_inner());
+        // The cue may have been re-added since it was removed
+        if !self.cue_index.contains_key(cue) {
+            keys.remove(cue);
+        }
+        true
+    }
+
     pub fn update_cue_co_occurrence(&self, cues: &[String]) {
         for i in 0..cues.len() {
             let cue_a = cues[i].to_lowercase().trim().to_string();
@@ -151,25 +1007,28 @@ where
                 let cue_b = cues[j].to_lowercase().trim().to_string();
                 if cue_b.is_empty() || cue_a == cue_b { continue; }
                 
-                // Update A -> B
-                self.cue_co_occurrence
-                    .entry(cue_a.clone())
-                    .or_insert_with(|| DashMap::with_hasher(RandomState::new()))
-                    .entry(cue_b.clone())
-                    .and_modify(|c| *c += 1)
-                    .or_insert(1);
-                
-                // Update B -> A
-                self.cue_co_occurrence
-                    .entry(cue_b.clone())
-                    .or_insert_with(|| DashMap::with_hasher(RandomState::new()))
-                    .entry(cue_a.clone())
-                    .and_modify(|c| *c += 1)
-                    .or_insert(1);
+                // Update A -> B and B -> A
+                self.bump_co_occurrence(&cue_a, &cue_b);
+                self.bump_co_occurrence(&cue_b, &cue_a);
             }
         }
     }
 
+    fn bump_co_occurrence(&self, cue: &str, neighbour: &str) {
+        let inner = self.cue_co_occurrence
+            .entry(cue.to_string())
+            .or_insert_with(|| DashMap::with_hasher(RandomState::new()));
+        if let Some(mut count) = inner.get_mut(neighbour) {
+            *count += 1;
+            return;
+        }
+        let cap = self.tuning.co_occurrence_max_neighbors;
+        if cap > 0 && inner.len() >= cap {
+            evict_co_occurrence(&inner, cap - 1);
+        }
+        inner.insert(neighbour.to_string(), 1);
+    }
+
     pub fn add_memory(
         &self,
         content: String,
@@ -177,6 +1036,33 @@ where
         metadata: Option<HashMap<String, serde_json::Value>>,
         stats: T,
         disable_temporal_chunking: bool,
+    ) -> String {
+        self.add_memory_in_group(content, cues, metadata, stats, disable_temporal_chunking, None)
+    }
+
+    /// `add_memory` with temporal chunking scoped to `group` (an agent, API key
+    /// or session) within the memory's project, so concurrent writers to one
+    /// project only chain episodes with their own earlier memories. `None`
+    /// chains per project.
+    pub fn add_memory_in_group(
+        &self,
+        content: String,
+        cues: Vec<String>,
+        metadata: Option<HashMap<String, serde_json::Value>>,
+        stats: T,
+        disable_temporal_chunking: bool,
+        group: Option<&str>,
+    ) -> String {
+        self.add_memory_chained(content, cues, metadata, stats, EpisodeChain::Temporal { group, disabled: disable_temporal_chunking })
+    }
+
+    fn add_memory_chained(
+        &self,
+        content: String,
+        cues: Vec<String>,
+        metadata: Option<HashMap<String, serde_json::Value>>,
+        stats: T,
+        episode_chain: EpisodeChain<'_>,
     ) -> String {
         // Create payload (Compressed or Encrypted)
         let payload = match Memory::<T>::create_payload(&content, self.master_key.as_deref()) {
@@ -187,7 +1073,7 @@ where
             }
         };
 
-        let mut memory = Memory::new(payload, metadata);
+        let mut memory = Memory::new(self.store_payload(payload), metadata);
         let memory_id = memory.id.clone();
         
         // Store cues in memory
@@ -197,143 +1083,620 @@ where
         // 1. Temporal Chunking
         let project_id = memory.metadata.get("project_id")
             .and_then(|v| v.as_str())
-            .unwrap_or("default")
-            .to_string();
+            .unwrap_or("default");
+        let group = match episode_chain {
+            EpisodeChain::Temporal { group, .. } => group,
+            EpisodeChain::Session(session_cue) => Some(session_cue),
+        };
+        let chain = temporal_chain_key(project_id, group);
         
-        if let Some(last_event) = self.last_events.get(&project_id) {
-            let (last_id, last_time, last_cues) = last_event.clone();
-            let now = memory.created_at;
-            
-            // Time proximity (< 5 mins) and High cue overlap (> 50%)
-            let time_diff = now - last_time;
-            let overlap = memory.cues.iter().filter(|c| last_cues.contains(c)).count();
-            let overlap_ratio = if !memory.cues.is_empty() {
-                (overlap as f64) / (memory.cues.len() as f64)
-            } else {
-                0.0
-            };
-            
-            if time_diff < 300.0 && overlap_ratio > 0.5 && !disable_temporal_chunking {
-                let episode_cue = format!("episode:{}", last_id);
-                memory.cues.push(episode_cue.clone());
+        // The entry keeps the chain's shard locked from reading the last event
+        // to replacing it, so concurrent writers to a chain can't both link to
+        // the same predecessor.
+        let last_event = self.last_events.entry(chain);
+        let previous = match (&last_event, episode_chain) {
+            (dashmap::mapref::entry::Entry::Occupied(last_event), EpisodeChain::Temporal { disabled, .. }) => {
+                let (last_id, last_time, last_cues) = last_event.get();
+                let now = memory.created_at;
+                
+                // Time proximity (< 5 mins) and High cue overlap (> 50%)
+                let time_diff = now - last_time;
+                let overlap = memory.cues.iter().filter(|c| last_cues.contains(c)).count();
+                let overlap_ratio = if !memory.cues.is_empty() {
+                    (overlap as f64) / (memory.cues.len() as f64)
+                } else {
+                    0.0
+                };
+                
+                (time_diff < 300.0 && overlap_ratio > 0.5 && !disabled).then(|| last_id.clone())
             }
+            (dashmap::mapref::entry::Entry::Vacant(_), EpisodeChain::Temporal { .. }) => None,
+            // Sessions always chain; the last event is missing after a reload
+            (last_event, EpisodeChain::Session(session_cue)) => match last_event {
+                dashmap::mapref::entry::Entry::Occupied(last_event) => Some(last_event.get().0.clone()),
+                dashmap::mapref::entry::Entry::Vacant(_) => None,
+            }
+            .filter(|id| self.memories.contains_key(id))
+            .or_else(|| self.latest_memory_with_cue(session_cue)),
+        };
+        if let Some(previous) = previous {
+            memory.cues.push(format!("episode:{}", previous));
         }
-        self.last_events.insert(project_id, (memory_id.clone(), memory.created_at, memory.cues.clone()));
+        let event = (memory_id.clone(), memory.created_at, memory.cues.clone());
+        let chain_guard = match last_event {
+            dashmap::mapref::entry::Entry::Occupied(mut last_event) => {
+                last_event.insert(event);
+                last_event.into_ref()
+            }
+            dashmap::mapref::entry::Entry::Vacant(slot) => slot.insert(event),
+        };
+        // A session's next writer checks its predecessor exists, so the chain
+        // stays locked until this memory is in the map
+        let _session_guard = match episode_chain {
+            EpisodeChain::Session(_) => Some(chain_guard),
+            EpisodeChain::Temporal { .. } => {
+                drop(chain_guard);
+                None
+            }
+        };
+        let created_at = memory.created_at;
+        self.content_bytes.fetch_add(memory.content.len(), Ordering::Relaxed);
+        self.mark_changed();
+        self.index_fields(&memory_id, &memory.cues);
         if self.memories.insert(memory_id.clone(), memory).is_none() {
             self.memory_count.fetch_add(1, Ordering::Relaxed);
         }
+        self.content_index.insert(&memory_id, content_hash(&content));
         
         // Index by cues (Double Indexing)
         for cue in &cues {
             let cue_lower = cue.to_lowercase().trim().to_string();
             if cue_lower.is_empty() { continue; }
 
-            // 1. Index full cue
-            if !self.cue_index.contains_key(&cue_lower) {
-                 self.cue_count.fetch_add(1, Ordering::Relaxed);
-            }
-            self.cue_index
-                .entry(cue_lower.clone())
-                .or_insert_with(OrderedSet::new)
-                .add(memory_id.clone());
+            // 1. Index full cue
+            self.index_cue(cue_lower.clone(), memory_id.clone());
+             
+             // 2. Index value
+             if let Some((_, value)) = cue_lower.split_once(':') {
+                 if !value.is_empty() {
+                     let val_str = value.to_string();
+                      self.index_cue(val_str, memory_id.clone());
+                 }
+             }
+
+
+        }
+
+        self.publish(|project_id| LiveEvent::MemoryAdded {
+            project_id,
+            memory_id: memory_id.clone(),
+            cues: cues.clone(),
+            created_at,
+        });
+        
+        memory_id
+    }
+    
+    /// Add a memory to an explicit episode (session). It gets a `session:{id}` cue,
+    /// `session_id` metadata and an `episode:` cue chaining it to the session's
+    /// previous memory, without the time and overlap heuristics of implicit
+    /// temporal chunking.
+    pub fn add_memory_to_session(
+        &self,
+        session_id: &str,
+        content: String,
+        mut cues: Vec<String>,
+        metadata: Option<HashMap<String, serde_json::Value>>,
+        stats: T,
+    ) -> String {
+        let session_cue = format!("session:{}", session_id);
+        cues.push(session_cue.clone());
+        let mut metadata = metadata.unwrap_or_default();
+        metadata.insert("session_id".to_string(), serde_json::json!(session_id));
+
+        self.add_memory_chained(content, cues, Some(metadata), stats, EpisodeChain::Session(&session_cue))
+    }
+
+    /// Newest memory carrying `cue`, by creation time
+    fn latest_memory_with_cue(&self, cue: &str) -> Option<String> {
+        let ids = self.cue_index.get(&cue.to_lowercase())?.get_recent_owned(None);
+        ids.into_iter()
+            .filter_map(|id| self.memories.get(&id).map(|m| (m.created_at, id)))
+            .max_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(_, id)| id)
+    }
+
+    /// Memories of a session, oldest first
+    pub fn session_timeline(&self, session_id: &str) -> Vec<Memory<T>> {
+        let ids = self.cue_index
+            .get(&format!("session:{}", session_id))
+            .map(|set| set.get_recent_owned(None))
+            .unwrap_or_default();
+        let mut memories: Vec<Memory<T>> = ids.iter().rev().filter_map(|id| self.get_memory(id)).collect();
+        memories.sort_by(|a, b| a.created_at.partial_cmp(&b.created_at).unwrap_or(std::cmp::Ordering::Equal));
+        memories
+    }
+
+    /// A recent memory that `content` and `cues` would duplicate, with the
+    /// Jaccard similarity of the two cue sets (1.0 for identical content).
+    /// Identical means equal after lowercasing and collapsing whitespace; a near
+    /// duplicate reaches `dedup_jaccard_threshold`, and its words (as a set) also
+    /// overlap the new content by `dedup_content_threshold`. Only the `dedup_window` most
+    /// recent memories of each cue are compared, and `episode:` cues are ignored
+    /// since temporal chunking adds them after the fact.
+    pub fn find_near_duplicate(&self, content: &str, cues: &[String]) -> Option<(String, f64)> {
+        let jaccard_threshold = self.tuning.dedup_jaccard_threshold;
+        let content_threshold = self.tuning.dedup_content_threshold;
+        let window = self.tuning.dedup_window;
+        let new_cues = dedup_cue_set(cues);
+        let fingerprint = content_fingerprint(content);
+        let new_words = content_word_set(content);
+        let mut seen = HashSet::new();
+        let mut best: Option<(String, f64)> = None;
+
+        for cue in &new_cues {
+            let Some(recent) = self.cue_index.get(cue).map(|set| set.get_recent_owned(Some(window))) else {
+                continue;
+            };
+            for id in recent {
+                if !seen.insert(id.clone()) {
+                    continue;
+                }
+                let Some(memory) = self.memories.get(&id) else { continue };
+                let Ok(existing_content) = memory.access_content(self.master_key.as_deref()) else {
+                    continue;
+                };
+                if content_fingerprint(&existing_content) == fingerprint {
+                    return Some((id, 1.0));
+                }
+
+                let existing = dedup_cue_set(&memory.cues);
+                let union = new_cues.union(&existing).count();
+                if union == 0 {
+                    continue;
+                }
+                let similarity = new_cues.intersection(&existing).count() as f64 / union as f64;
+                let better = match &best {
+                    Some((_, s)) => similarity > *s,
+                    None => true,
+                };
+                if similarity < jaccard_threshold || !better {
+                    continue;
+                }
+                // Shared cues alone don't make a duplicate (tagged-alike memories
+                // with different text), so the words have to overlap too
+                let existing_words = content_word_set(&existing_content);
+                let words_union = new_words.union(&existing_words).count();
+                let word_similarity = match words_union {
+                    0 => 1.0,
+                    n => new_words.intersection(&existing_words).count() as f64 / n as f64,
+                };
+                if word_similarity >= content_threshold {
+                    best = Some((id, similarity));
+                }
+            }
+        }
+        best
+    }
+
+    pub fn reinforce_memory(&self, memory_id: &str, cues: Vec<String>) -> bool {
+        if let Some(mut memory) = self.memories.get_mut(memory_id) {
+            self.mark_changed();
+            memory.touch();
+            memory.stats.manual_boost(); // Manual reinforcement boost
+        } else {
+            return false;
+        }
+        
+        // Update co-occurrence matrix with cues used for reinforcement
+        self.update_cue_co_occurrence(&cues);
+
+        // Move to front for each cue (Double Indexing)
+        for cue in cues {
+            let cue_lower = cue.to_lowercase().trim().to_string();
+            if cue_lower.is_empty() { continue; }
+
+            // 1. Move full cue
+            if let Some(mut entry) = self.cue_index.get_mut(&cue_lower) {
+                entry.move_to_front(memory_id);
+            }
+            
+            // 2. Move value
+            if let Some((_, value)) = cue_lower.split_once(':') {
+                if !value.is_empty() {
+                    if let Some(mut entry) = self.cue_index.get_mut(value) {
+                        entry.move_to_front(memory_id);
+                    }
+                }
+            }
+        }
+
+        self.publish_reinforced(memory_id);
+        
+        true
+    }
+
+    /// Pin or unpin a memory. Returns false if the memory does not exist.
+    pub fn set_pinned(&self, memory_id: &str, pinned: bool) -> bool {
+        let Some(mut memory) = self.memories.get_mut(memory_id) else {
+            return false;
+        };
+        if pinned {
+            memory.metadata.insert(PINNED_METADATA_KEY.to_string(), serde_json::json!(true));
+        } else {
+            memory.metadata.remove(PINNED_METADATA_KEY);
+        }
+        drop(memory);
+        self.mark_changed();
+        true
+    }
+
+    /// Record where a memory was ingested from. Returns false if the memory does not exist.
+    pub fn set_provenance(&self, memory_id: &str, provenance: &Provenance) -> bool {
+        let Some(mut memory) = self.memories.get_mut(memory_id) else {
+            return false;
+        };
+        memory.metadata.insert(PROVENANCE_METADATA_KEY.to_string(), serde_json::json!(provenance));
+        drop(memory);
+        self.mark_changed();
+        true
+    }
+
+    /// Add a typed link from one memory to another. Returns false if it already exists.
+    pub fn add_link(&self, source_id: &str, kind: &str, target_id: &str) -> Result<bool, String> {
+        let kind = kind.trim().to_lowercase();
+        if kind.is_empty() || kind.len() > 64 || !kind.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("Invalid link type '{}' (use letters, digits and '_')", kind));
+        }
+        if source_id == target_id {
+            return Err("A memory cannot link to itself".to_string());
+        }
+        if !self.memories.contains_key(target_id) {
+            return Err(format!("Memory '{}' not found", target_id));
+        }
+        let mut memory = self.memories
+            .get_mut(source_id)
+            .ok_or_else(|| format!("Memory '{}' not found", source_id))?;
+
+        let link = MemoryLink { kind, target: target_id.to_string() };
+        if memory.links.contains(&link) {
+            return Ok(false);
+        }
+        memory.links.push(link);
+        drop(memory);
+        self.mark_changed();
+        Ok(true)
+    }
+
+    /// Remove links from `source_id` to `target_id` (of one type, or all types)
+    pub fn remove_link(&self, source_id: &str, kind: Option<&str>, target_id: &str) -> bool {
+        let Some(mut memory) = self.memories.get_mut(source_id) else {
+            return false;
+        };
+        let before = memory.links.len();
+        memory.links.retain(|l| !(l.target == target_id && kind.map(|k| l.kind == k).unwrap_or(true)));
+        if memory.links.len() == before {
+            return false;
+        }
+        drop(memory);
+        self.mark_changed();
+        true
+    }
+
+    /// Outgoing links of a memory whose target still exists
+    pub fn links(&self, memory_id: &str) -> Vec<MemoryLink> {
+        let links = self.memories.get(memory_id).map(|m| m.links.clone()).unwrap_or_default();
+        links.into_iter().filter(|l| self.memories.contains_key(&l.target)).collect()
+    }
+
+    /// Links pointing at a memory, as (source id, type). Scans every memory.
+    pub fn backlinks(&self, memory_id: &str) -> Vec<(String, String)> {
+        let mut backlinks = Vec::new();
+        for entry in self.memories.iter() {
+            for link in &entry.value().links {
+                if link.target == memory_id {
+                    backlinks.push((entry.key().clone(), link.kind.clone()));
+                }
+            }
+        }
+        backlinks
+    }
+
+    /// Recall expansion: add memories linked from `results` (of the given
+    /// types, or any type when `kinds` is empty) that pass `filter`. A linked
+    /// memory scores `LINK_SCORE_DECAY` times the result it was reached from and
+    /// carries `linked_from` and `link_type` in its metadata. Results are then
+    /// sorted by score and cut to `limit` again, so links only displace weaker results.
+    pub fn follow_links(&self, results: &mut Vec<RecallResult>, kinds: &[String], limit: usize, filter: &RecallFilter) {
+        let mut seen: HashSet<String> = results.iter().map(|r| r.memory_id.clone()).collect();
+        let mut linked = Vec::new();
+
+        for result in results.iter() {
+            for link in self.links(&result.memory_id) {
+                if !kinds.is_empty() && !kinds.contains(&link.kind) {
+                    continue;
+                }
+                if seen.contains(&link.target) {
+                    continue;
+                }
+                let Some(memory) = self.memories.get(&link.target) else { continue };
+                if !filter.matches(memory.value()) {
+                    continue;
+                }
+                let content = memory.access_content(self.master_key.as_deref()).unwrap_or_else(|_| "<decryption failed>".to_string());
+                if !filter.matches_phrase(&content) {
+                    continue;
+                }
+                seen.insert(link.target.clone());
+                let mut metadata = memory.metadata.clone();
+                metadata.insert("linked_from".to_string(), serde_json::json!(result.memory_id));
+                metadata.insert("link_type".to_string(), serde_json::json!(link.kind));
+                linked.push(RecallResult {
+                    memory_id: link.target.clone(),
+                    content,
+                    score: result.score * LINK_SCORE_DECAY,
+                    match_integrity: 0.0,
+                    intersection_count: 0,
+                    recency_score: 0.0,
+                    reinforcement_score: memory.stats.get_reinforcement_count() as f64,
+                    salience_score: memory.stats.get_salience(),
+                    created_at: memory.created_at,
+                    metadata,
+                    explain: None,
+                });
+            }
+        }
+        results.extend(linked);
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        results.truncate(limit);
+    }
+
+    /// Outgoing links of every memory that has any, for the trailing links
+    /// section of snapshots and archives
+    pub fn links_snapshot(&self) -> HashMap<String, Vec<MemoryLink>> {
+        self.memories
+            .iter()
+            .filter(|m| !m.links.is_empty())
+            .map(|m| (m.key().clone(), m.links.clone()))
+            .collect()
+    }
 
-            if !self.cue_index.contains_key(&cue_lower) {
-                 self.cue_count.fetch_add(1, Ordering::Relaxed);
+    /// Put back links saved by `links_snapshot`
+    pub fn restore_links(&self, links: HashMap<String, Vec<MemoryLink>>) {
+        for (id, links) in links {
+            if let Some(mut memory) = self.memories.get_mut(&id) {
+                memory.links = links;
             }
-             self.cue_index.entry(cue_lower.clone()).or_insert_with(OrderedSet::new).add(memory_id.clone());
-             
-             // 2. Index value
-             if let Some((_, value)) = cue_lower.split_once(':') {
-                 if !value.is_empty() {
-                     let val_str = value.to_string();
-                      if !self.cue_index.contains_key(&val_str) {
-                         self.cue_count.fetch_add(1, Ordering::Relaxed);
-                      }
-                      self.cue_index.entry(val_str).or_insert_with(OrderedSet::new).add(memory_id.clone());
-                 }
-             }
-
+        }
+    }
 
+    /// Move links that legacy snapshots kept in memory metadata to `Memory::links`
+    pub fn migrate_legacy_links(&self) {
+        for mut memory in self.memories.iter_mut() {
+            memory.take_legacy_links();
         }
-        
-        memory_id
     }
-    
-    pub fn reinforce_memory(&self, memory_id: &str, cues: Vec<String>) -> bool {
-        if let Some(mut memory) = self.memories.get_mut(memory_id) {
-            memory.touch();
-            memory.stats.manual_boost(); // Manual reinforcement boost
+
+    /// Value of the memory's first `{namespace}:` cue (e.g. its `path`)
+    pub fn cue_value(&self, memory_id: &str, namespace: &str) -> Option<String> {
+        let memory = self.memories.get(memory_id)?;
+        memory.cues.iter().find_map(|cue| cue_in_namespace(cue, namespace)).map(str::to_string)
+    }
+
+    /// Ids of the memories carrying at least `min_intersection` of `cues` (every
+    /// memory without cues) that pass `filter`, oldest first, read from the cue
+    /// index without scoring. The phrase constraint needs the content and is
+    /// left to the caller.
+    pub fn matching_memory_ids(&self, cues: &[String], min_intersection: usize, filter: &RecallFilter) -> Vec<String> {
+        let candidates: Vec<String> = if cues.is_empty() {
+            self.memories.iter().map(|m| m.key().clone()).collect()
         } else {
-            return false;
-        }
-        
-        // Update co-occurrence matrix with cues used for reinforcement
-        self.update_cue_co_occurrence(&cues);
+            let unique: HashSet<&String> = cues.iter().collect();
+            let mut hits: HashMap<String, usize> = HashMap::new();
+            for cue in unique {
+                if let Some(list) = self.cue_index.get(cue) {
+                    for id in list.iter() {
+                        *hits.entry(id.clone()).or_insert(0) += 1;
+                    }
+                }
+            }
+            let min = min_intersection.max(1);
+            hits.into_iter().filter(|(_, n)| *n >= min).map(|(id, _)| id).collect()
+        };
 
-        // Move to front for each cue (Double Indexing)
-        for cue in cues {
-            let cue_lower = cue.to_lowercase().trim().to_string();
-            if cue_lower.is_empty() { continue; }
+        let mut matching: Vec<(f64, String)> = candidates
+            .into_iter()
+            .filter_map(|id| {
+                let memory = self.memories.get(&id)?;
+                filter.matches(memory.value()).then_some((memory.created_at, id))
+            })
+            .collect();
+        matching.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+        matching.into_iter().map(|(_, id)| id).collect()
+    }
 
-            // 1. Move full cue
-            if let Some(mut entry) = self.cue_index.get_mut(&cue_lower) {
-                entry.move_to_front(memory_id);
+    /// Count the memories carrying at least `min_intersection` of `cues` (every
+    /// memory without cues) that pass `filter`, optionally per value of the
+    /// `by_cue` namespace (the `bucket_limit` largest) and per day created.
+    /// Memories are counted from the cue index without being scored.
+    pub fn count_matching(
+        &self,
+        cues: &[String],
+        min_intersection: usize,
+        filter: &RecallFilter,
+        by_cue: Option<&str>,
+        by_day: bool,
+        bucket_limit: usize,
+    ) -> CountReport {
+        let phrase = filter.phrase_words();
+        let mut report = CountReport::default();
+        let mut values: HashMap<String, usize> = HashMap::new();
+        let mut days: BTreeMap<i64, usize> = BTreeMap::new();
+        for id in self.matching_memory_ids(cues, min_intersection, filter) {
+            let Some(memory) = self.memories.get(&id) else { continue };
+            if !phrase.is_empty()
+                && !memory
+                    .access_content(self.master_key.as_deref())
+                    .is_ok_and(|content| filter.matches_phrase_words(&phrase, &content))
+            {
+                continue;
             }
-            
-            // 2. Move value
-            if let Some((_, value)) = cue_lower.split_once(':') {
-                if !value.is_empty() {
-                    if let Some(mut entry) = self.cue_index.get_mut(value) {
-                        entry.move_to_front(memory_id);
-                    }
+            report.count += 1;
+            if let Some(namespace) = by_cue {
+                let distinct: HashSet<&str> = memory.cues.iter().filter_map(|c| cue_in_namespace(c, namespace)).collect();
+                for value in distinct {
+                    *values.entry(value.to_string()).or_insert(0) += 1;
                 }
             }
+            if by_day {
+                *days.entry((memory.created_at / 86_400.0).floor() as i64).or_insert(0) += 1;
+            }
         }
-        
-        true
+
+        if by_cue.is_some() {
+            let mut buckets: Vec<CountBucket> = values.into_iter().map(|(key, count)| CountBucket { key, count }).collect();
+            buckets.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)));
+            buckets.truncate(bucket_limit);
+            report.by_cue = Some(buckets);
+        }
+        if by_day {
+            report.by_day = Some(days.into_iter().map(|(day, count)| CountBucket { key: day_key(day), count }).collect());
+        }
+        report
     }
 
     pub fn delete_memory(&self, memory_id: &str) -> bool {
         if let Some((_, memory)) = self.memories.remove(memory_id) {
+             self.mark_changed();
+             self.vectors.remove(memory_id);
+             self.history.remove(memory_id);
+             self.access.remove(memory_id);
+             self.content_index.remove(memory_id);
+             self.field_tokens.remove(memory_id);
              self.memory_count.fetch_sub(1, Ordering::Relaxed);
+             self.content_bytes.fetch_sub(memory.content.len(), Ordering::Relaxed);
              // Remove from cue index (Double Indexing)
              for cue in memory.cues {
                  let cue_lower = cue.to_lowercase().trim().to_string();
                  if cue_lower.is_empty() { continue; }
                  
                  // 1. Remove from full cue entry
-                 if let Some(mut entry) = self.cue_index.get_mut(&cue_lower) {
-                     entry.remove(memory_id);
-                     if entry.is_empty() {
-                         drop(entry); // Release RefMut to allow removal
-                         if self.cue_index.remove(&cue_lower).is_some() {
-                             self.cue_count.fetch_sub(1, Ordering::Relaxed);
-                         }
-                     }
-                 }
+                 self.unindex_cue(&cue_lower, memory_id);
                  
                  // 2. Remove from value entry
                  if let Some((_, value)) = cue_lower.split_once(':') {
                      if !value.is_empty() {
-                         if let Some(mut entry) = self.cue_index.get_mut(value) {
-                             entry.remove(memory_id);
-                             if entry.is_empty() {
-                                 drop(entry);
-                                 if self.cue_index.remove(value).is_some() {
-                                     self.cue_count.fetch_sub(1, Ordering::Relaxed);
-                                 }
-                             }
-                         }
+                         self.unindex_cue(value, memory_id);
                      }
                  }
 
              }
+            self.publish(|project_id| LiveEvent::MemoryDeleted {
+                project_id,
+                memory_id: memory_id.to_string(),
+            });
             true
         } else {
             false
         }
     }
 
+    /// Move a memory to a new id, keeping its content, cues, metadata, stats,
+    /// history, embedding and its position in every cue list. Links from
+    /// other memories are not rewritten. Returns false if `from` doesn't
+    /// exist or `to` already does.
+    pub fn rename_memory(&self, from: &str, to: &str) -> bool {
+        if !self.move_memory(from, to) {
+            return false;
+        }
+        let Some(memory) = self.memories.get(to) else {
+            return false;
+        };
+        let (cues, created_at) = (memory.cues.clone(), memory.created_at);
+        drop(memory);
+        self.publish(|project_id| LiveEvent::MemoryDeleted {
+            project_id,
+            memory_id: from.to_string(),
+        });
+        self.publish(|project_id| LiveEvent::MemoryAdded {
+            project_id,
+            memory_id: to.to_string(),
+            cues,
+            created_at,
+        });
+        true
+    }
+
+    /// Apply several renames as one step, so chains and swaps (`a -> b`,
+    /// `b -> a`) work. A target that exists and is not itself moved away is
+    /// replaced. Returns the number of memories moved.
+    pub fn rename_memories(&self, moves: &[(String, String)]) -> usize {
+        // Stage every source under a temporary id first
+        let staged: Vec<(String, String, String)> = moves
+            .iter()
+            .filter_map(|(from, to)| {
+                let temp = format!("{}\u{0}moving", from);
+                self.move_memory(from, &temp).then(|| (from.clone(), temp, to.clone()))
+            })
+            .collect();
+
+        let mut moved = 0;
+        for (from, temp, to) in staged {
+            if self.memories.contains_key(&to) {
+                self.delete_memory(&to);
+            }
+            if self.move_memory(&temp, &to) {
+                moved += 1;
+                if let Some(memory) = self.memories.get(&to) {
+                    let (cues, created_at) = (memory.cues.clone(), memory.created_at);
+                    drop(memory);
+                    self.publish(|project_id| LiveEvent::MemoryDeleted { project_id, memory_id: from });
+                    self.publish(|project_id| LiveEvent::MemoryAdded { project_id, memory_id: to, cues, created_at });
+                }
+            } else {
+                // Can't happen after the delete above; keep the memory under its old id
+                self.move_memory(&temp, &from);
+            }
+        }
+        moved
+    }
+
+    /// `rename_memory` without live events
+    fn move_memory(&self, from: &str, to: &str) -> bool {
+        if from == to || self.memories.contains_key(to) {
+            return false;
+        }
+        let Some((_, mut memory)) = self.memories.remove(from) else {
+            return false;
+        };
+        self.mark_changed();
+
+        // Same cues, so only the ids inside their lists change (Double Indexing)
+        for cue in &memory.cues {
+            let cue_lower = cue.to_lowercase().trim().to_string();
+            if cue_lower.is_empty() { continue; }
+            if let Some(mut entry) = self.cue_index.get_mut(&cue_lower) {
+                entry.rename(from, to);
+            }
+            if let Some((_, value)) = cue_lower.split_once(':') {
+                if let Some(mut entry) = self.cue_index.get_mut(value) {
+                    entry.rename(from, to);
+                }
+            }
+        }
+
+        memory.id = to.to_string();
+        self.memories.insert(to.to_string(), memory);
+        self.vectors.rename(from, to);
+        self.history.rename(from, to);
+        self.access.rename(from, to);
+        self.content_index.rename(from, to);
+        if let Some((_, fields)) = self.field_tokens.remove(from) {
+            self.field_tokens.insert(to.to_string(), fields);
+        }
+        true
+    }
+
     pub fn get_cue_frequency(&self, cue: &str) -> usize {
         let cue_lower = cue.to_lowercase();
         let cue_trimmed = cue_lower.trim();
@@ -344,6 +1707,26 @@ where
         }
     }
     
+    /// Write counter, incremented by every mutation (not persisted)
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    fn mark_changed(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Bytes of stored memory payloads (after compression/encryption)
+    pub fn stored_bytes(&self) -> usize {
+        self.content_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Distinct indexed cues (read from the index itself; `cue_count` is not
+    /// rebuilt when an engine is restored from a snapshot)
+    pub fn total_cues(&self) -> usize {
+        self.cue_index.len()
+    }
+
     pub fn total_memories(&self) -> usize {
         self.memory_count.load(Ordering::Relaxed)
     }
@@ -358,12 +1741,33 @@ where
         reinforce: bool,
         overwrite_cues: bool,
     ) -> String {
+        self.mark_changed();
         if self.memories.contains_key(&id) {
             {
                 if let Some(mut memory) = self.memories.get_mut(&id) {
+                    // Keep the state being replaced, if it actually changes
+                    if self.history.is_enabled() {
+                        let changed = memory.access_content(self.master_key.as_deref()).map(|old| old != content).unwrap_or(true)
+                            || (overwrite_cues && memory.cues != cues);
+                        if changed {
+                            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs_f64();
+                            match memory.content.bytes() {
+                                Ok(old) => {
+                                    self.history.record(&id, old.into_owned(), memory.cues.clone(), memory.metadata.clone(), now);
+                                }
+                                Err(e) => tracing::error!("Failed to keep version of {}: {}", id, e),
+                            }
+                        }
+                    }
+
                     // Update content ALWAYS
                     match Memory::<T>::create_payload(&content, self.master_key.as_deref()) {
-                        Ok(p) => memory.content = p,
+                        Ok(p) => {
+                            self.content_bytes.fetch_add(p.len(), Ordering::Relaxed);
+                            self.content_bytes.fetch_sub(memory.content.len(), Ordering::Relaxed);
+                            memory.content = self.store_payload(p);
+                            self.content_index.insert(&id, content_hash(&content));
+                        }
                         Err(e) => tracing::error!("Failed to update content: {}", e),
                     }
                     
@@ -389,6 +1793,7 @@ where
                 if let Some(mut mem) = self.memories.get_mut(&id) {
                     mem.cues = Vec::new(); // Clear
                 }
+                self.index_fields(&id, &[]);
                 // Now attach new cues (effectively replacing)
                 self.attach_cues(&id, cues.clone());
             } else {
@@ -402,7 +1807,20 @@ where
             return id;
         }
         
-        // Insert new
+        self.insert_memory(id, content, cues, metadata, stats, true)
+    }
+
+    /// Insert a memory under a new id. Imports skip `update_co_occurrence`, as
+    /// `add_memory` does, so an imported project completes patterns like its source.
+    fn insert_memory(
+        &self,
+        id: String,
+        content: String,
+        cues: Vec<String>,
+        metadata: Option<HashMap<String, serde_json::Value>>,
+        stats: Option<T>,
+        update_co_occurrence: bool,
+    ) -> String {
         let payload = match Memory::<T>::create_payload(&content, self.master_key.as_deref()) {
             Ok(p) => p,
             Err(e) => {
@@ -411,16 +1829,20 @@ where
             }
         };
 
-        let mut memory = Memory::new(payload, metadata);
+        let mut memory = Memory::new(self.store_payload(payload), metadata);
         memory.id = id.clone();
         memory.cues = cues.clone();
         if let Some(s) = stats {
             memory.stats = s;
         }
+        let created_at = memory.created_at;
+        self.content_bytes.fetch_add(memory.content.len(), Ordering::Relaxed);
+        self.index_fields(&id, &memory.cues);
         
         if self.memories.insert(id.clone(), memory).is_none() {
             self.memory_count.fetch_add(1, Ordering::Relaxed);
         }
+        self.content_index.insert(&id, content_hash(&content));
         
         // Index by cues (Double Indexing)
         for cue in &cues { // Iterate by reference to avoid move
@@ -429,30 +1851,27 @@ where
             
             // 1. Index full cue
             let cue_lower_clone = cue_lower.clone();
-            if !self.cue_index.contains_key(&cue_lower_clone) {
-                 self.cue_count.fetch_add(1, Ordering::Relaxed);
-            }
-            self.cue_index
-                .entry(cue_lower_clone)
-                .or_insert_with(OrderedSet::new)
-                .add(id.clone());
+            self.index_cue(cue_lower_clone, id.clone());
             
             // 2. Index value
             if let Some((_, value)) = cue_lower.split_once(':') {
                 if !value.is_empty() {
                     let val_str = value.to_string();
-                    if !self.cue_index.contains_key(&val_str) {
-                         self.cue_count.fetch_add(1, Ordering::Relaxed);
-                    }
-                    self.cue_index
-                        .entry(val_str)
-                        .or_insert_with(OrderedSet::new)
-                        .add(id.clone());
+                    self.index_cue(val_str, id.clone());
                 }
             }
 
         }
-        self.update_cue_co_occurrence(&cues);
+        if update_co_occurrence {
+            self.update_cue_co_occurrence(&cues);
+        }
+
+        self.publish(|project_id| LiveEvent::MemoryAdded {
+            project_id,
+            memory_id: id.clone(),
+            cues: cues.clone(),
+            created_at,
+        });
         
         id
     }
@@ -460,6 +1879,7 @@ where
     pub fn attach_cues(&self, memory_id: &str, cues: Vec<String>) -> bool {
         // 1. Get memory and check if it exists
         if let Some(mut memory) = self.memories.get_mut(memory_id) {
+            self.mark_changed();
             // 2. Identify new cues (deduplication)
             let mut new_cues = Vec::new();
             for cue in cues {
@@ -479,32 +1899,21 @@ where
 
             // 3. Update memory.cues
             memory.cues.extend(new_cues.clone());
+            self.index_fields(memory_id, &memory.cues);
 
             // 4. Update index for new cues (Double Indexing)
-            for cue in new_cues {
+            for cue in &new_cues {
                 let cue_lower = cue.to_lowercase().trim().to_string();
                 
                 // 1. Index full cue
                 let cue_lower_clone = cue_lower.clone();
-                if !self.cue_index.contains_key(&cue_lower_clone) {
-                     self.cue_count.fetch_add(1, Ordering::Relaxed);
-                }
-                self.cue_index
-                    .entry(cue_lower_clone)
-                    .or_insert_with(OrderedSet::new)
-                    .add(memory_id.to_string());
+                self.index_cue(cue_lower_clone, memory_id.to_string());
                 
                 // 2. Index value
                 if let Some((_, value)) = cue_lower.split_once(':') {
                     if !value.is_empty() {
                         let val_str = value.to_string();
-                         if !self.cue_index.contains_key(&val_str) {
-                             self.cue_count.fetch_add(1, Ordering::Relaxed);
-                         }
-                        self.cue_index
-                            .entry(val_str)
-                            .or_insert_with(OrderedSet::new)
-                            .add(memory_id.to_string());
+                         self.index_cue(val_str, memory_id.to_string());
                     }
                 }
 
@@ -513,6 +1922,12 @@ where
             let all_cues = memory.cues.clone();
             drop(memory); 
             self.update_cue_co_occurrence(&all_cues);
+
+            self.publish(|project_id| LiveEvent::CuesAttached {
+                project_id,
+                memory_id: memory_id.to_string(),
+                cues: new_cues,
+            });
             
             return true;
         } else {
@@ -520,34 +1935,58 @@ where
         }
     }
     
+    /// Remove cues from a memory and from the index (matched case-insensitively).
+    /// Returns the cues that were removed.
+    pub fn detach_cues(&self, memory_id: &str, cues: &[String]) -> Vec<String> {
+        let wanted: HashSet<String> = cues
+            .iter()
+            .map(|c| c.to_lowercase().trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect();
+        let Some(mut memory) = self.memories.get_mut(memory_id) else {
+            return Vec::new();
+        };
+        let (removed, kept): (Vec<String>, Vec<String>) = memory.cues
+            .iter()
+            .cloned()
+            .partition(|c| wanted.contains(c.to_lowercase().trim()));
+        if removed.is_empty() {
+            return removed;
+        }
+        self.mark_changed();
+        memory.cues = kept;
+        self.index_fields(memory_id, &memory.cues);
+
+        // A value entry stays while another cue still maps to it ("payments" and "service:payments")
+        let still_indexed: HashSet<String> = memory.cues.iter().flat_map(|c| cue_index_keys(c)).collect();
+        drop(memory);
+        for key in removed.iter().flat_map(|c| cue_index_keys(c)) {
+            if !still_indexed.contains(&key) {
+                self.unindex_cue(&key, memory_id);
+            }
+        }
+
+        self.publish(|project_id| LiveEvent::CuesDetached {
+            project_id,
+            memory_id: memory_id.to_string(),
+            cues: removed.clone(),
+        });
+        removed
+    }
+
     pub fn remove_cues_from_index(&self, memory_id: &str, cues: &[String]) {
+        self.mark_changed();
         for cue in cues {
              let cue_lower = cue.to_lowercase().trim().to_string();
              if cue_lower.is_empty() { continue; }
              
              // 1. Remove from full cue entry
-             if let Some(mut entry) = self.cue_index.get_mut(&cue_lower) {
-                 entry.remove(memory_id);
-                 if entry.is_empty() {
-                     drop(entry); 
-                     if self.cue_index.remove(&cue_lower).is_some() {
-                         self.cue_count.fetch_sub(1, Ordering::Relaxed);
-                     }
-                 }
-             }
+             self.unindex_cue(&cue_lower, memory_id);
              
              // 2. Remove from value entry
              if let Some((_, value)) = cue_lower.split_once(':') {
                  if !value.is_empty() {
-                     if let Some(mut entry) = self.cue_index.get_mut(value) {
-                         entry.remove(memory_id);
-                         if entry.is_empty() {
-                             drop(entry);
-                             if self.cue_index.remove(value).is_some() {
-                                 self.cue_count.fetch_sub(1, Ordering::Relaxed);
-                             }
-                         }
-                     }
+                     self.unindex_cue(value, memory_id);
                  }
              }
          }
@@ -581,7 +2020,7 @@ where
             .map(|c| (c, 1.0))
             .collect();
             
-        self.recall_weighted(weighted_cues, limit, auto_reinforce, min_intersection, false, false, false, false, heatmap)
+        self.recall_weighted(weighted_cues, limit, &RecallParams { auto_reinforce, min_intersection, heatmap, ..RecallParams::default() })
     }
 
     /// O(limit) recall using intersection-first strategy.
@@ -728,19 +2167,29 @@ where
         candidates.into_iter().take(limit).collect()
     }
 
-    pub fn recall_weighted(
+    pub fn recall_weighted(&self, query_cues: Vec<(String, f64)>, limit: usize, params: &RecallParams<'_>) -> Vec<RecallResult> {
+        self.recall_weighted_inner(query_cues, limit, params, None)
+    }
+
+    /// Same as `recall_weighted` (with explain enabled), but also returns the score
+    /// trace of every candidate considered, including the ones that were pruned.
+    pub fn recall_weighted_traced(&self, query_cues: Vec<(String, f64)>, limit: usize, params: &RecallParams<'_>) -> (Vec<RecallResult>, RecallTrace) {
+        let mut trace = RecallTrace::default();
+        let params = RecallParams { explain: true, ..*params };
+        let results = self.recall_weighted_inner(query_cues, limit, &params, Some(&mut trace));
+        (results, trace)
+    }
+
+    fn recall_weighted_inner(
         &self,
         query_cues: Vec<(String, f64)>,
         limit: usize,
-        auto_reinforce: bool,
-        min_intersection: Option<usize>,
-        explain: bool,
-        disable_pattern_completion: bool,
-        disable_salience_bias: bool,
-        disable_systems_consolidation: bool,
-        heatmap: Option<&HashMap<String, f32>>,
+        params: &RecallParams<'_>,
+        mut trace: Option<&mut RecallTrace>,
     ) -> Vec<RecallResult> {
-        if query_cues.is_empty() {
+        let RecallParams { auto_reinforce, min_intersection, explain, disable_pattern_completion, disable_systems_consolidation, filter, hybrid, .. } = *params;
+        // In hybrid mode a query vector alone is enough
+        if query_cues.is_empty() && hybrid.is_none() {
             return Vec::new();
         }
         
@@ -750,8 +2199,14 @@ where
             .map(|(c, w)| (c.to_lowercase().trim().to_string(), *w))
             .filter(|(c, _)| !c.is_empty() && self.cue_index.contains_key(c))
             .collect();
+
+        // Excluded cues never contribute weight, even if they were also asked for
+        let scan_limit = filter.and_then(|f| f.scan_limit);
+        let filter = filter.filter(|f| !f.is_empty());
+        let excluded: &[String] = filter.map(|f| f.exclude_cues.as_slice()).unwrap_or(&[]);
+        active_cues.retain(|(c, _)| !excluded.contains(c));
         
-        if active_cues.is_empty() {
+        if active_cues.is_empty() && hybrid.is_none() {
             return Vec::new();
         }
 
@@ -764,7 +2219,7 @@ where
                 for entry in co_map.iter() {
                     let (inferred_cue, count) = entry.pair();
                     // Skip if already in query
-                    if active_cues.iter().any(|(c, _)| c == inferred_cue) {
+                    if active_cues.iter().any(|(c, _)| c == inferred_cue) || excluded.contains(inferred_cue) {
                         continue;
                     }
                     
@@ -795,12 +2250,39 @@ where
             // Even with high IDF, an inferred cue should be a tie-breaker, not a driver.
             let pattern_completion_weight = 0.1; 
             for (inf_cue, _) in inferred_list.into_iter().take(5) {
+                if let Some(t) = trace.as_deref_mut() {
+                    t.pattern_completion_cues.push(inf_cue.clone());
+                }
                 active_cues.push((inf_cue, pattern_completion_weight));
             }
         }
+
+        if let Some(t) = trace.as_deref_mut() {
+            t.active_cues = active_cues.clone();
+        }
         
+        // A phrase constraint drops candidates after scoring, so score a wider pool
+        let phrase = filter.map(RecallFilter::phrase_words).unwrap_or_default();
+        let has_phrase = !phrase.is_empty();
+        let search_limit = if has_phrase { limit.saturating_mul(PHRASE_CANDIDATE_FACTOR) } else { limit };
+
+        // Only the best `search_limit` candidates can be returned unless a later
+        // step rescores or drops candidates, or every candidate must be seen
+        // (trace)
+        let top_k = (hybrid.is_none() && min_intersection.is_none() && trace.is_none())
+            .then_some(search_limit);
+
         // 2. Consolidated search using Selective Set Intersection
-        let mut results = self.consolidated_search(&active_cues, limit, explain, disable_salience_bias, disable_systems_consolidation, heatmap);
+        let search_span = tracing::debug_span!("search", cues = active_cues.len(), limit = search_limit).entered();
+        let mut results = self.consolidated_search(&active_cues, search_limit, top_k, scan_limit, &RecallParams { filter, ..*params }, trace.as_deref_mut());
+
+        // 2b. Hybrid: blend in cosine similarity (and nearest-vector candidates)
+        let similarities = match hybrid {
+            Some(h) => self.blend_vector_scores(&mut results, h, search_limit, filter, disable_systems_consolidation),
+            None => HashMap::new(),
+        };
+        drop(search_span);
+        let _score_span = tracing::debug_span!("score", candidates = results.len()).entered();
         
         // Filter by minimum intersection if specified (on primary cues only?)
         // For now, simple retention.
@@ -808,14 +2290,6 @@ where
             results.retain(|r| r.intersection_count >= min_int);
         }
         
-        // 3. Auto-reinforce if enabled (only primary cues)
-        if auto_reinforce {
-            let primary_cues: Vec<String> = query_cues.iter().map(|(c, _)| c.clone()).collect();
-            for result in &results {
-                self.reinforce_memory(&result.memory_id, primary_cues.clone());
-            }
-        }
-
         // Global sort by score
         results.sort_unstable_by(|a, b| {
             b.score
@@ -823,7 +2297,56 @@ where
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
+        // 2c. Phrase constraint, checked on decrypted content best-first until `limit` pass
+        let mut phrase_mismatches: HashSet<String> = HashSet::new();
+        if let Some(f) = filter.filter(|_| has_phrase) {
+            let mut passed = 0;
+            results.retain(|r| {
+                if passed >= limit {
+                    return false;
+                }
+                let matched = self.memories
+                    .get(&r.memory_id)
+                    .and_then(|m| m.access_content(self.master_key.as_deref()).ok())
+                    .is_some_and(|content| f.matches_phrase_words(&phrase, &content));
+                if matched {
+                    passed += 1;
+                } else {
+                    phrase_mismatches.insert(r.memory_id.clone());
+                }
+                matched
+            });
+        }
+
         results.truncate(limit);
+
+        // 3. Auto-reinforce the returned memories if enabled (only primary cues)
+        if auto_reinforce {
+            let primary_cues: Vec<String> = query_cues.iter().map(|(c, _)| c.clone()).collect();
+            for result in &results {
+                self.reinforce_memory(&result.memory_id, primary_cues.clone());
+            }
+        }
+
+        if let Some(t) = trace {
+            let kept: HashSet<&str> = results.iter().map(|r| r.memory_id.as_str()).collect();
+            for c in t.candidates.iter_mut().filter(|c| c.excluded_reason.is_none()) {
+                if kept.contains(c.memory_id.as_str()) {
+                    c.included = true;
+                } else if phrase_mismatches.contains(&c.memory_id) {
+                    c.excluded_reason = Some("phrase_mismatch".to_string());
+                } else if min_intersection.is_some_and(|m| c.intersection_count < m) {
+                    c.excluded_reason = Some("below_min_intersection".to_string());
+                } else {
+                    c.excluded_reason = Some("below_limit".to_string());
+                }
+            }
+            t.candidates.sort_by(|a, b| {
+                b.score.unwrap_or(f64::MIN)
+                    .partial_cmp(&a.score.unwrap_or(f64::MIN))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+        }
         
         // Finalize results by accessing content only for the top K
         let mut final_results = Vec::with_capacity(results.len());
@@ -832,7 +2355,7 @@ where
              if let Some(memory) = self.memories.get(&candidate.memory_id) {
                  
                  let explain_data = if explain {
-                    Some(serde_json::json!({
+                    let mut data = serde_json::json!({
                         "intersection_weighted": candidate.intersection_weighted,
                         "score": candidate.score,
                         "match_integrity": candidate.match_integrity,
@@ -840,7 +2363,11 @@ where
                         "recency_score": candidate.recency_score,
                         "reinforcement_score": candidate.reinforcement_score,
                         "salience_score": candidate.salience_score,
-                    }))
+                    });
+                    if hybrid.is_some() {
+                        data["vector_similarity"] = serde_json::json!(similarities.get(&candidate.memory_id).copied().unwrap_or(0.0));
+                    }
+                    Some(data)
                  } else {
                      None
                  };
@@ -865,7 +2392,69 @@ where
         final_results
     }
     
-    fn consolidated_search(&self, query_cues: &[(String, f64)], limit: usize, explain: bool, disable_salience_bias: bool, disable_systems_consolidation: bool, heatmap: Option<&HashMap<String, f32>>) -> Vec<ScoredMemoryCandidate> {
+    /// Hybrid scoring: cue scores are rescaled to [0, 1] (best cue match = 1) and blended
+    /// with cosine similarity as `(1 - alpha) * cue + alpha * cosine`. The nearest vectors
+    /// join the candidates even without a shared cue, so paraphrases can surface.
+    /// Returns the similarity of every candidate that has an embedding.
+    fn blend_vector_scores(
+        &self,
+        results: &mut Vec<ScoredMemoryCandidate>,
+        hybrid: &HybridQuery,
+        limit: usize,
+        filter: Option<&RecallFilter>,
+        disable_systems_consolidation: bool,
+    ) -> HashMap<String, f64> {
+        let alpha = hybrid.alpha.clamp(0.0, 1.0);
+        let mut similarities = HashMap::new();
+        if self.vectors.is_empty() {
+            return similarities;
+        }
+
+        let max_cue_score = results.iter().map(|r| r.score).fold(0.0, f64::max);
+        for r in results.iter_mut() {
+            let cue_score = if max_cue_score > 0.0 { r.score / max_cue_score } else { 0.0 };
+            let similarity = self.vectors.similarity(&hybrid.vector, &r.memory_id).map_or(0.0, |s| s.max(0.0) as f64);
+            if self.vectors.contains(&r.memory_id) {
+                similarities.insert(r.memory_id.clone(), similarity);
+            }
+            r.score = (1.0 - alpha) * cue_score + alpha * similarity;
+        }
+
+        let scan = (limit * self.tuning.adaptive_scan_factor).min(self.tuning.adaptive_scan_max).max(limit);
+        let neighbours = self.vectors.search(&hybrid.vector, scan).unwrap_or_default();
+        let known: HashSet<String> = results.iter().map(|r| r.memory_id.clone()).collect();
+        for (memory_id, similarity) in neighbours {
+            if similarity <= 0.0 || known.contains(&memory_id) {
+                continue;
+            }
+            let Some(memory) = self.memories.get(&memory_id) else { continue };
+            if filter.is_some_and(|f| !f.matches(memory.value())) {
+                continue;
+            }
+            if disable_systems_consolidation && memory.cues.iter().any(|c| c == "type:summary") {
+                continue;
+            }
+            let similarity = similarity as f64;
+            similarities.insert(memory_id.clone(), similarity);
+            results.push(ScoredMemoryCandidate {
+                memory_id,
+                score: alpha * similarity,
+                match_integrity: 0.0,
+                intersection_count: 0,
+                recency_score: 0.0,
+                reinforcement_score: 0.0,
+                salience_score: 0.0,
+                created_at: memory.created_at,
+                intersection_weighted: 0.0,
+                match_count: 0.0,
+            });
+        }
+
+        similarities
+    }
+
+    fn consolidated_search(&self, query_cues: &[(String, f64)], limit: usize, top_k: Option<usize>, scan_limit: Option<usize>, params: &RecallParams<'_>, mut trace: Option<&mut RecallTrace>) -> Vec<ScoredMemoryCandidate> {
+        let RecallParams { explain, disable_salience_bias, disable_systems_consolidation, heatmap, filter, .. } = *params;
         if query_cues.is_empty() {
             return Vec::new();
         }
@@ -883,6 +2472,17 @@ where
                 let df = ordered_set.len() as f64;
                 let idf = ((total_memories - df + 0.5) / (df + 0.5)).ln().max(self.tuning.idf_threshold_percent);
                 let adjusted_weight = weight * idf;
+
+                if let Some(t) = trace.as_deref_mut() {
+                    t.cue_weights.push(CueWeightTrace {
+                        cue: cue.clone(),
+                        weight: *weight,
+                        document_frequency: ordered_set.len(),
+                        idf,
+                        adjusted_weight,
+                        scan_budget: 0,
+                    });
+                }
                 
                 cue_data.push((cue.clone(), adjusted_weight, ordered_set));
             }
@@ -896,14 +2496,20 @@ where
         // Processing rarer cues first produces fewer candidates to probe
         cue_data.sort_by(|a, b| a.2.len().cmp(&b.2.len()));
 
-        // OPTIMIZATION 2: Adaptive scan limit based on requested limit
-        // For limit=5, we don't need to scan 10k items per cue
-        // Scale: limit * factor, capped at max for safety
-        let adaptive_scan_limit = (limit * self.tuning.adaptive_scan_factor).min(self.tuning.adaptive_scan_max);
+        // OPTIMIZATION 2: Adaptive scan limit based on requested limit and on
+        // the selectivity of the rarest cue (see `scan_budget_per_cue`)
+        let adaptive_scan_limit = self.scan_budget_per_cue(cue_data[0].2.len(), limit, scan_limit);
+        if let Some(t) = trace.as_deref_mut() {
+            for w in t.cue_weights.iter_mut() {
+                w.scan_budget = w.document_frequency.min(adaptive_scan_limit);
+            }
+        }
 
         // 2. Perform Union-based search with O(1) Probing
         let mut candidates = Vec::new();
         let mut seen_memories = HashSet::new();
+        let field_boosts = &self.tuning.field_boosts;
+        let boost_fields = !field_boosts.is_uniform();
 
         for (cue_idx, (_cue, _weight, set)) in cue_data.iter().enumerate() {
             let scan_limit = std::cmp::min(set.len(), adaptive_scan_limit);
@@ -916,23 +2522,44 @@ where
                 }
                 seen_memories.insert((*memory_id).clone());
 
+                // Drop filtered-out candidates before probing and scoring
+                if let Some(f) = filter {
+                    let passes = matches!(self.memories.get(memory_id.as_str()), Some(memory) if f.matches(memory.value()));
+                    if !passes {
+                        if let Some(t) = trace.as_deref_mut() {
+                            t.candidates.push(CandidateTrace::pruned(memory_id, "filtered"));
+                        }
+                        continue;
+                    }
+                }
+
                 let mut total_weight = 0.0;
                 let mut positions_info = Vec::with_capacity(cue_data.len());
 
+                // Fields of this memory's structural cues (title/header/path), for boosts
+                let fields = if boost_fields { self.field_tokens.get(memory_id.as_str()) } else { None };
+                let fields = fields.as_deref().map_or(&[][..], Vec::as_slice);
+
                 // 3. For each NEW candidate, probe ALL query cue lists to get full intersection data
-                for (other_idx, (_other_cue, other_weight, other_set)) in cue_data.iter().enumerate() {
+                for (other_idx, (other_cue, other_weight, other_set)) in cue_data.iter().enumerate() {
+                    let weight = if boost_fields {
+                        *other_weight * field_boost(field_boosts, fields, other_cue)
+                    } else {
+                        *other_weight
+                    };
+
                     // Optimization: if it's the current set we're iterating, we know it's there
                     if other_idx == cue_idx {
-                        total_weight += *other_weight;
-                        positions_info.push((pos_rev, other_set.len(), *other_weight));
+                        total_weight += weight;
+                        positions_info.push((pos_rev, other_set.len(), weight));
                         continue;
                     }
 
                     // O(1) probe into other sets
                     if let Some(oldest_idx) = other_set.get_index_of(memory_id) {
-                        total_weight += *other_weight;
+                        total_weight += weight;
                         let recency_pos = (other_set.len() - 1) - oldest_idx;
-                        positions_info.push((recency_pos, other_set.len(), *other_weight));
+                        positions_info.push((recency_pos, other_set.len(), weight));
                     }
                 }
 
@@ -941,24 +2568,115 @@ where
             }
         }
         
+        let candidate_ids: Vec<String> = if trace.is_some() {
+            candidates.iter().map(|(id, _, _)| id.to_string()).collect()
+        } else {
+            Vec::new()
+        };
+
         // 5. Score candidates
-        let results = self.score_consolidated_candidates(candidates, explain, disable_salience_bias, disable_systems_consolidation, heatmap);
+        let results = self.score_consolidated_candidates(candidates, top_k, explain, disable_salience_bias, disable_systems_consolidation, heatmap);
+
+        if let Some(t) = trace {
+            let scored: HashMap<&str, &ScoredMemoryCandidate> = results.iter().map(|r| (r.memory_id.as_str(), r)).collect();
+            for id in &candidate_ids {
+                let matched_cues: Vec<String> = cue_data
+                    .iter()
+                    .filter(|(_, _, set)| set.get_index_of(id).is_some())
+                    .map(|(cue, _, _)| cue.clone())
+                    .collect();
+
+                let entry = match scored.get(id.as_str()) {
+                    Some(r) => CandidateTrace {
+                        memory_id: id.clone(),
+                        matched_cues,
+                        score: Some(r.score),
+                        intersection_weighted: Some(r.intersection_weighted),
+                        intersection_count: r.intersection_count,
+                        match_integrity: Some(r.match_integrity),
+                        recency_score: Some(r.recency_score),
+                        reinforcement_score: Some(r.reinforcement_score),
+                        salience_score: Some(r.salience_score),
+                        included: false,
+                        excluded_reason: None,
+                    },
+                    None => CandidateTrace {
+                        matched_cues,
+                        ..CandidateTrace::pruned(id, "summary_disabled")
+                    },
+                };
+                t.candidates.push(entry);
+            }
+        }
 
         results
     }
 
+    /// Entries scanned from each query cue's list. The base budget is
+    /// `limit * adaptive_scan_factor`, capped at `adaptive_scan_max`. When even
+    /// the rarest cue (`driver_df`) is longer than that, no cue can be scanned
+    /// completely and matches older than the newest entries would be missed, so
+    /// the budget grows to `adaptive_scan_coverage` of the rarest list, up to
+    /// `adaptive_scan_ceiling`. When a cue fits the base budget it is scanned
+    /// fully and probes the broader lists, so they keep the base budget.
+    /// `scan_limit` overrides the computation.
+    fn scan_budget_per_cue(&self, driver_df: usize, limit: usize, scan_limit: Option<usize>) -> usize {
+        if let Some(scan_limit) = scan_limit {
+            return scan_limit.max(1);
+        }
+        let base = limit
+            .saturating_mul(self.tuning.adaptive_scan_factor)
+            .min(self.tuning.adaptive_scan_max)
+            .max(limit);
+        if driver_df <= base {
+            return base;
+        }
+        let widened = (driver_df as f64 * self.tuning.adaptive_scan_coverage) as usize;
+        widened.min(self.tuning.adaptive_scan_ceiling).max(base)
+    }
+
+    /// Scan budget `recall_weighted` would use for each of `cues`, rarest first
+    pub fn scan_budgets(&self, cues: &[(String, f64)], limit: usize, scan_limit: Option<usize>) -> Vec<ScanBudget> {
+        let mut budgets: Vec<ScanBudget> = cues
+            .iter()
+            .map(|(c, _)| c.to_lowercase().trim().to_string())
+            .filter_map(|cue| {
+                let document_frequency = self.cue_index.get(&cue)?.len();
+                Some(ScanBudget { cue, document_frequency, budget: 0 })
+            })
+            .collect();
+        budgets.sort_by_key(|b| b.document_frequency);
+        budgets.dedup_by(|a, b| a.cue == b.cue);
+        let Some(driver_df) = budgets.first().map(|b| b.document_frequency) else {
+            return budgets;
+        };
+        let per_cue = self.scan_budget_per_cue(driver_df, limit.max(1), scan_limit);
+        for b in &mut budgets {
+            b.budget = b.document_frequency.min(per_cue);
+        }
+        budgets
+    }
+
+    /// Score candidates. With `top_k`, only the `top_k` best are kept, in a
+    /// bounded min-heap, so broad queries don't allocate and sort every
+    /// candidate; the result is then unordered.
     fn score_consolidated_candidates<'a>(
         &self, 
         candidates: Vec<(&'a str, Vec<(usize, usize, f64)>, f64)>, 
+        top_k: Option<usize>,
         _explain: bool, 
         disable_salience_bias: bool, 
         disable_systems_consolidation: bool,
         heatmap: Option<&HashMap<String, f32>>
     ) -> Vec<ScoredMemoryCandidate> {
+        use std::cmp::Reverse;
+        use std::collections::BinaryHeap;
+
         let max_rec_weight = self.tuning.max_rec_weight;
         let max_freq_weight = self.tuning.max_freq_weight;
         
-        let mut results = Vec::with_capacity(candidates.len());
+        let mut results = Vec::with_capacity(if top_k.is_some() { 0 } else { candidates.len() });
+        let mut heap: BinaryHeap<Reverse<ByScore>> = BinaryHeap::with_capacity(top_k.map_or(0, |k| k + 1));
         
         for (memory_id_ref, positions_info, total_weight) in candidates {
             
@@ -1020,7 +2738,12 @@ where
                 
                 // Final score includes salience
                 // We use salience_score (Effective + Market) here
-                let score = intersection_score + (recency_score * avg_w_rec) + (frequency_score * avg_w_freq) + (_salience_score * self.tuning.salience_score_multiplier);
+                let mut score = intersection_score + (recency_score * avg_w_rec) + (frequency_score * avg_w_freq) + (_salience_score * self.tuning.salience_score_multiplier);
+                if let Some(floor) = self.tuning.pinned_score_floor {
+                    if memory.is_pinned() {
+                        score = score.max(floor);
+                    }
+                }
                 
                 // Match integrity calculation
                 // 1. Intersection strength (relative to match count)
@@ -1035,7 +2758,14 @@ where
                 let reinforcement_boost = (frequency_score / 2.0).min(1.0);
                 let match_integrity = (intersection_strength * 0.5 + context_agreement * 0.3 + reinforcement_boost * 0.2).min(1.0);
 
-                results.push(ScoredMemoryCandidate {
+                // Not better than the worst kept candidate: skip building it
+                let beaten = top_k.is_some_and(|k| heap.len() >= k)
+                    && heap.peek().is_some_and(|Reverse(worst)| score <= worst.0.score);
+                if beaten {
+                    continue;
+                }
+
+                let candidate = ScoredMemoryCandidate {
                     memory_id: memory_id_ref.to_string(),
                     score,
                     match_integrity,
@@ -1046,36 +2776,220 @@ where
                     created_at: memory.created_at,
                     intersection_weighted: total_weight,
                     match_count,
-                });
+                };
+                match top_k {
+                    Some(k) => {
+                        heap.push(Reverse(ByScore(candidate)));
+                        if heap.len() > k {
+                            heap.pop();
+                        }
+                    }
+                    None => results.push(candidate),
+                }
             }
         }
         
+        if top_k.is_some() {
+            results = heap.into_iter().map(|Reverse(ByScore(c))| c).collect();
+        }
         results
     }
     
     pub fn get_memory(&self, memory_id: &str) -> Option<Memory<T>> {
         self.memories.get(memory_id).map(|m| m.clone())
     }
+
+    /// Memory ids ordered oldest first, so replaying an export keeps recency order
+    pub fn memory_ids_by_age(&self) -> Vec<String> {
+        let mut ids: Vec<(String, f64)> = self.memories
+            .iter()
+            .map(|m| (m.key().clone(), m.created_at))
+            .collect();
+        ids.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        ids.into_iter().map(|(id, _)| id).collect()
+    }
+
+    pub fn export_record(&self, memory_id: &str) -> Option<MemoryRecord<T>> {
+        let memory = self.memories.get(memory_id)?;
+        let content = match memory.access_content(self.master_key.as_deref()) {
+            Ok(c) => c,
+            Err(e) => {
+                tracing::error!("Failed to export memory {}: {}", memory_id, e);
+                return None;
+            }
+        };
+
+        Some(MemoryRecord {
+            id: memory.id.clone(),
+            content,
+            cues: memory.cues.clone(),
+            metadata: memory.metadata.clone(),
+            stats: memory.stats.clone(),
+            created_at: Some(memory.created_at),
+            last_accessed: Some(memory.last_accessed),
+            links: memory.links.clone(),
+        })
+    }
+
+    /// Insert an exported record. Returns false if a memory with the same id already exists.
+    pub fn import_record(&self, record: MemoryRecord<T>) -> bool {
+        if self.memories.contains_key(&record.id) {
+            return false;
+        }
+
+        self.mark_changed();
+        let id = self.insert_memory(record.id, record.content, record.cues, Some(record.metadata), Some(record.stats), false);
+
+        if let Some(mut memory) = self.memories.get_mut(&id) {
+            if let Some(created_at) = record.created_at {
+                memory.created_at = created_at;
+            }
+            if let Some(last_accessed) = record.last_accessed {
+                memory.last_accessed = last_accessed;
+            }
+            // Exports from before links had their own field carry them in metadata
+            memory.links = record.links;
+            memory.take_legacy_links();
+        }
+        true
+    }
     
     // Consolidate Memory function removed from generic implementation
     // It requires specific knowledge of how to merge T
     // Will be re-implemented in specialized impl blocks if needed
 
+    /// Graph of the `limit` most recently accessed memories (0 = all), their cues
+    /// and the links between them
     pub fn get_graph_data(&self, limit: usize) -> serde_json::Value {
+        self.get_graph_page(limit, None).map(|(graph, _)| graph).unwrap_or_default()
+    }
+
+    /// One page of the full graph, most recently accessed memories first. `cursor`
+    /// is the `next_cursor` of the previous page (None once the last page is reached).
+    /// Memories accessed between two requests move ahead of the cursor and are
+    /// not repeated.
+    pub fn get_graph_page(&self, limit: usize, cursor: Option<&str>) -> Result<(serde_json::Value, Option<String>), String> {
+        let after = cursor.map(parse_graph_cursor).transpose()?;
+
+        // 1. Order memories by last access without cloning them
+        let mut keys: Vec<(f64, String)> = self.memories
+            .iter()
+            .map(|kv| (kv.value().last_accessed, kv.key().clone()))
+            .filter(|(at, id)| match &after {
+                Some((after_at, after_id)) => graph_order(*at, id, *after_at, after_id) == std::cmp::Ordering::Greater,
+                None => true,
+            })
+            .collect();
+        keys.sort_unstable_by(|a, b| graph_order(a.0, &a.1, b.0, &b.1));
+        let mut next_cursor = None;
+        if limit > 0 && keys.len() > limit {
+            keys.truncate(limit);
+            next_cursor = keys.last().map(|(at, id)| format!("{}|{}", at, id));
+        }
+
+        let ids: Vec<String> = keys.into_iter().map(|(_, id)| id).collect();
+        Ok((self.graph_for_memories(&ids, None), next_cursor))
+    }
+
+    /// Local subgraph around `node` (`cue:<name>` or a memory id), following up to
+    /// `depth` memory-cue hops and visiting at most `limit` memories. Posting lists
+    /// are walked newest first, so a broad cue only contributes its recent memories.
+    /// Returns None when the node does not exist.
+    pub fn graph_neighborhood(&self, node: &str, depth: usize, limit: usize) -> Option<serde_json::Value> {
+        let limit = limit.max(1);
+        let mut memory_ids: Vec<String> = Vec::new();
+        let mut seen_memories: HashSet<String> = HashSet::new();
+        let mut seen_cues: HashSet<String> = HashSet::new();
+        let mut frontier_memories: Vec<String> = Vec::new();
+        let mut frontier_cues: Vec<String> = Vec::new();
+
+        if let Some(cue) = node.strip_prefix("cue:") {
+            let cue = cue.to_lowercase().trim().to_string();
+            if !self.cue_index.contains_key(&cue) {
+                return None;
+            }
+            seen_cues.insert(cue.clone());
+            frontier_cues.push(cue);
+        } else {
+            if !self.memories.contains_key(node) {
+                return None;
+            }
+            seen_memories.insert(node.to_string());
+            memory_ids.push(node.to_string());
+            frontier_memories.push(node.to_string());
+        }
+
+        let mut truncated = false;
+        for _ in 0..depth {
+            let mut next_cues = Vec::new();
+            let mut next_memories = Vec::new();
+
+            for id in &frontier_memories {
+                let Some(memory) = self.memories.get(id) else { continue };
+                for cue in &memory.cues {
+                    if seen_cues.insert(cue.clone()) {
+                        next_cues.push(cue.clone());
+                    }
+                }
+            }
+
+            'cues: for cue in &frontier_cues {
+                let Some(set) = self.cue_index.get(cue) else { continue };
+                for id in set.iter().rev() {
+                    if seen_memories.contains(id) {
+                        continue;
+                    }
+                    if memory_ids.len() >= limit {
+                        truncated = true;
+                        break 'cues;
+                    }
+                    seen_memories.insert(id.clone());
+                    memory_ids.push(id.clone());
+                    next_memories.push(id.clone());
+                }
+            }
+
+            if next_cues.is_empty() && next_memories.is_empty() {
+                break;
+            }
+            frontier_cues = next_cues;
+            frontier_memories = next_memories;
+        }
+
+        let mut graph = self.graph_for_memories(&memory_ids, Some(&seen_cues));
+        graph["node"] = serde_json::json!(node);
+        graph["depth"] = serde_json::json!(depth);
+        graph["truncated"] = serde_json::json!(truncated);
+        Some(graph)
+    }
+
+    /// Nodes and links for `ids`. Cue nodes are every cue of those memories, or
+    /// exactly `cues` when given (memory-cue links outside it are left out).
+    fn graph_for_memories(&self, ids: &[String], cues: Option<&HashSet<String>>) -> serde_json::Value {
         let mut nodes = Vec::new();
         let mut links = Vec::new();
         let mut added_nodes = HashSet::new();
+        let mut memory_links = Vec::new();
 
-        // 1. Get recent memories
-        let mut memories: Vec<_> = self.memories.iter().map(|kv| kv.value().clone()).collect();
-        // Sort by last accessed desc
-        memories.sort_unstable_by(|a, b| b.last_accessed.partial_cmp(&a.last_accessed).unwrap_or(std::cmp::Ordering::Equal));
-        if limit > 0 {
-            memories.truncate(limit);
+        // Cues requested explicitly come first, so they exist even without memories
+        if let Some(cues) = cues {
+            let mut sorted: Vec<&String> = cues.iter().collect();
+            sorted.sort();
+            for cue in sorted {
+                let cue_id = format!("cue:{}", cue);
+                nodes.push(serde_json::json!({
+                    "id": cue_id,
+                    "label": cue,
+                    "group": "cue",
+                    "val": 1.0
+                }));
+                added_nodes.insert(cue_id);
+            }
         }
 
-        // 2. Add memories and their directly connected cues
-        for mem in &memories {
+        // 1. Add memories and their directly connected cues
+        for id in ids {
+            let Some(mem) = self.memories.get(id) else { continue };
             if !added_nodes.contains(&mem.id) {
                 // Truncate content for label
                 let content_str = mem.access_content(self.master_key.as_deref()).unwrap_or_default();
@@ -1092,6 +3006,9 @@ where
             }
 
             for cue in &mem.cues {
+                if cues.is_some_and(|allowed| !allowed.contains(cue)) {
+                    continue;
+                }
                 let cue_id = format!("cue:{}", cue);
                 if !added_nodes.contains(&cue_id) {
                     nodes.push(serde_json::json!({
@@ -1109,9 +3026,24 @@ where
                     "value": 2.0
                 }));
             }
+            memory_links.push((mem.id.clone(), mem.links.clone()));
+        }
+        
+        // 1b. Typed memory-to-memory links between memories in the graph
+        for (source, targets) in memory_links {
+            for link in targets {
+                if added_nodes.contains(&link.target) {
+                    links.push(serde_json::json!({
+                        "source": source,
+                        "target": link.target,
+                        "value": 3.0,
+                        "type": link.kind
+                    }));
+                }
+            }
         }
         
-        // 3. Add cue-cue edges from co-occurrence (With budget)
+        // 2. Add cue-cue edges from co-occurrence (With budget)
         // Prevent O(N^2) explosion by capping total links
         let max_total_links = 10_000;
         if links.len() < max_total_links {
@@ -1264,6 +3196,256 @@ where
         results
     }
 
+    /// Approximate heap bytes held by the cue index (keys + posting lists)
+    pub fn index_heap_bytes(&self) -> usize {
+        self.cue_index
+            .iter()
+            .map(|e| e.key().capacity() + e.value().heap_bytes())
+            .sum()
+    }
+
+    /// Approximate heap bytes held by memory payloads, cue lists and metadata keys
+    pub fn payload_heap_bytes(&self) -> usize {
+        self.memories
+            .iter()
+            .map(|m| {
+                m.id.capacity()
+                    + m.content.resident_bytes()
+                    + m.cues.iter().map(|c| c.capacity()).sum::<usize>()
+                    + m.metadata.keys().map(|k| k.capacity()).sum::<usize>()
+            })
+            .sum()
+    }
+
+    pub fn co_occurrence_entries(&self) -> usize {
+        self.cue_co_occurrence.iter().map(|e| e.value().len()).sum()
+    }
+
+    /// Count a recall pass against each query cue that is indexed
+    pub fn record_cue_hits(&self, cues: &[(String, f64)]) {
+        for (cue, _) in cues {
+            if self.cue_index.contains_key(cue) {
+                *self.cue_hits.entry(cue.clone()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    /// Top `limit` cues by memory count, plus up to `limit` singleton and
+    /// never-recalled cues, and the IDF histogram
+    pub fn cue_analytics(&self, limit: usize) -> CueAnalytics {
+        let total_memories = self.memories.len();
+        let mut usage: Vec<CueUsage> = self.cue_index
+            .iter()
+            .map(|e| CueUsage {
+                cue: e.key().clone(),
+                memory_count: e.value().len(),
+                recall_hits: self.cue_hits.get(e.key()).map(|h| *h).unwrap_or(0),
+            })
+            .collect();
+        usage.sort_unstable_by(|a, b| b.memory_count.cmp(&a.memory_count).then_with(|| a.cue.cmp(&b.cue)));
+
+        let mut idf_histogram: Vec<IdfBucket> = IDF_HISTOGRAM_BOUNDS
+            .iter()
+            .enumerate()
+            .map(|(i, max)| IdfBucket {
+                min: if i == 0 { 0.0 } else { IDF_HISTOGRAM_BOUNDS[i - 1] },
+                max: Some(*max),
+                cues: 0,
+            })
+            .collect();
+        idf_histogram.push(IdfBucket { min: *IDF_HISTOGRAM_BOUNDS.last().unwrap_or(&0.0), max: None, cues: 0 });
+
+        let mut analytics = CueAnalytics {
+            total_cues: usage.len(),
+            total_memories,
+            ..CueAnalytics::default()
+        };
+        for cue in &usage {
+            // Same formula as consolidated_search
+            let df = cue.memory_count as f64;
+            let idf = ((total_memories as f64 - df + 0.5) / (df + 0.5)).ln().max(self.tuning.idf_threshold_percent);
+            let bucket = IDF_HISTOGRAM_BOUNDS.iter().position(|max| idf < *max).unwrap_or(IDF_HISTOGRAM_BOUNDS.len());
+            idf_histogram[bucket].cues += 1;
+
+            if cue.memory_count == 1 {
+                analytics.singleton_cue_count += 1;
+                if analytics.singleton_cues.len() < limit {
+                    analytics.singleton_cues.push(cue.cue.clone());
+                }
+            }
+            if cue.recall_hits == 0 {
+                analytics.never_recalled_count += 1;
+                if analytics.never_recalled.len() < limit {
+                    analytics.never_recalled.push(cue.clone());
+                }
+            }
+        }
+        analytics.idf_histogram = idf_histogram;
+        usage.truncate(limit);
+        analytics.top_cues = usage;
+        analytics
+    }
+
+    /// Count a recall returning `memory_ids`
+    pub fn record_recalled(&self, memory_ids: &[String]) {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        self.access.record(memory_ids, now);
+    }
+
+    pub fn memory_access(&self, memory_id: &str) -> MemoryAccess {
+        self.access.get(memory_id)
+    }
+
+    /// How many memories recall has returned, plus up to `limit` of those
+    /// older than `min_age_secs` it never has, oldest first
+    pub fn memory_analytics(&self, limit: usize, min_age_secs: u64) -> MemoryAnalytics {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0.0, |d| d.as_secs_f64());
+        let mut analytics = MemoryAnalytics { total_memories: self.memories.len(), ..Default::default() };
+        let mut idle: Vec<(f64, String)> = Vec::new();
+        for memory in self.memories.iter() {
+            if self.access.get(memory.key()).recall_count > 0 {
+                analytics.recalled_count += 1;
+            } else if now - memory.created_at >= min_age_secs as f64 {
+                idle.push((memory.created_at, memory.key().clone()));
+            }
+        }
+        analytics.never_recalled_count = idle.len();
+        idle.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+        analytics.never_recalled = idle
+            .into_iter()
+            .take(limit)
+            .filter_map(|(_, id)| {
+                let memory = self.memories.get(&id)?;
+                Some(IdleMemory {
+                    memory_id: id,
+                    created_at: memory.created_at,
+                    salience: memory.stats.get_salience(),
+                    cues: memory.cues.clone(),
+                })
+            })
+            .collect();
+        analytics
+    }
+
+    /// Writes and lock contention per cue_index shard, cues per shard and the
+    /// posting-list length distribution
+    pub fn cue_index_stats(&self) -> CueIndexReport {
+        let mut report = CueIndexReport {
+            shards: (0..self.cue_index.shards().len())
+                .map(|shard| ShardStats {
+                    shard,
+                    cues: 0,
+                    writes: self.index_stats.writes(shard),
+                    contended_writes: self.index_stats.contended(shard),
+                })
+                .collect(),
+            ..CueIndexReport::default()
+        };
+        for e in self.cue_index.iter() {
+            let shard = self.cue_index.determine_map(e.key());
+            report.shards[shard].cues += 1;
+            report.observe_list(e.key(), e.value().len(), e.value().segment_count());
+        }
+        report
+    }
+
+    /// Per-cue footprint, largest first. `limit` caps the number of cues returned.
+    pub fn cue_memory_breakdown(&self, limit: usize) -> Vec<CueFootprint> {
+        let mut breakdown: Vec<CueFootprint> = self.cue_index
+            .iter()
+            .map(|e| CueFootprint {
+                cue: e.key().clone(),
+                memory_count: e.value().len(),
+                capacity: e.value().capacity(),
+                bytes: e.key().capacity() + e.value().heap_bytes(),
+            })
+            .collect();
+        breakdown.sort_unstable_by_key(|e| std::cmp::Reverse(e.bytes));
+        breakdown.truncate(limit);
+        breakdown
+    }
+
+    /// Drop ids of deleted memories from posting lists, remove empty cues and
+    /// co-occurrence entries for cues that no longer exist, and release spare
+    /// capacity. Locks on the index and the memory map are never held together.
+    pub fn compact_index(&self) -> CompactionReport {
+        let mut report = CompactionReport {
+            index_bytes_before: self.index_heap_bytes(),
+            ..CompactionReport::default()
+        };
+
+        let cues: Vec<String> = self.cue_index.iter().map(|e| e.key().clone()).collect();
+        for cue in cues {
+            report.cues_scanned += 1;
+
+            let ids: Vec<String> = match self.cue_index.get(&cue) {
+                Some(set) => set.iter().cloned().collect(),
+                None => continue,
+            };
+            let stale: Vec<String> = ids
+                .into_iter()
+                .filter(|id| !self.memories.contains_key(id))
+                .collect();
+
+            if let Some(mut set) = self.cue_index.get_mut(&cue) {
+                // Ids indexed since the scan belong to memories that exist by now
+                for id in stale.iter().filter(|id| !self.memories.contains_key(*id)) {
+                    if set.remove(id) {
+                        report.stale_ids_removed += 1;
+                    }
+                }
+                if set.is_empty() {
+                    drop(set);
+                    // Only removed if still empty, as ids may be indexed once the guard is dropped
+                    if self.remove_empty_cue(&cue) {
+                        report.empty_cues_removed += 1;
+                    }
+                } else {
+                    set.shrink_to_fit();
+                }
+            }
+        }
+
+        let co_cues: Vec<String> = self.cue_co_occurrence.iter().map(|e| e.key().clone()).collect();
+        for cue in co_cues {
+            let removed = self.cue_co_occurrence.remove_if(&cue, |cue, _| !self.cue_index.contains_key(cue));
+            if let Some((_, inner)) = removed {
+                report.co_occurrence_entries_removed += inner.len();
+                continue;
+            }
+
+            let neighbours: Vec<String> = match self.cue_co_occurrence.get(&cue) {
+                Some(inner) => inner.iter().map(|e| e.key().clone()).collect(),
+                None => continue,
+            };
+            let gone: Vec<String> = neighbours
+                .into_iter()
+                .filter(|n| !self.cue_index.contains_key(n))
+                .collect();
+
+            if let Some(inner) = self.cue_co_occurrence.get(&cue) {
+                for n in &gone {
+                    if inner.remove(n).is_some() {
+                        report.co_occurrence_entries_removed += 1;
+                    }
+                }
+                // Lists restored from an older snapshot may exceed a lowered cap
+                let cap = self.tuning.co_occurrence_max_neighbors;
+                if cap > 0 && inner.len() > cap {
+                    report.co_occurrence_entries_removed += evict_co_occurrence(&inner, cap);
+                }
+                inner.shrink_to_fit();
+            }
+        }
+
+        self.cue_index.shrink_to_fit();
+        self.cue_co_occurrence.shrink_to_fit();
+        self.memories.shrink_to_fit();
+
+        report.index_bytes_after = self.index_heap_bytes();
+        report
+    }
+
     pub fn get_stats(&self) -> HashMap<String, serde_json::Value> {
         let mut stats = HashMap::new();
         stats.insert(
@@ -1274,6 +3456,17 @@ where
             "total_cues".to_string(),
             serde_json::json!(self.cue_count.load(Ordering::Relaxed)),
         );
+        stats.insert(
+            "co_occurrence_cues".to_string(),
+            serde_json::json!(self.cue_co_occurrence.len()),
+        );
+        stats.insert(
+            "co_occurrence_entries".to_string(),
+            serde_json::json!(self.co_occurrence_entries()),
+        );
+        if let Some(store) = &self.payload_store {
+            stats.insert("payload_store".to_string(), store.stats());
+        }
 
         stats
     }
@@ -1285,29 +3478,50 @@ where
 
 impl CueMapEngine<MainStats> {
     /// Decays dynamic salience for all memories and updates generic salience proxy
-    pub fn decay_salience(&self, decay_rate: f64) {
+    pub fn decay_salience(&self, decay_rate: f64) -> DecayReport {
+        self.decay_salience_with(decay_rate, false)
+    }
+
+    /// `decay_salience`, or with `dry_run` only the report of what it would change
+    pub fn decay_salience_with(&self, decay_rate: f64, dry_run: bool) -> DecayReport {
+        if !dry_run {
+            self.mark_changed();
+        }
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_secs();
+        let mut report = DecayReport { dry_run, rate: decay_rate, memories: self.memories.len(), ..Default::default() };
+        let decayed_through = self.salience_decayed_at();
 
         for mut memory in self.memories.iter_mut() {
              let stats = &mut memory.value_mut().stats;
-             let time_delta = now.saturating_sub(stats.last_boosted_at);
+             // Heat boosted since the last run decays from its boost; older heat
+             // was already decayed through the last run, so runs don't compound
+             let time_delta = now.saturating_sub(stats.last_boosted_at.max(decayed_through));
              
              // Simple exponential decay: N(t) = N0 * e^(-lambda * t)
              // We use hours as time unit
              let hours_passed = (time_delta as f64) / 3600.0;
-             if hours_passed > 0.1 {
+             if time_delta > 0 && stats.dynamic_salience != 0.0 {
                  let decay_factor = (-decay_rate * hours_passed).exp();
-                 stats.dynamic_salience *= decay_factor;
+                 let mut decayed = stats.dynamic_salience * decay_factor;
                  
-                 // Clamp near zero
-                 if stats.dynamic_salience < 0.01 {
-                     stats.dynamic_salience = 0.0;
+                 // Clamp near zero (penalties decay back up the same way)
+                 if decayed.abs() < 0.01 {
+                     decayed = 0.0;
+                     report.zeroed += 1;
+                 }
+                 report.decayed += usize::from(decayed != stats.dynamic_salience);
+                 if !dry_run {
+                     stats.dynamic_salience = decayed;
                  }
              }
         }
+        if !dry_run {
+            self.set_salience_decayed_at(now);
+        }
+        report
     }
 
     /// Reinforces memory by adding dynamic heat (Brain logic)
@@ -1316,6 +3530,7 @@ impl CueMapEngine<MainStats> {
     /// This prevents "Context Poisoning" (log explosion) from high-frequency events.
     pub fn reinforce_dynamic(&self, memory_id: &str, amount: f64) {
         if let Some(mut memory) = self.memories.get_mut(memory_id) {
+            self.mark_changed();
             memory.touch();
             let stats = &mut memory.stats;
             
@@ -1327,7 +3542,64 @@ impl CueMapEngine<MainStats> {
                 .duration_since(UNIX_EPOCH)
                 .unwrap()
                 .as_secs();
+        } else {
+            return;
+        }
+        self.publish_reinforced(memory_id);
+    }
+
+    /// Lower a memory's dynamic heat by `amount`. The result may go negative; it
+    /// decays back toward zero like any heat, so the penalty fades with time.
+    /// Effective salience never drops below zero. Returns false if the memory does not exist.
+    pub fn penalize_dynamic(&self, memory_id: &str, amount: f64) -> bool {
+        let Some(mut memory) = self.memories.get_mut(memory_id) else {
+            return false;
+        };
+        self.mark_changed();
+        memory.stats.demote(amount);
+        true
+    }
+
+    /// Push a memory down without deleting it: lower its dynamic heat by `amount`
+    /// and detach `cues`. Returns the detached cues, or None if the memory does not exist.
+    pub fn demote_memory(&self, memory_id: &str, amount: f64, cues: &[String]) -> Option<Vec<String>> {
+        if !self.penalize_dynamic(memory_id, amount) {
+            return None;
+        }
+        let detached = if cues.is_empty() { Vec::new() } else { self.detach_cues(memory_id, cues) };
+
+        if self.live_feed.is_some() {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
+            if let Some(salience) = self.memories.get(memory_id).map(|m| m.stats.effective_salience_at(now)) {
+                self.publish(|project_id| LiveEvent::MemoryDemoted {
+                    project_id,
+                    memory_id: memory_id.to_string(),
+                    salience,
+                });
+            }
+        }
+        Some(detached)
+    }
+
+    /// Apply feedback on one recall: `useful` memories are reinforced with the
+    /// recall's cues, the other returned memories lose `penalty` dynamic heat.
+    /// Ids that were not part of the recall are ignored. Returns (reinforced, penalized).
+    pub fn apply_recall_feedback(&self, returned: &[String], cues: &[String], useful: &[String], penalty: f64) -> (Vec<String>, Vec<String>) {
+        let mut reinforced = Vec::new();
+        let mut penalized = Vec::new();
+        for id in returned {
+            if useful.contains(id) {
+                if self.reinforce_memory(id, cues.to_vec()) {
+                    reinforced.push(id.clone());
+                }
+            } else if penalty > 0.0 && self.penalize_dynamic(id, penalty) {
+                penalized.push(id.clone());
+            }
         }
+        (reinforced, penalized)
     }
 
     /// Calculates "Effective Importance" by combining Intrinsic + Decayed Dynamic + Market Heatmap
@@ -1392,27 +3664,48 @@ impl CueMapEngine<MainStats> {
     
     /// Prune memories with low salience (Brain Plasticity)
     pub fn prune_low_salience(&self, threshold: f64) -> usize {
-        let mut to_remove = Vec::new();
+        let to_remove = self.low_salience_candidates(threshold, 0);
+        
+        let count = to_remove.len();
+        for id in to_remove {
+            self.delete_memory(&id);
+        }
+        
+        count
+    }
+
+    /// Memories `prune_low_salience` would remove, lowest salience first. With
+    /// `max_idle_secs`, only those neither created nor recalled for that long.
+    pub fn low_salience_candidates(&self, threshold: f64, max_idle_secs: u64) -> Vec<String> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs_f64();
+        let mut candidates = Vec::new();
         
         for entry in self.memories.iter() {
-            let stats = &entry.value().stats;
+            let memory = entry.value();
+            let stats = &memory.stats;
             // Total effective salience
             let total_salience = stats.intrinsic_salience + stats.dynamic_salience;
             
             // Protect high reinforcement memories from pruning even if cold?
             // Maybe not, if unused for a LONG time.
             
+            if memory.is_pinned() {
+                continue;
+            }
+            if now - memory.last_accessed.max(memory.created_at) < max_idle_secs as f64 {
+                continue;
+            }
+            
             if total_salience < threshold && stats.reinforcement_count < 5 {
-                to_remove.push(entry.key().clone());
+                candidates.push((entry.key().clone(), total_salience));
             }
         }
         
-        let count = to_remove.len();
-        for id in to_remove {
-            self.delete_memory(&id);
-        }
-        
-        count
+        candidates.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        candidates.into_iter().map(|(id, _)| id).collect()
     }
 
     /// Consolidate memories - specialized for MainStats
@@ -1425,8 +3718,8 @@ impl CueMapEngine<MainStats> {
             let (id_a, mem_a) = entry.pair();
             if seen.contains(id_a) { continue; }
             
-            // Skip already consolidated memories to avoid recursion
-            if mem_a.metadata.get("consolidated").and_then(|v| v.as_bool()).unwrap_or(false) {
+            // Skip already consolidated memories to avoid recursion, and pinned ones
+            if mem_a.is_pinned() || mem_a.metadata.get("consolidated").and_then(|v| v.as_bool()).unwrap_or(false) {
                 continue;
             }
             
@@ -1438,7 +3731,7 @@ impl CueMapEngine<MainStats> {
                         if id_a == id_b || seen.contains(id_b) { continue; }
                         
                         if let Some(mem_b) = self.memories.get(id_b) {
-                             if mem_b.metadata.get("consolidated").and_then(|v| v.as_bool()).unwrap_or(false) {
+                             if mem_b.is_pinned() || mem_b.metadata.get("consolidated").and_then(|v| v.as_bool()).unwrap_or(false) {
                                 continue;
                             }
                             
@@ -1500,6 +3793,7 @@ impl CueMapEngine<MainStats> {
             let mut metadata = HashMap::new();
             metadata.insert("consolidated".to_string(), serde_json::json!(true));
             metadata.insert("original_count".to_string(), serde_json::json!(group.len()));
+            metadata.insert("consolidated_from".to_string(), serde_json::json!(group));
             
             let mut cues_vec: Vec<String> = combined_cues.into_iter().collect();
             cues_vec.push("type:summary".to_string());
@@ -1530,6 +3824,7 @@ impl CueMapEngine<LexiconStats> {
     /// Tiered Reinforcement for Dictionary (Minute/Daily Buckets)
     pub fn reinforce_tiered(&self, memory_id: &str, amount: u64) {
         if let Some(mut memory) = self.memories.get_mut(memory_id) {
+             self.mark_changed();
              memory.touch();
              let stats = &mut memory.stats;
              stats.total_count += amount;
@@ -1551,16 +3846,45 @@ impl CueMapEngine<LexiconStats> {
         }
     }
     
+    /// Remove lexicon entries that were reinforced fewer than `min_count` times
+    /// and have not been touched for `max_idle_secs`. Returns the number removed.
+    pub fn prune_idle_entries(&self, min_count: u64, max_idle_secs: u64) -> usize {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs_f64();
+
+        let to_remove: Vec<String> = self.memories
+            .iter()
+            .filter(|entry| {
+                let memory = entry.value();
+                let last_seen = memory.last_accessed
+                    .max(memory.created_at)
+                    .max(memory.stats.last_reinforced as f64);
+                memory.stats.total_count < min_count && now - last_seen > max_idle_secs as f64
+            })
+            .map(|entry| entry.key().clone())
+            .collect();
+
+        let count = to_remove.len();
+        for id in to_remove {
+            self.delete_memory(&id);
+        }
+        count
+    }
+    
     /// Trending identification (Spike detection)
-    /// Sums bucket counts in window to identify trending cues
-    pub fn get_trending_items(&self, limit: usize) -> Vec<(String, f64)> {
+    /// Sums bucket counts in the last `window_mins` minutes (at most an hour of
+    /// minute buckets is kept) and compares them with the daily baseline
+    pub fn trending_cues(&self, window_mins: u32, limit: usize) -> Vec<TrendingCue> {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_secs();
         let current_min = (now / 60) as u32;
-        // Look at last 15 mins
-        let window_start = current_min.saturating_sub(15);
+        let today = (now / 86400) as u32;
+        let window_start = current_min.saturating_sub(window_mins);
+        let baseline_start = today.saturating_sub(BASELINE_DAYS);
         
         let mut trending = Vec::new();
         
@@ -1568,34 +3892,45 @@ impl CueMapEngine<LexiconStats> {
             let stats = &entry.value().stats;
             
             // Calculate velocity in window
-            let mut recent_velocity = 0.0;
-            for (min, count) in &stats.minute_stats {
-                if *min >= window_start {
-                    recent_velocity += *count as f64;
-                }
-            }
-            
-            if recent_velocity >= 1.0 {
-                // Normalize by baseline (total count / age?) or simply raw velocity for "Trending Now"
-                trending.push((entry.key().clone(), recent_velocity));
+            let count: u64 = stats.minute_stats.iter()
+                .filter(|(min, _)| **min >= window_start)
+                .map(|(_, count)| *count as u64)
+                .sum();
+            if count == 0 {
+                continue;
             }
+
+            // Baseline: days before today, from the first one seen (up to a week back)
+            let history: Vec<(u32, u32)> = stats.daily_stats.iter()
+                .filter(|(day, _)| **day >= baseline_start && **day < today)
+                .map(|(day, count)| (*day, *count))
+                .collect();
+            let baseline = match history.iter().map(|(day, _)| *day).min() {
+                Some(first) => {
+                    let total: u64 = history.iter().map(|(_, count)| *count as u64).sum();
+                    total as f64 / ((today - first) as f64 * 1440.0)
+                }
+                None => 0.0,
+            };
+            let velocity = count as f64 / window_mins.max(1) as f64;
+
+            trending.push(TrendingCue {
+                cue: entry.key().strip_prefix("cue:").unwrap_or(entry.key()).to_string(),
+                count,
+                velocity,
+                baseline,
+                lift: (baseline > 0.0).then(|| velocity / baseline),
+            });
         }
         
-        trending.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        trending.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.cue.cmp(&b.cue)));
         
         // Log top 5 items with bucket breakdown
         if !trending.is_empty() {
-             tracing::info!("Trending: Identification finished. Found {} candidates >= 1.0 velocity", trending.len());
-             for i in 0..trending.len().min(5) {
-                 let (cue, velocity) = &trending[i];
-                 if let Some(entry) = self.memories.get(cue) {
-                     let stats = &entry.stats;
-                     let m_count = stats.minute_stats.len();
-                     let d_count = stats.daily_stats.len();
-                     let total = stats.total_count;
-                     tracing::info!("Trending: [Rank {}] Cue '{}' velocity={:.2}. Stats: totals={}, min_buckets={}, day_buckets={}, last_ref={}", 
-                        i+1, cue, velocity, total, m_count, d_count, stats.last_reinforced);
-                 }
+             tracing::debug!("Trending: Identification finished. Found {} candidates in the last {}m", trending.len(), window_mins);
+             for (i, item) in trending.iter().take(5).enumerate() {
+                 tracing::debug!("Trending: [Rank {}] Cue '{}' count={} velocity={:.2}/min baseline={:.4}/min",
+                    i + 1, item.cue, item.count, item.velocity, item.baseline);
              }
         }
 
@@ -1604,3 +3939,150 @@ impl CueMapEngine<LexiconStats> {
         trending
     }
 }
+
+/// Tokens of each field-carrying structural cue on a memory, per field
+type FieldTokens = Vec<(CueField, Vec<String>)>;
+
+/// Tokens of each field-carrying structural cue on a memory (`title:…`, `header:…`, `path:…`)
+fn structural_field_tokens(cues: &[String]) -> FieldTokens {
+    cues.iter()
+        .filter_map(|cue| CueField::of_structural_cue(cue))
+        .map(|(field, value)| (field, crate::nl::tokenize_to_cues(value)))
+        .collect()
+}
+
+/// Boost for a query cue on one memory: a structural cue gets its own field's boost,
+/// a plain token the strongest field whose text contains it (body otherwise)
+fn field_boost(boosts: &FieldBoosts, fields: &[(CueField, Vec<String>)], cue: &str) -> f64 {
+    if let Some((field, _)) = CueField::of_structural_cue(cue) {
+        return boosts.get(field);
+    }
+    fields
+        .iter()
+        .filter(|(_, tokens)| tokens.iter().any(|t| t == cue))
+        .map(|(field, _)| boosts.get(*field))
+        .fold(boosts.body, f64::max)
+}
+
+/// `*` wildcard match over the whole of `text`
+pub(crate) fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// Lowercased alphanumeric words, for phrase matching
+fn content_words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Value of `cue` if it is in `namespace` (`path:src/main.rs` -> `src/main.rs`)
+fn cue_in_namespace<'a>(cue: &'a str, namespace: &str) -> Option<&'a str> {
+    let (ns, value) = cue.split_once(':')?;
+    (ns.eq_ignore_ascii_case(namespace) && !value.is_empty()).then_some(value)
+}
+
+/// Lowercased cues for duplicate detection, without temporal `episode:` cues
+fn dedup_cue_set(cues: &[String]) -> HashSet<String> {
+    cues.iter()
+        .map(|c| c.to_lowercase().trim().to_string())
+        .filter(|c| !c.is_empty() && !c.starts_with("episode:"))
+        .collect()
+}
+
+/// Distinct lowercased words of content, for comparing near duplicates
+fn content_word_set(content: &str) -> HashSet<String> {
+    content_words(content).into_iter().collect()
+}
+
+/// Hash of content with case and whitespace differences removed
+fn content_fingerprint(content: &str) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    for word in content.split_whitespace() {
+        word.to_lowercase().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// How `add_memory_chained` links a memory to the previous one of its chain
+#[derive(Clone, Copy)]
+enum EpisodeChain<'a> {
+    /// Implicit temporal chunking within a group: chained only when close in
+    /// time and cues
+    Temporal { group: Option<&'a str>, disabled: bool },
+    /// An explicit session (its `session:` cue): always chained
+    Session(&'a str),
+}
+
+/// `last_events` key of a temporal-chunking chain: the project, or a group
+/// within it
+fn temporal_chain_key(project_id: &str, group: Option<&str>) -> String {
+    match group {
+        Some(group) => format!("{}#{}", project_id, group),
+        None => project_id.to_string(),
+    }
+}
+
+/// Shrink a neighbour list to at most `keep` entries. Every count is halved and
+/// neighbours that decay to zero are dropped, so pairs seen once long ago make
+/// room before established ones; if that is not enough the weakest are evicted.
+/// Returns the number of neighbours removed.
+fn evict_co_occurrence(inner: &DashMap<String, u64, RandomState>, keep: usize) -> usize {
+    let before = inner.len();
+    inner.retain(|_, count| {
+        *count /= 2;
+        *count > 0
+    });
+    if inner.len() > keep {
+        let mut counts: Vec<(String, u64)> = inner.iter().map(|e| (e.key().clone(), *e.value())).collect();
+        counts.sort_unstable_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        let excess = inner.len() - keep;
+        for (neighbour, _) in counts.into_iter().take(excess) {
+            inner.remove(&neighbour);
+        }
+    }
+    before - inner.len()
+}
+
+/// Graph page order: most recently accessed first, ties broken by id
+fn graph_order(a_at: f64, a_id: &str, b_at: f64, b_id: &str) -> std::cmp::Ordering {
+    b_at.total_cmp(&a_at).then_with(|| a_id.cmp(b_id))
+}
+
+/// `{last_accessed}|{id}` cursor of a graph page
+fn parse_graph_cursor(cursor: &str) -> Result<(f64, String), String> {
+    let (at, id) = cursor.split_once('|').ok_or_else(|| "Invalid graph cursor".to_string())?;
+    let at = at.parse::<f64>().map_err(|_| "Invalid graph cursor".to_string())?;
+    Ok((at, id.to_string()))
+}
+
+/// Index entries a cue is stored under: the cue itself and, for `key:value`
+/// cues, the value (double indexing)
+fn cue_index_keys(cue: &str) -> Vec<String> {
+    let cue_lower = cue.to_lowercase().trim().to_string();
+    if cue_lower.is_empty() {
+        return Vec::new();
+    }
+    let value = cue_lower
+        .split_once(':')
+        .map(|(_, value)| value.to_string())
+        .filter(|value| !value.is_empty());
+    std::iter::once(cue_lower).chain(value).collect()
+}
@@ -0,0 +1,265 @@
+//! Named-entity and code-identifier extraction for ProposeCues.
+//!
+//! Rule-based and dependency-free, in the same spirit as the RAKE phrases in
+//! `crate::nl`:
+//! - `entity:` cues for runs of capitalized words (people, organisations,
+//!   places), acronyms and dates. Dates are normalized to ISO (`2024-03-15`,
+//!   or `2024-03` without a day).
+//! - `symbol:` cues for code identifiers (`getUserById`, `MAX_RETRIES`),
+//!   canonicalized to snake_case so the same name matches across languages.
+//!   Their parts (`user`) are proposed as plain cues, since the tokenizer
+//!   would otherwise index the identifier as one opaque word.
+//!
+//! Multi-word values are joined with `_`, the same joiner RAKE phrases use.
+
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+/// Cap on cues extracted from one memory
+pub const MAX_ENTITY_CUES: usize = 20;
+
+static ISO_DATE_REGEX: OnceLock<Regex> = OnceLock::new();
+static MONTH_DAY_YEAR_REGEX: OnceLock<Regex> = OnceLock::new();
+static DAY_MONTH_YEAR_REGEX: OnceLock<Regex> = OnceLock::new();
+static MONTH_YEAR_REGEX: OnceLock<Regex> = OnceLock::new();
+static IDENTIFIER_REGEX: OnceLock<Regex> = OnceLock::new();
+static CAPITALIZED_STOPWORDS: OnceLock<HashSet<&'static str>> = OnceLock::new();
+
+const MONTHS: &str = r"jan(?:uary)?|feb(?:ruary)?|mar(?:ch)?|apr(?:il)?|may|june?|july?|aug(?:ust)?|sep(?:t(?:ember)?)?|oct(?:ober)?|nov(?:ember)?|dec(?:ember)?";
+
+/// Words allowed inside a name ("Bank of America", "Ludwig van Beethoven")
+const NAME_CONNECTORS: &[&str] = &["of", "van", "von", "de", "del", "da", "la", "&"];
+
+/// Acronyms that are markers, not entities
+const ACRONYM_NOISE: &[&str] = &["ok", "todo", "fixme", "note", "xxx", "tbd", "fyi", "eof"];
+
+/// Entity and symbol cues for `text`, at most `MAX_ENTITY_CUES`
+pub fn extract_entity_cues(text: &str) -> Vec<String> {
+    let mut cues: Vec<String> = Vec::new();
+    let push = |cue: String, cues: &mut Vec<String>| {
+        if !cues.contains(&cue) {
+            cues.push(cue);
+        }
+    };
+
+    for date in extract_dates(text) {
+        push(format!("entity:{}", date), &mut cues);
+    }
+    for entity in extract_named_entities(text) {
+        push(format!("entity:{}", entity), &mut cues);
+    }
+    let symbols = extract_symbols(text);
+    for (symbol, _) in &symbols {
+        push(format!("symbol:{}", symbol), &mut cues);
+    }
+    for (_, parts) in symbols {
+        for part in parts {
+            if part.len() >= 3 && !crate::nl::get_stopwords().contains(part.as_str()) {
+                push(crate::nl::stem_word(&part), &mut cues);
+            }
+        }
+    }
+
+    cues.truncate(MAX_ENTITY_CUES);
+    cues
+}
+
+/// Dates in ISO form (`yyyy-mm-dd`, or `yyyy-mm` for "March 2024")
+pub fn extract_dates(text: &str) -> Vec<String> {
+    let iso = ISO_DATE_REGEX.get_or_init(|| Regex::new(r"\b(\d{4})-(\d{2})-(\d{2})\b").unwrap());
+    let mdy = MONTH_DAY_YEAR_REGEX.get_or_init(|| {
+        Regex::new(&format!(r"(?i)\b({})\.?\s+(\d{{1,2}})(?:st|nd|rd|th)?,?\s+(\d{{4}})\b", MONTHS)).unwrap()
+    });
+    let dmy = DAY_MONTH_YEAR_REGEX.get_or_init(|| {
+        Regex::new(&format!(r"(?i)\b(\d{{1,2}})(?:st|nd|rd|th)?\s+({})\.?,?\s+(\d{{4}})\b", MONTHS)).unwrap()
+    });
+    let my = MONTH_YEAR_REGEX.get_or_init(|| {
+        Regex::new(&format!(r"(?i)\b({})\.?,?\s+(\d{{4}})\b", MONTHS)).unwrap()
+    });
+
+    let mut dates = Vec::new();
+    for caps in iso.captures_iter(text) {
+        push_date(&mut dates, &caps[1], caps[2].parse().unwrap_or(0), Some(&caps[3]));
+    }
+    for caps in mdy.captures_iter(text) {
+        push_date(&mut dates, &caps[3], month_number(&caps[1]), Some(&caps[2]));
+    }
+    for caps in dmy.captures_iter(text) {
+        push_date(&mut dates, &caps[3], month_number(&caps[2]), Some(&caps[1]));
+    }
+    // "March 2024" without a day (skipped when a full date already covers it)
+    for caps in my.captures_iter(text) {
+        let month = month_number(&caps[1]);
+        let prefix = format!("{}-{:02}", &caps[2], month);
+        if !dates.iter().any(|d| d.starts_with(&prefix)) {
+            push_date(&mut dates, &caps[2], month, None);
+        }
+    }
+    dates
+}
+
+fn push_date(dates: &mut Vec<String>, year: &str, month: u32, day: Option<&str>) {
+    let Ok(year) = year.parse::<u32>() else { return };
+    if !(1..=12).contains(&month) {
+        return;
+    }
+    let date = match day.map(|d| d.parse::<u32>()) {
+        Some(Ok(day)) if (1..=31).contains(&day) => format!("{:04}-{:02}-{:02}", year, month, day),
+        Some(_) => return,
+        None => format!("{:04}-{:02}", year, month),
+    };
+    if !dates.contains(&date) {
+        dates.push(date);
+    }
+}
+
+fn month_number(name: &str) -> u32 {
+    let lower = name.to_lowercase();
+    ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"]
+        .iter()
+        .position(|m| lower.starts_with(m))
+        .map(|i| i as u32 + 1)
+        .unwrap_or(0)
+}
+
+/// Runs of capitalized words and acronyms, lowercased and `_`-joined.
+/// A single capitalized word at the start of a sentence is ambiguous and skipped.
+pub fn extract_named_entities(text: &str) -> Vec<String> {
+    let stop = CAPITALIZED_STOPWORDS.get_or_init(|| {
+        [
+            "i", "monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday",
+            "january", "february", "march", "april", "may", "june", "july", "august",
+            "september", "october", "november", "december",
+        ].into_iter().collect()
+    });
+
+    let mut entities: Vec<String> = Vec::new();
+    let emit = |run: &mut Vec<String>, run_at_start: bool, entities: &mut Vec<String>| {
+        // Drop trailing connectors ("Bank of")
+        while run.last().is_some_and(|w| NAME_CONNECTORS.contains(&w.as_str())) {
+            run.pop();
+        }
+        let keep = match run.len() {
+            0 => false,
+            1 => !run_at_start && !stop.contains(run[0].as_str()),
+            n => n <= 4,
+        };
+        if keep {
+            let entity = run.join("_");
+            if !entities.contains(&entity) {
+                entities.push(entity);
+            }
+        }
+        run.clear();
+    };
+
+    for sentence in text.split(['.', '!', '?', '\n']) {
+        let mut run: Vec<String> = Vec::new();
+        let mut run_at_start = false;
+        let mut first = true;
+
+        for raw in sentence.split_whitespace() {
+            let word = raw.trim_matches(|c: char| !c.is_alphanumeric() && c != '&');
+            let breaks_after = raw.ends_with([',', ';', ':', ')', '"']);
+            let at_start = first;
+            first = false;
+            if word.is_empty() {
+                emit(&mut run, run_at_start, &mut entities);
+                continue;
+            }
+
+            if is_acronym(word) {
+                emit(&mut run, run_at_start, &mut entities);
+                let lower = word.to_lowercase();
+                if !ACRONYM_NOISE.contains(&lower.as_str()) && !entities.contains(&lower) {
+                    entities.push(lower);
+                }
+            } else if is_capitalized(word) && !(run.is_empty() && crate::nl::get_stopwords().contains(word.to_lowercase().as_str())) {
+                if run.is_empty() {
+                    run_at_start = at_start;
+                }
+                run.push(word.to_lowercase());
+            } else if !run.is_empty() && NAME_CONNECTORS.contains(&word) {
+                run.push(word.to_string());
+            } else {
+                emit(&mut run, run_at_start, &mut entities);
+            }
+
+            if breaks_after {
+                emit(&mut run, run_at_start, &mut entities);
+            }
+        }
+        emit(&mut run, run_at_start, &mut entities);
+    }
+    entities
+}
+
+/// "Alice", "O'Brien" (but not "getUser", "HttpClient" or "HTTP", which are symbols/acronyms)
+fn is_capitalized(word: &str) -> bool {
+    let mut chars = word.chars();
+    let Some(first) = chars.next() else { return false };
+    if !first.is_uppercase() {
+        return false;
+    }
+    let rest: Vec<char> = chars.collect();
+    rest.iter().any(|c| c.is_lowercase())
+        && rest.iter().all(|c| c.is_lowercase() || *c == '\'' || *c == '-')
+}
+
+/// "AWS", "NASA": 2-6 uppercase letters
+fn is_acronym(word: &str) -> bool {
+    (2..=6).contains(&word.len()) && word.chars().all(|c| c.is_ascii_uppercase())
+}
+
+/// Code identifiers with their parts: `getUserById` -> (`get_user_by_id`, [get, user, by, id]).
+/// Only identifiers with at least two parts (camelCase, PascalCase, snake_case) count.
+pub fn extract_symbols(text: &str) -> Vec<(String, Vec<String>)> {
+    let identifier = IDENTIFIER_REGEX.get_or_init(|| Regex::new(r"\b[A-Za-z_][A-Za-z0-9_]*\b").unwrap());
+
+    let mut symbols: Vec<(String, Vec<String>)> = Vec::new();
+    for m in identifier.find_iter(text) {
+        let ident = m.as_str().trim_matches('_');
+        if ident.len() < 4 {
+            continue;
+        }
+        let digits = ident.chars().filter(|c| c.is_ascii_digit()).count();
+        if digits * 3 > ident.len() {
+            continue; // hash-like
+        }
+        let parts = split_identifier(ident);
+        if parts.len() < 2 || parts.iter().all(|p| p.len() <= 1) {
+            continue;
+        }
+        let symbol = parts.join("_");
+        if !symbols.iter().any(|(s, _)| *s == symbol) {
+            symbols.push((symbol, parts));
+        }
+    }
+    symbols
+}
+
+/// Split on `_` and case changes: `parseJSONResponse` -> [parse, json, response]
+pub fn split_identifier(ident: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    for chunk in ident.split('_').filter(|c| !c.is_empty()) {
+        let chars: Vec<char> = chunk.chars().collect();
+        let mut current = String::new();
+        for (i, &c) in chars.iter().enumerate() {
+            let boundary = i > 0 && c.is_uppercase() && {
+                let prev = chars[i - 1];
+                let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+                // fooBar | JSONResponse (the R starts a new word)
+                prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_lower)
+            };
+            if boundary && !current.is_empty() {
+                parts.push(std::mem::take(&mut current).to_lowercase());
+            }
+            current.push(c);
+        }
+        if !current.is_empty() {
+            parts.push(current.to_lowercase());
+        }
+    }
+    parts
+}
@@ -0,0 +1,604 @@
+//! Ranking evaluation against golden traces (`cuemap eval`).
+//!
+//! A suite file (YAML or JSON) lists queries with the memories each one should
+//! find (`relevant`, plain or graded) and, optionally, the ranking recorded
+//! from a known-good run (`golden`). Suites run either in-process, against a
+//! fresh engine seeded with the suite's `memories`, or against a live server.
+//! Each query is scored with MRR, NDCG@k and recall@k (k = its `limit`), and
+//! its results are diffed rank by rank against the golden trace.
+//!
+//! ```yaml
+//! name: payments
+//! memories:
+//!   - id: timeout
+//!     content: Payments service timed out calling the ledger
+//!     cues: [service:payments, error:timeout]
+//! queries:
+//!   - name: payments-timeout
+//!     cues: [service:payments, error:timeout]
+//!     relevant: [timeout]
+//! ```
+//!
+//! Memory ids in `relevant` and `golden` are the suite's own `id`s for seeded
+//! memories, and the server's memory ids otherwise. Recalls never reinforce,
+//! so running a suite does not change the rankings it measures.
+
+use crate::config::{CueGenStrategy, TuningConfig};
+use crate::embedded::{Client, RecallOptions};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Largest score difference from a golden trace that still matches
+pub const DEFAULT_EPSILON: f64 = 1e-3;
+
+/// Project the in-process engine seeds when a suite names none
+const DEFAULT_EVAL_PROJECT: &str = "eval";
+
+fn default_limit() -> usize {
+    10
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalSuite {
+    /// Defaults to the file name
+    #[serde(default)]
+    pub name: String,
+    /// Project the queries run in (a live run's `--project` wins)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Memories added before the queries run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub memories: Vec<EvalMemory>,
+    pub queries: Vec<EvalQuery>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalMemory {
+    /// Name the suite's judgments and golden traces use for this memory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub content: String,
+    /// Empty: cues are extracted from the content, like `POST /memories`
+    #[serde(default)]
+    pub cues: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, serde_json::Value>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalQuery {
+    pub name: String,
+    #[serde(default)]
+    pub cues: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_text: Option<String>,
+    /// Results fetched, and the k of NDCG@k and recall@k
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+    #[serde(default, skip_serializing_if = "Relevance::is_empty")]
+    pub relevant: Relevance,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub golden: Option<GoldenTrace>,
+}
+
+/// Memories a query should find: a list (all equally relevant) or a map of
+/// memory id to relevance grade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Relevance {
+    Ids(Vec<String>),
+    Graded(IndexMap<String, f64>),
+}
+
+impl Default for Relevance {
+    fn default() -> Self {
+        Relevance::Ids(Vec::new())
+    }
+}
+
+impl Relevance {
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Memories with a positive grade
+    pub fn len(&self) -> usize {
+        match self {
+            Relevance::Ids(ids) => ids.len(),
+            Relevance::Graded(grades) => grades.values().filter(|g| **g > 0.0).count(),
+        }
+    }
+
+    pub fn grade(&self, memory_id: &str) -> f64 {
+        match self {
+            Relevance::Ids(ids) => if ids.iter().any(|id| id == memory_id) { 1.0 } else { 0.0 },
+            Relevance::Graded(grades) => grades.get(memory_id).copied().unwrap_or(0.0).max(0.0),
+        }
+    }
+
+    fn grades(&self) -> Vec<f64> {
+        match self {
+            Relevance::Ids(ids) => vec![1.0; ids.len()],
+            Relevance::Graded(grades) => grades.values().copied().filter(|g| *g > 0.0).collect(),
+        }
+    }
+}
+
+/// A ranking recorded from a known-good run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GoldenTrace {
+    #[serde(default)]
+    pub query: String,
+    /// Cues the query was run with
+    #[serde(default)]
+    pub normalized_query: Vec<String>,
+    pub results: Vec<TraceResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TraceResult {
+    pub memory_id: String,
+    /// 1-based
+    pub rank: usize,
+    pub score: f64,
+    pub intersection_count: usize,
+}
+
+impl EvalSuite {
+    /// Parse a `.yaml`/`.yml` or JSON suite file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let data = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let mut suite: EvalSuite = if is_yaml(path) {
+            serde_yaml::from_str(&data).map_err(|e| format!("Invalid suite {}: {}", path.display(), e))?
+        } else {
+            serde_json::from_str(&data).map_err(|e| format!("Invalid suite {}: {}", path.display(), e))?
+        };
+        if suite.name.is_empty() {
+            suite.name = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        }
+        Ok(suite)
+    }
+
+    /// Write the suite back in the format of `path` (comments are not kept)
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let data = if is_yaml(path) {
+            serde_yaml::to_string(self).map_err(|e| e.to_string())?
+        } else {
+            serde_json::to_string_pretty(self).map_err(|e| e.to_string())?
+        };
+        std::fs::write(path, data).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Replace each query's golden trace with the ranking it got in `report`
+    pub fn record_golden(&mut self, report: &SuiteReport) {
+        for (query, result) in self.queries.iter_mut().zip(&report.queries) {
+            query.golden = Some(GoldenTrace {
+                query: query.query_text.clone().unwrap_or_default(),
+                normalized_query: query.cues.clone(),
+                results: result.results.clone(),
+            });
+        }
+    }
+}
+
+fn is_yaml(path: &Path) -> bool {
+    matches!(path.extension().and_then(|e| e.to_str()), Some("yaml" | "yml"))
+}
+
+/// `path` itself, or the suite files of a directory in name order
+pub fn suite_files(path: &Path) -> Result<Vec<PathBuf>, String> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files: Vec<PathBuf> = std::fs::read_dir(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && matches!(p.extension().and_then(|e| e.to_str()), Some("yaml" | "yml" | "json")))
+        .collect();
+    files.sort();
+    if files.is_empty() {
+        return Err(format!("No .yaml, .yml or .json suites in {}", path.display()));
+    }
+    Ok(files)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct RankingMetrics {
+    /// Reciprocal rank of the first relevant result (0 when none is returned)
+    pub mrr: f64,
+    pub ndcg: f64,
+    pub recall: f64,
+}
+
+/// Metrics of one ranking at cutoff `k`; `None` when the query has no judgments
+pub fn ranking_metrics(ranked: &[String], relevant: &Relevance, k: usize) -> Option<RankingMetrics> {
+    if relevant.is_empty() {
+        return None;
+    }
+    let top = &ranked[..ranked.len().min(k)];
+    let mrr = ranked
+        .iter()
+        .position(|id| relevant.grade(id) > 0.0)
+        .map_or(0.0, |i| 1.0 / (i + 1) as f64);
+
+    let dcg = |grades: &mut dyn Iterator<Item = f64>| -> f64 {
+        grades.enumerate().map(|(i, g)| (2f64.powf(g) - 1.0) / ((i + 2) as f64).log2()).sum()
+    };
+    let mut ideal = relevant.grades();
+    ideal.sort_by(|a, b| b.total_cmp(a));
+    let idcg = dcg(&mut ideal.into_iter().take(k));
+    let ndcg = if idcg > 0.0 { dcg(&mut top.iter().map(|id| relevant.grade(id))) / idcg } else { 0.0 };
+
+    let found = top.iter().filter(|id| relevant.grade(id) > 0.0).count();
+    Some(RankingMetrics { mrr, ndcg, recall: found as f64 / relevant.len() as f64 })
+}
+
+/// Differences from `golden`, rank by rank over its results. Results past the
+/// golden trace's length are not compared.
+pub fn diff_trace(actual: &[TraceResult], golden: &GoldenTrace, epsilon: f64) -> Vec<String> {
+    let mut diffs = Vec::new();
+    for (i, expected) in golden.results.iter().enumerate() {
+        let rank = i + 1;
+        let Some(got) = actual.get(i) else {
+            diffs.push(format!("rank {}: expected {}, got nothing", rank, expected.memory_id));
+            continue;
+        };
+        if got.memory_id != expected.memory_id {
+            let moved = match actual.iter().find(|r| r.memory_id == expected.memory_id) {
+                Some(r) => format!("{} is now rank {}", expected.memory_id, r.rank),
+                None => format!("{} not returned", expected.memory_id),
+            };
+            diffs.push(format!("rank {}: expected {}, got {} ({})", rank, expected.memory_id, got.memory_id, moved));
+            continue;
+        }
+        if (got.score - expected.score).abs() > epsilon {
+            diffs.push(format!("rank {} ({}): score {:.4} -> {:.4}", rank, got.memory_id, expected.score, got.score));
+        }
+        if got.intersection_count != expected.intersection_count {
+            diffs.push(format!(
+                "rank {} ({}): intersection_count {} -> {}",
+                rank, got.memory_id, expected.intersection_count, got.intersection_count
+            ));
+        }
+    }
+    diffs
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryReport {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<RankingMetrics>,
+    pub results: Vec<TraceResult>,
+    /// Whether the query has a golden trace
+    pub golden: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub diffs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SuiteSummary {
+    /// Queries with relevance judgments (the means are over these)
+    pub judged: usize,
+    pub mean_mrr: f64,
+    pub mean_ndcg: f64,
+    pub mean_recall: f64,
+    pub golden_checked: usize,
+    pub golden_failed: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SuiteReport {
+    pub suite: String,
+    pub queries: Vec<QueryReport>,
+    pub summary: SuiteSummary,
+}
+
+impl SuiteReport {
+    fn new(suite: String, queries: Vec<QueryReport>) -> Self {
+        let judged: Vec<RankingMetrics> = queries.iter().filter_map(|q| q.metrics).collect();
+        let mean = |f: fn(&RankingMetrics) -> f64| {
+            if judged.is_empty() { 0.0 } else { judged.iter().map(f).sum::<f64>() / judged.len() as f64 }
+        };
+        let summary = SuiteSummary {
+            judged: judged.len(),
+            mean_mrr: mean(|m| m.mrr),
+            mean_ndcg: mean(|m| m.ndcg),
+            mean_recall: mean(|m| m.recall),
+            golden_checked: queries.iter().filter(|q| q.golden).count(),
+            golden_failed: queries.iter().filter(|q| !q.diffs.is_empty()).count(),
+        };
+        Self { suite, queries, summary }
+    }
+
+    /// Every golden trace matched
+    pub fn passed(&self) -> bool {
+        self.summary.golden_failed == 0
+    }
+}
+
+/// Two-sided p-value below which an A/B difference counts as significant
+pub const DEFAULT_SIGNIFICANCE: f64 = 0.05;
+
+/// Up to this many judged queries every sign assignment is tried; above it
+/// the permutation test samples `SAMPLED_PERMUTATIONS` of them
+const EXACT_PERMUTATION_MAX_QUERIES: usize = 16;
+const SAMPLED_PERMUTATIONS: usize = 10_000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricComparison {
+    pub metric: &'static str,
+    pub baseline: f64,
+    pub candidate: f64,
+    /// candidate - baseline
+    pub delta: f64,
+    pub p_value: f64,
+    pub significant: bool,
+}
+
+/// A query whose results differ between the two runs
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryChange {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ndcg_delta: Option<f64>,
+    pub baseline: Vec<String>,
+    pub candidate: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SuiteComparison {
+    pub suite: String,
+    /// Queries with judgments in both runs (the metrics are over these)
+    pub judged: usize,
+    pub metrics: Vec<MetricComparison>,
+    pub changed: Vec<QueryChange>,
+}
+
+impl SuiteComparison {
+    /// The candidate is significantly worse on some metric
+    pub fn regressed(&self) -> bool {
+        self.metrics.iter().any(|m| m.significant && m.delta < 0.0)
+    }
+}
+
+/// Compare two runs of the same suite, metric by metric, with a paired
+/// permutation test over the judged queries
+pub fn compare_reports(baseline: &SuiteReport, candidate: &SuiteReport, alpha: f64) -> SuiteComparison {
+    let pairs: Vec<(RankingMetrics, RankingMetrics)> = baseline
+        .queries
+        .iter()
+        .zip(&candidate.queries)
+        .filter_map(|(a, b)| Some((a.metrics?, b.metrics?)))
+        .collect();
+
+    let metrics = [
+        ("mrr", (|m: &RankingMetrics| m.mrr) as fn(&RankingMetrics) -> f64),
+        ("ndcg", |m| m.ndcg),
+        ("recall", |m| m.recall),
+    ]
+    .into_iter()
+    .map(|(metric, value)| {
+        let n = pairs.len().max(1) as f64;
+        let diffs: Vec<f64> = pairs.iter().map(|(a, b)| value(b) - value(a)).collect();
+        let p_value = paired_permutation_test(&diffs);
+        MetricComparison {
+            metric,
+            baseline: pairs.iter().map(|(a, _)| value(a)).sum::<f64>() / n,
+            candidate: pairs.iter().map(|(_, b)| value(b)).sum::<f64>() / n,
+            delta: diffs.iter().sum::<f64>() / n,
+            p_value,
+            significant: p_value < alpha,
+        }
+    })
+    .collect();
+
+    let ranked = |q: &QueryReport| q.results.iter().map(|r| r.memory_id.clone()).collect::<Vec<_>>();
+    let changed = baseline
+        .queries
+        .iter()
+        .zip(&candidate.queries)
+        .filter(|(a, b)| ranked(a) != ranked(b))
+        .map(|(a, b)| QueryChange {
+            name: a.name.clone(),
+            ndcg_delta: a.metrics.zip(b.metrics).map(|(a, b)| b.ndcg - a.ndcg),
+            baseline: ranked(a),
+            candidate: ranked(b),
+        })
+        .collect();
+
+    SuiteComparison { suite: baseline.suite.clone(), judged: pairs.len(), metrics, changed }
+}
+
+/// Two-sided p-value of the mean of paired differences under the null
+/// hypothesis that each difference's sign is arbitrary
+pub fn paired_permutation_test(diffs: &[f64]) -> f64 {
+    use rand::{Rng, SeedableRng};
+
+    let observed = diffs.iter().sum::<f64>().abs();
+    if observed < 1e-12 {
+        return 1.0;
+    }
+    // Sums within rounding of the observed one count as at least as extreme
+    let extreme = |signs: &mut dyn FnMut(usize) -> bool| {
+        let sum: f64 = diffs.iter().enumerate().map(|(i, d)| if signs(i) { -d } else { *d }).sum();
+        sum.abs() >= observed - 1e-12
+    };
+
+    if diffs.len() <= EXACT_PERMUTATION_MAX_QUERIES {
+        let total = 1u32 << diffs.len();
+        let hits = (0..total).filter(|mask| extreme(&mut |i| mask & (1u32 << i) != 0)).count();
+        return hits as f64 / total as f64;
+    }
+    // Fixed seed: the same runs always get the same p-value
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0x5eed);
+    let hits = (0..SAMPLED_PERMUTATIONS).filter(|_| extreme(&mut |_| rng.gen::<bool>())).count();
+    (hits + 1) as f64 / (SAMPLED_PERMUTATIONS + 1) as f64
+}
+
+/// Where a suite's queries run
+pub enum EvalTarget {
+    /// A fresh in-memory engine per suite, seeded with the suite's memories
+    InProcess { tuning: TuningConfig, cuegen_strategy: CueGenStrategy },
+    /// A running server. The suite's memories are only added with `seed`.
+    Http { url: String, api_key: Option<String>, project: Option<String>, seed: bool },
+}
+
+/// Run every query of `suite` against `target`
+pub async fn run_suite(suite: &EvalSuite, target: &EvalTarget, epsilon: f64) -> Result<SuiteReport, String> {
+    let rankings = match target {
+        EvalTarget::InProcess { tuning, cuegen_strategy } => run_in_process(suite, tuning.clone(), cuegen_strategy.clone()).await?,
+        EvalTarget::Http { url, api_key, project, seed } => {
+            let project = project.clone().or_else(|| suite.project.clone()).ok_or("A project is required for a live run (--project or the suite's `project`)")?;
+            run_http(suite, url.trim_end_matches('/'), api_key.as_deref(), &project, *seed).await?
+        }
+    };
+
+    let queries = suite
+        .queries
+        .iter()
+        .zip(rankings)
+        .map(|(query, results)| {
+            let ranked: Vec<String> = results.iter().map(|r| r.memory_id.clone()).collect();
+            QueryReport {
+                name: query.name.clone(),
+                metrics: ranking_metrics(&ranked, &query.relevant, query.limit),
+                golden: query.golden.is_some(),
+                diffs: query.golden.as_ref().map(|g| diff_trace(&results, g, epsilon)).unwrap_or_default(),
+                results,
+            }
+        })
+        .collect();
+    Ok(SuiteReport::new(suite.name.clone(), queries))
+}
+
+/// Memory ids as the suite names them
+struct IdMap(HashMap<String, String>);
+
+impl IdMap {
+    fn name(&self, memory_id: &str) -> String {
+        self.0.get(memory_id).cloned().unwrap_or_else(|| memory_id.to_string())
+    }
+
+    fn insert(&mut self, memory: &EvalMemory, memory_id: String) {
+        if let Some(id) = &memory.id {
+            self.0.insert(memory_id, id.clone());
+        }
+    }
+}
+
+async fn run_in_process(suite: &EvalSuite, tuning: TuningConfig, cuegen_strategy: CueGenStrategy) -> Result<Vec<Vec<TraceResult>>, String> {
+    let client = Client::builder()
+        .tuning(tuning)
+        .cuegen_strategy(cuegen_strategy)
+        .snapshot_interval(None)
+        .background_jobs(false)
+        .build()?;
+    let project = suite.project.as_deref().unwrap_or(DEFAULT_EVAL_PROJECT);
+
+    let mut ids = IdMap(HashMap::new());
+    for memory in &suite.memories {
+        let memory_id = client.add_memory(project, &memory.content, memory.cues.clone(), memory.metadata.clone()).await?;
+        ids.insert(memory, memory_id);
+    }
+
+    let mut rankings = Vec::with_capacity(suite.queries.len());
+    for query in &suite.queries {
+        let results = client
+            .recall_with(project, RecallOptions {
+                cues: query.cues.clone(),
+                query_text: query.query_text.clone(),
+                limit: query.limit,
+                auto_reinforce: false,
+                ..RecallOptions::default()
+            })
+            .await
+            .map_err(|e| format!("Query '{}': {}", query.name, e))?;
+        rankings.push(
+            results
+                .iter()
+                .enumerate()
+                .map(|(i, r)| TraceResult {
+                    memory_id: ids.name(&r.memory_id),
+                    rank: i + 1,
+                    score: r.score,
+                    intersection_count: r.intersection_count,
+                })
+                .collect(),
+        );
+    }
+    Ok(rankings)
+}
+
+async fn run_http(suite: &EvalSuite, url: &str, api_key: Option<&str>, project: &str, seed: bool) -> Result<Vec<Vec<TraceResult>>, String> {
+    if !suite.memories.is_empty() && !seed {
+        return Err(format!(
+            "Suite '{}' defines memories; pass --seed to add them to project '{}' first",
+            suite.name, project
+        ));
+    }
+    let server = Server { http: reqwest::Client::new(), url, api_key, project };
+
+    let mut ids = IdMap(HashMap::new());
+    for memory in &suite.memories {
+        let body = server.post("/memories", serde_json::json!({
+            "content": memory.content,
+            "cues": memory.cues,
+            "metadata": memory.metadata,
+        }))
+        .await?;
+        let memory_id = body.get("memory_id").and_then(|v| v.as_str()).ok_or("/memories: response has no memory_id")?;
+        ids.insert(memory, memory_id.to_string());
+    }
+
+    let mut rankings = Vec::with_capacity(suite.queries.len());
+    for query in &suite.queries {
+        let body = server.post("/recall", serde_json::json!({
+            "cues": query.cues,
+            "query_text": query.query_text,
+            "limit": query.limit,
+            "auto_reinforce": false,
+        }))
+        .await
+        .map_err(|e| format!("Query '{}': {}", query.name, e))?;
+        let results = body.get("results").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+        rankings.push(
+            results
+                .iter()
+                .enumerate()
+                .map(|(i, r)| TraceResult {
+                    memory_id: ids.name(r.get("memory_id").and_then(|v| v.as_str()).unwrap_or_default()),
+                    rank: i + 1,
+                    score: r.get("score").and_then(|v| v.as_f64()).unwrap_or(0.0),
+                    intersection_count: r.get("intersection_count").and_then(|v| v.as_u64()).unwrap_or(0) as usize,
+                })
+                .collect(),
+        );
+    }
+    Ok(rankings)
+}
+
+struct Server<'a> {
+    http: reqwest::Client,
+    url: &'a str,
+    api_key: Option<&'a str>,
+    project: &'a str,
+}
+
+impl Server<'_> {
+    async fn post(&self, path: &str, body: serde_json::Value) -> Result<serde_json::Value, String> {
+        let mut req = self.http.post(format!("{}{}", self.url, path)).header("X-Project-ID", self.project).json(&body);
+        if let Some(key) = self.api_key {
+            req = req.header("X-API-Key", key);
+        }
+        let res = req.send().await.map_err(|e| format!("{} failed: {}", path, e))?;
+        let status = res.status();
+        let body: serde_json::Value = res.json().await.map_err(|e| format!("{}: invalid response: {}", path, e))?;
+        if !status.is_success() {
+            return Err(format!("{} returned {}: {}", path, status, body));
+        }
+        Ok(body)
+    }
+}
@@ -0,0 +1,201 @@
+//! Read-replica (follower) mode: `cuemap start --follow <primary-url|cloud>`.
+//!
+//! A follower periodically compares each project's revision on the source with
+//! the one it last pulled, and replaces changed projects wholesale:
+//! - from a primary server via `GET /projects/:id/archive` (full project archive),
+//! - from a bucket written by `--cloud-replicate-interval` via the snapshot files.
+//!
+//! The follower serves recall traffic read-only and never writes snapshots of its own.
+
+use crate::multi_tenant::{MultiTenantEngine, ProjectStats};
+use crate::persistence::CloudBackupManager;
+use crate::replication::fetch_manifest;
+use ahash::RandomState;
+use dashmap::DashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info};
+
+pub enum FollowSource {
+    /// Another CueMap server
+    Primary { url: String, api_key: Option<String> },
+    /// A bucket kept up to date by a replicating primary
+    Bucket(Arc<CloudBackupManager>),
+}
+
+pub struct Follower {
+    mt_engine: Arc<MultiTenantEngine>,
+    source: FollowSource,
+    http: reqwest::Client,
+    // project -> source revision currently loaded
+    revisions: DashMap<String, String, RandomState>,
+}
+
+impl Follower {
+    pub fn new(mt_engine: Arc<MultiTenantEngine>, source: FollowSource) -> Self {
+        Self {
+            mt_engine,
+            source,
+            http: reqwest::Client::new(),
+            revisions: DashMap::with_hasher(RandomState::new()),
+        }
+    }
+
+    /// Spawn the sync loop (the first sync runs immediately)
+    pub fn start(self: Arc<Self>, interval: Duration) {
+        tokio::spawn(async move {
+            info!("Follower: syncing every {}s", interval.as_secs());
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.sync().await {
+                    Ok(updated) if !updated.is_empty() => info!("Follower: updated {} projects", updated.len()),
+                    Ok(_) => debug!("Follower: up to date"),
+                    Err(e) => error!("Follower: sync failed: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Pull every project whose revision changed and drop projects removed on the source.
+    /// Returns the ids of projects that were (re)loaded.
+    pub async fn sync(&self) -> Result<Vec<String>, String> {
+        let remote = self.remote_revisions().await?;
+        let mut updated = Vec::new();
+
+        for (project_id, revision) in &remote {
+            if self.revisions.get(project_id).map(|r| r.clone()).as_deref() == Some(revision.as_str()) {
+                continue;
+            }
+            match self.pull(project_id).await {
+                Ok(()) => {
+                    self.revisions.insert(project_id.clone(), revision.clone());
+                    updated.push(project_id.clone());
+                }
+                Err(e) => error!("Follower: failed to pull '{}': {}", project_id, e),
+            }
+        }
+
+        let live: HashSet<&String> = remote.iter().map(|(id, _)| id).collect();
+        let stale: Vec<String> = self.revisions
+            .iter()
+            .filter(|e| !live.contains(e.key()))
+            .map(|e| e.key().clone())
+            .collect();
+        for project_id in stale {
+            self.revisions.remove(&project_id);
+            self.mt_engine.delete_project(&project_id);
+            info!("Follower: dropped '{}' (removed on source)", project_id);
+        }
+
+        Ok(updated)
+    }
+
+    async fn remote_revisions(&self) -> Result<Vec<(String, String)>, String> {
+        match &self.source {
+            FollowSource::Primary { url, api_key } => {
+                let projects: Vec<ProjectStats> = self
+                    .get(&format!("{}/projects", url.trim_end_matches('/')), api_key.as_deref())
+                    .await?
+                    .json()
+                    .await
+                    .map_err(|e| format!("Invalid /projects response: {}", e))?;
+                Ok(projects.into_iter().map(|p| (p.project_id, p.revision)).collect())
+            }
+            FollowSource::Bucket(cloud) => {
+                let entries = cloud.list_snapshots().await.map_err(|e| e.to_string())?;
+                let mut revisions = Vec::with_capacity(entries.len());
+                for entry in entries {
+                    let revision = match fetch_manifest(cloud, &entry.project_id).await? {
+                        Some(manifest) => manifest.replicated_at.to_string(),
+                        None => entry.last_modified,
+                    };
+                    revisions.push((entry.project_id, revision));
+                }
+                Ok(revisions)
+            }
+        }
+    }
+
+    async fn pull(&self, project_id: &str) -> Result<(), String> {
+        let engine = self.mt_engine.clone();
+        let id = project_id.to_string();
+
+        match &self.source {
+            FollowSource::Primary { url, api_key } => {
+                let archive = self
+                    .get(&format!("{}/projects/{}/archive", url.trim_end_matches('/'), project_id), api_key.as_deref())
+                    .await?
+                    .bytes()
+                    .await
+                    .map_err(|e| e.to_string())?;
+                tokio::task::spawn_blocking(move || engine.restore_project(&id, &archive))
+                    .await
+                    .map_err(|e| e.to_string())??;
+            }
+            FollowSource::Bucket(cloud) => {
+                let (main, aliases, lexicon) = cloud
+                    .download_project_snapshot(project_id)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let meta = cloud.get_project_object(project_id, ".meta.json").await.map_err(|e| e.to_string())?;
+                let vectors = cloud.get_project_object(project_id, "_vectors.bin").await.map_err(|e| e.to_string())?;
+                let history = cloud.get_project_object(project_id, "_history.bin").await.map_err(|e| e.to_string())?;
+
+                tokio::task::spawn_blocking(move || {
+                    let dir = engine.snapshots_dir();
+                    // Through a temp file and a rename, so a crash or a concurrent load never sees
+                    // a torn file, and generations hard-linked to the old file keep their copy
+                    let write = |suffix: &str, data: &[u8]| {
+                        let path = dir.join(format!("{}{}", id, suffix));
+                        let temp_path = dir.join(format!("{}{}.tmp", id, suffix));
+                        std::fs::write(&temp_path, data)
+                            .and_then(|_| std::fs::rename(&temp_path, &path))
+                            .map_err(|e| format!("Failed to write {}{}: {}", id, suffix, e))
+                    };
+                    write(".bin", &main)?;
+                    if let Some(data) = &aliases {
+                        write("_aliases.bin", data)?;
+                    }
+                    if let Some(data) = &lexicon {
+                        write("_lexicon.bin", data)?;
+                    }
+                    if let Some(data) = &meta {
+                        write(".meta.json", data)?;
+                    }
+                    match &vectors {
+                        Some(data) => write("_vectors.bin", data)?,
+                        None => {
+                            let _ = std::fs::remove_file(dir.join(format!("{}_vectors.bin", id)));
+                        }
+                    }
+                    match &history {
+                        Some(data) => write("_history.bin", data)?,
+                        None => {
+                            let _ = std::fs::remove_file(dir.join(format!("{}_history.bin", id)));
+                        }
+                    }
+                    engine.load_project(&id).map(|_| ())
+                })
+                .await
+                .map_err(|e| e.to_string())??;
+            }
+        }
+
+        debug!("Follower: pulled '{}'", project_id);
+        Ok(())
+    }
+
+    async fn get(&self, url: &str, api_key: Option<&str>) -> Result<reqwest::Response, String> {
+        let mut request = self.http.get(url);
+        if let Some(key) = api_key {
+            request = request.header("X-API-Key", key);
+        }
+        let response = request.send().await.map_err(|e| format!("GET {}: {}", url, e))?;
+        if !response.status().is_success() {
+            return Err(format!("GET {}: HTTP {}", url, response.status()));
+        }
+        Ok(response)
+    }
+}
@@ -0,0 +1,73 @@
+//! Result fusion for cross-project recall.
+//!
+//! Scores of different projects aren't comparable: they depend on each
+//! project's cue frequencies, reinforcement history and size. A recall over
+//! several `projects` with `merge: true` ranks all results in one list by a
+//! fused score instead:
+//! - `rrf` (reciprocal rank fusion): `1 / (RRF_K + rank)`, using only each
+//!   result's rank within its project;
+//! - `zscore`: the score's distance from its project's mean score, in standard
+//!   deviations.
+
+use serde::{Deserialize, Serialize};
+
+/// Rank offset of reciprocal rank fusion; damps the weight of the very first ranks
+pub const RRF_K: f64 = 60.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FusionStrategy {
+    #[default]
+    Rrf,
+    ZScore,
+}
+
+/// A result's place in the merged ranking
+#[derive(Debug, Clone, PartialEq)]
+pub struct FusedHit {
+    /// Index of the result list it came from
+    pub list: usize,
+    /// Index of the result within that list
+    pub rank: usize,
+    pub fused_score: f64,
+}
+
+/// Merge per-project result lists, each sorted by score (best first), into one
+/// ranking of at most `limit` hits. Ties keep the better original score first,
+/// then the order of the lists.
+pub fn fuse(scores: &[Vec<f64>], strategy: FusionStrategy, limit: usize) -> Vec<FusedHit> {
+    let mut hits: Vec<(FusedHit, f64)> = Vec::new();
+    for (list, list_scores) in scores.iter().enumerate() {
+        let normalize = normalizer(list_scores, strategy);
+        for (rank, &score) in list_scores.iter().enumerate() {
+            let fused_score = match strategy {
+                FusionStrategy::Rrf => 1.0 / (RRF_K + rank as f64 + 1.0),
+                FusionStrategy::ZScore => normalize(score),
+            };
+            hits.push((FusedHit { list, rank, fused_score }, score));
+        }
+    }
+
+    hits.sort_by(|(a, a_score), (b, b_score)| {
+        b.fused_score
+            .total_cmp(&a.fused_score)
+            .then(b_score.total_cmp(a_score))
+            .then(a.list.cmp(&b.list))
+            .then(a.rank.cmp(&b.rank))
+    });
+    hits.into_iter().take(limit).map(|(hit, _)| hit).collect()
+}
+
+/// z-score against the list's mean and standard deviation (0 when all scores are equal)
+fn normalizer(scores: &[f64], strategy: FusionStrategy) -> impl Fn(f64) -> f64 {
+    let (mean, std_dev) = match strategy {
+        FusionStrategy::ZScore if !scores.is_empty() => {
+            let n = scores.len() as f64;
+            let mean = scores.iter().sum::<f64>() / n;
+            let variance = scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n;
+            (mean, variance.sqrt())
+        }
+        _ => (0.0, 0.0),
+    };
+    move |score| if std_dev > f64::EPSILON { (score - mean) / std_dev } else { 0.0 }
+}
@@ -0,0 +1,96 @@
+//! Typo-tolerant cue matching.
+//!
+//! Query cues without an exact hit in the cue index are compared against the
+//! indexed cues with an optimal-string-alignment distance (Levenshtein plus
+//! adjacent transpositions, so `kuberentes` is one edit from `kubernetes`).
+//! The scan is brute force over the index keys with a length prefilter and a
+//! banded, early-exit distance (over bytes for ASCII cues), which keeps it in
+//! the low milliseconds for per-project vocabularies.
+
+use serde::Serialize;
+
+/// Weight multiplier per edit for a corrected cue
+pub const FUZZY_WEIGHT_DECAY: f64 = 0.8;
+
+/// Corrections kept per query cue (closest and most used first)
+pub const FUZZY_MAX_CORRECTIONS: usize = 3;
+
+/// A query cue replaced by an indexed cue within the edit budget
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FuzzyCorrection {
+    pub query_cue: String,
+    pub cue: String,
+    pub distance: usize,
+}
+
+/// Edits allowed for a cue of `len` characters: none for 1-2, one for 3-5,
+/// two beyond, capped by `max`
+pub fn allowed_distance(len: usize, max: usize) -> usize {
+    let auto = match len {
+        0..=2 => 0,
+        3..=5 => 1,
+        _ => 2,
+    };
+    auto.min(max)
+}
+
+/// Optimal string alignment distance between `a` and `b`, or `None` when it exceeds `max`
+pub fn osa_distance(a: &str, b: &str, max: usize) -> Option<usize> {
+    if a == b {
+        return Some(0);
+    }
+    // Cues are mostly ASCII: compare bytes without collecting chars
+    if a.is_ascii() && b.is_ascii() {
+        return banded_osa(a.as_bytes(), b.as_bytes(), max);
+    }
+    if a.chars().count().abs_diff(b.chars().count()) > max {
+        return None;
+    }
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    banded_osa(&a, &b, max)
+}
+
+/// OSA distance over the diagonal band `|i - j| <= max`; cells outside it
+/// can't lead to a distance within `max`, so they are never computed
+fn banded_osa<T: PartialEq>(a: &[T], b: &[T], max: usize) -> Option<usize> {
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+
+    // Anything above `max` is as good as infinite
+    let out = max + 1;
+    let width = b.len() + 1;
+    let mut prev2 = vec![out; width];
+    let mut prev: Vec<usize> = (0..width).collect();
+    let mut curr = vec![out; width];
+
+    for i in 1..=a.len() {
+        let lo = i.saturating_sub(max).max(1);
+        let hi = (i + max).min(b.len());
+        // Left of the band: the first column, or a cell the next cells must not use
+        curr[lo - 1] = if lo == 1 { i } else { out };
+        let mut row_min = curr[lo - 1];
+        for j in lo..=hi {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut d = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d = d.min(prev2[j - 2] + 1);
+            }
+            curr[j] = d;
+            row_min = row_min.min(d);
+        }
+        // Right of the band, read by the next row
+        if hi < b.len() {
+            curr[hi + 1] = out;
+        }
+        if row_min > max {
+            return None;
+        }
+        std::mem::swap(&mut prev2, &mut prev);
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    let distance = prev[b.len()];
+    (distance <= max).then_some(distance)
+}
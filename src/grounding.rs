@@ -1,4 +1,5 @@
 use crate::engine::RecallResult;
+use crate::tokenizer::{CharTokenizer, Tokenizer};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +24,14 @@ pub struct ExcludedItem {
     pub reason: String,
 }
 
+/// Inline marker in the context block and the memory it cites
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Citation {
+    /// `[1]`, `[2]`, ... as written in `verified_context`
+    pub marker: String,
+    pub memory_id: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GroundingProof {
     pub trace_id: String,
@@ -30,83 +39,111 @@ pub struct GroundingProof {
     pub normalized_query: Vec<String>,
     pub expanded_cues: Vec<(String, f64)>,
     pub token_budget: u32,
+    /// Token counter the budget was packed with (`chars`, `bpe`, `whitespace`)
+    #[serde(default)]
+    pub tokenizer: String,
+    /// Tokens of the full context block, framing included
+    #[serde(default)]
+    pub tokens_used: u32,
     pub selected: Vec<SelectedItem>,
     pub excluded_top: Vec<ExcludedItem>,
+    /// One entry per selected memory, in context order
+    #[serde(default)]
+    pub citations: Vec<Citation>,
+}
+
+impl GroundingProof {
+    /// Citations whose marker appears in `text`, e.g. an answer written from
+    /// the context block
+    pub fn citations_in(&self, text: &str) -> Vec<Citation> {
+        self.citations.iter().filter(|c| text.contains(&c.marker)).cloned().collect()
+    }
 }
 
+const CONTEXT_OPEN: &str = "[VERIFIED CONTEXT]\n";
+const CONTEXT_CLOSE: &str = "[/VERIFIED CONTEXT]";
+
 pub struct GroundingEngine;
 
 impl GroundingEngine {
     /// Estimates tokens based on character count (1 token ~= 4 chars)
     pub fn estimate_tokens(content: &str) -> u32 {
-        ((content.len() as f64) / 4.0).ceil() as u32
+        CharTokenizer.count_tokens(content)
     }
 
+    /// Greedily packs `results` into `token_budget`. Each memory is costed as
+    /// the line it renders to in the context block, and the block's opening
+    /// and closing markers are reserved up front, so the whole block fits.
     pub fn select_memories(
         _query_text: String,
         _normalized_query: Vec<String>,
         _expanded_cues: Vec<(String, f64)>,
         results: Vec<RecallResult>,
         token_budget: u32,
+        tokenizer: &dyn Tokenizer,
     ) -> (Vec<SelectedItem>, Vec<ExcludedItem>, String) {
         let mut selected = Vec::new();
         let mut excluded_top = Vec::new();
-        let mut current_tokens = 0;
+        let mut current_tokens = tokenizer.count_tokens(CONTEXT_OPEN) + tokenizer.count_tokens(CONTEXT_CLOSE);
 
         for result in results {
-            let tokens = Self::estimate_tokens(&result.content);
-            
-            if current_tokens + tokens <= token_budget {
-                let source = result.metadata
-                    .get("source")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("unknown")
-                    .to_string();
-                
-                let timestamp = result.metadata
-                    .get("timestamp")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string())
-                    .unwrap_or_else(|| {
-                        let secs = result.created_at as i64;
-                        let nanos = ((result.created_at - secs as f64) * 1_000_000_000.0) as u32;
-                        if let Some(dt) = chrono::DateTime::from_timestamp(secs, nanos) {
-                            dt.format("%Y-%m-%dT%H:%M:%SZ").to_string()
-                        } else {
-                            "unknown".to_string()
-                        }
-                    });
-
-                let why = format!(
-                    "Ranked #{} with score {:.2} ({} matches, integrity {:.2})",
-                    selected.len() + 1,
-                    result.score,
-                    result.intersection_count,
-                    result.match_integrity
-                );
-
-                selected.push(SelectedItem {
-                    memory_id: result.memory_id,
-                    content: result.content,
-                    score: result.score,
-                    intersection_count: result.intersection_count,
-                    recency_component: result.recency_score,
-                    reinforcement_component: result.reinforcement_score,
-                    match_integrity: result.match_integrity,
-                    source,
-                    timestamp,
-                    estimated_tokens: tokens,
-                    why,
+            let source = result.metadata
+                .get("source")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string();
+
+            let timestamp = result.metadata
+                .get("timestamp")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| {
+                    let secs = result.created_at as i64;
+                    let nanos = ((result.created_at - secs as f64) * 1_000_000_000.0) as u32;
+                    if let Some(dt) = chrono::DateTime::from_timestamp(secs, nanos) {
+                        dt.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+                    } else {
+                        "unknown".to_string()
+                    }
                 });
+
+            let why = format!(
+                "Ranked #{} with score {:.2} ({} matches, integrity {:.2})",
+                selected.len() + 1,
+                result.score,
+                result.intersection_count,
+                result.match_integrity
+            );
+
+            let mut item = SelectedItem {
+                memory_id: result.memory_id,
+                content: result.content,
+                score: result.score,
+                intersection_count: result.intersection_count,
+                recency_component: result.recency_score,
+                reinforcement_component: result.reinforcement_score,
+                match_integrity: result.match_integrity,
+                source,
+                timestamp,
+                estimated_tokens: 0,
+                why,
+            };
+            let tokens = tokenizer.count_tokens(&Self::format_context_line(selected.len() + 1, &item));
+
+            if current_tokens + tokens <= token_budget {
+                item.estimated_tokens = tokens;
+                selected.push(item);
                 current_tokens += tokens;
-            } else {
-                if excluded_top.len() < 5 { // Only track top 5 exclusions
-                    excluded_top.push(ExcludedItem {
-                        memory_id: result.memory_id,
-                        score: result.score,
-                        reason: format!("Exceeds remaining token budget (needs {}, has {})", tokens, token_budget - current_tokens),
-                    });
-                }
+            } else if excluded_top.len() < 5 { // Only track top 5 exclusions
+                excluded_top.push(ExcludedItem {
+                    memory_id: item.memory_id,
+                    score: item.score,
+                    reason: format!(
+                        "Exceeds remaining token budget (needs {}, has {})",
+                        tokens,
+                        token_budget.saturating_sub(current_tokens)
+                    ),
+                });
             }
         }
 
@@ -119,21 +156,31 @@ impl GroundingEngine {
             return "".to_string();
         }
 
-        let mut block = String::from("[VERIFIED CONTEXT]\n");
+        let mut block = String::from(CONTEXT_OPEN);
         for (idx, item) in selected.iter().enumerate() {
-            block.push_str(&format!(
-                "({}) {} (source={}, id={}, score={:.2}, ts={})\n",
-                idx + 1,
-                item.content,
-                item.source,
-                item.memory_id,
-                item.score,
-                item.timestamp
-            ));
+            block.push_str(&Self::format_context_line(idx + 1, item));
         }
-        block.push_str("[/VERIFIED CONTEXT]");
+        block.push_str(CONTEXT_CLOSE);
         block
     }
+
+    /// `[n]`, the citation marker of the n-th selected memory (1-based)
+    pub fn citation_marker(number: usize) -> String {
+        format!("[{}]", number)
+    }
+
+    /// One cited entry of the context block, trailing newline included
+    pub fn format_context_line(number: usize, item: &SelectedItem) -> String {
+        format!(
+            "{} {} (source={}, id={}, score={:.2}, ts={})\n",
+            Self::citation_marker(number),
+            item.content,
+            item.source,
+            item.memory_id,
+            item.score,
+            item.timestamp
+        )
+    }
 }
 
 pub fn create_grounding_proof(
@@ -151,6 +198,16 @@ pub fn create_grounding_proof(
         normalized_query,
         expanded_cues,
         token_budget,
+        tokenizer: String::new(),
+        tokens_used: 0,
+        citations: selected
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| Citation {
+                marker: GroundingEngine::citation_marker(idx + 1),
+                memory_id: item.memory_id.clone(),
+            })
+            .collect(),
         selected,
         excluded_top,
     }
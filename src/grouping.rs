@@ -0,0 +1,86 @@
+//! Grouped recall: `group_by` on `POST /recall` collapses results per value
+//! of a cue namespace (e.g. `path` for the source document of ingested
+//! chunks), keeping the best chunk of each group.
+//!
+//! Results are grouped after scoring (and reranking), out of
+//! `limit * GROUP_CANDIDATE_FACTOR` candidates, so a document with many
+//! matching chunks doesn't crowd the others out of the `limit` groups.
+
+use crate::engine::RecallResult;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Candidates fetched per requested group
+pub const GROUP_CANDIDATE_FACTOR: usize = 5;
+
+/// How a group's score is computed from its chunks' scores
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupScore {
+    /// Score of the best chunk
+    #[default]
+    Max,
+    /// Sum of the chunk scores, favouring documents that match throughout
+    Sum,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecallGroup {
+    /// Value of the grouping cue (e.g. the path); absent for a memory
+    /// without one, which forms a group of its own
+    pub key: Option<String>,
+    /// Group score, as chosen by `group_score`
+    pub score: f64,
+    /// Chunks of the group among the candidates
+    pub hits: usize,
+    /// Their ids, best first
+    pub memory_ids: Vec<String>,
+    pub best: RecallResult,
+}
+
+/// Validate a `group_by` namespace (`path` or `path:`)
+pub fn parse_namespace(group_by: &str) -> Result<String, String> {
+    let namespace = group_by.trim().trim_end_matches(':').to_lowercase();
+    if namespace.is_empty() || namespace.contains(':') {
+        return Err(format!("Invalid group_by '{}' (expected a cue namespace such as path)", group_by));
+    }
+    Ok(namespace)
+}
+
+/// Group `results` (sorted best first) by `key_of`, returning the `limit`
+/// best groups, best first
+pub fn group_results<F>(results: Vec<RecallResult>, limit: usize, score: GroupScore, key_of: F) -> Vec<RecallGroup>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut groups: Vec<RecallGroup> = Vec::new();
+    let mut by_key: HashMap<String, usize> = HashMap::new();
+
+    for result in results {
+        let key = key_of(&result.memory_id);
+        if let Some(&i) = key.as_ref().and_then(|k| by_key.get(k)) {
+            let group = &mut groups[i];
+            group.hits += 1;
+            group.memory_ids.push(result.memory_id.clone());
+            if score == GroupScore::Sum {
+                group.score += result.score;
+            }
+            continue;
+        }
+        if let Some(key) = &key {
+            by_key.insert(key.clone(), groups.len());
+        }
+        groups.push(RecallGroup {
+            key,
+            score: result.score,
+            hits: 1,
+            memory_ids: vec![result.memory_id.clone()],
+            best: result,
+        });
+    }
+
+    // Stable, so groups of equal score keep the order of their best chunks
+    groups.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    groups.truncate(limit);
+    groups
+}
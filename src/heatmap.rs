@@ -0,0 +1,160 @@
+//! Market heatmap: per-cue lifts added to the salience of memories carrying
+//! the cue, so currently "hot" topics rank higher.
+//!
+//! Entries come from two sources. `PUT /heatmap` sets externally computed
+//! trend signals, optionally with a TTL over which the lift decays linearly
+//! to zero. The `heatmap` job syncs the lexicon's trending cues. A set entry
+//! takes precedence over a trending one for the same cue. Recall reads
+//! `weights()`, which is recomputed on every change and decay pass rather
+//! than per query. Entries are saved with the project
+//! (`{project}_heatmap.bin`).
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Largest lift (either sign) a set entry may have
+pub const MAX_HEATMAP_WEIGHT: f32 = 10.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeatmapSource {
+    /// Set through `PUT /heatmap`
+    Manual,
+    /// Synced from the lexicon's trending cues
+    Trending,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeatmapEntry {
+    /// Lift when set, before decay
+    pub weight: f32,
+    pub source: HeatmapSource,
+    /// Unix seconds
+    pub set_at: u64,
+    /// Unix seconds at which the lift reaches zero and the entry is dropped
+    pub expires_at: Option<u64>,
+}
+
+impl HeatmapEntry {
+    /// The lift at `now`, decayed linearly over the entry's TTL
+    pub fn effective_weight(&self, now: u64) -> f32 {
+        match self.expires_at {
+            None => self.weight,
+            Some(expires_at) if now >= expires_at => 0.0,
+            Some(expires_at) => {
+                let ttl = expires_at.saturating_sub(self.set_at).max(1);
+                let remaining = expires_at - now.max(self.set_at);
+                self.weight * (remaining.min(ttl) as f32 / ttl as f32)
+            }
+        }
+    }
+}
+
+/// Body of `PUT /heatmap`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeatmapUpdateRequest {
+    /// Cue to lift; a weight of 0 removes the cue's entry
+    pub weights: HashMap<String, f32>,
+    /// Decay the lifts to zero over this many seconds (default: no decay)
+    #[serde(default)]
+    pub ttl_seconds: Option<u64>,
+    /// Remove every other set entry first (trending entries are kept)
+    #[serde(default)]
+    pub replace: bool,
+}
+
+/// A project's heatmap
+#[derive(Debug, Clone, Default)]
+pub struct MarketHeatmap {
+    entries: HashMap<String, HeatmapEntry>,
+    /// Effective lift per cue, as read by recall
+    weights: HashMap<String, f32>,
+}
+
+/// Entries saved next to the project snapshot (`{project}_heatmap.bin`)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HeatmapSnapshot {
+    pub entries: HashMap<String, HeatmapEntry>,
+}
+
+pub fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+impl MarketHeatmap {
+    pub fn from_snapshot(snapshot: HeatmapSnapshot) -> Self {
+        let mut heatmap = Self { entries: snapshot.entries, weights: HashMap::new() };
+        heatmap.decay(now_secs());
+        heatmap
+    }
+
+    /// Trending lifts of archives written before entries were kept
+    pub fn from_weights(weights: HashMap<String, f32>) -> Self {
+        let now = now_secs();
+        let mut heatmap = Self::default();
+        for (cue, weight) in weights {
+            heatmap.entries.insert(cue, HeatmapEntry { weight, source: HeatmapSource::Trending, set_at: now, expires_at: None });
+        }
+        heatmap.decay(now);
+        heatmap
+    }
+
+    pub fn to_snapshot(&self) -> HeatmapSnapshot {
+        HeatmapSnapshot { entries: self.entries.clone() }
+    }
+
+    /// Effective lift per cue
+    pub fn weights(&self) -> &HashMap<String, f32> {
+        &self.weights
+    }
+
+    pub fn entries(&self) -> &HashMap<String, HeatmapEntry> {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Apply a `PUT /heatmap` (cues already normalized and weights validated).
+    /// Returns the number of entries set and removed.
+    pub fn apply(&mut self, weights: HashMap<String, f32>, ttl_seconds: Option<u64>, replace: bool, now: u64) -> (usize, usize) {
+        let mut removed = 0;
+        if replace {
+            let before = self.entries.len();
+            self.entries.retain(|cue, e| e.source != HeatmapSource::Manual || weights.contains_key(cue));
+            removed += before - self.entries.len();
+        }
+        let expires_at = ttl_seconds.filter(|t| *t > 0).map(|t| now + t);
+        let mut set = 0;
+        for (cue, weight) in weights {
+            if weight == 0.0 {
+                removed += usize::from(self.entries.remove(&cue).is_some());
+            } else {
+                self.entries.insert(cue, HeatmapEntry { weight, source: HeatmapSource::Manual, set_at: now, expires_at });
+                set += 1;
+            }
+        }
+        removed += self.decay(now);
+        (set, removed)
+    }
+
+    /// Replace the trending entries; set entries keep their cues
+    pub fn sync_trending(&mut self, trending: HashMap<String, f32>, now: u64) {
+        self.entries.retain(|_, e| e.source != HeatmapSource::Trending);
+        for (cue, weight) in trending {
+            self.entries.entry(cue).or_insert(HeatmapEntry { weight, source: HeatmapSource::Trending, set_at: now, expires_at: None });
+        }
+        self.decay(now);
+    }
+
+    /// Drop expired entries and recompute the effective lifts. Returns the
+    /// number of entries dropped.
+    pub fn decay(&mut self, now: u64) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|_, e| e.expires_at.is_none_or(|at| now < at));
+        self.weights = self.entries.iter().map(|(cue, e)| (cue.clone(), e.effective_weight(now))).collect();
+        before - self.entries.len()
+    }
+}
@@ -0,0 +1,146 @@
+//! Version history of memories overwritten in place.
+//!
+//! `CueMapEngine::upsert_memory_with_id` replaces the content, cues and
+//! metadata of an existing memory. When history is enabled, the replaced state
+//! is kept here (newest last, at most `max_versions` per memory) so it can be
+//! listed and rolled back. Content stays in its stored form (compressed or
+//! encrypted), exactly as it was on the memory.
+
+use ahash::RandomState;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A replaced state of a memory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryVersion {
+    /// 1 for the first content a memory had, increasing with every overwrite
+    pub version: u64,
+    /// Unix timestamp (seconds) when this version was replaced
+    pub replaced_at: f64,
+    /// Payload as stored on the memory (compressed or encrypted)
+    pub content: Vec<u8>,
+    pub cues: Vec<String>,
+    pub metadata: HashMap<String, serde_json::Value>,
+}
+
+/// On-disk form of a `MemoryHistory` (`{project}_history.bin`)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HistorySnapshot {
+    pub versions: HashMap<String, Vec<MemoryVersion>>,
+}
+
+pub struct MemoryHistory {
+    versions: DashMap<String, VecDeque<MemoryVersion>, RandomState>,
+    // 0 disables history
+    max_versions: AtomicUsize,
+}
+
+impl Default for MemoryHistory {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl MemoryHistory {
+    pub fn new(max_versions: usize) -> Self {
+        Self {
+            versions: DashMap::with_hasher(RandomState::new()),
+            max_versions: AtomicUsize::new(max_versions),
+        }
+    }
+
+    pub fn from_snapshot(snapshot: HistorySnapshot, max_versions: usize) -> Self {
+        let history = Self::new(max_versions);
+        for (id, versions) in snapshot.versions {
+            history.versions.insert(id, versions.into());
+        }
+        history
+    }
+
+    pub fn to_snapshot(&self) -> HistorySnapshot {
+        HistorySnapshot {
+            versions: self
+                .versions
+                .iter()
+                .map(|e| (e.key().clone(), e.value().iter().cloned().collect()))
+                .collect(),
+        }
+    }
+
+    pub fn max_versions(&self) -> usize {
+        self.max_versions.load(Ordering::Relaxed)
+    }
+
+    /// Change the cap; existing histories are trimmed on their next write
+    pub fn set_max_versions(&self, max_versions: usize) {
+        self.max_versions.store(max_versions, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_versions() > 0
+    }
+
+    /// Memories with at least one stored version
+    pub fn len(&self) -> usize {
+        self.versions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.versions.is_empty()
+    }
+
+    /// Version number the current state of a memory has (1 without history)
+    pub fn current_version(&self, id: &str) -> u64 {
+        self.versions
+            .get(id)
+            .and_then(|v| v.back().map(|last| last.version + 1))
+            .unwrap_or(1)
+    }
+
+    /// Keep the state a memory is about to lose. Returns its version number.
+    pub fn record(&self, id: &str, content: Vec<u8>, cues: Vec<String>, metadata: HashMap<String, serde_json::Value>, replaced_at: f64) -> u64 {
+        let max = self.max_versions();
+        let mut versions = self.versions.entry(id.to_string()).or_default();
+        let version = versions.back().map(|last| last.version + 1).unwrap_or(1);
+        versions.push_back(MemoryVersion { version, replaced_at, content, cues, metadata });
+        while versions.len() > max {
+            versions.pop_front();
+        }
+        version
+    }
+
+    /// Stored versions of a memory, oldest first
+    pub fn versions(&self, id: &str) -> Vec<MemoryVersion> {
+        self.versions
+            .get(id)
+            .map(|v| v.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn get(&self, id: &str, version: u64) -> Option<MemoryVersion> {
+        self.versions
+            .get(id)
+            .and_then(|v| v.iter().find(|m| m.version == version).cloned())
+    }
+
+    pub fn remove(&self, id: &str) -> bool {
+        self.versions.remove(id).is_some()
+    }
+
+    /// Move the versions of `from` to `to`
+    pub fn rename(&self, from: &str, to: &str) -> bool {
+        match self.versions.remove(from) {
+            Some((_, versions)) => {
+                self.versions.insert(to.to_string(), versions);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn clear(&self) {
+        self.versions.clear();
+    }
+}
@@ -0,0 +1,121 @@
+//! Cue index shard statistics.
+//!
+//! The cue index is a sharded `DashMap`; every write to a cue takes that
+//! shard's write lock. `IndexShardStats` counts writes per shard and how many
+//! of them found the shard already locked, so a hot shard (usually one giant
+//! cue such as `source:agent`) shows up in `/metrics`. `CueIndexReport` adds a
+//! point-in-time view of cues per shard and the posting-list length
+//! distribution.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Upper bounds of the posting-list length histogram (the last bucket is +Inf)
+pub const LIST_LENGTH_BUCKETS: &[usize] = &[1, 10, 100, 1_000, 10_000, 100_000];
+
+pub struct IndexShardStats {
+    writes: Vec<AtomicU64>,
+    contended: Vec<AtomicU64>,
+}
+
+impl IndexShardStats {
+    pub fn new(shards: usize) -> Self {
+        Self {
+            writes: (0..shards).map(|_| AtomicU64::new(0)).collect(),
+            contended: (0..shards).map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+
+    /// Count a write to `shard`; `contended` when the shard lock was already held
+    pub fn record(&self, shard: usize, contended: bool) {
+        if let Some(writes) = self.writes.get(shard) {
+            writes.fetch_add(1, Ordering::Relaxed);
+        }
+        if contended {
+            if let Some(count) = self.contended.get(shard) {
+                count.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn writes(&self, shard: usize) -> u64 {
+        self.writes.get(shard).map(|w| w.load(Ordering::Relaxed)).unwrap_or(0)
+    }
+
+    pub fn contended(&self, shard: usize) -> u64 {
+        self.contended.get(shard).map(|c| c.load(Ordering::Relaxed)).unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ShardStats {
+    pub shard: usize,
+    pub cues: usize,
+    pub writes: u64,
+    pub contended_writes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CueIndexReport {
+    pub shards: Vec<ShardStats>,
+    /// Cumulative counts of posting lists with at most `LIST_LENGTH_BUCKETS[i]`
+    /// ids; the final entry counts every list
+    pub list_length_buckets: Vec<u64>,
+    /// Sum of all posting-list lengths
+    pub total_list_length: u64,
+    pub max_list_length: usize,
+    pub hottest_cue: Option<String>,
+    /// Cues whose posting list has been split into more than one segment
+    pub segmented_cues: usize,
+}
+
+impl CueIndexReport {
+    /// Count one posting list in the length histogram
+    pub fn observe_list(&mut self, cue: &str, len: usize, segments: usize) {
+        if self.list_length_buckets.is_empty() {
+            self.list_length_buckets = vec![0; LIST_LENGTH_BUCKETS.len() + 1];
+        }
+        for (bucket, bound) in self.list_length_buckets.iter_mut().zip(LIST_LENGTH_BUCKETS) {
+            if len <= *bound {
+                *bucket += 1;
+            }
+        }
+        if let Some(total) = self.list_length_buckets.last_mut() {
+            *total += 1;
+        }
+        self.total_list_length += len as u64;
+        if len > self.max_list_length || self.hottest_cue.is_none() {
+            self.max_list_length = len;
+            self.hottest_cue = Some(cue.to_string());
+        }
+        if segments > 1 {
+            self.segmented_cues += 1;
+        }
+    }
+
+    /// Fold another engine's report into this one (shards are summed by index)
+    pub fn merge(&mut self, other: &CueIndexReport) {
+        for shard in &other.shards {
+            match self.shards.get_mut(shard.shard) {
+                Some(mine) => {
+                    mine.cues += shard.cues;
+                    mine.writes += shard.writes;
+                    mine.contended_writes += shard.contended_writes;
+                }
+                None => self.shards.push(shard.clone()),
+            }
+        }
+        if self.list_length_buckets.len() < other.list_length_buckets.len() {
+            self.list_length_buckets.resize(other.list_length_buckets.len(), 0);
+        }
+        for (mine, theirs) in self.list_length_buckets.iter_mut().zip(&other.list_length_buckets) {
+            *mine += theirs;
+        }
+        self.total_list_length += other.total_list_length;
+        if other.max_list_length > self.max_list_length || self.hottest_cue.is_none() {
+            self.max_list_length = other.max_list_length;
+            self.hottest_cue = other.hottest_cue.clone();
+        }
+        self.segmented_cues += other.segmented_cues;
+    }
+}
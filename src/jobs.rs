@@ -1,14 +1,15 @@
 use crate::multi_tenant::MultiTenantEngine;
 use crate::projects::ProjectContext;
-use crate::structures::{MainStats, LexiconStats};
-use crate::normalization::normalize_cue;
-use crate::taxonomy::validate_cues;
+use crate::structures::{CueField, MainStats, LexiconStats, Provenance};
 use crate::config::*;
 use crate::metrics::MetricsCollector;
+use crate::embeddings::{embed_memories, EmbeddingProvider};
+use crate::lexicon_quality::{prune_lexicon, record_expansions, LexiconPruneRequest};
+use crate::audit::{AuditAction, AuditEntry, SYSTEM_ACTOR};
 use std::sync::Arc;
-use tokio::sync::mpsc;
-use tracing::{info, warn, error, debug};
-use std::collections::HashSet;
+use tokio::sync::{oneshot, Notify, OwnedSemaphorePermit, Semaphore};
+use tracing::{info, warn, error, debug, debug_span, Instrument, Span};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use rayon::prelude::*;
 use smallvec::SmallVec;
 use uuid::Uuid;
@@ -21,19 +22,492 @@ pub const ALIAS_SAMPLE_SIZE: usize = 50;
 pub const ALIAS_SIZE_SIMILARITY_MAX_RATIO: f64 = 0.5;
 pub const ALIAS_OVERLAP_THRESHOLD: f64 = 0.65;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Job {
     ProposeCues { project_id: String, memory_id: String, content: String },
     TrainLexiconFromMemory { project_id: String, memory_id: String },
     ProposeAliases { project_id: String },
-    ExtractAndIngest { project_id: String, memory_id: String, content: String, file_path: String, structural_cues: Vec<String>, category: crate::agent::chunker::ChunkCategory },
+    ExtractAndIngest { project_id: String, memory_id: String, content: String, file_path: String, structural_cues: Vec<String>, category: crate::agent::chunker::ChunkCategory, provenance: Provenance },
     VerifyFile { project_id: String, file_path: String, valid_memory_ids: Vec<String> },
     UpdateGraph { project_id: String, memory_id: String },
     ReinforceMemories { project_id: String, memory_ids: Vec<String>, cues: Vec<String> },
-    ReinforceLexicon { project_id: String, memory_ids: Vec<String>, cues: Vec<String> },
+    /// `result_ids` are the memories the recall returned, to tally each
+    /// lexicon entry as a hit or a false expansion
+    ReinforceLexicon { project_id: String, memory_ids: Vec<String>, cues: Vec<String>, result_ids: Vec<String> },
     ConsolidateMemories { project_id: String },
-    UpdateMarketHeatmap { project_id: String },
+    /// Sync cues trending over the last `window_mins` minutes into the market heatmap
+    UpdateMarketHeatmap { project_id: String, window_mins: u32 },
     DeleteMemory { project_id: String, memory_id: String },
+    /// Move unchanged chunks whose line range shifted to their new ids, as
+    /// (from, to), and record the new line ranges
+    MoveMemories { project_id: String, moves: Vec<(String, String)>, provenance: Vec<(String, Provenance)> },
+    /// Compute embeddings with the project's provider (`force` re-embeds memories that have one)
+    EmbedMemories { project_id: String, memory_ids: Vec<String>, force: bool },
+    /// Store the facts the project's LLM finds in a chunk as memories of their own
+    ExtractFacts { project_id: String, memory_id: String, content: String, file_path: String },
+    /// Decay lexicon quality tallies and remove low-quality or unused entries
+    PruneLexicon { project_id: String, request: LexiconPruneRequest },
+}
+
+impl Job {
+    pub fn kind(&self) -> JobKind {
+        match self {
+            Job::ExtractAndIngest { .. } | Job::VerifyFile { .. } | Job::DeleteMemory { .. } | Job::MoveMemories { .. } => JobKind::Ingest,
+            Job::ReinforceMemories { .. } | Job::ReinforceLexicon { .. } => JobKind::Reinforce,
+            Job::ProposeCues { .. } => JobKind::ProposeCues,
+            Job::TrainLexiconFromMemory { .. } => JobKind::TrainLexicon,
+            Job::UpdateGraph { .. } => JobKind::UpdateGraph,
+            Job::EmbedMemories { .. } => JobKind::Embed,
+            Job::ProposeAliases { .. } => JobKind::ProposeAliases,
+            Job::ConsolidateMemories { .. } => JobKind::Consolidate,
+            Job::UpdateMarketHeatmap { .. } => JobKind::Heatmap,
+            Job::ExtractFacts { .. } => JobKind::ExtractFacts,
+            Job::PruneLexicon { .. } => JobKind::PruneLexicon,
+        }
+    }
+
+    pub fn project_id(&self) -> &str {
+        match self {
+            Job::ProposeCues { project_id, .. }
+            | Job::TrainLexiconFromMemory { project_id, .. }
+            | Job::ProposeAliases { project_id }
+            | Job::ExtractAndIngest { project_id, .. }
+            | Job::VerifyFile { project_id, .. }
+            | Job::UpdateGraph { project_id, .. }
+            | Job::ReinforceMemories { project_id, .. }
+            | Job::ReinforceLexicon { project_id, .. }
+            | Job::ConsolidateMemories { project_id }
+            | Job::UpdateMarketHeatmap { project_id, .. }
+            | Job::DeleteMemory { project_id, .. }
+            | Job::MoveMemories { project_id, .. }
+            | Job::EmbedMemories { project_id, .. }
+            | Job::ExtractFacts { project_id, .. }
+            | Job::PruneLexicon { project_id, .. } => project_id,
+        }
+    }
+}
+
+/// What a job does. Each kind has its own queue and concurrency limit in the
+/// worker pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    /// Chunk writes, deletes, moves and file verification. Run in order
+    /// within a project; the limit is how many projects ingest at once.
+    Ingest,
+    /// Recall feedback (memories and lexicon)
+    Reinforce,
+    ProposeCues,
+    TrainLexicon,
+    UpdateGraph,
+    Embed,
+    ExtractFacts,
+    ProposeAliases,
+    Consolidate,
+    Heatmap,
+    PruneLexicon,
+}
+
+impl JobKind {
+    pub const ALL: [JobKind; 11] = [
+        JobKind::Reinforce,
+        JobKind::Ingest,
+        JobKind::Embed,
+        JobKind::ProposeCues,
+        JobKind::TrainLexicon,
+        JobKind::UpdateGraph,
+        JobKind::ExtractFacts,
+        JobKind::ProposeAliases,
+        JobKind::Consolidate,
+        JobKind::Heatmap,
+        JobKind::PruneLexicon,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            JobKind::Ingest => "ingest",
+            JobKind::Reinforce => "reinforce",
+            JobKind::ProposeCues => "propose_cues",
+            JobKind::TrainLexicon => "train_lexicon",
+            JobKind::UpdateGraph => "update_graph",
+            JobKind::Embed => "embed",
+            JobKind::ExtractFacts => "extract_facts",
+            JobKind::ProposeAliases => "propose_aliases",
+            JobKind::Consolidate => "consolidate",
+            JobKind::Heatmap => "heatmap",
+            JobKind::PruneLexicon => "prune_lexicon",
+        }
+    }
+
+    /// Kinds with a higher priority get free workers first: feedback a user
+    /// is waiting on, then writes, then enrichment, then maintenance
+    pub fn priority(self) -> u8 {
+        match self {
+            JobKind::Reinforce => 3,
+            JobKind::Ingest => 2,
+            JobKind::Embed | JobKind::ProposeCues | JobKind::TrainLexicon | JobKind::UpdateGraph | JobKind::ExtractFacts => 1,
+            JobKind::ProposeAliases | JobKind::Consolidate | JobKind::Heatmap | JobKind::PruneLexicon => 0,
+        }
+    }
+
+    pub fn default_concurrency(self) -> usize {
+        match self {
+            JobKind::Reinforce => 4,
+            JobKind::Ingest | JobKind::ProposeCues | JobKind::Embed => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// Worker pool sizing and retries (`[jobs.workers]`)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct WorkerPoolConfig {
+    /// Jobs running at once, over all kinds
+    #[serde(default = "default_max_workers")]
+    pub max_workers: usize,
+    /// Jobs of one kind running at once, overriding `JobKind::default_concurrency`
+    #[serde(default)]
+    pub concurrency: HashMap<JobKind, usize>,
+    /// Runs of a failing job before it moves to the dead-letter list
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Wait before the first retry; doubles with every further attempt
+    #[serde(default = "default_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+    /// Jobs of one kind waiting for a worker; queuing one more waits for room
+    #[serde(default = "default_max_queued")]
+    pub max_queued: usize,
+}
+
+fn default_max_workers() -> usize {
+    8
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+fn default_retry_backoff_ms() -> u64 {
+    1000
+}
+
+fn default_max_queued() -> usize {
+    1000
+}
+
+impl Default for WorkerPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_workers: default_max_workers(),
+            concurrency: HashMap::new(),
+            max_attempts: default_max_attempts(),
+            retry_backoff_ms: default_retry_backoff_ms(),
+            max_queued: default_max_queued(),
+        }
+    }
+}
+
+impl WorkerPoolConfig {
+    pub fn limit(&self, kind: JobKind) -> usize {
+        self.concurrency.get(&kind).copied().unwrap_or_else(|| kind.default_concurrency()).max(1)
+    }
+
+    /// Wait before the retry that follows `attempts` failed runs
+    pub fn retry_delay(&self, attempts: u32) -> std::time::Duration {
+        let factor = 1u64 << attempts.saturating_sub(1).min(10);
+        std::time::Duration::from_millis(self.retry_backoff_ms.saturating_mul(factor))
+    }
+}
+
+/// Jobs of one kind waiting (for a worker or a retry) and running
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct QueueDepth {
+    pub queued: usize,
+    pub running: usize,
+}
+
+/// Failed jobs kept in the dead-letter list; older ones are dropped
+pub const MAX_FAILED_JOBS: usize = 1000;
+
+/// A job that failed on every attempt
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FailedJob {
+    pub id: String,
+    pub kind: JobKind,
+    pub project_id: String,
+    pub attempts: u32,
+    /// Error of the last attempt
+    pub error: String,
+    /// Unix seconds
+    pub failed_at: u64,
+    #[serde(skip)]
+    job: Job,
+}
+
+/// What `DELETE /jobs/:id` did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelOutcome {
+    /// Taken off its queue, or out of the wait before a retry
+    Cancelled,
+    /// Removed from the dead-letter list
+    Discarded,
+    /// Already running, which can't be interrupted
+    Running,
+    NotFound,
+}
+
+struct PendingJob {
+    id: String,
+    job: Job,
+    /// Failed runs so far
+    attempts: u32,
+    /// Set for jobs someone awaits (`WorkerPool::run`)
+    done: Option<oneshot::Sender<()>>,
+    /// Span that queued the job (e.g. an API request); the job's own span links to it
+    origin: Span,
+    /// Room the job takes in its kind's queue until it starts. Retries have
+    /// none, so a failing job never waits behind new ones.
+    slot: Option<OwnedSemaphorePermit>,
+}
+
+#[derive(Default)]
+struct PoolState {
+    queues: HashMap<JobKind, VecDeque<PendingJob>>,
+    running: HashMap<JobKind, usize>,
+    running_total: usize,
+    running_ids: HashSet<String>,
+    /// Projects with an ingest job running
+    ingesting: HashSet<String>,
+    /// Failed jobs waiting out their backoff, by id
+    retrying: HashMap<String, PendingJob>,
+    failed: VecDeque<FailedJob>,
+}
+
+/// Per-kind job queues drained by a bounded number of workers. A free
+/// worker takes the oldest job of the highest-priority kind that is under its
+/// concurrency limit, so a backlog of one kind can't hold up the others.
+/// Failed jobs are retried with exponential backoff, then kept in a
+/// dead-letter list. Each kind queues at most `max_queued` jobs; submitting
+/// more waits until workers make room.
+pub struct WorkerPool {
+    config: WorkerPoolConfig,
+    state: std::sync::Mutex<PoolState>,
+    wake: Notify,
+    room: HashMap<JobKind, Arc<Semaphore>>,
+}
+
+impl WorkerPool {
+    pub fn new(config: WorkerPoolConfig) -> Self {
+        let room = JobKind::ALL
+            .iter()
+            .map(|kind| (*kind, Arc::new(Semaphore::new(config.max_queued.max(1)))))
+            .collect();
+        Self {
+            config,
+            state: std::sync::Mutex::new(PoolState::default()),
+            wake: Notify::new(),
+            room,
+        }
+    }
+
+    /// Queue a job without waiting for it to run, once its kind's queue has
+    /// room. Returns its id.
+    pub async fn submit(&self, job: Job) -> String {
+        let id = Uuid::new_v4().to_string();
+        let slot = self.reserve(job.kind()).await;
+        self.push(PendingJob { id: id.clone(), job, attempts: 0, done: None, origin: Span::current(), slot });
+        id
+    }
+
+    /// Queue a job and wait until it has run (or failed for good)
+    pub async fn run(&self, job: Job) {
+        let (tx, rx) = oneshot::channel();
+        let slot = self.reserve(job.kind()).await;
+        self.push(PendingJob { id: Uuid::new_v4().to_string(), job, attempts: 0, done: Some(tx), origin: Span::current(), slot });
+        let _ = rx.await;
+    }
+
+    /// Wait for room in a kind's queue
+    async fn reserve(&self, kind: JobKind) -> Option<OwnedSemaphorePermit> {
+        self.room[&kind].clone().acquire_owned().await.ok()
+    }
+
+    fn push(&self, pending: PendingJob) {
+        let kind = pending.job.kind();
+        self.state.lock().unwrap().queues.entry(kind).or_default().push_back(pending);
+        self.wake.notify_one();
+    }
+
+    /// The job a free worker should take next, if any may start now
+    fn next(&self) -> Option<PendingJob> {
+        let mut state = self.state.lock().unwrap();
+        let PoolState { queues, running, running_total, running_ids, ingesting, .. } = &mut *state;
+        if *running_total >= self.config.max_workers.max(1) {
+            return None;
+        }
+
+        let mut kinds = JobKind::ALL.to_vec();
+        kinds.sort_by_key(|kind| std::cmp::Reverse(kind.priority()));
+        for kind in kinds {
+            if running.get(&kind).copied().unwrap_or(0) >= self.config.limit(kind) {
+                continue;
+            }
+            let Some(queue) = queues.get_mut(&kind) else { continue };
+            // Ingest jobs of a project run one at a time, in order
+            let index = match kind {
+                JobKind::Ingest => queue.iter().position(|p| !ingesting.contains(p.job.project_id())),
+                _ => (!queue.is_empty()).then_some(0),
+            };
+            let Some(mut pending) = index.and_then(|i| queue.remove(i)) else { continue };
+            // Leaving the queue makes room for a waiting submitter
+            pending.slot = None;
+
+            *running.entry(kind).or_default() += 1;
+            *running_total += 1;
+            running_ids.insert(pending.id.clone());
+            if kind == JobKind::Ingest {
+                ingesting.insert(pending.job.project_id().to_string());
+            }
+            return Some(pending);
+        }
+        None
+    }
+
+    fn finish(&self, kind: JobKind, project_id: &str, id: &str) {
+        {
+            let mut state = self.state.lock().unwrap();
+            if let Some(running) = state.running.get_mut(&kind) {
+                *running = running.saturating_sub(1);
+            }
+            state.running_total = state.running_total.saturating_sub(1);
+            state.running_ids.remove(id);
+            if kind == JobKind::Ingest {
+                state.ingesting.remove(project_id);
+            }
+        }
+        self.wake.notify_one();
+    }
+
+    /// Queue a failed job again once its backoff has passed, unless it's
+    /// cancelled in the meantime
+    fn retry_later(self: &Arc<Self>, pending: PendingJob) {
+        let delay = self.config.retry_delay(pending.attempts);
+        let id = pending.id.clone();
+        self.state.lock().unwrap().retrying.insert(id.clone(), pending);
+
+        let pool = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            let pending = pool.state.lock().unwrap().retrying.remove(&id);
+            if let Some(pending) = pending {
+                pool.push(pending);
+            }
+        });
+    }
+
+    fn dead_letter(&self, id: String, job: Job, attempts: u32, error: String) {
+        let failed = FailedJob {
+            id,
+            kind: job.kind(),
+            project_id: job.project_id().to_string(),
+            attempts,
+            error,
+            failed_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            job,
+        };
+        let mut state = self.state.lock().unwrap();
+        state.failed.push_back(failed);
+        while state.failed.len() > MAX_FAILED_JOBS {
+            state.failed.pop_front();
+        }
+    }
+
+    /// The dead-letter list, oldest first
+    pub fn failed(&self) -> Vec<FailedJob> {
+        self.state.lock().unwrap().failed.iter().cloned().collect()
+    }
+
+    /// Queue a dead-lettered job again with a fresh set of attempts. False if
+    /// no failed job has this id.
+    pub fn retry_failed(&self, id: &str) -> bool {
+        let failed = {
+            let mut state = self.state.lock().unwrap();
+            let index = state.failed.iter().position(|f| f.id == id);
+            index.and_then(|i| state.failed.remove(i))
+        };
+        match failed {
+            Some(failed) => {
+                self.push(PendingJob { id: failed.id, job: failed.job, attempts: 0, done: None, origin: Span::current(), slot: None });
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drop a queued or retrying job, or a dead-letter entry. Returns the
+    /// job too when it was cancelled before finishing.
+    pub fn cancel(&self, id: &str) -> (CancelOutcome, Option<Job>) {
+        let mut state = self.state.lock().unwrap();
+        if state.running_ids.contains(id) {
+            return (CancelOutcome::Running, None);
+        }
+        if let Some(pending) = state.retrying.remove(id) {
+            return (CancelOutcome::Cancelled, Some(pending.job));
+        }
+        for queue in state.queues.values_mut() {
+            if let Some(pending) = queue.iter().position(|p| p.id == id).and_then(|i| queue.remove(i)) {
+                return (CancelOutcome::Cancelled, Some(pending.job));
+            }
+        }
+        if let Some(i) = state.failed.iter().position(|f| f.id == id) {
+            state.failed.remove(i);
+            return (CancelOutcome::Discarded, None);
+        }
+        (CancelOutcome::NotFound, None)
+    }
+
+    /// Queued and running jobs of every kind
+    pub fn depths(&self) -> BTreeMap<JobKind, QueueDepth> {
+        let state = self.state.lock().unwrap();
+        JobKind::ALL
+            .iter()
+            .map(|kind| {
+                let retrying = state.retrying.values().filter(|p| p.job.kind() == *kind).count();
+                let depth = QueueDepth {
+                    queued: state.queues.get(kind).map(|q| q.len()).unwrap_or(0) + retrying,
+                    running: state.running.get(kind).copied().unwrap_or(0),
+                };
+                (*kind, depth)
+            })
+            .collect()
+    }
+
+    /// Jobs waiting (for a worker or a retry) per project
+    pub fn backlog_by_project(&self) -> HashMap<String, usize> {
+        let state = self.state.lock().unwrap();
+        let mut backlog: HashMap<String, usize> = HashMap::new();
+        for pending in state.queues.values().flatten().chain(state.retrying.values()) {
+            *backlog.entry(pending.job.project_id().to_string()).or_default() += 1;
+        }
+        backlog
+    }
+}
+
+/// Releases a worker slot when a job finishes, even if it panicked
+struct WorkerSlot {
+    pool: Arc<WorkerPool>,
+    kind: JobKind,
+    project_id: String,
+    id: String,
+}
+
+impl Drop for WorkerSlot {
+    fn drop(&mut self) {
+        self.pool.finish(self.kind, &self.project_id, &self.id);
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -54,6 +528,73 @@ pub struct JobProgress {
     pub train_lexicon_total: usize,
     pub update_graph_completed: usize,
     pub update_graph_total: usize,
+    /// Open explicit ingestion session, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// The agent's initial scan of its watch directory, once one has started
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scan: Option<ScanProgress>,
+}
+
+/// Where an agent's initial directory scan is
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct ScanProgress {
+    pub files_total: usize,
+    pub files_scanned: usize,
+    pub files_remaining: usize,
+    /// Files that could not be read or chunked
+    pub files_failed: usize,
+    pub bytes_total: u64,
+    pub bytes_processed: u64,
+    /// File being read and chunked right now
+    pub current_file: Option<String>,
+    /// Seconds since the scan started
+    pub elapsed_secs: u64,
+    /// Estimated seconds left, from the byte throughput so far
+    pub eta_secs: Option<u64>,
+    pub done: bool,
+}
+
+struct ScanState {
+    files_total: usize,
+    files_scanned: usize,
+    files_failed: usize,
+    bytes_total: u64,
+    bytes_processed: u64,
+    current_file: Option<String>,
+    started: std::time::Instant,
+    /// Scan duration, once finished
+    finished: Option<std::time::Duration>,
+}
+
+impl ScanState {
+    fn progress(&self) -> ScanProgress {
+        let elapsed = self.finished.unwrap_or_else(|| self.started.elapsed());
+        let eta_secs = if self.finished.is_some() {
+            Some(0)
+        } else if self.bytes_processed > 0 {
+            let remaining = self.bytes_total.saturating_sub(self.bytes_processed) as f64;
+            Some((elapsed.as_secs_f64() * remaining / self.bytes_processed as f64).ceil() as u64)
+        } else if self.files_scanned > 0 {
+            // Only empty files so far
+            let remaining = self.files_total.saturating_sub(self.files_scanned) as f64;
+            Some((elapsed.as_secs_f64() * remaining / self.files_scanned as f64).ceil() as u64)
+        } else {
+            None
+        };
+        ScanProgress {
+            files_total: self.files_total,
+            files_scanned: self.files_scanned,
+            files_remaining: self.files_total.saturating_sub(self.files_scanned),
+            files_failed: self.files_failed,
+            bytes_total: self.bytes_total,
+            bytes_processed: self.bytes_processed,
+            current_file: self.current_file.clone(),
+            elapsed_secs: elapsed.as_secs(),
+            eta_secs,
+            done: self.finished.is_some(),
+        }
+    }
 }
 
 /// Tracks a bulk ingestion session with buffered jobs
@@ -69,8 +610,16 @@ pub struct IngestionSession {
     pub train_lexicon_completed: std::sync::atomic::AtomicUsize,
     pub update_graph_completed: std::sync::atomic::AtomicUsize,
     last_write: tokio::sync::Mutex<std::time::Instant>,
+    /// Id of the explicit session (`POST /ingest/sessions`) holding the
+    /// buffered jobs until commit or abort; None falls back to auto-flush
+    hold: std::sync::Mutex<Option<String>>,
+    scan: std::sync::Mutex<Option<ScanState>>,
 }
 
+/// An explicit session idle this long is committed by auto-flush, so a
+/// client that never commits can't hold jobs back forever
+pub const EXPLICIT_SESSION_IDLE_SECS: u64 = 30 * 60;
+
 impl IngestionSession {
     pub fn new(project_id: String) -> Self {
         Self {
@@ -85,6 +634,8 @@ impl IngestionSession {
             train_lexicon_completed: std::sync::atomic::AtomicUsize::new(0),
             update_graph_completed: std::sync::atomic::AtomicUsize::new(0),
             last_write: tokio::sync::Mutex::new(std::time::Instant::now()),
+            hold: std::sync::Mutex::new(None),
+            scan: std::sync::Mutex::new(None),
         }
     }
     
@@ -112,8 +663,52 @@ impl IngestionSession {
             train_lexicon_total: 0,
             update_graph_completed: self.update_graph_completed.load(std::sync::atomic::Ordering::Relaxed),
             update_graph_total: 0,
+            session_id: self.held_by(),
+            scan: self.scan_progress(),
+        }
+    }
+
+    /// Start tracking an agent scan over `files_total` files of `bytes_total` bytes
+    pub fn start_scan(&self, files_total: usize, bytes_total: u64) {
+        *self.scan.lock().unwrap() = Some(ScanState {
+            files_total,
+            files_scanned: 0,
+            files_failed: 0,
+            bytes_total,
+            bytes_processed: 0,
+            current_file: None,
+            started: std::time::Instant::now(),
+            finished: None,
+        });
+    }
+
+    /// The scan moved on to `path`
+    pub fn scanning_file(&self, path: &str) {
+        if let Some(scan) = self.scan.lock().unwrap().as_mut() {
+            scan.current_file = Some(path.to_string());
+        }
+    }
+
+    /// The scan is done with a file of `bytes` bytes
+    pub fn file_scanned(&self, bytes: u64, failed: bool) {
+        if let Some(scan) = self.scan.lock().unwrap().as_mut() {
+            scan.files_scanned += 1;
+            scan.files_failed += failed as usize;
+            scan.bytes_processed += bytes;
+            scan.current_file = None;
         }
     }
+
+    pub fn finish_scan(&self) {
+        if let Some(scan) = self.scan.lock().unwrap().as_mut() {
+            scan.current_file = None;
+            scan.finished = Some(scan.started.elapsed());
+        }
+    }
+
+    pub fn scan_progress(&self) -> Option<ScanProgress> {
+        self.scan.lock().unwrap().as_ref().map(ScanState::progress)
+    }
     
     /// Buffer a job for later processing
     pub async fn buffer_job(&self, job: Job) {
@@ -145,9 +740,71 @@ impl IngestionSession {
         self.writes_total.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     }
     
-    /// Check if we should auto-flush (no writes for 2 seconds)
+    /// Open an explicit session: buffered jobs wait for `commit` or `abort`
+    /// instead of the auto-flush. Fails if one is already open.
+    pub async fn begin(&self) -> Result<String, String> {
+        let id = {
+            let mut hold = self.hold.lock().unwrap();
+            if let Some(open) = hold.as_ref() {
+                return Err(format!("Project '{}' already has an open ingestion session '{}'", self.project_id, open));
+            }
+            let id = Uuid::new_v4().to_string();
+            *hold = Some(id.clone());
+            id
+        };
+        // Reopen a finished session for the new batch
+        let _ = self.phase.compare_exchange(2, 0, std::sync::atomic::Ordering::Relaxed, std::sync::atomic::Ordering::Relaxed);
+        *self.last_write.lock().await = std::time::Instant::now();
+        Ok(id)
+    }
+
+    /// Id of the open explicit session, if any
+    pub fn held_by(&self) -> Option<String> {
+        self.hold.lock().unwrap().clone()
+    }
+
+    /// Close the explicit session `id`. False if it isn't the open one.
+    pub fn release(&self, id: &str) -> bool {
+        let mut hold = self.hold.lock().unwrap();
+        if hold.as_deref() == Some(id) {
+            *hold = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Drop every buffered job without running it. Returns how many there were.
+    pub async fn discard(&self) -> usize {
+        let dropped = std::mem::take(&mut *self.pending_propose_cues.lock().await).len()
+            + std::mem::take(&mut *self.pending_train_lexicon.lock().await).len()
+            + std::mem::take(&mut *self.pending_update_graph.lock().await).len();
+        let _ = self.phase.compare_exchange(0, 2, std::sync::atomic::Ordering::Relaxed, std::sync::atomic::Ordering::Relaxed);
+        dropped
+    }
+
+    /// Wait until every expected write has landed, so a flush sees all the
+    /// jobs they buffer
+    pub async fn writes_settled(&self) {
+        use std::sync::atomic::Ordering;
+
+        while self.writes_completed.load(Ordering::Relaxed) < self.writes_total.load(Ordering::Relaxed) {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+    }
+
+    /// Check if we should auto-flush (no writes for 2 seconds, and no open
+    /// explicit session)
     pub async fn should_auto_flush(&self) -> bool {
         let last = *self.last_write.lock().await;
+        if self.hold.lock().unwrap().is_some() {
+            if last.elapsed().as_secs() < EXPLICIT_SESSION_IDLE_SECS {
+                return false;
+            }
+            if let Some(id) = self.hold.lock().unwrap().take() {
+                warn!("[Jobs] Ingestion session {} for project '{}' idle for too long, committing", id, self.project_id);
+            }
+        }
         let writes_done = self.writes_completed.load(std::sync::atomic::Ordering::Relaxed);
         let writes_expected = self.writes_total.load(std::sync::atomic::Ordering::Relaxed);
         
@@ -158,11 +815,13 @@ impl IngestionSession {
     pub fn is_stale(&self) -> bool {
         let phase = self.phase.load(std::sync::atomic::Ordering::Relaxed);
         // If done/idle for more than 5 minutes
-        phase == 2 && self.writes_total.load(std::sync::atomic::Ordering::Relaxed) > 0 
+        phase == 2 && self.writes_total.load(std::sync::atomic::Ordering::Relaxed) > 0 && self.held_by().is_none()
     }
     
-    /// Flush and process all buffered jobs in order
-    pub async fn flush(&self, provider: &Arc<dyn ProjectProvider>, metrics: &Option<Arc<MetricsCollector>>) {
+    /// Flush and process all buffered jobs in order: every ProposeCues, then
+    /// every TrainLexicon, then every UpdateGraph, each phase spread over the
+    /// pool's workers
+    pub async fn flush(&self, pool: &WorkerPool) {
         use std::sync::atomic::Ordering;
         
         // Try to transition Writing -> Processing
@@ -185,25 +844,22 @@ impl IngestionSession {
                   total_propose, total_train, total_graph);
             
             // Process ProposeCues first
-            for (_i, (project_id, memory_id, content)) in propose_cues.into_iter().enumerate() {
-
-                process_job(Job::ProposeCues { project_id, memory_id, content }, provider, metrics).await;
+            futures::future::join_all(propose_cues.into_iter().map(|(project_id, memory_id, content)| async move {
+                pool.run(Job::ProposeCues { project_id, memory_id, content }).await;
                 self.propose_cues_completed.fetch_add(1, Ordering::Relaxed);
-            }
+            })).await;
             
             // Then TrainLexicon
-            for (_i, (project_id, memory_id)) in train_lexicon.into_iter().enumerate() {
-
-                process_job(Job::TrainLexiconFromMemory { project_id, memory_id }, provider, metrics).await;
+            futures::future::join_all(train_lexicon.into_iter().map(|(project_id, memory_id)| async move {
+                pool.run(Job::TrainLexiconFromMemory { project_id, memory_id }).await;
                 self.train_lexicon_completed.fetch_add(1, Ordering::Relaxed);
-            }
+            })).await;
             
             // Finally UpdateGraph
-            for (_i, (project_id, memory_id)) in update_graph.into_iter().enumerate() {
-
-                process_job(Job::UpdateGraph { project_id, memory_id }, provider, metrics).await;
+            futures::future::join_all(update_graph.into_iter().map(|(project_id, memory_id)| async move {
+                pool.run(Job::UpdateGraph { project_id, memory_id }).await;
                 self.update_graph_completed.fetch_add(1, Ordering::Relaxed);
-            }
+            })).await;
             
             debug!("[Jobs] All background jobs complete ✓");
         }
@@ -218,16 +874,14 @@ impl IngestionSession {
 /// Manages ingestion sessions per project
 pub struct SessionManager {
     sessions: dashmap::DashMap<String, Arc<IngestionSession>>,
-    provider: Arc<dyn ProjectProvider>,
-    metrics: Option<Arc<MetricsCollector>>,
+    pool: Arc<WorkerPool>,
 }
 
 impl SessionManager {
-    pub fn new(provider: Arc<dyn ProjectProvider>, metrics: Option<Arc<MetricsCollector>>) -> Self {
+    pub fn new(pool: Arc<WorkerPool>) -> Self {
         Self {
             sessions: dashmap::DashMap::new(),
-            provider,
-            metrics,
+            pool,
         }
     }
     
@@ -243,6 +897,8 @@ impl SessionManager {
             train_lexicon_total: 0,
             update_graph_completed: 0,
             update_graph_total: 0,
+            session_id: None,
+            scan: None,
         };
         
         let mut active_count = 0;
@@ -258,6 +914,19 @@ impl SessionManager {
             global.train_lexicon_total += p.train_lexicon_total;
             global.update_graph_completed += p.update_graph_completed;
             global.update_graph_total += p.update_graph_total;
+            if let Some(scan) = p.scan {
+                // Files are counted over all scans; time is the slowest one's
+                let total = global.scan.get_or_insert_with(|| ScanProgress { eta_secs: Some(0), done: true, ..Default::default() });
+                total.files_total += scan.files_total;
+                total.files_scanned += scan.files_scanned;
+                total.files_remaining += scan.files_remaining;
+                total.files_failed += scan.files_failed;
+                total.bytes_total += scan.bytes_total;
+                total.bytes_processed += scan.bytes_processed;
+                total.elapsed_secs = total.elapsed_secs.max(scan.elapsed_secs);
+                total.eta_secs = total.eta_secs.zip(scan.eta_secs).map(|(a, b)| a.max(b));
+                total.done &= scan.done;
+            }
             
             if p.phase != "idle" && p.phase != "done" {
                 active_count += 1;
@@ -288,9 +957,37 @@ impl SessionManager {
     /// Flush a specific session
     pub async fn flush_session(&self, project_id: &str) {
         if let Some(session) = self.get(project_id) {
-            session.flush(&self.provider, &self.metrics).await;
+            session.flush(&self.pool).await;
         }
     }
+
+    /// Open an explicit ingestion session for a project
+    pub async fn begin(&self, project_id: &str) -> Result<String, String> {
+        self.get_or_create(project_id).begin().await
+    }
+
+    /// Close explicit session `id` and run its buffered jobs once pending
+    /// writes have landed. False if it isn't the project's open session.
+    pub fn commit(self: &Arc<Self>, project_id: &str, id: &str) -> bool {
+        let Some(session) = self.get(project_id).filter(|s| s.release(id)) else {
+            return false;
+        };
+        let manager = self.clone();
+        tokio::spawn(async move {
+            session.writes_settled().await;
+            debug!("[Jobs] Committing ingestion session for project: {}", session.project_id);
+            session.flush(&manager.pool).await;
+        });
+        true
+    }
+
+    /// Close explicit session `id` and drop its buffered jobs. Memories it
+    /// wrote stay. None if it isn't the project's open session.
+    pub async fn abort(&self, project_id: &str, id: &str) -> Option<usize> {
+        let session = self.get(project_id).filter(|s| s.release(id))?;
+        session.writes_settled().await;
+        Some(session.discard().await)
+    }
     
     /// Start auto-flush background task
     pub fn start_auto_flush(self: Arc<Self>) {
@@ -315,7 +1012,7 @@ impl SessionManager {
                 // 2. Flush sessions outside the lock
                 for session in sessions_to_flush {
                     debug!("[Jobs] Auto-flushing session for project: {}", session.project_id);
-                    session.flush(&manager.provider, &manager.metrics).await;
+                    session.flush(&manager.pool).await;
                 }
                 
                 // Cleanup stale sessions every 30 iterations (60 seconds)
@@ -332,22 +1029,52 @@ impl SessionManager {
 }
 
 pub struct JobQueue {
-    sender: mpsc::Sender<Job>,
+    pool: Arc<WorkerPool>,
     pub session_manager: Arc<SessionManager>,
     pub metrics: Option<Arc<MetricsCollector>>,
 }
 
 // Abstraction to access projects regardless of mode
 pub trait ProjectProvider: Send + Sync + 'static {
+    /// The project, created if it doesn't exist (for ingestion)
     fn get_project(&self, project_id: &str) -> Option<Arc<ProjectContext>>;
+
+    /// The project if it exists, reloaded if it was archived, never created
+    /// (for jobs on existing memories)
+    fn get_or_load_project(&self, project_id: &str) -> Option<Arc<ProjectContext>> {
+        self.get_project(project_id)
+    }
+
     fn save_project(&self, project_id: &str) -> Result<(), String>;
     fn list_active_projects(&self) -> Vec<String>;
+
+    /// Check a write of new memories against the project's quota
+    fn check_quota(&self, _project_id: &str, _incoming_memories: usize, _incoming_bytes: u64) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Embedding provider configured for the project (None = no server-side embeddings)
+    fn embedding_provider(&self, _project_id: &str) -> Result<Option<Arc<dyn EmbeddingProvider>>, String> {
+        Ok(None)
+    }
+
+    /// LLM client of the project (None = one built from the project's `llm_config`)
+    fn llm_client(&self, _project_id: &str) -> Option<Arc<crate::llm::LlmClient>> {
+        None
+    }
+
+    /// Append to the audit log (jobs have no caller, so entries are the server's own)
+    fn record_audit(&self, _entry: AuditEntry) {}
 }
 
 impl ProjectProvider for MultiTenantEngine {
     fn get_project(&self, project_id: &str) -> Option<Arc<ProjectContext>> {
         self.get_or_create_project(project_id.to_string()).ok()
     }
+
+    fn get_or_load_project(&self, project_id: &str) -> Option<Arc<ProjectContext>> {
+        MultiTenantEngine::get_or_load_project(self, &project_id.to_string())
+    }
     
     fn save_project(&self, project_id: &str) -> Result<(), String> {
         self.save_project(&project_id.to_string()).map(|_| ())
@@ -356,73 +1083,137 @@ impl ProjectProvider for MultiTenantEngine {
     fn list_active_projects(&self) -> Vec<String> {
         self.list_projects().into_iter().map(|p| p.project_id).collect()
     }
+
+    fn check_quota(&self, project_id: &str, incoming_memories: usize, incoming_bytes: u64) -> Result<(), String> {
+        MultiTenantEngine::check_quota(self, project_id, incoming_memories, incoming_bytes).map_err(|e| e.to_string())
+    }
+
+    fn embedding_provider(&self, project_id: &str) -> Result<Option<Arc<dyn EmbeddingProvider>>, String> {
+        MultiTenantEngine::embedding_provider(self, project_id)
+    }
+
+    fn llm_client(&self, project_id: &str) -> Option<Arc<crate::llm::LlmClient>> {
+        Some(MultiTenantEngine::llm_client(self, project_id))
+    }
+
+    fn record_audit(&self, entry: AuditEntry) {
+        self.audit().record(entry);
+    }
 }
 
 
 
 impl JobQueue {
     pub fn new(provider: Arc<dyn ProjectProvider>, metrics: Option<Arc<MetricsCollector>>, disable_bg_jobs: bool) -> Self {
-        let (tx, mut rx) = mpsc::channel(1000);
+        Self::with_workers(provider, metrics, disable_bg_jobs, WorkerPoolConfig::default())
+    }
+
+    pub fn with_workers(
+        provider: Arc<dyn ProjectProvider>,
+        metrics: Option<Arc<MetricsCollector>>,
+        disable_bg_jobs: bool,
+        workers: WorkerPoolConfig,
+    ) -> Self {
+        let pool = Arc::new(WorkerPool::new(workers));
+        let session_manager = Arc::new(SessionManager::new(pool.clone()));
+
+        let dispatcher = pool.clone();
         let provider_clone = provider.clone();
-        let session_manager = Arc::new(SessionManager::new(provider.clone(), metrics.clone()));
         let session_manager_clone = session_manager.clone();
         let metrics_clone = metrics.clone();
-        
         tokio::spawn(async move {
-            while let Some(job) = rx.recv().await {
-                // Determine if this job should signal a session write completion
-                let project_for_completion = match &job {
-                    Job::ExtractAndIngest { project_id, .. } => Some(project_id.clone()),
-                    _ => None,
-                };
+            loop {
+                while let Some(pending) = dispatcher.next() {
+                    let slot = WorkerSlot {
+                        pool: dispatcher.clone(),
+                        kind: pending.job.kind(),
+                        project_id: pending.job.project_id().to_string(),
+                        id: pending.id.clone(),
+                    };
+                    let provider = provider_clone.clone();
+                    let sessions = session_manager_clone.clone();
+                    let metrics = metrics_clone.clone();
+                    tokio::spawn(async move {
+                        let PendingJob { id, job, attempts, done, origin, .. } = pending;
+                        let span = debug_span!("job", kind = job.kind().name(), project_id = job.project_id(), job_id = %id, attempt = attempts + 1);
+                        span.follows_from(&origin);
+                        let is_write = matches!(job, Job::ExtractAndIngest { .. });
+                        // Kept for a retry, should this run fail
+                        let retry = job.clone();
 
-                if !disable_bg_jobs {
-                    process_job(job, &provider_clone, &metrics_clone).await;
-                }
+                        // Flushed session jobs are awaited and run even with
+                        // background processing off, as before
+                        let result = if !disable_bg_jobs || done.is_some() {
+                            process_job(job, &provider, &metrics).instrument(span).await
+                        } else {
+                            Ok(())
+                        };
+                        let pool = slot.pool.clone();
+                        let project_id = slot.project_id.clone();
+                        drop(slot);
 
-                // If it was a write job, signal completion to the session
-                if let Some(pid) = project_for_completion {
-                    if let Some(session) = session_manager_clone.get(&pid) {
-                        debug!("[Jobs] Async write job complete for project: {}", pid);
-                        session.write_complete();
-                    }
+                        if let Err(e) = result {
+                            let attempts = attempts + 1;
+                            if attempts < pool.config.max_attempts {
+                                warn!("[Jobs] {} job {} failed (attempt {}), retrying: {}", retry.kind().name(), id, attempts, e);
+                                pool.retry_later(PendingJob { id, job: retry, attempts, done, origin, slot: None });
+                                return;
+                            }
+                            error!("[Jobs] {} job {} failed after {} attempts: {}", retry.kind().name(), id, attempts, e);
+                            pool.dead_letter(id, retry, attempts, e);
+                        }
+
+                        // If it was a write job, signal completion to the session
+                        if is_write {
+                            if let Some(session) = sessions.get(&project_id) {
+                                debug!("[Jobs] Async write job complete for project: {}", project_id);
+                                session.write_complete();
+                            }
+                        }
+                        if let Some(done) = done {
+                            let _ = done.send(());
+                        }
+                    });
                 }
+                dispatcher.wake.notified().await;
             }
         });
         
         if !disable_bg_jobs {
             session_manager.clone().start_auto_flush();
-            
-            // Background Task: Market Heatmap Sync (Every 60s)
-            let tx_sync = tx.clone();
-            let provider_sync = provider.clone();
-            tokio::spawn(async move {
-                let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
-                debug!("JobQueue: Market Heatmap Sync background task started");
-                loop {
-                    interval.tick().await;
-                    let projects = provider_sync.list_active_projects();
-                    debug!("JobQueue: Ticking Market Heatmap Sync ({} projects)", projects.len());
-                    // Trigger update for all active projects
-                    for pid in projects {
-                        let _ = tx_sync.send(Job::UpdateMarketHeatmap { project_id: pid }).await;
-                    }
-                }
-            });
         }
         
         Self { 
-            sender: tx,
+            pool,
             session_manager,
             metrics,
         }
     }
     
-    /// Enqueue a job immediately (for non-buffered jobs like Reinforce)
-    pub async fn enqueue(&self, job: Job) {
-        if let Err(e) = self.sender.send(job).await {
-            warn!("Failed to enqueue job: {}", e);
-        }
+    /// Background Task: Market Heatmap Sync. Every `interval`, queue a heatmap
+    /// update of each active project from the cues trending over the last
+    /// `window_mins` minutes (see `[jobs] market_heatmap_interval_seconds`).
+    pub fn start_heatmap_sync(&self, provider: Arc<dyn ProjectProvider>, interval: std::time::Duration, window_mins: u32) {
+        let pool_sync = self.pool.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            debug!("JobQueue: Market Heatmap Sync background task started");
+            loop {
+                interval.tick().await;
+                let projects = provider.list_active_projects();
+                debug!("JobQueue: Ticking Market Heatmap Sync ({} projects)", projects.len());
+                // Trigger update for all active projects
+                for pid in projects {
+                    pool_sync.submit(Job::UpdateMarketHeatmap { project_id: pid, window_mins }).await;
+                }
+            }
+        });
+    }
+
+    /// Enqueue a job immediately (for non-buffered jobs like Reinforce).
+    /// Returns the job id.
+    pub async fn enqueue(&self, job: Job) -> String {
+        self.pool.submit(job).await
     }
     
     /// Buffer a job for phased processing
@@ -455,6 +1246,38 @@ impl JobQueue {
     pub fn get_global_progress(&self) -> JobProgress {
         self.session_manager.get_global_progress()
     }
+
+    /// Queued and running jobs per kind
+    pub fn queue_depths(&self) -> BTreeMap<JobKind, QueueDepth> {
+        self.pool.depths()
+    }
+
+    /// Jobs waiting for a worker or a retry, per project
+    pub fn project_backlog(&self) -> HashMap<String, usize> {
+        self.pool.backlog_by_project()
+    }
+
+    /// Jobs that failed on every attempt, oldest first
+    pub fn failed_jobs(&self) -> Vec<FailedJob> {
+        self.pool.failed()
+    }
+
+    /// Queue a dead-lettered job again
+    pub fn retry_job(&self, id: &str) -> bool {
+        self.pool.retry_failed(id)
+    }
+
+    /// Drop a job that hasn't started, or a dead-letter entry
+    pub fn cancel_job(&self, id: &str) -> CancelOutcome {
+        let (outcome, job) = self.pool.cancel(id);
+        // A cancelled write won't run, so stop its session waiting for it
+        if let Some(Job::ExtractAndIngest { project_id, .. }) = job {
+            if let Some(session) = self.session_manager.get(&project_id) {
+                session.write_complete();
+            }
+        }
+        outcome
+    }
 }
 
 struct CueCandidate {
@@ -542,6 +1365,158 @@ pub fn is_lexicon_trainable(cue: &str) -> bool {
     !lower.starts_with("source:")
 }
 
+/// Cues an agent chunk is stored under: its normalized tokens, the field
+/// cues of its structural metadata, git cues, layout and connector document
+/// cues, and `path:`/`source:`/`category:` cues.
+/// Pure, so `/ingest/preview` can show them without writing anything.
+pub fn agent_chunk_cues(
+    ctx: &ProjectContext,
+    content: &str,
+    file_path: &str,
+    structural_cues: &[String],
+    category: crate::agent::chunker::ChunkCategory,
+) -> Vec<String> {
+    use crate::agent::chunker::ChunkCategory;
+
+    let mut resolved_cues: Vec<String>;
+
+    // 1. Resolve raw content cues (tokens only, no expansion)
+    let lang = structural_cues.iter()
+        .find(|c| c.starts_with("lang:"))
+        .map(|c| crate::nl::Language::from(c.as_str()))
+        .unwrap_or(crate::nl::Language::Default);
+
+    match category {
+        ChunkCategory::Conversation => {
+            resolved_cues = structural_cues.iter()
+                .filter(|c| !c.starts_with("git:message:"))
+                .cloned()
+                .collect();
+            let (normalized_tokens, _, _) = ctx.resolve_cues_from_text_with_lang(content, true, lang);
+            for token in normalized_tokens {
+                if !resolved_cues.contains(&token) {
+                    resolved_cues.push(token);
+                }
+            }
+        },
+        // Treat all other categories similarly: Just get tokens.
+        // Prose/WebContent getting WordNet expansion is now handled by Lexicon Training below.
+        _ => {
+             let (normalized_tokens, _, _) = ctx.resolve_cues_from_text_with_lang(content, true, lang);
+             resolved_cues = normalized_tokens;
+        }
+    }
+
+    // 2. Add metadata cues
+    // Titles/headers/names are kept so recall can boost matches in those fields
+    for cue in structural_cues {
+        if CueField::of_structural_cue(cue).is_some() && !resolved_cues.contains(cue) {
+            resolved_cues.push(cue.clone());
+        }
+    }
+
+    // 2b. Git metadata: the last commit's message is recallable by its words
+    for cue in structural_cues {
+        if let Some(message) = cue.strip_prefix("git:message:") {
+            let (tokens, _, _) = ctx.resolve_cues_from_text_with_lang(message, true, lang);
+            for token in tokens {
+                if !resolved_cues.contains(&token) {
+                    resolved_cues.push(token);
+                }
+            }
+        } else if (cue.starts_with("git:") || cue.starts_with("commit:")) && !resolved_cues.contains(cue) {
+            resolved_cues.push(cue.clone());
+        }
+    }
+
+    // 2c. Document layout: pages and recording timestamps for citations, the
+    // section a table sits in, and whether the text came from OCR
+    for cue in structural_cues {
+        let layout = ["page:", "timestamp:", "table:", "ocr:"].iter().any(|prefix| cue.starts_with(prefix));
+        if layout && !resolved_cues.contains(cue) {
+            resolved_cues.push(cue.clone());
+        }
+    }
+    // 2d. Metadata of documents synced by connectors (labels, authors, state)
+    for cue in structural_cues {
+        let document = crate::connectors::DOCUMENT_CUE_PREFIXES.iter().any(|prefix| cue.starts_with(prefix));
+        if document && !resolved_cues.contains(cue) {
+            resolved_cues.push(cue.clone());
+        }
+    }
+    resolved_cues.push(format!("path:{}", file_path));
+    resolved_cues.push("source:agent".to_string());
+    resolved_cues.push(format!("category:{:?}", category).to_lowercase());
+
+    resolved_cues
+}
+
+/// Separates a chunk's id from the number of one of its facts
+pub const FACT_ID_SEPARATOR: &str = "#fact:";
+
+/// Id of the `index`th fact extracted from chunk `chunk_id`
+pub fn fact_memory_id(chunk_id: &str, index: usize) -> String {
+    format!("{}{}{}", chunk_id, FACT_ID_SEPARATOR, index)
+}
+
+/// The chunk a fact was extracted from (any other id is returned as is)
+pub fn fact_parent_id(memory_id: &str) -> &str {
+    memory_id.split_once(FACT_ID_SEPARATOR).map_or(memory_id, |(chunk_id, _)| chunk_id)
+}
+
+/// Ids of the stored facts of a chunk, in order
+fn fact_ids(ctx: &ProjectContext, chunk_id: &str) -> Vec<String> {
+    (0..)
+        .map(|i| fact_memory_id(chunk_id, i))
+        .take_while(|id| ctx.main.get_memory(id).is_some())
+        .collect()
+}
+
+/// Delete the facts of a chunk from the `from`th on. Returns their ids.
+fn delete_facts(ctx: &ProjectContext, chunk_id: &str, from: usize) -> Vec<String> {
+    fact_ids(ctx, chunk_id)
+        .into_iter()
+        .skip(from)
+        .filter(|id| ctx.main.delete_memory(id))
+        .collect()
+}
+
+/// Audit memories the server deleted on its own, e.g. chunks of a changed file
+fn audit_deletions(provider: &Arc<dyn ProjectProvider>, project_id: &str, memory_ids: &[String], reason: &str) {
+    for memory_id in memory_ids {
+        provider.record_audit(
+            AuditEntry::new(SYSTEM_ACTOR.to_string(), AuditAction::MemoryDeleted, project_id)
+                .with_memory(memory_id)
+                .with_detail(serde_json::json!({"reason": reason})),
+        );
+    }
+}
+
+/// Store `facts` as memories of their own next to chunk `chunk_id`,
+/// replacing the ones of an earlier extraction. Returns their ids.
+pub fn store_facts(ctx: &ProjectContext, chunk_id: &str, file_path: &str, facts: &[crate::llm::Fact]) -> Vec<String> {
+    let mut ids = Vec::with_capacity(facts.len());
+    for (i, fact) in facts.iter().enumerate() {
+        let id = fact_memory_id(chunk_id, i);
+        let text = fact.text();
+        let mut structural_cues = fact.cues();
+        structural_cues.push("type:fact".to_string());
+        let mut cues = agent_chunk_cues(ctx, &text, file_path, &[], crate::agent::chunker::ChunkCategory::Prose);
+        for cue in structural_cues {
+            if !cues.contains(&cue) {
+                cues.push(cue);
+            }
+        }
+
+        let mut metadata = HashMap::new();
+        metadata.insert("fact".to_string(), serde_json::json!(fact));
+        ctx.main.upsert_memory_with_id(id.clone(), text, cues, Some(metadata), Some(MainStats::default()), false, true);
+        ids.push(id);
+    }
+    delete_facts(ctx, chunk_id, facts.len());
+    ids
+}
+
 // Shared logic for training lexicon from memory content (Identity + WordNet Synonyms)
 fn train_lexicon_impl(ctx: &ProjectContext, memory_id: &str, content: &str) {
     // Detect language from memory cues if available
@@ -555,7 +1530,7 @@ fn train_lexicon_impl(ctx: &ProjectContext, memory_id: &str, content: &str) {
     };
 
     // Tokenize content
-    let tokens = crate::nl::tokenize_to_cues_with_lang(content, lang);
+    let tokens = ctx.tokenize_as(content, lang);
 
     
     if tokens.is_empty() {
@@ -624,10 +1599,12 @@ fn train_lexicon_impl(ctx: &ProjectContext, memory_id: &str, content: &str) {
     }
 }
 
-async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Option<Arc<MetricsCollector>>) {
+/// Run one job. Errors are transient failures (LLM or embedding service
+/// unreachable, snapshot not saved) that the worker pool retries.
+async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Option<Arc<MetricsCollector>>) -> Result<(), String> {
     match job {
         Job::TrainLexiconFromMemory { project_id, memory_id } => {
-            if let Some(ctx) = provider.get_project(&project_id) {
+            if let Some(ctx) = provider.get_or_load_project(&project_id) {
                 let ctx_clone = ctx.clone();
                 let memory_id_clone = memory_id.clone();
                 
@@ -636,19 +1613,23 @@ async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Op
                      if let Some(memory) = ctx_clone.main.get_memory(&memory_id_clone) {
                          let content = memory.access_content(ctx_clone.main.get_master_key().as_deref()).unwrap_or_default();
                          train_lexicon_impl(&ctx_clone, &memory_id_clone, &content);
+                         ctx_clone.learn_acronyms(&content);
                      }
                 }).await.unwrap();
             }
         }
 
         Job::ProposeCues { project_id, memory_id, content } => {
-             if let Some(ctx) = provider.get_project(&project_id) {
+             if let Some(ctx) = provider.get_or_load_project(&project_id) {
                  let ctx_clone = ctx.clone();
                  let memory_id_clone = memory_id.clone();
                  let content_clone = content.clone();
                  let project_id_clone = project_id.clone();
+                 let llm = provider
+                     .llm_client(&project_id)
+                     .unwrap_or_else(|| Arc::new(crate::llm::LlmClient::new((*ctx.llm_config).clone())));
                  
-                 tokio::task::spawn_blocking(move || {
+                 let llm_error = tokio::task::spawn_blocking(move || {
                      let ctx = ctx_clone;
                      let memory_id = memory_id_clone;
                      let content = content_clone;
@@ -674,7 +1655,7 @@ async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Op
                  // If Lexicon found very few cues, add raw tokens as seed cues for expansion.
                  // Limit to 10 seeds because expansion multiplies them (each seed → multiple synonyms).
                  if known_cues.len() < 3 {
-                     let tokens = crate::nl::tokenize_to_cues_with_lang(&content, lang);
+                     let tokens = ctx.tokenize_as(&content, lang);
                      for token in tokens.into_iter().take(10) {
                          if !known_cues.contains(&token) {
                              known_cues.push(token);
@@ -687,6 +1668,7 @@ async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Op
                  let mut glove_cues: Vec<String> = Vec::new();
                  let mut context_cues: Vec<String> = Vec::new();
                  let mut llm_cues: Vec<String> = Vec::new();
+                 let mut llm_error = None;
                  
                  // IDF Filtering: Identify expansion candidates (rare cues only)
                  let total = ctx.total_memories();
@@ -696,7 +1678,7 @@ async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Op
                  // PERF/QUALITY: Use raw tokens for expansion to avoid Lexicon Pollution loop.
                  // We only expand what is explicitly in the content.
                  // Filter by IDF to skip common words (e.g. "the").
-                 let tokens = crate::nl::tokenize_to_cues_with_lang(&content, lang);
+                 let tokens = ctx.tokenize_as(&content, lang);
                  let expansion_candidates: Vec<String> = tokens.iter()
                      .filter(|c| ctx.get_cue_frequency(c) <= threshold)
                      .cloned()
@@ -725,21 +1707,17 @@ async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Op
                         context_cues.extend(context_result);
                     },
                      CueGenStrategy::Ollama => {
-                         // LLM Expansion
-                         // Use global config or fallback if enabled
-                         if ctx.llm_config.enabled || matches!(ctx.cuegen_strategy, CueGenStrategy::Ollama) {
-                             let content_ref = content.clone();
-                             let known_cues_ref = known_cues.clone();
-                             // Convert to legacy config for llm module
-                             let legacy_config = ctx.llm_config.to_legacy();
-                             
-                             match rt_handle.block_on(async move {
-                                 // ensure we are using the function from llm module
-                                 crate::llm::propose_cues(&content_ref, &legacy_config, &known_cues_ref).await
-                             }) {
-                                 Ok(result) => llm_cues.extend(result),
-                                 Err(e) => error!("Job: LLM failed: {}", e),
-                             }
+                         // LLM expansion below, whatever the provider
+                     }
+                 }
+
+                 // LLM Expansion: the project's provider, when enabled or asked for by the strategy
+                 if llm.config().enabled || matches!(ctx.cuegen_strategy, CueGenStrategy::Ollama) {
+                     match rt_handle.block_on(llm.propose_cues(&content, &known_cues)) {
+                         Ok(result) => llm_cues.extend(result),
+                         Err(e) => {
+                             error!("Job: LLM ({}) failed: {}", llm.name(), e);
+                             llm_error = Some(e);
                          }
                      }
                  }
@@ -757,6 +1735,10 @@ async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Op
                  log_sample("GloVe", &glove_cues);
                  log_sample("Context", &context_cues);
                  log_sample("LLM", &llm_cues);
+
+                 // Named entities, dates and code identifiers (entity:/symbol: cues)
+                 let extracted_cues = crate::entities::extract_entity_cues(&content);
+                 log_sample("Entities", &extracted_cues);
                  
 
                  
@@ -792,15 +1774,18 @@ async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Op
                      proposed_cues.truncate(MAX_PROPOSED_CUES);
                      debug!("Job: Truncated proposed cues to {}", MAX_PROPOSED_CUES);
                  }
+
+                 // Extracted cues come from the text itself and have their own cap
+                 filter_and_add(extracted_cues, &mut seen, &mut proposed_cues);
                  
                  // 5. Merge, Normalize & Validate
                  let mut normalized_cues = Vec::new();
                  for cue in proposed_cues {
-                     let (normalized, _) = normalize_cue(&cue, &ctx.normalization);
+                     let normalized = ctx.normalize_cue(&cue);
                      normalized_cues.push(normalized);
                  }
                  
-                 let report = validate_cues(normalized_cues, &ctx.taxonomy);
+                 let report = ctx.validate_cues(normalized_cues);
                  
                  // 6. Attach accepted cues
                  if !report.accepted.is_empty() {
@@ -810,7 +1795,7 @@ async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Op
                      debug!("Job: Attached {} cues to memory {}: {:?}{}", report.accepted.len(), memory_id, sample, suffix);
                      
                      // 7. Retrain lexicon with new cues
-                     let tokens = crate::nl::tokenize_to_cues_with_lang(&content, lang);
+                     let tokens = ctx.tokenize_as(&content, lang);
                      if !tokens.is_empty() {
                          for canonical_cue in report.accepted {
                              if !is_lexicon_trainable(&canonical_cue) {
@@ -841,11 +1826,16 @@ async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Op
                          }
                      }
                  }
+                 llm_error
                  }).await.unwrap();
+                 // Cues from the other sources are attached already; a retry adds the LLM's
+                 if let Some(e) = llm_error {
+                     return Err(format!("LLM cue proposal failed: {}", e));
+                 }
              }
         }
         Job::ProposeAliases { project_id } => {
-            if let Some(ctx) = provider.get_project(&project_id) {
+            if let Some(ctx) = provider.get_or_load_project(&project_id) {
                 let ctx_clone = ctx.clone();
                 let project_id_clone = project_id.clone();
 
@@ -915,9 +1905,9 @@ async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Op
                                 if let Some(entry_a) = cue_index.get(&cand_a.cue) {
                                     if let Some(entry_b) = cue_index.get(&cand_b.cue) {
                                         let (smaller, larger) = if entry_a.len() < entry_b.len() {
-                                            (&entry_a.items, &entry_b.items)
+                                            (&*entry_a, &*entry_b)
                                         } else {
-                                            (&entry_b.items, &entry_a.items)
+                                            (&*entry_b, &*entry_a)
                                         };
                                         
                                         let exact_intersection = smaller.iter().filter(|id| larger.contains(*id)).count();
@@ -966,8 +1956,33 @@ async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Op
             }
 
         }
-        Job::ExtractAndIngest { project_id, memory_id, content, file_path, structural_cues, category } => {
+        Job::ExtractAndIngest { project_id, memory_id, content, file_path, structural_cues, category, provenance } => {
             if let Some(ctx) = provider.get_project(&project_id) {
+                // Re-chunked files update existing memories; only new ones count against the quota
+                if ctx.main.get_memory(&memory_id).is_none() {
+                    // A chunk from a URL, upload or posted content that is already stored
+                    // (from another source) only adds its cues. Watched files keep their
+                    // own chunks since their ids follow the file's lines.
+                    if ctx.tuning.dedup_identical && provenance.source_type != "file" {
+                        if let Some(existing) = ctx.main.find_identical(&content) {
+                            let ctx_clone = ctx.clone();
+                            tokio::task::spawn_blocking(move || {
+                                let cues = agent_chunk_cues(&ctx_clone, &content, &file_path, &structural_cues, category);
+                                ctx_clone.main.attach_cues(&existing, cues);
+                                debug!("Agent: {} is identical to {}, merged its cues", memory_id, existing);
+                            }).await.unwrap();
+                            if let Some(m) = metrics {
+                                m.record_ingestion();
+                            }
+                            return Ok(());
+                        }
+                    }
+                    if let Err(e) = provider.check_quota(&project_id, 1, content.len() as u64) {
+                        warn!("Agent: Skipping {}: {}", memory_id, e);
+                        return Ok(());
+                    }
+                }
+
                 let ctx_clone = ctx.clone();
                 let memory_id_clone = memory_id.clone();
                 let content_clone = content.clone();
@@ -977,38 +1992,7 @@ async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Op
                 tokio::task::spawn_blocking(move || {
                     debug!("Agent: Fast extraction starting for {} (category: {:?})", memory_id_clone, category);
                     
-                    use crate::agent::chunker::ChunkCategory;
-                    
-                    let mut resolved_cues: Vec<String>;
-                    
-                    // 1. Resolve raw content cues (tokens only, no expansion)
-                    let lang = structural_cues_clone.iter()
-                        .find(|c| c.starts_with("lang:"))
-                        .map(|c| crate::nl::Language::from(c.as_str()))
-                        .unwrap_or(crate::nl::Language::Default);
-
-                    match category {
-                        ChunkCategory::Conversation => {
-                            resolved_cues = structural_cues_clone;
-                            let (normalized_tokens, _, _) = ctx_clone.resolve_cues_from_text_with_lang(&content_clone, true, lang);
-                            for token in normalized_tokens {
-                                if !resolved_cues.contains(&token) {
-                                    resolved_cues.push(token);
-                                }
-                            }
-                        },
-                        // Treat all other categories similarly: Just get tokens.
-                        // Prose/WebContent getting WordNet expansion is now handled by Lexicon Training below.
-                        _ => {
-                             let (normalized_tokens, _, _) = ctx_clone.resolve_cues_from_text_with_lang(&content_clone, true, lang);
-                             resolved_cues = normalized_tokens;
-                        }
-                    }
-                    
-                    // 2. Add metadata cues
-                    resolved_cues.push(format!("path:{}", file_path_clone));
-                    resolved_cues.push("source:agent".to_string());
-                    resolved_cues.push(format!("category:{:?}", category).to_lowercase());
+                    let resolved_cues = agent_chunk_cues(&ctx_clone, &content_clone, &file_path_clone, &structural_cues_clone, category);
                     
                     // 3. Upsert memory (Lean cues only)
                     ctx_clone.main.upsert_memory_with_id(
@@ -1020,6 +2004,7 @@ async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Op
                         false,
                         true
                     );
+                    ctx_clone.main.set_provenance(&memory_id_clone, &provenance);
                     
                     // Note: Lexicon training is now handled by buffered TrainLexiconFromMemory jobs
                     // to ensure all writes complete before background processing starts.
@@ -1027,6 +2012,17 @@ async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Op
                     debug!("Agent: Ingested {} ({:?}, {} cues)", memory_id_clone, category, resolved_cues.len());
                 }).await.unwrap();
 
+                // Embed the chunk with the project's provider (if any)
+                match provider.embedding_provider(&project_id) {
+                    Ok(Some(embedder)) => {
+                        if let Err(e) = embed_memories(&ctx, embedder.as_ref(), std::slice::from_ref(&memory_id), true).await {
+                            warn!("Agent: failed to embed {}: {}", memory_id, e);
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => warn!("Agent: embedding provider unavailable for '{}': {}", project_id, e),
+                }
+
                 // Record ingestion metric
                 if let Some(m) = metrics {
                     m.record_ingestion();
@@ -1035,31 +2031,35 @@ async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Op
         }
 
         Job::VerifyFile { project_id, file_path, valid_memory_ids } => {
-             if let Some(ctx) = provider.get_project(&project_id) {
+             if let Some(ctx) = provider.get_or_load_project(&project_id) {
                   // Strategy:
                   // 1. Look up all memories associated with "path:{file_path}"
                   // 2. Filter for those that are NOT in valid_memory_ids
                   // 3. Delete them
                   
-                  let path_cue = format!("path:{}", file_path);
-                  if let Some(ordered_set) = ctx.main.get_cue_index().get(&path_cue) {
-                      // Get all memory IDs associated with this file
-                      let current_memories = ordered_set.get_recent_owned(None);
+                  let path_cue = format!("path:{}", file_path).to_lowercase();
+                  // Ids of pages ingested from a URL start with their source
+                  let source_prefix = format!("{}:", file_path);
+                  // All memory IDs associated with this file (the index entry is
+                  // released before deleting, which updates it)
+                  let current_memories = ctx.main.get_cue_index().get(&path_cue).map(|set| set.get_recent_owned(None));
+                  if let Some(current_memories) = current_memories {
                       let valid_set: HashSet<String> = valid_memory_ids.into_iter().collect();
                       
-                      let mut deleted_count = 0;
+                      let mut deleted = Vec::new();
                       for mem_id in current_memories {
-                          // Only delete if it's an agent-managed memory (check prefix "file:")
-                          // and not in the valid set.
-                          if mem_id.starts_with("file:") && !valid_set.contains(&mem_id) {
-                               if ctx.main.delete_memory(&mem_id) {
-                                   deleted_count += 1;
-                               }
+                          // Only delete if it's an agent-managed memory (prefix "file:",
+                          // or this URL's source) and not in the valid set.
+                          let managed = mem_id.starts_with("file:") || mem_id.starts_with(&source_prefix);
+                          // Facts live as long as the chunk they came from
+                          if managed && !valid_set.contains(fact_parent_id(&mem_id)) && ctx.main.delete_memory(&mem_id) {
+                               deleted.push(mem_id);
                           }
                       }
                       
-                      if deleted_count > 0 {
-                          info!("Agent: Verified {}. Pruned {} stale memories.", file_path, deleted_count);
+                      audit_deletions(provider, &project_id, &deleted, "stale_chunk");
+                      if !deleted.is_empty() {
+                          info!("Agent: Verified {}. Pruned {} stale memories.", file_path, deleted.len());
                       } else {
                           debug!("Agent: Verified {}. No stale memories found.", file_path);
                       }
@@ -1067,14 +2067,85 @@ async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Op
              }
         }
         Job::DeleteMemory { project_id, memory_id } => {
-            if let Some(ctx) = provider.get_project(&project_id) {
+            if let Some(ctx) = provider.get_or_load_project(&project_id) {
+                let mut deleted = delete_facts(&ctx, &memory_id, 0);
                 if ctx.main.delete_memory(&memory_id) {
                     debug!("Job: Deleted stale memory {}", memory_id);
+                    deleted.insert(0, memory_id);
+                }
+                audit_deletions(provider, &project_id, &deleted, "stale_chunk");
+            }
+        }
+        Job::MoveMemories { project_id, moves, provenance } => {
+            if let Some(ctx) = provider.get_or_load_project(&project_id) {
+                // Facts follow the chunk they were extracted from
+                let fact_moves: Vec<(String, String)> = moves
+                    .iter()
+                    .flat_map(|(from, to)| {
+                        fact_ids(&ctx, from).into_iter().enumerate().map(move |(i, id)| (id, fact_memory_id(to, i)))
+                    })
+                    .collect();
+                ctx.main.rename_memories(&fact_moves);
+                let moved = ctx.main.rename_memories(&moves);
+                for (memory_id, provenance) in &provenance {
+                    ctx.main.set_provenance(memory_id, provenance);
+                }
+                debug!("Job: Moved {} of {} shifted memories", moved, moves.len());
+            }
+        }
+        Job::EmbedMemories { project_id, memory_ids, force } => {
+            if let Some(ctx) = provider.get_or_load_project(&project_id) {
+                match provider.embedding_provider(&project_id) {
+                    Ok(Some(embedder)) => match embed_memories(&ctx, embedder.as_ref(), &memory_ids, force).await {
+                        Ok(stored) => debug!("Job: Embedded {} memories with {}", stored, embedder.name()),
+                        Err(e) => return Err(format!("Embedding failed for '{}': {}", project_id, e)),
+                    },
+                    Ok(None) => debug!("Job: No embedding provider for '{}', skipping", project_id),
+                    Err(e) => return Err(format!("Embedding provider unavailable for '{}': {}", project_id, e)),
+                }
+            }
+        }
+        Job::ExtractFacts { project_id, memory_id, content, file_path } => {
+            if let Some(ctx) = provider.get_or_load_project(&project_id) {
+                let llm = provider
+                    .llm_client(&project_id)
+                    .unwrap_or_else(|| Arc::new(crate::llm::LlmClient::new((*ctx.llm_config).clone())));
+                if !llm.config().enabled {
+                    debug!("Job: No LLM enabled for '{}', skipping fact extraction", project_id);
+                    return Ok(());
+                }
+                let facts = llm
+                    .extract_facts(&content)
+                    .await
+                    .map_err(|e| format!("Fact extraction failed for {}: {}", memory_id, e))?;
+
+                let new_facts = facts.len().saturating_sub(fact_ids(&ctx, &memory_id).len());
+                let bytes = facts.iter().map(|f| f.text().len() as u64).sum();
+                if let Err(e) = provider.check_quota(&project_id, new_facts, bytes) {
+                    warn!("Job: Skipping facts of {}: {}", memory_id, e);
+                    return Ok(());
+                }
+
+                let ctx_clone = ctx.clone();
+                let memory_id_clone = memory_id.clone();
+                let fact_ids = tokio::task::spawn_blocking(move || {
+                    store_facts(&ctx_clone, &memory_id_clone, &file_path, &facts)
+                }).await.unwrap();
+                debug!("Job: Stored {} facts of {}", fact_ids.len(), memory_id);
+
+                match provider.embedding_provider(&project_id) {
+                    Ok(Some(embedder)) if !fact_ids.is_empty() => {
+                        if let Err(e) = embed_memories(&ctx, embedder.as_ref(), &fact_ids, true).await {
+                            warn!("Job: failed to embed facts of {}: {}", memory_id, e);
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Job: embedding provider unavailable for '{}': {}", project_id, e),
                 }
             }
         }
         Job::UpdateGraph { project_id, memory_id } => {
-            if let Some(ctx) = provider.get_project(&project_id) {
+            if let Some(ctx) = provider.get_or_load_project(&project_id) {
                 let ctx_clone = ctx.clone();
                 let memory_id_clone = memory_id.clone();
                 tokio::task::spawn_blocking(move || {
@@ -1089,7 +2160,7 @@ async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Op
         }
 
         Job::ReinforceMemories { project_id, memory_ids, cues } => {
-            if let Some(ctx) = provider.get_project(&project_id) {
+            if let Some(ctx) = provider.get_or_load_project(&project_id) {
                 // 1. Primary Reinforcement
                 debug!("Job: Starting reinforcement for {} memories", memory_ids.len());
                 for memory_id in &memory_ids {
@@ -1120,7 +2191,7 @@ async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Op
                                 // Use content as query
                                 let content = mem.access_content(ctx_clone.main.get_master_key().as_deref()).unwrap_or_default();
                                 let ripple_results = ctx_clone.main.recall_fast(
-                                    crate::nl::tokenize_to_cues_with_lang(&content, lang), 
+                                    ctx_clone.tokenize_as(&content, lang), 
                                     10
                                 );
                                 
@@ -1145,70 +2216,136 @@ async fn process_job(job: Job, provider: &Arc<dyn ProjectProvider>, metrics: &Op
                 }
             }
         }
-        Job::ReinforceLexicon { project_id, memory_ids, cues } => {
-            if let Some(ctx) = provider.get_project(&project_id) {
+        Job::ReinforceLexicon { project_id, memory_ids, cues, result_ids } => {
+            if let Some(ctx) = provider.get_or_load_project(&project_id) {
                 for memory_id in &memory_ids {
                     // Use tiered reinforcement logic (buckets)
                     ctx.lexicon.reinforce_tiered(memory_id, 1);
                 }
+                record_expansions(&ctx, &memory_ids, &result_ids);
                 debug!("Job: Reinforced {} lexicon entries with {} cues", memory_ids.len(), cues.len());
             }
         }
+        Job::PruneLexicon { project_id, request } => {
+            if let Some(ctx) = provider.get_or_load_project(&project_id) {
+                let report = tokio::task::spawn_blocking(move || prune_lexicon(&ctx, &request))
+                    .await
+                    .map_err(|e| e.to_string())?;
+                info!(
+                    "Lexicon prune: Removed {} of {} entries in project '{}'",
+                    report.pruned, report.evaluated, project_id
+                );
+                if report.pruned > 0 {
+                    provider.record_audit(
+                        AuditEntry::new(SYSTEM_ACTOR.to_string(), AuditAction::LexiconPruned, &project_id)
+                            .with_detail(serde_json::json!({"pruned": report.pruned})),
+                    );
+                    if let Err(e) = provider.save_project(&project_id) {
+                        return Err(format!("Failed to save project '{}' after lexicon prune: {}", project_id, e));
+                    }
+                }
+            }
+        }
         Job::ConsolidateMemories { project_id } => {
-            if let Some(ctx) = provider.get_project(&project_id) {
+            if let Some(ctx) = provider.get_or_load_project(&project_id) {
                 info!("Starting autonomous consolidation for project '{}'", project_id);
                 let merged = ctx.main.consolidate_memories(0.9); // 90% overlap threshold
                 if !merged.is_empty() {
                         info!("Consolidation: Merged {} overlapping groups in project '{}'", merged.len(), project_id);
+                        summarize_consolidated(provider, &ctx, &project_id, &merged).await;
                         // Save snapshot after significant change
                         if let Err(e) = provider.save_project(&project_id) {
-                            error!("Failed to save project '{}' after consolidation: {}", project_id, e);
+                            return Err(format!("Failed to save project '{}' after consolidation: {}", project_id, e));
                         }
                 } else {
                     info!("Consolidation: No overlapping memories found for '{}'", project_id);
                 }
             }
         }
-        Job::UpdateMarketHeatmap { project_id } => {
-            if let Some(ctx) = provider.get_project(&project_id) {
+        Job::UpdateMarketHeatmap { project_id, window_mins } => {
+            if let Some(ctx) = provider.get_or_load_project(&project_id) {
                 // Sync Lexicon Trending -> Market Heatmap
                 // 1. Get trending items (Top 1000?)
-                let trending = ctx.lexicon.get_trending_items(1000);
-                
+                let trending = ctx.lexicon.trending_cues(window_mins, 1000);
+                let now = crate::heatmap::now_secs();
+                let mut heatmap = ctx.market_heatmap.write().unwrap();
+
                 if !trending.is_empty() {
-                    let mut map = ctx.market_heatmap.write().unwrap();
-                    map.clear();
-                    
-                    for (cue, velocity) in trending {
-                        // Normalize velocity to 0.0 - 2.0 range
-                        // Log10(1 + v) is a good start.
-                        let score = (1.0 + velocity).log10() as f32;
-                        
-                        // Cap at 2.0 to prevent runaway market override
-                        let final_score = score.min(2.0);
+                    let mut lifts = HashMap::new();
+
+                    for item in trending {
+                        // Normalize the window count to 0.0 - 2.0
+                        let final_score = crate::trending::heatmap_lift(item.count);
                         
                         if final_score > 0.1 {
-                            let clean_cue = cue.strip_prefix("cue:").unwrap_or(&cue).to_string();
-                            map.insert(clean_cue.clone(), final_score);
                             // Log top 10 cues contributing to heatmap
-                            if map.len() <= 10 {
-                                debug!("Job: [Heatmap] Cue '{}' added (original id: '{}') with lift {:.2} (velocity={:.2})", clean_cue, cue, final_score, velocity);
+                            if lifts.len() < 10 {
+                                debug!("Job: [Heatmap] Cue '{}' added with lift {:.2} (count={}, velocity={:.2}/min)", item.cue, final_score, item.count, item.velocity);
                             }
+                            lifts.insert(item.cue, final_score);
                         }
                     }
-                    
-                    let total_cues = map.len();
-                    let avg_lift = if total_cues > 0 {
-                        map.values().sum::<f32>() / total_cues as f32
-                    } else {
-                        0.0
-                    };
-                    
-                    debug!("Job: Updated Market Heatmap for '{}' with {} active cues (avg lift: {:.2})", project_id, total_cues, avg_lift);
+
+                    // Entries set through PUT /heatmap keep their cues
+                    heatmap.sync_trending(lifts, now);
                 } else {
-                    debug!("Job: No trending cues found for project '{}', heatmap unchanged", project_id);
+                    debug!("Job: No trending cues found for project '{}', trending entries unchanged", project_id);
                 }
+
+                // 2. Decay entries set with a TTL
+                let expired = heatmap.decay(now);
+                let total_cues = heatmap.weights().len();
+                let avg_lift = if total_cues > 0 {
+                    heatmap.weights().values().sum::<f32>() / total_cues as f32
+                } else {
+                    0.0
+                };
+
+                debug!("Job: Updated Market Heatmap for '{}' with {} active cues (avg lift: {:.2}, {} expired)", project_id, total_cues, avg_lift, expired);
             }
         }
+    }
+    Ok(())
+}
+
+/// Replace the truncated text of freshly consolidated memories with an LLM
+/// summary when the project has an LLM enabled. Failures keep the truncated
+/// text rather than failing the job, so a retry can't consolidate twice.
+async fn summarize_consolidated(provider: &Arc<dyn ProjectProvider>, ctx: &ProjectContext, project_id: &str, merged: &[(String, Vec<String>)]) {
+    let llm = provider
+        .llm_client(project_id)
+        .unwrap_or_else(|| Arc::new(crate::llm::LlmClient::new((*ctx.llm_config).clone())));
+    if !llm.config().enabled {
+        return;
+    }
+    let master_key = ctx.main.get_master_key();
+
+    for (new_id, group) in merged {
+        let texts: Vec<String> = group
+            .iter()
+            .filter_map(|id| ctx.main.get_memory(id))
+            .filter_map(|memory| memory.access_content(master_key.as_deref()).ok())
+            .collect();
+        let Some(memory) = ctx.main.get_memory(new_id) else { continue };
+        if texts.is_empty() {
+            continue;
+        }
+
+        match llm.summarize(&texts).await {
+            Ok(summary) => {
+                let mut metadata = memory.metadata.clone();
+                metadata.insert("summarized_by".to_string(), serde_json::json!(llm.name()));
+                ctx.main.upsert_memory_with_id(
+                    new_id.clone(),
+                    format!("[Consolidated Memory]\n{}", summary),
+                    Vec::new(),
+                    Some(metadata),
+                    None,
+                    false,
+                    false,
+                );
+            }
+            Err(e) => warn!("Consolidation: summary of '{}' in project '{}' failed, keeping truncated text: {}", new_id, project_id, e),
         }
     }
+}
@@ -0,0 +1,215 @@
+//! Lexicon quality scoring and pruning.
+//!
+//! Lexicon entries (identity and WordNet mappings per token) are never removed
+//! on their own. After each recall, every entry the query was expanded through
+//! is tallied: a *hit* when one of the returned memories carries the entry's
+//! canonical cue, a *false expansion* when none does. A prune run
+//! (`POST /lexicon/prune`, the `prune_lexicon` job) decays the tallies so old
+//! evidence fades, then removes entries that mostly expand falsely or have
+//! gone unused.
+
+use crate::projects::ProjectContext;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Candidates listed in a prune report
+pub const PRUNE_REPORT_MAX: usize = 100;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LexiconQuality {
+    /// Recalls where a returned memory carried the entry's canonical cue (decayed)
+    pub hits: f64,
+    /// Recalls where none did (decayed)
+    pub false_expansions: f64,
+    /// Unix time of the last hit
+    pub last_hit: u64,
+}
+
+impl LexiconQuality {
+    pub fn observations(&self) -> f64 {
+        self.hits + self.false_expansions
+    }
+
+    /// Share of expansions that hit, pulled towards 0.5 while there are few
+    pub fn score(&self) -> f64 {
+        (self.hits + 1.0) / (self.observations() + 2.0)
+    }
+}
+
+/// Tallies saved next to the lexicon snapshot (`{project}_lexicon_quality.bin`)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LexiconQualitySnapshot {
+    pub entries: HashMap<String, LexiconQuality>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LexiconPruneRequest {
+    /// Report what would be pruned without changing anything
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Entries scoring below this are pruned, once seen `min_observations` times
+    #[serde(default = "default_min_score")]
+    pub min_score: f64,
+    #[serde(default = "default_min_observations")]
+    pub min_observations: f64,
+    /// Entries without a hit for this long (counting from their last use) are pruned
+    #[serde(default = "default_max_idle_secs")]
+    pub max_idle_secs: u64,
+    /// Factor the tallies are multiplied by on each run
+    #[serde(default = "default_decay")]
+    pub decay: f64,
+}
+
+fn default_min_score() -> f64 {
+    0.2
+}
+
+fn default_min_observations() -> f64 {
+    5.0
+}
+
+fn default_max_idle_secs() -> u64 {
+    30 * 86400
+}
+
+fn default_decay() -> f64 {
+    0.9
+}
+
+impl Default for LexiconPruneRequest {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            min_score: default_min_score(),
+            min_observations: default_min_observations(),
+            max_idle_secs: default_max_idle_secs(),
+            decay: default_decay(),
+        }
+    }
+}
+
+impl LexiconPruneRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.min_score) {
+            return Err("min_score must be between 0 and 1".to_string());
+        }
+        if !(self.decay > 0.0 && self.decay <= 1.0) {
+            return Err("decay must be in (0, 1]".to_string());
+        }
+        if !(self.min_observations.is_finite() && self.min_observations >= 0.0) {
+            return Err("min_observations must be a non-negative number".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PruneCandidate {
+    pub id: String,
+    pub canonical: String,
+    pub tokens: Vec<String>,
+    pub score: f64,
+    pub hits: f64,
+    pub false_expansions: f64,
+    /// `low_score` or `unused`
+    pub reason: &'static str,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LexiconPruneReport {
+    pub dry_run: bool,
+    pub evaluated: usize,
+    pub pruned: usize,
+    /// The first `PRUNE_REPORT_MAX` entries pruned (or, in a dry run, that would be)
+    pub candidates: Vec<PruneCandidate>,
+}
+
+/// Tally the lexicon entries a recall expanded through against the memories it returned
+pub fn record_expansions(ctx: &ProjectContext, lexicon_ids: &[String], result_ids: &[String]) {
+    let key = ctx.lexicon.get_master_key();
+    let memories = ctx.main.get_memories();
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+
+    for id in lexicon_ids {
+        let Some(canonical) = ctx.lexicon.get_memories().get(id).and_then(|m| m.access_content(key.as_deref()).ok()) else {
+            continue;
+        };
+        let canonical = ctx.normalize_cue(&canonical);
+        let hit = result_ids
+            .iter()
+            .any(|rid| memories.get(rid).is_some_and(|m| m.cues.contains(&canonical)));
+
+        let mut quality = ctx.lexicon_quality.entry(id.clone()).or_default();
+        if hit {
+            quality.hits += 1.0;
+            quality.last_hit = now;
+        } else {
+            quality.false_expansions += 1.0;
+        }
+    }
+}
+
+/// Decay the tallies and remove low-quality or unused entries. A dry run
+/// leaves both the tallies and the lexicon untouched.
+pub fn prune_lexicon(ctx: &ProjectContext, req: &LexiconPruneRequest) -> LexiconPruneReport {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let key = ctx.lexicon.get_master_key();
+    let mut report = LexiconPruneReport { dry_run: req.dry_run, ..Default::default() };
+    let mut to_remove = Vec::new();
+
+    for entry in ctx.lexicon.get_memories().iter() {
+        report.evaluated += 1;
+        let memory = entry.value();
+        let mut quality = ctx.lexicon_quality.get(entry.key()).map(|q| *q).unwrap_or_default();
+        quality.hits *= req.decay;
+        quality.false_expansions *= req.decay;
+
+        let last_used = (memory.created_at as u64)
+            .max(memory.last_accessed as u64)
+            .max(memory.stats.last_reinforced)
+            .max(quality.last_hit);
+        let reason = if quality.observations() >= req.min_observations && quality.score() < req.min_score {
+            Some("low_score")
+        } else if quality.hits < 1.0 && now.saturating_sub(last_used) > req.max_idle_secs {
+            Some("unused")
+        } else {
+            None
+        };
+
+        match reason {
+            Some(reason) => {
+                if report.candidates.len() < PRUNE_REPORT_MAX {
+                    report.candidates.push(PruneCandidate {
+                        id: entry.key().clone(),
+                        canonical: memory.access_content(key.as_deref()).unwrap_or_default(),
+                        tokens: memory.cues.clone(),
+                        score: quality.score(),
+                        hits: quality.hits,
+                        false_expansions: quality.false_expansions,
+                        reason,
+                    });
+                }
+                to_remove.push(entry.key().clone());
+            }
+            None if !req.dry_run && quality.observations() > 0.0 => {
+                ctx.lexicon_quality.insert(entry.key().clone(), quality);
+            }
+            None => {}
+        }
+    }
+
+    report.pruned = to_remove.len();
+    if !req.dry_run {
+        for id in &to_remove {
+            ctx.lexicon.delete_memory(id);
+            ctx.lexicon_quality.remove(id);
+        }
+        // Tallies of entries deleted some other way
+        ctx.lexicon_quality.retain(|id, _| ctx.lexicon.get_memories().contains_key(id));
+        if !to_remove.is_empty() {
+            ctx.query_cache.clear();
+        }
+    }
+    report
+}
@@ -9,13 +9,50 @@ pub mod taxonomy;
 pub mod projects;
 pub mod multi_tenant;
 pub mod nl;
+pub mod multilingual;
+pub mod entities;
 pub mod jobs;
 pub mod llm;
 pub mod agent;
+pub mod connectors;
 pub mod grounding;
+pub mod tokenizer;
+pub mod openapi;
+pub mod client_gen;
 pub mod semantic;
 pub mod web;
 pub mod crypto;
 pub mod metrics;
+pub mod scheduler;
+pub mod live;
+pub mod audit;
+pub mod replication;
+pub mod follower;
+pub mod vector;
+pub mod embeddings;
+pub mod rerank;
+pub mod fuzzy;
+pub mod query_rewrite;
+pub mod lexicon_quality;
+pub mod vocabulary;
+pub mod fusion;
+pub mod payload_store;
+pub mod history;
+pub mod index_stats;
+pub mod telemetry;
+pub mod slow_queries;
+pub mod query_log;
+pub mod eval;
+pub mod heatmap;
+pub mod trending;
+pub mod maintenance;
+pub mod grouping;
+pub mod snippets;
+pub mod aggregation;
+pub mod access;
+pub mod content_index;
 
 
+pub mod embedded;
+
+pub use embedded::{Client, ClientBuilder, RecallOptions};
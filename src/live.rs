@@ -0,0 +1,83 @@
+//! Live change feed for dashboards (`GET /ws`).
+//!
+//! Engines publish small incremental events (new memories, attached or detached
+//! cues, reinforcement, demotion, deletions) on a broadcast channel so the UI can patch its
+//! graph instead of re-polling `/graph`, which clones every memory.
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveEvent {
+    MemoryAdded {
+        project_id: String,
+        memory_id: String,
+        cues: Vec<String>,
+        created_at: f64,
+    },
+    CuesAttached {
+        project_id: String,
+        memory_id: String,
+        cues: Vec<String>,
+    },
+    CuesDetached {
+        project_id: String,
+        memory_id: String,
+        cues: Vec<String>,
+    },
+    MemoryReinforced {
+        project_id: String,
+        memory_id: String,
+        reinforcement_count: u64,
+        salience: f64,
+    },
+    MemoryDemoted {
+        project_id: String,
+        memory_id: String,
+        salience: f64,
+    },
+    MemoryDeleted {
+        project_id: String,
+        memory_id: String,
+    },
+    Stats {
+        project_id: String,
+        total_memories: usize,
+        total_cues: usize,
+    },
+}
+
+impl LiveEvent {
+    pub fn project_id(&self) -> &str {
+        match self {
+            LiveEvent::MemoryAdded { project_id, .. }
+            | LiveEvent::CuesAttached { project_id, .. }
+            | LiveEvent::CuesDetached { project_id, .. }
+            | LiveEvent::MemoryReinforced { project_id, .. }
+            | LiveEvent::MemoryDemoted { project_id, .. }
+            | LiveEvent::MemoryDeleted { project_id, .. }
+            | LiveEvent::Stats { project_id, .. } => project_id,
+        }
+    }
+}
+
+/// Publisher handle held by a project's main engine
+#[derive(Clone)]
+pub struct LiveFeed {
+    project_id: String,
+    tx: broadcast::Sender<LiveEvent>,
+}
+
+impl LiveFeed {
+    pub fn new(project_id: String, tx: broadcast::Sender<LiveEvent>) -> Self {
+        Self { project_id, tx }
+    }
+
+    /// Build and send an event, skipping the work entirely when nobody is subscribed
+    pub fn publish(&self, build: impl FnOnce(String) -> LiveEvent) {
+        if self.tx.receiver_count() > 0 {
+            let _ = self.tx.send(build(self.project_id.clone()));
+        }
+    }
+}
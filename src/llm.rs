@@ -1,9 +1,19 @@
+//! LLM providers for cue proposal, fact extraction and other generation jobs.
+//!
+//! The provider is configured server-wide under `[llm]` and can be overridden
+//! per project (`PUT /projects/:id/llm`):
+//! - `ollama`: Ollama's `/api/generate`
+//! - `openai`: any OpenAI-compatible `/v1/chat/completions` endpoint
+//! - `vllm`: the same API, defaulting to a local vLLM server
+//! - `anthropic`: Anthropic's `/v1/messages`
+
+use async_trait::async_trait;
 use regex::Regex;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::env;
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
 use std::process::{Command, Stdio};
 use std::time::Duration;
 use tracing::{info, error};
@@ -94,6 +104,348 @@ fn get_client() -> &'static Client {
     })
 }
 
+/// Providers accepted in `[llm] provider`
+pub const PROVIDERS: &[&str] = &["ollama", "openai", "vllm", "anthropic"];
+
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+
+/// Reply budget for cue proposal
+const PROPOSAL_MAX_TOKENS: usize = 512;
+
+/// Rough token size, for cutting text to a token budget
+const BYTES_PER_TOKEN: usize = 4;
+
+/// Reply budget for fact extraction
+const FACTS_MAX_TOKENS: usize = 1024;
+
+/// Reply budget for rating rerank candidates
+const RERANK_MAX_TOKENS: usize = 256;
+
+/// Text of each rerank candidate sent to the model, in tokens
+const RERANK_PASSAGE_TOKENS: usize = 200;
+
+/// Facts kept per extraction; the rest of a long reply is dropped
+pub const MAX_FACTS: usize = 20;
+
+/// A (subject, predicate, object) statement extracted from a chunk, e.g.
+/// ("payment service", "listens on port", "8443")
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fact {
+    pub subject: String,
+    pub predicate: String,
+    pub object: String,
+}
+
+impl Fact {
+    /// The fact as a sentence, stored as the memory's content
+    pub fn text(&self) -> String {
+        format!("{} {} {}", self.subject, self.predicate, self.object)
+    }
+
+    /// `subj:`, `pred:` and `obj:` cues, lowercased with spaces as `_`
+    pub fn cues(&self) -> Vec<String> {
+        let value = |v: &str| v.split_whitespace().collect::<Vec<_>>().join("_").to_lowercase();
+        vec![
+            format!("subj:{}", value(&self.subject)),
+            format!("pred:{}", value(&self.predicate)),
+            format!("obj:{}", value(&self.object)),
+        ]
+    }
+}
+
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    /// Provider and model, e.g. `anthropic/claude-3-5-haiku-latest`
+    fn name(&self) -> String;
+
+    /// The model's reply to `prompt`, following the `system` instructions,
+    /// of at most `max_tokens` tokens
+    async fn complete(&self, system: &str, prompt: &str, max_tokens: usize) -> Result<String, String>;
+}
+
+/// Build the provider for a config
+pub fn build_provider(config: &crate::config::LlmConfig) -> Result<Arc<dyn LlmProvider>, String> {
+    let url = |default: &str| {
+        let url = config.url.trim();
+        if url.is_empty() { default.to_string() } else { url.trim_end_matches('/').to_string() }
+    };
+    let model = config.model.clone();
+    let provider: Arc<dyn LlmProvider> = match config.provider.as_str() {
+        "ollama" => Arc::new(OllamaLlm { url: url(DEFAULT_OLLAMA_URL), model }),
+        "openai" => Arc::new(OpenAiLlm {
+            label: "openai",
+            url: url("https://api.openai.com"),
+            model,
+            api_key: config.api_key.clone().or_else(|| env::var("OPENAI_API_KEY").ok()),
+        }),
+        "vllm" => Arc::new(OpenAiLlm { label: "vllm", url: url("http://localhost:8000"), model, api_key: config.api_key.clone() }),
+        "anthropic" => Arc::new(AnthropicLlm {
+            url: url("https://api.anthropic.com"),
+            model,
+            api_key: config
+                .api_key
+                .clone()
+                .or_else(|| env::var("ANTHROPIC_API_KEY").ok())
+                .ok_or("The anthropic LLM provider needs an api_key (or ANTHROPIC_API_KEY)")?,
+        }),
+        other => {
+            return Err(format!("Unsupported LLM provider '{}' (expected one of: {})", other, PROVIDERS.join(", ")))
+        }
+    };
+    Ok(provider)
+}
+
+/// A project's LLM: its provider, called within the project's rate limits
+pub struct LlmClient {
+    config: crate::config::LlmConfig,
+    provider: Result<Arc<dyn LlmProvider>, String>,
+    /// Earliest start of the next request under `requests_per_minute`
+    next_slot: tokio::sync::Mutex<tokio::time::Instant>,
+    in_flight: Option<tokio::sync::Semaphore>,
+}
+
+impl LlmClient {
+    pub fn new(config: crate::config::LlmConfig) -> Self {
+        let provider = build_provider(&config);
+        Self::with_provider(config, provider)
+    }
+
+    /// A client for an already built provider (or the error building it)
+    pub fn with_provider(config: crate::config::LlmConfig, provider: Result<Arc<dyn LlmProvider>, String>) -> Self {
+        let in_flight = config.max_concurrent.filter(|n| *n > 0).map(tokio::sync::Semaphore::new);
+        Self {
+            config,
+            provider,
+            next_slot: tokio::sync::Mutex::new(tokio::time::Instant::now()),
+            in_flight,
+        }
+    }
+
+    pub fn config(&self) -> &crate::config::LlmConfig {
+        &self.config
+    }
+
+    pub fn name(&self) -> String {
+        match &self.provider {
+            Ok(provider) => provider.name(),
+            Err(_) => format!("{}/{}", self.config.provider, self.config.model),
+        }
+    }
+
+    /// Call the model, waiting for a free slot under the rate limits first
+    pub async fn complete(&self, system: &str, prompt: &str, max_tokens: usize) -> Result<String, String> {
+        let provider = self.provider.as_ref().map_err(|e| e.clone())?;
+        let _permit = match &self.in_flight {
+            Some(in_flight) => Some(in_flight.acquire().await.map_err(|e| e.to_string())?),
+            None => None,
+        };
+        self.wait_turn().await;
+        provider.complete(system, prompt, max_tokens).await
+    }
+
+    async fn wait_turn(&self) {
+        let Some(rpm) = self.config.requests_per_minute.filter(|r| *r > 0) else {
+            return;
+        };
+        let interval = Duration::from_secs_f64(60.0 / rpm as f64);
+        let start = {
+            let mut next = self.next_slot.lock().await;
+            let start = (*next).max(tokio::time::Instant::now());
+            *next = start + interval;
+            start
+        };
+        tokio::time::sleep_until(start).await;
+    }
+
+    /// Cues for `content`, expanding on the `known_cues` found without the LLM
+    pub async fn propose_cues(&self, content: &str, known_cues: &[String]) -> Result<Vec<String>, String> {
+        let reply = self.complete(&propose_cues_prompt(known_cues), content, PROPOSAL_MAX_TOKENS).await?;
+        parse_proposal_response(&reply)
+    }
+
+    /// Discrete facts stated in `content`, at most `MAX_FACTS`
+    pub async fn extract_facts(&self, content: &str) -> Result<Vec<Fact>, String> {
+        let reply = self.complete(EXTRACT_FACTS_PROMPT, content, FACTS_MAX_TOKENS).await?;
+        parse_facts_response(&reply)
+    }
+
+    /// Answer `question` from `context`, the verified context of a grounded
+    /// recall, citing its `[n]` markers. The reply is cut to `max_tokens`.
+    pub async fn answer(&self, question: &str, context: &str, max_tokens: usize) -> Result<String, String> {
+        let max_tokens = max_tokens.max(1);
+        let mut answer = self.complete(ANSWER_PROMPT, &answer_prompt(question, context), max_tokens).await?.trim().to_string();
+        truncate_at_char(&mut answer, max_tokens * BYTES_PER_TOKEN);
+        if answer.is_empty() {
+            return Err(format!("{}: empty answer", self.name()));
+        }
+        Ok(answer)
+    }
+
+    /// Relevance of each passage to `query`, 0 to 10, in order. Passages are
+    /// cut to `RERANK_PASSAGE_TOKENS` each.
+    pub async fn rate_passages(&self, query: &str, passages: &[String]) -> Result<Vec<f32>, String> {
+        if passages.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut prompt = format!("Query: {}\n\n", query.trim());
+        for (i, passage) in passages.iter().enumerate() {
+            let mut passage = passage.trim().to_string();
+            truncate_at_char(&mut passage, RERANK_PASSAGE_TOKENS * BYTES_PER_TOKEN);
+            prompt.push_str(&format!("[{}] {}\n\n", i + 1, passage));
+        }
+        let reply = self.complete(RERANK_PROMPT, &prompt, RERANK_MAX_TOKENS.max(passages.len() * 4)).await?;
+        parse_rerank_scores(&reply, passages.len())
+    }
+
+    /// One summary of `texts` (e.g. memories merged by consolidation). The
+    /// input is cut to `summary_input_tokens` and the reply to
+    /// `summary_max_tokens`, whatever the model returns.
+    pub async fn summarize(&self, texts: &[String]) -> Result<String, String> {
+        let mut prompt = String::new();
+        for (i, text) in texts.iter().enumerate() {
+            prompt.push_str(&format!("[{}] {}\n\n", i + 1, text.trim()));
+        }
+        truncate_at_char(&mut prompt, self.config.summary_input_tokens.max(1) * BYTES_PER_TOKEN);
+
+        let max_tokens = self.config.summary_max_tokens.max(1);
+        let system = format!(
+            "Summarize the numbered notes below into one self-contained note. Keep names, numbers, dates and \
+             decisions; drop repetition. Answer with the summary only, in at most {} words.",
+            max_tokens * 3 / 4
+        );
+        let mut summary = self.complete(&system, &prompt, max_tokens).await?.trim().to_string();
+        truncate_at_char(&mut summary, max_tokens * BYTES_PER_TOKEN);
+        if summary.is_empty() {
+            return Err(format!("{}: empty summary", self.name()));
+        }
+        Ok(summary)
+    }
+}
+
+/// Cut `text` to at most `max_bytes`, on a character boundary
+fn truncate_at_char(text: &mut String, max_bytes: usize) {
+    if text.len() > max_bytes {
+        let mut end = max_bytes;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+    }
+}
+
+/// Ollama `/api/generate`
+pub struct OllamaLlm {
+    url: String,
+    model: String,
+}
+
+#[async_trait]
+impl LlmProvider for OllamaLlm {
+    fn name(&self) -> String {
+        format!("ollama/{}", self.model)
+    }
+
+    async fn complete(&self, system: &str, prompt: &str, max_tokens: usize) -> Result<String, String> {
+        let request = get_client().post(format!("{}/api/generate", self.url));
+        let body = json!({
+            "model": self.model,
+            "system": system,
+            "prompt": prompt,
+            "stream": false,
+            "options": { "num_predict": max_tokens },
+        });
+        let reply = post_json(request, body, &self.name()).await?;
+        reply["response"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| format!("{}: response has no text", self.name()))
+    }
+}
+
+/// OpenAI-compatible `/v1/chat/completions` (OpenAI, vLLM, LM Studio, gateways, ...)
+pub struct OpenAiLlm {
+    label: &'static str,
+    url: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiLlm {
+    fn name(&self) -> String {
+        format!("{}/{}", self.label, self.model)
+    }
+
+    async fn complete(&self, system: &str, prompt: &str, max_tokens: usize) -> Result<String, String> {
+        let mut request = get_client().post(format!("{}/v1/chat/completions", self.url));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let body = json!({
+            "model": self.model,
+            "messages": [
+                { "role": "system", "content": system },
+                { "role": "user", "content": prompt },
+            ],
+            "max_tokens": max_tokens,
+        });
+        let reply = post_json(request, body, &self.name()).await?;
+        reply["choices"][0]["message"]["content"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| format!("{}: response has no message", self.name()))
+    }
+}
+
+/// Anthropic `/v1/messages`
+pub struct AnthropicLlm {
+    url: String,
+    model: String,
+    api_key: String,
+}
+
+#[async_trait]
+impl LlmProvider for AnthropicLlm {
+    fn name(&self) -> String {
+        format!("anthropic/{}", self.model)
+    }
+
+    async fn complete(&self, system: &str, prompt: &str, max_tokens: usize) -> Result<String, String> {
+        let request = get_client()
+            .post(format!("{}/v1/messages", self.url))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01");
+        let body = json!({
+            "model": self.model,
+            "max_tokens": max_tokens,
+            "system": system,
+            "messages": [{ "role": "user", "content": prompt }],
+        });
+        let reply = post_json(request, body, &self.name()).await?;
+        let text: String = reply["content"]
+            .as_array()
+            .ok_or_else(|| format!("{}: response has no content", self.name()))?
+            .iter()
+            .filter_map(|block| block["text"].as_str())
+            .collect();
+        Ok(text)
+    }
+}
+
+async fn post_json(request: reqwest::RequestBuilder, body: serde_json::Value, name: &str) -> Result<serde_json::Value, String> {
+    let response = request
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("{}: request failed: {}", name, e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("{}: HTTP {}: {}", name, status, text));
+    }
+    response.json().await.map_err(|e| format!("{}: invalid response: {}", name, e))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LlmConfig {
     pub provider: String, // "ollama"
@@ -145,10 +497,15 @@ impl LlmConfig {
 }
 
 pub async fn propose_cues(content: &str, config: &LlmConfig, known_cues: &[String]) -> Result<Vec<String>, String> {
-    match config.provider.as_str() {
-        "ollama" => propose_cues_ollama(content, config, known_cues).await,
-        _ => Err(format!("Unsupported provider: {}", config.provider)),
-    }
+    let config = crate::config::LlmConfig {
+        enabled: true,
+        provider: config.provider.clone(),
+        model: config.model.clone(),
+        url: config.ollama_url.clone(),
+        api_key: config.api_key.clone(),
+        ..Default::default()
+    };
+    LlmClient::new(config).propose_cues(content, known_cues).await
 }
 
 // Deprecated, but keeping for compatibility/reference
@@ -271,8 +628,100 @@ pub fn parse_extraction_response(response_text: &str, content: &str) -> (String,
     (summary, cues)
 }
 
-// Deprecated, but keeping for compatibility/reference
-async fn propose_cues_ollama(content: &str, config: &LlmConfig, known_cues: &[String]) -> Result<Vec<String>, String> {
+/// System prompt for answer synthesis
+pub const ANSWER_PROMPT: &str = "Answer the question using only the verified context. Each context entry starts with a \
+citation marker such as [1]; cite the markers of the entries you rely on right after the statements they support. \
+If the context does not contain the answer, say that you don't know. Be concise.";
+
+/// User prompt for answer synthesis: the context block, then the question
+pub fn answer_prompt(question: &str, context: &str) -> String {
+    format!("{}\n\nQuestion: {}", context, question.trim())
+}
+
+/// System prompt for fact extraction
+const RERANK_PROMPT: &str = r#"Rate how well each numbered passage answers the query, from 0 (unrelated) to 10 (answers it directly).
+Return ONLY a JSON object with one score per passage, in passage order:
+{"scores": [7, 0, 3]}"#;
+
+/// Scores of a rerank reply; Err unless there is exactly one number per passage
+pub fn parse_rerank_scores(response_text: &str, expected: usize) -> Result<Vec<f32>, String> {
+    let clean_text = response_text
+        .trim()
+        .trim_start_matches("```json")
+        .trim_start_matches("```")
+        .trim_end_matches("```")
+        .trim();
+    let json_start = clean_text.find('{').unwrap_or(0);
+    let json_end = clean_text.rfind('}').map(|i| i + 1).unwrap_or(clean_text.len());
+
+    let parsed = serde_json::from_str::<serde_json::Value>(&clean_text[json_start..json_end.max(json_start)])
+        .map_err(|e| format!("Failed to parse rerank scores from LLM response ({}). Response was: {}", e, response_text))?;
+    let Some(items) = parsed.get("scores").and_then(|v| v.as_array()) else {
+        return Err(format!("LLM response has no scores list. Response was: {}", response_text));
+    };
+    let scores: Vec<f32> = items.iter().filter_map(|v| v.as_f64()).map(|v| v as f32).collect();
+    if scores.len() != expected || scores.len() != items.len() {
+        return Err(format!("Expected {} rerank scores, got {}. Response was: {}", expected, items.len(), response_text));
+    }
+    Ok(scores)
+}
+
+const EXTRACT_FACTS_PROMPT: &str = r#"You are a fact extraction engine. List the discrete facts stated in the text as (subject, predicate, object) triples.
+
+OUTPUT FORMAT (CRITICAL): {"facts": [{"subject": "...", "predicate": "...", "object": "..."}, ...]}
+
+EXAMPLE:
+Input: "The payment service listens on port 8443 and is owned by the billing team."
+Output: {"facts": [{"subject": "payment service", "predicate": "listens on port", "object": "8443"}, {"subject": "payment service", "predicate": "is owned by", "object": "billing team"}]}
+
+RULES:
+- Only facts the text states; no guesses or general knowledge
+- Keep each part short: a name, a verb phrase, a value
+- Use the full name of the subject, not a pronoun
+- Return {"facts": []} if the text states no facts
+- Return ONLY valid JSON"#;
+
+/// Facts in a reply to the fact extraction prompt: objects with `subject`,
+/// `predicate` and `object`, or `[subject, predicate, object]` arrays.
+/// Incomplete triples are skipped; a reply without a `facts` list is an error.
+pub fn parse_facts_response(response_text: &str) -> Result<Vec<Fact>, String> {
+    let clean_text = response_text
+        .trim()
+        .trim_start_matches("```json")
+        .trim_start_matches("```")
+        .trim_end_matches("```")
+        .trim();
+    let json_start = clean_text.find('{').unwrap_or(0);
+    let json_end = clean_text.rfind('}').map(|i| i + 1).unwrap_or(clean_text.len());
+
+    let parsed = serde_json::from_str::<serde_json::Value>(&clean_text[json_start..json_end.max(json_start)])
+        .map_err(|e| format!("Failed to parse facts from LLM response ({}). Response was: {}", e, response_text))?;
+    let Some(items) = parsed.get("facts").and_then(|v| v.as_array()) else {
+        return Err(format!("LLM response has no facts list. Response was: {}", response_text));
+    };
+
+    let part = |v: Option<&serde_json::Value>| v.and_then(|v| v.as_str()).map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+    let mut facts: Vec<Fact> = Vec::new();
+    for item in items {
+        let (subject, predicate, object) = match item {
+            serde_json::Value::Array(parts) => (part(parts.first()), part(parts.get(1)), part(parts.get(2))),
+            _ => (part(item.get("subject")), part(item.get("predicate")), part(item.get("object"))),
+        };
+        if let (Some(subject), Some(predicate), Some(object)) = (subject, predicate, object) {
+            let fact = Fact { subject, predicate, object };
+            if !facts.contains(&fact) {
+                facts.push(fact);
+            }
+        }
+        if facts.len() == MAX_FACTS {
+            break;
+        }
+    }
+    Ok(facts)
+}
+
+/// System prompt for cue proposal
+fn propose_cues_prompt(known_cues: &[String]) -> String {
     let context_hint = if !known_cues.is_empty() {
         format!(
             "I have already identified these potential cues based on keywords: {:?}. Use them as a starting point.\n   CRITICAL: The system is deterministic. Your goal is SEMANTIC EXPANSION (synonyms, hypernyms) to aid recall.\n   Do NOT hallucinate unrelated concepts or go 'crazy'. Keep suggestions grounded in the content.",
@@ -282,7 +731,7 @@ async fn propose_cues_ollama(content: &str, config: &LlmConfig, known_cues: &[St
         String::new()
     };
     
-    let system_prompt = format!(r#"You are a semantic tagging engine. Extract rich, queryable cues to enable powerful recall.
+    format!(r#"You are a semantic tagging engine. Extract rich, queryable cues to enable powerful recall.
 {} 
 
 OUTPUT FORMAT (CRITICAL): {{"cues": ["key:value", "key:value", ...]}}
@@ -310,35 +759,7 @@ RULES:
 - NO spaces or special characters in cues
 - Extract 5-8 diverse cues per memory
 - Include semantic neighbors (e.g., "meal" → also add "food", "recipe")
-- Return ONLY valid JSON"#, context_hint);
-
-    let url = format!("{}/api/generate", config.ollama_url);
-    
-    let response = get_client()
-        .post(&url)
-        .json(&json!({
-            "model": config.model,
-            "system": system_prompt,
-            "prompt": content,
-            "stream": false
-        }))
-        .send()
-        .await
-        .map_err(|e| format!("Ollama connection error: {}. Is Ollama running?", e))?;
-
-    if !response.status().is_success() {
-        let text = response.text().await.unwrap_or_default();
-        return Err(format!("Ollama API error: {}", text));
-    }
-
-    let body: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
-    
-    // Extract response text from Ollama format
-    let response_text = body["response"]
-        .as_str()
-        .ok_or("Invalid Ollama response format")?;
-    
-    parse_proposal_response(response_text)
+- Return ONLY valid JSON"#, context_hint)
 }
 
 pub fn parse_proposal_response(response_text: &str) -> Result<Vec<String>, String> {
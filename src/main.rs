@@ -1,5 +1,6 @@
 use cuemap::auth::AuthConfig;
-use cuemap::config::CueGenStrategy;
+use cuemap::crypto::EncryptionKey;
+use cuemap::config::{CueGenStrategy, EmbeddingStrategy};
 use cuemap::semantic::SemanticEngine;
 use cuemap::config;
 use cuemap::*;
@@ -29,6 +30,7 @@ struct Cli {
 #[derive(clap::Subcommand, Debug)]
 enum Commands {
     /// Start the CueMap server
+    #[command(alias = "serve")]
     Start(StartArgs),
 
     /// Add a memory via natural language
@@ -63,6 +65,163 @@ enum Commands {
     Logs(LogsArgs),
     /// Stop the background server
     Stop(StopArgs),
+
+    /// Summarize a snapshot file offline (checksum, codec, encryption, counts)
+    Inspect(InspectArgs),
+    /// Export a project from the data directory without a running server
+    Export(ExportArgs),
+    /// Restore a project in the data directory from an archive or snapshot generation
+    Restore(RestoreArgs),
+    /// Compact project indexes and rewrite their snapshots offline
+    Compact(CompactArgs),
+
+    /// Run ranking eval suites (MRR, NDCG, recall@k) and diff them against golden traces
+    Eval(EvalArgs),
+    /// Re-run logged recall queries against the data directory (e.g. a restored snapshot)
+    Replay(ReplayArgs),
+}
+
+/// Data directory of the offline admin commands, layered like `start`
+/// (config file, then `--data-dir`)
+#[derive(clap::Args, Debug)]
+struct DataDirArgs {
+    /// Config file path (default: ~/.cuemap/server_config.toml)
+    #[arg(long)]
+    config: Option<String>,
+
+    /// Config profile (default: "default")
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Data directory (overrides config)
+    #[arg(short, long)]
+    data_dir: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+struct InspectArgs {
+    /// Snapshot file (`{project}.bin`, a sidecar, or a file in a snapshot generation)
+    snapshot: PathBuf,
+    /// Project whose snapshot key decrypts the file (default: from the file name)
+    #[arg(short, long)]
+    project: Option<String>,
+    /// List every memory with its cues and content
+    #[arg(long)]
+    memories: bool,
+    #[command(flatten)]
+    data: DataDirArgs,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum ExportFormat {
+    /// One memory record per line (as `GET /projects/:id/export`)
+    Jsonl,
+    /// Full project archive (as `GET /projects/:id/archive`)
+    Archive,
+}
+
+#[derive(Parser, Debug)]
+struct ExportArgs {
+    #[arg(short, long)]
+    project: String,
+    #[arg(long, value_enum, default_value_t = ExportFormat::Jsonl)]
+    format: ExportFormat,
+    /// Output file (default: stdout)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    #[command(flatten)]
+    data: DataDirArgs,
+}
+
+#[derive(Parser, Debug)]
+struct RestoreArgs {
+    #[arg(short, long)]
+    project: String,
+    /// Project archive (from `export --format archive` or `GET /projects/:id/archive`)
+    #[arg(long, required_unless_present = "generation", conflicts_with = "generation")]
+    from: Option<PathBuf>,
+    /// Snapshot generation (see `GET /projects/:id/snapshots`)
+    #[arg(long)]
+    generation: Option<String>,
+    /// Run even though a background server seems to be running
+    #[arg(long)]
+    force: bool,
+    #[command(flatten)]
+    data: DataDirArgs,
+}
+
+#[derive(Parser, Debug)]
+struct CompactArgs {
+    /// Only this project (default: every project in the data directory)
+    #[arg(short, long)]
+    project: Option<String>,
+    /// Run even though a background server seems to be running
+    #[arg(long)]
+    force: bool,
+    #[command(flatten)]
+    data: DataDirArgs,
+}
+
+#[derive(Parser, Debug)]
+struct EvalArgs {
+    /// Suite file, or a directory of `.yaml`/`.yml`/`.json` suites
+    #[arg(long)]
+    suite: PathBuf,
+    /// Run against this server instead of an in-process engine
+    #[arg(long)]
+    url: Option<String>,
+    /// Project queried on the server (default: the suite's `project`)
+    #[arg(short, long)]
+    project: Option<String>,
+    /// API key for the server (default: CUEMAP_API_KEY)
+    #[arg(long)]
+    api_key: Option<String>,
+    /// Add the suites' memories to the server's project before querying
+    #[arg(long)]
+    seed: bool,
+    /// Largest score difference from a golden trace that still matches
+    #[arg(long, default_value_t = eval::DEFAULT_EPSILON)]
+    epsilon: f64,
+    /// Record the current rankings as the golden traces (rewrites the suite files)
+    #[arg(long)]
+    update_golden: bool,
+    /// Print the reports as JSON
+    #[arg(long)]
+    json: bool,
+    /// A/B mode: also run the suites against this config file or server URL (the
+    /// candidate) and report significant ranking differences from the baseline
+    #[arg(long)]
+    against: Option<String>,
+    /// Significance level of the A/B comparison (two-sided p-value)
+    #[arg(long, default_value_t = eval::DEFAULT_SIGNIFICANCE)]
+    alpha: f64,
+    /// Config file whose tuning the in-process engine uses (default: ~/.cuemap/server_config.toml)
+    #[arg(long)]
+    config: Option<String>,
+    /// Config profile (default: "default")
+    #[arg(long)]
+    profile: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+struct ReplayArgs {
+    /// Query log written by `start --query-log` (`recall_queries.jsonl`)
+    log: PathBuf,
+    /// Only this project's queries
+    #[arg(short, long)]
+    project: Option<String>,
+    /// Only the most recent N queries
+    #[arg(long)]
+    last: Option<usize>,
+    /// Also write the queries as an eval suite (requires --project), to record
+    /// golden traces with `cuemap eval --url ... --update-golden`
+    #[arg(long)]
+    suite_out: Option<PathBuf>,
+    /// Print the summary as JSON
+    #[arg(long)]
+    json: bool,
+    #[command(flatten)]
+    data: DataDirArgs,
 }
 
 #[derive(Parser, Debug)]
@@ -142,10 +301,38 @@ struct StartArgs {
     #[arg(long)]
     agent_throttle: Option<u64>,
 
+    /// Extra directory for the agent as PATH or PATH=PROJECT (repeatable)
+    #[arg(long = "agent-root")]
+    agent_roots: Vec<String>,
+
+    /// Only ingest files matching this glob (repeatable)
+    #[arg(long = "agent-include")]
+    agent_include: Vec<String>,
+
+    /// Never ingest files matching this glob, e.g. `*.lock` (repeatable)
+    #[arg(long = "agent-exclude")]
+    agent_exclude: Vec<String>,
+
+    /// Skip files larger than this many bytes
+    #[arg(long)]
+    agent_max_file_size: Option<u64>,
+
+    /// Don't attach git branch/author/commit cues to agent chunks
+    #[arg(long)]
+    agent_no_git: bool,
+
+    /// Also ingest git commit history as memories
+    #[arg(long)]
+    agent_git_history: bool,
+
     /// Cue generation strategy
     #[arg(long)]
     cuegen: Option<CueGenStrategy>,
 
+    /// Embedding provider for hybrid recall (default for all projects)
+    #[arg(long)]
+    embeddings: Option<EmbeddingStrategy>,
+
     /// Disable background jobs (for benchmarking)
     #[arg(long)]
     disable_bg_jobs: bool,
@@ -154,10 +341,68 @@ struct StartArgs {
     #[arg(long)]
     disable_snapshots: bool,
 
+    /// Unload projects idle for N seconds, reloading them from their snapshots on next access
+    #[arg(long)]
+    archive_idle: Option<u64>,
+
+    /// Keep at most N projects in memory, unloading the least recently used ones
+    #[arg(long)]
+    max_resident_projects: Option<usize>,
+
+    /// Keep N earlier snapshots per project for point-in-time restore
+    #[arg(long)]
+    snapshot_generations: Option<usize>,
+
+    /// Keep at most one snapshot generation per N seconds (default 3600)
+    #[arg(long)]
+    snapshot_generation_interval: Option<u64>,
+
+    /// Snapshot compression codec (none, zstd, lz4)
+    #[arg(long, value_enum)]
+    snapshot_codec: Option<persistence::SnapshotCodec>,
+
+    /// zstd level for snapshots, 1 (fastest) to 22 (smallest)
+    #[arg(long)]
+    snapshot_compression_level: Option<i32>,
+
+    /// Check the checksums of every snapshot in the data directory, report corrupt
+    /// or truncated files and exit (status 1 if any is corrupt) instead of serving
+    #[arg(long)]
+    verify_snapshots: bool,
+
     /// Enable autonomous systems consolidation (daily job)
     #[arg(long)]
     enable_consolidation: bool,
 
+    /// Projects with their own series in /metrics; the rest are summed under project="(other)" (0 = none)
+    #[arg(long)]
+    metrics_max_projects: Option<usize>,
+
+    /// Keep recalls taking at least N ms in the slow-query log (GET /debug/slow-queries)
+    #[arg(long)]
+    slow_query_ms: Option<u64>,
+
+    /// Log each recall's normalized cues to recall_queries.jsonl for `cuemap replay`
+    #[arg(long)]
+    query_log: bool,
+
+    /// Log output: text, or one JSON object per line (also CUEMAP_LOG_FORMAT)
+    #[arg(long, value_enum)]
+    log_format: Option<config::LogFormat>,
+
+    /// Export traces and metrics to this OTLP gRPC collector, e.g. http://localhost:4317
+    /// (`otel` feature; also OTEL_EXPORTER_OTLP_ENDPOINT)
+    #[arg(long)]
+    otlp_endpoint: Option<String>,
+
+    /// Service name reported to the collector (default "cuemap")
+    #[arg(long)]
+    otel_service_name: Option<String>,
+
+    /// Fraction of new traces to sample, 0 to 1
+    #[arg(long)]
+    otel_sample_ratio: Option<f64>,
+
     // ========== Cloud Backup Options ==========
     
     /// Cloud backup provider (s3, gcs, azure, local)
@@ -184,6 +429,34 @@ struct StartArgs {
     #[arg(long)]
     cloud_auto_backup: bool,
 
+    /// Continuously replicate changed projects to the cloud bucket every N seconds
+    #[arg(long)]
+    cloud_replicate_interval: Option<u64>,
+
+    // ========== Follower (Read Replica) Options ==========
+
+    /// Follow a primary server URL, or "cloud" for the configured bucket (read-only replica)
+    #[arg(long)]
+    follow: Option<String>,
+
+    /// Follower sync interval in seconds
+    #[arg(long, default_value_t = 30)]
+    follow_interval: u64,
+
+    /// API key sent to the primary when following a server (or CUEMAP_FOLLOW_API_KEY)
+    #[arg(long)]
+    follow_api_key: Option<String>,
+
+    // ========== Snapshot Encryption Options ==========
+
+    /// Passphrase for snapshot encryption-at-rest (prefer CUEMAP_SNAPSHOT_KEY)
+    #[arg(long)]
+    snapshot_key: Option<String>,
+
+    /// Previous snapshot passphrase accepted on load (repeat for key rotation)
+    #[arg(long)]
+    previous_snapshot_key: Vec<String>,
+
     /// Log file path
     #[arg(long)]
     log_file: Option<String>,
@@ -246,6 +519,15 @@ enum IngestType {
         /// Only follow links within the same domain
         #[arg(long, default_value = "true")]
         same_domain_only: bool,
+        /// Treat the URL as a sitemap or RSS/Atom feed (detected from the URL otherwise)
+        #[arg(long)]
+        feed: bool,
+        /// Re-read the feed every N seconds
+        #[arg(long)]
+        refresh_interval: Option<u64>,
+        /// Also store facts extracted by the project's LLM as separate memories
+        #[arg(long)]
+        extract_facts: bool,
     },
 }
 
@@ -268,6 +550,9 @@ struct RecallArgs {
     /// Token budget for grounded recall (context window)
     #[arg(long, default_value = "500")]
     token_budget: u32,
+    /// Model the grounded context is for; picks the token counter (e.g. gpt-4o)
+    #[arg(long)]
+    model: Option<String>,
     /// Disable automatic reinforcement during recall
     #[arg(long)]
     no_auto_reinforce: bool,
@@ -445,19 +730,59 @@ async fn main() {
                 if let Some(a) = &args.assets_dir { config.server.assets_dir = Some(a.clone()); }
                 if let Some(s) = args.snapshot_interval { config.persistence.snapshot_interval_seconds = s; }
                 if let Some(c) = &args.cuegen { config.search.cuegen_strategy = c.clone(); }
+                if let Some(e) = &args.embeddings { config.embeddings.strategy = e.clone(); }
                 if let Some(t) = args.agent_throttle { config.agent.throttle_ms = t; }
                 if let Some(w) = &args.agent_dir { 
                     config.agent.watch_dir = Some(w.clone()); 
                     config.agent.enabled = true;
                 }
+                for spec in &args.agent_roots {
+                    let root = config::AgentRoot::parse(spec).unwrap_or_else(|e| {
+                        eprintln!("Error: {}", e);
+                        std::process::exit(1);
+                    });
+                    config.agent.roots.push(root);
+                    config.agent.enabled = true;
+                }
+                config.agent.filter.include.extend(args.agent_include.iter().cloned());
+                config.agent.filter.exclude.extend(args.agent_exclude.iter().cloned());
+                if let Some(max) = args.agent_max_file_size { config.agent.filter.max_file_size = Some(max); }
+                if args.agent_no_git { config.agent.git.metadata = false; }
+                if args.agent_git_history { config.agent.git.history = true; }
+                if let Err(e) = config.agent.filter.validate() {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+                if let Err(e) = config.schedule.validate() {
+                    eprintln!("Error: [schedule] {}", e);
+                    std::process::exit(1);
+                }
 
                 // Boolean flags (only enable restriction/feature if flag is present, or if config says so)
                 // For "disable" flags: if CLI says disable, force disable.
                 if args.disable_bg_jobs { config.jobs.background_processing = false; }
                 if args.disable_snapshots { config.persistence.enabled = false; }
+                if let Some(i) = args.archive_idle { config.persistence.archive_idle_seconds = i; }
+                if let Some(m) = args.max_resident_projects { config.persistence.max_resident_projects = m; }
+                if let Some(n) = args.snapshot_generations { config.persistence.snapshot_generations = n; }
+                if let Some(i) = args.snapshot_generation_interval { config.persistence.snapshot_generation_interval_seconds = i; }
+                if let Some(codec) = args.snapshot_codec {
+                    config.persistence.compress_snapshots = codec != persistence::SnapshotCodec::None;
+                    config.persistence.snapshot_codec = codec;
+                }
+                if let Some(level) = args.snapshot_compression_level { config.persistence.snapshot_compression_level = level; }
                 
                 // For "enable" flags: if CLI says enable, force enable.
                 if args.enable_consolidation { config.jobs.consolidation_enabled = true; }
+
+                // Logging, metrics and telemetry overrides
+                if let Some(f) = args.log_format { config.server.log_format = f; }
+                if let Some(n) = args.metrics_max_projects { config.metrics.max_project_series = n; }
+                if let Some(ms) = args.slow_query_ms { config.metrics.slow_query_ms = ms; }
+                if args.query_log { config.metrics.query_log = true; }
+                if let Some(e) = &args.otlp_endpoint { config.telemetry.otlp_endpoint = Some(e.clone()); }
+                if let Some(n) = &args.otel_service_name { config.telemetry.service_name = n.clone(); }
+                if let Some(r) = args.otel_sample_ratio { config.telemetry.sample_ratio = r; }
                 
                 // Cloud overrides
                 if let Some(p) = &args.cloud_backup { config.persistence.cloud.provider = p.clone(); }
@@ -466,8 +791,24 @@ async fn main() {
                 if let Some(e) = &args.cloud_endpoint { config.persistence.cloud.endpoint = Some(e.clone()); }
                 if let Some(p) = &args.cloud_prefix { config.persistence.cloud.prefix = p.clone(); }
                 if args.cloud_auto_backup { config.persistence.cloud.auto_backup = true; }
+                if let Some(i) = args.cloud_replicate_interval { config.persistence.cloud.replicate_interval_seconds = i; }
 
-                run_server(config, args.load_static, args.child_process).await;
+                // Snapshot encryption overrides
+                if let Some(k) = &args.snapshot_key { config.security.snapshot_key = Some(k.clone()); }
+                if !args.previous_snapshot_key.is_empty() {
+                    config.security.previous_snapshot_keys = args.previous_snapshot_key.clone();
+                }
+
+                if args.verify_snapshots {
+                    verify_snapshots(&PathBuf::from(&config.server.data_dir).join("snapshots"));
+                }
+
+                let follow = args.follow.clone().map(|source| FollowArgs {
+                    source,
+                    interval: args.follow_interval,
+                    api_key: args.follow_api_key.clone().or_else(|| std::env::var("CUEMAP_FOLLOW_API_KEY").ok()),
+                });
+                run_server(config, args.load_static, follow, args.child_process).await;
             }
         },
         Commands::Add(args) => handle_add(args).await,
@@ -482,10 +823,50 @@ async fn main() {
         Commands::Status(args) => handle_status(args).await,
         Commands::Logs(args) => handle_logs(args).await,
         Commands::Stop(args) => handle_stop(args).await,
+        Commands::Inspect(args) => handle_inspect(args),
+        Commands::Export(args) => handle_export(args),
+        Commands::Restore(args) => handle_restore(args),
+        Commands::Compact(args) => handle_compact(args),
+        Commands::Eval(args) => handle_eval(args).await,
+        Commands::Replay(args) => handle_replay(args).await,
+    }
+}
+
+/// `start --verify-snapshots`: print a checksum report of every snapshot file and exit
+fn verify_snapshots(dir: &Path) -> ! {
+    let checks = persistence::PersistenceManager::verify_snapshots_in_dir(dir);
+    if checks.is_empty() {
+        println!("No snapshots found in {}", dir.display());
+        std::process::exit(0);
+    }
+    let mut corrupt = 0;
+    let mut unverified = 0;
+    for check in &checks {
+        let name = check.path.strip_prefix(dir).unwrap_or(&check.path).display();
+        match check.status {
+            persistence::SnapshotStatus::Ok => println!("ok          {} ({} bytes)", name, check.bytes),
+            persistence::SnapshotStatus::Unverified => {
+                unverified += 1;
+                println!("unverified  {} ({} bytes, written without a checksum)", name, check.bytes);
+            }
+            persistence::SnapshotStatus::Corrupt => {
+                corrupt += 1;
+                println!("CORRUPT     {}: {}", name, check.error.as_deref().unwrap_or("unknown error"));
+            }
+        }
     }
+    println!("\n{} files checked: {} corrupt, {} without checksum", checks.len(), corrupt, unverified);
+    std::process::exit(if corrupt > 0 { 1 } else { 0 });
 }
 
-async fn run_server(config: config::ServerConfig, load_static: Option<String>, is_child: bool) {
+/// `--follow` settings for read-replica mode
+struct FollowArgs {
+    source: String,
+    interval: u64,
+    api_key: Option<String>,
+}
+
+async fn run_server(config: config::ServerConfig, load_static: Option<String>, follow: Option<FollowArgs>, is_child: bool) {
     // Extract commonly used configs
     let server_config = &config.server;
     let auth_config_struct = &config.security;
@@ -499,13 +880,34 @@ async fn run_server(config: config::ServerConfig, load_static: Option<String>, i
         .add_directive("cuemap::jobs=warn".parse().unwrap())
         .add_directive("tiktoken_rs=warn".parse().unwrap());
 
-    // Build layers
-    let stdout_layer = fmt::layer().with_writer(std::io::stdout);
-
-    Registry::default()
-        .with(filter)
-        .with(stdout_layer)
-        .init();
+    // Build layers; each has its own filter so the OTLP layer can see debug spans the logs leave out
+    let stdout_layer = match server_config.log_format {
+        config::LogFormat::Text => fmt::layer().with_writer(std::io::stdout).with_filter(filter).boxed(),
+        // Each line carries the fields of the request span it was logged in (request_id, route, ...)
+        config::LogFormat::Json => fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(false)
+            .with_writer(std::io::stdout)
+            .with_filter(filter)
+            .boxed(),
+    };
+    let registry = Registry::default().with(stdout_layer);
+    let telemetry_error = match telemetry::tracing_layer(&config.telemetry) {
+        Ok(Some(otel_layer)) => {
+            registry.with(otel_layer).init();
+            None
+        }
+        Ok(None) => {
+            registry.init();
+            None
+        }
+        Err(e) => {
+            registry.init();
+            Some(e)
+        }
+    };
     
     // Write PID file for the server
     let pid = std::process::id();
@@ -516,6 +918,11 @@ async fn run_server(config: config::ServerConfig, load_static: Option<String>, i
     
     info!("CueMap Rust Engine - Production Mode");
     info!("Logs are written to stdout");
+    match (&telemetry_error, &config.telemetry.otlp_endpoint) {
+        (Some(e), _) => warn!("Telemetry: OTLP export disabled: {}", e),
+        (None, Some(endpoint)) => info!("Telemetry: exporting traces to {}", endpoint),
+        _ => {}
+    }
     
     // Initialize authentication
     // Adapt SecurityConfig to AuthConfig
@@ -523,7 +930,12 @@ async fn run_server(config: config::ServerConfig, load_static: Option<String>, i
     
     // Check for start mode
     let is_static = load_static.is_some();
+    // Followers replace their data from the primary, so they never write their own
+    let read_only = is_static || follow.is_some();
     
+    if let Some(f) = &follow {
+        info!("Follower mode enabled (read-only), following: {}", f.source);
+    }
     if is_static {
         info!("Static loading mode enabled (read-only)");
         info!("Loading from: {}", load_static.as_ref().unwrap());
@@ -545,8 +957,6 @@ async fn run_server(config: config::ServerConfig, load_static: Option<String>, i
     let semantic_engine = SemanticEngine::new(Some(Path::new(&assets_path)));
     let cuegen_strategy = config.search.cuegen_strategy.clone();
 
-    
-    use cuemap::crypto::EncryptionKey;
 
      // Build the router with appropriate engine state
     info!("Multi-tenant mode enabled");
@@ -564,59 +974,26 @@ async fn run_server(config: config::ServerConfig, load_static: Option<String>, i
         config.tuning.clone(),
         config.llm.clone(),
     );
+    if config.embeddings.strategy != EmbeddingStrategy::None {
+        info!("Embeddings: {:?} provider enabled by default", config.embeddings.strategy);
+    }
+    mt_engine.set_embedding_config(config.embeddings.clone());
+    if cfg!(feature = "ocr") && config.ocr.enabled {
+        info!("OCR: images and scanned PDFs are read with {}", config.ocr.engine());
+    }
+    cuemap::agent::ocr::configure(config.ocr.clone());
+    if config.transcription.enabled {
+        info!("Transcription: audio and video are sent to {}", config.transcription.url);
+    }
+    cuemap::agent::transcribe::configure(config.transcription.clone());
 
-    // Master Key Discovery Hierarchy
-    let master_key = if let Ok(key_hex) = std::env::var("CUEMAP_MASTER_KEY") {
-         // 1. Env Var (Hex) - Highest priority for automation
-         match hex::decode(&key_hex) {
-             Ok(bytes) if bytes.len() == 32 => {
-                 info!("Security: Master key loaded from CUEMAP_MASTER_KEY (Hex)");
-                 Some(Arc::new(EncryptionKey::new(bytes)))
-             }
-             _ => {
-                 error!("Security: CUEMAP_MASTER_KEY must be a 32-byte hex string");
-                 None
-             }
-         }
-    } else if let Ok(pass) = std::env::var("CUEMAP_MASTER_PASSWORD") {
-        // 2. Env Var (Passphrase) - Secondary automation path
-        info!("Security: Deriving master key from CUEMAP_MASTER_PASSWORD...");
-        let salt = get_or_create_salt();
-        Some(Arc::new(EncryptionKey::from_passphrase(&pass, &salt)))
-    } else if let Some(key_hex) = &auth_config_struct.master_key {
-        // 3. Config File (Hex)
-        match hex::decode(key_hex) {
-            Ok(bytes) if bytes.len() == 32 => {
-                info!("Security: Master key loaded from config file");
-                Some(Arc::new(EncryptionKey::new(bytes)))
-            }
-            _ => {
-                error!("Security: master_key in config must be a 32-byte hex string");
-                None
-            }
-        }
-    } else if !is_child && std::io::stdin().is_terminal() {
-        // 4. Interactive Prompt (Fallback for humans)
-        println!("\n🔑 Encryption Required");
-        match rpassword::prompt_password("Enter Master Passphrase (will be derived via PBKDF2): ") {
-            Ok(pass) if !pass.is_empty() => {
-                let salt = get_or_create_salt();
-                info!("Security: Deriving master key from interactive passphrase...");
-                Some(Arc::new(EncryptionKey::from_passphrase(&pass, &salt)))
-            }
-            _ => {
-                warn!("Security: No passphrase entered. Server will run UNENCRYPTED.");
-                None
-            }
-        }
-    } else {
-        None
-    };
-
-    if let Some(key) = master_key {
+    if let Some(key) = discover_master_key(auth_config_struct, !is_child) {
         mt_engine.set_master_key(Some(key));
     }
 
+    // Snapshot encryption-at-rest (per-project derived keys)
+    let rotate_snapshot_keys = configure_snapshot_keyring(&mut mt_engine, auth_config_struct);
+
     // Set Signing Key from Config
     let signing_key = if let Some(secret) = &auth_config_struct.secret_key {
          Some(Arc::new(secret.clone().into_bytes()))
@@ -629,17 +1006,58 @@ async fn run_server(config: config::ServerConfig, load_static: Option<String>, i
         }
     };
 
+    if config.persistence.max_resident_projects > 0 {
+        // Unloading saves a snapshot, which read-only servers never write
+        let evict = !read_only && config.persistence.enabled;
+        info!(
+            "Admission: at most {} resident projects ({})",
+            config.persistence.max_resident_projects,
+            if evict { "least recently used are unloaded" } else { "new projects are refused" }
+        );
+        mt_engine.set_residency_limit(config.persistence.max_resident_projects, evict);
+    }
+
+    mt_engine.set_payload_cache_entries(config.persistence.payload_cache_entries);
+    mt_engine.set_default_schedule(config.schedule.clone());
+    if config.persistence.snapshot_generations > 0 {
+        info!(
+            "Snapshot generations: keeping {} per project, at most one per {}s",
+            config.persistence.snapshot_generations, config.persistence.snapshot_generation_interval_seconds
+        );
+        mt_engine.set_snapshot_generations(
+            config.persistence.snapshot_generations,
+            config.persistence.snapshot_generation_interval_seconds,
+        );
+    }
+    mt_engine.set_snapshot_compression(config.persistence.snapshot_compression());
+
     let mt_engine = Arc::new(mt_engine);
     
     // Auto-load all available snapshots
     info!("Loading snapshots from: {}", snapshots_dir);
-    let _ = mt_engine.load_all(); // Ignoring errors for brevity
+    for (project_id, result) in mt_engine.load_all() {
+        if let Err(e) = result {
+            // Most commonly a missing/rotated-out snapshot key for an encrypted snapshot
+            error!("Failed to load project '{}': {}", project_id, e);
+        }
+    }
+
+    // Complete a snapshot key rotation by re-writing everything with the current key
+    if rotate_snapshot_keys && !read_only && config.persistence.enabled {
+        let results = mt_engine.rotate_snapshot_keys();
+        let failed = results.values().filter(|r| r.is_err()).count();
+        info!("Security: Re-encrypted {} project snapshots with the current key ({} failed)", results.len() - failed, failed);
+    }
     
     // Setup shutdown handler
-    if !is_static {
+    if !read_only {
         if config.persistence.enabled {
             setup_multi_tenant_shutdown_handler(mt_engine.clone()).await;
             mt_engine.start_periodic_snapshots(Duration::from_secs(config.persistence.snapshot_interval_seconds));
+            if config.persistence.archive_idle_seconds > 0 {
+                info!("Archival: unloading projects idle for {}s", config.persistence.archive_idle_seconds);
+                mt_engine.start_archiver(Duration::from_secs(config.persistence.archive_idle_seconds));
+            }
         } else {
             warn!("Periodic snapshots and shutdown save are DISABLED.");
         }
@@ -647,10 +1065,39 @@ async fn run_server(config: config::ServerConfig, load_static: Option<String>, i
 
     
     // Initialize metrics collector
-    let metrics = Arc::new(cuemap::metrics::MetricsCollector::new());
+    let mut metrics = cuemap::metrics::MetricsCollector::with_max_project_series(config.metrics.max_project_series)
+        .with_slow_query_log(slow_queries::SlowQueryLog::new(config.metrics.slow_query_ms, config.metrics.slow_query_log_size));
+    if config.metrics.query_log {
+        let path = Path::new(&config.server.data_dir).join(query_log::QUERY_LOG_FILE);
+        info!("Query log: recording recall cues to {}", path.display());
+        metrics = metrics.with_query_log(query_log::QueryLog::open(
+            path,
+            config.metrics.query_log_max_mb * 1024 * 1024,
+            config.metrics.query_log_sample_rate,
+        ));
+    }
+    let metrics = Arc::new(metrics);
 
     let provider: Arc<dyn jobs::ProjectProvider> = mt_engine.clone();
-    let job_queue = Arc::new(jobs::JobQueue::new(provider, Some(metrics.clone()), !config.jobs.background_processing));
+    let job_queue = Arc::new(jobs::JobQueue::with_workers(
+        provider.clone(),
+        Some(metrics.clone()),
+        !config.jobs.background_processing,
+        config.jobs.workers.clone(),
+    ));
+    if config.jobs.background_processing && config.jobs.market_heatmap_interval_seconds > 0 {
+        job_queue.start_heatmap_sync(
+            provider,
+            Duration::from_secs(config.jobs.market_heatmap_interval_seconds),
+            config.jobs.market_heatmap_window_minutes.clamp(1, trending::MAX_TRENDING_WINDOW_MINS),
+        );
+    }
+
+    if telemetry_error.is_none() {
+        if let Err(e) = telemetry::start_metrics(&config.telemetry, mt_engine.clone(), metrics.clone(), job_queue.clone()) {
+            warn!("Telemetry: OTLP metrics export disabled: {}", e);
+        }
+    }
 
     // Start autonomous systems consolidation if enabled
     if config.jobs.consolidation_enabled {
@@ -673,24 +1120,63 @@ async fn run_server(config: config::ServerConfig, load_static: Option<String>, i
         });
     }
     
+    // Per-project maintenance schedules (GET/PUT /projects/:id/schedule)
+    if !read_only && config.jobs.scheduler_tick_seconds > 0 {
+        let scheduler = Arc::new(scheduler::Scheduler::new(
+            mt_engine.clone(),
+            job_queue.clone(),
+            config.persistence.enabled,
+        ));
+        scheduler.start(Duration::from_secs(config.jobs.scheduler_tick_seconds));
+    }
+    
     let mt_engine = mt_engine;
     
     // Initialize dynamic Agent Manager
-    let agent_manager = Arc::new(agent::manager::AgentManager::new(job_queue.clone(), mt_engine.clone()));
-
-    // Auto-start agents for projects with watch directories configured
-    for proj_stats in mt_engine.list_projects() {
-        if let Ok(meta) = mt_engine.load_project_meta(&proj_stats.project_id) {
-            if meta.agent_enabled {
-                if let Some(watch_dir) = meta.watch_dir {
-                    let agent_config = agent::AgentConfig {
-                        project_id: meta.project_id.clone(),
-                        watch_dir,
-                        throttle_ms: config.agent.throttle_ms,
-                        state_file: Some(std::path::PathBuf::from(&server_config.data_dir).join("snapshots").join(format!("{}_agent_state.json", meta.project_id))),
-                    };
-                    agent_manager.start_agent(&meta.project_id, agent_config).await;
+    let agent_manager = Arc::new(agent::manager::AgentManager::with_defaults(job_queue.clone(), mt_engine.clone(), config.agent.filter.clone()));
+
+    // Auto-start agents for projects with watch directories configured (not on followers,
+    // whose data comes from the primary)
+    if follow.is_none() {
+        for proj_stats in mt_engine.list_projects() {
+            if let Ok(meta) = mt_engine.load_project_meta(&proj_stats.project_id) {
+                if meta.agent_enabled {
+                    if let Some(watch_dir) = meta.watch_dir {
+                        let agent_config = agent::AgentConfig {
+                            project_id: meta.project_id.clone(),
+                            watch_dir,
+                            throttle_ms: config.agent.throttle_ms,
+                            state_file: Some(std::path::PathBuf::from(&server_config.data_dir).join("snapshots").join(format!("{}_agent_state.json", meta.project_id))),
+                            filter: Default::default(),
+                            git: config.agent.git.clone(),
+                            chunking: meta.chunking,
+                            extract_facts: config.agent.extract_facts,
+                        };
+                        agent_manager.start_agent(&meta.project_id, agent_config).await;
+                    }
+                }
+            }
+        }
+
+        // Server-wide roots from `[agent]` / `--agent-root`, each into its own project
+        if config.agent.enabled {
+            for root in config.agent.all_roots() {
+                if let Err(e) = mt_engine.get_or_create_project(root.project.clone()) {
+                    error!("Agent root `{}`: cannot open project '{}': {}", root.path, root.project, e);
+                    continue;
                 }
+                let key = agent::manager::root_agent_key(&root.project, &root.path);
+                let agent_config = agent::AgentConfig {
+                    project_id: root.project.clone(),
+                    watch_dir: root.path.clone(),
+                    throttle_ms: config.agent.throttle_ms,
+                    state_file: Some(std::path::PathBuf::from(&server_config.data_dir).join("snapshots").join(format!("{}_agent_state.json", key))),
+                    filter: root.filter.clone(),
+                    git: config.agent.git.clone(),
+                    chunking: mt_engine.get_project_chunking(&root.project).unwrap_or_default(),
+                    extract_facts: config.agent.extract_facts,
+                };
+                agent_manager.start_agent(&key, agent_config).await;
             }
         }
     }
@@ -706,7 +1192,8 @@ async fn run_server(config: config::ServerConfig, load_static: Option<String>, i
             &c.prefix,
             c.auto_backup
          ) {
-             Ok(conf) => {
+             Ok(mut conf) => {
+                  conf.compression = config.persistence.snapshot_compression();
                   match persistence::CloudBackupManager::new(conf).await {
                       Ok(m) => Some(Arc::new(m)),
                       Err(_) => None,
@@ -717,9 +1204,35 @@ async fn run_server(config: config::ServerConfig, load_static: Option<String>, i
     } else {
         None
     };
+
+    // Continuous replication (warm standby)
+    if let Some(cloud) = &cloud_backup {
+        let interval = config.persistence.cloud.replicate_interval_seconds;
+        if !read_only && interval > 0 {
+            let replicator = Arc::new(replication::Replicator::new(mt_engine.clone(), cloud.clone()));
+            replicator.start(Duration::from_secs(interval));
+        }
+    }
+    
+    // Read replica: pull projects from a primary server or a replicated bucket
+    if let Some(f) = follow {
+        let source = if f.source == "cloud" {
+            cloud_backup.clone().map(follower::FollowSource::Bucket)
+        } else {
+            Some(follower::FollowSource::Primary { url: f.source.clone(), api_key: f.api_key })
+        };
+        match source {
+            Some(source) => {
+                let follower = Arc::new(follower::Follower::new(mt_engine.clone(), source));
+                follower.start(Duration::from_secs(f.interval.max(1)));
+            }
+            None => error!("Follower: --follow cloud requires a configured cloud backup provider"),
+        }
+    }
     
     let app = Router::new()
-        .merge(api::routes(mt_engine, job_queue, metrics, auth_config, is_static, cloud_backup, signing_key, agent_manager.clone()))
+        .merge(api::routes(mt_engine, job_queue, metrics, auth_config, read_only, cloud_backup, signing_key, agent_manager.clone()))
+        .layer(axum::middleware::from_fn(telemetry::trace_request))
         .layer(CorsLayer::permissive());
 
     let addr = SocketAddr::from(([0, 0, 0, 0], server_config.port));
@@ -730,6 +1243,74 @@ async fn run_server(config: config::ServerConfig, load_static: Option<String>, i
 }
 
 /// Setup shutdown handler for multi-tenant mode
+/// Master key discovery hierarchy: `CUEMAP_MASTER_KEY`, `CUEMAP_MASTER_PASSWORD`,
+/// the config file, then (when `interactive`) a passphrase prompt
+fn discover_master_key(security: &config::SecurityConfig, interactive: bool) -> Option<Arc<EncryptionKey>> {
+    if let Ok(key_hex) = std::env::var("CUEMAP_MASTER_KEY") {
+         // 1. Env Var (Hex) - Highest priority for automation
+         match hex::decode(&key_hex) {
+             Ok(bytes) if bytes.len() == 32 => {
+                 info!("Security: Master key loaded from CUEMAP_MASTER_KEY (Hex)");
+                 Some(Arc::new(EncryptionKey::new(bytes)))
+             }
+             _ => {
+                 error!("Security: CUEMAP_MASTER_KEY must be a 32-byte hex string");
+                 None
+             }
+         }
+    } else if let Ok(pass) = std::env::var("CUEMAP_MASTER_PASSWORD") {
+        // 2. Env Var (Passphrase) - Secondary automation path
+        info!("Security: Deriving master key from CUEMAP_MASTER_PASSWORD...");
+        let salt = get_or_create_salt();
+        Some(Arc::new(EncryptionKey::from_passphrase(&pass, &salt)))
+    } else if let Some(key_hex) = &security.master_key {
+        // 3. Config File (Hex)
+        match hex::decode(key_hex) {
+            Ok(bytes) if bytes.len() == 32 => {
+                info!("Security: Master key loaded from config file");
+                Some(Arc::new(EncryptionKey::new(bytes)))
+            }
+            _ => {
+                error!("Security: master_key in config must be a 32-byte hex string");
+                None
+            }
+        }
+    } else if interactive && std::io::stdin().is_terminal() {
+        // 4. Interactive Prompt (Fallback for humans)
+        println!("\n🔑 Encryption Required");
+        match rpassword::prompt_password("Enter Master Passphrase (will be derived via PBKDF2): ") {
+            Ok(pass) if !pass.is_empty() => {
+                let salt = get_or_create_salt();
+                info!("Security: Deriving master key from interactive passphrase...");
+                Some(Arc::new(EncryptionKey::from_passphrase(&pass, &salt)))
+            }
+            _ => {
+                warn!("Security: No passphrase entered. Server will run UNENCRYPTED.");
+                None
+            }
+        }
+    } else {
+        None
+    }
+}
+
+/// Install the snapshot keyring when a snapshot key is configured. Returns
+/// whether previous keys are configured, i.e. a key rotation is pending.
+fn configure_snapshot_keyring(mt_engine: &mut multi_tenant::MultiTenantEngine, security: &config::SecurityConfig) -> bool {
+    if let Some(pass) = security.snapshot_key.as_ref().filter(|p| !p.is_empty()) {
+        let keyring = cuemap::crypto::SnapshotKeyring::new(pass.clone(), security.previous_snapshot_keys.clone());
+        info!("Security: Snapshot encryption enabled ({} previous keys)", security.previous_snapshot_keys.len());
+        let rotate = keyring.has_previous_keys();
+        mt_engine.set_snapshot_keyring(Some(Arc::new(keyring)));
+        rotate
+    } else {
+        if !security.previous_snapshot_keys.is_empty() {
+            warn!("Security: previous snapshot keys configured without a current snapshot key; ignoring");
+        }
+        false
+    }
+}
+
 async fn setup_multi_tenant_shutdown_handler(mt_engine: Arc<multi_tenant::MultiTenantEngine>) {
     tokio::spawn(async move {
         // Create futures for both SIGINT (Ctrl+C) and SIGTERM (docker stop)
@@ -787,6 +1368,7 @@ async fn setup_multi_tenant_shutdown_handler(mt_engine: Arc<multi_tenant::MultiT
                 error!("Possible cause: A project was locked by a long-running ingestion task.");
             }
         }
+        let _ = tokio::task::spawn_blocking(telemetry::shutdown).await;
         
         std::process::exit(0);
     });
@@ -866,6 +1448,7 @@ async fn handle_add(args: AddArgs) {
         cues: args.cues,
         disable_temporal_chunking: args.disable_temporal_chunking,
         async_ingest: args.async_ingest,
+        ..Default::default()
     };
 
     let res = client.post(format!("{}/memories", args.url))
@@ -907,7 +1490,7 @@ async fn handle_ingest(args: IngestArgs) {
                 eprintln!("✗ Read file failed: {}", path);
             }
         }
-        IngestType::Url { url, project, depth, same_domain_only } => {
+        IngestType::Url { url, project, depth, same_domain_only, feed, refresh_interval, extract_facts } => {
             let project = project.or_else(get_default_project).expect("Project ID required");
             let res = client.post("http://localhost:8080/ingest/url")
                     .header("X-Project-ID", project)
@@ -915,6 +1498,10 @@ async fn handle_ingest(args: IngestArgs) {
                         url, 
                         depth,
                         same_domain_only,
+                        feed: feed.then_some(true),
+                        refresh_interval_secs: refresh_interval,
+                        chunking: Default::default(),
+                        extract_facts,
                     })
                     .send()
                     .await;
@@ -936,6 +1523,7 @@ async fn handle_recall(args: RecallArgs) {
             query_text: args.query,
             limit: args.limit,
             token_budget: args.token_budget,
+            model: args.model,
             auto_reinforce: !args.no_auto_reinforce,
             projects: None,
             disable_pattern_completion: args.disable_pattern_completion,
@@ -1007,6 +1595,7 @@ async fn handle_recall(args: RecallArgs) {
             disable_systems_consolidation: args.disable_systems_consolidation,
             disable_alias_expansion: !args.enable_alias_expansion,
             depth: args.depth,
+            ..Default::default()
         };
         let res = client.post(format!("{}/recall", args.url))
             .header("X-Project-ID", project)
@@ -1513,3 +2102,436 @@ async fn handle_stop(_args: StopArgs) {
         }
     }
 }
+
+// ========== Offline admin commands ==========
+// These work on the data directory directly; stop the server first, or its
+// next snapshot overwrites what they write.
+
+fn load_admin_config(args: &DataDirArgs) -> config::ServerConfig {
+    let mut config = config::ServerConfig::load(args.config.clone().map(PathBuf::from), args.profile.clone())
+        .unwrap_or_else(|e| admin_exit(format!("Failed to load configuration: {}", e)));
+    if let Some(d) = &args.data_dir {
+        config.server.data_dir = d.clone();
+    }
+    config
+}
+
+/// Print an admin command's error and exit with status 1
+fn admin_exit(message: String) -> ! {
+    eprintln!("✗ {}", message);
+    std::process::exit(1);
+}
+
+/// Refuse to write while a detached server (which keeps its own copy in RAM) may be running
+fn ensure_server_stopped(force: bool) {
+    let pid_path = config::get_base_dir().join("server.pid");
+    if pid_path.exists() && !force {
+        admin_exit(format!(
+            "A server seems to be running ({} exists); stop it with `cuemap stop` or pass --force",
+            pid_path.display()
+        ));
+    }
+}
+
+/// The data directory's projects, configured like the server's (snapshot keys,
+/// compression, payload storage, generations). Nothing is loaded yet.
+fn open_offline_engine(config: &config::ServerConfig, master_key: Option<Arc<EncryptionKey>>) -> multi_tenant::MultiTenantEngine {
+    let snapshots_dir = PathBuf::from(&config.server.data_dir).join("snapshots");
+    if !snapshots_dir.exists() {
+        admin_exit(format!("No snapshots directory at {}", snapshots_dir.display()));
+    }
+    let assets_path = config.server.assets_dir.clone().unwrap_or_else(|| config.server.data_dir.clone());
+    let mut engine = multi_tenant::MultiTenantEngine::with_snapshots_dir(
+        &snapshots_dir,
+        config.search.cuegen_strategy.clone(),
+        SemanticEngine::new(Some(Path::new(&assets_path))),
+        config.tuning.clone(),
+        config.llm.clone(),
+    );
+    engine.set_embedding_config(config.embeddings.clone());
+    if master_key.is_some() {
+        engine.set_master_key(master_key);
+    }
+    configure_snapshot_keyring(&mut engine, &config.security);
+    engine.set_payload_cache_entries(config.persistence.payload_cache_entries);
+    engine.set_snapshot_generations(config.persistence.snapshot_generations, config.persistence.snapshot_generation_interval_seconds);
+    engine.set_snapshot_compression(config.persistence.snapshot_compression());
+    engine
+}
+
+fn handle_inspect(args: InspectArgs) {
+    use cuemap::persistence::{PersistenceManager, SnapshotStatus};
+    use cuemap::structures::{LexiconStats, MainStats};
+
+    let config = load_admin_config(&args.data);
+    let path = &args.snapshot;
+    let check = PersistenceManager::verify_snapshot_file(path);
+    println!("Snapshot:  {}", path.display());
+    println!("Size:      {} bytes", check.bytes);
+    match check.status {
+        SnapshotStatus::Ok => println!("Checksum:  ok (sha-256)"),
+        SnapshotStatus::Unverified => println!("Checksum:  none (written without a checksum)"),
+        SnapshotStatus::Corrupt => admin_exit(check.error.unwrap_or_else(|| "Snapshot is corrupt".to_string())),
+    }
+
+    let data = std::fs::read(path).unwrap_or_else(|e| admin_exit(format!("Failed to read {}: {}", path.display(), e)));
+    let body = PersistenceManager::checksummed_body(&data, path).unwrap_or_else(|e| admin_exit(e));
+    let encrypted = PersistenceManager::is_encrypted_snapshot(body);
+    println!("Encrypted: {}", if encrypted { "yes" } else { "no" });
+    if !encrypted {
+        let codec = PersistenceManager::snapshot_codec(body).map(|c| format!("{:?}", c).to_lowercase());
+        println!("Codec:     {}", codec.as_deref().unwrap_or("none"));
+    }
+
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    // Aliases and lexicon sidecars are engines and get listed like the main snapshot
+    if PersistenceManager::is_sidecar_file(name) && !name.ends_with("_aliases.bin") && !name.ends_with("_lexicon.bin") {
+        println!("Contents:  sidecar file (embeddings, history, co-occurrence, lexicon quality, heatmap or access stats)");
+        return;
+    }
+
+    let master_key = if args.memories { discover_master_key(&config.security, true) } else { None };
+    let engine = open_offline_engine(&config, None);
+    let keys = engine.snapshot_file_keys(path, args.project.as_deref());
+    // Memory ids, cues and content (or why it can't be shown), sorted by id
+    let loaded = if name.ends_with("lexicon.bin") {
+        PersistenceManager::load_from_path_with_keys::<LexiconStats>(path, &keys).map(|(memories, cues)| {
+            let rows: Vec<_> = memories
+                .iter()
+                .map(|m| (m.key().clone(), m.cues.clone(), m.access_content(master_key.as_deref())))
+                .collect();
+            (rows, cues.len())
+        })
+    } else {
+        PersistenceManager::load_from_path_with_keys::<MainStats>(path, &keys).map(|(memories, cues)| {
+            let rows: Vec<_> = memories
+                .iter()
+                .map(|m| (m.key().clone(), m.cues.clone(), m.access_content(master_key.as_deref())))
+                .collect();
+            (rows, cues.len())
+        })
+    };
+    let (mut rows, cue_count) = loaded.unwrap_or_else(|e| admin_exit(format!("Failed to load snapshot: {}", e)));
+    println!("Memories:  {}", rows.len());
+    println!("Cues:      {}", cue_count);
+
+    if args.memories {
+        rows.sort_by(|a, b| a.0.cmp(&b.0));
+        for (id, cues, content) in rows {
+            println!("\n{}", id);
+            println!("  cues:    {}", cues.join(", "));
+            match content {
+                Ok(text) => println!("  content: {}", text.chars().take(120).collect::<String>()),
+                Err(_) => println!("  content: [encrypted; set the master key to read it]"),
+            }
+        }
+    }
+}
+
+fn handle_export(args: ExportArgs) {
+    use std::io::Write;
+
+    let config = load_admin_config(&args.data);
+    let master_key = match args.format {
+        ExportFormat::Jsonl => discover_master_key(&config.security, true),
+        ExportFormat::Archive => None,
+    };
+    let engine = open_offline_engine(&config, master_key);
+    let ctx = engine.load_project(&args.project).unwrap_or_else(|e| admin_exit(e));
+
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(std::io::BufWriter::new(
+            File::create(path).unwrap_or_else(|e| admin_exit(format!("Failed to create {}: {}", path.display(), e))),
+        )),
+        None => Box::new(std::io::BufWriter::new(std::io::stdout().lock())),
+    };
+    let written = match args.format {
+        ExportFormat::Jsonl => ctx.main.memory_ids_by_age().iter().try_fold(0, |count, id| {
+            let Some(record) = ctx.main.export_record(id) else {
+                return Ok(count);
+            };
+            serde_json::to_writer(&mut out, &record)
+                .map_err(std::io::Error::from)
+                .and_then(|_| out.write_all(b"\n"))
+                .map(|_| count + 1)
+        }),
+        ExportFormat::Archive => {
+            let data = engine.dump_project(&args.project).unwrap_or_else(|e| admin_exit(e));
+            out.write_all(&data).map(|_| ctx.main.total_memories())
+        }
+    };
+    let written = written.and_then(|count| out.flush().map(|_| count)).unwrap_or_else(|e| admin_exit(format!("Export failed: {}", e)));
+    if let Some(path) = &args.output {
+        eprintln!("✓ Exported {} memories of '{}' to {}", written, args.project, path.display());
+    }
+}
+
+fn handle_restore(args: RestoreArgs) {
+    if !multi_tenant::validate_project_id(&args.project) {
+        admin_exit(format!("Invalid project ID '{}'", args.project));
+    }
+    ensure_server_stopped(args.force);
+    let config = load_admin_config(&args.data);
+    let engine = open_offline_engine(&config, None);
+
+    // Loaded first, so the state being replaced is kept as a generation when generations are enabled
+    let _ = engine.load_project(&args.project);
+    let (ctx, previous) = match (&args.from, &args.generation) {
+        (Some(path), _) => {
+            let data = std::fs::read(path).unwrap_or_else(|e| admin_exit(format!("Failed to read {}: {}", path.display(), e)));
+            let ctx = engine.restore_project(&args.project, &data).unwrap_or_else(|e| admin_exit(e));
+            engine.save_project(&args.project).unwrap_or_else(|e| admin_exit(e));
+            (ctx, None)
+        }
+        (None, Some(generation)) => engine.restore_generation(&args.project, generation).unwrap_or_else(|e| admin_exit(e)),
+        (None, None) => unreachable!("clap requires --from or --generation"),
+    };
+    println!(
+        "✓ Restored '{}' ({} memories, {} cues)",
+        args.project,
+        ctx.main.total_memories(),
+        ctx.main.total_cues()
+    );
+    if let Some(previous) = previous {
+        println!("  The replaced state was kept as generation {}", previous);
+    }
+}
+
+fn handle_compact(args: CompactArgs) {
+    ensure_server_stopped(args.force);
+    let config = load_admin_config(&args.data);
+    let engine = open_offline_engine(&config, None);
+
+    let project_ids = match args.project {
+        Some(id) => vec![id],
+        None => engine.list_snapshots(),
+    };
+    let mut failed = false;
+    for project_id in project_ids {
+        // Unloading saves the project and frees its RAM before the next one
+        let result = engine.load_project(&project_id).and_then(|ctx| {
+            let reports = ctx.compact_indexes();
+            drop(ctx);
+            engine.unload_project(&project_id)?;
+            Ok(reports)
+        });
+        match result {
+            Ok(reports) => {
+                let mut names: Vec<_> = reports.keys().cloned().collect();
+                names.sort();
+                for name in names {
+                    let r = &reports[&name];
+                    println!(
+                        "✓ {} {}: {} stale ids and {} empty cues removed, index {} -> {} bytes",
+                        project_id, name, r.stale_ids_removed, r.empty_cues_removed, r.index_bytes_before, r.index_bytes_after
+                    );
+                }
+            }
+            Err(e) => {
+                failed = true;
+                eprintln!("✗ {}: {}", project_id, e);
+            }
+        }
+    }
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+/// A server URL, or else a config file whose tuning an in-process engine uses
+fn eval_target(spec: Option<String>, args: &EvalArgs) -> eval::EvalTarget {
+    match spec {
+        Some(url) if url.starts_with("http://") || url.starts_with("https://") => eval::EvalTarget::Http {
+            url,
+            api_key: args.api_key.clone().or_else(|| std::env::var("CUEMAP_API_KEY").ok()),
+            project: args.project.clone(),
+            seed: args.seed,
+        },
+        config_path => {
+            let config = config::ServerConfig::load(config_path.map(PathBuf::from), args.profile.clone())
+                .unwrap_or_else(|e| admin_exit(format!("Failed to load configuration: {}", e)));
+            eval::EvalTarget::InProcess { tuning: config.tuning, cuegen_strategy: config.search.cuegen_strategy }
+        }
+    }
+}
+
+async fn handle_eval(args: EvalArgs) {
+    if args.against.is_some() && args.update_golden {
+        admin_exit("--update-golden cannot be combined with --against".to_string());
+    }
+    let target = eval_target(args.url.clone().or_else(|| args.config.clone()), &args);
+    let candidate = args.against.clone().map(|spec| eval_target(Some(spec), &args));
+
+    let files = eval::suite_files(&args.suite).unwrap_or_else(|e| admin_exit(e));
+    let mut reports = Vec::new();
+    let mut comparisons = Vec::new();
+    let mut failed = false;
+    for path in files {
+        let mut suite = eval::EvalSuite::load(&path).unwrap_or_else(|e| admin_exit(e));
+        let report = match eval::run_suite(&suite, &target, args.epsilon).await {
+            Ok(report) => report,
+            Err(e) => {
+                failed = true;
+                eprintln!("✗ {}: {}", suite.name, e);
+                continue;
+            }
+        };
+
+        if let Some(candidate) = &candidate {
+            match eval::run_suite(&suite, candidate, args.epsilon).await {
+                Ok(candidate_report) => {
+                    let comparison = eval::compare_reports(&report, &candidate_report, args.alpha);
+                    failed |= comparison.regressed();
+                    if !args.json {
+                        print_eval_comparison(&comparison, args.alpha);
+                    }
+                    comparisons.push(comparison);
+                }
+                Err(e) => {
+                    failed = true;
+                    eprintln!("✗ {} (--against): {}", suite.name, e);
+                }
+            }
+            continue;
+        }
+
+        if args.update_golden {
+            suite.record_golden(&report);
+            suite.save(&path).unwrap_or_else(|e| admin_exit(e));
+            eprintln!("✓ Recorded {} golden traces in {}", suite.queries.len(), path.display());
+        } else if !report.passed() {
+            failed = true;
+        }
+        if !args.json {
+            print_eval_report(&report);
+        }
+        reports.push(report);
+    }
+
+    if args.json {
+        let out = if candidate.is_some() { serde_json::to_string_pretty(&comparisons) } else { serde_json::to_string_pretty(&reports) };
+        println!("{}", out.unwrap());
+    }
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+fn print_eval_comparison(comparison: &eval::SuiteComparison, alpha: f64) {
+    println!("\nSuite {}: baseline vs candidate over {} judged queries", comparison.suite, comparison.judged);
+    println!("  {:<8} {:>9} {:>9} {:>8} {:>7}", "metric", "baseline", "candidate", "delta", "p");
+    for m in &comparison.metrics {
+        let verdict = match (m.significant, m.delta < 0.0) {
+            (true, true) => "  ✗ significantly worse",
+            (true, false) => "  ✓ significantly better",
+            _ => "",
+        };
+        println!("  {:<8} {:>9.3} {:>9.3} {:>+8.3} {:>7.3}{}", m.metric, m.baseline, m.candidate, m.delta, m.p_value, verdict);
+    }
+    if !comparison.changed.is_empty() {
+        println!("  Rankings changed for {} queries:", comparison.changed.len());
+        for change in &comparison.changed {
+            match change.ndcg_delta {
+                Some(delta) => println!("    {:<32} NDCG {:+.3}", change.name, delta),
+                None => println!("    {:<32} (no judgments)", change.name),
+            }
+        }
+    }
+    if comparison.metrics.iter().all(|m| !m.significant) {
+        println!("  No significant difference at p < {}", alpha);
+    }
+}
+
+fn print_eval_report(report: &eval::SuiteReport) {
+    println!("\nSuite {} ({} queries)", report.suite, report.queries.len());
+    for query in &report.queries {
+        let metrics = match query.metrics {
+            Some(m) => format!("MRR {:.3}  NDCG {:.3}  recall {:.3}", m.mrr, m.ndcg, m.recall),
+            None => "(no judgments)".to_string(),
+        };
+        let mark = if query.diffs.is_empty() { "✓" } else { "✗" };
+        println!("  {} {:<32} {}", mark, query.name, metrics);
+        for diff in &query.diffs {
+            println!("      {}", diff);
+        }
+    }
+    let s = &report.summary;
+    if s.judged > 0 {
+        println!("  Mean over {} judged queries: MRR {:.3}  NDCG {:.3}  recall {:.3}", s.judged, s.mean_mrr, s.mean_ndcg, s.mean_recall);
+    }
+    if s.golden_checked > 0 {
+        println!("  Golden traces: {}/{} match", s.golden_checked - s.golden_failed, s.golden_checked);
+    }
+}
+
+async fn handle_replay(args: ReplayArgs) {
+    let mut queries = query_log::read_query_log(&args.log).unwrap_or_else(|e| admin_exit(e));
+    if let Some(project) = &args.project {
+        queries.retain(|q| &q.project_id == project);
+    }
+    if let Some(last) = args.last {
+        queries.drain(..queries.len().saturating_sub(last));
+    }
+    if queries.is_empty() {
+        admin_exit(format!("No queries to replay in {}", args.log.display()));
+    }
+
+    if let Some(path) = &args.suite_out {
+        let project = args.project.clone().unwrap_or_else(|| admin_exit("--suite-out requires --project".to_string()));
+        let suite = eval::EvalSuite {
+            name: format!("{}-replay", project),
+            project: Some(project.clone()),
+            memories: Vec::new(),
+            queries: queries
+                .iter()
+                .enumerate()
+                .map(|(i, q)| eval::EvalQuery {
+                    name: format!("{}-{}", project, i + 1),
+                    cues: q.cues.clone(),
+                    query_text: None,
+                    limit: q.limit,
+                    relevant: Default::default(),
+                    golden: None,
+                })
+                .collect(),
+        };
+        suite.save(path).unwrap_or_else(|e| admin_exit(e));
+        eprintln!("✓ Wrote {} queries to {}", suite.queries.len(), path.display());
+    }
+
+    // The data directory's projects, read-only: no periodic snapshots, no jobs
+    let config = load_admin_config(&args.data);
+    let mut builder = cuemap::Client::builder()
+        .data_dir(&config.server.data_dir)
+        .snapshot_interval(None)
+        .background_jobs(false)
+        .cuegen_strategy(config.search.cuegen_strategy.clone())
+        .tuning(config.tuning.clone())
+        .embeddings(config.embeddings.clone());
+    if let Some(key) = discover_master_key(&config.security, true) {
+        builder = builder.master_key((*key).clone());
+    }
+    if let Some(pass) = config.security.snapshot_key.as_ref().filter(|p| !p.is_empty()) {
+        builder = builder.snapshot_keyring(cuemap::crypto::SnapshotKeyring::new(pass.clone(), config.security.previous_snapshot_keys.clone()));
+    }
+    let client = builder.build().unwrap_or_else(|e| admin_exit(e));
+
+    let summary = query_log::replay(&client, &queries).await;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&summary).unwrap());
+    } else {
+        let (logged, replayed) = (&summary.logged_latency, &summary.replay_latency);
+        println!("Replayed {} queries in {:.1}s ({} failed)", summary.queries, summary.wall_seconds, summary.failed);
+        println!("  {:<10} {:>9} {:>9} {:>9} {:>9}", "latency", "p50", "p95", "p99", "max");
+        println!("  {:<10} {:>9.2} {:>9.2} {:>9.2} {:>9.2}", "logged", logged.p50, logged.p95, logged.p99, logged.max);
+        println!("  {:<10} {:>9.2} {:>9.2} {:>9.2} {:>9.2}", "replay", replayed.p50, replayed.p95, replayed.p99, replayed.max);
+        println!("  Results per query: {:.2} logged, {:.2} now", summary.mean_logged_results, summary.mean_replay_results);
+        println!("  {} queries no longer find anything, {} now find results", summary.now_empty, summary.now_found);
+        for error in &summary.errors {
+            eprintln!("  ✗ {}", error);
+        }
+    }
+    if summary.failed > 0 {
+        std::process::exit(1);
+    }
+}
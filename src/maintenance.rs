@@ -0,0 +1,90 @@
+//! Forgetting: salience decay and low-salience pruning of a project's
+//! memories, run from `POST /maintenance/decay` and `POST /maintenance/prune`
+//! or on the project's schedule (`decay_interval_secs`, `prune_interval_secs`).
+//!
+//! Unset request fields fall back to the project's schedule, which falls back
+//! to the server's `[schedule]` defaults.
+
+use crate::engine::CueMapEngine;
+use crate::scheduler::ProjectSchedule;
+use crate::structures::MainStats;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DecayRequest {
+    /// Report what would change without changing anything
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Decay rate per hour (default: the schedule's `decay_rate`)
+    #[serde(default)]
+    pub rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PruneRequest {
+    /// Report the memories that would be pruned without deleting them
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Memories whose salience is below this are pruned (default: the
+    /// schedule's `prune_threshold`)
+    #[serde(default)]
+    pub threshold: Option<f64>,
+    /// ...once unused for this long (default: the schedule's `prune_max_idle_secs`)
+    #[serde(default)]
+    pub max_idle_secs: Option<u64>,
+}
+
+impl DecayRequest {
+    /// Decay rate to use, validated
+    pub fn rate(&self, schedule: &ProjectSchedule) -> Result<f64, String> {
+        let rate = self.rate.unwrap_or(schedule.decay_rate);
+        if !(rate.is_finite() && rate >= 0.0) {
+            return Err("rate must be a non-negative number".to_string());
+        }
+        Ok(rate)
+    }
+}
+
+impl PruneRequest {
+    /// Threshold and idle time to use, validated
+    pub fn resolve(&self, schedule: &ProjectSchedule) -> Result<(f64, u64), String> {
+        let threshold = self.threshold.unwrap_or(schedule.prune_threshold);
+        if !threshold.is_finite() {
+            return Err("threshold must be a number".to_string());
+        }
+        Ok((threshold, self.max_idle_secs.unwrap_or(schedule.prune_max_idle_secs)))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DecayReport {
+    pub dry_run: bool,
+    pub rate: f64,
+    pub memories: usize,
+    /// Memories whose dynamic salience decayed
+    pub decayed: usize,
+    /// ...of which the dynamic salience reached zero
+    pub zeroed: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PruneReport {
+    pub dry_run: bool,
+    pub threshold: f64,
+    pub max_idle_secs: u64,
+    /// Memories pruned (or, for a dry run, that would be)
+    pub pruned: usize,
+    /// Their ids, lowest salience first
+    pub memory_ids: Vec<String>,
+}
+
+/// Remove (or with `dry_run` list) the memories below `threshold` salience
+/// that have been idle for `max_idle_secs`. Pinned and often-reinforced
+/// memories are kept.
+pub fn prune_memories(engine: &CueMapEngine<MainStats>, threshold: f64, max_idle_secs: u64, dry_run: bool) -> PruneReport {
+    let mut memory_ids = engine.low_salience_candidates(threshold, max_idle_secs);
+    if !dry_run {
+        memory_ids.retain(|id| engine.delete_memory(id));
+    }
+    PruneReport { dry_run, threshold, max_idle_secs, pruned: memory_ids.len(), memory_ids }
+}
@@ -5,11 +5,46 @@
 
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::RwLock;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use crate::query_log::QueryLog;
+use crate::slow_queries::SlowQueryLog;
 
 /// Maximum latency samples to keep for P99 calculation
 const LATENCY_WINDOW_SIZE: usize = 1000;
 
+/// Latency samples kept per project
+const PROJECT_LATENCY_WINDOW_SIZE: usize = 200;
+
+/// Projects with their own `/metrics` series unless configured otherwise
+pub const DEFAULT_MAX_PROJECT_SERIES: usize = 50;
+
+/// `project` label of the series summing the projects beyond the cap (never a valid project id)
+pub const OTHER_PROJECTS_LABEL: &str = "(other)";
+
+#[derive(Default)]
+struct ProjectRecalls {
+    count: u64,
+    latencies: VecDeque<f64>,
+}
+
+/// A project's recall count and P99 latency (ms) over its recent recalls
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProjectRecallStats {
+    pub recalls: u64,
+    pub p99_latency_ms: f64,
+}
+
+/// One project's row of the per-project `/metrics` breakdown
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectSeries {
+    pub project: String,
+    pub memories: u64,
+    pub recalls: u64,
+    /// None for the `(other)` row, whose latencies can't be merged
+    pub recall_latency_p99: Option<f64>,
+    pub job_backlog: u64,
+}
+
 /// Collects and exposes Prometheus-format metrics
 pub struct MetricsCollector {
     /// Total memory ingestions since startup
@@ -18,6 +53,13 @@ pub struct MetricsCollector {
     pub recall_count: AtomicU64,
     /// Sliding window of recent recall latencies (ms)
     recall_latencies: RwLock<VecDeque<f64>>,
+    project_recalls: RwLock<HashMap<String, ProjectRecalls>>,
+    /// Projects with their own `/metrics` series (0 = no per-project series)
+    max_project_series: usize,
+    /// Recent recalls over the slow-query threshold
+    pub slow_queries: SlowQueryLog,
+    /// Recall queries kept for replay (`[metrics] query_log`)
+    pub query_log: Option<QueryLog>,
 }
 
 impl Default for MetricsCollector {
@@ -28,13 +70,35 @@ impl Default for MetricsCollector {
 
 impl MetricsCollector {
     pub fn new() -> Self {
+        Self::with_max_project_series(DEFAULT_MAX_PROJECT_SERIES)
+    }
+
+    pub fn with_max_project_series(max_project_series: usize) -> Self {
         Self {
             ingestion_count: AtomicU64::new(0),
             recall_count: AtomicU64::new(0),
             recall_latencies: RwLock::new(VecDeque::with_capacity(LATENCY_WINDOW_SIZE)),
+            project_recalls: RwLock::new(HashMap::new()),
+            max_project_series,
+            slow_queries: SlowQueryLog::default(),
+            query_log: None,
         }
     }
 
+    pub fn with_slow_query_log(mut self, slow_queries: SlowQueryLog) -> Self {
+        self.slow_queries = slow_queries;
+        self
+    }
+
+    pub fn with_query_log(mut self, query_log: QueryLog) -> Self {
+        self.query_log = Some(query_log);
+        self
+    }
+
+    pub fn max_project_series(&self) -> usize {
+        self.max_project_series
+    }
+
     /// Record a memory ingestion event
     pub fn record_ingestion(&self) {
         self.ingestion_count.fetch_add(1, Ordering::Relaxed);
@@ -52,19 +116,36 @@ impl MetricsCollector {
         }
     }
 
+    /// Record a recall served by one project (on top of `record_recall`)
+    pub fn record_project_recall(&self, project_id: &str, latency_ms: f64) {
+        if let Ok(mut projects) = self.project_recalls.write() {
+            let project = match projects.get_mut(project_id) {
+                Some(project) => project,
+                None => projects.entry(project_id.to_string()).or_default(),
+            };
+            project.count += 1;
+            if project.latencies.len() >= PROJECT_LATENCY_WINDOW_SIZE {
+                project.latencies.pop_front();
+            }
+            project.latencies.push_back(latency_ms);
+        }
+    }
+
+    /// Recall count and P99 latency of every project that served a recall
+    pub fn project_recalls(&self) -> HashMap<String, ProjectRecallStats> {
+        match self.project_recalls.read() {
+            Ok(projects) => projects
+                .iter()
+                .map(|(id, p)| (id.clone(), ProjectRecallStats { recalls: p.count, p99_latency_ms: p99(&p.latencies) }))
+                .collect(),
+            Err(_) => HashMap::new(),
+        }
+    }
+
     /// Calculate P99 latency from the sliding window
     pub fn get_p99_latency(&self) -> f64 {
         if let Ok(latencies) = self.recall_latencies.read() {
-            if latencies.is_empty() {
-                return 0.0;
-            }
-            
-            let mut sorted: Vec<f64> = latencies.iter().copied().collect();
-            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
-            
-            let p99_index = ((sorted.len() as f64) * 0.99).ceil() as usize - 1;
-            let p99_index = p99_index.min(sorted.len() - 1);
-            sorted[p99_index]
+            p99(&latencies)
         } else {
             0.0
         }
@@ -92,6 +173,44 @@ impl MetricsCollector {
     }
 }
 
+fn p99(latencies: &VecDeque<f64>) -> f64 {
+    if latencies.is_empty() {
+        return 0.0;
+    }
+
+    let mut sorted: Vec<f64> = latencies.iter().copied().collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    let p99_index = ((sorted.len() as f64) * 0.99).ceil() as usize - 1;
+    let p99_index = p99_index.min(sorted.len() - 1);
+    sorted[p99_index]
+}
+
+/// The per-project `/metrics` rows: the `max_series` busiest projects (most
+/// recalls, then job backlog, then memories) in that order, and one `(other)`
+/// row summing the rest. Empty when `max_series` is 0.
+pub fn project_series(mut rows: Vec<ProjectSeries>, max_series: usize) -> Vec<ProjectSeries> {
+    if max_series == 0 {
+        return Vec::new();
+    }
+    rows.sort_by(|a, b| {
+        (b.recalls, b.job_backlog, b.memories)
+            .cmp(&(a.recalls, a.job_backlog, a.memories))
+            .then_with(|| a.project.cmp(&b.project))
+    });
+    if rows.len() > max_series {
+        let rest = rows.split_off(max_series);
+        rows.push(ProjectSeries {
+            project: OTHER_PROJECTS_LABEL.to_string(),
+            memories: rest.iter().map(|r| r.memories).sum(),
+            recalls: rest.iter().map(|r| r.recalls).sum(),
+            recall_latency_p99: None,
+            job_backlog: rest.iter().map(|r| r.job_backlog).sum(),
+        });
+    }
+    rows
+}
+
 /// Get current process memory usage in bytes (RSS)
 /// Uses getrusage() which works on both Linux and macOS
 pub fn get_memory_usage_bytes() -> u64 {
@@ -165,6 +284,44 @@ mod tests {
         assert!((avg - 2.0).abs() < 0.01);
     }
 
+    #[test]
+    fn test_project_recalls() {
+        let metrics = MetricsCollector::new();
+        metrics.record_project_recall("alpha", 5.0);
+        metrics.record_project_recall("alpha", 50.0);
+        metrics.record_project_recall("beta", 1.0);
+
+        let projects = metrics.project_recalls();
+        assert_eq!(projects["alpha"], ProjectRecallStats { recalls: 2, p99_latency_ms: 50.0 });
+        assert_eq!(projects["beta"].recalls, 1);
+    }
+
+    #[test]
+    fn test_project_series_cap() {
+        let row = |project: &str, recalls: u64, memories: u64| ProjectSeries {
+            project: project.to_string(),
+            memories,
+            recalls,
+            recall_latency_p99: Some(1.0),
+            job_backlog: 1,
+        };
+        let rows = vec![row("quiet", 0, 900), row("hot", 40, 10), row("warm", 3, 10), row("idle", 0, 5)];
+
+        let capped = project_series(rows.clone(), 2);
+        let names: Vec<&str> = capped.iter().map(|r| r.project.as_str()).collect();
+        assert_eq!(names, vec!["hot", "warm", OTHER_PROJECTS_LABEL]);
+        assert_eq!(capped[2], ProjectSeries {
+            project: OTHER_PROJECTS_LABEL.to_string(),
+            memories: 905,
+            recalls: 0,
+            recall_latency_p99: None,
+            job_backlog: 2,
+        });
+
+        assert_eq!(project_series(rows.clone(), 4).len(), 4);
+        assert!(project_series(rows, 0).is_empty());
+    }
+
     #[test]
     fn test_empty_latencies() {
         let metrics = MetricsCollector::new();
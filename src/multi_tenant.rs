@@ -1,14 +1,31 @@
 //! Multi-tenant engine supporting project isolation.
 
-use crate::structures::{MainStats, LexiconStats};
-use crate::engine::CueMapEngine;
-use crate::persistence::PersistenceManager;
-use crate::projects::ProjectContext;
-use crate::crypto::EncryptionKey;
+use crate::structures::{MainStats, LexiconStats, MemoryLink};
+use crate::engine::{CompactionReport, CueMapEngine, EngineArchive, RecallProfile};
+use crate::persistence::{PersistenceManager, ProjectCoOccurrence, SnapshotCompression, SnapshotStatus, SNAPSHOT_SIDECAR_SUFFIXES};
+use crate::projects::{EpisodeSession, ProjectContext};
+use crate::crypto::{EncryptionKey, SnapshotKeyring};
+use crate::scheduler::ProjectSchedule;
+use crate::agent::chunker::ChunkingConfig;
+use crate::agent::feeds::FeedSubscription;
+use crate::connectors::ConnectorConfig;
+use crate::live::{LiveEvent, LiveFeed};
+use crate::audit::AuditLog;
+use crate::vector::{VectorIndex, VectorSnapshot};
+use crate::history::MemoryHistory;
+use crate::lexicon_quality::LexiconQualitySnapshot;
+use crate::heatmap::{HeatmapSnapshot, MarketHeatmap};
+use crate::access::AccessStats;
 use crate::normalization::NormalizationConfig;
 use crate::taxonomy::Taxonomy;
-use crate::config::{CueGenStrategy, TuningConfig, LlmConfig};
-use std::collections::HashMap;
+use crate::config::{CueGenStrategy, EmbeddingConfig, TuningConfig, LlmConfig, RerankConfig, RerankStrategy};
+use crate::embeddings::{build_provider, EmbeddingProvider};
+use crate::llm::LlmClient;
+use crate::rerank::{build_reranker, Reranker};
+use crate::multilingual::TextLanguage;
+use crate::nl::Tokenizer;
+use crate::payload_store::{PayloadStore, StorageBackend, DEFAULT_PAYLOAD_CACHE_ENTRIES};
+use std::collections::{BTreeMap, HashMap};
 use crate::semantic::SemanticEngine;
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
@@ -16,8 +33,10 @@ use ahash::RandomState;
 
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH, Duration};
+use tokio::sync::broadcast;
 
 pub type ProjectId = String;
 
@@ -28,6 +47,26 @@ pub struct ProjectStats {
     pub total_cues: usize,
     pub created_at: f64,
     pub last_activity: f64,
+    /// Opaque token that changes whenever the project's data does (used by followers)
+    #[serde(default)]
+    pub revision: String,
+    /// Unloaded after going idle; reloaded from its snapshot on next access
+    #[serde(default)]
+    pub archived: bool,
+    /// Where memory payloads are kept
+    #[serde(default)]
+    pub storage: StorageBackend,
+}
+
+/// Counters of the archival tier (see `MultiTenantEngine::archive_idle_projects`)
+#[derive(Debug, Default)]
+pub struct ArchiveStats {
+    pub evictions: AtomicU64,
+    pub reloads: AtomicU64,
+    /// Projects refused because the resident cap was reached and nothing could be unloaded
+    pub rejections: AtomicU64,
+    /// Unix time of the last eviction (0 = none yet)
+    pub last_eviction_at: AtomicU64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +75,65 @@ pub struct ProjectMeta {
     pub created_at: u64,
     pub watch_dir: Option<String>,
     pub agent_enabled: bool,
+    /// Snapshot encryption override: None follows the server keyring,
+    /// Some(false) keeps this project's snapshots plaintext, Some(true) requires a key.
+    #[serde(default)]
+    pub encrypt_snapshots: Option<bool>,
+    /// Maintenance schedule (None = nothing scheduled)
+    #[serde(default)]
+    pub schedule: Option<ProjectSchedule>,
+    /// Resource limits (all unlimited by default)
+    #[serde(default)]
+    pub quota: ProjectQuota,
+    /// Embedding provider override (None = server `[embeddings]` default)
+    #[serde(default)]
+    pub embeddings: Option<EmbeddingConfig>,
+    /// Natural language of the project's text (`auto` detects it per text)
+    #[serde(default)]
+    pub language: TextLanguage,
+    /// Episodes opened with `POST /sessions`, by id
+    #[serde(default)]
+    pub sessions: BTreeMap<String, EpisodeSession>,
+    /// Recall path for requests that don't set `profile`
+    #[serde(default)]
+    pub recall_profile: RecallProfile,
+    /// Sitemaps and feeds ingested with `/ingest/url`, by feed URL
+    #[serde(default)]
+    pub feeds: BTreeMap<String, FeedSubscription>,
+    /// External sources synced into the project, by connector id
+    #[serde(default)]
+    pub connectors: BTreeMap<String, ConnectorConfig>,
+    /// Chunk size limits for content ingested into the project
+    #[serde(default)]
+    pub chunking: ChunkingConfig,
+    /// LLM override (None = server `[llm]` default)
+    #[serde(default)]
+    pub llm: Option<LlmConfig>,
+    /// Rerank stage applied to recall candidates
+    #[serde(default)]
+    pub rerank: RerankConfig,
+    /// Acronym → expansion for query rewriting, learned from ingested text or added by hand
+    #[serde(default)]
+    pub acronyms: BTreeMap<String, String>,
+    /// Rules cues must pass on ingest (namespaces, values, patterns, length)
+    #[serde(default)]
+    pub taxonomy: Taxonomy,
+    /// Pipeline cues are normalized with at ingest and query time
+    #[serde(default)]
+    pub normalization: NormalizationConfig,
+    /// Where memory payloads are kept; fixed when the project is created
+    #[serde(default)]
+    pub storage: StorageBackend,    /// Unix seconds dynamic salience has been decayed through (0 = never)
+    #[serde(default)]
+    pub salience_decayed_at: u64,
+    /// Rules text is split into cues with; fixed when the project is created
+    #[serde(default = "legacy_tokenizer")]
+    pub tokenizer: Tokenizer,
+}
+
+/// Projects whose meta predates the `tokenizer` field were tokenized with the ASCII rules
+fn legacy_tokenizer() -> Tokenizer {
+    Tokenizer::Ascii
 }
 
 impl ProjectMeta {
@@ -48,10 +146,213 @@ impl ProjectMeta {
                 .as_secs(),
             watch_dir: None,
             agent_enabled: false,
+            encrypt_snapshots: None,
+            schedule: None,
+            quota: ProjectQuota::default(),
+            embeddings: None,
+            language: TextLanguage::English,
+            sessions: BTreeMap::new(),
+            recall_profile: RecallProfile::default(),
+            feeds: BTreeMap::new(),
+            connectors: BTreeMap::new(),
+            chunking: ChunkingConfig::default(),
+            llm: None,
+            rerank: RerankConfig::default(),
+            acronyms: BTreeMap::new(),
+            taxonomy: Taxonomy::default(),
+            normalization: NormalizationConfig::default(),
+            storage: StorageBackend::Memory,
+            salience_decayed_at: 0,
+            tokenizer: Tokenizer::Unicode,
+        }
+    }
+}
+
+/// Per-project resource limits. `None` means unlimited.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectQuota {
+    #[serde(default)]
+    pub max_memories: Option<usize>,
+    #[serde(default)]
+    pub max_cues: Option<usize>,
+    /// Limit on stored memory payload bytes (after compression/encryption)
+    #[serde(default)]
+    pub max_storage_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QuotaUsage {
+    pub memories: usize,
+    pub cues: usize,
+    pub storage_bytes: u64,
+}
+
+/// Returned when a write would push a project over one of its quotas
+#[derive(Debug, Clone, Serialize)]
+pub struct QuotaExceeded {
+    pub project_id: ProjectId,
+    /// Which limit was hit: "memories", "cues" or "storage_bytes"
+    pub resource: String,
+    pub limit: u64,
+    pub current: u64,
+    pub requested: u64,
+}
+
+impl std::fmt::Display for QuotaExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Project '{}' quota exceeded for {}: {} used + {} requested > {} allowed",
+            self.project_id, self.resource, self.current, self.requested, self.limit
+        )
+    }
+}
+
+// v2: embeddings of the main engine
+// v4: memory links out of metadata
+const PROJECT_ARCHIVE_VERSION: u32 = 4;
+
+/// Starts an encrypted archive: followed by the source project id (u16 LE
+/// length, then UTF-8) in clear text, so restoring under another id can
+/// pick the source project's keys, then the encrypted archive
+const ENCRYPTED_ARCHIVE_MAGIC: &[u8; 8] = b"CMARCHE1";
+
+/// Snapshot key derivation id of the organization lexicon
+const ORG_KEY_ID: &str = "org";
+
+/// Everything needed to bring a project back exactly as it was (see `dump_project`)
+#[derive(Serialize, Deserialize)]
+pub struct ProjectArchive {
+    pub version: u32,
+    pub project_id: ProjectId,
+    pub saved_at: u64,
+    pub main: EngineArchive<MainStats>,
+    pub aliases: EngineArchive<MainStats>,
+    pub lexicon: EngineArchive<LexiconStats>,
+    /// Effective heatmap lifts when dumped (what version 1-2 archives carry)
+    pub market_heatmap: HashMap<String, f32>,
+    pub meta: ProjectMeta,
+    pub vectors: VectorSnapshot,
+    pub heatmap: HeatmapSnapshot,
+    /// Links of the main engine's memories (`Memory::links` is not serialized)
+    pub links: HashMap<String, Vec<MemoryLink>>,
+}
+
+/// Version 3 layout; links are still in memory metadata
+#[derive(Deserialize)]
+struct ProjectArchiveV3 {
+    version: u32,
+    project_id: ProjectId,
+    saved_at: u64,
+    main: EngineArchive<MainStats>,
+    aliases: EngineArchive<MainStats>,
+    lexicon: EngineArchive<LexiconStats>,
+    market_heatmap: HashMap<String, f32>,
+    meta: ProjectMeta,
+    vectors: VectorSnapshot,
+    heatmap: HeatmapSnapshot,
+}
+
+impl From<ProjectArchiveV3> for ProjectArchive {
+    fn from(v3: ProjectArchiveV3) -> Self {
+        Self {
+            version: v3.version,
+            project_id: v3.project_id,
+            saved_at: v3.saved_at,
+            main: v3.main,
+            aliases: v3.aliases,
+            lexicon: v3.lexicon,
+            market_heatmap: v3.market_heatmap,
+            meta: v3.meta,
+            vectors: v3.vectors,
+            heatmap: v3.heatmap,
+            links: HashMap::new(),
+        }
+    }
+}
+
+/// Version 2 layout
+#[derive(Deserialize)]
+struct ProjectArchiveV2 {
+    version: u32,
+    project_id: ProjectId,
+    saved_at: u64,
+    main: EngineArchive<MainStats>,
+    aliases: EngineArchive<MainStats>,
+    lexicon: EngineArchive<LexiconStats>,
+    market_heatmap: HashMap<String, f32>,
+    meta: ProjectMeta,
+    vectors: VectorSnapshot,
+}
+
+impl From<ProjectArchiveV2> for ProjectArchive {
+    fn from(v2: ProjectArchiveV2) -> Self {
+        Self {
+            version: v2.version,
+            project_id: v2.project_id,
+            saved_at: v2.saved_at,
+            main: v2.main,
+            aliases: v2.aliases,
+            lexicon: v2.lexicon,
+            market_heatmap: v2.market_heatmap,
+            meta: v2.meta,
+            vectors: v2.vectors,
+            heatmap: HeatmapSnapshot::default(),
+            links: HashMap::new(),
+        }
+    }
+}
+
+/// Version 1 layout (bincode has no optional trailing fields)
+#[derive(Deserialize)]
+struct ProjectArchiveV1 {
+    version: u32,
+    project_id: ProjectId,
+    saved_at: u64,
+    main: EngineArchive<MainStats>,
+    aliases: EngineArchive<MainStats>,
+    lexicon: EngineArchive<LexiconStats>,
+    market_heatmap: HashMap<String, f32>,
+    meta: ProjectMeta,
+}
+
+impl From<ProjectArchiveV1> for ProjectArchive {
+    fn from(v1: ProjectArchiveV1) -> Self {
+        Self {
+            version: v1.version,
+            project_id: v1.project_id,
+            saved_at: v1.saved_at,
+            main: v1.main,
+            aliases: v1.aliases,
+            lexicon: v1.lexicon,
+            market_heatmap: v1.market_heatmap,
+            meta: v1.meta,
+            vectors: VectorSnapshot::default(),
+            heatmap: HeatmapSnapshot::default(),
+            links: HashMap::new(),
         }
     }
 }
 
+/// A retained copy of a project's snapshot files (see `MultiTenantEngine::restore_generation`)
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotGeneration {
+    /// Milliseconds since the epoch at which it was kept; also its directory name
+    pub generation: String,
+    pub created_at: f64,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// Snapshot files and metadata of a project, as named in the snapshots directory
+fn project_snapshot_files(project_id: &ProjectId) -> Vec<String> {
+    std::iter::once("")
+        .chain(SNAPSHOT_SIDECAR_SUFFIXES)
+        .map(|suffix| format!("{}{}.bin", project_id, suffix))
+        .chain(std::iter::once(format!("{}.meta.json", project_id)))
+        .collect()
+}
+
 #[derive(Clone)]
 pub struct MultiTenantEngine {
     projects: Arc<DashMap<ProjectId, Arc<ProjectContext>, RandomState>>,
@@ -59,8 +360,46 @@ pub struct MultiTenantEngine {
     cuegen_strategy: CueGenStrategy,
     semantic_engine: SemanticEngine,
     master_key: Option<Arc<EncryptionKey>>,
+    snapshot_keyring: Option<Arc<SnapshotKeyring>>,
     tuning: Arc<TuningConfig>,
     llm_config: Arc<LlmConfig>,
+    live_tx: broadcast::Sender<LiveEvent>,
+    // Quotas are checked on every write, so keep them out of the meta file hot path
+    quotas: Arc<DashMap<ProjectId, ProjectQuota, RandomState>>,
+    audit: Arc<AuditLog>,
+    // Generations restart at zero, so revisions are qualified by the process that produced them
+    instance_id: Arc<str>,
+    embedding_config: Arc<EmbeddingConfig>,
+    // Effective embedding config per project (meta override or server default)
+    project_embeddings: Arc<DashMap<ProjectId, EmbeddingConfig, RandomState>>,
+    // Providers are shared by every project using the same config (ONNX sessions are heavy)
+    embedders: Arc<DashMap<EmbeddingConfig, Arc<dyn EmbeddingProvider>, RandomState>>,
+    // LLM client per project, so each project gets its own rate limits
+    llm_clients: Arc<DashMap<ProjectId, Arc<LlmClient>, RandomState>>,
+    // Rerank config per project (read on every recall)
+    project_rerank: Arc<DashMap<ProjectId, RerankConfig, RandomState>>,
+    // Cross-encoders are shared like embedders
+    cross_encoders: Arc<DashMap<RerankConfig, Arc<dyn Reranker>, RandomState>>,
+    // Vocabulary shared by every project (see `ProjectContext::org_lexicon`)
+    org_lexicon: Arc<CueMapEngine<LexiconStats>>,
+    // Projects unloaded by `archive_idle_projects`, with their stats at eviction
+    archived: Arc<DashMap<ProjectId, ProjectStats, RandomState>>,
+    archive_stats: Arc<ArchiveStats>,
+    // Held while a project moves between RAM and its snapshot, so it is never loaded twice
+    archive_lock: Arc<Mutex<()>>,
+    // Held per project while its meta file is loaded, changed and saved (see `update_project_meta`)
+    meta_locks: Arc<DashMap<ProjectId, Arc<Mutex<()>>, RandomState>>,
+    // Resident project cap (0 = none) and whether to unload projects to stay under it
+    max_resident_projects: usize,
+    evict_for_room: bool,
+    // Hot payloads kept in RAM per disk-backed project
+    payload_cache_entries: usize,
+    snapshot_compression: SnapshotCompression,
+    // Snapshot generations kept per project (0 = none) and the least time between two
+    snapshot_generations: usize,
+    generation_interval_secs: u64,
+    // Schedule of projects without one of their own (`[schedule]`)
+    default_schedule: ProjectSchedule,
 }
 
 impl MultiTenantEngine {
@@ -77,25 +416,125 @@ impl MultiTenantEngine {
             eprintln!("Warning: Failed to create snapshots directory: {}", e);
         }
         
+        let audit = Arc::new(AuditLog::open(snapshots_dir.join("audit.log")));
+        
         Self {
             projects: Arc::new(DashMap::with_hasher(RandomState::new())),
             snapshots_dir,
             cuegen_strategy,
             semantic_engine,
             master_key: None,
+            snapshot_keyring: None,
             tuning: Arc::new(tuning),
             llm_config: Arc::new(llm_config),
+            live_tx: broadcast::channel(1024).0,
+            quotas: Arc::new(DashMap::with_hasher(RandomState::new())),
+            audit,
+            instance_id: uuid::Uuid::new_v4().to_string().into(),
+            embedding_config: Arc::new(EmbeddingConfig::default()),
+            project_embeddings: Arc::new(DashMap::with_hasher(RandomState::new())),
+            embedders: Arc::new(DashMap::with_hasher(RandomState::new())),
+            llm_clients: Arc::new(DashMap::with_hasher(RandomState::new())),
+            project_rerank: Arc::new(DashMap::with_hasher(RandomState::new())),
+            cross_encoders: Arc::new(DashMap::with_hasher(RandomState::new())),
+            org_lexicon: Arc::new(CueMapEngine::new()),
+            archived: Arc::new(DashMap::with_hasher(RandomState::new())),
+            archive_stats: Arc::new(ArchiveStats::default()),
+            archive_lock: Arc::new(Mutex::new(())),
+            meta_locks: Arc::new(DashMap::with_hasher(RandomState::new())),
+            max_resident_projects: 0,
+            evict_for_room: false,
+            payload_cache_entries: DEFAULT_PAYLOAD_CACHE_ENTRIES,
+            snapshot_compression: SnapshotCompression::default(),
+            snapshot_generations: 0,
+            generation_interval_secs: 0,
+            default_schedule: ProjectSchedule::default(),
         }
     }
 
+    /// Set before any project or the organization lexicon is loaded
     pub fn set_master_key(&mut self, key: Option<Arc<EncryptionKey>>) {
+        let mut org_lexicon = CueMapEngine::with_tuning(self.tuning.as_ref().clone());
+        org_lexicon.set_master_key(key.clone());
+        self.org_lexicon = Arc::new(org_lexicon);
         self.master_key = key;
     }
+
+    /// Server-wide embedding provider (projects may override it)
+    pub fn set_embedding_config(&mut self, config: EmbeddingConfig) {
+        self.embedding_config = Arc::new(config);
+    }
+
+    /// Directory holding project snapshots and their sidecars
+    pub fn snapshots_dir(&self) -> &Path {
+        &self.snapshots_dir
+    }
+
+    /// Audit log stored next to the snapshots
+    pub fn audit(&self) -> &Arc<AuditLog> {
+        &self.audit
+    }
+
+    /// Subscribe to live change events from every project's main engine
+    pub fn subscribe_live(&self) -> broadcast::Receiver<LiveEvent> {
+        self.live_tx.subscribe()
+    }
+
+    fn live_feed(&self, project_id: &ProjectId) -> LiveFeed {
+        LiveFeed::new(project_id.clone(), self.live_tx.clone())
+    }
+
+    /// Enable encryption-at-rest for snapshot files
+    pub fn set_snapshot_keyring(&mut self, keyring: Option<Arc<SnapshotKeyring>>) {
+        self.snapshot_keyring = keyring;
+    }
+
+    /// Resolve the key used to write a project's snapshots (None = plaintext)
+    fn snapshot_write_key(&self, project_id: &ProjectId) -> Result<Option<EncryptionKey>, String> {
+        let meta = self.load_project_meta(project_id)
+            .unwrap_or_else(|_| ProjectMeta::new(project_id.clone()));
+
+        match (meta.encrypt_snapshots, &self.snapshot_keyring) {
+            (Some(false), _) => Ok(None),
+            (_, Some(keyring)) => Ok(Some(keyring.current_key(project_id))),
+            (Some(true), None) => Err(format!(
+                "Project '{}' requires encrypted snapshots but no snapshot key is configured",
+                project_id
+            )),
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Keys accepted when reading a project's snapshots (current + previous)
+    fn snapshot_read_keys(&self, project_id: &ProjectId) -> Vec<EncryptionKey> {
+        self.snapshot_keyring
+            .as_ref()
+            .map(|k| k.keys_for(project_id))
+            .unwrap_or_default()
+    }
+    
+    /// Keys accepted when reading a snapshot file, by its owner: the organization
+    /// for `org/lexicon.bin`, otherwise `project_id` or the project its file name starts with
+    pub fn snapshot_file_keys(&self, path: &Path, project_id: Option<&str>) -> Vec<EncryptionKey> {
+        let Some(keyring) = &self.snapshot_keyring else {
+            return Vec::new();
+        };
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+        let in_org_dir = path.parent().and_then(|p| p.file_name()).is_some_and(|d| d == "org");
+        let owner = match project_id {
+            Some(id) => id,
+            None if in_org_dir && stem == "lexicon" => ORG_KEY_ID,
+            None => SNAPSHOT_SIDECAR_SUFFIXES.iter().find_map(|s| stem.strip_suffix(s)).unwrap_or(stem),
+        };
+        keyring.keys_for(owner)
+    }
     
     pub fn get_or_create_project(&self, project_id: ProjectId) -> Result<Arc<ProjectContext>, String> {
         if let Some(ctx) = self.projects.get(&project_id) {
             ctx.touch();
             Ok(ctx.clone())
+        } else if let Some(ctx) = self.admit().and_then(|_| self.reload_archived(&project_id))? {
+            Ok(ctx)
         } else {
 
 
@@ -111,15 +550,41 @@ impl MultiTenantEngine {
             
             // Set master key on engines
             ctx_obj.main.set_master_key(self.master_key.clone());
+            ctx_obj.main.set_live_feed(Some(self.live_feed(&project_id)));
+            ctx_obj.main.set_payload_store(self.open_payload_store(&project_id));
             ctx_obj.aliases.set_master_key(self.master_key.clone());
             ctx_obj.lexicon.set_master_key(self.master_key.clone());
+            ctx_obj.org_lexicon = Some(self.org_lexicon.clone());
             
             let ctx = Arc::new(ctx_obj);
             self.projects.insert(project_id.clone(), ctx.clone());
             
             // Ensure meta exists
-            if let Ok(meta) = self.load_project_meta(&project_id) {
-                let _ = self.save_project_meta(&meta);
+            if let Ok(meta) = self.update_project_meta(&project_id, |meta| meta.clone()) {
+                if let Ok(mut language) = ctx.text_language.write() {
+                    *language = meta.language;
+                }
+                if let Ok(mut tokenizer) = ctx.tokenizer.write() {
+                    *tokenizer = meta.tokenizer;
+                }
+                for (id, session) in &meta.sessions {
+                    ctx.sessions.insert(id.clone(), session.clone());
+                }
+                if let Ok(mut profile) = ctx.recall_profile.write() {
+                    *profile = meta.recall_profile;
+                }
+                if let Ok(mut schedule) = ctx.schedule.write() {
+                    *schedule = meta.schedule.clone();
+                }
+                for (acronym, expansion) in &meta.acronyms {
+                    ctx.acronyms.insert(acronym.clone(), expansion.clone());
+                }
+                if let Ok(mut taxonomy) = ctx.taxonomy.write() {
+                    *taxonomy = meta.taxonomy.clone();
+                }
+                if let Ok(mut normalization) = ctx.normalization.write() {
+                    *normalization = Arc::new(meta.normalization.clone());
+                }
             }
             
             Ok(ctx)
@@ -135,7 +600,11 @@ impl MultiTenantEngine {
             let mut ticker = tokio::time::interval(interval);
             loop {
                 ticker.tick().await;
-                let results = engine.save_all();
+                // Snapshots stream engine state to disk; keep that I/O off the async workers
+                let saver = engine.clone();
+                let results = tokio::task::spawn_blocking(move || saver.save_all())
+                    .await
+                    .unwrap_or_default();
                 let saved = results.iter().filter(|(_, r)| r.is_ok()).count();
                 let failed = results.iter().filter(|(_, r)| r.is_err()).count();
                 
@@ -154,42 +623,317 @@ impl MultiTenantEngine {
     pub fn get_project(&self, project_id: &ProjectId) -> Option<Arc<ProjectContext>> {
         self.projects.get(project_id).map(|e| e.clone())
     }
+
+    /// The loaded project, reloading it if it was archived (never creates one)
+    pub fn get_or_load_project(&self, project_id: &ProjectId) -> Option<Arc<ProjectContext>> {
+        match self.get_project(project_id) {
+            Some(ctx) => Some(ctx),
+            None => self.reload_archived(project_id).unwrap_or_else(|e| {
+                tracing::warn!("Archival: failed to reload '{}': {}", project_id, e);
+                None
+            }),
+        }
+    }
+
+    /// The loaded project, or an empty unregistered context with its saved
+    /// language, for dry runs that must not create the project
+    pub fn get_project_or_scratch(&self, project_id: &ProjectId) -> Arc<ProjectContext> {
+        self.get_project(project_id).unwrap_or_else(|| {
+            Arc::new(self.assemble_project(project_id, CueMapEngine::new(), CueMapEngine::new(), CueMapEngine::new()))
+        })
+    }
+
+    pub fn loaded_project_ids(&self) -> Vec<ProjectId> {
+        self.projects.iter().map(|e| e.key().clone()).collect()
+    }
     
+    /// Loaded projects (see `archived_projects` for the unloaded ones)
     pub fn list_projects(&self) -> Vec<ProjectStats> {
         self.projects
             .iter()
-            .map(|entry| {
-                let project_id = entry.key().clone();
-                let ctx = entry.value();
-                let stats = ctx.main.get_stats();
-                
-                ProjectStats {
-                    project_id,
-                    total_memories: stats.get("total_memories")
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(0) as usize,
-                    total_cues: stats.get("total_cues")
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(0) as usize,
-                    created_at: SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs_f64(),
-                    last_activity: SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs_f64(),
+            .map(|entry| self.project_stats(entry.key(), entry.value()))
+            .collect()
+    }
+
+    fn project_stats(&self, project_id: &ProjectId, ctx: &ProjectContext) -> ProjectStats {
+        let stats = ctx.main.get_stats();
+
+        ProjectStats {
+            project_id: project_id.clone(),
+            total_memories: stats.get("total_memories")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0) as usize,
+            total_cues: stats.get("total_cues")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0) as usize,
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs_f64(),
+            last_activity: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs_f64(),
+            revision: format!("{}-{}", self.instance_id, ctx.generation()),
+            archived: false,
+            storage: if ctx.main.payload_store().is_some() { StorageBackend::Disk } else { StorageBackend::Memory },
+        }
+    }
+
+    /// Projects unloaded to their snapshots, as they were when evicted
+    pub fn archived_projects(&self) -> Vec<ProjectStats> {
+        let mut projects: Vec<ProjectStats> = self.archived.iter().map(|e| e.value().clone()).collect();
+        projects.sort_by(|a, b| a.project_id.cmp(&b.project_id));
+        projects
+    }
+
+    pub fn archive_stats(&self) -> &ArchiveStats {
+        &self.archive_stats
+    }
+
+    /// Save and unload every project idle for at least `max_idle_secs`. A project
+    /// still held by a request or job, or written to while it was being saved,
+    /// stays loaded. Returns the ids unloaded.
+    pub fn archive_idle_projects(&self, max_idle_secs: u64) -> Vec<ProjectId> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let is_idle = |ctx: &ProjectContext| now.saturating_sub(ctx.get_last_activity()) >= max_idle_secs;
+        let idle: Vec<ProjectId> = self.projects
+            .iter()
+            .filter(|entry| is_idle(entry.value()))
+            .map(|entry| entry.key().clone())
+            .collect();
+
+        idle.into_iter()
+            .filter(|project_id| match self.unload(project_id, Some(max_idle_secs)) {
+                Ok(unloaded) => unloaded,
+                Err(e) => {
+                    tracing::warn!("Archival: failed to save '{}', keeping it loaded: {}", project_id, e);
+                    false
                 }
             })
             .collect()
     }
+
+    /// Save a project and unload it (see `archive_idle_projects`). Returns false
+    /// when it isn't loaded or is in use.
+    pub fn unload_project(&self, project_id: &ProjectId) -> Result<bool, String> {
+        self.unload(project_id, None)
+    }
+
+    fn unload(&self, project_id: &ProjectId, max_idle_secs: Option<u64>) -> Result<bool, String> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let is_idle = |ctx: &ProjectContext| max_idle_secs.is_none_or(|max| now.saturating_sub(ctx.get_last_activity()) >= max);
+        let Some(ctx) = self.get_project(project_id) else {
+            return Ok(false);
+        };
+        // Held by the map and this function only; skip the save for a project in use
+        if Arc::strong_count(&ctx) > 2 || !is_idle(&ctx) {
+            return Ok(false);
+        }
+        let generation = ctx.generation();
+        let mut stats = self.project_stats(project_id, &ctx);
+        stats.last_activity = ctx.get_last_activity() as f64;
+        stats.archived = true;
+        drop(ctx);
+
+        self.save_project(project_id)?;
+
+        let _guard = self.archive_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let removed = self.projects.remove_if(project_id, |_, ctx| {
+            Arc::strong_count(ctx) == 1 && ctx.generation() == generation && is_idle(ctx)
+        });
+        if removed.is_none() {
+            return Ok(false);
+        }
+        self.archived.insert(project_id.clone(), stats);
+        self.archive_stats.evictions.fetch_add(1, Ordering::Relaxed);
+        self.archive_stats.last_eviction_at.store(now, Ordering::Relaxed);
+        tracing::info!("Archival: unloaded project '{}'", project_id);
+        Ok(true)
+    }
+
+    /// Cap the number of projects held in RAM (0 = no cap). With `evict`, the
+    /// least recently used projects are unloaded to make room for another one;
+    /// without it, loading or creating a project past the cap fails.
+    pub fn set_residency_limit(&mut self, max_resident_projects: usize, evict: bool) {
+        self.max_resident_projects = max_resident_projects;
+        self.evict_for_room = evict;
+    }
+
+    /// Make room for one more resident project, unloading the least recently
+    /// used ones that aren't in use
+    fn admit(&self) -> Result<(), String> {
+        let max = self.max_resident_projects;
+        if max == 0 || self.projects.len() < max {
+            return Ok(());
+        }
+
+        let mut lru: Vec<(u64, ProjectId)> = self.projects
+            .iter()
+            .map(|entry| (entry.value().get_last_activity(), entry.key().clone()))
+            .collect();
+        lru.sort();
+
+        if self.evict_for_room {
+            for (_, project_id) in &lru {
+                if self.projects.len() < max {
+                    return Ok(());
+                }
+                if let Err(e) = self.unload(project_id, None) {
+                    tracing::warn!("Admission: failed to unload '{}': {}", project_id, e);
+                }
+            }
+            if self.projects.len() < max {
+                return Ok(());
+            }
+        }
+
+        self.archive_stats.rejections.fetch_add(1, Ordering::Relaxed);
+        let hint = match lru.first() {
+            Some((_, project_id)) => format!(
+                "; unload the least recently used project with POST /projects/{}/unload",
+                project_id
+            ),
+            None => String::new(),
+        };
+        Err(format!(
+            "Capacity reached: {} of {} resident projects are in use{}",
+            self.projects.len(), max, hint
+        ))
+    }
+
+    /// Load an archived project back from its snapshot (None if it is neither
+    /// loaded nor archived)
+    fn reload_archived(&self, project_id: &ProjectId) -> Result<Option<Arc<ProjectContext>>, String> {
+        // Checked under the lock: an eviction in progress removes the project
+        // from `projects` before listing it in `archived`
+        let _guard = self.archive_lock.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(ctx) = self.get_project(project_id) {
+            ctx.touch();
+            return Ok(Some(ctx));
+        }
+        if !self.archived.contains_key(project_id) {
+            return Ok(None);
+        }
+
+        let ctx = self.load_project(project_id)?;
+        self.archive_stats.reloads.fetch_add(1, Ordering::Relaxed);
+        tracing::info!("Archival: reloaded project '{}'", project_id);
+        Ok(Some(ctx))
+    }
+
+    /// Periodically unload projects idle for `max_idle` (checked every minute, or
+    /// more often for shorter idle periods)
+    pub fn start_archiver(&self, max_idle: Duration) {
+        let engine = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(max_idle.min(Duration::from_secs(60)).max(Duration::from_secs(1)));
+            loop {
+                ticker.tick().await;
+                let archiver = engine.clone();
+                let unloaded = tokio::task::spawn_blocking(move || archiver.archive_idle_projects(max_idle.as_secs()))
+                    .await
+                    .unwrap_or_default();
+                if !unloaded.is_empty() {
+                    tracing::debug!("Archival: unloaded {} idle projects", unloaded.len());
+                }
+            }
+        });
+    }
     
     pub fn delete_project(&self, project_id: &ProjectId) -> bool {
-        self.projects.remove(project_id).is_some()
+        let archived = self.archived.remove(project_id).is_some();
+        self.quotas.remove(project_id);
+        self.project_embeddings.remove(project_id);
+        self.llm_clients.remove(project_id);
+        self.project_rerank.remove(project_id);
+        match self.projects.remove(project_id) {
+            Some((_, ctx)) => {
+                if let Some(store) = ctx.main.payload_store() {
+                    let _ = fs::remove_file(store.path());
+                }
+                true
+            }
+            None => archived,
+        }
+    }
+
+    /// Create a project with the given payload storage. An existing project
+    /// keeps the backend it was created with.
+    pub fn create_project(&self, project_id: ProjectId, storage: StorageBackend) -> Result<Arc<ProjectContext>, String> {
+        match self.project_storage(&project_id) {
+            Some(existing) if existing != storage => {
+                return Err(format!(
+                    "Project '{}' already exists with {:?} storage; the storage backend is chosen at creation",
+                    project_id, existing
+                ));
+            }
+            Some(_) => {}
+            None if storage != StorageBackend::Memory => {
+                self.update_project_meta(&project_id, |meta| meta.storage = storage)?;
+            }
+            None => {}
+        }
+        self.get_or_create_project(project_id)
+    }
+
+    /// Storage backend of a project that exists (loaded, archived or on disk)
+    pub fn project_storage(&self, project_id: &ProjectId) -> Option<StorageBackend> {
+        if let Some(ctx) = self.get_project(project_id) {
+            return Some(if ctx.main.payload_store().is_some() { StorageBackend::Disk } else { StorageBackend::Memory });
+        }
+        let exists = self.archived.contains_key(project_id)
+            || self.snapshots_dir.join(format!("{}.bin", project_id)).exists();
+        exists.then(|| self.load_project_meta(project_id).map(|meta| meta.storage).unwrap_or_default())
+    }
+
+    /// Codec and level of engine snapshots written from now on (raw bincode by default)
+    pub fn set_snapshot_compression(&mut self, compression: SnapshotCompression) {
+        self.snapshot_compression = compression;
+    }
+
+    /// Keep up to `keep` earlier copies of each project's snapshot files (0 = none),
+    /// at most one per `min_interval_secs`, for `restore_generation`
+    pub fn set_snapshot_generations(&mut self, keep: usize, min_interval_secs: u64) {
+        self.snapshot_generations = keep;
+        self.generation_interval_secs = min_interval_secs;
+    }
+
+    /// Payloads kept in RAM per disk-backed project (`persistence.payload_cache_entries`)
+    pub fn set_payload_cache_entries(&mut self, entries: usize) {
+        self.payload_cache_entries = entries;
+    }
+
+    /// A fresh payload log for a disk-backed project (None for in-memory projects).
+    /// If the log can't be created the project keeps its payloads in RAM.
+    fn open_payload_store(&self, project_id: &ProjectId) -> Option<Arc<PayloadStore>> {
+        let storage = self.load_project_meta(project_id).map(|meta| meta.storage).unwrap_or_default();
+        if storage != StorageBackend::Disk {
+            return None;
+        }
+        let path = self.snapshots_dir.join(format!("{}_payloads.log", project_id));
+        match PayloadStore::create(&path, self.payload_cache_entries) {
+            Ok(store) => Some(Arc::new(store)),
+            Err(e) => {
+                tracing::warn!("Project '{}' keeps its payloads in memory: {}", project_id, e);
+                None
+            }
+        }
     }
     
-    /// Save a project snapshot to disk (main, aliases, lexicon)
+    /// Save a project snapshot to disk (main, aliases, lexicon), keeping a
+    /// generation of it when generations are enabled
     pub fn save_project(&self, project_id: &ProjectId) -> Result<PathBuf, String> {
+        let main_path = self.write_project_snapshot(project_id)?;
+        if self.snapshot_generations > 0 {
+            if let Err(e) = self.retain_generation(project_id, false) {
+                tracing::warn!("Failed to keep a snapshot generation of '{}': {}", project_id, e);
+            }
+        }
+        Ok(main_path)
+    }
+
+    fn write_project_snapshot(&self, project_id: &ProjectId) -> Result<PathBuf, String> {
         let ctx = self.get_project(project_id)
             .ok_or_else(|| format!("Project '{}' not found", project_id))?;
         
@@ -198,14 +942,82 @@ impl MultiTenantEngine {
         let aliases_path = self.snapshots_dir.join(format!("{}_aliases.bin", project_id));
         let lexicon_path = self.snapshots_dir.join(format!("{}_lexicon.bin", project_id));
         
-        PersistenceManager::save_to_path(&ctx.main, &main_path)
+        let key = self.snapshot_write_key(project_id)?;
+        
+        PersistenceManager::save_to_path_with_options(&ctx.main, &main_path, key.as_ref(), self.snapshot_compression)
             .map_err(|e| format!("Failed to save main engine: {}", e))?;
         
-        PersistenceManager::save_to_path(&ctx.aliases, &aliases_path)
+        PersistenceManager::save_to_path_with_options(&ctx.aliases, &aliases_path, key.as_ref(), self.snapshot_compression)
             .map_err(|e| format!("Failed to save aliases engine: {}", e))?;
         
-        PersistenceManager::save_to_path(&ctx.lexicon, &lexicon_path)
+        PersistenceManager::save_to_path_with_options(&ctx.lexicon, &lexicon_path, key.as_ref(), self.snapshot_compression)
             .map_err(|e| format!("Failed to save lexicon engine: {}", e))?;
+
+        // Embeddings (only written once the project has any)
+        let vectors_path = self.snapshots_dir.join(format!("{}_vectors.bin", project_id));
+        if !ctx.main.vector_index().is_empty() {
+            PersistenceManager::save_vectors(&ctx.main.vector_index().to_snapshot(), &vectors_path, key.as_ref())?;
+        } else if vectors_path.exists() {
+            let _ = fs::remove_file(&vectors_path);
+        }
+
+        // Version history (only written once any memory has been overwritten)
+        let history_path = self.snapshots_dir.join(format!("{}_history.bin", project_id));
+        if !ctx.main.history().is_empty() {
+            PersistenceManager::save_history(&ctx.main.history().to_snapshot(), &history_path, key.as_ref())?;
+        } else if history_path.exists() {
+            let _ = fs::remove_file(&history_path);
+        }
+
+        // Recall statistics (only written once a recall returned something)
+        let access_path = self.snapshots_dir.join(format!("{}_access.bin", project_id));
+        if !ctx.main.access_stats().is_empty() {
+            PersistenceManager::save_access(&ctx.main.access_stats().to_snapshot(), &access_path, key.as_ref())?;
+        } else if access_path.exists() {
+            let _ = fs::remove_file(&access_path);
+        }
+
+        // Co-occurrence matrices, so loading can skip rebuilding them
+        let co_occurrence = ProjectCoOccurrence {
+            main: ctx.main.co_occurrence_snapshot(),
+            aliases: ctx.aliases.co_occurrence_snapshot(),
+            lexicon: ctx.lexicon.co_occurrence_snapshot(),
+        };
+        let co_occurrence_path = self.snapshots_dir.join(format!("{}_cooccurrence.bin", project_id));
+        PersistenceManager::save_co_occurrence(&co_occurrence, &co_occurrence_path, key.as_ref())?;
+
+        // Lexicon quality tallies (only written once a recall went through the lexicon)
+        let quality_path = self.snapshots_dir.join(format!("{}_lexicon_quality.bin", project_id));
+        if !ctx.lexicon_quality.is_empty() {
+            let snapshot = LexiconQualitySnapshot {
+                entries: ctx.lexicon_quality.iter().map(|e| (e.key().clone(), *e.value())).collect(),
+            };
+            PersistenceManager::save_lexicon_quality(&snapshot, &quality_path, key.as_ref())?;
+        } else if quality_path.exists() {
+            let _ = fs::remove_file(&quality_path);
+        }
+
+        // Market heatmap (only written while it has entries)
+        let heatmap_path = self.snapshots_dir.join(format!("{}_heatmap.bin", project_id));
+        let heatmap = ctx.market_heatmap.read().map(|h| h.to_snapshot()).unwrap_or_default();
+        if !heatmap.entries.is_empty() {
+            PersistenceManager::save_heatmap(&heatmap, &heatmap_path, key.as_ref())?;
+        } else if heatmap_path.exists() {
+            let _ = fs::remove_file(&heatmap_path);
+        }
+
+        // Acronyms learned and salience decayed since the last save
+        let acronyms = ctx.acronym_table();
+        let salience_decayed_at = ctx.main.salience_decayed_at();
+        let meta_lock = self.meta_lock(project_id);
+        let _meta_guard = meta_lock.lock().unwrap_or_else(|e| e.into_inner());
+        if let Ok(mut meta) = self.load_project_meta(project_id) {
+            if meta.acronyms != acronyms || meta.salience_decayed_at != salience_decayed_at {
+                meta.acronyms = acronyms;
+                meta.salience_decayed_at = salience_decayed_at;
+                self.save_project_meta(&meta)?;
+            }
+        }
         
         tracing::info!("Saved project '{}' (main + aliases + lexicon)", project_id);
         
@@ -222,19 +1034,75 @@ impl MultiTenantEngine {
             return Err(format!("Snapshot for project '{}' not found", project_id));
         }
         
-        // Load main engine (required)
-        let (memories, cue_index) = PersistenceManager::load_from_path::<MainStats>(&main_path)
+        let keys = self.snapshot_read_keys(project_id);
+
+        // Persisted co-occurrence (optional); each engine rebuilds its matrix when missing or stale
+        let co_occurrence_path = self.snapshots_dir.join(format!("{}_cooccurrence.bin", project_id));
+        let mut co_occurrence = if co_occurrence_path.exists() {
+            match PersistenceManager::load_co_occurrence(&co_occurrence_path, &keys) {
+                Ok(snapshot) => Some(snapshot),
+                Err(e) => {
+                    tracing::warn!("Failed to load co-occurrence for '{}': {}", project_id, e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        
+        // Load main engine (required); a disk-backed project's payloads go to its log as they are read
+        let payload_store = self.open_payload_store(project_id);
+        let (memories, cue_index) = PersistenceManager::load_from_path_into_store::<MainStats>(&main_path, &keys, payload_store.as_ref())
             .map_err(|e| format!("Failed to load main engine: {}", e))?;
-        let mut main_engine = CueMapEngine::from_state(memories, cue_index);
-        main_engine.set_master_key(self.master_key.clone());
-        main_engine.set_tuning_config(self.tuning.as_ref().clone());
+        let mut main_engine = CueMapEngine::from_state_with_co_occurrence(
+            memories,
+            cue_index,
+            co_occurrence.as_mut().map(|c| std::mem::take(&mut c.main)),
+        );
+        main_engine.set_payload_store(payload_store);
+
+        // Embeddings (optional)
+        let vectors_path = self.snapshots_dir.join(format!("{}_vectors.bin", project_id));
+        if vectors_path.exists() {
+            match PersistenceManager::load_vectors(&vectors_path, &keys) {
+                Ok(mut snapshot) => {
+                    snapshot.vectors.retain(|id, _| main_engine.get_memories().contains_key(id));
+                    main_engine.set_vector_index(VectorIndex::from_snapshot(snapshot));
+                }
+                Err(e) => tracing::warn!("Failed to load embeddings for '{}': {}", project_id, e),
+            }
+        }
+
+        // Version history (optional)
+        let history_path = self.snapshots_dir.join(format!("{}_history.bin", project_id));
+        if history_path.exists() {
+            match PersistenceManager::load_history(&history_path, &keys) {
+                Ok(mut snapshot) => {
+                    snapshot.versions.retain(|id, _| main_engine.get_memories().contains_key(id));
+                    main_engine.set_history(MemoryHistory::from_snapshot(snapshot, self.tuning.max_memory_versions));
+                }
+                Err(e) => tracing::warn!("Failed to load history for '{}': {}", project_id, e),
+            }
+        }
+
+        // Recall statistics (optional)
+        let access_path = self.snapshots_dir.join(format!("{}_access.bin", project_id));
+        if access_path.exists() {
+            match PersistenceManager::load_access(&access_path, &keys) {
+                Ok(mut snapshot) => {
+                    snapshot.entries.retain(|id, _| main_engine.get_memories().contains_key(id));
+                    main_engine.set_access_stats(AccessStats::from_snapshot(snapshot));
+                }
+                Err(e) => tracing::warn!("Failed to load access stats for '{}': {}", project_id, e),
+            }
+        }
         
         // Load aliases engine (optional - may not exist for older snapshots)
-        let mut aliases_engine = if aliases_path.exists() {
-            match PersistenceManager::load_from_path::<MainStats>(&aliases_path) {
+        let aliases_engine = if aliases_path.exists() {
+            match PersistenceManager::load_from_path_with_keys::<MainStats>(&aliases_path, &keys) {
                 Ok((memories, cue_index)) => {
                     tracing::debug!("Loaded aliases for project '{}'", project_id);
-                    CueMapEngine::from_state(memories, cue_index)
+                    CueMapEngine::from_state_with_co_occurrence(memories, cue_index, co_occurrence.as_mut().map(|c| std::mem::take(&mut c.aliases)))
                 }
                 Err(e) => {
                     tracing::warn!("Failed to load aliases for '{}': {}", project_id, e);
@@ -244,15 +1112,13 @@ impl MultiTenantEngine {
         } else {
             CueMapEngine::new()
         };
-        aliases_engine.set_master_key(self.master_key.clone());
-        aliases_engine.set_tuning_config(self.tuning.as_ref().clone());
         
         // Load lexicon engine (optional - may not exist for older snapshots)
-        let mut lexicon_engine = if lexicon_path.exists() {
-            match PersistenceManager::load_from_path::<LexiconStats>(&lexicon_path) {
+        let lexicon_engine = if lexicon_path.exists() {
+            match PersistenceManager::load_from_path_with_keys::<LexiconStats>(&lexicon_path, &keys) {
                 Ok((memories, cue_index)) => {
                     tracing::debug!("Loaded lexicon for project '{}'", project_id);
-                    CueMapEngine::from_state(memories, cue_index)
+                    CueMapEngine::from_state_with_co_occurrence(memories, cue_index, co_occurrence.as_mut().map(|c| std::mem::take(&mut c.lexicon)))
                 }
                 Err(e) => {
                     tracing::warn!("Failed to load lexicon for '{}': {}", project_id, e);
@@ -262,16 +1128,79 @@ impl MultiTenantEngine {
         } else {
             CueMapEngine::new()
         };
-        lexicon_engine.set_master_key(self.master_key.clone());
-        lexicon_engine.set_tuning_config(self.tuning.as_ref().clone());
         
-        let ctx = Arc::new(ProjectContext {
-            main: main_engine,
-            aliases: aliases_engine,
-            lexicon: lexicon_engine,
+        let ctx = Arc::new(self.assemble_project(project_id, main_engine, aliases_engine, lexicon_engine));
+
+        // Lexicon quality tallies (optional)
+        let quality_path = self.snapshots_dir.join(format!("{}_lexicon_quality.bin", project_id));
+        if quality_path.exists() {
+            match PersistenceManager::load_lexicon_quality(&quality_path, &keys) {
+                Ok(snapshot) => {
+                    for (id, quality) in snapshot.entries {
+                        if ctx.lexicon.get_memories().contains_key(&id) {
+                            ctx.lexicon_quality.insert(id, quality);
+                        }
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to load lexicon quality for '{}': {}", project_id, e),
+            }
+        }
+
+        // Market heatmap (optional)
+        let heatmap_path = self.snapshots_dir.join(format!("{}_heatmap.bin", project_id));
+        if heatmap_path.exists() {
+            match PersistenceManager::load_heatmap(&heatmap_path, &keys) {
+                Ok(snapshot) => {
+                    if let Ok(mut heatmap) = ctx.market_heatmap.write() {
+                        *heatmap = MarketHeatmap::from_snapshot(snapshot);
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to load heatmap for '{}': {}", project_id, e),
+            }
+        }
+        
+        self.projects.insert(project_id.clone(), ctx.clone());
+        self.archived.remove(project_id);
+        
+        Ok(ctx)
+    }
+
+    /// Wrap restored engines in a `ProjectContext`, applying this server's keys, tuning and live feed
+    fn assemble_project(
+        &self,
+        project_id: &ProjectId,
+        mut main: CueMapEngine<MainStats>,
+        mut aliases: CueMapEngine<MainStats>,
+        mut lexicon: CueMapEngine<LexiconStats>,
+    ) -> ProjectContext {
+        main.set_master_key(self.master_key.clone());
+        main.set_live_feed(Some(self.live_feed(project_id)));
+        main.set_tuning_config(self.tuning.as_ref().clone());
+        main.history().set_max_versions(self.tuning.max_memory_versions);
+        aliases.set_master_key(self.master_key.clone());
+        aliases.set_tuning_config(self.tuning.as_ref().clone());
+        lexicon.set_master_key(self.master_key.clone());
+        lexicon.set_tuning_config(self.tuning.as_ref().clone());
+        if main.payload_store().is_none() {
+            main.set_payload_store(self.open_payload_store(project_id));
+        }
+        let offloaded = main.offload_payloads();
+        if offloaded > 0 {
+            tracing::debug!("Moved {} payloads of '{}' to disk", offloaded, project_id);
+        }
+        main.rebuild_content_index();
+        let meta = self.load_project_meta(project_id).ok();
+        if let Some(meta) = &meta {
+            main.set_salience_decayed_at(meta.salience_decayed_at);
+        }
+
+        ProjectContext {
+            main,
+            aliases,
+            lexicon,
             query_cache: DashMap::with_hasher(RandomState::new()),
-            normalization: NormalizationConfig::default(),
-            taxonomy: Taxonomy::default(),
+            normalization: RwLock::new(Arc::new(meta.as_ref().map(|meta| meta.normalization.clone()).unwrap_or_default())),
+            taxonomy: RwLock::new(meta.as_ref().map(|meta| meta.taxonomy.clone()).unwrap_or_default()),
             cuegen_strategy: self.cuegen_strategy.clone(),
             semantic_engine: self.semantic_engine.clone(),
             last_activity: std::sync::atomic::AtomicU64::new(
@@ -280,13 +1209,151 @@ impl MultiTenantEngine {
                     .unwrap()
                     .as_secs()
             ),
-            market_heatmap: Arc::new(RwLock::new(HashMap::new())),
+            market_heatmap: Arc::new(RwLock::new(MarketHeatmap::default())),
             tuning: self.tuning.clone(),
             llm_config: self.llm_config.clone(),
-        });
-        
+            text_language: RwLock::new(meta.as_ref().map(|meta| meta.language).unwrap_or_default()),
+            tokenizer: RwLock::new(meta.as_ref().map(|meta| meta.tokenizer).unwrap_or_default()),
+            recall_profile: RwLock::new(meta.as_ref().map(|meta| meta.recall_profile).unwrap_or_default()),
+            schedule: RwLock::new(meta.as_ref().and_then(|meta| meta.schedule.clone())),
+            sessions: meta.as_ref().map(|meta| meta.sessions.clone().into_iter().collect()).unwrap_or_default(),
+            recall_log: DashMap::with_hasher(RandomState::new()),
+            acronyms: meta.map(|meta| meta.acronyms.into_iter().collect()).unwrap_or_default(),
+            lexicon_quality: DashMap::with_hasher(RandomState::new()),
+            org_lexicon: Some(self.org_lexicon.clone()),
+        }
+    }
+
+    /// Serialize a loaded project into a single archive: all three engines with
+    /// their co-occurrence matrices and temporal chains, the market heatmap and
+    /// the project meta (schedule, quota, watch dir). Encrypted like the
+    /// project's snapshots.
+    pub fn dump_project(&self, project_id: &ProjectId) -> Result<Vec<u8>, String> {
+        let ctx = self.get_or_load_project(project_id)
+            .ok_or_else(|| format!("Project '{}' not found", project_id))?;
+
+        let heatmap = ctx.market_heatmap.read().map(|h| h.clone()).unwrap_or_default();
+        let mut meta = self.load_project_meta(project_id)?;
+        meta.salience_decayed_at = ctx.main.salience_decayed_at();
+        let archive = ProjectArchive {
+            version: PROJECT_ARCHIVE_VERSION,
+            project_id: project_id.clone(),
+            saved_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            main: ctx.main.to_archive(),
+            aliases: ctx.aliases.to_archive(),
+            lexicon: ctx.lexicon.to_archive(),
+            market_heatmap: heatmap.weights().clone(),
+            meta,
+            vectors: ctx.main.vector_index().to_snapshot(),
+            heatmap: heatmap.to_snapshot(),
+            links: ctx.main.links_snapshot(),
+        };
+
+        let data = bincode::serialize(&archive)
+            .map_err(|e| format!("Failed to serialize archive: {}", e))?;
+        let Some(key) = self.snapshot_write_key(project_id)? else {
+            return Ok(data);
+        };
+        let encrypted = PersistenceManager::encrypt_snapshot(&data, &key)?;
+        let id_len = u16::try_from(project_id.len()).map_err(|_| format!("Project id '{}' is too long", project_id))?;
+        let mut out = Vec::with_capacity(ENCRYPTED_ARCHIVE_MAGIC.len() + 2 + project_id.len() + encrypted.len());
+        out.extend_from_slice(ENCRYPTED_ARCHIVE_MAGIC);
+        out.extend_from_slice(&id_len.to_le_bytes());
+        out.extend_from_slice(project_id.as_bytes());
+        out.extend_from_slice(&encrypted);
+        Ok(out)
+    }
+
+    /// Split an encrypted archive into its source project id and ciphertext
+    fn split_encrypted_archive(data: &[u8]) -> Result<Option<(ProjectId, &[u8])>, String> {
+        let Some(rest) = data.strip_prefix(ENCRYPTED_ARCHIVE_MAGIC.as_slice()) else {
+            return Ok(None);
+        };
+        let invalid = || "Invalid project archive: truncated header".to_string();
+        let (len, rest) = rest.split_first_chunk::<2>().ok_or_else(invalid)?;
+        let len = u16::from_le_bytes(*len) as usize;
+        if rest.len() < len {
+            return Err(invalid());
+        }
+        let (id, encrypted) = rest.split_at(len);
+        let id = String::from_utf8(id.to_vec()).map_err(|_| "Invalid project archive: source id is not UTF-8".to_string())?;
+        Ok(Some((id, encrypted)))
+    }
+
+    /// Restore a project from `dump_project` output, replacing any loaded project
+    /// with the same id. The archive may come from a different project id.
+    pub fn restore_project(&self, project_id: &ProjectId, data: &[u8]) -> Result<Arc<ProjectContext>, String> {
+        let plaintext;
+        // Decrypt with the keys of the project the archive was dumped from;
+        // archives without the header predate it and only restore under their own id
+        let (source_id, data) = match Self::split_encrypted_archive(data)? {
+            Some((source_id, encrypted)) => (source_id, encrypted),
+            None => (project_id.clone(), data),
+        };
+        let data = if PersistenceManager::is_encrypted_snapshot(data) {
+            let keys = self.snapshot_read_keys(&source_id);
+            let label = PathBuf::from(format!("{}.archive", source_id));
+            plaintext = PersistenceManager::decrypt_snapshot(data, &keys, &label)?;
+            &plaintext[..]
+        } else {
+            data
+        };
+
+        let archive: ProjectArchive = match bincode::deserialize(data) {
+            Ok(archive) => archive,
+            Err(e) => bincode::deserialize::<ProjectArchiveV3>(data)
+                .map(ProjectArchive::from)
+                .or_else(|_| bincode::deserialize::<ProjectArchiveV2>(data).map(ProjectArchive::from))
+                .or_else(|_| bincode::deserialize::<ProjectArchiveV1>(data).map(ProjectArchive::from))
+                .map_err(|_| format!("Invalid project archive: {}", e))?,
+        };
+        if archive.version > PROJECT_ARCHIVE_VERSION {
+            return Err(format!("Unsupported archive version {}", archive.version));
+        }
+
+        let mut meta = archive.meta;
+        meta.project_id = project_id.clone();
+        {
+            let meta_lock = self.meta_lock(project_id);
+            let _meta_guard = meta_lock.lock().unwrap_or_else(|e| e.into_inner());
+            self.save_project_meta(&meta)?;
+        }
+        self.quotas.insert(project_id.clone(), meta.quota.clone());
+        self.project_embeddings.remove(project_id);
+        self.llm_clients.remove(project_id);
+        self.project_rerank.remove(project_id);
+
+        let mut main = CueMapEngine::from_archive(archive.main);
+        main.set_vector_index(VectorIndex::from_snapshot(archive.vectors));
+        if archive.version < 4 {
+            main.migrate_legacy_links();
+        }
+        main.restore_links(archive.links);
+        let ctx = self.assemble_project(
+            project_id,
+            main,
+            CueMapEngine::from_archive(archive.aliases),
+            CueMapEngine::from_archive(archive.lexicon),
+        );
+        if let Ok(mut heatmap) = ctx.market_heatmap.write() {
+            *heatmap = if archive.heatmap.entries.is_empty() {
+                MarketHeatmap::from_weights(archive.market_heatmap)
+            } else {
+                MarketHeatmap::from_snapshot(archive.heatmap)
+            };
+        }
+
+        let ctx = Arc::new(ctx);
         self.projects.insert(project_id.clone(), ctx.clone());
-        
+        self.archived.remove(project_id);
+        tracing::info!(
+            "Restored project '{}' from archive of '{}' ({} memories)",
+            project_id, archive.project_id, ctx.main.total_memories()
+        );
+
         Ok(ctx)
     }
     
@@ -301,14 +1368,38 @@ impl MultiTenantEngine {
             let result = self.save_project(&project_id);
             results.insert(project_id, result);
         }
+
+        if let Err(e) = self.save_org_lexicon() {
+            tracing::warn!("Failed to save organization lexicon: {}", e);
+        }
         
         results
     }
     
+    /// Compact the in-memory indexes of every loaded project
+    pub fn compact_all(&self) -> HashMap<String, HashMap<String, CompactionReport>> {
+        let project_ids: Vec<String> = self.projects.iter().map(|e| e.key().clone()).collect();
+
+        project_ids
+            .into_iter()
+            .filter_map(|id| self.get_project(&id).map(|ctx| (id, ctx.compact_indexes())))
+            .collect()
+    }
+    
+    /// Re-write every loaded project's snapshots with the current snapshot key.
+    /// Run after loading with previous keys configured to complete a key rotation.
+    pub fn rotate_snapshot_keys(&self) -> HashMap<String, Result<PathBuf, String>> {
+        self.save_all()
+    }
+    
     /// Load all available snapshots from disk
     pub fn load_all(&self) -> HashMap<String, Result<(), String>> {
         let mut results = HashMap::new();
         let snapshots = self.list_snapshots();
+
+        if let Err(e) = self.load_org_lexicon() {
+            tracing::warn!("Failed to load organization lexicon: {}", e);
+        }
         
         for project_id in snapshots {
             let result = self.load_project(&project_id)
@@ -320,6 +1411,55 @@ impl MultiTenantEngine {
         results
     }
     
+    /// Vocabulary shared by every project, consulted after each project's own lexicon
+    pub fn org_lexicon(&self) -> &Arc<CueMapEngine<LexiconStats>> {
+        &self.org_lexicon
+    }
+
+    /// Drop cached query resolutions after the organization lexicon changed
+    pub fn org_lexicon_changed(&self) {
+        for entry in self.projects.iter() {
+            entry.value().query_cache.clear();
+        }
+    }
+
+    fn org_lexicon_path(&self) -> PathBuf {
+        self.snapshots_dir.join("org").join("lexicon.bin")
+    }
+
+    /// Save the organization lexicon to `org/lexicon.bin` in the snapshots
+    /// directory (a subdirectory, so it can't collide with a project's files)
+    pub fn save_org_lexicon(&self) -> Result<(), String> {
+        let path = self.org_lexicon_path();
+        if self.org_lexicon.total_memories() == 0 && !path.exists() {
+            return Ok(());
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let key = self.snapshot_keyring.as_ref().map(|k| k.current_key(ORG_KEY_ID));
+        PersistenceManager::save_to_path_with_options(self.org_lexicon.as_ref(), &path, key.as_ref(), self.snapshot_compression)
+            .map_err(|e| format!("Failed to save organization lexicon: {}", e))
+    }
+
+    /// Merge the saved organization lexicon into the live one
+    pub fn load_org_lexicon(&self) -> Result<usize, String> {
+        let path = self.org_lexicon_path();
+        if !path.exists() {
+            return Ok(0);
+        }
+        let keys = self.snapshot_keyring.as_ref().map(|k| k.keys_for(ORG_KEY_ID)).unwrap_or_default();
+        let (memories, _) = PersistenceManager::load_from_path_with_keys::<LexiconStats>(&path, &keys)
+            .map_err(|e| format!("Failed to load organization lexicon: {}", e))?;
+        let count = memories.len();
+        for (id, memory) in memories {
+            let canonical = memory.access_content(self.master_key.as_deref())?;
+            self.org_lexicon.upsert_memory_with_id(id, canonical, memory.cues, None, Some(memory.stats), false, false);
+        }
+        self.org_lexicon_changed();
+        Ok(count)
+    }
+    
     /// List available snapshots on disk
     pub fn list_snapshots(&self) -> Vec<String> {
         PersistenceManager::list_snapshots_in_dir(&self.snapshots_dir)
@@ -329,16 +1469,161 @@ impl MultiTenantEngine {
     #[allow(dead_code)]
     pub fn delete_snapshot(&self, project_id: &ProjectId) -> Result<(), String> {
         let snapshot_path = self.snapshots_dir.join(format!("{}.bin", project_id));
-        let meta_path = self.snapshots_dir.join(format!("{}.meta.json", project_id));
         
-        // Try to delete meta if exists
-        if meta_path.exists() {
-             let _ = fs::remove_file(meta_path);
+        // Try to delete meta, sidecars and payloads if they exist
+        let others = project_snapshot_files(project_id)
+            .into_iter()
+            .chain(std::iter::once(format!("{}_payloads.log", project_id)))
+            .map(|name| self.snapshots_dir.join(name))
+            .filter(|path| *path != snapshot_path);
+        for path in others {
+            if path.exists() {
+                let _ = fs::remove_file(path);
+            }
+        }
+        let generations_dir = self.generations_dir(project_id);
+        if generations_dir.exists() {
+             let _ = fs::remove_dir_all(generations_dir);
         }
 
         PersistenceManager::delete_snapshot(&snapshot_path)
     }
 
+    fn generations_dir(&self, project_id: &ProjectId) -> PathBuf {
+        self.snapshots_dir.join("generations").join(project_id)
+    }
+
+    /// Retained snapshot generations of a project, newest first
+    pub fn list_generations(&self, project_id: &ProjectId) -> Vec<SnapshotGeneration> {
+        let Ok(entries) = fs::read_dir(self.generations_dir(project_id)) else {
+            return Vec::new();
+        };
+        let mut generations: Vec<SnapshotGeneration> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_str()?.to_string();
+                let millis: u64 = name.parse().ok()?;
+                let files: Vec<u64> = fs::read_dir(entry.path())
+                    .ok()?
+                    .flatten()
+                    .filter_map(|f| f.metadata().ok().map(|m| m.len()))
+                    .collect();
+                Some(SnapshotGeneration {
+                    generation: name,
+                    created_at: millis as f64 / 1000.0,
+                    files: files.len(),
+                    bytes: files.iter().sum(),
+                })
+            })
+            .collect();
+        generations.sort_by(|a, b| b.created_at.total_cmp(&a.created_at));
+        generations
+    }
+
+    /// Copy a project's current snapshot files into a new generation, unless
+    /// the newest one is younger than the generation interval, then drop
+    /// generations beyond the retention count. `force` skips both checks, so a
+    /// restore never prunes the generation it is about to read.
+    /// Snapshot files are hard-linked where possible: saves replace them by rename.
+    fn retain_generation(&self, project_id: &ProjectId, force: bool) -> Result<Option<String>, String> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+        let generations = self.list_generations(project_id);
+        if let Some(newest) = generations.first() {
+            let age_secs = now.saturating_sub((newest.created_at * 1000.0) as u64) / 1000;
+            if !force && age_secs < self.generation_interval_secs {
+                return Ok(None);
+            }
+        }
+
+        let dir = self.generations_dir(project_id);
+        let mut millis = now;
+        while dir.join(millis.to_string()).exists() {
+            millis += 1;
+        }
+        let generation = millis.to_string();
+        // Built under a temporary name so a partial copy is never listed
+        let staging = dir.join(format!("{}.tmp", generation));
+        fs::create_dir_all(&staging).map_err(|e| format!("Failed to create {:?}: {}", staging, e))?;
+        for name in project_snapshot_files(project_id) {
+            let from = self.snapshots_dir.join(&name);
+            if !from.exists() {
+                continue;
+            }
+            let to = staging.join(&name);
+            let linked = name.ends_with(".bin") && fs::hard_link(&from, &to).is_ok();
+            if !linked {
+                fs::copy(&from, &to).map_err(|e| format!("Failed to copy {:?}: {}", from, e))?;
+            }
+        }
+        fs::rename(&staging, dir.join(&generation)).map_err(|e| format!("Failed to keep generation {}: {}", generation, e))?;
+
+        if force {
+            return Ok(Some(generation));
+        }
+        for old in self.list_generations(project_id).iter().skip(self.snapshot_generations.max(1)) {
+            let _ = fs::remove_dir_all(dir.join(&old.generation));
+        }
+        Ok(Some(generation))
+    }
+
+    /// Roll a project back to a retained generation, replacing its current
+    /// snapshot files and reloading it. When generations are enabled, the state
+    /// being replaced is kept as a new generation first, so the restore can be undone.
+    /// Returns the restored project and the generation holding the replaced state.
+    pub fn restore_generation(&self, project_id: &ProjectId, generation: &str) -> Result<(Arc<ProjectContext>, Option<String>), String> {
+        let source = self.generations_dir(project_id).join(generation);
+        if generation.is_empty() || !generation.bytes().all(|b| b.is_ascii_digit()) || !source.join(format!("{}.bin", project_id)).exists() {
+            return Err(format!("Snapshot generation '{}' of project '{}' not found", generation, project_id));
+        }
+        for name in project_snapshot_files(project_id).iter().filter(|name| name.ends_with(".bin")) {
+            let path = source.join(name);
+            if path.exists() {
+                let check = PersistenceManager::verify_snapshot_file(&path);
+                if check.status == SnapshotStatus::Corrupt {
+                    return Err(check.error.unwrap_or_else(|| format!("Snapshot {:?} is corrupt", path)));
+                }
+            }
+        }
+
+        let previous = if self.snapshot_generations > 0 && self.get_project(project_id).is_some() {
+            self.write_project_snapshot(project_id)?;
+            self.retain_generation(project_id, true)?
+        } else {
+            None
+        };
+
+        // Taken out of service first, so a background save can't overwrite the restored files
+        let _guard = self.archive_lock.lock().unwrap_or_else(|e| e.into_inner());
+        self.projects.remove(project_id);
+        let meta_lock = self.meta_lock(project_id);
+        let meta_guard = meta_lock.lock().unwrap_or_else(|e| e.into_inner());
+        for name in project_snapshot_files(project_id) {
+            let from = source.join(&name);
+            let to = self.snapshots_dir.join(&name);
+            if from.exists() {
+                let temp = self.snapshots_dir.join(format!("{}.restore.tmp", name));
+                fs::copy(&from, &temp)
+                    .and_then(|_| fs::rename(&temp, &to))
+                    .map_err(|e| format!("Failed to restore {:?}: {}", to, e))?;
+            } else if to.exists() {
+                let _ = fs::remove_file(&to);
+            }
+        }
+        drop(meta_guard);
+
+        let meta = self.load_project_meta(project_id)?;
+        self.quotas.insert(project_id.clone(), meta.quota.clone());
+        self.project_embeddings.remove(project_id);
+        self.llm_clients.remove(project_id);
+        self.project_rerank.remove(project_id);
+        let ctx = self.load_project(project_id)?;
+        tracing::info!(
+            "Restored project '{}' from snapshot generation {} ({} memories)",
+            project_id, generation, ctx.main.total_memories()
+        );
+        Ok((ctx, previous))
+    }
+
     /// Load project metadata
     pub fn load_project_meta(&self, project_id: &ProjectId) -> Result<ProjectMeta, String> {
         let meta_path = self.snapshots_dir.join(format!("{}.meta.json", project_id));
@@ -347,8 +1632,13 @@ impl MultiTenantEngine {
             let meta: ProjectMeta = serde_json::from_str(&content).map_err(|e| e.to_string())?;
             Ok(meta)
         } else {
-            // Return default if not found (legacy projects)
-            Ok(ProjectMeta::new(project_id.clone()))
+            // Return default if not found (legacy projects, whose snapshots
+            // were tokenized with the ASCII rules)
+            let mut meta = ProjectMeta::new(project_id.clone());
+            if self.snapshots_dir.join(format!("{}.bin", project_id)).exists() {
+                meta.tokenizer = legacy_tokenizer();
+            }
+            Ok(meta)
         }
     }
 
@@ -360,6 +1650,22 @@ impl MultiTenantEngine {
         Ok(())
     }
 
+    /// Load a project's meta, change it with `update` and save it, holding the
+    /// project's meta lock throughout so concurrent setters don't lose each
+    /// other's changes. `update` must not call back into other meta setters.
+    pub fn update_project_meta<R>(&self, project_id: &str, update: impl FnOnce(&mut ProjectMeta) -> R) -> Result<R, String> {
+        let meta_lock = self.meta_lock(project_id);
+        let _meta_guard = meta_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut meta = self.load_project_meta(&project_id.to_string())?;
+        let result = update(&mut meta);
+        self.save_project_meta(&meta)?;
+        Ok(result)
+    }
+
+    fn meta_lock(&self, project_id: &str) -> Arc<Mutex<()>> {
+        self.meta_locks.entry(project_id.to_string()).or_default().clone()
+    }
+
     /// Set watch directory for a project
     pub fn set_project_watch_dir(&self, project_id: &str, watch_dir: Option<String>) -> Result<(), String> {
         // Validation
@@ -370,18 +1676,433 @@ impl MultiTenantEngine {
             }
         }
 
-        // Get current meta
-        let mut meta = self.load_project_meta(&project_id.to_string())?;
-        
-        meta.watch_dir = watch_dir;
-        // Auto-enable agent if watch dir is set
-        meta.agent_enabled = meta.watch_dir.is_some();
-        
-        self.save_project_meta(&meta)?;
-        
+        self.update_project_meta(project_id, |meta| {
+            meta.watch_dir = watch_dir;
+            // Auto-enable agent if watch dir is set
+            meta.agent_enabled = meta.watch_dir.is_some();
+        })
+    }
+    
+    /// Set the snapshot encryption override for a project
+    pub fn set_project_snapshot_encryption(&self, project_id: &str, encrypt: Option<bool>) -> Result<(), String> {
+        if encrypt == Some(true) && self.snapshot_keyring.is_none() {
+            return Err("No snapshot key is configured on this server".to_string());
+        }
+
+        self.update_project_meta(project_id, |meta| meta.encrypt_snapshots = encrypt)
+    }
+    
+    /// Schedule of a project. Loaded projects answer from memory, so the
+    /// scheduler's ticks don't read every meta file.
+    pub fn get_project_schedule(&self, project_id: &str) -> Result<ProjectSchedule, String> {
+        let schedule = match self.get_project(&project_id.to_string()) {
+            Some(ctx) => ctx.schedule.read().map(|s| s.clone()).unwrap_or_default(),
+            None => self.load_project_meta(&project_id.to_string())?.schedule,
+        };
+        Ok(schedule.unwrap_or_else(|| self.default_schedule.clone()))
+    }
+
+    /// Schedule of projects that have not set their own (`[schedule]`)
+    pub fn set_default_schedule(&mut self, schedule: ProjectSchedule) {
+        self.default_schedule = schedule;
+    }
+
+    pub fn set_project_schedule(&self, project_id: &str, schedule: ProjectSchedule) -> Result<(), String> {
+        schedule.validate()?;
+
+        self.update_project_meta(project_id, |meta| meta.schedule = Some(schedule.clone()))?;
+
+        if let Some(ctx) = self.get_project(&project_id.to_string()) {
+            if let Ok(mut current) = ctx.schedule.write() {
+                *current = Some(schedule);
+            }
+        }
         Ok(())
     }
     
+    pub fn get_project_feeds(&self, project_id: &str) -> Result<Vec<FeedSubscription>, String> {
+        let meta = self.load_project_meta(&project_id.to_string())?;
+        Ok(meta.feeds.into_values().collect())
+    }
+
+    pub fn get_project_feed(&self, project_id: &str, url: &str) -> Result<Option<FeedSubscription>, String> {
+        let meta = self.load_project_meta(&project_id.to_string())?;
+        Ok(meta.feeds.get(url).cloned())
+    }
+
+    /// Add or replace a feed subscription
+    pub fn set_project_feed(&self, project_id: &str, feed: FeedSubscription) -> Result<(), String> {
+        self.update_project_meta(project_id, |meta| {
+            meta.feeds.insert(feed.url.clone(), feed);
+        })
+    }
+
+    /// Forget a feed (its ingested pages stay). Returns false if it wasn't subscribed.
+    pub fn remove_project_feed(&self, project_id: &str, url: &str) -> Result<bool, String> {
+        self.update_project_meta(project_id, |meta| meta.feeds.remove(url).is_some())
+    }
+
+    pub fn get_project_connectors(&self, project_id: &str) -> Result<Vec<ConnectorConfig>, String> {
+        let meta = self.load_project_meta(&project_id.to_string())?;
+        Ok(meta.connectors.into_values().collect())
+    }
+
+    pub fn get_project_connector(&self, project_id: &str, id: &str) -> Result<Option<ConnectorConfig>, String> {
+        let meta = self.load_project_meta(&project_id.to_string())?;
+        Ok(meta.connectors.get(id).cloned())
+    }
+
+    /// Add or replace a connector
+    pub fn set_project_connector(&self, project_id: &str, connector: ConnectorConfig) -> Result<(), String> {
+        self.update_project_meta(project_id, |meta| {
+            meta.connectors.insert(connector.id.clone(), connector);
+        })
+    }
+
+    /// Remove a connector (its ingested documents stay). Returns false if there was none.
+    pub fn remove_project_connector(&self, project_id: &str, id: &str) -> Result<bool, String> {
+        self.update_project_meta(project_id, |meta| meta.connectors.remove(id).is_some())
+    }
+
+    pub fn get_project_quota(&self, project_id: &str) -> Result<ProjectQuota, String> {
+        if let Some(quota) = self.quotas.get(project_id) {
+            return Ok(quota.clone());
+        }
+        let meta = self.load_project_meta(&project_id.to_string())?;
+        self.quotas.insert(project_id.to_string(), meta.quota.clone());
+        Ok(meta.quota)
+    }
+
+    pub fn set_project_quota(&self, project_id: &str, quota: ProjectQuota) -> Result<(), String> {
+        self.update_project_meta(project_id, |meta| meta.quota = quota.clone())?;
+        self.quotas.insert(project_id.to_string(), quota);
+
+        Ok(())
+    }
+
+    pub fn get_project_chunking(&self, project_id: &str) -> Result<ChunkingConfig, String> {
+        Ok(self.load_project_meta(&project_id.to_string())?.chunking)
+    }
+
+    /// Set a project's chunk size limits. Applies to content chunked from now
+    /// on; existing memories keep their chunks until their source is re-ingested.
+    pub fn set_project_chunking(&self, project_id: &str, chunking: ChunkingConfig) -> Result<(), String> {
+        chunking.validate()?;
+        self.update_project_meta(project_id, |meta| meta.chunking = chunking)
+    }
+
+    pub fn get_project_language(&self, project_id: &str) -> Result<TextLanguage, String> {
+        Ok(self.load_project_meta(&project_id.to_string())?.language)
+    }
+
+    /// Set the natural language of a project's text. Applies to text tokenized
+    /// from now on; cues of existing memories are not re-derived.
+    pub fn set_project_language(&self, project_id: &str, language: TextLanguage) -> Result<(), String> {
+        self.update_project_meta(project_id, |meta| meta.language = language)?;
+
+        if let Some(ctx) = self.get_project(&project_id.to_string()) {
+            if let Ok(mut current) = ctx.text_language.write() {
+                *current = language;
+            }
+            ctx.query_cache.clear();
+        }
+        Ok(())
+    }
+
+    /// Recall path a project uses when a request doesn't pick one
+    pub fn get_project_recall_profile(&self, project_id: &str) -> Result<RecallProfile, String> {
+        Ok(self.load_project_meta(&project_id.to_string())?.recall_profile)
+    }
+
+    pub fn set_project_recall_profile(&self, project_id: &str, profile: RecallProfile) -> Result<(), String> {
+        self.update_project_meta(project_id, |meta| meta.recall_profile = profile)?;
+
+        if let Some(ctx) = self.get_project(&project_id.to_string()) {
+            if let Ok(mut current) = ctx.recall_profile.write() {
+                *current = profile;
+            }
+        }
+        Ok(())
+    }
+
+    /// Acronym table used to rewrite a project's recall queries
+    pub fn get_project_acronyms(&self, project_id: &str) -> Result<BTreeMap<String, String>, String> {
+        match self.get_project(&project_id.to_string()) {
+            Some(ctx) => Ok(ctx.acronym_table()),
+            None => Ok(self.load_project_meta(&project_id.to_string())?.acronyms),
+        }
+    }
+
+    /// Add or replace an acronym of a project. Both sides are lowercased.
+    pub fn set_project_acronym(&self, project_id: &str, acronym: &str, expansion: &str) -> Result<(), String> {
+        let acronym = acronym.trim().to_lowercase();
+        let expansion = expansion.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+        if acronym.is_empty() || acronym.contains(char::is_whitespace) {
+            return Err("acronym must be a single word".to_string());
+        }
+        if expansion.is_empty() || expansion == acronym {
+            return Err("expansion must be non-empty and differ from the acronym".to_string());
+        }
+
+        let ctx = self.get_or_create_project(project_id.to_string())?;
+        ctx.acronyms.insert(acronym, expansion);
+        self.update_project_meta(project_id, |meta| meta.acronyms = ctx.acronym_table())
+    }
+
+    /// Remove an acronym of a project. False if it wasn't in the table.
+    pub fn remove_project_acronym(&self, project_id: &str, acronym: &str) -> Result<bool, String> {
+        let acronym = acronym.trim().to_lowercase();
+        let ctx = self.get_or_create_project(project_id.to_string())?;
+        let removed = ctx.acronyms.remove(&acronym).is_some();
+        self.update_project_meta(project_id, |meta| meta.acronyms = ctx.acronym_table())?;
+        Ok(removed)
+    }
+
+    pub fn get_project_taxonomy(&self, project_id: &str) -> Result<Taxonomy, String> {
+        Ok(self.load_project_meta(&project_id.to_string())?.taxonomy)
+    }
+
+    /// Replace a project's taxonomy. Applies to cues written from now on;
+    /// existing memories keep their cues. Patterns are lowercased like cues.
+    pub fn set_project_taxonomy(&self, project_id: &str, mut taxonomy: Taxonomy) -> Result<Taxonomy, String> {
+        taxonomy.validate()?;
+        for pattern in &mut taxonomy.forbidden_patterns {
+            *pattern = pattern.trim().to_lowercase();
+        }
+
+        self.update_project_meta(project_id, |meta| meta.taxonomy = taxonomy.clone())?;
+
+        if let Some(ctx) = self.get_project(&project_id.to_string()) {
+            if let Ok(mut current) = ctx.taxonomy.write() {
+                *current = taxonomy.clone();
+            }
+            ctx.query_cache.clear();
+        }
+        Ok(taxonomy)
+    }
+
+    pub fn get_project_normalization(&self, project_id: &str) -> Result<NormalizationConfig, String> {
+        Ok(self.load_project_meta(&project_id.to_string())?.normalization)
+    }
+
+    /// Replace a project's cue normalization pipeline. Cues of existing
+    /// memories are re-normalized with it so they keep matching queries;
+    /// returns the pipeline and the number of memories whose cues changed.
+    pub fn set_project_normalization(&self, project_id: &str, mut normalization: NormalizationConfig) -> Result<(NormalizationConfig, usize), String> {
+        normalization.validate()?;
+
+        self.update_project_meta(project_id, |meta| meta.normalization = normalization.clone())?;
+
+        // Archived projects are reloaded so their cues are rewritten too
+        let mut updated = 0;
+        if let Some(ctx) = self.get_or_load_project(&project_id.to_string()) {
+            if let Ok(mut current) = ctx.normalization.write() {
+                *current = Arc::new(normalization.clone());
+            }
+            updated = ctx.renormalize_memories();
+        }
+        Ok((normalization, updated))
+    }
+
+    /// Open a named episode in a project
+    pub fn create_session(&self, project_id: &str, name: Option<String>) -> Result<EpisodeSession, String> {
+        let ctx = self.get_or_create_project(project_id.to_string())?;
+        let id = uuid::Uuid::new_v4().to_string();
+        let session = EpisodeSession {
+            name: name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| id.clone()),
+            id: id.clone(),
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+        };
+
+        self.update_project_meta(project_id, |meta| {
+            meta.sessions.insert(id.clone(), session.clone());
+        })?;
+        ctx.sessions.insert(id, session.clone());
+        Ok(session)
+    }
+
+    /// Embedding settings in effect for a project (its override or the server default)
+    pub fn get_project_embeddings(&self, project_id: &str) -> EmbeddingConfig {
+        if let Some(config) = self.project_embeddings.get(project_id) {
+            return config.clone();
+        }
+        let config = self.load_project_meta(&project_id.to_string())
+            .ok()
+            .and_then(|meta| meta.embeddings)
+            .unwrap_or_else(|| (*self.embedding_config).clone());
+        self.project_embeddings.insert(project_id.to_string(), config.clone());
+        config
+    }
+
+    /// Override the embedding provider of a project (None = server default).
+    /// Existing vectors are dropped when the effective provider changes, since
+    /// vectors from different models are not comparable; re-embed with
+    /// `POST /memories/:id/embed` or `POST /projects/:id/embed`.
+    pub fn set_project_embeddings(&self, project_id: &str, config: Option<EmbeddingConfig>) -> Result<EmbeddingConfig, String> {
+        let effective = config.clone().unwrap_or_else(|| (*self.embedding_config).clone());
+        self.provider_for(&effective)?;
+
+        let previous = self.get_project_embeddings(project_id);
+        self.update_project_meta(project_id, |meta| meta.embeddings = config)?;
+        self.project_embeddings.insert(project_id.to_string(), effective.clone());
+
+        if previous != effective {
+            if let Some(ctx) = self.get_project(&project_id.to_string()) {
+                ctx.main.clear_embeddings();
+            }
+        }
+        Ok(effective)
+    }
+
+    /// LLM settings in effect for a project (its override or the server default)
+    pub fn get_project_llm(&self, project_id: &str) -> LlmConfig {
+        self.llm_client(project_id).config().clone()
+    }
+
+    /// Override the LLM of a project (None = server default). Its rate limits
+    /// start over.
+    pub fn set_project_llm(&self, project_id: &str, config: Option<LlmConfig>) -> Result<LlmConfig, String> {
+        let effective = config.clone().unwrap_or_else(|| (*self.llm_config).clone());
+        effective.validate()?;
+
+        self.update_project_meta(project_id, |meta| meta.llm = config)?;
+        self.llm_clients.insert(project_id.to_string(), Arc::new(LlmClient::new(effective.clone())));
+        Ok(effective)
+    }
+
+    /// Client for a project's LLM, used by cue proposal and other generation jobs
+    pub fn llm_client(&self, project_id: &str) -> Arc<LlmClient> {
+        if let Some(client) = self.llm_clients.get(project_id) {
+            return client.clone();
+        }
+        let config = self.load_project_meta(&project_id.to_string())
+            .ok()
+            .and_then(|meta| meta.llm)
+            .unwrap_or_else(|| (*self.llm_config).clone());
+        self.llm_clients
+            .entry(project_id.to_string())
+            .or_insert_with(|| Arc::new(LlmClient::new(config)))
+            .clone()
+    }
+
+    /// Rerank settings of a project
+    pub fn get_project_rerank(&self, project_id: &str) -> RerankConfig {
+        if let Some(config) = self.project_rerank.get(project_id) {
+            return config.clone();
+        }
+        let config = self.load_project_meta(&project_id.to_string())
+            .map(|meta| meta.rerank)
+            .unwrap_or_default();
+        self.project_rerank.insert(project_id.to_string(), config.clone());
+        config
+    }
+
+    /// Set the rerank stage of a project. The scorer is built up front, so a
+    /// missing model or a disabled LLM is reported here rather than on recall.
+    pub fn set_project_rerank(&self, project_id: &str, config: RerankConfig) -> Result<RerankConfig, String> {
+        config.validate()?;
+        self.reranker_for(project_id, &config)?;
+
+        self.update_project_meta(project_id, |meta| meta.rerank = config.clone())?;
+        self.project_rerank.insert(project_id.to_string(), config.clone());
+        Ok(config)
+    }
+
+    /// Scorer of a project's rerank stage (None = strategy `none`)
+    pub fn reranker(&self, project_id: &str) -> Result<Option<Arc<dyn Reranker>>, String> {
+        self.reranker_for(project_id, &self.get_project_rerank(project_id))
+    }
+
+    fn reranker_for(&self, project_id: &str, config: &RerankConfig) -> Result<Option<Arc<dyn Reranker>>, String> {
+        // LLM rerankers wrap the project's client and are cheap to build
+        if config.strategy != RerankStrategy::Onnx {
+            return build_reranker(config, self.llm_client(project_id));
+        }
+        let key = RerankConfig { enabled: false, top_n: 0, ..config.clone() };
+        if let Some(reranker) = self.cross_encoders.get(&key) {
+            return Ok(Some(reranker.clone()));
+        }
+        let reranker = build_reranker(config, self.llm_client(project_id))?;
+        if let Some(r) = &reranker {
+            self.cross_encoders.insert(key, r.clone());
+        }
+        Ok(reranker)
+    }
+
+    /// Provider used to embed a project's memories and queries (None = not configured)
+    pub fn embedding_provider(&self, project_id: &str) -> Result<Option<Arc<dyn EmbeddingProvider>>, String> {
+        self.provider_for(&self.get_project_embeddings(project_id))
+    }
+
+    fn provider_for(&self, config: &EmbeddingConfig) -> Result<Option<Arc<dyn EmbeddingProvider>>, String> {
+        if let Some(provider) = self.embedders.get(config) {
+            return Ok(Some(provider.clone()));
+        }
+        let provider = build_provider(config, &self.semantic_engine)?;
+        if let Some(p) = &provider {
+            self.embedders.insert(config.clone(), p.clone());
+        }
+        Ok(provider)
+    }
+
+    /// Current usage of the resources covered by quotas (zero if the project is not loaded)
+    pub fn quota_usage(&self, project_id: &str) -> QuotaUsage {
+        match self.get_project(&project_id.to_string()) {
+            Some(ctx) => QuotaUsage {
+                memories: ctx.main.total_memories(),
+                cues: ctx.main.total_cues(),
+                storage_bytes: ctx.main.stored_bytes() as u64,
+            },
+            None => QuotaUsage::default(),
+        }
+    }
+
+    /// Check whether adding `incoming_memories` memories with `incoming_bytes` of
+    /// content fits within the project's quota. Cue limits are checked against
+    /// the current index only, since new cues are not known until cue generation runs.
+    pub fn check_quota(&self, project_id: &str, incoming_memories: usize, incoming_bytes: u64) -> Result<(), QuotaExceeded> {
+        let quota = match self.get_project_quota(project_id) {
+            Ok(q) => q,
+            Err(e) => {
+                tracing::warn!("Failed to read quota for '{}': {}", project_id, e);
+                return Ok(());
+            }
+        };
+        if quota == ProjectQuota::default() {
+            return Ok(());
+        }
+
+        let usage = self.quota_usage(project_id);
+        let exceeded = |resource: &str, limit: u64, current: u64, requested: u64| QuotaExceeded {
+            project_id: project_id.to_string(),
+            resource: resource.to_string(),
+            limit,
+            current,
+            requested,
+        };
+
+        if let Some(max) = quota.max_memories {
+            if usage.memories + incoming_memories > max {
+                return Err(exceeded("memories", max as u64, usage.memories as u64, incoming_memories as u64));
+            }
+        }
+        if let Some(max) = quota.max_cues {
+            if incoming_memories > 0 && usage.cues >= max {
+                return Err(exceeded("cues", max as u64, usage.cues as u64, 0));
+            }
+        }
+        if let Some(max) = quota.max_storage_bytes {
+            if usage.storage_bytes + incoming_bytes > max {
+                return Err(exceeded("storage_bytes", max, usage.storage_bytes, incoming_bytes));
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn get_global_stats(&self) -> HashMap<String, serde_json::Value> {
         let projects = self.list_projects();
         
@@ -405,6 +2126,18 @@ impl MultiTenantEngine {
             "projects".to_string(),
             serde_json::json!(projects),
         );
+        stats.insert(
+            "archive".to_string(),
+            serde_json::json!({
+                "archived_projects": self.archived.len(),
+                "evictions": self.archive_stats.evictions.load(Ordering::Relaxed),
+                "reloads": self.archive_stats.reloads.load(Ordering::Relaxed),
+                "rejections": self.archive_stats.rejections.load(Ordering::Relaxed),
+                "resident_projects": self.projects.len(),
+                "max_resident_projects": self.max_resident_projects,
+                "last_eviction_at": self.archive_stats.last_eviction_at.load(Ordering::Relaxed),
+            }),
+        );
         
         stats
     }
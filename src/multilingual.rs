@@ -0,0 +1,189 @@
+//! Natural-language support for tokenization: language detection, stopword
+//! lists, stemmers and CJK segmentation.
+//!
+//! English keeps the original pipeline in `crate::nl` (nlprule lemmatization).
+//! German, French and Spanish use Snowball stemmers and their own stopwords.
+//! Chinese, Japanese and Korean are written without (reliable) word
+//! boundaries, so runs of CJK characters are segmented into overlapping
+//! character bigrams, the same scheme Lucene's CJK analyzer uses.
+
+use rust_stemmers::{Algorithm, Stemmer};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+static GERMAN_STOPWORDS: OnceLock<HashSet<&'static str>> = OnceLock::new();
+static FRENCH_STOPWORDS: OnceLock<HashSet<&'static str>> = OnceLock::new();
+static SPANISH_STOPWORDS: OnceLock<HashSet<&'static str>> = OnceLock::new();
+static CJK_STOPWORDS: OnceLock<HashSet<&'static str>> = OnceLock::new();
+
+static GERMAN_STEMMER: OnceLock<Stemmer> = OnceLock::new();
+static FRENCH_STEMMER: OnceLock<Stemmer> = OnceLock::new();
+static SPANISH_STEMMER: OnceLock<Stemmer> = OnceLock::new();
+
+/// Language of a project's text. `Auto` detects it per text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum TextLanguage {
+    #[serde(rename = "auto")]
+    Auto,
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "de")]
+    German,
+    #[serde(rename = "fr")]
+    French,
+    #[serde(rename = "es")]
+    Spanish,
+    /// Chinese, Japanese and Korean
+    #[serde(rename = "cjk")]
+    Cjk,
+}
+
+impl TextLanguage {
+    /// The language to tokenize `text` with (`Auto` is detected)
+    pub fn resolve(self, text: &str) -> TextLanguage {
+        match self {
+            TextLanguage::Auto => detect_language(text),
+            lang => lang,
+        }
+    }
+
+    pub fn stopwords(self) -> &'static HashSet<&'static str> {
+        match self {
+            TextLanguage::Auto | TextLanguage::English => crate::nl::get_stopwords(),
+            TextLanguage::German => GERMAN_STOPWORDS.get_or_init(|| {
+                [
+                    "aber", "alle", "allem", "allen", "aller", "alles", "als", "also", "am", "an", "ander", "andere", "anderen", "auch", "auf", "aus",
+                    "bei", "bin", "bis", "bist", "da", "damit", "dann", "das", "dass", "dein", "deine", "dem", "den", "denn", "der", "des", "dich", "die",
+                    "dies", "diese", "diesem", "diesen", "dieser", "dieses", "doch", "dort", "du", "durch", "ein", "eine", "einem", "einen", "einer", "eines",
+                    "er", "es", "euer", "für", "gegen", "hat", "hatte", "habe", "haben", "hier", "hin", "ich", "ihm", "ihn", "ihr", "ihre", "im", "in",
+                    "ist", "jede", "jedem", "jeden", "jeder", "jetzt", "kann", "kein", "keine", "man", "mein", "meine", "mich", "mir", "mit", "muss",
+                    "nach", "nicht", "noch", "nun", "nur", "ob", "oder", "ohne", "sehr", "sein", "seine", "sich", "sie", "sind", "so", "solche", "soll",
+                    "über", "um", "und", "uns", "unser", "unter", "viel", "vom", "von", "vor", "war", "waren", "was", "weil", "welche", "wenn", "werden",
+                    "wie", "wieder", "will", "wir", "wird", "wo", "zu", "zum", "zur", "zwischen",
+                ].into_iter().collect()
+            }),
+            TextLanguage::French => FRENCH_STOPWORDS.get_or_init(|| {
+                [
+                    "ai", "au", "aux", "avec", "avez", "avons", "ce", "ces", "cet", "cette", "comme", "dans", "de", "des", "du", "elle", "elles", "en",
+                    "est", "et", "été", "être", "eu", "il", "ils", "je", "la", "le", "les", "leur", "leurs", "lui", "ma", "mais", "me", "mes", "moi",
+                    "mon", "ne", "nos", "notre", "nous", "on", "ont", "ou", "où", "par", "pas", "plus", "pour", "qu", "que", "qui", "sa", "sans", "se",
+                    "ses", "si", "son", "sont", "sur", "ta", "te", "tes", "toi", "ton", "tous", "tout", "tu", "un", "une", "vos", "votre", "vous",
+                    "était", "sera", "fait", "très", "aussi", "donc", "car", "ni", "ça", "cela", "ceci", "dont",
+                ].into_iter().collect()
+            }),
+            TextLanguage::Spanish => SPANISH_STOPWORDS.get_or_init(|| {
+                [
+                    "al", "algo", "algunos", "ante", "antes", "como", "con", "contra", "cual", "cuando", "de", "del", "desde", "donde", "durante", "el",
+                    "ella", "ellas", "ellos", "en", "entre", "era", "es", "esa", "esas", "ese", "eso", "esos", "esta", "está", "están", "estas", "este",
+                    "esto", "estos", "fue", "ha", "han", "hay", "la", "las", "le", "les", "lo", "los", "más", "me", "mi", "mis", "muy", "nada", "ni",
+                    "no", "nos", "nosotros", "o", "otra", "otras", "otro", "otros", "para", "pero", "poco", "por", "porque", "que", "qué", "quien",
+                    "se", "sea", "ser", "si", "sí", "sin", "sobre", "son", "su", "sus", "también", "tanto", "te", "tiene", "todo", "todos", "tu", "tus",
+                    "un", "una", "uno", "unos", "y", "ya", "yo",
+                ].into_iter().collect()
+            }),
+            TextLanguage::Cjk => CJK_STOPWORDS.get_or_init(|| {
+                // Particles and function characters that make noisy bigrams
+                [
+                    "的", "了", "是", "在", "和", "与", "也", "就", "都", "而", "及", "或", "这", "那", "之",
+                    "の", "は", "が", "を", "に", "で", "と", "も", "へ", "や", "な", "た", "て", "す", "ま",
+                    "은", "는", "이", "가", "을", "를", "에", "의", "와", "과", "도",
+                ].into_iter().collect()
+            }),
+        }
+    }
+
+    /// Stem a lowercased word
+    pub fn stem(self, word: &str) -> String {
+        let stemmer = match self {
+            TextLanguage::German => GERMAN_STEMMER.get_or_init(|| Stemmer::create(Algorithm::German)),
+            TextLanguage::French => FRENCH_STEMMER.get_or_init(|| Stemmer::create(Algorithm::French)),
+            TextLanguage::Spanish => SPANISH_STEMMER.get_or_init(|| Stemmer::create(Algorithm::Spanish)),
+            TextLanguage::Auto | TextLanguage::English => return crate::nl::stem_word(word),
+            TextLanguage::Cjk => return word.to_string(),
+        };
+        // Very short words stem badly
+        if word.chars().count() <= 3 {
+            return word.to_string();
+        }
+        stemmer.stem(word).into_owned()
+    }
+}
+
+/// Guess the language of `text`: CJK when at least 30% of its letters are CJK,
+/// otherwise the language whose stopwords (and accented letters) occur most.
+/// Falls back to English when the evidence is thin, so short queries stay English.
+pub fn detect_language(text: &str) -> TextLanguage {
+    let mut letters = 0usize;
+    let mut cjk = 0usize;
+    let mut german = 0usize;
+    let mut french = 0usize;
+    let mut spanish = 0usize;
+    for c in text.chars() {
+        if is_cjk(c) {
+            cjk += 1;
+        }
+        if c.is_alphabetic() {
+            letters += 1;
+        }
+        match c.to_lowercase().next().unwrap_or(c) {
+            'ä' | 'ö' | 'ü' | 'ß' => german += 1,
+            'à' | 'â' | 'ç' | 'è' | 'ê' | 'ë' | 'î' | 'ï' | 'ô' | 'û' | 'ù' | 'œ' => french += 1,
+            'ñ' | 'á' | 'í' | 'ó' | 'ú' | '¿' | '¡' => spanish += 1,
+            _ => {}
+        }
+    }
+    if letters == 0 {
+        return TextLanguage::English;
+    }
+    if cjk * 10 >= letters * 3 {
+        return TextLanguage::Cjk;
+    }
+
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let hits = |lang: TextLanguage| words.iter().filter(|w| lang.stopwords().contains(*w)).count();
+    let english = hits(TextLanguage::English);
+
+    let (best, score) = [
+        (TextLanguage::German, hits(TextLanguage::German) + german),
+        (TextLanguage::French, hits(TextLanguage::French) + french),
+        (TextLanguage::Spanish, hits(TextLanguage::Spanish) + spanish),
+    ]
+    .into_iter()
+    .max_by_key(|(_, score)| *score)
+    .unwrap_or((TextLanguage::English, 0));
+
+    if score >= 2 && score > english {
+        best
+    } else {
+        TextLanguage::English
+    }
+}
+
+/// Han ideographs, kana and hangul
+pub fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF     // Hiragana, Katakana
+        | 0x3400..=0x4DBF   // CJK Extension A
+        | 0x4E00..=0x9FFF   // CJK Unified Ideographs
+        | 0xF900..=0xFAFF   // CJK Compatibility Ideographs
+        | 0x1100..=0x11FF   // Hangul Jamo
+        | 0x3130..=0x318F   // Hangul Compatibility Jamo
+        | 0xAC00..=0xD7AF   // Hangul Syllables
+        | 0x20000..=0x2A6DF // CJK Extension B
+    )
+}
+
+/// Overlapping character bigrams of a run of CJK characters (a lone character is kept)
+pub fn cjk_bigrams(run: &str) -> Vec<String> {
+    let chars: Vec<char> = run.chars().collect();
+    if chars.len() == 1 {
+        return vec![chars[0].to_string()];
+    }
+    chars.windows(2).map(|pair| pair.iter().collect()).collect()
+}
@@ -1,11 +1,17 @@
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashSet, HashMap};
 use std::sync::OnceLock;
 use dashmap::DashMap;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+use crate::multilingual::{cjk_bigrams, is_cjk, TextLanguage};
 
 // Stopword list for filtering common words
 static STOPWORDS: OnceLock<HashSet<&'static str>> = OnceLock::new();
 static TOKEN_REGEX: OnceLock<Regex> = OnceLock::new();
+static WORD_REGEX: OnceLock<Regex> = OnceLock::new();
 static URL_REGEX: OnceLock<Regex> = OnceLock::new();
 static PHRASE_DELIMITER_REGEX: OnceLock<Regex> = OnceLock::new();
 
@@ -41,6 +47,33 @@ pub enum Language {
     Html,
 }
 
+/// What tokenization does with emoji
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmojiMode {
+    /// `🚀` becomes the cue `emoji:rocket`, its GitHub shortcode
+    #[default]
+    Shortcode,
+    /// The emoji itself is the cue
+    Keep,
+    /// Emoji yield no cues
+    Drop,
+}
+
+/// Rules text is split into cues with. A project keeps the rules it was
+/// created with, so cues derived from new text and queries keep matching the
+/// cues of its existing memories.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tokenizer {
+    /// Unicode word boundaries with accent folding, hashtags and emoji
+    #[default]
+    Unicode,
+    /// Lowercased text split by the token pattern, as before `unicode`;
+    /// accented words are split at the accent and emoji are dropped
+    Ascii,
+}
+
 impl From<&str> for Language {
     fn from(s: &str) -> Self {
         match s {
@@ -203,6 +236,13 @@ fn get_token_regex() -> &'static Regex {
     })
 }
 
+/// Words of any script, for the non-English pipelines
+fn get_word_regex() -> &'static Regex {
+    WORD_REGEX.get_or_init(|| {
+        Regex::new(r"\p{L}[\p{L}\p{N}]*").unwrap()
+    })
+}
+
 fn get_url_regex() -> &'static Regex {
     URL_REGEX.get_or_init(|| {
         // Capture domain name from URLs
@@ -242,6 +282,87 @@ fn is_hash_like(token: &str) -> bool {
     has_digit && has_letter
 }
 
+/// A piece of text as tokenization sees it
+enum Segment<'a> {
+    Word(&'a str),
+    /// Word right after a `#`, without it
+    Hashtag(&'a str),
+    /// Cue for an emoji
+    Emoji(String),
+}
+
+/// Split text at Unicode word boundaries (UAX #29), which keep words with
+/// accents, apostrophes and combining marks whole and treat emoji sequences
+/// (skin tones, ZWJ families, flags) as one piece
+fn segment_text(text: &str, emoji: EmojiMode) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut after_hash = false;
+    for piece in text.split_word_bounds() {
+        if piece.chars().any(char::is_alphanumeric) {
+            if after_hash && piece.starts_with(char::is_alphabetic) {
+                segments.push(Segment::Hashtag(piece));
+            } else {
+                segments.push(Segment::Word(piece));
+            }
+        } else if let Some(cue) = emoji_cue(piece, emoji) {
+            segments.push(Segment::Emoji(cue));
+        }
+        after_hash = piece == "#";
+    }
+    segments
+}
+
+/// Cue for `piece` if it is an emoji. Symbols below U+2000 that Unicode also
+/// lists as emoji (`©`, `®`) are left to be dropped as punctuation.
+fn emoji_cue(piece: &str, mode: EmojiMode) -> Option<String> {
+    if mode == EmojiMode::Drop || piece.chars().all(|c| (c as u32) < 0x2000) {
+        return None;
+    }
+    // Text-style and emoji-style variants (with or without U+FE0F) are the same emoji
+    let bare = piece.trim_end_matches('\u{fe0f}');
+    let emoji = emojis::get(piece)
+        .or_else(|| emojis::get(bare))
+        .or_else(|| emojis::get(&format!("{}\u{fe0f}", bare)))?;
+    match mode {
+        EmojiMode::Keep => Some(emoji.as_str().to_string()),
+        _ => {
+            // Skin tones share the base emoji's shortcode
+            let base = emoji.with_skin_tone(emojis::SkinTone::Default).unwrap_or(emoji);
+            let name = match base.shortcode() {
+                Some(shortcode) => shortcode.to_string(),
+                None => base
+                    .name()
+                    .to_lowercase()
+                    .split(|c: char| !c.is_alphanumeric())
+                    .filter(|w| !w.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("_"),
+            };
+            Some(format!("emoji:{}", name))
+        }
+    }
+}
+
+fn fold_word(word: &str, fold: bool) -> String {
+    let word = word.to_lowercase().replace('\u{2019}', "'");
+    if fold {
+        fold_accents(&word)
+    } else {
+        word
+    }
+}
+
+/// Strip accents and other combining marks: "Crème Brûlée" -> "Creme Brulee"
+pub fn fold_accents(text: &str) -> String {
+    text.nfd().filter(|c| !is_combining_mark(*c)).nfc().collect()
+}
+
+fn push_unique(cues: &mut Vec<String>, cue: String) {
+    if !cues.contains(&cue) {
+        cues.push(cue);
+    }
+}
+
 pub fn normalize_text(text: &str) -> String {
     text.to_lowercase()
         .chars()
@@ -256,10 +377,15 @@ pub fn normalize_text(text: &str) -> String {
 /// 1. Split text by punctuation and stopwords
 /// 2. Extract candidate phrases (word sequences between delimiters)
 /// 3. Return meaningful multi-word phrases as underscore-joined bigrams
-fn extract_rake_phrases(text: &str, lang: Language) -> Vec<String> {
+fn extract_rake_phrases(text: &str, lang: Language, text_lang: TextLanguage, tokenizer: Tokenizer) -> Vec<String> {
     let lower = text.to_lowercase();
+    // Fold accents as the word tokens were
+    let lower = match (tokenizer, text_lang) {
+        (Tokenizer::Unicode, TextLanguage::English | TextLanguage::Auto) => fold_accents(&lower),
+        _ => lower,
+    };
     let delimiter_regex = get_phrase_delimiter_regex();
-    let stopwords = get_stopwords();
+    let stopwords = text_lang.stopwords();
     let lang_stopwords = get_language_stopwords(lang);
     
     // Split by punctuation first
@@ -286,7 +412,7 @@ fn extract_rake_phrases(text: &str, lang: Language) -> Vec<String> {
             }
             
             // Check against both global stopwords and language-specific ones
-            if stopwords.contains(clean.as_str()) || lang_stopwords.contains(clean.as_str()) || clean.len() <= 1 {
+            if stopwords.contains(clean.as_str()) || lang_stopwords.contains(clean.as_str()) || clean.chars().count() <= 1 {
                 // Stopword encountered - emit current phrase if valid
                 if current_phrase.len() >= 2 && current_phrase.len() <= 4 {
                     let phrase = current_phrase.join("_");
@@ -297,7 +423,7 @@ fn extract_rake_phrases(text: &str, lang: Language) -> Vec<String> {
                 current_phrase.clear();
             } else {
                 // Stem the word before adding to phrase
-                let stemmed = stem_word(&clean);
+                let stemmed = text_lang.stem(&clean);
                 current_phrase.push(stemmed);
             }
         }
@@ -321,36 +447,100 @@ pub fn tokenize_to_cues(text: &str) -> Vec<String> {
 }
 
 pub fn tokenize_to_cues_with_lang(text: &str, lang: Language) -> Vec<String> {
+    tokenize_to_cues_in(text, lang, TextLanguage::English)
+}
+
+/// Tokenize text written in `text_lang` (`Auto` detects it per text; code
+/// without a project language is read as English)
+pub fn tokenize_to_cues_in(text: &str, lang: Language, text_lang: TextLanguage) -> Vec<String> {
+    tokenize_to_cues_with(text, lang, text_lang, EmojiMode::default())
+}
+
+/// Tokenize text written in `text_lang`, turning emoji into cues per `emoji`
+pub fn tokenize_to_cues_with(text: &str, lang: Language, text_lang: TextLanguage, emoji: EmojiMode) -> Vec<String> {
+    tokenize_to_cues_by(text, lang, text_lang, Tokenizer::Unicode, emoji)
+}
+
+/// Tokenize text written in `text_lang` with a project's `tokenizer` rules
+/// (`emoji` only applies to `Tokenizer::Unicode`)
+pub fn tokenize_to_cues_by(text: &str, lang: Language, text_lang: TextLanguage, tokenizer: Tokenizer, emoji: EmojiMode) -> Vec<String> {
+    let text_lang = match (lang, text_lang) {
+        (Language::Default, text_lang) => text_lang.resolve(text),
+        (_, TextLanguage::Auto) => TextLanguage::English,
+        (_, text_lang) => text_lang,
+    };
+    match text_lang {
+        TextLanguage::Cjk => tokenize_cjk(text, lang, tokenizer, emoji),
+        text_lang => tokenize_words(text, lang, text_lang, tokenizer, emoji),
+    }
+}
+
+fn tokenize_words(text: &str, lang: Language, text_lang: TextLanguage, tokenizer: Tokenizer, emoji: EmojiMode) -> Vec<String> {
     // 1. Pre-sanitize (URLs, etc.)
     let sanitized = sanitize_text(text);
     
-    // 2. Normalize
-    let normalized = normalize_text(&sanitized);
-    
     let mut cues = Vec::new();
-    let stopwords = get_stopwords();
+    let stopwords = text_lang.stopwords();
     let lang_stopwords = get_language_stopwords(lang);
+    // English keeps its ASCII token pattern, so accents are folded first
+    // ("café" -> "cafe"); other languages stem accented words as written
+    let (token_regex, fold) = match text_lang {
+        TextLanguage::English | TextLanguage::Auto => (get_token_regex(), true),
+        _ => (get_word_regex(), false),
+    };
+    
+    // Skip stopwords, single chars, and hash-like tokens, then stem (lemmatization)
+    let push_tokens = |cues: &mut Vec<String>, word: &str| {
+        for token in token_regex.find_iter(word) {
+            let t = token.as_str();
+            if stopwords.contains(t) || lang_stopwords.contains(t) || t.chars().count() <= 1 || is_hash_like(t) {
+                continue;
+            }
+            let stemmed = text_lang.stem(t);
+            if !stemmed.is_empty() {
+                push_unique(cues, stemmed);
+            }
+        }
+    };
     
-    // 3. Extract individual tokens (filtered and stemmed)
-    for token in get_token_regex().find_iter(&normalized) {
-        let t = token.as_str();
+    let segments = match tokenizer {
+        Tokenizer::Unicode => segment_text(&sanitized, emoji),
+        Tokenizer::Ascii => {
+            push_tokens(&mut cues, normalize_text(&sanitized).as_str());
+            Vec::new()
+        }
+    };
+    
+    // 2. Split into words, hashtags and emoji at Unicode word boundaries
+    for segment in segments {
+        let word = match segment {
+            Segment::Emoji(cue) => {
+                push_unique(&mut cues, cue);
+                continue;
+            }
+            // Hashtags are names: kept whole and unstemmed, even when they look like ids
+            Segment::Hashtag(tag) => {
+                let tag = fold_word(tag, fold);
+                let tag: String = tag.chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+                if tag.chars().count() > 1 {
+                    push_unique(&mut cues, tag);
+                }
+                continue;
+            }
+            Segment::Word(word) => fold_word(word, fold),
+        };
         
-        // Skip stopwords, single chars, and hash-like tokens
-        if stopwords.contains(t) || lang_stopwords.contains(t) || t.len() <= 1 || is_hash_like(t) {
+        // Whole words first, so contractions ("don't") match stopwords
+        if stopwords.contains(word.as_str()) || lang_stopwords.contains(word.as_str()) {
             continue;
         }
         
-        // Stem the token (lemmatization)
-        let stemmed = stem_word(t);
-        
-        // Only add if not empty and not already present
-        if !stemmed.is_empty() && !cues.contains(&stemmed) {
-            cues.push(stemmed);
-        }
+        // 3. Extract individual tokens (filtered and stemmed)
+        push_tokens(&mut cues, word.as_str());
     }
     
     // 4. Extract quality bigrams using RAKE-style phrase detection (already stemmed internally)
-    let rake_phrases = extract_rake_phrases(&sanitized, lang);
+    let rake_phrases = extract_rake_phrases(&sanitized, lang, text_lang, tokenizer);
     for phrase in rake_phrases {
         if !cues.contains(&phrase) {
             cues.push(phrase);
@@ -359,3 +549,50 @@ pub fn tokenize_to_cues_with_lang(text: &str, lang: Language) -> Vec<String> {
     
     cues
 }
+
+/// CJK runs become character bigrams (split at particles); any other text in
+/// the input goes through the English pipeline
+fn tokenize_cjk(text: &str, lang: Language, tokenizer: Tokenizer, emoji: EmojiMode) -> Vec<String> {
+    let normalized = match tokenizer {
+        Tokenizer::Unicode => sanitize_text(text).to_lowercase(),
+        Tokenizer::Ascii => normalize_text(&sanitize_text(text)),
+    };
+    let particles = TextLanguage::Cjk.stopwords();
+
+    let mut cues: Vec<String> = Vec::new();
+    let mut other = String::new();
+    let mut run = String::new();
+    let flush = |run: &mut String, cues: &mut Vec<String>| {
+        if !run.is_empty() {
+            for bigram in cjk_bigrams(run) {
+                if !cues.contains(&bigram) {
+                    cues.push(bigram);
+                }
+            }
+            run.clear();
+        }
+    };
+
+    for c in normalized.chars() {
+        if is_cjk(c) && c.is_alphanumeric() {
+            other.push(' ');
+            let mut buf = [0u8; 4];
+            if particles.contains(&*c.encode_utf8(&mut buf)) {
+                flush(&mut run, &mut cues);
+            } else {
+                run.push(c);
+            }
+        } else {
+            flush(&mut run, &mut cues);
+            other.push(c);
+        }
+    }
+    flush(&mut run, &mut cues);
+
+    for cue in tokenize_words(&other, lang, TextLanguage::English, tokenizer, emoji) {
+        if !cues.contains(&cue) {
+            cues.push(cue);
+        }
+    }
+    cues
+}
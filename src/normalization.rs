@@ -1,31 +1,97 @@
+//! Cue normalization: the pipeline every cue goes through before it is
+//! written or queried, so both sides agree on one spelling.
+//!
+//! Steps run in order: trim, Unicode NFKC folding, lowercasing, accent
+//! stripping, number canonicalization, the project's rewrite rules and
+//! stemming. Each project configures its pipeline (`PUT
+//! /projects/:id/normalization`); it is stored in the project meta.
+
+use crate::multilingual::TextLanguage;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct RewriteRule {
     pub name: String,
     pub pattern: String,
     pub replace: String,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct NormalizationConfig {
+    #[serde(default = "default_true")]
     pub lowercase: bool,
+    #[serde(default = "default_true")]
     pub trim: bool,
+    /// Fold compatibility forms (full-width letters and digits, ligatures) with Unicode NFKC
+    #[serde(default)]
+    pub unicode_nfkc: bool,
+    /// Drop diacritics (`café` -> `cafe`)
+    #[serde(default)]
+    pub strip_accents: bool,
+    /// Write numeric values one way: `1,000` and `01000` become `1000`.
+    /// Fractions are kept as written so versions like `3.10` stay distinct.
+    #[serde(default)]
+    pub canonicalize_numbers: bool,
+    /// Stem each word of a cue (after its namespace) in the project's language
+    #[serde(default)]
+    pub stemming: bool,
+    /// Namespaces whose values are identifiers, only trimmed and lowercased
+    #[serde(default = "default_verbatim_namespaces")]
+    pub verbatim_namespaces: Vec<String>,
     #[serde(default)]
     pub rewrite_rules: Vec<RewriteRule>,
 }
 
+fn default_true() -> bool {
+    true
+}
+
+/// Sorted, as `validate` leaves them
+fn default_verbatim_namespaces() -> Vec<String> {
+    ["commit", "episode", "path", "session", "url"].iter().map(|ns| ns.to_string()).collect()
+}
+
 impl Default for NormalizationConfig {
     fn default() -> Self {
         Self {
             lowercase: true,
             trim: true,
+            unicode_nfkc: false,
+            strip_accents: false,
+            canonicalize_numbers: false,
+            stemming: false,
+            verbatim_namespaces: default_verbatim_namespaces(),
             rewrite_rules: Vec::new(),
         }
     }
 }
 
+impl NormalizationConfig {
+    /// Check the rewrite rules compile and tidy the namespaces
+    pub fn validate(&mut self) -> Result<(), String> {
+        for rule in &self.rewrite_rules {
+            if rule.name.trim().is_empty() {
+                return Err("Rewrite rules need a name".to_string());
+            }
+            Regex::new(&rule.pattern).map_err(|e| format!("Invalid pattern of rule '{}': {}", rule.name, e))?;
+        }
+        for namespace in &mut self.verbatim_namespaces {
+            *namespace = namespace.trim().trim_end_matches(':').to_lowercase();
+        }
+        self.verbatim_namespaces.retain(|ns| !ns.is_empty());
+        self.verbatim_namespaces.sort();
+        self.verbatim_namespaces.dedup();
+        Ok(())
+    }
+
+    fn is_verbatim(&self, cue: &str) -> bool {
+        cue.split_once(':')
+            .is_some_and(|(ns, _)| self.verbatim_namespaces.iter().any(|v| v.eq_ignore_ascii_case(ns)))
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct NormalizeTrace {
     pub raw: String,
@@ -33,23 +99,46 @@ pub struct NormalizeTrace {
     pub applied_rules: Vec<String>,
 }
 
-/// Normalizes a cue string based on the provided configuration.
-/// Applies trimming, lowercasing, and rewrite rules sequentially.
+/// Normalizes a cue string based on the provided configuration, stemming
+/// (when enabled) as English.
 pub fn normalize_cue(raw: &str, config: &NormalizationConfig) -> (String, NormalizeTrace) {
+    normalize_cue_in(raw, config, TextLanguage::English)
+}
+
+/// Normalizes a cue string based on the provided configuration, stemming in
+/// `language`. Built-in steps that change the cue are listed in the trace by
+/// name (`nfkc`, `strip_accents`, `numbers`, `stem`) alongside rewrite rules.
+pub fn normalize_cue_in(raw: &str, config: &NormalizationConfig, language: TextLanguage) -> (String, NormalizeTrace) {
     let mut current = raw.to_string();
     let mut applied_rules = Vec::new();
+    let verbatim = config.is_verbatim(raw.trim_start());
 
     // 1. Trim
     if config.trim {
         current = current.trim().to_string();
     }
 
-    // 2. Lowercase
+    // 2. Unicode compatibility folding
+    if config.unicode_nfkc && !verbatim {
+        apply_step(&mut current, &mut applied_rules, "nfkc", |s| s.nfkc().collect());
+    }
+
+    // 3. Lowercase
     if config.lowercase {
         current = current.to_lowercase();
     }
 
-    // 3. Rewrite Rules
+    // 4. Accents
+    if config.strip_accents && !verbatim {
+        apply_step(&mut current, &mut applied_rules, "strip_accents", crate::nl::fold_accents);
+    }
+
+    // 5. Numbers
+    if config.canonicalize_numbers && !verbatim {
+        apply_step(&mut current, &mut applied_rules, "numbers", canonical_number_value);
+    }
+
+    // 6. Rewrite Rules
     for rule in &config.rewrite_rules {
         if let Ok(re) = Regex::new(&rule.pattern) {
             if re.is_match(&current) {
@@ -62,6 +151,11 @@ pub fn normalize_cue(raw: &str, config: &NormalizationConfig) -> (String, Normal
         }
     }
 
+    // 7. Stemming, last so rewrite rules see whole words
+    if config.stemming && !config.is_verbatim(&current) {
+        apply_step(&mut current, &mut applied_rules, "stem", |s| stem_value(s, language));
+    }
+
     (
         current.clone(),
         NormalizeTrace {
@@ -72,3 +166,65 @@ pub fn normalize_cue(raw: &str, config: &NormalizationConfig) -> (String, Normal
     )
 }
 
+/// Run a built-in step, naming it in `applied` if it changed the cue
+fn apply_step(current: &mut String, applied: &mut Vec<String>, name: &str, step: impl Fn(&str) -> String) {
+    let next = step(current.as_str());
+    if next != *current {
+        *current = next;
+        applied.push(name.to_string());
+    }
+}
+
+/// The cue with its value (after the namespace, if any) rewritten without
+/// thousands separators or leading zeros when it is a number
+fn canonical_number_value(cue: &str) -> String {
+    let (prefix, value) = match cue.split_once(':') {
+        Some((namespace, value)) => (&cue[..namespace.len() + 1], value),
+        None => ("", cue),
+    };
+    let (integer, fraction) = match value.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (value, None),
+    };
+    let mut groups = integer.split(',');
+    // Separators only between groups of three digits
+    let grouped = groups.next().is_some_and(|first| (1..=3).contains(&first.len())) && groups.all(|g| g.len() == 3);
+    let digits: String = integer.chars().filter(|c| *c != ',').collect();
+    let well_formed = !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit())
+        && (grouped || !integer.contains(','))
+        && fraction.is_none_or(|f| !f.is_empty() && f.chars().all(|c| c.is_ascii_digit()));
+    if !well_formed {
+        return cue.to_string();
+    }
+    let trimmed = digits.trim_start_matches('0');
+    let integer = if trimmed.is_empty() { "0" } else { trimmed };
+    match fraction {
+        Some(fraction) => format!("{}{}.{}", prefix, integer, fraction),
+        None => format!("{}{}", prefix, integer),
+    }
+}
+
+/// Stem every word of the cue's value, keeping the namespace and separators
+fn stem_value(cue: &str, language: TextLanguage) -> String {
+    let (mut out, value) = match cue.split_once(':') {
+        Some((namespace, value)) => (format!("{}:", namespace), value),
+        None => (String::new(), cue),
+    };
+    let mut word = String::new();
+    for c in value.chars() {
+        if c.is_alphabetic() {
+            word.push(c);
+            continue;
+        }
+        if !word.is_empty() {
+            out.push_str(&language.stem(&word));
+            word.clear();
+        }
+        out.push(c);
+    }
+    if !word.is_empty() {
+        out.push_str(&language.stem(&word));
+    }
+    out
+}
@@ -0,0 +1,833 @@
+//! OpenAPI 3 description of the HTTP API, served at `GET /openapi.json`.
+//!
+//! Every route registered in `api::routes` has an entry in `OPERATIONS`, and
+//! every JSON request body a schema in `request_schemas`. `tests/openapi_test.rs`
+//! keeps them honest: it fails when a route is missing here, or when a schema's
+//! fields (and which of them are required) differ from its request struct.
+
+use serde_json::{json, Map, Value};
+
+/// Whether an operation reads the `X-Project-ID` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectHeader {
+    Required,
+    /// Falls back to all projects, or to a `project` query parameter
+    Optional,
+    /// Global endpoint, or the project is part of the path
+    Global,
+}
+
+/// Request payload of an operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Body {
+    None,
+    /// JSON body described by the named schema in `components.schemas`
+    Json(&'static str),
+    /// NDJSON as produced by `GET /projects/:id/export`
+    Ndjson,
+    /// Binary archive as produced by `GET /projects/:id/archive`
+    Binary,
+    /// YAML vocabulary as produced by `GET /lexicon/export` and `GET /aliases/export`
+    Yaml,
+    /// `multipart/form-data` with a `file` field
+    Multipart,
+}
+
+/// Media type of a successful response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Response {
+    Json,
+    EventStream,
+    Ndjson,
+    Binary,
+    Text,
+    Yaml,
+    WebSocket,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct QueryParam {
+    pub name: &'static str,
+    /// JSON schema type: `string`, `integer`, `number` or `boolean`
+    pub kind: &'static str,
+    pub description: &'static str,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Operation {
+    /// Lowercase HTTP method
+    pub method: &'static str,
+    /// Path in axum syntax (`/memories/:id`)
+    pub path: &'static str,
+    pub tag: &'static str,
+    pub summary: &'static str,
+    pub project: ProjectHeader,
+    pub query: &'static [QueryParam],
+    pub body: Body,
+    pub response: Response,
+}
+
+const fn op(method: &'static str, path: &'static str, tag: &'static str, summary: &'static str) -> Operation {
+    Operation {
+        method,
+        path,
+        tag,
+        summary,
+        project: ProjectHeader::Required,
+        query: &[],
+        body: Body::None,
+        response: Response::Json,
+    }
+}
+
+impl Operation {
+    const fn body(mut self, schema: &'static str) -> Self {
+        self.body = Body::Json(schema);
+        self
+    }
+
+    const fn raw_body(mut self, body: Body) -> Self {
+        self.body = body;
+        self
+    }
+
+    const fn project(mut self, project: ProjectHeader) -> Self {
+        self.project = project;
+        self
+    }
+
+    const fn query(mut self, query: &'static [QueryParam]) -> Self {
+        self.query = query;
+        self
+    }
+
+    const fn response(mut self, response: Response) -> Self {
+        self.response = response;
+        self
+    }
+}
+
+const fn param(name: &'static str, kind: &'static str, description: &'static str) -> QueryParam {
+    QueryParam { name, kind, description }
+}
+
+const LIMIT: QueryParam = param("limit", "integer", "Maximum number of entries returned");
+const GRAPH_PROJECT: QueryParam = param("project", "string", "Project to read when no X-Project-ID header is sent");
+
+pub const OPERATIONS: &[Operation] = &[
+    op("get", "/", "system", "Server name and version").project(ProjectHeader::Global),
+    op("get", "/openapi.json", "system", "This OpenAPI document").project(ProjectHeader::Global),
+    op("get", "/metrics", "system", "Prometheus metrics").project(ProjectHeader::Global).response(Response::Text),
+    op("get", "/debug/slow-queries", "system", "Recent recalls over the slow-query threshold, with per-stage timings").project(ProjectHeader::Optional).query(&[LIMIT]),
+    op("get", "/stats", "system", "Engine statistics (one project, or every project without a header)").project(ProjectHeader::Optional),
+    op("get", "/stats/memory-breakdown", "system", "Heap usage by component").query(&[LIMIT]),
+    op("post", "/maintenance/compact", "system", "Compact cue indexes (one project, or all without a header)").project(ProjectHeader::Optional),
+    op("post", "/maintenance/decay", "system", "Decay the salience of the project's memories").body("DecayRequest"),
+    op("post", "/maintenance/prune", "system", "Prune idle low-salience memories (dry_run lists them)").body("PruneRequest"),
+    op("get", "/jobs/status", "system", "Background job queue status").project(ProjectHeader::Optional),
+    op("get", "/jobs/failed", "system", "Jobs that failed on every retry (dead-letter list)").project(ProjectHeader::Optional),
+    op("post", "/jobs/:id/retry", "system", "Queue a failed job again").project(ProjectHeader::Global),
+    op("delete", "/jobs/:id", "system", "Cancel a queued job or discard a failed one").project(ProjectHeader::Global),
+    op("get", "/audit", "system", "Query the audit log").project(ProjectHeader::Optional).query(&[
+        param("project_id", "string", "Only entries for this project"),
+        param("memory_id", "string", "Only entries for this memory"),
+        param("action", "string", "memory_created, memory_deleted, project_created, project_deleted, project_exported or project_imported"),
+        param("actor", "string", "Only entries by this actor"),
+        param("since", "number", "Unix timestamp (seconds), inclusive"),
+        param("until", "number", "Unix timestamp (seconds), inclusive"),
+        LIMIT,
+    ]),
+    op("get", "/ws", "system", "Live event feed (WebSocket)").project(ProjectHeader::Global).response(Response::WebSocket).query(&[
+        param("project", "string", "Only events for this project"),
+        param("stats_interval_secs", "integer", "Seconds between stats events"),
+    ]),
+    op("post", "/memories", "memories", "Add a memory").body("AddMemoryRequest"),
+    op("post", "/memories/search/export", "memories", "Stream every memory matching a cue filter as NDJSON")
+        .body("MemoryExportRequest")
+        .response(Response::Ndjson),
+    op("get", "/memories/analytics", "memories", "Recall coverage and the memories no recall has returned").query(&[
+        LIMIT,
+        param("min_age_secs", "integer", "Only list memories at least this old (default 0)"),
+    ]),
+    op("get", "/memories/:id", "memories", "Get a memory"),
+    op("delete", "/memories/:id", "memories", "Delete a memory"),
+    op("patch", "/memories/:id/reinforce", "memories", "Reinforce a memory on cues").body("ReinforceRequest"),
+    op("patch", "/memories/:id/demote", "memories", "Lower a memory's salience and detach cues").body("DemoteRequest"),
+    op("put", "/memories/:id/embedding", "memories", "Set a memory's embedding").body("SetEmbeddingRequest"),
+    op("put", "/memories/:id/pin", "memories", "Pin or unpin a memory").body("PinMemoryRequest"),
+    op("get", "/memories/:id/history", "memories", "Replaced versions of a memory"),
+    op("post", "/memories/:id/rollback", "memories", "Restore an earlier version").body("RollbackRequest"),
+    op("get", "/memories/:id/links", "memories", "Links and backlinks of a memory"),
+    op("post", "/memories/:id/links", "memories", "Add a typed link").body("MemoryLinkRequest"),
+    op("delete", "/memories/:id/links", "memories", "Remove a link").body("MemoryLinkRequest"),
+    op("post", "/memories/:id/embed", "memories", "Embed a memory with the project's provider"),
+    op("get", "/sessions", "sessions", "List episode sessions"),
+    op("post", "/sessions", "sessions", "Open an episode session").body("CreateSessionRequest"),
+    op("get", "/sessions/:id/timeline", "sessions", "Memories of a session in order"),
+    op("post", "/recall", "recall", "Recall memories by cues or query text").body("RecallRequest"),
+    op("post", "/recall/stream", "recall", "Recall with results streamed as server-sent events")
+        .body("RecallRequest")
+        .response(Response::EventStream),
+    op("post", "/recall/count", "recall", "Count the memories a query matches, per cue value and per day").body("CountRequest"),
+    op("post", "/recall/grounded", "recall", "Budgeted, signed context for an LLM prompt").body("RecallGroundedRequest"),
+    op("post", "/recall/web", "recall", "Recall against a fetched web page").body("RecallWebRequest"),
+    op("post", "/answer", "recall", "Answer a question with the project's LLM from grounded recall, with citations").body("AnswerRequest"),
+    op("post", "/recall/:recall_id/feedback", "recall", "Rate the results of a recall").body("RecallFeedbackRequest"),
+    op("get", "/recall/:recall_id/citations/:citation", "recall", "Resolve a grounded recall citation to its memory"),
+    op("post", "/context/expand", "recall", "Suggest query expansions from co-occurring cues").body("ContextExpandRequest"),
+    op("get", "/cues/analytics", "recall", "Top, singleton and never-recalled cues").query(&[LIMIT]),
+    op("get", "/heatmap", "recall", "Market heatmap lifts, strongest first").query(&[LIMIT]),
+    op("put", "/heatmap", "recall", "Set market heatmap lifts (optionally decaying over a TTL)").body("HeatmapUpdateRequest"),
+    op("get", "/trending", "recall", "Cues trending in the lexicon, with velocity and baseline").query(&[
+        LIMIT,
+        param("window", "string", "Look-back window, e.g. 15m (default) or 1h (at most)"),
+    ]),
+    op("get", "/graph", "graph", "Memory/cue graph, paginated").project(ProjectHeader::Optional).query(&[
+        LIMIT,
+        param("cursor", "string", "`next_cursor` of the previous page"),
+        GRAPH_PROJECT,
+    ]),
+    op("get", "/graph/neighborhood", "graph", "Graph around one memory or cue").project(ProjectHeader::Optional).query(&[
+        param("node", "string", "Memory id or `cue:<cue>`"),
+        param("depth", "integer", "Hops to follow"),
+        LIMIT,
+        GRAPH_PROJECT,
+    ]),
+    op("get", "/projects", "projects", "List projects").project(ProjectHeader::Global),
+    op("post", "/projects", "projects", "Create a project").project(ProjectHeader::Global).body("CreateProjectRequest"),
+    op("delete", "/projects/:id", "projects", "Delete a project").project(ProjectHeader::Global),
+    op("post", "/projects/:id/unload", "projects", "Save a project and unload it from memory").project(ProjectHeader::Global),
+    op("get", "/projects/:id/snapshots", "projects", "List retained snapshot generations").project(ProjectHeader::Global),
+    op("post", "/projects/:id/restore", "projects", "Restore a snapshot generation").project(ProjectHeader::Global).body("RestoreGenerationRequest"),
+    op("post", "/projects/:id/watch-dir", "projects", "Watch a directory for ingestion").project(ProjectHeader::Global).body("SetWatchDirRequest"),
+    op("get", "/agent/config", "agent", "Agent filter defaults and running agents").project(ProjectHeader::Global),
+    op("put", "/agent/config", "agent", "Replace agent filter defaults and restart agents").project(ProjectHeader::Global).body("IngestFilterConfig"),
+    op("post", "/projects/:id/snapshot-encryption", "projects", "Snapshot encryption policy")
+        .project(ProjectHeader::Global)
+        .body("SetSnapshotEncryptionRequest"),
+    op("get", "/projects/:id/schedule", "projects", "Maintenance schedule").project(ProjectHeader::Global),
+    op("put", "/projects/:id/schedule", "projects", "Set the maintenance schedule").project(ProjectHeader::Global).body("ProjectSchedule"),
+    op("get", "/projects/:id/quota", "projects", "Quota and usage").project(ProjectHeader::Global),
+    op("put", "/projects/:id/quota", "projects", "Set the quota").project(ProjectHeader::Global).body("ProjectQuota"),
+    op("get", "/projects/:id/embeddings", "projects", "Embedding provider").project(ProjectHeader::Global),
+    op("put", "/projects/:id/embeddings", "projects", "Set the embedding provider (null = server default)")
+        .project(ProjectHeader::Global)
+        .body("EmbeddingConfig"),
+    op("get", "/projects/:id/llm", "projects", "LLM used for cue proposal").project(ProjectHeader::Global),
+    op("put", "/projects/:id/llm", "projects", "Set the LLM (null = server default)").project(ProjectHeader::Global).body("LlmConfig"),
+    op("get", "/projects/:id/rerank", "projects", "Rerank stage of recall").project(ProjectHeader::Global),
+    op("put", "/projects/:id/rerank", "projects", "Set the rerank stage").project(ProjectHeader::Global).body("RerankConfig"),
+    op("get", "/projects/:id/taxonomy", "projects", "Cue taxonomy of a project").project(ProjectHeader::Global),
+    op("put", "/projects/:id/taxonomy", "projects", "Replace the cue taxonomy").project(ProjectHeader::Global).body("Taxonomy"),
+    op("post", "/projects/:id/taxonomy/validate", "projects", "Dry-run cues against a taxonomy").project(ProjectHeader::Global).body("ValidateCuesRequest"),
+    op("get", "/projects/:id/normalization", "projects", "Cue normalization pipeline of a project").project(ProjectHeader::Global),
+    op("put", "/projects/:id/normalization", "projects", "Replace the normalization pipeline and re-normalize existing cues")
+        .project(ProjectHeader::Global)
+        .body("NormalizationConfig"),
+    op("get", "/projects/:id/acronyms", "projects", "Acronyms used to rewrite recall queries").project(ProjectHeader::Global),
+    op("post", "/projects/:id/acronyms", "projects", "Add an acronym").project(ProjectHeader::Global).body("AddAcronymRequest"),
+    op("delete", "/projects/:id/acronyms/:acronym", "projects", "Remove an acronym").project(ProjectHeader::Global),
+    op("get", "/projects/:id/language", "projects", "Text language").project(ProjectHeader::Global),
+    op("put", "/projects/:id/language", "projects", "Set the text language").project(ProjectHeader::Global).body("ProjectLanguageRequest"),
+    op("get", "/projects/:id/recall-profile", "projects", "Default recall profile").project(ProjectHeader::Global),
+    op("put", "/projects/:id/recall-profile", "projects", "Set the default recall profile")
+        .project(ProjectHeader::Global)
+        .body("ProjectRecallProfileRequest"),
+    op("get", "/projects/:id/chunking", "projects", "Chunk size limits").project(ProjectHeader::Global),
+    op("put", "/projects/:id/chunking", "projects", "Set the chunk size limits").project(ProjectHeader::Global).body("ChunkingConfig"),
+    op("post", "/projects/:id/embed", "projects", "Embed every memory of a project").project(ProjectHeader::Global).query(&[
+        param("force", "boolean", "Re-embed memories that already have a vector"),
+    ]),
+    op("get", "/projects/:id/export", "projects", "Export memories as NDJSON").project(ProjectHeader::Global).response(Response::Ndjson),
+    op("post", "/projects/:id/import", "projects", "Import NDJSON memories").project(ProjectHeader::Global).raw_body(Body::Ndjson),
+    op("get", "/projects/:id/archive", "projects", "Download a full project archive").project(ProjectHeader::Global).response(Response::Binary),
+    op("post", "/projects/:id/archive", "projects", "Restore a project archive").project(ProjectHeader::Global).raw_body(Body::Binary),
+    op("post", "/aliases", "lexicon", "Add an alias").body("AddAliasRequest"),
+    op("get", "/aliases", "lexicon", "Aliases of a cue").query(&[param("cue", "string", "Cue to look up")]),
+    op("post", "/aliases/merge", "lexicon", "Alias several cues to one").body("MergeAliasRequest"),
+    op("get", "/aliases/export", "lexicon", "Export aliases as YAML").response(Response::Yaml),
+    op("put", "/aliases/export", "lexicon", "Import an alias YAML file").raw_body(Body::Yaml).query(&[param("mode", "string", "merge (default) or replace")]),
+    op("get", "/aliases/proposed", "lexicon", "Aliases proposed by the alias job"),
+    op("post", "/aliases/:id/approve", "lexicon", "Approve a proposed alias").body("ApproveAliasRequest"),
+    op("post", "/aliases/:id/reject", "lexicon", "Reject a proposed alias"),
+    op("get", "/lexicon/inspect/:cue", "lexicon", "Lexicon entries for a token"),
+    op("delete", "/lexicon/entry/:id", "lexicon", "Delete a lexicon entry"),
+    op("get", "/lexicon/graph", "lexicon", "Lexicon token graph"),
+    op("post", "/lexicon/wire", "lexicon", "Map a token to a canonical cue").body("WireLexiconRequest"),
+    op("get", "/lexicon/export", "lexicon", "Export the lexicon as YAML").response(Response::Yaml),
+    op("put", "/lexicon/export", "lexicon", "Import a lexicon YAML file").raw_body(Body::Yaml).query(&[param("mode", "string", "merge (default) or replace")]),
+    op("post", "/lexicon/prune", "lexicon", "Prune low-quality lexicon entries").body("LexiconPruneRequest"),
+    op("get", "/lexicon/synonyms/:cue", "lexicon", "Synonyms of a cue"),
+    op("get", "/org/lexicon", "lexicon", "Organization lexicon shared by every project"),
+    op("post", "/org/lexicon/wire", "lexicon", "Map a token to a canonical cue for every project").body("WireLexiconRequest"),
+    op("delete", "/org/lexicon/entry/:id", "lexicon", "Delete an organization lexicon entry"),
+    op("get", "/org/lexicon/export", "lexicon", "Export the organization lexicon as YAML").response(Response::Yaml),
+    op("put", "/org/lexicon/export", "lexicon", "Import an organization lexicon YAML file").raw_body(Body::Yaml).query(&[param("mode", "string", "merge (default) or replace")]),
+    op("post", "/ingest/url", "ingest", "Ingest a web page (optionally crawling links), sitemap or RSS/Atom feed").body("IngestUrlRequest"),
+    op("post", "/ingest/content", "ingest", "Ingest raw content as a named file").body("IngestContentRequest"),
+    op("post", "/ingest/file", "ingest", "Ingest an uploaded file").raw_body(Body::Multipart),
+    op("post", "/ingest/preview", "ingest", "Preview chunks and cues without ingesting").body("IngestPreviewRequest"),
+    op("post", "/ingest/sessions", "ingest", "Open an ingestion session; buffered jobs wait for commit or abort"),
+    op("post", "/ingest/sessions/:id/commit", "ingest", "Close an ingestion session and run its buffered jobs"),
+    op("post", "/ingest/sessions/:id/abort", "ingest", "Close an ingestion session and drop its buffered jobs"),
+    op("get", "/ingest/feeds", "ingest", "Sitemaps and feeds ingested into the project"),
+    op("delete", "/ingest/feeds", "ingest", "Stop tracking a sitemap or feed (its pages stay)").query(&[
+        param("url", "string", "Feed URL, as posted to /ingest/url"),
+    ]),
+    op("get", "/connectors", "connectors", "External sources synced into the project"),
+    op("put", "/connectors/:id", "connectors", "Add or reconfigure a GitHub, Notion, mbox or IMAP connector").body("ConnectorRequest"),
+    op("delete", "/connectors/:id", "connectors", "Remove a connector (its documents stay)"),
+    op("post", "/connectors/:id/sync", "connectors", "Sync a connector's changed documents now").query(&[
+        param("full", "boolean", "Start over from the beginning instead of the sync cursor"),
+    ]),
+    op("post", "/backup/upload", "backup", "Upload a project snapshot to cloud storage").project(ProjectHeader::Global).body("BackupRequest"),
+    op("post", "/backup/download", "backup", "Restore a project snapshot from cloud storage").project(ProjectHeader::Global).body("BackupRequest"),
+    op("get", "/backup/list", "backup", "List cloud snapshots").project(ProjectHeader::Global),
+    op("delete", "/backup/:project_id", "backup", "Delete a cloud snapshot").project(ProjectHeader::Global),
+];
+
+fn string() -> Value {
+    json!({"type": "string"})
+}
+
+fn integer() -> Value {
+    json!({"type": "integer", "minimum": 0})
+}
+
+fn number() -> Value {
+    json!({"type": "number"})
+}
+
+fn boolean() -> Value {
+    json!({"type": "boolean"})
+}
+
+fn array(items: Value) -> Value {
+    json!({"type": "array", "items": items})
+}
+
+fn strings() -> Value {
+    array(string())
+}
+
+fn any() -> Value {
+    json!({})
+}
+
+fn one_of(values: &[&str]) -> Value {
+    json!({"type": "string", "enum": values})
+}
+
+fn nullable(mut schema: Value) -> Value {
+    if let Some(obj) = schema.as_object_mut() {
+        obj.insert("nullable".to_string(), Value::Bool(true));
+    }
+    schema
+}
+
+fn reference(name: &str) -> Value {
+    json!({"$ref": format!("#/components/schemas/{}", name)})
+}
+
+fn with_default(mut schema: Value, default: Value) -> Value {
+    if let Some(obj) = schema.as_object_mut() {
+        obj.insert("default".to_string(), default);
+    }
+    schema
+}
+
+/// Object schema; fields listed in `required` have no serde default
+fn object(fields: Vec<(&str, Value)>, required: &[&str]) -> Value {
+    let properties: Map<String, Value> = fields.into_iter().map(|(k, v)| (k.to_string(), v)).collect();
+    let mut schema = json!({"type": "object", "properties": properties});
+    if !required.is_empty() {
+        schema["required"] = json!(required);
+    }
+    schema
+}
+
+/// Schemas of every JSON request body, keyed by request type name
+pub fn request_schemas() -> Map<String, Value> {
+    let mut schemas = Map::new();
+    let mut add = |name: &str, schema: Value| {
+        schemas.insert(name.to_string(), schema);
+    };
+
+    add("AddMemoryRequest", object(vec![
+        ("content", string()),
+        ("cues", strings()),
+        ("metadata", nullable(json!({"type": "object", "additionalProperties": true}))),
+        ("disable_temporal_chunking", boolean()),
+        ("async_ingest", boolean()),
+        ("embedding", nullable(array(number()))),
+        ("pinned", boolean()),
+        ("dedup", boolean()),
+        ("session_id", nullable(string())),
+        ("chunking_key", nullable(string())),
+    ], &["content", "cues"]));
+
+    add("MetadataFilter", object(vec![
+        ("key", string()),
+        ("equals", nullable(any())),
+        ("prefix", nullable(string())),
+    ], &["key"]));
+
+    add("RecallRequest", object(vec![
+        ("cues", strings()),
+        ("query_text", nullable(string())),
+        ("limit", with_default(integer(), json!(10))),
+        ("auto_reinforce", with_default(boolean(), json!(true))),
+        ("projects", nullable(strings())),
+        ("merge", boolean()),
+        ("fusion", with_default(one_of(&["rrf", "zscore"]), json!("rrf"))),
+        ("min_intersection", nullable(integer())),
+        ("explain", boolean()),
+        ("disable_pattern_completion", boolean()),
+        ("disable_salience_bias", boolean()),
+        ("disable_systems_consolidation", boolean()),
+        ("disable_alias_expansion", with_default(boolean(), json!(true))),
+        ("depth", with_default(integer(), json!(1))),
+        ("created_after", nullable(number())),
+        ("created_before", nullable(number())),
+        ("metadata_filters", array(reference("MetadataFilter"))),
+        ("exclude_cues", strings()),
+        ("explain_level", with_default(one_of(&["basic", "full"]), json!("basic"))),
+        ("hybrid_alpha", nullable(number())),
+        ("query_vector", nullable(array(number()))),
+        ("fuzzy", boolean()),
+        ("cue_prefix", strings()),
+        ("phrase", nullable(string())),
+        ("phrase_window", integer()),
+        ("follow_links", boolean()),
+        ("link_types", strings()),
+        ("profile", nullable(one_of(&["fast", "balanced", "thorough"]))),
+        ("scan_limit", nullable(integer())),
+        ("rerank", nullable(boolean())),
+        ("rewrite_query", with_default(boolean(), json!(true))),
+        ("group_by", nullable(string())),
+        ("group_score", with_default(one_of(&["max", "sum"]), json!("max"))),
+        ("snippets", boolean()),
+        ("snippet_window", with_default(integer(), json!(60))),
+        ("max_snippets", with_default(integer(), json!(3))),
+    ], &[]));
+
+    add("CountRequest", object(vec![
+        ("cues", strings()),
+        ("query_text", nullable(string())),
+        ("min_intersection", nullable(integer())),
+        ("created_after", nullable(number())),
+        ("created_before", nullable(number())),
+        ("metadata_filters", array(reference("MetadataFilter"))),
+        ("exclude_cues", strings()),
+        ("phrase", nullable(string())),
+        ("phrase_window", integer()),
+        ("by_cue", nullable(string())),
+        ("by_day", boolean()),
+        ("limit", with_default(integer(), json!(50))),
+    ], &[]));
+
+    add("MemoryExportRequest", object(vec![
+        ("cues", strings()),
+        ("query_text", nullable(string())),
+        ("min_intersection", nullable(integer())),
+        ("created_after", nullable(number())),
+        ("created_before", nullable(number())),
+        ("metadata_filters", array(reference("MetadataFilter"))),
+        ("exclude_cues", strings()),
+        ("phrase", nullable(string())),
+        ("phrase_window", integer()),
+    ], &[]));
+
+    add("RecallGroundedRequest", object(vec![
+        ("query_text", string()),
+        ("token_budget", with_default(integer(), json!(2048))),
+        ("model", nullable(string())),
+        ("limit", with_default(integer(), json!(10))),
+        ("projects", nullable(strings())),
+        ("auto_reinforce", with_default(boolean(), json!(true))),
+        ("disable_pattern_completion", boolean()),
+        ("disable_salience_bias", boolean()),
+        ("disable_systems_consolidation", boolean()),
+        ("min_intersection", nullable(integer())),
+        ("disable_alias_expansion", with_default(boolean(), json!(true))),
+    ], &["query_text"]));
+
+    add("AnswerRequest", object(vec![
+        ("question", string()),
+        ("token_budget", with_default(integer(), json!(2048))),
+        ("limit", with_default(integer(), json!(10))),
+        ("max_tokens", with_default(integer(), json!(512))),
+        ("auto_reinforce", with_default(boolean(), json!(true))),
+    ], &["question"]));
+
+    add("RecallWebRequest", object(vec![
+        ("url", nullable(string())),
+        ("query", string()),
+        ("persist", boolean()),
+    ], &["query"]));
+
+    add("RecallFeedbackRequest", object(vec![
+        ("useful", strings()),
+        ("penalize_rest", with_default(boolean(), json!(true))),
+    ], &[]));
+
+    add("ReinforceRequest", object(vec![("cues", strings())], &["cues"]));
+
+    add("DemoteRequest", object(vec![
+        ("amount", with_default(number(), json!(0.5))),
+        ("detach_cues", strings()),
+    ], &[]));
+
+    add("SetEmbeddingRequest", object(vec![("embedding", array(number()))], &["embedding"]));
+
+    add("PinMemoryRequest", object(vec![("pinned", with_default(boolean(), json!(true)))], &[]));
+
+    add("RollbackRequest", object(vec![("version", integer())], &["version"]));
+
+    add("MemoryLinkRequest", object(vec![
+        ("type", nullable(string())),
+        ("target", string()),
+    ], &["target"]));
+
+    add("CreateSessionRequest", object(vec![("name", nullable(string()))], &[]));
+
+    add("ContextExpandRequest", object(vec![
+        ("query", string()),
+        ("limit", with_default(integer(), json!(20))),
+        ("min_score", nullable(number())),
+    ], &["query"]));
+
+    add("CreateProjectRequest", object(vec![
+        ("project_id", string()),
+        ("storage", with_default(one_of(&["memory", "disk"]), json!("memory"))),
+    ], &["project_id"]));
+
+    add("SetWatchDirRequest", object(vec![("watch_dir", string())], &["watch_dir"]));
+
+    add("IngestFilterConfig", object(vec![
+        ("include", strings()),
+        ("exclude", strings()),
+        ("max_file_size", nullable(integer())),
+    ], &[]));
+
+    add("RestoreGenerationRequest", object(vec![("generation", string())], &["generation"]));
+
+    add("SetSnapshotEncryptionRequest", object(vec![("enabled", nullable(boolean()))], &[]));
+
+    add("ProjectSchedule", object(vec![
+        ("consolidation_interval_secs", nullable(integer())),
+        ("decay_interval_secs", nullable(integer())),
+        ("decay_rate", with_default(number(), json!(0.05))),
+        ("prune_interval_secs", nullable(integer())),
+        ("prune_threshold", with_default(number(), json!(1.0))),
+        ("prune_max_idle_secs", with_default(integer(), json!(30 * 86400))),
+        ("lexicon_prune_interval_secs", nullable(integer())),
+        ("lexicon_prune_min_count", with_default(integer(), json!(1))),
+        ("lexicon_prune_max_idle_secs", with_default(integer(), json!(30 * 86400))),
+        ("snapshot_interval_secs", nullable(integer())),
+    ], &[]));
+
+    add("ProjectQuota", object(vec![
+        ("max_memories", nullable(integer())),
+        ("max_cues", nullable(integer())),
+        ("max_storage_bytes", nullable(integer())),
+    ], &[]));
+
+    add("EmbeddingConfig", nullable(object(vec![
+        ("strategy", with_default(one_of(&["none", "glove", "onnx", "ollama", "openai"]), json!("none"))),
+        ("model", string()),
+        ("url", string()),
+        ("api_key", nullable(string())),
+    ], &[])));
+
+    add("LlmConfig", nullable(object(vec![
+        ("enabled", boolean()),
+        ("provider", with_default(one_of(&["ollama", "openai", "vllm", "anthropic"]), json!("ollama"))),
+        ("model", with_default(string(), json!("mistral"))),
+        ("url", string()),
+        ("api_key", nullable(string())),
+        ("requests_per_minute", nullable(integer())),
+        ("max_concurrent", nullable(integer())),
+        ("summary_max_tokens", with_default(integer(), json!(256))),
+        ("summary_input_tokens", with_default(integer(), json!(3000))),
+    ], &[])));
+
+    add("RerankConfig", object(vec![
+        ("enabled", boolean()),
+        ("strategy", with_default(one_of(&["none", "onnx", "llm"]), json!("none"))),
+        ("model", string()),
+        ("top_n", with_default(integer(), json!(20))),
+    ], &[]));
+
+    let string_lists = || json!({"type": "object", "additionalProperties": strings()});
+    add("Taxonomy", object(vec![
+        ("allowed_keys", strings()),
+        ("allowed_values", string_lists()),
+        ("allowed_value_prefixes", string_lists()),
+        ("forbidden_patterns", strings()),
+        ("max_cue_length", nullable(integer())),
+    ], &[]));
+
+    add("RewriteRule", object(vec![
+        ("name", string()),
+        ("pattern", string()),
+        ("replace", string()),
+    ], &["name", "pattern", "replace"]));
+
+    add("NormalizationConfig", object(vec![
+        ("lowercase", with_default(boolean(), json!(true))),
+        ("trim", with_default(boolean(), json!(true))),
+        ("unicode_nfkc", boolean()),
+        ("strip_accents", boolean()),
+        ("canonicalize_numbers", boolean()),
+        ("stemming", boolean()),
+        ("verbatim_namespaces", strings()),
+        ("rewrite_rules", array(reference("RewriteRule"))),
+    ], &[]));
+
+    add("ValidateCuesRequest", object(vec![
+        ("cues", strings()),
+        ("taxonomy", nullable(reference("Taxonomy"))),
+    ], &["cues"]));
+
+    add("AddAcronymRequest", object(vec![
+        ("acronym", string()),
+        ("expansion", string()),
+    ], &["acronym", "expansion"]));
+
+    add("ProjectLanguageRequest", object(vec![
+        ("language", one_of(&["auto", "en", "de", "fr", "es", "cjk"])),
+    ], &["language"]));
+
+    add("ProjectRecallProfileRequest", object(vec![
+        ("profile", one_of(&["fast", "balanced", "thorough"])),
+    ], &["profile"]));
+
+    add("ChunkingConfig", object(vec![
+        ("max_tokens", nullable(integer())),
+        ("overlap_tokens", nullable(integer())),
+        ("min_chunk_chars", nullable(integer())),
+    ], &[]));
+
+    add("AddAliasRequest", object(vec![
+        ("from", string()),
+        ("to", string()),
+        ("weight", nullable(number())),
+    ], &["from", "to"]));
+
+    add("LexiconPruneRequest", object(vec![
+        ("dry_run", boolean()),
+        ("min_score", with_default(number(), json!(0.2))),
+        ("min_observations", with_default(number(), json!(5.0))),
+        ("max_idle_secs", with_default(integer(), json!(30 * 86400))),
+        ("decay", with_default(number(), json!(0.9))),
+    ], &[]));
+
+    add("DecayRequest", object(vec![
+        ("dry_run", boolean()),
+        ("rate", nullable(number())),
+    ], &[]));
+
+    add("PruneRequest", object(vec![
+        ("dry_run", boolean()),
+        ("threshold", nullable(number())),
+        ("max_idle_secs", nullable(integer())),
+    ], &[]));
+
+    add("HeatmapUpdateRequest", object(vec![
+        ("weights", json!({"type": "object", "additionalProperties": number()})),
+        ("ttl_seconds", nullable(integer())),
+        ("replace", boolean()),
+    ], &["weights"]));
+
+    add("ApproveAliasRequest", object(vec![
+        ("downweight", nullable(number())),
+    ], &[]));
+
+    add("MergeAliasRequest", object(vec![
+        ("cues", strings()),
+        ("to", string()),
+    ], &["cues", "to"]));
+
+    add("WireLexiconRequest", object(vec![
+        ("token", string()),
+        ("canonical", string()),
+    ], &["token", "canonical"]));
+
+    add("IngestUrlRequest", object(vec![
+        ("url", string()),
+        ("depth", integer()),
+        ("same_domain_only", with_default(boolean(), json!(true))),
+        ("feed", nullable(boolean())),
+        ("refresh_interval_secs", nullable(integer())),
+        ("chunking", reference("ChunkingConfig")),
+        ("extract_facts", boolean()),
+    ], &["url"]));
+
+    add("IngestContentRequest", object(vec![
+        ("content", string()),
+        ("filename", with_default(string(), json!("content.txt"))),
+        ("chunking", reference("ChunkingConfig")),
+        ("extract_facts", boolean()),
+    ], &["content"]));
+
+    add("IngestPreviewRequest", object(vec![
+        ("url", nullable(string())),
+        ("content", nullable(string())),
+        ("filename", with_default(string(), json!("content.txt"))),
+        ("path", nullable(string())),
+        ("chunking", reference("ChunkingConfig")),
+    ], &[]));
+
+    add("ConnectorRequest", object(vec![
+        ("kind", one_of(&["github", "notion", "mbox", "imap"])),
+        ("repo", string()),
+        ("database_id", nullable(string())),
+        ("path", string()),
+        ("api_url", string()),
+        ("username", string()),
+        ("token_env", string()),
+        ("sync_interval_secs", nullable(integer())),
+    ], &["kind"]));
+
+    add("BackupRequest", object(vec![("project_id", string())], &["project_id"]));
+
+    schemas
+}
+
+/// `/memories/:id` -> (`/memories/{id}`, [`id`])
+fn openapi_path(path: &str) -> (String, Vec<String>) {
+    let mut params = Vec::new();
+    let segments: Vec<String> = path
+        .split('/')
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(name) => {
+                params.push(name.to_string());
+                format!("{{{}}}", name)
+            }
+            None => segment.to_string(),
+        })
+        .collect();
+    (segments.join("/"), params)
+}
+
+fn operation_object(operation: &Operation, path_params: &[String]) -> Value {
+    let mut parameters: Vec<Value> = path_params
+        .iter()
+        .map(|name| json!({"name": name, "in": "path", "required": true, "schema": string()}))
+        .collect();
+    match operation.project {
+        ProjectHeader::Required => parameters.push(json!({"$ref": "#/components/parameters/ProjectId"})),
+        ProjectHeader::Optional => parameters.push(json!({"$ref": "#/components/parameters/OptionalProjectId"})),
+        ProjectHeader::Global => {}
+    }
+    for q in operation.query {
+        parameters.push(json!({
+            "name": q.name,
+            "in": "query",
+            "required": false,
+            "description": q.description,
+            "schema": {"type": q.kind},
+        }));
+    }
+
+    let success = match operation.response {
+        Response::Json => json!({"description": "OK", "content": {"application/json": {"schema": {"type": "object"}}}}),
+        Response::EventStream => json!({"description": "Server-sent events", "content": {"text/event-stream": {"schema": string()}}}),
+        Response::Ndjson => json!({"description": "One JSON memory per line", "content": {"application/x-ndjson": {"schema": string()}}}),
+        Response::Binary => json!({"description": "Archive", "content": {"application/octet-stream": {"schema": {"type": "string", "format": "binary"}}}}),
+        Response::Text => json!({"description": "Prometheus text format", "content": {"text/plain": {"schema": string()}}}),
+        Response::Yaml => json!({"description": "Editable YAML", "content": {"application/yaml": {"schema": string()}}}),
+        Response::WebSocket => json!({"description": "Switching to the WebSocket protocol"}),
+    };
+    let status = if operation.response == Response::WebSocket { "101" } else { "200" };
+
+    let mut operation_json = json!({
+        "operationId": operation_id(operation),
+        "summary": operation.summary,
+        "tags": [operation.tag],
+        "parameters": parameters,
+        "responses": {
+            status: success,
+            "default": {
+                "description": "Error",
+                "content": {"application/json": {"schema": reference("Error")}}
+            }
+        }
+    });
+
+    let body = match operation.body {
+        Body::None => None,
+        Body::Json(schema) => Some(json!({"application/json": {"schema": reference(schema)}})),
+        Body::Ndjson => Some(json!({"application/x-ndjson": {"schema": string()}})),
+        Body::Binary => Some(json!({"application/octet-stream": {"schema": {"type": "string", "format": "binary"}}})),
+        Body::Yaml => Some(json!({"application/yaml": {"schema": string()}})),
+        Body::Multipart => Some(json!({"multipart/form-data": {"schema": object(
+            vec![("file", json!({"type": "string", "format": "binary"}))],
+            &["file"],
+        )}})),
+    };
+    if let Some(content) = body {
+        operation_json["requestBody"] = json!({"required": true, "content": content});
+    }
+    operation_json
+}
+
+/// Stable, unique id such as `post_memories_id_reinforce`
+pub fn operation_id(operation: &Operation) -> String {
+    let path: Vec<String> = operation
+        .path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.trim_start_matches(':').replace(['-', '.'], "_"))
+        .collect();
+    if path.is_empty() {
+        operation.method.to_string()
+    } else {
+        format!("{}_{}", operation.method, path.join("_"))
+    }
+}
+
+/// The full OpenAPI 3.0 document
+pub fn spec() -> Value {
+    let mut paths = Map::new();
+    for operation in OPERATIONS {
+        let (path, params) = openapi_path(operation.path);
+        let item = paths.entry(path).or_insert_with(|| json!({}));
+        item[operation.method] = operation_object(operation, &params);
+    }
+
+    let mut schemas = request_schemas();
+    schemas.insert("Error".to_string(), object(vec![("error", string())], &["error"]));
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "CueMap",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Temporal-associative memory store"
+        },
+        "paths": paths,
+        "components": {
+            "schemas": schemas,
+            "parameters": {
+                "ProjectId": {
+                    "name": "X-Project-ID",
+                    "in": "header",
+                    "required": true,
+                    "schema": string()
+                },
+                "OptionalProjectId": {
+                    "name": "X-Project-ID",
+                    "in": "header",
+                    "required": false,
+                    "schema": string()
+                }
+            },
+            "securitySchemes": {
+                "ApiKey": {"type": "apiKey", "in": "header", "name": "X-API-Key"}
+            }
+        },
+        "security": [{"ApiKey": []}, {}]
+    })
+}
@@ -0,0 +1,217 @@
+//! Disk-backed memory payloads, to keep large projects' resident size down.
+//!
+//! A project created with `"storage": "disk"` keeps its cue index, cues and
+//! metadata in memory, but writes each memory's (compressed, possibly
+//! encrypted) content to an append-only log next to its snapshot. Only the
+//! most recently read payloads stay in RAM, in an LRU cache.
+//!
+//! The log is scratch space: snapshots still carry every payload, so exports,
+//! backups and archives work unchanged. Each load rebuilds the log from the
+//! snapshot, which also drops the space of overwritten and deleted payloads.
+//! Loading streams the snapshot and writes each payload to the log as it is
+//! decoded, so payloads never all sit in RAM at once. Encrypted snapshots are
+//! the exception: they are sealed as a whole and decrypted in memory first.
+
+use lru::LruCache;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Payloads kept in RAM per disk-backed project unless configured otherwise
+pub const DEFAULT_PAYLOAD_CACHE_ENTRIES: usize = 4096;
+
+/// Where a project keeps memory payloads; chosen when the project is created
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// Everything in RAM
+    #[default]
+    Memory,
+    /// Payloads in an on-disk log with a hot cache
+    Disk,
+}
+
+/// Append-only payload log with an LRU cache of recently read payloads
+pub struct PayloadStore {
+    path: PathBuf,
+    file: Mutex<File>,
+    end: AtomicU64,
+    cache: Mutex<LruCache<u64, Arc<Vec<u8>>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl PayloadStore {
+    /// Start an empty log at `path`. An existing file is unlinked first, so a
+    /// previous store of the same project keeps reading its own file until dropped.
+    pub fn create(path: impl AsRef<Path>, cache_entries: usize) -> Result<Self, String> {
+        let path = path.as_ref().to_path_buf();
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("Failed to replace payload log {:?}: {}", path, e))?;
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to create payload log {:?}: {}", path, e))?;
+        let capacity = NonZeroUsize::new(cache_entries).unwrap_or(NonZeroUsize::MIN);
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+            end: AtomicU64::new(0),
+            cache: Mutex::new(LruCache::new(capacity)),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a payload and return where it was written
+    pub fn put(&self, bytes: &[u8]) -> Result<u64, String> {
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        let offset = self.end.load(Ordering::Relaxed);
+        file.seek(SeekFrom::Start(offset))
+            .and_then(|_| file.write_all(bytes))
+            .map_err(|e| format!("Failed to write payload log {:?}: {}", self.path, e))?;
+        self.end.store(offset + bytes.len() as u64, Ordering::Relaxed);
+        Ok(offset)
+    }
+
+    /// Append a payload and return the handle that reads it back
+    pub fn put_payload(self: &Arc<Self>, bytes: &[u8]) -> Result<Payload, String> {
+        let offset = self.put(bytes)?;
+        Ok(Payload::Stored { store: self.clone(), offset, len: bytes.len() })
+    }
+
+    /// Read a payload, from the cache when it was read recently
+    pub fn get(&self, offset: u64, len: usize) -> Result<Arc<Vec<u8>>, String> {
+        if let Some(bytes) = self.cache.lock().unwrap_or_else(|e| e.into_inner()).get(&offset) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(bytes.clone());
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let bytes = Arc::new(self.read(offset, len)?);
+        self.cache.lock().unwrap_or_else(|e| e.into_inner()).put(offset, bytes.clone());
+        Ok(bytes)
+    }
+
+    /// Read a payload without touching the cache (snapshots read every payload once)
+    pub fn read(&self, offset: u64, len: usize) -> Result<Vec<u8>, String> {
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        let mut bytes = vec![0u8; len];
+        file.seek(SeekFrom::Start(offset))
+            .and_then(|_| file.read_exact(&mut bytes))
+            .map_err(|e| format!("Failed to read payload log {:?}: {}", self.path, e))?;
+        Ok(bytes)
+    }
+
+    /// Bytes written to the log, including overwritten and deleted payloads
+    pub fn file_bytes(&self) -> u64 {
+        self.end.load(Ordering::Relaxed)
+    }
+
+    pub fn stats(&self) -> serde_json::Value {
+        let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        serde_json::json!({
+            "file_bytes": self.file_bytes(),
+            "cached_payloads": cache.len(),
+            "cache_capacity": cache.cap().get(),
+            "cache_hits": self.hits.load(Ordering::Relaxed),
+            "cache_misses": self.misses.load(Ordering::Relaxed),
+        })
+    }
+}
+
+/// A memory's stored content: in RAM, or a slice of a project's payload log.
+/// Serializes as the plain bytes either way, so snapshots don't depend on the log.
+#[derive(Clone)]
+pub enum Payload {
+    Inline(Vec<u8>),
+    Stored {
+        store: Arc<PayloadStore>,
+        offset: u64,
+        len: usize,
+    },
+}
+
+impl Payload {
+    /// The stored bytes (read through the store's cache for disk payloads)
+    pub fn bytes(&self) -> Result<Cow<'_, [u8]>, String> {
+        match self {
+            Payload::Inline(bytes) => Ok(Cow::Borrowed(bytes)),
+            Payload::Stored { store, offset, len } => Ok(Cow::Owned(store.get(*offset, *len)?.as_ref().clone())),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Payload::Inline(bytes) => bytes.len(),
+            Payload::Stored { len, .. } => *len,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Heap bytes the payload holds in RAM (0 once it is on disk)
+    pub fn resident_bytes(&self) -> usize {
+        match self {
+            Payload::Inline(bytes) => bytes.capacity(),
+            Payload::Stored { .. } => 0,
+        }
+    }
+
+    pub fn is_stored(&self) -> bool {
+        matches!(self, Payload::Stored { .. })
+    }
+}
+
+impl Default for Payload {
+    fn default() -> Self {
+        Payload::Inline(Vec::new())
+    }
+}
+
+impl From<Vec<u8>> for Payload {
+    fn from(bytes: Vec<u8>) -> Self {
+        Payload::Inline(bytes)
+    }
+}
+
+impl fmt::Debug for Payload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Payload::Inline(bytes) => f.debug_tuple("Inline").field(&bytes.len()).finish(),
+            Payload::Stored { offset, len, .. } => f.debug_struct("Stored").field("offset", offset).field("len", len).finish(),
+        }
+    }
+}
+
+impl Serialize for Payload {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Payload::Inline(bytes) => bytes.serialize(serializer),
+            Payload::Stored { store, offset, len } => store
+                .read(*offset, *len)
+                .map_err(serde::ser::Error::custom)?
+                .serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Payload {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<u8>::deserialize(deserializer).map(Payload::Inline)
+    }
+}
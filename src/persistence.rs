@@ -21,8 +21,15 @@
 //! - `AZURE_STORAGE_ACCOUNT_NAME` - Storage account name
 //! - `AZURE_STORAGE_ACCOUNT_KEY` - Storage account key
 
-use crate::engine::CueMapEngine;
-use crate::structures::{Memory, OrderedSet, MemoryStats};
+use crate::engine::{CoOccurrenceSnapshot, CueMapEngine};
+use crate::crypto::{self, EncryptionKey};
+use crate::structures::{Memory, MemoryLink, OrderedSet, MemoryStats};
+use crate::vector::VectorSnapshot;
+use crate::history::HistorySnapshot;
+use crate::lexicon_quality::LexiconQualitySnapshot;
+use crate::heatmap::HeatmapSnapshot;
+use crate::access::AccessSnapshot;
+use crate::payload_store::{Payload, PayloadStore};
 use bytes::Bytes;
 use dashmap::DashMap;
 use ahash::RandomState;
@@ -35,8 +42,10 @@ use object_store::{
     ObjectStore, PutPayload,
 };
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::fs;
+use std::io::{BufReader, BufWriter, Cursor, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -50,13 +59,197 @@ struct PersistedState<T> {
     cue_index: HashMap<String, Vec<String>>, // Flattened OrderedSet
     version: u32,
     saved_at: u64,
+    /// Memory links (`Memory::links`). Trailing, so older binaries still read
+    /// the snapshot and ignore them.
+    links: HashMap<String, Vec<MemoryLink>>,
 }
 
-const PERSISTENCE_VERSION: u32 = 1;
+/// Version 1 layout, whose links live in memory metadata (bincode has no
+/// optional trailing fields)
+#[derive(Deserialize)]
+struct PersistedStateV1<T> {
+    memories: HashMap<String, Memory<T>>,
+    cue_index: HashMap<String, Vec<String>>,
+    version: u32,
+    saved_at: u64,
+}
+
+/// Memories and cue index of a loaded snapshot
+pub type LoadedMaps<T> = (DashMap<String, Memory<T>, RandomState>, DashMap<String, OrderedSet, RandomState>);
+
+/// A snapshot decoded by `read_state`
+struct LoadedState<T> {
+    memories: DashMap<String, Memory<T>, RandomState>,
+    cue_index: DashMap<String, OrderedSet, RandomState>,
+    version: u32,
+    saved_at: u64,
+}
+
+impl<T: Default> From<PersistedStateV1<T>> for PersistedState<T> {
+    fn from(v1: PersistedStateV1<T>) -> Self {
+        let mut memories = v1.memories;
+        for memory in memories.values_mut() {
+            memory.take_legacy_links();
+        }
+        Self {
+            memories,
+            cue_index: v1.cue_index,
+            version: v1.version,
+            saved_at: v1.saved_at,
+            links: HashMap::new(),
+        }
+    }
+}
+
+// v2: trailing links section
+const PERSISTENCE_VERSION: u32 = 2;
+
+/// Co-occurrence matrices of a project's three engines, saved next to the snapshot
+/// so loading does not rebuild them from every memory
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ProjectCoOccurrence {
+    pub main: CoOccurrenceSnapshot,
+    pub aliases: CoOccurrenceSnapshot,
+    pub lexicon: CoOccurrenceSnapshot,
+}
+
+/// Header for encrypted snapshot files:
+/// `[magic (8B) | key fingerprint (8B) | nonce (12B) | ciphertext]`
+const ENCRYPTED_SNAPSHOT_MAGIC: &[u8; 8] = b"CMSNAPE1";
+const ENCRYPTED_SNAPSHOT_HEADER_LEN: usize = 16;
+
+/// Outermost header of snapshot files: `[magic (8B) | SHA-256 of the rest (32B) | body]`,
+/// where the body is raw, compressed or encrypted. Files without it predate checksums.
+const CHECKSUMMED_SNAPSHOT_MAGIC: &[u8; 8] = b"CMSNAPC1";
+const CHECKSUMMED_SNAPSHOT_HEADER_LEN: usize = 40;
+
+/// Header for compressed snapshot files: `[magic (8B) | codec (1B) | compressed bincode]`.
+/// Encrypted snapshots are compressed before encryption, so the header sits inside the ciphertext.
+const COMPRESSED_SNAPSHOT_MAGIC: &[u8; 8] = b"CMSNAPZ1";
+const COMPRESSED_SNAPSHOT_HEADER_LEN: usize = 9;
+
+/// Suffixes of the snapshot files stored next to a project's `{id}.bin`
+pub const SNAPSHOT_SIDECAR_SUFFIXES: [&str; 8] = ["_aliases", "_lexicon", "_vectors", "_history", "_cooccurrence", "_lexicon_quality", "_heatmap", "_access"];
+
+/// Compression codec of snapshot files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SnapshotCodec {
+    /// Raw bincode
+    #[default]
+    None,
+    /// Zstandard at the configured level (best ratio)
+    Zstd,
+    /// LZ4 frames (fastest; the level is ignored)
+    Lz4,
+}
+
+impl SnapshotCodec {
+    fn id(self) -> u8 {
+        match self {
+            SnapshotCodec::None => 0,
+            SnapshotCodec::Zstd => 1,
+            SnapshotCodec::Lz4 => 2,
+        }
+    }
+
+    fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(SnapshotCodec::None),
+            1 => Some(SnapshotCodec::Zstd),
+            2 => Some(SnapshotCodec::Lz4),
+            _ => None,
+        }
+    }
+}
+
+/// Outcome of checking a snapshot file's checksum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SnapshotStatus {
+    Ok,
+    /// Written before snapshots carried checksums; loads, but can't be checked
+    Unverified,
+    /// Truncated, modified or unreadable
+    Corrupt,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotCheck {
+    pub path: PathBuf,
+    pub status: SnapshotStatus,
+    pub bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl SnapshotCheck {
+    fn corrupt(path: &Path, error: String) -> Self {
+        Self { path: path.to_path_buf(), status: SnapshotStatus::Corrupt, bytes: 0, error: Some(error) }
+    }
+}
+
+/// How snapshot files are compressed when written. Reading detects the codec
+/// from the file header, so files written with any setting stay loadable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SnapshotCompression {
+    pub codec: SnapshotCodec,
+    /// zstd level (1-22; 3 is zstd's default)
+    pub level: i32,
+}
+
+impl SnapshotCompression {
+    pub fn new(codec: SnapshotCodec, level: i32) -> Self {
+        Self { codec, level }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.codec != SnapshotCodec::None
+    }
+
+    /// Compress `reader` into `writer`, header first
+    pub fn compress_stream<R: Read, W: Write>(&self, reader: &mut R, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(COMPRESSED_SNAPSHOT_MAGIC)?;
+        writer.write_all(&[self.codec.id()])?;
+        match self.codec {
+            SnapshotCodec::None => {
+                std::io::copy(reader, writer)?;
+            }
+            SnapshotCodec::Zstd => zstd::stream::copy_encode(reader, &mut *writer, self.level)?,
+            SnapshotCodec::Lz4 => {
+                let mut encoder = lz4_flex::frame::FrameEncoder::new(&mut *writer);
+                std::io::copy(reader, &mut encoder)?;
+                encoder.finish().map_err(std::io::Error::other)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Compressed copy of a snapshot (unchanged when compression is off or the
+    /// data is already compressed or encrypted). A checksummed snapshot is verified
+    /// and keeps its checksum header.
+    pub fn compress(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        if PersistenceManager::is_checksummed_snapshot(data) {
+            let body = PersistenceManager::checksummed_body(data, Path::new("snapshot"))?;
+            if !self.is_enabled() || PersistenceManager::is_compressed_snapshot(body) || PersistenceManager::is_encrypted_snapshot(body) {
+                return Ok(data.to_vec());
+            }
+            return Ok(PersistenceManager::with_checksum(&self.compress(body)?));
+        }
+        if !self.is_enabled() || PersistenceManager::is_compressed_snapshot(data) || PersistenceManager::is_encrypted_snapshot(data) {
+            return Ok(data.to_vec());
+        }
+        let mut out = Vec::with_capacity(data.len() / 2);
+        self.compress_stream(&mut Cursor::new(data), &mut out)
+            .map_err(|e| format!("Failed to compress snapshot: {}", e))?;
+        Ok(out)
+    }
+}
 
 pub struct PersistenceManager {
     data_dir: PathBuf,
     snapshot_interval: Duration,
+    compression: SnapshotCompression,
 }
 
 impl PersistenceManager {
@@ -71,8 +264,14 @@ impl PersistenceManager {
         Self {
             data_dir,
             snapshot_interval: Duration::from_secs(snapshot_interval_secs),
+            compression: SnapshotCompression::default(),
         }
     }
+
+    /// Compression for snapshots written by `save_state`
+    pub fn set_snapshot_compression(&mut self, compression: SnapshotCompression) {
+        self.compression = compression;
+    }
     
     /// Save engine state to a specific path (used by multi-tenant)
     /// Save engine state to a specific path (used by multi-tenant)
@@ -81,66 +280,339 @@ impl PersistenceManager {
         path: &Path,
     ) -> Result<(), Box<dyn std::error::Error>> 
     where T: Serialize + for<'de> Deserialize<'de> + Clone + Default + Send + Sync + MemoryStats + 'static
+    {
+        Self::save_to_path_with_key(engine, path, None)
+    }
+
+    /// Save engine state, encrypting the snapshot file when a key is provided
+    pub fn save_to_path_with_key<T>(
+        engine: &CueMapEngine<T>,
+        path: &Path,
+        key: Option<&EncryptionKey>,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where T: Serialize + for<'de> Deserialize<'de> + Clone + Default + Send + Sync + MemoryStats + 'static
+    {
+        Self::save_to_path_with_options(engine, path, key, SnapshotCompression::default())
+    }
+
+    /// Save engine state, compressed with `compression` and then encrypted when a key is provided
+    pub fn save_to_path_with_options<T>(
+        engine: &CueMapEngine<T>,
+        path: &Path,
+        key: Option<&EncryptionKey>,
+        compression: SnapshotCompression,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where T: Serialize + for<'de> Deserialize<'de> + Clone + Default + Send + Sync + MemoryStats + 'static
     {
         let start = std::time::Instant::now();
         
-        let memories = engine.get_memories();
-        let cue_index = engine.get_cue_index();
-        
-        // Convert DashMaps to serializable format
-        let memories_map: HashMap<String, Memory<T>> = memories
-            .iter()
-            .map(|entry| (entry.key().clone(), entry.value().clone()))
-            .collect();
-        
-        let cue_index_map: HashMap<String, Vec<String>> = cue_index
-            .iter()
-            .map(|entry| {
-                let cue = entry.key().clone();
-                let ordered_set = entry.value();
-                let memory_ids = ordered_set.get_recent_owned(None);
-                (cue, memory_ids)
-            })
-            .collect();
-        
-        let state = PersistedState {
-            memories: memories_map,
-            cue_index: cue_index_map,
-            version: PERSISTENCE_VERSION,
-            saved_at: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
-        };
-        
-        // Serialize to bincode
-        let data = bincode::serialize(&state)?;
-        
         // Write to temp file first (atomic operation)
         let temp_path = path.with_extension("bin.tmp");
-        fs::write(&temp_path, &data)?;
+        let (memory_count, cue_count, bytes) = Self::write_snapshot_file(engine, &temp_path, key, compression)?;
         
         // Rename to final location (atomic on most filesystems)
         fs::rename(&temp_path, path)?;
         
         let duration = start.elapsed();
         info!(
-            "Saved {} memories and {} cues to {:?} in {:?} ({} bytes)",
-            state.memories.len(),
-            state.cue_index.len(),
+            "Saved {} memories and {} cues to {:?} in {:?} ({} bytes{}{})",
+            memory_count,
+            cue_count,
             path,
             duration,
-            data.len()
+            bytes,
+            if compression.is_enabled() { format!(", {:?}", compression.codec).to_lowercase() } else { String::new() },
+            if key.is_some() { ", encrypted" } else { "" }
         );
         
         Ok(())
     }
+
+    /// Write a snapshot to `path`. Plaintext snapshots are streamed straight to
+    /// disk; encrypted ones are streamed into one buffer, compressed and sealed in
+    /// a single pass. Compressed plaintext snapshots are streamed to a raw file first
+    /// (writing patches the map lengths in place), then compressed from it.
+    /// Returns (memories, cues, bytes written).
+    fn write_snapshot_file<T>(
+        engine: &CueMapEngine<T>,
+        path: &Path,
+        key: Option<&EncryptionKey>,
+        compression: SnapshotCompression,
+    ) -> Result<(usize, usize, usize), Box<dyn std::error::Error>>
+    where T: Serialize + for<'de> Deserialize<'de> + Clone + Default + Send + Sync + MemoryStats + 'static
+    {
+        match key {
+            Some(k) => {
+                let mut buffer = Cursor::new(Vec::new());
+                let (memory_count, cue_count) = Self::write_state(engine, &mut buffer)?;
+                let compressed = compression.compress(buffer.get_ref())?;
+                let data = Self::with_checksum(&Self::encrypt_snapshot(&compressed, k)?);
+                fs::write(path, &data)?;
+                Ok((memory_count, cue_count, data.len()))
+            }
+            None if compression.is_enabled() => {
+                let raw_path = path.with_extension("raw");
+                let mut writer = BufWriter::new(fs::File::create(&raw_path)?);
+                let (memory_count, cue_count) = Self::write_state(engine, &mut writer)?;
+                writer.into_inner().map_err(|e| e.into_error())?;
+
+                let mut reader = BufReader::new(fs::File::open(&raw_path)?);
+                let mut writer = BufWriter::new(fs::File::create(path)?);
+                Self::write_checksum_placeholder(&mut writer)?;
+                let result = compression.compress_stream(&mut reader, &mut writer);
+                let _ = fs::remove_file(&raw_path);
+                result?;
+                let bytes = writer.stream_position()? as usize;
+                writer.into_inner().map_err(|e| e.into_error())?;
+                Self::fill_checksum(path)?;
+                Ok((memory_count, cue_count, bytes))
+            }
+            None => {
+                let mut writer = BufWriter::new(fs::File::create(path)?);
+                Self::write_checksum_placeholder(&mut writer)?;
+                let (memory_count, cue_count) = Self::write_state(engine, &mut writer)?;
+                let bytes = writer.stream_position()? as usize;
+                writer.into_inner().map_err(|e| e.into_error())?;
+                Self::fill_checksum(path)?;
+                Ok((memory_count, cue_count, bytes))
+            }
+        }
+    }
+
+    /// Serialize an engine in the `PersistedState` bincode layout without cloning it first.
+    ///
+    /// Ids are collected up front and each entry is cloned (releasing its shard
+    /// lock) just before it is written, so concurrent writers are only ever blocked
+    /// for a single entry and the extra memory is one list of keys. Entries deleted
+    /// while the snapshot runs are skipped, so the map lengths are patched in at the end.
+    fn write_state<T, W>(
+        engine: &CueMapEngine<T>,
+        writer: &mut W,
+    ) -> Result<(usize, usize), Box<dyn std::error::Error>>
+    where
+        T: Serialize + for<'de> Deserialize<'de> + Clone + Default + Send + Sync + MemoryStats + 'static,
+        W: Write + Seek,
+    {
+        let memories = engine.get_memories();
+        let ids: Vec<String> = memories.iter().map(|entry| entry.key().clone()).collect();
+
+        let memories_len_pos = writer.stream_position()?;
+        bincode::serialize_into(&mut *writer, &0u64)?;
+        let mut memory_count = 0u64;
+        for id in &ids {
+            let memory = match memories.get(id) {
+                Some(entry) => entry.value().clone(),
+                None => continue,
+            };
+            bincode::serialize_into(&mut *writer, &(id, &memory))?;
+            memory_count += 1;
+        }
+
+        let cue_index = engine.get_cue_index();
+        let cues: Vec<String> = cue_index.iter().map(|entry| entry.key().clone()).collect();
+
+        let cues_len_pos = writer.stream_position()?;
+        bincode::serialize_into(&mut *writer, &0u64)?;
+        let mut cue_count = 0u64;
+        for cue in &cues {
+            let memory_ids = match cue_index.get(cue) {
+                Some(entry) => entry.value().get_recent_owned(None),
+                None => continue,
+            };
+            bincode::serialize_into(&mut *writer, &(cue, &memory_ids))?;
+            cue_count += 1;
+        }
+
+        let saved_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        bincode::serialize_into(&mut *writer, &PERSISTENCE_VERSION)?;
+        bincode::serialize_into(&mut *writer, &saved_at)?;
+        bincode::serialize_into(&mut *writer, &engine.links_snapshot())?;
+
+        let end = writer.stream_position()?;
+        writer.seek(SeekFrom::Start(memories_len_pos))?;
+        bincode::serialize_into(&mut *writer, &memory_count)?;
+        writer.seek(SeekFrom::Start(cues_len_pos))?;
+        bincode::serialize_into(&mut *writer, &cue_count)?;
+        writer.seek(SeekFrom::Start(end))?;
+        writer.flush()?;
+
+        Ok((memory_count as usize, cue_count as usize))
+    }
     
-    /// Load engine state from a specific path (used by multi-tenant)
+    /// Decode a snapshot body, current layout first
+    fn decode_state<T>(data: &[u8]) -> Result<PersistedState<T>, Box<dyn std::error::Error>>
+    where T: for<'de> Deserialize<'de> + Default
+    {
+        match bincode::deserialize::<PersistedState<T>>(data) {
+            Ok(state) => Ok(state),
+            Err(e) => bincode::deserialize::<PersistedStateV1<T>>(data)
+                .map(PersistedState::from)
+                .map_err(|_| e.into()),
+        }
+    }
+
     /// Load engine state from a specific path (used by multi-tenant)
     pub fn load_from_path<T>(
         path: &Path,
-    ) -> Result<(DashMap<String, Memory<T>, RandomState>, DashMap<String, OrderedSet, RandomState>), Box<dyn std::error::Error>> 
+    ) -> Result<LoadedMaps<T>, Box<dyn std::error::Error>> 
+    where T: Serialize + for<'de> Deserialize<'de> + Clone + Default + Send + Sync + MemoryStats + 'static
+    {
+        Self::load_from_path_with_keys(path, &[])
+    }
+
+    /// Save an embedding index next to its engine snapshot (`{project}_vectors.bin`)
+    pub fn save_vectors(snapshot: &VectorSnapshot, path: &Path, key: Option<&EncryptionKey>) -> Result<(), String> {
+        let mut data = bincode::serialize(snapshot)
+            .map_err(|e| format!("Failed to serialize embeddings: {}", e))?;
+        if let Some(key) = key {
+            data = Self::encrypt_snapshot(&data, key)?;
+        }
+        let data = Self::with_checksum(&data);
+        let temp_path = path.with_extension("bin.tmp");
+        fs::write(&temp_path, &data).map_err(|e| format!("Failed to write {:?}: {}", temp_path, e))?;
+        fs::rename(&temp_path, path).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+        debug!("Saved {} embeddings to {:?}", snapshot.vectors.len(), path);
+        Ok(())
+    }
+
+    pub fn load_vectors(path: &Path, keys: &[EncryptionKey]) -> Result<VectorSnapshot, String> {
+        let data = Self::read_sidecar(path, keys)?;
+        bincode::deserialize(&data).map_err(|e| format!("Invalid embeddings file {:?}: {}", path, e))
+    }
+
+    /// Save memory version history next to its engine snapshot (`{project}_history.bin`)
+    pub fn save_history(snapshot: &HistorySnapshot, path: &Path, key: Option<&EncryptionKey>) -> Result<(), String> {
+        let mut data = bincode::serialize(snapshot)
+            .map_err(|e| format!("Failed to serialize history: {}", e))?;
+        if let Some(key) = key {
+            data = Self::encrypt_snapshot(&data, key)?;
+        }
+        let data = Self::with_checksum(&data);
+        let temp_path = path.with_extension("bin.tmp");
+        fs::write(&temp_path, &data).map_err(|e| format!("Failed to write {:?}: {}", temp_path, e))?;
+        fs::rename(&temp_path, path).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+        debug!("Saved history of {} memories to {:?}", snapshot.versions.len(), path);
+        Ok(())
+    }
+
+    pub fn load_history(path: &Path, keys: &[EncryptionKey]) -> Result<HistorySnapshot, String> {
+        let data = Self::read_sidecar(path, keys)?;
+        bincode::deserialize(&data).map_err(|e| format!("Invalid history file {:?}: {}", path, e))
+    }
+
+    /// Save the co-occurrence matrices of a project's engines (`{project}_cooccurrence.bin`)
+    pub fn save_co_occurrence(snapshot: &ProjectCoOccurrence, path: &Path, key: Option<&EncryptionKey>) -> Result<(), String> {
+        let mut data = bincode::serialize(snapshot)
+            .map_err(|e| format!("Failed to serialize co-occurrence: {}", e))?;
+        if let Some(key) = key {
+            data = Self::encrypt_snapshot(&data, key)?;
+        }
+        let data = Self::with_checksum(&data);
+        let temp_path = path.with_extension("bin.tmp");
+        fs::write(&temp_path, &data).map_err(|e| format!("Failed to write {:?}: {}", temp_path, e))?;
+        fs::rename(&temp_path, path).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+        debug!("Saved co-occurrence of {} cues to {:?}", snapshot.main.neighbours.len(), path);
+        Ok(())
+    }
+
+    pub fn load_co_occurrence(path: &Path, keys: &[EncryptionKey]) -> Result<ProjectCoOccurrence, String> {
+        let data = Self::read_sidecar(path, keys)?;
+        bincode::deserialize(&data).map_err(|e| format!("Invalid co-occurrence file {:?}: {}", path, e))
+    }
+
+    /// Save the lexicon quality tallies of a project (`{project}_lexicon_quality.bin`)
+    pub fn save_lexicon_quality(snapshot: &LexiconQualitySnapshot, path: &Path, key: Option<&EncryptionKey>) -> Result<(), String> {
+        let mut data = bincode::serialize(snapshot)
+            .map_err(|e| format!("Failed to serialize lexicon quality: {}", e))?;
+        if let Some(key) = key {
+            data = Self::encrypt_snapshot(&data, key)?;
+        }
+        let data = Self::with_checksum(&data);
+        let temp_path = path.with_extension("bin.tmp");
+        fs::write(&temp_path, &data).map_err(|e| format!("Failed to write {:?}: {}", temp_path, e))?;
+        fs::rename(&temp_path, path).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+        debug!("Saved quality of {} lexicon entries to {:?}", snapshot.entries.len(), path);
+        Ok(())
+    }
+
+    pub fn load_lexicon_quality(path: &Path, keys: &[EncryptionKey]) -> Result<LexiconQualitySnapshot, String> {
+        let data = Self::read_sidecar(path, keys)?;
+        bincode::deserialize(&data).map_err(|e| format!("Invalid lexicon quality file {:?}: {}", path, e))
+    }
+
+    /// Save the market heatmap entries of a project (`{project}_heatmap.bin`)
+    pub fn save_heatmap(snapshot: &HeatmapSnapshot, path: &Path, key: Option<&EncryptionKey>) -> Result<(), String> {
+        let mut data = bincode::serialize(snapshot)
+            .map_err(|e| format!("Failed to serialize heatmap: {}", e))?;
+        if let Some(key) = key {
+            data = Self::encrypt_snapshot(&data, key)?;
+        }
+        let data = Self::with_checksum(&data);
+        let temp_path = path.with_extension("bin.tmp");
+        fs::write(&temp_path, &data).map_err(|e| format!("Failed to write {:?}: {}", temp_path, e))?;
+        fs::rename(&temp_path, path).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+        debug!("Saved {} heatmap entries to {:?}", snapshot.entries.len(), path);
+        Ok(())
+    }
+
+    pub fn load_heatmap(path: &Path, keys: &[EncryptionKey]) -> Result<HeatmapSnapshot, String> {
+        let data = Self::read_sidecar(path, keys)?;
+        bincode::deserialize(&data).map_err(|e| format!("Invalid heatmap file {:?}: {}", path, e))
+    }
+
+    /// Save the per-memory recall statistics of a project (`{project}_access.bin`)
+    pub fn save_access(snapshot: &AccessSnapshot, path: &Path, key: Option<&EncryptionKey>) -> Result<(), String> {
+        let mut data = bincode::serialize(snapshot)
+            .map_err(|e| format!("Failed to serialize access stats: {}", e))?;
+        if let Some(key) = key {
+            data = Self::encrypt_snapshot(&data, key)?;
+        }
+        let data = Self::with_checksum(&data);
+        let temp_path = path.with_extension("bin.tmp");
+        fs::write(&temp_path, &data).map_err(|e| format!("Failed to write {:?}: {}", temp_path, e))?;
+        fs::rename(&temp_path, path).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+        debug!("Saved access stats of {} memories to {:?}", snapshot.entries.len(), path);
+        Ok(())
+    }
+
+    pub fn load_access(path: &Path, keys: &[EncryptionKey]) -> Result<AccessSnapshot, String> {
+        let data = Self::read_sidecar(path, keys)?;
+        bincode::deserialize(&data).map_err(|e| format!("Invalid access stats file {:?}: {}", path, e))
+    }
+
+    /// Read a sidecar file, verifying its checksum and decrypting it
+    fn read_sidecar(path: &Path, keys: &[EncryptionKey]) -> Result<Vec<u8>, String> {
+        let data = fs::read(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+        let data = Self::verify_checksum(data, path)?;
+        if Self::is_encrypted_snapshot(&data) {
+            return Self::decrypt_snapshot(&data, keys, path);
+        }
+        Ok(data)
+    }
+
+    /// Load engine state, decrypting the snapshot with whichever of `keys` it was written with.
+    /// Plaintext snapshots are still accepted, so encryption can be enabled on existing data.
+    pub fn load_from_path_with_keys<T>(
+        path: &Path,
+        keys: &[EncryptionKey],
+    ) -> Result<LoadedMaps<T>, Box<dyn std::error::Error>>
+    where T: Serialize + for<'de> Deserialize<'de> + Clone + Default + Send + Sync + MemoryStats + 'static
+    {
+        Self::load_from_path_into_store(path, keys, None)
+    }
+
+    /// Load a snapshot one entry at a time. With a payload store, each memory's
+    /// payload is appended to it as soon as it is decoded, so only one payload is
+    /// in RAM at once. Plaintext snapshots (compressed or not) are read from disk
+    /// as a stream; encrypted ones are sealed as a whole and decrypted in memory.
+    pub fn load_from_path_into_store<T>(
+        path: &Path,
+        keys: &[EncryptionKey],
+        store: Option<&Arc<PayloadStore>>,
+    ) -> Result<LoadedMaps<T>, Box<dyn std::error::Error>>
     where T: Serialize + for<'de> Deserialize<'de> + Clone + Default + Send + Sync + MemoryStats + 'static
     {
         if !path.exists() {
@@ -149,8 +621,34 @@ impl PersistenceManager {
         
         info!("Loading state from {:?}", path);
         
-        let data = fs::read(path)?;
-        let state: PersistedState<T> = bincode::deserialize(&data)?;
+        let mut file = BufReader::new(fs::File::open(path)?);
+        Self::verify_checksum_stream(&mut file, path)?;
+        let body_start = file.stream_position()?;
+        let mut header = [0u8; COMPRESSED_SNAPSHOT_HEADER_LEN];
+        let header_len = Self::read_up_to(&mut file, &mut header)?;
+        let header = &header[..header_len];
+
+        let state = if Self::is_encrypted_snapshot_header(header) {
+            file.seek(SeekFrom::Start(body_start))?;
+            let mut data = Vec::new();
+            file.read_to_end(&mut data)?;
+            let mut data = Self::decrypt_snapshot(&data, keys, path)?;
+            if Self::is_compressed_snapshot(&data) {
+                data = Self::decompress_snapshot(&data, path)?;
+            }
+            Self::read_state::<T, _>(&mut Cursor::new(data), store)?
+        } else if Self::is_compressed_snapshot(header) {
+            let codec = SnapshotCodec::from_id(header[8])
+                .ok_or_else(|| format!("Snapshot {:?} uses an unknown compression codec", path))?;
+            match codec {
+                SnapshotCodec::None => Self::read_state::<T, _>(&mut file, store)?,
+                SnapshotCodec::Zstd => Self::read_state::<T, _>(&mut BufReader::new(zstd::stream::read::Decoder::with_buffer(file)?), store)?,
+                SnapshotCodec::Lz4 => Self::read_state::<T, _>(&mut BufReader::new(lz4_flex::frame::FrameDecoder::new(file)), store)?,
+            }
+        } else {
+            file.seek(SeekFrom::Start(body_start))?;
+            Self::read_state::<T, _>(&mut file, store)?
+        };
         
         info!(
             "Loaded {} memories and {} cues from snapshot (version: {}, saved: {})",
@@ -160,22 +658,266 @@ impl PersistenceManager {
             state.saved_at
         );
         
-        // Convert to DashMaps
+        Ok((state.memories, state.cue_index))
+    }
+
+    /// Decode the `PersistedState` layout entry by entry, straight into the engine's maps.
+    /// Snapshots that end before the links section are version 1, whose links live in metadata.
+    fn read_state<T, R: Read>(
+        reader: &mut R,
+        store: Option<&Arc<PayloadStore>>,
+    ) -> Result<LoadedState<T>, Box<dyn std::error::Error>>
+    where T: for<'de> Deserialize<'de> + Default
+    {
+        let memory_count: u64 = bincode::deserialize_from(&mut *reader)?;
         let memories = DashMap::with_hasher(RandomState::new());
-        for (id, memory) in state.memories {
+        for _ in 0..memory_count {
+            let (id, mut memory): (String, Memory<T>) = bincode::deserialize_from(&mut *reader)?;
+            if let (Some(store), Payload::Inline(bytes)) = (store, &memory.content) {
+                memory.content = store.put_payload(bytes)?;
+            }
             memories.insert(id, memory);
         }
-        
+
+        let cue_count: u64 = bincode::deserialize_from(&mut *reader)?;
         let cue_index = DashMap::with_hasher(RandomState::new());
-        for (cue, memory_ids) in state.cue_index {
+        for _ in 0..cue_count {
+            let (cue, memory_ids): (String, Vec<String>) = bincode::deserialize_from(&mut *reader)?;
             let mut ordered_set = OrderedSet::new();
             for memory_id in memory_ids {
                 ordered_set.add(memory_id);
             }
             cue_index.insert(cue, ordered_set);
         }
-        
-        Ok((memories, cue_index))
+
+        let version: u32 = bincode::deserialize_from(&mut *reader)?;
+        let saved_at: u64 = bincode::deserialize_from(&mut *reader)?;
+        match bincode::deserialize_from::<_, HashMap<String, Vec<MemoryLink>>>(&mut *reader) {
+            Ok(mut links) => {
+                for mut memory in memories.iter_mut() {
+                    if let Some(links) = links.remove(memory.key()) {
+                        memory.links = links;
+                    }
+                }
+            }
+            Err(e) if matches!(&*e, bincode::ErrorKind::Io(io) if io.kind() == std::io::ErrorKind::UnexpectedEof) => {
+                for mut memory in memories.iter_mut() {
+                    memory.take_legacy_links();
+                }
+            }
+            Err(e) => return Err(e),
+        }
+
+        Ok(LoadedState { memories, cue_index, version, saved_at })
+    }
+
+    /// Check a snapshot's checksum without reading it into memory, leaving `file`
+    /// at the start of the body. Files written before checksums existed are rewound.
+    fn verify_checksum_stream(file: &mut BufReader<fs::File>, path: &Path) -> Result<(), String> {
+        let corrupt = |e: std::io::Error| format!("Snapshot {:?} is corrupt: {}", path, e);
+        let mut header = [0u8; CHECKSUMMED_SNAPSHOT_HEADER_LEN];
+        let header_len = Self::read_up_to(file, &mut header).map_err(corrupt)?;
+        if !Self::is_checksummed_snapshot(&header[..header_len]) {
+            file.seek(SeekFrom::Start(0)).map_err(corrupt)?;
+            return Ok(());
+        }
+        if header_len < CHECKSUMMED_SNAPSHOT_HEADER_LEN {
+            return Err(format!("Snapshot {:?} is corrupt: truncated inside its header", path));
+        }
+        let mut hasher = Sha256::new();
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let n = file.read(&mut buf).map_err(corrupt)?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+        }
+        if hasher.finalize().as_slice() != &header[8..] {
+            return Err(format!("Snapshot {:?} is corrupt: checksum mismatch (file truncated or modified)", path));
+        }
+        file.seek(SeekFrom::Start(CHECKSUMMED_SNAPSHOT_HEADER_LEN as u64)).map_err(corrupt)?;
+        Ok(())
+    }
+
+    /// Fill as much of `buf` as the reader has left; returns the bytes read
+    fn read_up_to<R: Read>(reader: &mut R, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            match reader.read(&mut buf[filled..])? {
+                0 => break,
+                n => filled += n,
+            }
+        }
+        Ok(filled)
+    }
+    
+    /// Check whether snapshot bytes start with the checksum header
+    pub fn is_checksummed_snapshot(data: &[u8]) -> bool {
+        data.len() >= 8 && &data[..8] == CHECKSUMMED_SNAPSHOT_MAGIC
+    }
+
+    /// Prefix snapshot bytes with the checksum header
+    pub fn with_checksum(data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(CHECKSUMMED_SNAPSHOT_HEADER_LEN + data.len());
+        out.extend_from_slice(CHECKSUMMED_SNAPSHOT_MAGIC);
+        out.extend_from_slice(&Sha256::digest(data));
+        out.extend_from_slice(data);
+        out
+    }
+
+    /// The body of a checksummed snapshot once its checksum matches; files
+    /// written before checksums existed are returned unchanged
+    pub fn checksummed_body<'a>(data: &'a [u8], path: &Path) -> Result<&'a [u8], String> {
+        if !Self::is_checksummed_snapshot(data) {
+            return Ok(data);
+        }
+        if data.len() < CHECKSUMMED_SNAPSHOT_HEADER_LEN {
+            return Err(format!("Snapshot {:?} is corrupt: truncated inside its header", path));
+        }
+        let body = &data[CHECKSUMMED_SNAPSHOT_HEADER_LEN..];
+        if Sha256::digest(body).as_slice() != &data[8..CHECKSUMMED_SNAPSHOT_HEADER_LEN] {
+            return Err(format!("Snapshot {:?} is corrupt: checksum mismatch (file truncated or modified)", path));
+        }
+        Ok(body)
+    }
+
+    /// `checksummed_body` for an owned buffer
+    pub fn verify_checksum(mut data: Vec<u8>, path: &Path) -> Result<Vec<u8>, String> {
+        if !Self::is_checksummed_snapshot(&data) {
+            return Ok(data);
+        }
+        Self::checksummed_body(&data, path)?;
+        data.drain(..CHECKSUMMED_SNAPSHOT_HEADER_LEN);
+        Ok(data)
+    }
+
+    /// Reserve the checksum header of a streamed snapshot; `fill_checksum` completes it
+    fn write_checksum_placeholder<W: Write>(writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(CHECKSUMMED_SNAPSHOT_MAGIC)?;
+        writer.write_all(&[0u8; CHECKSUMMED_SNAPSHOT_HEADER_LEN - 8])
+    }
+
+    /// Hash a streamed snapshot's body into its reserved header and sync the file
+    fn fill_checksum(path: &Path) -> std::io::Result<()> {
+        let mut file = fs::OpenOptions::new().read(true).write(true).open(path)?;
+        file.seek(SeekFrom::Start(CHECKSUMMED_SNAPSHOT_HEADER_LEN as u64))?;
+        let mut hasher = Sha256::new();
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let n = file.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+        }
+        file.seek(SeekFrom::Start(8))?;
+        file.write_all(&hasher.finalize())?;
+        file.sync_all()
+    }
+
+    /// Check one snapshot file without deserializing it
+    pub fn verify_snapshot_file(path: &Path) -> SnapshotCheck {
+        let data = match fs::read(path) {
+            Ok(data) => data,
+            Err(e) => return SnapshotCheck::corrupt(path, format!("Failed to read {:?}: {}", path, e)),
+        };
+        if !Self::is_checksummed_snapshot(&data) {
+            return SnapshotCheck { path: path.to_path_buf(), status: SnapshotStatus::Unverified, bytes: data.len() as u64, error: None };
+        }
+        match Self::checksummed_body(&data, path) {
+            Ok(_) => SnapshotCheck { path: path.to_path_buf(), status: SnapshotStatus::Ok, bytes: data.len() as u64, error: None },
+            Err(e) => SnapshotCheck { bytes: data.len() as u64, ..SnapshotCheck::corrupt(path, e) },
+        }
+    }
+
+    /// Check every snapshot file under `dir` (sidecars and the org lexicon included), sorted by path
+    pub fn verify_snapshots_in_dir(dir: &Path) -> Vec<SnapshotCheck> {
+        let mut checks = Vec::new();
+        let mut dirs = vec![dir.to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            let Ok(entries) = fs::read_dir(&dir) else { continue };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if path.extension().is_some_and(|ext| ext == "bin") {
+                    checks.push(Self::verify_snapshot_file(&path));
+                }
+            }
+        }
+        checks.sort_by(|a, b| a.path.cmp(&b.path));
+        checks
+    }
+
+    /// Check whether raw snapshot bytes carry the encrypted snapshot header
+    pub fn is_encrypted_snapshot(data: &[u8]) -> bool {
+        data.len() >= ENCRYPTED_SNAPSHOT_HEADER_LEN && &data[..8] == ENCRYPTED_SNAPSHOT_MAGIC
+    }
+
+    /// `is_encrypted_snapshot` for the first bytes of a file being streamed
+    fn is_encrypted_snapshot_header(header: &[u8]) -> bool {
+        header.len() >= 8 && &header[..8] == ENCRYPTED_SNAPSHOT_MAGIC
+    }
+
+    /// Check whether (decrypted) snapshot bytes carry the compressed snapshot header
+    pub fn is_compressed_snapshot(data: &[u8]) -> bool {
+        data.len() >= COMPRESSED_SNAPSHOT_HEADER_LEN && &data[..8] == COMPRESSED_SNAPSHOT_MAGIC
+    }
+
+    /// Codec a snapshot was written with (`None` for raw bincode and encrypted files)
+    pub fn snapshot_codec(data: &[u8]) -> Option<SnapshotCodec> {
+        let data = if Self::is_checksummed_snapshot(data) { &data[CHECKSUMMED_SNAPSHOT_HEADER_LEN..] } else { data };
+        if Self::is_compressed_snapshot(data) {
+            SnapshotCodec::from_id(data[8])
+        } else {
+            None
+        }
+    }
+
+    /// Raw bincode of a compressed snapshot, with whichever codec its header names
+    pub fn decompress_snapshot(data: &[u8], path: &Path) -> Result<Vec<u8>, String> {
+        let codec = Self::snapshot_codec(data)
+            .ok_or_else(|| format!("Snapshot {:?} uses an unknown compression codec", path))?;
+        let body = &data[COMPRESSED_SNAPSHOT_HEADER_LEN..];
+        let decoded = match codec {
+            SnapshotCodec::None => Ok(body.to_vec()),
+            SnapshotCodec::Zstd => zstd::stream::decode_all(body),
+            SnapshotCodec::Lz4 => {
+                let mut out = Vec::with_capacity(body.len() * 2);
+                lz4_flex::frame::FrameDecoder::new(body).read_to_end(&mut out).map(|_| out)
+            }
+        };
+        decoded.map_err(|e| format!("Failed to decompress snapshot {:?} ({:?}): {}", path, codec, e))
+    }
+
+    pub(crate) fn encrypt_snapshot(data: &[u8], key: &EncryptionKey) -> Result<Vec<u8>, String> {
+        let ciphertext = crypto::encrypt(data, key)?;
+
+        let mut out = Vec::with_capacity(ENCRYPTED_SNAPSHOT_HEADER_LEN + ciphertext.len());
+        out.extend_from_slice(ENCRYPTED_SNAPSHOT_MAGIC);
+        out.extend_from_slice(&crypto::key_fingerprint(key));
+        out.extend(ciphertext);
+        Ok(out)
+    }
+
+    pub(crate) fn decrypt_snapshot(data: &[u8], keys: &[EncryptionKey], path: &Path) -> Result<Vec<u8>, String> {
+        if keys.is_empty() {
+            return Err(format!("Snapshot {:?} is encrypted but no snapshot key is configured", path));
+        }
+
+        let fingerprint = &data[8..ENCRYPTED_SNAPSHOT_HEADER_LEN];
+        let (index, key) = keys
+            .iter()
+            .enumerate()
+            .find(|(_, k)| crypto::key_fingerprint(k) == fingerprint)
+            .ok_or_else(|| format!("No configured snapshot key matches {:?} (was it rotated out?)", path))?;
+
+        if index > 0 {
+            info!("Snapshot {:?} was written with a previous key; it will be re-encrypted on next save", path);
+        }
+
+        crypto::decrypt(&data[ENCRYPTED_SNAPSHOT_HEADER_LEN..], key)
     }
     
     /// List all snapshot files in a directory (main engines only, not aliases/lexicon)
@@ -186,12 +928,8 @@ impl PersistenceManager {
             for entry in entries.flatten() {
                 let path = entry.path();
                 if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-                    // Only include main engine files, not aliases or lexicon
-                    if filename.ends_with(".bin") 
-                        && !filename.ends_with(".tmp")
-                        && !filename.ends_with("_aliases.bin")
-                        && !filename.ends_with("_lexicon.bin") 
-                    {
+                    // Only include main engine files, not aliases, lexicon or embeddings
+                    if filename.ends_with(".bin") && !Self::is_sidecar_file(filename) {
                         let project_id = filename.replace(".bin", "");
                         snapshots.push(project_id);
                     }
@@ -205,6 +943,11 @@ impl PersistenceManager {
     
     /// Delete a snapshot file
     #[allow(dead_code)]
+    /// Whether `name` is a project's sidecar snapshot file (`{id}_vectors.bin` and the like)
+    pub fn is_sidecar_file(name: &str) -> bool {
+        name.strip_suffix(".bin").is_some_and(|stem| SNAPSHOT_SIDECAR_SUFFIXES.iter().any(|s| stem.ends_with(s)))
+    }
+
     pub fn delete_snapshot(path: &Path) -> Result<(), String> {
         if path.exists() {
             fs::remove_file(path)
@@ -223,7 +966,7 @@ impl PersistenceManager {
     
     pub fn load_state<T>(
         &self,
-    ) -> Result<(DashMap<String, Memory<T>, RandomState>, DashMap<String, OrderedSet, RandomState>), Box<dyn std::error::Error>> 
+    ) -> Result<LoadedMaps<T>, Box<dyn std::error::Error>> 
     where T: Serialize + for<'de> Deserialize<'de> + Clone + Default + Send + Sync + MemoryStats + 'static
     {
         let snapshot_path = self.snapshot_path();
@@ -235,8 +978,11 @@ impl PersistenceManager {
         
         info!("Loading state from {:?}", snapshot_path);
         
-        let data = fs::read(&snapshot_path)?;
-        let state: PersistedState<T> = bincode::deserialize(&data)?;
+        let mut data = Self::verify_checksum(fs::read(&snapshot_path)?, &snapshot_path)?;
+        if Self::is_compressed_snapshot(&data) {
+            data = Self::decompress_snapshot(&data, &snapshot_path)?;
+        }
+        let state = Self::decode_state::<T>(&data)?;
         
         info!(
             "Loaded {} memories and {} cues from snapshot (version: {}, saved: {})",
@@ -248,7 +994,11 @@ impl PersistenceManager {
         
         // Convert to DashMaps
         let memories = DashMap::with_hasher(RandomState::new());
-        for (id, memory) in state.memories {
+        let mut links = state.links;
+        for (id, mut memory) in state.memories {
+            if let Some(links) = links.remove(&id) {
+                memory.links = links;
+            }
             memories.insert(id, memory);
         }
         
@@ -272,42 +1022,9 @@ impl PersistenceManager {
     {
         let start = std::time::Instant::now();
         
-        let memories = engine.get_memories();
-        let cue_index = engine.get_cue_index();
-        
-        // Convert DashMaps to serializable format
-        let memories_map: HashMap<String, Memory<T>> = memories
-            .iter()
-            .map(|entry| (entry.key().clone(), entry.value().clone()))
-            .collect();
-        
-        let cue_index_map: HashMap<String, Vec<String>> = cue_index
-            .iter()
-            .map(|entry| {
-                let cue = entry.key().clone();
-                let ordered_set = entry.value();
-                // Use owned version for serialization
-                let memory_ids = ordered_set.get_recent_owned(None);
-                (cue, memory_ids)
-            })
-            .collect();
-        
-        let state = PersistedState {
-            memories: memories_map,
-            cue_index: cue_index_map,
-            version: PERSISTENCE_VERSION,
-            saved_at: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
-        };
-        
-        // Serialize to bincode
-        let data = bincode::serialize(&state)?;
-        
         // Write to temp file first (atomic operation)
         let temp_path = self.temp_snapshot_path();
-        fs::write(&temp_path, &data)?;
+        let (memory_count, cue_count, bytes) = Self::write_snapshot_file(engine, &temp_path, None, self.compression)?;
         
         // Rename to final location (atomic on most filesystems)
         fs::rename(&temp_path, &self.snapshot_path())?;
@@ -315,10 +1032,10 @@ impl PersistenceManager {
         let duration = start.elapsed();
         info!(
             "Saved {} memories and {} cues to snapshot in {:?} ({} bytes)",
-            state.memories.len(),
-            state.cue_index.len(),
+            memory_count,
+            cue_count,
             duration,
-            data.len()
+            bytes
         );
         
         Ok(())
@@ -338,10 +1055,15 @@ impl PersistenceManager {
             loop {
                 interval.tick().await;
                 
-                if let Err(e) = persistence.save_state(&engine) {
-                    error!("Background snapshot failed: {}", e);
-                } else {
-                    info!("Background snapshot completed");
+                let persistence = persistence.clone();
+                let engine = engine.clone();
+                let result = tokio::task::spawn_blocking(move || {
+                    persistence.save_state(&engine).map_err(|e| e.to_string())
+                }).await;
+                match result {
+                    Ok(Ok(())) => info!("Background snapshot completed"),
+                    Ok(Err(e)) => error!("Background snapshot failed: {}", e),
+                    Err(e) => error!("Background snapshot task panicked: {}", e),
                 }
             }
         })
@@ -353,6 +1075,7 @@ impl Clone for PersistenceManager {
         Self {
             data_dir: self.data_dir.clone(),
             snapshot_interval: self.snapshot_interval,
+            compression: self.compression,
         }
     }
 }
@@ -427,6 +1150,8 @@ pub struct CloudBackupConfig {
     pub prefix: String,
     /// Automatically backup after each local save
     pub auto_backup: bool,
+    /// Compression applied to raw snapshot files before upload
+    pub compression: SnapshotCompression,
 }
 
 impl CloudBackupConfig {
@@ -479,6 +1204,7 @@ impl CloudBackupConfig {
             provider,
             prefix: prefix.to_string(),
             auto_backup,
+            compression: SnapshotCompression::default(),
         })
     }
 }
@@ -566,6 +1292,7 @@ impl CloudBackupManager {
         data: Bytes,
     ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
         let path = self.get_object_path(project_id, ".bin");
+        let data = self.compress_for_upload(data)?;
         let size = data.len() as u64;
 
         debug!("Uploading snapshot: {} ({} bytes)", path, size);
@@ -589,6 +1316,7 @@ impl CloudBackupManager {
 
         // Upload main engine
         let main_path = self.get_object_path(project_id, ".bin");
+        let main_data = self.compress_for_upload(main_data)?;
         let main_size = main_data.len() as u64;
         self.store.put(&main_path, PutPayload::from_bytes(main_data)).await?;
         total_size += main_size;
@@ -597,6 +1325,7 @@ impl CloudBackupManager {
         // Upload aliases engine if provided
         if let Some(data) = aliases_data {
             let path = self.get_object_path(project_id, "_aliases.bin");
+            let data = self.compress_for_upload(data)?;
             let size = data.len() as u64;
             self.store.put(&path, PutPayload::from_bytes(data)).await?;
             total_size += size;
@@ -606,6 +1335,7 @@ impl CloudBackupManager {
         // Upload lexicon engine if provided
         if let Some(data) = lexicon_data {
             let path = self.get_object_path(project_id, "_lexicon.bin");
+            let data = self.compress_for_upload(data)?;
             let size = data.len() as u64;
             self.store.put(&path, PutPayload::from_bytes(data)).await?;
             total_size += size;
@@ -616,6 +1346,15 @@ impl CloudBackupManager {
         Ok(total_size)
     }
 
+    /// Snapshot files saved uncompressed are compressed for the transfer (with a
+    /// header, so they load as-is once downloaded); compressed and encrypted ones pass through
+    fn compress_for_upload(&self, data: Bytes) -> Result<Bytes, String> {
+        if !self.config.compression.is_enabled() {
+            return Ok(data);
+        }
+        self.config.compression.compress(&data).map(Bytes::from)
+    }
+
     /// Download a project snapshot from cloud storage
     pub async fn download_snapshot(
         &self,
@@ -675,6 +1414,33 @@ impl CloudBackupManager {
         Ok((main_data, aliases_data, lexicon_data))
     }
 
+    /// Upload an auxiliary per-project object (meta file, replication manifest)
+    pub async fn put_project_object(
+        &self,
+        project_id: &str,
+        suffix: &str,
+        data: Bytes,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let path = self.get_object_path(project_id, suffix);
+        self.store.put(&path, PutPayload::from_bytes(data)).await?;
+        debug!("Uploaded {}", path);
+        Ok(())
+    }
+
+    /// Download an auxiliary per-project object, `None` if it does not exist
+    pub async fn get_project_object(
+        &self,
+        project_id: &str,
+        suffix: &str,
+    ) -> Result<Option<Bytes>, Box<dyn std::error::Error + Send + Sync>> {
+        let path = self.get_object_path(project_id, suffix);
+        match self.store.get(&path).await {
+            Ok(result) => Ok(Some(result.bytes().await?)),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     /// List all available cloud backups
     pub async fn list_snapshots(&self) -> Result<Vec<BackupEntry>, Box<dyn std::error::Error + Send + Sync>> {
         use futures::TryStreamExt;
@@ -689,11 +1455,8 @@ impl CloudBackupManager {
         while let Some(meta) = list_stream.try_next().await? {
             let path_str = meta.location.to_string();
             
-            // Only include main engine files (not aliases/lexicon)
-            if path_str.ends_with(".bin") 
-                && !path_str.ends_with("_aliases.bin") 
-                && !path_str.ends_with("_lexicon.bin") 
-            {
+            // Only include main engine files (not aliases/lexicon/embeddings)
+            if path_str.ends_with(".bin") && !PersistenceManager::is_sidecar_file(&path_str) {
                 // Extract project_id from path
                 let filename = path_str
                     .strip_prefix(&self.config.prefix)
@@ -718,12 +1481,11 @@ impl CloudBackupManager {
         &self,
         project_id: &str,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        // Delete all 3 engine files
-        let paths = vec![
-            self.get_object_path(project_id, ".bin"),
-            self.get_object_path(project_id, "_aliases.bin"),
-            self.get_object_path(project_id, "_lexicon.bin"),
-        ];
+        // Delete the snapshot and every sidecar
+        let paths: Vec<ObjectPath> = std::iter::once("")
+            .chain(SNAPSHOT_SIDECAR_SUFFIXES)
+            .map(|suffix| self.get_object_path(project_id, &format!("{}.bin", suffix)))
+            .collect();
 
         for path in paths {
             match self.store.delete(&path).await {
@@ -1,42 +1,124 @@
 use crate::structures::{MainStats, LexiconStats};
 use std::collections::HashMap;
-use crate::engine::CueMapEngine;
+use crate::engine::{CompactionReport, CueMapEngine, RecallProfile};
 use crate::normalization::NormalizationConfig;
 use crate::taxonomy::Taxonomy;
 use crate::config::{CueGenStrategy, TuningConfig, LlmConfig};
 use crate::semantic::SemanticEngine;
+use crate::multilingual::TextLanguage;
+use crate::nl::Tokenizer;
+use crate::history::MemoryHistory;
+use crate::lexicon_quality::LexiconQuality;
+use crate::heatmap::MarketHeatmap;
+use crate::scheduler::ProjectSchedule;
 use dashmap::DashMap;
 use std::sync::{Arc, RwLock};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use ahash::RandomState;
 
+/// A named episode opened with `POST /sessions`. Memories added with its id are
+/// chained in order (see `CueMapEngine::add_memory_to_session`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EpisodeSession {
+    pub id: String,
+    pub name: String,
+    pub created_at: u64,
+}
+
+/// Recalls kept for `POST /recall/:recall_id/feedback` and citation lookups, per project
+pub const RECALL_LOG_MAX: usize = 1000;
+
+/// Seconds after a recall during which feedback is accepted and citations resolve
+pub const RECALL_FEEDBACK_TTL_SECS: u64 = 3600;
+
+/// What a recall returned, kept until it expires or is evicted. Grounded
+/// recalls log only the selected memories, in citation order.
+#[derive(Debug, Clone)]
+pub struct RecallRecord {
+    pub memory_ids: Vec<String>,
+    pub cues: Vec<String>,
+    pub created_at: u64,
+    /// Feedback has been given; a recall can be rated once
+    pub rated: bool,
+}
+
+/// Cues of an alias entry. Only active aliases get the `from:`/`to:` cues
+/// recall expansion looks up; the `alias_id:` cue keeps the alias job from
+/// proposing a reviewed pair again.
+pub fn alias_cues(id: &str, status: &str, reason: &str, from: &str, to: &str) -> Vec<String> {
+    let mut cues = vec![
+        "type:alias".to_string(),
+        format!("status:{}", status),
+        format!("reason:{}", reason),
+        format!("alias_id:{}", id),
+    ];
+    if status == "active" {
+        cues.push(format!("from:{}", from));
+        cues.push(format!("to:{}", to));
+    }
+    cues
+}
+
+fn recall_expired(record: &RecallRecord) -> bool {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    now.saturating_sub(record.created_at) >= RECALL_FEEDBACK_TTL_SECS
+}
+
 pub struct ProjectContext {
     pub main: CueMapEngine<MainStats>,
     pub aliases: CueMapEngine<MainStats>,
     pub lexicon: CueMapEngine<LexiconStats>,
     pub query_cache: DashMap<String, Vec<String>, RandomState>,
-    pub normalization: NormalizationConfig,
-    pub taxonomy: Taxonomy,
+    /// Cue normalization pipeline applied at ingest and query time (persisted in the project meta)
+    pub normalization: RwLock<Arc<NormalizationConfig>>,
+    /// Cue rules applied on ingest and to resolved query cues (persisted in the project meta)
+    pub taxonomy: RwLock<Taxonomy>,
     pub cuegen_strategy: CueGenStrategy,
     pub semantic_engine: SemanticEngine,
     pub last_activity: AtomicU64,
     // Shared Context (holds top 10k cues)
-    pub market_heatmap: Arc<RwLock<HashMap<String, f32>>>,
+    pub market_heatmap: Arc<RwLock<MarketHeatmap>>,
     pub tuning: Arc<TuningConfig>,
     pub llm_config: Arc<LlmConfig>,
+    /// Natural language of the project's text (tokenizer, stemmer, stopwords)
+    pub text_language: RwLock<TextLanguage>,
+    /// Rules text is split into cues with (fixed when the project is created)
+    pub tokenizer: RwLock<Tokenizer>,
+    /// Open episodes by id (persisted in the project meta)
+    pub sessions: DashMap<String, EpisodeSession, RandomState>,
+    /// Recall path used when a request doesn't pick one
+    pub recall_profile: RwLock<RecallProfile>,
+    /// The project's own maintenance schedule (persisted in the project meta;
+    /// None follows the server's `[schedule]`)
+    pub schedule: RwLock<Option<ProjectSchedule>>,
+    /// Recent recalls awaiting feedback, by recall id (in memory only)
+    pub recall_log: DashMap<String, RecallRecord, RandomState>,
+    /// Acronym → expansion, used to rewrite recall queries (persisted in the project meta)
+    pub acronyms: DashMap<String, String, RandomState>,
+    /// Hit and false-expansion tallies by lexicon entry id (see `crate::lexicon_quality`)
+    pub lexicon_quality: DashMap<String, LexiconQuality, RandomState>,
+    /// Organization lexicon shared by every project of a server, consulted for
+    /// tokens the project's own lexicon doesn't know (None outside a server)
+    pub org_lexicon: Option<Arc<CueMapEngine<LexiconStats>>>,
 }
 
 impl ProjectContext {
     pub fn new(normalization: NormalizationConfig, taxonomy: Taxonomy, cuegen_strategy: CueGenStrategy, semantic_engine: SemanticEngine, tuning: Arc<TuningConfig>, llm_config: Arc<LlmConfig>) -> Self {
+        let mut main = CueMapEngine::with_tuning(tuning.as_ref().clone());
+        main.set_history(MemoryHistory::new(tuning.max_memory_versions));
         Self {
-            main: CueMapEngine::with_tuning(tuning.as_ref().clone()),
+            main,
             aliases: CueMapEngine::with_tuning(tuning.as_ref().clone()),
             lexicon: CueMapEngine::with_tuning(tuning.as_ref().clone()),
             query_cache: DashMap::with_hasher(RandomState::new()),
-            normalization,
-            taxonomy,
+            normalization: RwLock::new(Arc::new(normalization)),
+            taxonomy: RwLock::new(taxonomy),
             cuegen_strategy,
             semantic_engine,
             last_activity: AtomicU64::new(
@@ -45,10 +127,80 @@ impl ProjectContext {
                     .unwrap()
                     .as_secs()
             ),
-            market_heatmap: Arc::new(RwLock::new(HashMap::new())),
+            market_heatmap: Arc::new(RwLock::new(MarketHeatmap::default())),
             tuning,
             llm_config,
+            text_language: RwLock::new(TextLanguage::English),
+            tokenizer: RwLock::new(Tokenizer::default()),
+            sessions: DashMap::with_hasher(RandomState::new()),
+            recall_profile: RwLock::new(RecallProfile::default()),
+            schedule: RwLock::new(None),
+            recall_log: DashMap::with_hasher(RandomState::new()),
+            acronyms: DashMap::with_hasher(RandomState::new()),
+            lexicon_quality: DashMap::with_hasher(RandomState::new()),
+            org_lexicon: None,
+        }
+    }
+
+    pub fn text_language(&self) -> TextLanguage {
+        self.text_language.read().map(|l| *l).unwrap_or_default()
+    }
+
+    pub fn tokenizer(&self) -> Tokenizer {
+        self.tokenizer.read().map(|t| *t).unwrap_or_default()
+    }
+
+    pub fn normalization(&self) -> Arc<NormalizationConfig> {
+        self.normalization.read().map(|n| n.clone()).unwrap_or_default()
+    }
+
+    /// Normalize a cue with the project's pipeline and language, the same way
+    /// for cues written and cues queried
+    pub fn normalize_cue(&self, cue: &str) -> String {
+        crate::normalization::normalize_cue_in(cue, &self.normalization(), self.text_language()).0
+    }
+
+    /// Re-normalize the cues of every memory with the current pipeline,
+    /// replacing the cues it changes. Returns the number of memories updated.
+    pub fn renormalize_memories(&self) -> usize {
+        let ids: Vec<String> = self.main.get_memories().iter().map(|m| m.key().clone()).collect();
+        let mut updated = 0;
+        for id in ids {
+            let Some(cues) = self.main.get_memories().get(&id).map(|m| m.cues.clone()) else {
+                continue;
+            };
+            let (old, new): (Vec<String>, Vec<String>) = cues
+                .into_iter()
+                .filter_map(|cue| {
+                    let normalized = self.normalize_cue(&cue);
+                    (normalized != cue && !normalized.is_empty()).then_some((cue, normalized))
+                })
+                .unzip();
+            if old.is_empty() {
+                continue;
+            }
+            self.main.detach_cues(&id, &old);
+            self.main.attach_cues(&id, new);
+            updated += 1;
         }
+        self.query_cache.clear();
+        updated
+    }
+
+    /// Check cues against the project's taxonomy
+    pub fn validate_cues(&self, cues: Vec<String>) -> crate::taxonomy::ValidationReport {
+        let taxonomy = self.taxonomy.read().unwrap_or_else(|e| e.into_inner());
+        crate::taxonomy::validate_cues(cues, &taxonomy)
+    }
+
+    /// Tokenize text in the project's language
+    pub fn tokenize(&self, text: &str) -> Vec<String> {
+        self.tokenize_as(text, crate::nl::Language::Default)
+    }
+
+    /// Tokenize text in the project's language, as code in `lang`
+    pub fn tokenize_as(&self, text: &str, lang: crate::nl::Language) -> Vec<String> {
+        crate::nl::tokenize_to_cues_by(text, lang, self.text_language(), self.tokenizer(), self.tuning.emoji_mode)
     }
     
     pub fn touch(&self) {
@@ -61,6 +213,66 @@ impl ProjectContext {
         );
     }
     
+    /// Remember what a recall returned so feedback can refer to it, and count
+    /// the recall in the memories' access stats. Returns the recall id.
+    pub fn log_recall(&self, memory_ids: Vec<String>, cues: Vec<String>) -> String {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        if self.recall_log.len() >= RECALL_LOG_MAX {
+            self.recall_log.retain(|_, r| now.saturating_sub(r.created_at) < RECALL_FEEDBACK_TTL_SECS);
+        }
+        while self.recall_log.len() >= RECALL_LOG_MAX {
+            let oldest = self.recall_log
+                .iter()
+                .min_by_key(|r| r.created_at)
+                .map(|r| r.key().clone());
+            match oldest {
+                Some(id) => { self.recall_log.remove(&id); }
+                None => break,
+            }
+        }
+        self.main.record_recalled(&memory_ids);
+        let recall_id = uuid::Uuid::new_v4().to_string();
+        self.recall_log.insert(recall_id.clone(), RecallRecord { memory_ids, cues, created_at: now, rated: false });
+        recall_id
+    }
+
+    /// Take a logged recall for feedback; None when unknown, expired or already answered
+    pub fn take_recall(&self, recall_id: &str) -> Option<RecallRecord> {
+        let mut record = self.recall_log.get_mut(recall_id)?;
+        if record.rated || recall_expired(&record) {
+            return None;
+        }
+        record.rated = true;
+        Some(record.clone())
+    }
+
+    /// Look up a logged recall (e.g. to resolve a citation); None when unknown or expired
+    pub fn get_recall(&self, recall_id: &str) -> Option<RecallRecord> {
+        let record = self.recall_log.get(recall_id)?;
+        (!recall_expired(&record)).then(|| record.clone())
+    }
+
+    /// Add the acronyms `text` defines to the table. Existing entries are kept,
+    /// so manual ones win. Returns how many were added.
+    pub fn learn_acronyms(&self, text: &str) -> usize {
+        let mut added = 0;
+        for (acronym, expansion) in crate::query_rewrite::extract_acronyms(text) {
+            if let dashmap::mapref::entry::Entry::Vacant(entry) = self.acronyms.entry(acronym) {
+                entry.insert(expansion);
+                added += 1;
+            }
+        }
+        added
+    }
+
+    /// The acronym table, sorted
+    pub fn acronym_table(&self) -> std::collections::BTreeMap<String, String> {
+        self.acronyms.iter().map(|e| (e.key().clone(), e.value().clone())).collect()
+    }
+
     pub fn get_last_activity(&self) -> u64 {
         self.last_activity.load(Ordering::Relaxed)
     }
@@ -73,6 +285,20 @@ impl ProjectContext {
     pub fn total_memories(&self) -> usize {
         self.main.total_memories()
     }
+
+    /// Combined write counter of all three engines; changes whenever the project does
+    pub fn generation(&self) -> u64 {
+        self.main.generation() + self.aliases.generation() + self.lexicon.generation()
+    }
+
+    /// Compact the inverted indexes of all three engines (main, aliases, lexicon)
+    pub fn compact_indexes(&self) -> HashMap<String, CompactionReport> {
+        let mut reports = HashMap::new();
+        reports.insert("main".to_string(), self.main.compact_index());
+        reports.insert("aliases".to_string(), self.aliases.compact_index());
+        reports.insert("lexicon".to_string(), self.lexicon.compact_index());
+        reports
+    }
     
     /// Resolves cues from text using the Lexicon.
     /// Returns (resolved_cues, lexicon_memory_ids) - the memory IDs can be used for async reinforcement.
@@ -92,7 +318,7 @@ impl ProjectContext {
         
         // Tokenize first - we need tokens for return value regardless of cache
         let t_tok = Instant::now();
-        let tokens = crate::nl::tokenize_to_cues_with_lang(text, lang);
+        let tokens = self.tokenize_as(text, lang);
         let tok_ms = t_tok.elapsed().as_secs_f64() * 1000.0;
 
         if tokens.is_empty() {
@@ -121,7 +347,7 @@ impl ProjectContext {
         if skip_lexicon {
             // Skip lexicon - just normalize the tokens directly
             for token in &tokens {
-                let (normalized, _) = crate::normalization::normalize_cue(token, &self.normalization);
+                let normalized = self.normalize_cue(token);
                 if !canonical_cues.contains(&normalized) {
                     canonical_cues.push(normalized);
                 }
@@ -132,16 +358,35 @@ impl ProjectContext {
             
             for result in lexicon_results {
                 // result.content is the canonical cue
-                let (normalized, _) = crate::normalization::normalize_cue(&result.content, &self.normalization);
+                let normalized = self.normalize_cue(&result.content);
                 canonical_cues.push(normalized);
                 lexicon_memory_ids.push(result.memory_id.clone());
             }
+
+            // Organization vocabulary fills in tokens the project hasn't learned;
+            // its entries are never reinforced from a project's recalls
+            if let Some(org_lexicon) = &self.org_lexicon {
+                let unknown: Vec<String> = tokens
+                    .iter()
+                    .filter(|t| self.lexicon.get_cue_frequency(t) == 0)
+                    .cloned()
+                    .collect();
+                if !unknown.is_empty() {
+                    for result in org_lexicon.recall_fast(unknown, 64) {
+                        let normalized = self.normalize_cue(&result.content);
+                        if !canonical_cues.contains(&normalized) {
+                            canonical_cues.push(normalized);
+                        }
+                    }
+                }
+            }
             
             // FALLBACK: If Lexicon returned nothing, use raw tokens directly
             // This ensures queries for terms not trained in Lexicon still work
-            if canonical_cues.is_empty() {
-                for token in &tokens {
-                    let (normalized, _) = crate::normalization::normalize_cue(token, &self.normalization);
+            if lexicon_memory_ids.is_empty() {
+                let org_known = |t: &String| self.org_lexicon.as_ref().is_some_and(|org| org.get_cue_frequency(t) > 0);
+                for token in tokens.iter().filter(|t| !org_known(t)) {
+                    let normalized = self.normalize_cue(token);
                     if !canonical_cues.contains(&normalized) {
                         canonical_cues.push(normalized);
                     }
@@ -152,7 +397,7 @@ impl ProjectContext {
         
         // Validate list
         let t_val = Instant::now();
-        let report = crate::taxonomy::validate_cues(canonical_cues, &self.taxonomy);
+        let report = self.validate_cues(canonical_cues);
         let accepted = report.accepted;
         let val_ms = t_val.elapsed().as_secs_f64() * 1000.0;
         
@@ -174,6 +419,69 @@ impl ProjectContext {
         (accepted, lexicon_memory_ids, tokens)
     }
     
+    /// Aliases proposed by the `propose_aliases` job and not reviewed yet, newest first
+    pub fn proposed_aliases(&self) -> Vec<Value> {
+        let ids = self
+            .aliases
+            .get_cue_index()
+            .get("status:proposed")
+            .map(|set| set.get_recent_owned(None))
+            .unwrap_or_default();
+        ids.iter().filter_map(|id| self.alias(id)).collect()
+    }
+
+    /// An alias entry with its `id`
+    pub fn alias(&self, id: &str) -> Option<Value> {
+        let memory = self.aliases.get_memory(id)?;
+        let content = memory.access_content(self.aliases.get_master_key().as_deref()).ok()?;
+        let mut data: Value = serde_json::from_str(&content).ok()?;
+        data.as_object_mut()?.insert("id".to_string(), Value::from(id));
+        Some(data)
+    }
+
+    /// Approve a proposed alias: recalls of `from` then also look up `to`, at
+    /// `downweight` (default: the proposal's overlap score)
+    pub fn approve_alias(&self, id: &str, downweight: Option<f64>) -> Result<Value, String> {
+        if let Some(weight) = downweight {
+            if !(weight > 0.0 && weight <= 1.0) {
+                return Err("downweight must be in (0, 1]".to_string());
+            }
+        }
+        self.review_alias(id, "active", downweight)
+    }
+
+    /// Reject a proposed alias. The entry stays as a tombstone under its
+    /// `alias_id:` cue, so the job doesn't propose the same pair again.
+    pub fn reject_alias(&self, id: &str) -> Result<Value, String> {
+        self.review_alias(id, "rejected", None)
+    }
+
+    fn review_alias(&self, id: &str, status: &str, downweight: Option<f64>) -> Result<Value, String> {
+        let mut data = self.alias(id).ok_or_else(|| format!("Alias '{}' not found", id))?;
+        let current = data.get("status").and_then(|v| v.as_str()).unwrap_or("active");
+        if current != "proposed" {
+            return Err(format!("Alias '{}' is {}, not proposed", id, current));
+        }
+        let from = data.get("from").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        let to = data.get("to").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        let reason = data.get("reason").and_then(|v| v.as_str()).unwrap_or("overlap_analysis").to_string();
+
+        data["status"] = Value::from(status);
+        if let Some(weight) = downweight {
+            data["downweight"] = Value::from(weight);
+        }
+        data["reviewed_at"] = Value::from(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs());
+        if let Some(object) = data.as_object_mut() {
+            object.remove("id");
+        }
+
+        let cues = alias_cues(id, status, &reason, &from, &to);
+        self.aliases.upsert_memory_with_id(id.to_string(), data.to_string(), cues, None, Some(MainStats::default()), false, true);
+
+        data["id"] = Value::from(id);
+        Ok(data)
+    }
+
     pub fn expand_query_cues(&self, cues: Vec<String>, original_tokens: &[String]) -> Vec<(String, f64)> {
         let mut expanded: Vec<(String, f64)> = Vec::new();
         
@@ -199,6 +507,11 @@ impl ProjectContext {
             for alias in aliases {
                 // Parse alias content to get target cue and weight
                 if let Ok(data) = serde_json::from_str::<Value>(&alias.content) {
+                     // Proposed and rejected aliases never expand
+                     if data.get("status").and_then(|v| v.as_str()).is_some_and(|status| status != "active") {
+                         continue;
+                     }
+
                      // STRICT FILTER: Check if 'from' matches the current cue exactly
                      if let Some(from_val) = data.get("from").and_then(|v| v.as_str()) {
                          if from_val != cue {
@@ -0,0 +1,237 @@
+//! Opt-in log of recall queries, for replay (`cuemap replay`).
+//!
+//! With `[metrics] query_log` on, each single-project `POST /recall` appends
+//! one JSON line to `recall_queries.jsonl` in the data directory: the project,
+//! the query's normalized cues and recall options, the result count and the
+//! latency. Query text, filter values, memory contents and the caller's
+//! identity are never written. The file is rotated to `.1` when it reaches
+//! its size cap.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::error;
+
+/// File name of the query log in the data directory
+pub const QUERY_LOG_FILE: &str = "recall_queries.jsonl";
+
+/// Size at which the log is rotated unless configured otherwise
+pub const DEFAULT_QUERY_LOG_MAX_MB: u64 = 100;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LoggedQuery {
+    /// Unix seconds
+    pub timestamp: u64,
+    pub project_id: String,
+    /// Query cues after normalization (cues given and resolved from the text)
+    pub cues: Vec<String>,
+    pub limit: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_intersection: Option<usize>,
+    #[serde(default)]
+    pub disable_pattern_completion: bool,
+    #[serde(default)]
+    pub disable_salience_bias: bool,
+    #[serde(default)]
+    pub disable_systems_consolidation: bool,
+    #[serde(default)]
+    pub disable_alias_expansion: bool,
+    #[serde(default)]
+    pub fuzzy: bool,
+    pub result_count: usize,
+    pub latency_ms: f64,
+}
+
+impl LoggedQuery {
+    pub fn new(project_id: String, cues: Vec<String>) -> Self {
+        Self {
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+            project_id,
+            cues,
+            ..Default::default()
+        }
+    }
+}
+
+pub struct QueryLog {
+    path: PathBuf,
+    max_bytes: u64,
+    sample_rate: f64,
+    /// Open file and its current size
+    file: Mutex<Option<(File, u64)>>,
+}
+
+impl QueryLog {
+    /// `sample_rate` is the share of recalls logged (0-1)
+    pub fn open<P: AsRef<Path>>(path: P, max_bytes: u64, sample_rate: f64) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let file = match Self::open_file(&path) {
+            Ok(f) => Some(f),
+            Err(e) => {
+                error!("Query log: failed to open {:?}: {}", path, e);
+                None
+            }
+        };
+        Self {
+            path,
+            max_bytes,
+            sample_rate,
+            file: Mutex::new(file),
+        }
+    }
+
+    fn open_file(path: &Path) -> std::io::Result<(File, u64)> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let len = file.metadata()?.len();
+        Ok((file, len))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append one query (subject to sampling). Failures are logged rather
+    /// than failing the recall.
+    pub fn record(&self, query: &LoggedQuery) {
+        if self.sample_rate < 1.0 && rand::random::<f64>() >= self.sample_rate {
+            return;
+        }
+        let mut line = match serde_json::to_string(query) {
+            Ok(l) => l,
+            Err(e) => {
+                error!("Query log: failed to serialize entry: {}", e);
+                return;
+            }
+        };
+        line.push('\n');
+
+        let mut guard = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        if matches!(guard.as_ref(), Some((_, len)) if self.max_bytes > 0 && len + line.len() as u64 > self.max_bytes) {
+            *guard = None;
+            let rotated = self.path.with_extension("jsonl.1");
+            let reopened = std::fs::rename(&self.path, &rotated).and_then(|_| Self::open_file(&self.path));
+            match reopened {
+                Ok(f) => *guard = Some(f),
+                Err(e) => error!("Query log: failed to rotate {:?}: {}", self.path, e),
+            }
+        }
+        if let Some((file, len)) = guard.as_mut() {
+            match file.write_all(line.as_bytes()) {
+                Ok(()) => *len += line.len() as u64,
+                Err(e) => error!("Query log: failed to append to {:?}: {}", self.path, e),
+            }
+        }
+    }
+}
+
+/// Every query of a log file, oldest first
+pub fn read_query_log(path: &Path) -> Result<Vec<LoggedQuery>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut queries = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let query = serde_json::from_str(&line).map_err(|e| format!("{}:{}: {}", path.display(), i + 1, e))?;
+        queries.push(query);
+    }
+    Ok(queries)
+}
+
+/// Latency distribution of a set of recalls, in milliseconds
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct LatencySummary {
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+impl LatencySummary {
+    fn of(mut latencies: Vec<f64>) -> Self {
+        if latencies.is_empty() {
+            return Self::default();
+        }
+        latencies.sort_by(|a, b| a.total_cmp(b));
+        let at = |q: f64| latencies[((latencies.len() as f64 * q).ceil() as usize).clamp(1, latencies.len()) - 1];
+        Self { p50: at(0.5), p95: at(0.95), p99: at(0.99), max: latencies[latencies.len() - 1] }
+    }
+}
+
+/// Outcome of re-running logged queries
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReplaySummary {
+    pub queries: usize,
+    pub failed: usize,
+    /// As logged in production
+    pub logged_latency: LatencySummary,
+    pub replay_latency: LatencySummary,
+    pub wall_seconds: f64,
+    pub mean_logged_results: f64,
+    pub mean_replay_results: f64,
+    /// Queries that found results when logged and find none now
+    pub now_empty: usize,
+    /// Queries that found nothing when logged and find results now
+    pub now_found: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// Errors kept in a summary; the rest are only counted
+const MAX_REPLAY_ERRORS: usize = 20;
+
+/// Re-run `queries` in order against `client`. Recalls never reinforce, so
+/// the data replayed against is left as it was.
+pub async fn replay(client: &crate::Client, queries: &[LoggedQuery]) -> ReplaySummary {
+    let started = std::time::Instant::now();
+    let mut summary = ReplaySummary { queries: queries.len(), ..Default::default() };
+    let mut latencies = Vec::with_capacity(queries.len());
+    let mut logged_results = 0;
+    let mut replay_results = 0;
+
+    for query in queries {
+        let start = std::time::Instant::now();
+        let result = client
+            .recall_with(&query.project_id, crate::RecallOptions {
+                cues: query.cues.clone(),
+                limit: query.limit,
+                auto_reinforce: false,
+                min_intersection: query.min_intersection,
+                disable_pattern_completion: query.disable_pattern_completion,
+                disable_salience_bias: query.disable_salience_bias,
+                disable_systems_consolidation: query.disable_systems_consolidation,
+                disable_alias_expansion: query.disable_alias_expansion,
+                fuzzy: query.fuzzy,
+                rewrite_query: false,
+                ..Default::default()
+            })
+            .await;
+        match result {
+            Ok(results) => {
+                latencies.push(start.elapsed().as_secs_f64() * 1000.0);
+                logged_results += query.result_count;
+                replay_results += results.len();
+                summary.now_empty += usize::from(query.result_count > 0 && results.is_empty());
+                summary.now_found += usize::from(query.result_count == 0 && !results.is_empty());
+            }
+            Err(e) => {
+                summary.failed += 1;
+                if summary.errors.len() < MAX_REPLAY_ERRORS {
+                    summary.errors.push(format!("{}: {}", query.project_id, e));
+                }
+            }
+        }
+    }
+
+    let replayed = (queries.len() - summary.failed).max(1) as f64;
+    summary.wall_seconds = started.elapsed().as_secs_f64();
+    summary.logged_latency = LatencySummary::of(queries.iter().map(|q| q.latency_ms).collect());
+    summary.replay_latency = LatencySummary::of(latencies);
+    summary.mean_logged_results = logged_results as f64 / replayed;
+    summary.mean_replay_results = replay_results as f64 / replayed;
+    summary
+}
@@ -0,0 +1,192 @@
+//! Query rewriting for recall.
+//!
+//! Before the `query_text` of a recall is tokenized, each word goes through:
+//! 1. the project's acronym table (`k8s` → `kubernetes`), learned from ingested
+//!    text that defines an acronym ("Service Level Objective (SLO)",
+//!    "Kubernetes (K8s)") or added with `POST /projects/:id/acronyms`;
+//! 2. spelling correction: a word whose token is in neither the cue index nor
+//!    the lexicon is replaced by the closest indexed word within
+//!    `tuning.fuzzy_max_edit_distance` edits.
+//!
+//! The rewritten query is reported under `explain.query_rewrite`.
+
+use crate::fuzzy::allowed_distance;
+use crate::projects::ProjectContext;
+use serde::Serialize;
+
+/// Longest acronym learned from text, in characters
+pub const MAX_ACRONYM_LEN: usize = 10;
+
+/// Longest expansion learned from text, in words
+pub const MAX_EXPANSION_WORDS: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AcronymExpansion {
+    pub acronym: String,
+    pub expansion: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpellingCorrection {
+    pub word: String,
+    pub corrected: String,
+    pub distance: usize,
+}
+
+/// A recall query before and after rewriting
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct QueryRewrite {
+    pub original: String,
+    pub rewritten: String,
+    pub acronyms: Vec<AcronymExpansion>,
+    pub corrections: Vec<SpellingCorrection>,
+}
+
+impl QueryRewrite {
+    pub fn changed(&self) -> bool {
+        self.original != self.rewritten
+    }
+}
+
+/// Expand acronyms and correct misspelled words of `text` against the project's
+/// acronym table and vocabulary. Stopwords, numbers and known words are kept.
+pub fn rewrite_query(ctx: &ProjectContext, text: &str) -> QueryRewrite {
+    let max_distance = ctx.tuning.fuzzy_max_edit_distance;
+    let mut rewrite = QueryRewrite { original: text.to_string(), ..Default::default() };
+    let mut words: Vec<String> = Vec::new();
+
+    for raw in text.split_whitespace() {
+        let word = clean_word(raw);
+        if word.is_empty() {
+            words.push(raw.to_string());
+            continue;
+        }
+        let tokens = ctx.tokenize(&word);
+        let known = !tokens.is_empty() && tokens.iter().all(|t| is_known(ctx, t));
+
+        if let Some(expansion) = ctx.acronyms.get(&word).map(|e| e.value().clone()) {
+            // Memories may use the short form too
+            words.push(if known { format!("{} {}", expansion, word) } else { expansion.clone() });
+            rewrite.acronyms.push(AcronymExpansion { acronym: word, expansion });
+            continue;
+        }
+
+        let correctable = tokens.len() == 1 && !known && !word.chars().any(|c| c.is_ascii_digit());
+        let budget = allowed_distance(tokens.first().map(|t| t.chars().count()).unwrap_or(0), max_distance);
+        let closest = if correctable && budget > 0 {
+            let token = &tokens[0];
+            [ctx.main.closest_cue(token, budget), ctx.lexicon.closest_cue(token, budget)]
+                .into_iter()
+                .flatten()
+                .min_by_key(|(_, distance)| *distance)
+        } else {
+            None
+        };
+        match closest {
+            Some((corrected, distance)) => {
+                words.push(corrected.clone());
+                rewrite.corrections.push(SpellingCorrection { word, corrected, distance });
+            }
+            None => words.push(raw.to_string()),
+        }
+    }
+
+    rewrite.rewritten = if rewrite.acronyms.is_empty() && rewrite.corrections.is_empty() {
+        text.to_string()
+    } else {
+        words.join(" ")
+    };
+    rewrite
+}
+
+fn is_known(ctx: &ProjectContext, token: &str) -> bool {
+    ctx.main.get_cue_frequency(token) > 0 || ctx.lexicon.get_cue_frequency(token) > 0
+}
+
+fn clean_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase()
+}
+
+/// Acronyms defined in `text`, as lowercase `(acronym, expansion)` pairs.
+/// Recognized: "Service Level Objective (SLO)", "SLO (Service Level
+/// Objective)" and numeronyms such as "Kubernetes (K8s)".
+pub fn extract_acronyms(text: &str) -> Vec<(String, String)> {
+    let mut found: Vec<(String, String)> = Vec::new();
+    let mut rest = text;
+    let mut offset = 0;
+
+    while let Some(open) = rest.find('(') {
+        let Some(close) = rest[open..].find(')').map(|i| open + i) else {
+            break;
+        };
+        let before: Vec<String> = text[..offset + open].split_whitespace().map(clean_word).collect();
+        let inner: Vec<String> = rest[open + 1..close].split_whitespace().map(clean_word).collect();
+        let inner_raw = rest[open + 1..close].trim();
+
+        let pair = if inner.len() == 1 && looks_like_acronym(inner_raw) {
+            // Long form first, acronym in parentheses
+            expansion_for(&inner[0], &before).map(|expansion| (inner[0].clone(), expansion))
+        } else if (2..=MAX_EXPANSION_WORDS).contains(&inner.len()) {
+            // Acronym first, long form in parentheses
+            let raw_acronym = text[..offset + open].split_whitespace().last().unwrap_or("");
+            let acronym = clean_word(raw_acronym);
+            (looks_like_acronym(raw_acronym.trim_matches(|c: char| !c.is_alphanumeric()))
+                && initials_match(&acronym, &inner))
+            .then(|| (acronym, inner.join(" ")))
+        } else {
+            None
+        };
+        if let Some(pair) = pair {
+            if !found.iter().any(|(a, _)| *a == pair.0) {
+                found.push(pair);
+            }
+        }
+
+        offset += close + 1;
+        rest = &rest[close + 1..];
+    }
+    found
+}
+
+/// 2+ alphanumeric characters with an uppercase letter or a digit past the first
+fn looks_like_acronym(word: &str) -> bool {
+    let len = word.chars().count();
+    (2..=MAX_ACRONYM_LEN).contains(&len)
+        && word.chars().all(|c| c.is_alphanumeric())
+        && word.chars().skip(1).any(|c| c.is_uppercase() || c.is_ascii_digit())
+}
+
+/// The words right before an acronym that it abbreviates
+fn expansion_for(acronym: &str, before: &[String]) -> Option<String> {
+    let last = before.last().filter(|w| !w.is_empty())?;
+    if is_numeronym(acronym, last) {
+        return Some(last.clone());
+    }
+    let letters = acronym.chars().filter(|c| c.is_alphabetic()).count();
+    if !(2..=MAX_EXPANSION_WORDS).contains(&letters) || before.len() < letters {
+        return None;
+    }
+    let words = &before[before.len() - letters..];
+    initials_match(acronym, words).then(|| words.join(" "))
+}
+
+fn initials_match(acronym: &str, words: &[String]) -> bool {
+    if acronym.chars().any(|c| !c.is_alphabetic()) {
+        return words.len() == 1 && is_numeronym(acronym, &words[0]);
+    }
+    let initials: String = words.iter().filter_map(|w| w.chars().next()).collect();
+    words.iter().all(|w| !w.is_empty()) && initials == acronym
+}
+
+/// `k8s` for `kubernetes`: first letter, count of the letters between, last letter
+fn is_numeronym(acronym: &str, word: &str) -> bool {
+    let chars: Vec<char> = acronym.chars().collect();
+    if chars.len() < 3 || !chars[0].is_alphabetic() || !chars[chars.len() - 1].is_alphabetic() {
+        return false;
+    }
+    let Ok(middle) = chars[1..chars.len() - 1].iter().collect::<String>().parse::<usize>() else {
+        return false;
+    };
+    let word: Vec<char> = word.chars().collect();
+    middle >= 2 && word.len() == middle + 2 && word[0] == chars[0] && word[word.len() - 1] == chars[chars.len() - 1]
+}
@@ -0,0 +1,152 @@
+//! Continuous replication of project snapshots to cloud storage.
+//!
+//! Every `interval`, projects whose engines changed since the last pass are
+//! snapshotted locally and uploaded through the `CloudBackupManager`, followed
+//! by the project's meta file and a small manifest. A standby instance can poll
+//! the manifests and restore any project whose `replicated_at` moved, so its lag
+//! is bounded by the replication interval plus the upload time.
+
+use crate::multi_tenant::MultiTenantEngine;
+use crate::persistence::CloudBackupManager;
+use ahash::RandomState;
+use bytes::Bytes;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, error, info};
+
+/// Object suffix of the per-project replication manifest
+pub const MANIFEST_SUFFIX: &str = ".replication.json";
+
+/// Written last, after all of a project's files have been uploaded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplicationManifest {
+    pub project_id: String,
+    /// Unix time (seconds, fractional) of the upload
+    pub replicated_at: f64,
+    pub total_memories: usize,
+    pub bytes: u64,
+}
+
+pub struct Replicator {
+    mt_engine: Arc<MultiTenantEngine>,
+    cloud: Arc<CloudBackupManager>,
+    // project -> engine generation at the last successful upload
+    replicated: DashMap<String, u64, RandomState>,
+}
+
+impl Replicator {
+    pub fn new(mt_engine: Arc<MultiTenantEngine>, cloud: Arc<CloudBackupManager>) -> Self {
+        Self {
+            mt_engine,
+            cloud,
+            replicated: DashMap::with_hasher(RandomState::new()),
+        }
+    }
+
+    /// Spawn the replication loop
+    pub fn start(self: Arc<Self>, interval: Duration) {
+        tokio::spawn(async move {
+            info!("Replication: Enabled (every {}s)", interval.as_secs());
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let results = self.replicate_changed().await;
+                let failed = results.iter().filter(|(_, r)| r.is_err()).count();
+                if failed > 0 {
+                    error!("Replication: {} of {} projects failed", failed, results.len());
+                } else if !results.is_empty() {
+                    debug!("Replication: uploaded {} projects", results.len());
+                }
+            }
+        });
+    }
+
+    /// Upload every loaded project that changed since its last successful upload
+    pub async fn replicate_changed(&self) -> Vec<(String, Result<ReplicationManifest, String>)> {
+        let mut results = Vec::new();
+
+        for project_id in self.mt_engine.loaded_project_ids() {
+            let generation = match self.mt_engine.get_project(&project_id) {
+                Some(ctx) => ctx.generation(),
+                None => continue,
+            };
+            if self.replicated.get(&project_id).map(|g| *g) == Some(generation) {
+                continue;
+            }
+
+            let result = self.replicate_project(&project_id).await;
+            match &result {
+                // Writes that landed during the upload bump the generation again and are picked up next pass
+                Ok(_) => {
+                    self.replicated.insert(project_id.clone(), generation);
+                }
+                Err(e) => error!("Replication: '{}' failed: {}", project_id, e),
+            }
+            results.push((project_id, result));
+        }
+
+        results
+    }
+
+    async fn replicate_project(&self, project_id: &str) -> Result<ReplicationManifest, String> {
+        let engine = self.mt_engine.clone();
+        let id = project_id.to_string();
+        tokio::task::spawn_blocking(move || engine.save_project(&id))
+            .await
+            .map_err(|e| e.to_string())??;
+
+        let dir = self.mt_engine.snapshots_dir();
+        let read = |suffix: &str| std::fs::read(dir.join(format!("{}{}", project_id, suffix))).ok().map(Bytes::from);
+        let main = read(".bin").ok_or_else(|| format!("Snapshot for '{}' missing after save", project_id))?;
+        let aliases = read("_aliases.bin");
+        let lexicon = read("_lexicon.bin");
+        let meta = read(".meta.json");
+        let vectors = read("_vectors.bin");
+        let history = read("_history.bin");
+
+        let bytes = self.cloud
+            .upload_project_snapshot(project_id, main, aliases, lexicon)
+            .await
+            .map_err(|e| e.to_string())?;
+        if let Some(meta) = meta {
+            self.cloud.put_project_object(project_id, ".meta.json", meta).await.map_err(|e| e.to_string())?;
+        }
+        if let Some(vectors) = vectors {
+            self.cloud.put_project_object(project_id, "_vectors.bin", vectors).await.map_err(|e| e.to_string())?;
+        }
+        if let Some(history) = history {
+            self.cloud.put_project_object(project_id, "_history.bin", history).await.map_err(|e| e.to_string())?;
+        }
+
+        let manifest = ReplicationManifest {
+            project_id: project_id.to_string(),
+            replicated_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs_f64(),
+            total_memories: self.mt_engine
+                .get_project(&project_id.to_string())
+                .map(|ctx| ctx.main.total_memories())
+                .unwrap_or(0),
+            bytes,
+        };
+        let body = serde_json::to_vec(&manifest).map_err(|e| e.to_string())?;
+        self.cloud
+            .put_project_object(project_id, MANIFEST_SUFFIX, Bytes::from(body))
+            .await
+            .map_err(|e| e.to_string())?;
+
+        info!("Replication: uploaded '{}' ({} bytes)", project_id, bytes);
+        Ok(manifest)
+    }
+}
+
+/// Read a project's replication manifest from the bucket, `None` if it was never replicated
+pub async fn fetch_manifest(cloud: &CloudBackupManager, project_id: &str) -> Result<Option<ReplicationManifest>, String> {
+    match cloud.get_project_object(project_id, MANIFEST_SUFFIX).await.map_err(|e| e.to_string())? {
+        Some(data) => serde_json::from_slice(&data).map(Some).map_err(|e| e.to_string()),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,186 @@
+//! Rerank stage for recall.
+//!
+//! Cue intersection and recency find the right memories but don't always put
+//! the most relevant one first. A reranker rescores the top `top_n` recall
+//! candidates against the query text; the stage is configured per project
+//! (`PUT /projects/:id/rerank`) and toggled per request with `rerank`:
+//! - `onnx`: ms-marco-MiniLM-L-6-v2 cross-encoder run in-process (`onnx` feature)
+//! - `llm`: the project's LLM rates each candidate from 0 to 10
+
+use crate::config::{RerankConfig, RerankStrategy};
+use crate::engine::RecallResult;
+use crate::llm::LlmClient;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait Reranker: Send + Sync {
+    /// Scorer and model, e.g. `onnx/ms-marco-MiniLM-L-6-v2`
+    fn name(&self) -> String;
+
+    /// Relevance of each text to `query`, in order (higher is more relevant)
+    async fn score(&self, query: &str, texts: &[String]) -> Result<Vec<f32>, String>;
+}
+
+/// Build the reranker for a config; `Ok(None)` for `strategy = "none"`.
+/// `llm` is the project's client, used by the `llm` strategy.
+pub fn build_reranker(config: &RerankConfig, llm: Arc<LlmClient>) -> Result<Option<Arc<dyn Reranker>>, String> {
+    let reranker: Arc<dyn Reranker> = match config.strategy {
+        RerankStrategy::None => return Ok(None),
+        RerankStrategy::Llm => {
+            if !llm.config().enabled {
+                return Err("The llm rerank strategy needs an enabled LLM (see PUT /projects/:id/llm)".to_string());
+            }
+            Arc::new(LlmReranker { client: llm })
+        }
+        #[cfg(feature = "onnx")]
+        RerankStrategy::Onnx => Arc::new(onnx::CrossEncoder::load(&config.model)?),
+        #[cfg(not(feature = "onnx"))]
+        RerankStrategy::Onnx => {
+            return Err("The onnx rerank strategy requires a build with `--features onnx`".to_string())
+        }
+    };
+    Ok(Some(reranker))
+}
+
+/// Rescore the first `top_n` results with `reranker` and reorder them by that
+/// score; results past `top_n` keep their order below them. Each rescored
+/// result gets a `rerank_score` metadata entry.
+pub async fn rerank(reranker: &dyn Reranker, query: &str, results: &mut Vec<RecallResult>, top_n: usize) -> Result<(), String> {
+    let n = top_n.min(results.len());
+    if n < 2 {
+        return Ok(());
+    }
+    let texts: Vec<String> = results[..n].iter().map(|r| r.content.clone()).collect();
+    let scores = reranker.score(query, &texts).await?;
+    if scores.len() != n {
+        return Err(format!("{} returned {} scores for {} candidates", reranker.name(), scores.len(), n));
+    }
+
+    let rest = results.split_off(n);
+    let mut scored: Vec<(f32, RecallResult)> = scores.into_iter().zip(results.drain(..)).collect();
+    // Stable, so ties keep their recall order
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    for (score, mut result) in scored {
+        result.metadata.insert("rerank_score".to_string(), serde_json::json!(score));
+        results.push(result);
+    }
+    results.extend(rest);
+    Ok(())
+}
+
+/// Candidates rated by the project's LLM
+pub struct LlmReranker {
+    client: Arc<LlmClient>,
+}
+
+#[async_trait]
+impl Reranker for LlmReranker {
+    fn name(&self) -> String {
+        format!("llm/{}", self.client.name())
+    }
+
+    async fn score(&self, query: &str, texts: &[String]) -> Result<Vec<f32>, String> {
+        self.client.rate_passages(query, texts).await
+    }
+}
+
+#[cfg(feature = "onnx")]
+mod onnx {
+    use super::*;
+    use crate::config::get_base_dir;
+    use crate::embeddings::onnx::download_if_missing;
+    use ort::session::Session;
+    use ort::value::Tensor;
+    use std::path::PathBuf;
+    use tokenizers::{PaddingParams, Tokenizer, TruncationParams};
+
+    const MODEL_URL: &str = "https://huggingface.co/cross-encoder/ms-marco-MiniLM-L-6-v2/resolve/main/onnx/model.onnx";
+    const TOKENIZER_URL: &str = "https://huggingface.co/cross-encoder/ms-marco-MiniLM-L-6-v2/resolve/main/tokenizer.json";
+    const MAX_TOKENS: usize = 512;
+
+    /// ms-marco-MiniLM-L-6-v2: one relevance logit per (query, passage) pair
+    #[derive(Clone)]
+    pub struct CrossEncoder {
+        session: Arc<Session>,
+        tokenizer: Arc<Tokenizer>,
+    }
+
+    impl CrossEncoder {
+        /// Load `model.onnx` + `tokenizer.json` from `model_dir`
+        /// (default `~/.cuemap/models/ms-marco-MiniLM-L-6-v2`, downloaded on first use)
+        pub fn load(model_dir: &str) -> Result<Self, String> {
+            let dir = if model_dir.is_empty() {
+                get_base_dir().join("models").join("ms-marco-MiniLM-L-6-v2")
+            } else {
+                PathBuf::from(model_dir)
+            };
+            let model_path = dir.join("model.onnx");
+            let tokenizer_path = dir.join("tokenizer.json");
+            if model_dir.is_empty() {
+                download_if_missing(&model_path, MODEL_URL)?;
+                download_if_missing(&tokenizer_path, TOKENIZER_URL)?;
+            }
+
+            let session = Session::builder()
+                .and_then(|b| b.commit_from_file(&model_path))
+                .map_err(|e| format!("Failed to load ONNX model {:?}: {}", model_path, e))?;
+            let mut tokenizer = Tokenizer::from_file(&tokenizer_path)
+                .map_err(|e| format!("Failed to load tokenizer {:?}: {}", tokenizer_path, e))?;
+            tokenizer.with_padding(Some(PaddingParams::default()));
+            tokenizer
+                .with_truncation(Some(TruncationParams { max_length: MAX_TOKENS, ..Default::default() }))
+                .map_err(|e| e.to_string())?;
+
+            Ok(Self { session: Arc::new(session), tokenizer: Arc::new(tokenizer) })
+        }
+
+        fn score_blocking(&self, query: String, texts: Vec<String>) -> Result<Vec<f32>, String> {
+            let pairs: Vec<(String, String)> = texts.into_iter().map(|text| (query.clone(), text)).collect();
+            let encodings = self.tokenizer.encode_batch(pairs, true).map_err(|e| e.to_string())?;
+            let batch = encodings.len();
+            let seq = encodings.first().map(|e| e.len()).unwrap_or(0);
+            if batch == 0 || seq == 0 {
+                return Ok(vec![0.0; batch]);
+            }
+
+            let mut ids = Vec::with_capacity(batch * seq);
+            let mut mask = Vec::with_capacity(batch * seq);
+            let mut types = Vec::with_capacity(batch * seq);
+            for encoding in &encodings {
+                ids.extend(encoding.get_ids().iter().map(|&x| x as i64));
+                mask.extend(encoding.get_attention_mask().iter().map(|&x| x as i64));
+                types.extend(encoding.get_type_ids().iter().map(|&x| x as i64));
+            }
+
+            let shape = vec![batch as i64, seq as i64];
+            let tensor = |data: Vec<i64>| Tensor::from_array((shape.clone(), data)).map_err(|e| e.to_string());
+            let inputs = ort::inputs![
+                "input_ids" => tensor(ids)?,
+                "attention_mask" => tensor(mask)?,
+                "token_type_ids" => tensor(types)?,
+            ]
+            .map_err(|e| e.to_string())?;
+            let outputs = self.session.run(inputs).map_err(|e| format!("ONNX inference failed: {}", e))?;
+            let (_, logits) = outputs["logits"].try_extract_raw_tensor::<f32>().map_err(|e| e.to_string())?;
+            // Shape [batch, 1]
+            Ok(logits.iter().take(batch).copied().collect())
+        }
+    }
+
+    #[async_trait]
+    impl Reranker for CrossEncoder {
+        fn name(&self) -> String {
+            "onnx/ms-marco-MiniLM-L-6-v2".to_string()
+        }
+
+        async fn score(&self, query: &str, texts: &[String]) -> Result<Vec<f32>, String> {
+            let encoder = self.clone();
+            let query = query.to_string();
+            let texts = texts.to_vec();
+            tokio::task::spawn_blocking(move || encoder.score_blocking(query, texts))
+                .await
+                .map_err(|e| e.to_string())?
+        }
+    }
+}
@@ -0,0 +1,426 @@
+//! Background scheduler for per-project maintenance.
+//!
+//! Consolidation, salience decay, low-salience pruning, lexicon pruning and
+//! snapshotting all exist as one-off operations; the scheduler runs them
+//! periodically according to each project's `ProjectSchedule` (stored in the
+//! project's meta file, or the server's `[schedule]` default). It also
+//! refreshes the project's sitemap/feed subscriptions and syncs its
+//! connectors, those that set an interval.
+
+use crate::agent::feeds::FeedSubscription;
+use crate::agent::ingester::Ingester;
+use crate::agent::AgentConfig;
+use crate::audit::{AuditAction, AuditEntry, SYSTEM_ACTOR};
+use crate::connectors::{self, ConnectorConfig};
+use crate::jobs::{Job, JobQueue};
+use crate::maintenance::prune_memories;
+use crate::multi_tenant::MultiTenantEngine;
+use ahash::RandomState;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{debug, error, info};
+
+/// Per-project maintenance intervals. An interval of `None` disables that task.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectSchedule {
+    /// Seconds between memory consolidation runs
+    #[serde(default)]
+    pub consolidation_interval_secs: Option<u64>,
+    /// Seconds between salience decay passes
+    #[serde(default)]
+    pub decay_interval_secs: Option<u64>,
+    /// Decay rate per hour passed to `decay_salience`
+    #[serde(default = "default_decay_rate")]
+    pub decay_rate: f64,
+    /// Seconds between low-salience pruning passes
+    #[serde(default)]
+    pub prune_interval_secs: Option<u64>,
+    /// Memories whose salience is below this are pruned (1.0 is a fresh memory's)
+    #[serde(default = "default_prune_threshold")]
+    pub prune_threshold: f64,
+    /// ...once neither created nor recalled for this long
+    #[serde(default = "default_prune_max_idle_secs")]
+    pub prune_max_idle_secs: u64,
+    /// Seconds between lexicon pruning passes
+    #[serde(default)]
+    pub lexicon_prune_interval_secs: Option<u64>,
+    /// Entries reinforced fewer times than this are pruning candidates
+    #[serde(default = "default_lexicon_prune_min_count")]
+    pub lexicon_prune_min_count: u64,
+    /// ...but only once they have been idle for this long
+    #[serde(default = "default_lexicon_prune_max_idle_secs")]
+    pub lexicon_prune_max_idle_secs: u64,
+    /// Seconds between snapshots of this project (on top of the global periodic snapshot)
+    #[serde(default)]
+    pub snapshot_interval_secs: Option<u64>,
+}
+
+fn default_decay_rate() -> f64 {
+    0.05
+}
+
+fn default_prune_threshold() -> f64 {
+    1.0
+}
+
+fn default_prune_max_idle_secs() -> u64 {
+    30 * 86400
+}
+
+fn default_lexicon_prune_min_count() -> u64 {
+    1
+}
+
+fn default_lexicon_prune_max_idle_secs() -> u64 {
+    30 * 86400
+}
+
+impl Default for ProjectSchedule {
+    fn default() -> Self {
+        Self {
+            consolidation_interval_secs: None,
+            decay_interval_secs: None,
+            decay_rate: default_decay_rate(),
+            prune_interval_secs: None,
+            prune_threshold: default_prune_threshold(),
+            prune_max_idle_secs: default_prune_max_idle_secs(),
+            lexicon_prune_interval_secs: None,
+            lexicon_prune_min_count: default_lexicon_prune_min_count(),
+            lexicon_prune_max_idle_secs: default_lexicon_prune_max_idle_secs(),
+            snapshot_interval_secs: None,
+        }
+    }
+}
+
+impl ProjectSchedule {
+    pub fn validate(&self) -> Result<(), String> {
+        let intervals = [
+            ("consolidation_interval_secs", self.consolidation_interval_secs),
+            ("decay_interval_secs", self.decay_interval_secs),
+            ("prune_interval_secs", self.prune_interval_secs),
+            ("lexicon_prune_interval_secs", self.lexicon_prune_interval_secs),
+            ("snapshot_interval_secs", self.snapshot_interval_secs),
+        ];
+        if let Some((name, _)) = intervals.iter().find(|(_, secs)| *secs == Some(0)) {
+            return Err(format!("{} must be at least 1 (omit it to disable the task)", name));
+        }
+        if !(self.decay_rate.is_finite() && self.decay_rate >= 0.0) {
+            return Err("decay_rate must be a non-negative number".to_string());
+        }
+        if !self.prune_threshold.is_finite() {
+            return Err("prune_threshold must be a number".to_string());
+        }
+        Ok(())
+    }
+
+    fn interval(&self, task: ScheduledTask) -> Option<Duration> {
+        let secs = match task {
+            ScheduledTask::Consolidation => self.consolidation_interval_secs,
+            ScheduledTask::Decay => self.decay_interval_secs,
+            ScheduledTask::Prune => self.prune_interval_secs,
+            ScheduledTask::LexiconPrune => self.lexicon_prune_interval_secs,
+            ScheduledTask::Snapshot => self.snapshot_interval_secs,
+            // Each feed subscription and connector has its own interval
+            ScheduledTask::FeedRefresh | ScheduledTask::ConnectorSync => None,
+        };
+        // 0 is rejected by `validate`; meta files saved before that disable the task
+        secs.filter(|&s| s > 0).map(Duration::from_secs)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduledTask {
+    Consolidation,
+    Decay,
+    /// Removing low-salience memories
+    Prune,
+    LexiconPrune,
+    Snapshot,
+    /// Re-reading a sitemap or feed (not in `ALL`; see `FeedSubscription`)
+    FeedRefresh,
+    /// Syncing a connector (not in `ALL`; see `ConnectorConfig`)
+    ConnectorSync,
+}
+
+impl ScheduledTask {
+    pub const ALL: [ScheduledTask; 5] = [
+        ScheduledTask::Consolidation,
+        ScheduledTask::Decay,
+        ScheduledTask::Prune,
+        ScheduledTask::LexiconPrune,
+        ScheduledTask::Snapshot,
+    ];
+}
+
+pub struct Scheduler {
+    mt_engine: Arc<MultiTenantEngine>,
+    job_queue: Arc<JobQueue>,
+    snapshots_enabled: bool,
+    started_at: Instant,
+    last_runs: DashMap<(String, ScheduledTask), Instant, RandomState>,
+    /// (project, task, feed URL or connector id) of feed refreshes and
+    /// connector syncs still running
+    in_flight: Arc<DashMap<(String, ScheduledTask, String), (), RandomState>>,
+}
+
+impl Scheduler {
+    pub fn new(mt_engine: Arc<MultiTenantEngine>, job_queue: Arc<JobQueue>, snapshots_enabled: bool) -> Self {
+        Self {
+            mt_engine,
+            job_queue,
+            snapshots_enabled,
+            started_at: Instant::now(),
+            last_runs: DashMap::with_hasher(RandomState::new()),
+            in_flight: Arc::new(DashMap::with_hasher(RandomState::new())),
+        }
+    }
+
+    /// Spawn the scheduler loop, checking for due tasks every `tick`
+    pub fn start(self: Arc<Self>, tick: Duration) {
+        tokio::spawn(async move {
+            info!("Scheduler: Enabled (tick every {}s)", tick.as_secs());
+            let mut ticker = tokio::time::interval(tick);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let ran = self.run_due().await;
+                if !ran.is_empty() {
+                    debug!("Scheduler: ran {} tasks", ran.len());
+                }
+            }
+        });
+    }
+
+    /// Run every task whose interval has elapsed. Intervals are measured from the
+    /// last run, or from scheduler start for tasks that have not run yet.
+    pub async fn run_due(&self) -> Vec<(String, ScheduledTask)> {
+        let mut ran = Vec::new();
+
+        for project_id in self.mt_engine.loaded_project_ids() {
+            let schedule = match self.mt_engine.get_project_schedule(&project_id) {
+                Ok(s) => s,
+                Err(e) => {
+                    error!("Scheduler: failed to read schedule for '{}': {}", project_id, e);
+                    continue;
+                }
+            };
+
+            for task in ScheduledTask::ALL {
+                let interval = match schedule.interval(task) {
+                    Some(i) => i,
+                    None => continue,
+                };
+                if task == ScheduledTask::Snapshot && !self.snapshots_enabled {
+                    continue;
+                }
+
+                let key = (project_id.clone(), task);
+                let last = self.last_runs.get(&key).map(|t| *t).unwrap_or(self.started_at);
+                if last.elapsed() < interval {
+                    continue;
+                }
+
+                self.last_runs.insert(key, Instant::now());
+                self.run_task(&project_id, task, &schedule).await;
+                ran.push((project_id.clone(), task));
+            }
+
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+            for feed in self.mt_engine.get_project_feeds(&project_id).unwrap_or_default() {
+                let Some(interval) = feed.refresh_interval_secs else {
+                    continue;
+                };
+                if feed.last_refreshed.is_some_and(|last| now < last + interval) {
+                    continue;
+                }
+                let key = (project_id.clone(), ScheduledTask::FeedRefresh, feed.url.clone());
+                if self.in_flight.insert(key, ()).is_some() {
+                    continue;
+                }
+                self.refresh_feed(&project_id, feed);
+                ran.push((project_id.clone(), ScheduledTask::FeedRefresh));
+            }
+
+            for connector in self.mt_engine.get_project_connectors(&project_id).unwrap_or_default() {
+                let Some(interval) = connector.sync_interval_secs else {
+                    continue;
+                };
+                if connector.last_synced.is_some_and(|last| now < last + interval) {
+                    continue;
+                }
+                let key = (project_id.clone(), ScheduledTask::ConnectorSync, connector.id.clone());
+                if self.in_flight.insert(key, ()).is_some() {
+                    continue;
+                }
+                self.sync_connector(&project_id, connector);
+                ran.push((project_id.clone(), ScheduledTask::ConnectorSync));
+            }
+        }
+
+        ran
+    }
+
+    /// Re-read a feed in the background: crawling its changed pages can take
+    /// longer than a tick
+    fn refresh_feed(&self, project_id: &str, mut feed: FeedSubscription) {
+        let mt_engine = self.mt_engine.clone();
+        let job_queue = self.job_queue.clone();
+        let in_flight = self.in_flight.clone();
+        let project_id = project_id.to_string();
+
+        tokio::spawn(async move {
+            let config = AgentConfig {
+                project_id: project_id.clone(),
+                watch_dir: String::new(),
+                throttle_ms: 0,
+                state_file: None,
+                filter: Default::default(),
+                git: Default::default(),
+                chunking: mt_engine.get_project_chunking(&project_id).unwrap_or_default(),
+                extract_facts: false,
+            };
+            let mut ingester = Ingester::new(config, job_queue);
+            match ingester.process_feed(&mut feed, &project_id).await {
+                Ok(refresh) if !refresh.ingested.is_empty() => {
+                    info!("Scheduler: refreshed {} pages of feed {} for '{}'", refresh.ingested.len(), feed.url, project_id);
+                }
+                Ok(_) => debug!("Scheduler: feed {} for '{}' is unchanged", feed.url, project_id),
+                Err(e) => {
+                    error!("Scheduler: refreshing feed {} for '{}' failed: {}", feed.url, project_id, e);
+                    // Wait a full interval before trying again
+                    feed.last_refreshed = Some(SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs());
+                }
+            }
+            // Keep the subscription's current interval, unless it was removed meanwhile
+            if let Ok(Some(current)) = mt_engine.get_project_feed(&project_id, &feed.url) {
+                feed.refresh_interval_secs = current.refresh_interval_secs;
+                if let Err(e) = mt_engine.set_project_feed(&project_id, feed.clone()) {
+                    error!("Scheduler: failed to save feed {} for '{}': {}", feed.url, project_id, e);
+                }
+            }
+            in_flight.remove(&(project_id, ScheduledTask::FeedRefresh, feed.url));
+        });
+    }
+
+    /// Sync a connector in the background
+    fn sync_connector(&self, project_id: &str, mut connector: ConnectorConfig) {
+        let mt_engine = self.mt_engine.clone();
+        let job_queue = self.job_queue.clone();
+        let in_flight = self.in_flight.clone();
+        let project_id = project_id.to_string();
+
+        tokio::spawn(async move {
+            let config = AgentConfig {
+                project_id: project_id.clone(),
+                watch_dir: String::new(),
+                throttle_ms: 0,
+                state_file: None,
+                filter: Default::default(),
+                git: Default::default(),
+                chunking: mt_engine.get_project_chunking(&project_id).unwrap_or_default(),
+                extract_facts: false,
+            };
+            let mut ingester = Ingester::new(config, job_queue);
+            let result = match connectors::build_connector(&connector) {
+                Ok(source) => ingester.process_connector(source.as_ref(), &mut connector, &project_id).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(report) => {
+                    if !report.synced.is_empty() {
+                        info!("Scheduler: synced {} documents of connector '{}' for '{}'", report.synced.len(), connector.id, project_id);
+                    }
+                    connector.last_error = None;
+                }
+                Err(e) => {
+                    error!("Scheduler: syncing connector '{}' for '{}' failed: {}", connector.id, project_id, e);
+                    // Wait a full interval before trying again
+                    connector.last_synced = Some(SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs());
+                    connector.last_error = Some(e);
+                }
+            }
+            // Keep the connector's current settings, unless it was removed or
+            // pointed elsewhere meanwhile
+            if let Ok(Some(current)) = mt_engine.get_project_connector(&project_id, &connector.id) {
+                if current.same_source(&connector) {
+                    let synced = ConnectorConfig {
+                        cursor: connector.cursor,
+                        last_synced: connector.last_synced,
+                        last_error: connector.last_error,
+                        ..current
+                    };
+                    if let Err(e) = mt_engine.set_project_connector(&project_id, synced) {
+                        error!("Scheduler: failed to save connector '{}' for '{}': {}", connector.id, project_id, e);
+                    }
+                }
+            }
+            in_flight.remove(&(project_id, ScheduledTask::ConnectorSync, connector.id));
+        });
+    }
+
+    async fn run_task(&self, project_id: &str, task: ScheduledTask, schedule: &ProjectSchedule) {
+        match task {
+            ScheduledTask::Consolidation => {
+                self.job_queue.enqueue(Job::ConsolidateMemories {
+                    project_id: project_id.to_string(),
+                }).await;
+            }
+            ScheduledTask::Decay => {
+                if let Some(ctx) = self.mt_engine.get_or_load_project(&project_id.to_string()) {
+                    let rate = schedule.decay_rate;
+                    let _ = tokio::task::spawn_blocking(move || ctx.main.decay_salience(rate)).await;
+                    debug!("Scheduler: decayed salience for '{}' (rate {})", project_id, rate);
+                }
+            }
+            ScheduledTask::Prune => {
+                if let Some(ctx) = self.mt_engine.get_or_load_project(&project_id.to_string()) {
+                    let threshold = schedule.prune_threshold;
+                    let max_idle = schedule.prune_max_idle_secs;
+                    let pruned = tokio::task::spawn_blocking(move || prune_memories(&ctx.main, threshold, max_idle, false).memory_ids)
+                        .await
+                        .unwrap_or_default();
+                    for memory_id in &pruned {
+                        self.mt_engine.audit().record(
+                            AuditEntry::new(SYSTEM_ACTOR.to_string(), AuditAction::MemoryDeleted, project_id)
+                                .with_memory(memory_id)
+                                .with_detail(serde_json::json!({"reason": "low_salience"})),
+                        );
+                    }
+                    if !pruned.is_empty() {
+                        info!("Scheduler: pruned {} low-salience memories from '{}'", pruned.len(), project_id);
+                    }
+                }
+            }
+            ScheduledTask::LexiconPrune => {
+                if let Some(ctx) = self.mt_engine.get_or_load_project(&project_id.to_string()) {
+                    let min_count = schedule.lexicon_prune_min_count;
+                    let max_idle = schedule.lexicon_prune_max_idle_secs;
+                    let pruned = tokio::task::spawn_blocking(move || ctx.lexicon.prune_idle_entries(min_count, max_idle))
+                        .await
+                        .unwrap_or(0);
+                    if pruned > 0 {
+                        self.mt_engine.audit().record(
+                            AuditEntry::new(SYSTEM_ACTOR.to_string(), AuditAction::LexiconPruned, project_id)
+                                .with_detail(serde_json::json!({"pruned": pruned})),
+                        );
+                        info!("Scheduler: pruned {} idle lexicon entries from '{}'", pruned, project_id);
+                    }
+                }
+            }
+            ScheduledTask::Snapshot => {
+                let engine = self.mt_engine.clone();
+                let id = project_id.to_string();
+                match tokio::task::spawn_blocking(move || engine.save_project(&id)).await {
+                    Ok(Ok(_)) => debug!("Scheduler: saved snapshot for '{}'", project_id),
+                    Ok(Err(e)) => error!("Scheduler: snapshot of '{}' failed: {}", project_id, e),
+                    Err(e) => error!("Scheduler: snapshot task for '{}' panicked: {}", project_id, e),
+                }
+            }
+            // Started per feed and connector by `run_due`
+            ScheduledTask::FeedRefresh | ScheduledTask::ConnectorSync => {}
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Slow-query log.
+//!
+//! Recalls slower than `[metrics] slow_query_ms` are kept, with their cue
+//! counts and per-stage timings, in a ring buffer of the most recent ones
+//! (`GET /debug/slow-queries`). It is in memory only and starts empty.
+
+use crate::engine::RecallProfile;
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Recalls slower than this (ms) are logged unless configured otherwise
+pub const DEFAULT_SLOW_QUERY_MS: u64 = 500;
+
+/// Slow queries kept unless configured otherwise
+pub const DEFAULT_SLOW_QUERY_LOG_SIZE: usize = 100;
+
+/// Longest query text kept per entry, in characters
+const MAX_QUERY_TEXT_CHARS: usize = 256;
+
+/// Wall-clock time of each stage of a request, in the order they ran
+pub struct StageTimer {
+    last: Instant,
+    stages: IndexMap<&'static str, f64>,
+}
+
+impl Default for StageTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StageTimer {
+    pub fn new() -> Self {
+        Self { last: Instant::now(), stages: IndexMap::new() }
+    }
+
+    /// End the current stage: the time since the previous lap is added to `stage`
+    pub fn lap(&mut self, stage: &'static str) {
+        let now = Instant::now();
+        *self.stages.entry(stage).or_default() += now.duration_since(self.last).as_secs_f64() * 1000.0;
+        self.last = now;
+    }
+
+    pub fn into_stages(self) -> IndexMap<&'static str, f64> {
+        self.stages
+    }
+}
+
+/// One recall that exceeded the threshold
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SlowQuery {
+    /// Unix seconds
+    pub timestamp: f64,
+    /// The projects of a cross-project recall are listed in `projects` instead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<String>,
+    /// Unset for cross-project recalls, whose projects may differ
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<RecallProfile>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query_text: Option<String>,
+    /// Cues asked for and resolved from the query text
+    pub cue_count: usize,
+    /// Cues searched after normalization and alias, fuzzy and prefix expansion
+    pub expanded_cue_count: usize,
+    /// Posting entries the search could scan (sum of the per-cue scan budgets)
+    pub candidate_count: usize,
+    pub result_count: usize,
+    pub total_ms: f64,
+    /// Milliseconds per stage, in the order they ran
+    pub stages: IndexMap<&'static str, f64>,
+}
+
+impl SlowQuery {
+    pub fn new(total_ms: f64, stages: IndexMap<&'static str, f64>) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs_f64(),
+            total_ms,
+            stages,
+            ..Default::default()
+        }
+    }
+
+    /// The query text as kept in the log (truncated)
+    pub fn clip_query_text(text: &str) -> String {
+        text.chars().take(MAX_QUERY_TEXT_CHARS).collect()
+    }
+}
+
+/// Ring buffer of the most recent slow queries
+pub struct SlowQueryLog {
+    threshold_ms: u64,
+    capacity: usize,
+    entries: Mutex<VecDeque<SlowQuery>>,
+}
+
+impl Default for SlowQueryLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_SLOW_QUERY_MS, DEFAULT_SLOW_QUERY_LOG_SIZE)
+    }
+}
+
+impl SlowQueryLog {
+    /// `capacity` 0 turns the log off
+    pub fn new(threshold_ms: u64, capacity: usize) -> Self {
+        Self { threshold_ms, capacity, entries: Mutex::new(VecDeque::new()) }
+    }
+
+    pub fn threshold_ms(&self) -> u64 {
+        self.threshold_ms
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Whether a recall that took `total_ms` belongs in the log; callers only
+    /// build the entry (scan budgets and all) when it does
+    pub fn is_slow(&self, total_ms: f64) -> bool {
+        self.capacity > 0 && total_ms >= self.threshold_ms as f64
+    }
+
+    pub fn record(&self, query: SlowQuery) {
+        if !self.is_slow(query.total_ms) {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(query);
+    }
+
+    /// Newest first, optionally only one project's (including cross-project recalls over it)
+    pub fn recent(&self, project_id: Option<&str>, limit: usize) -> Vec<SlowQuery> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries
+            .iter()
+            .rev()
+            .filter(|q| match project_id {
+                Some(id) => q.project_id.as_deref() == Some(id) || q.projects.iter().any(|p| p == id),
+                None => true,
+            })
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+}
@@ -0,0 +1,133 @@
+//! Snippets: windows of a recalled memory's content around the words that
+//! matched the query cues, with the offsets of those words, so UIs can show
+//! why a memory matched without re-tokenizing its content.
+//!
+//! Requested with `"snippets": true` on `POST /recall`; each result then
+//! carries a `snippets` metadata entry. Offsets count characters, not bytes.
+
+use crate::multilingual::TextLanguage;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Characters of context kept on each side of a match unless requested otherwise
+pub const DEFAULT_SNIPPET_WINDOW: usize = 60;
+
+/// Snippets per result unless requested otherwise
+pub const DEFAULT_MAX_SNIPPETS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Snippet {
+    pub text: String,
+    /// Offsets of `text` within the content
+    pub start: usize,
+    pub end: usize,
+    /// Matched words as `[start, end)` offsets within `text`
+    pub highlights: Vec<(usize, usize)>,
+}
+
+/// Words to highlight, from the query's cues
+pub struct SnippetTerms {
+    words: HashSet<String>,
+    language: TextLanguage,
+}
+
+impl SnippetTerms {
+    /// Words of the cues' values (`service:payments` highlights "payments"),
+    /// matched as written or by stem
+    pub fn from_cues<'a>(cues: impl IntoIterator<Item = &'a str>, language: TextLanguage) -> Self {
+        let mut words = HashSet::new();
+        for cue in cues {
+            let value = cue.rsplit(':').next().unwrap_or(cue);
+            for word in value.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()) {
+                let word = word.to_lowercase();
+                words.insert(language.stem(&word));
+                words.insert(word);
+            }
+        }
+        Self { words, language }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    fn matches(&self, word: &str) -> bool {
+        let word = word.to_lowercase();
+        self.words.contains(&word) || self.words.contains(&self.language.stem(&word))
+    }
+}
+
+/// Bounds of a snippet being built and the matches it covers, in characters
+type Window = (usize, usize, Vec<(usize, usize)>);
+
+/// Up to `max` snippets of `content` with `window` characters of context
+/// around each match, the ones with the most matches first, in content order
+pub fn snippets(content: &str, terms: &SnippetTerms, window: usize, max: usize) -> Vec<Snippet> {
+    if terms.is_empty() || max == 0 {
+        return Vec::new();
+    }
+    let chars: Vec<char> = content.chars().collect();
+
+    // Matched words, in order
+    let mut matches = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if !chars[i].is_alphanumeric() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && chars[i].is_alphanumeric() {
+            i += 1;
+        }
+        if terms.matches(&chars[start..i].iter().collect::<String>()) {
+            matches.push((start, i));
+        }
+    }
+
+    // One window per match, merged where they overlap
+    let mut windows: Vec<Window> = Vec::new();
+    for (match_start, match_end) in matches {
+        let (start, end) = snap_to_words(&chars, match_start, match_end, window);
+        match windows.last_mut() {
+            Some(last) if start <= last.1 => {
+                last.1 = last.1.max(end);
+                last.2.push((match_start, match_end));
+            }
+            _ => windows.push((start, end, vec![(match_start, match_end)])),
+        }
+    }
+
+    windows.sort_by_key(|w| std::cmp::Reverse(w.2.len()));
+    windows.truncate(max);
+    windows.sort_by_key(|w| w.0);
+    windows
+        .into_iter()
+        .map(|(start, end, highlights)| Snippet {
+            text: chars[start..end].iter().collect(),
+            start,
+            end,
+            highlights: highlights.into_iter().map(|(s, e)| (s - start, e - start)).collect(),
+        })
+        .collect()
+}
+
+/// Extend a match by `window` characters each way without cutting words,
+/// dropping surrounding whitespace
+fn snap_to_words(chars: &[char], match_start: usize, match_end: usize, window: usize) -> (usize, usize) {
+    let mut start = match_start.saturating_sub(window);
+    while start > 0 && start < match_start && chars[start - 1].is_alphanumeric() {
+        start += 1;
+    }
+    while start < match_start && chars[start].is_whitespace() {
+        start += 1;
+    }
+    let mut end = (match_end + window).min(chars.len());
+    while end < chars.len() && end > match_end && chars[end].is_alphanumeric() {
+        end -= 1;
+    }
+    while end > match_end && chars[end - 1].is_whitespace() {
+        end -= 1;
+    }
+    (start, end)
+}
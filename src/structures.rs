@@ -5,6 +5,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
 use crate::crypto::{self, EncryptionKey};
+use crate::payload_store::Payload;
 use ahash::RandomState;
 
 // =============================================================================
@@ -58,6 +59,12 @@ impl MainStats {
         
         self.intrinsic_salience + (self.dynamic_salience * decay_factor)
     }
+
+    /// Push the memory down by `amount` of dynamic heat. Heat may go negative and
+    /// decays back toward zero like a boost, but salience never drops below zero.
+    pub fn demote(&mut self, amount: f64) {
+        self.dynamic_salience = (self.dynamic_salience - amount).max(-self.intrinsic_salience);
+    }
 }
 
 /// Lexicon stats: Tiered Time-Series Statistics
@@ -145,13 +152,48 @@ impl MemoryStats for LexiconStats {
 // Generic Memory Struct
 // =============================================================================
 
+/// Metadata flag that exempts a memory from pruning and consolidation
+pub const PINNED_METADATA_KEY: &str = "pinned";
+
+/// Metadata key older snapshots and archives kept links under; moved to
+/// `Memory::links` when they are loaded
+pub const LEGACY_LINKS_METADATA_KEY: &str = "links";
+
+/// Typed edge to another memory (`caused_by`, `supersedes`, `part_of`, ...)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryLink {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub target: String,
+}
+
+/// Metadata key holding where an ingested memory came from (a `Provenance`)
+pub const PROVENANCE_METADATA_KEY: &str = "provenance";
+
+/// Origin of an ingested memory, recorded the same way by every ingestion path
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// `file`, `upload`, `content`, `url`, `connector` or `git`
+    pub source_type: String,
+    /// File path, URL, file name, `<connector>:<document>` or repository root
+    pub uri: String,
+    /// First and last line of the source the memory was chunked from
+    pub line_range: Option<(usize, usize)>,
+    /// First and last page, for paged documents (PDFs)
+    pub page_range: Option<(usize, usize)>,
+    /// Unix seconds
+    pub ingest_time: u64,
+    /// Hex SHA-256 of the content
+    pub content_hash: String,
+}
+
 /// Generic Memory wrapper for all memory types.
 /// The `stats` field contains type-specific payload (MainStats or LexiconStats).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Memory<T> {
     pub id: String,
-    // Content is now just raw bytes (Compressed OR Encrypted)
-    pub content: Vec<u8>, 
+    // Content is now just raw bytes (Compressed OR Encrypted), in RAM or on disk
+    pub content: Payload,
     pub created_at: f64,
     pub last_accessed: f64,
     #[serde(default)]
@@ -160,6 +202,10 @@ pub struct Memory<T> {
     pub metadata: HashMap<String, serde_json::Value>,
     /// Type-specific stats payload
     pub stats: T,
+    /// Outgoing typed links. Not part of the memory's bincode layout: snapshots
+    /// and archives carry them in a trailing section (see `PersistedState`).
+    #[serde(skip)]
+    pub links: Vec<MemoryLink>,
 }
 
 impl<T: Default> Memory<T> {
@@ -167,7 +213,7 @@ impl<T: Default> Memory<T> {
     // Callers should use `new_with_payload` or handle compression/encryption first.
     // Or we provide a helper that takes key.
     
-    pub fn new(content: Vec<u8>, metadata: Option<HashMap<String, serde_json::Value>>) -> Self {
+    pub fn new(content: impl Into<Payload>, metadata: Option<HashMap<String, serde_json::Value>>) -> Self {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
@@ -175,15 +221,45 @@ impl<T: Default> Memory<T> {
         
         Self {
             id: Uuid::new_v4().to_string(),
-            content,
+            content: content.into(),
             created_at: now,
             last_accessed: now,
             cues: Vec::new(),
             metadata: metadata.unwrap_or_default(),
             stats: T::default(),
+            links: Vec::new(),
         }
     }
     
+    /// Pinned memories are never pruned or merged (see `PINNED_METADATA_KEY`)
+    pub fn is_pinned(&self) -> bool {
+        self.metadata.get(PINNED_METADATA_KEY).and_then(|v| v.as_bool()).unwrap_or(false)
+    }
+
+    /// Move links a legacy snapshot kept in metadata to `links`. Metadata
+    /// under the key that isn't a list of links is left alone.
+    pub fn take_legacy_links(&mut self) {
+        let Some(value) = self.metadata.get(LEGACY_LINKS_METADATA_KEY) else {
+            return;
+        };
+        if let Ok(links) = serde_json::from_value::<Vec<MemoryLink>>(value.clone()) {
+            self.metadata.remove(LEGACY_LINKS_METADATA_KEY);
+            for link in links {
+                if !self.links.contains(&link) {
+                    self.links.push(link);
+                }
+            }
+        }
+    }
+
+    /// Where the memory was ingested from (see `PROVENANCE_METADATA_KEY`);
+    /// None for memories added directly
+    pub fn provenance(&self) -> Option<Provenance> {
+        self.metadata
+            .get(PROVENANCE_METADATA_KEY)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+    }
+
     pub fn touch(&mut self) {
         self.last_accessed = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -196,23 +272,7 @@ impl<T: Default> Memory<T> {
     /// 1. Checks if data is Zstd compressed (Magic Bytes). If so, just decompress.
     /// 2. If not, assumes Encrypted. Tries to decrypt using key, then decompress.
     pub fn access_content(&self, key: Option<&EncryptionKey>) -> Result<String, String> {
-        // 1. Try to detect if it's just compressed (not encrypted)
-        if crypto::is_compressed(&self.content) {
-            let bytes = crypto::decompress(&self.content)
-                .map_err(|e| format!("Decompression failed (plaintext): {}", e))?;
-            return String::from_utf8(bytes).map_err(|e| format!("Invalid UTF-8: {}", e));
-        }
-        
-        // 2. Fallback: Assume Encrypted
-        // If content is not Zstd magic bytes, it must be encrypted (unless it's garbage)
-        let k = key.ok_or_else(|| "Memory appears encrypted (no magic bytes) but no key provided".to_string())?;
-        
-        let compressed = crypto::decrypt(&self.content, k)?;
-        // The decrypted payload MUST be compressed zstd data
-        let bytes = crypto::decompress(&compressed)
-            .map_err(|e| format!("Decompression failed (after decrypt): {}", e))?;
-            
-        String::from_utf8(bytes).map_err(|e| format!("Invalid UTF-8: {}", e))
+        decode_payload(&self.content.bytes()?, key)
     }
     
     /// Create payload from string (compress and optionally encrypt)
@@ -228,6 +288,27 @@ impl<T: Default> Memory<T> {
     }
 }
 
+/// Decode a stored payload (see `Memory::access_content`)
+pub fn decode_payload(content: &[u8], key: Option<&EncryptionKey>) -> Result<String, String> {
+    // 1. Try to detect if it's just compressed (not encrypted)
+    if crypto::is_compressed(content) {
+        let bytes = crypto::decompress(content)
+            .map_err(|e| format!("Decompression failed (plaintext): {}", e))?;
+        return String::from_utf8(bytes).map_err(|e| format!("Invalid UTF-8: {}", e));
+    }
+    
+    // 2. Fallback: Assume Encrypted
+    // If content is not Zstd magic bytes, it must be encrypted (unless it's garbage)
+    let k = key.ok_or_else(|| "Memory appears encrypted (no magic bytes) but no key provided".to_string())?;
+    
+    let compressed = crypto::decrypt(content, k)?;
+    // The decrypted payload MUST be compressed zstd data
+    let bytes = crypto::decompress(&compressed)
+        .map_err(|e| format!("Decompression failed (after decrypt): {}", e))?;
+        
+    String::from_utf8(bytes).map_err(|e| format!("Invalid UTF-8: {}", e))
+}
+
 /// Convenience: Memory<MainStats> can increment reinforcement_count on touch
 impl Memory<MainStats> {
     pub fn touch_and_reinforce(&mut self) {
@@ -236,6 +317,39 @@ impl Memory<MainStats> {
     }
 }
 
+// =============================================================================
+// Cue Fields
+// =============================================================================
+
+/// Document field a cue matched in, for BM25F-style boosts.
+/// Fields come from structural cues the chunkers attach (`title:…`, `header:…`, `path:…`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CueField {
+    Title,
+    Header,
+    Path,
+    Body,
+}
+
+impl CueField {
+    /// Field and value of a structural cue (`name:` and `symbol:` count as a title, `class:` as a header)
+    pub fn of_structural_cue(cue: &str) -> Option<(CueField, &str)> {
+        let (prefix, value) = cue.split_once(':')?;
+        let field = match prefix {
+            "title" | "name" | "symbol" => CueField::Title,
+            "header" | "class" | "selector" => CueField::Header,
+            "path" | "file" => CueField::Path,
+            _ => return None,
+        };
+        Some((field, value))
+    }
+}
+
+// =============================================================================
+// Ordered Set
+// =============================================================================
+
 /// Ordered set implementation using IndexSet for O(1) operations
 /// Most recent items are at the back (end)
 /// 
@@ -248,15 +362,23 @@ impl Memory<MainStats> {
 /// TODO: Optimize storage by interning UUID strings to u64 integers for V2.
 /// This would reduce memory overhead from ~5M string copies to ~5M u64s (8 bytes each)
 /// for a 1M memory dataset with 5 cues per memory.
+///
+/// Hot cues can be split into segments (`seal_if_longer`): `items` stays the
+/// newest segment and older ones are kept in `sealed`, oldest first. Writes only
+/// shift within the segment they touch, so a cue with millions of ids does not
+/// pay for its full length under the shard lock. Reads see one ordered list.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct OrderedSet {
     pub items: IndexSet<String, RandomState>,
+    #[serde(default)]
+    sealed: Vec<IndexSet<String, RandomState>>,
 }
 
 impl OrderedSet {
     pub fn new() -> Self {
         Self {
             items: IndexSet::with_hasher(RandomState::new()),
+            sealed: Vec::new(),
         }
     }
     
@@ -265,28 +387,65 @@ impl OrderedSet {
     pub fn add(&mut self, item: String) {
         // shift_remove is O(1) average case (hash lookup + swap with last)
         // insert is O(1) amortized
-        self.items.shift_remove(&item);
+        if !self.items.shift_remove(&item) {
+            self.remove_sealed(&item);
+        }
         self.items.insert(item);
     }
 
     /// Remove item from set - O(1) amortized
     pub fn remove(&mut self, item: &str) -> bool {
-        self.items.shift_remove(item)
+        self.items.shift_remove(item) || self.remove_sealed(item)
+    }
+
+    fn remove_sealed(&mut self, item: &str) -> bool {
+        let Some(pos) = self.sealed.iter().position(|segment| segment.contains(item)) else {
+            return false;
+        };
+        self.sealed[pos].shift_remove(item);
+        if self.sealed[pos].is_empty() {
+            self.sealed.remove(pos);
+        }
+        true
     }
     
     /// Move item to end (most recent position) - O(1) amortized
     /// This is the critical operation for reinforcement
     pub fn move_to_front(&mut self, item: &str) {
         // O(1) removal + O(1) insertion = O(1) total
-        if self.items.shift_remove(item) {
+        if self.remove(item) {
             self.items.insert(item.to_string());
         }
     }
+
+    pub fn contains(&self, item: &str) -> bool {
+        self.items.contains(item) || self.sealed.iter().any(|segment| segment.contains(item))
+    }
+
+    /// Replace `from` with `to` at the same position
+    pub fn rename(&mut self, from: &str, to: &str) -> bool {
+        if self.contains(to) {
+            return self.remove(from);
+        }
+        for segment in self.sealed.iter_mut().chain(std::iter::once(&mut self.items)) {
+            if let Some(index) = segment.get_index_of(from) {
+                segment.shift_remove_index(index);
+                segment.shift_insert(index, to.to_string());
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Items oldest -> newest
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &String> {
+        self.sealed.iter().flatten().chain(self.items.iter())
+    }
     
     /// Get items in reverse order (most recent first) - O(min(n, limit))
     /// Returns references to avoid cloning strings (zero-copy)
     pub fn get_recent(&self, limit: Option<usize>) -> Vec<&String> {
-        let iter = self.items.iter().rev();
+        let iter = self.iter().rev();
         
         match limit {
             Some(lim) => iter.take(lim).collect(),
@@ -297,7 +456,7 @@ impl OrderedSet {
     /// Get items as owned strings (for serialization)
     /// Only use when you need to own the strings
     pub fn get_recent_owned(&self, limit: Option<usize>) -> Vec<String> {
-        let iter = self.items.iter().rev();
+        let iter = self.iter().rev();
         
         match limit {
             Some(lim) => iter.take(lim).cloned().collect(),
@@ -305,19 +464,63 @@ impl OrderedSet {
         }
     }
 
-    /// Get the index of an item in the set - O(1)
+    /// Get the index of an item in the set - O(segments)
     /// Note: Returns index in insertion order (oldest -> newest)
     pub fn get_index_of(&self, item: &str) -> Option<usize> {
-        self.items.get_index_of(item)
+        let mut offset = 0;
+        for segment in &self.sealed {
+            if let Some(index) = segment.get_index_of(item) {
+                return Some(offset + index);
+            }
+            offset += segment.len();
+        }
+        self.items.get_index_of(item).map(|index| offset + index)
     }
     
     #[allow(dead_code)]
     pub fn len(&self) -> usize {
-        self.items.len()
+        self.items.len() + self.sealed.iter().map(|segment| segment.len()).sum::<usize>()
     }
     
     #[allow(dead_code)]
     pub fn is_empty(&self) -> bool {
-        self.items.is_empty()
+        self.items.is_empty() && self.sealed.is_empty()
+    }
+
+    /// Slots allocated across all segments
+    pub fn capacity(&self) -> usize {
+        self.items.capacity() + self.sealed.iter().map(|segment| segment.capacity()).sum::<usize>()
+    }
+
+    /// Number of segments (1 for an unsplit set)
+    pub fn segment_count(&self) -> usize {
+        self.sealed.len() + 1
+    }
+
+    /// Seal the newest segment once it holds more than `segment_size` ids.
+    /// Returns true when a new segment was started.
+    pub fn seal_if_longer(&mut self, segment_size: usize) -> bool {
+        if segment_size == 0 || self.items.len() <= segment_size {
+            return false;
+        }
+        let full = std::mem::replace(&mut self.items, IndexSet::with_hasher(RandomState::new()));
+        self.sealed.push(full);
+        true
+    }
+
+    /// Release spare capacity left behind by removals
+    pub fn shrink_to_fit(&mut self) {
+        self.items.shrink_to_fit();
+        for segment in &mut self.sealed {
+            segment.shrink_to_fit();
+        }
+    }
+
+    /// Approximate heap usage: entry slots (hash + String header + index slot) plus id bytes
+    pub fn heap_bytes(&self) -> usize {
+        let slot = std::mem::size_of::<String>() + 2 * std::mem::size_of::<usize>() + 1;
+        self.capacity() * slot
+            + self.iter().map(|id| id.capacity()).sum::<usize>()
+            + self.sealed.capacity() * std::mem::size_of::<IndexSet<String, RandomState>>()
     }
 }
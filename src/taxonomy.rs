@@ -1,14 +1,47 @@
+use crate::engine::wildcard_match;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+/// Longest `forbidden_patterns` entry, in characters
+pub const MAX_PATTERN_LEN: usize = 256;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Default, PartialEq)]
 pub struct Taxonomy {
+    /// Namespaces (`key` of `key:value`) allowed; empty allows any
     #[serde(default)]
     pub allowed_keys: Vec<String>,
     #[serde(default)]
     pub allowed_values: HashMap<String, Vec<String>>,
     #[serde(default)]
     pub allowed_value_prefixes: HashMap<String, Vec<String>>,
+    /// `*` wildcard patterns matched against the whole cue (`secret:*`, `*password*`)
+    #[serde(default)]
+    pub forbidden_patterns: Vec<String>,
+    /// Longest cue accepted, in characters (None = no limit)
+    #[serde(default)]
+    pub max_cue_length: Option<usize>,
+}
+
+impl Taxonomy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_cue_length == Some(0) {
+            return Err("max_cue_length must be at least 1".to_string());
+        }
+        for pattern in &self.forbidden_patterns {
+            if pattern.trim().is_empty() || pattern.chars().all(|c| c == '*') {
+                return Err(format!("Forbidden pattern '{}' would reject every cue", pattern));
+            }
+            if pattern.chars().count() > MAX_PATTERN_LEN {
+                return Err(format!("Forbidden patterns are limited to {} characters", MAX_PATTERN_LEN));
+            }
+        }
+        for key in self.allowed_values.keys().chain(self.allowed_value_prefixes.keys()) {
+            if key.is_empty() || key.contains(':') {
+                return Err(format!("Invalid key '{}' in allowed values", key));
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -29,6 +62,27 @@ pub fn validate_cues(cues: Vec<String>, taxonomy: &Taxonomy) -> ValidationReport
     let mut rejected = Vec::new();
 
     for cue in cues {
+        // 0. Limits that apply to plain cues too
+        if let Some(max) = taxonomy.max_cue_length {
+            let len = cue.chars().count();
+            if len > max {
+                rejected.push(RejectedCue {
+                    cue: cue.clone(),
+                    code: "too_long".to_string(),
+                    detail: format!("Cue is {} characters, max_cue_length is {}", len, max),
+                });
+                continue;
+            }
+        }
+        if let Some(pattern) = taxonomy.forbidden_patterns.iter().find(|p| wildcard_match(p, &cue)) {
+            rejected.push(RejectedCue {
+                cue: cue.clone(),
+                code: "forbidden_pattern".to_string(),
+                detail: format!("Cue matches forbidden pattern '{}'", pattern),
+            });
+            continue;
+        }
+
         // 1. Check format k:v
         let parts: Vec<&str> = cue.splitn(2, ':').collect();
         // Allow cues without keys (plain strings) based on new requirements
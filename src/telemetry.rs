@@ -0,0 +1,299 @@
+//! Request spans and access logs, and OpenTelemetry export of traces and
+//! metrics over OTLP (`otel` feature).
+//!
+//! Every API request runs in an `http.request` span carrying its request id
+//! (the caller's `X-Request-ID`, or a fresh one echoed back), route, project,
+//! status, latency and result count, and ends with a `request completed`
+//! event (target `cuemap::access`). Log lines written while serving the
+//! request carry the same fields.
+//!
+//! With `[telemetry] otlp_endpoint` set, the request span is exported too
+//! (continuing the caller's W3C `traceparent`), with the recall stages below
+//! it: `tokenize`, `normalize`, `expand`, then one `recall_pass` per hop
+//! holding the engine's `search` and `score`, and `rerank`. Every background
+//! job run gets a `job` span linked to the request that queued it. The
+//! `/metrics` values are exported as OTLP metrics.
+//!
+//! Stage and job spans are at debug level and only the OTLP layer enables
+//! them, so they cost nothing while export is off and never reach the logs.
+
+use crate::config::TelemetryConfig;
+use crate::jobs::JobQueue;
+use crate::metrics::MetricsCollector;
+use crate::multi_tenant::{validate_project_id, MultiTenantEngine};
+use axum::extract::{MatchedPath, RawPathParams, Request};
+use axum::http::HeaderValue;
+use axum::middleware::Next;
+use axum::response::Response;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{Instrument, Subscriber};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// The tracing layer exporting spans, when an endpoint is configured. Errors
+/// (a bad config, or a build without the `otel` feature) leave export off.
+pub fn tracing_layer<S>(config: &TelemetryConfig) -> Result<Option<Box<dyn Layer<S> + Send + Sync>>, String>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let Some(endpoint) = config.otlp_endpoint.as_deref() else {
+        return Ok(None);
+    };
+    config.validate()?;
+    #[cfg(feature = "otel")]
+    {
+        otlp::tracing_layer(config, endpoint).map(Some)
+    }
+    #[cfg(not(feature = "otel"))]
+    {
+        Err(format!("otlp_endpoint is set to {} but this build lacks the `otel` feature", endpoint))
+    }
+}
+
+/// Export the `/metrics` values every `metrics_interval_seconds`. A no-op
+/// without an endpoint, an interval or the `otel` feature.
+#[cfg_attr(not(feature = "otel"), allow(unused_variables))]
+pub fn start_metrics(
+    config: &TelemetryConfig,
+    mt_engine: Arc<MultiTenantEngine>,
+    metrics: Arc<MetricsCollector>,
+    job_queue: Arc<JobQueue>,
+) -> Result<(), String> {
+    let Some(endpoint) = config.otlp_endpoint.as_deref() else {
+        return Ok(());
+    };
+    if config.metrics_interval_seconds == 0 {
+        return Ok(());
+    }
+    #[cfg(feature = "otel")]
+    otlp::start_metrics(config, endpoint, mt_engine, metrics, job_queue)?;
+    Ok(())
+}
+
+/// Flush spans and metrics still buffered for export (on shutdown)
+pub fn shutdown() {
+    #[cfg(feature = "otel")]
+    otlp::shutdown();
+}
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// A caller's request id, if it is safe to log and echo: 1-128 characters of
+/// `[A-Za-z0-9._:-]`
+fn caller_request_id(req: &Request) -> Option<String> {
+    let id = req.headers().get(REQUEST_ID_HEADER)?.to_str().ok()?;
+    let valid = (1..=128).contains(&id.len())
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | ':' | '-'));
+    valid.then(|| id.to_string())
+}
+
+/// The project a request targets: its `X-Project-ID` header or the `:id` of a `/projects/:id` route
+fn request_project_id(req: &Request, route: Option<&str>, params: Option<&RawPathParams>) -> Option<String> {
+    let header = req.headers().get("X-Project-ID").and_then(|v| v.to_str().ok());
+    let from_path = || {
+        route.filter(|r| r.starts_with("/projects/:id"))?;
+        params?.iter().find(|(name, _)| *name == "id").map(|(_, value)| value)
+    };
+    header.or_else(from_path).filter(|id| validate_project_id(id)).map(String::from)
+}
+
+/// Middleware giving each API request an id and its root span, and logging its completion
+pub async fn trace_request(params: Option<RawPathParams>, req: Request, next: Next) -> Response {
+    let start = Instant::now();
+    let request_id = caller_request_id(&req).unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string());
+    let method = req.method().clone();
+    let route = req.extensions().get::<MatchedPath>().map(|p| p.as_str().to_string());
+    let span = tracing::info_span!(
+        "http.request",
+        otel.name = tracing::field::Empty,
+        otel.kind = tracing::field::Empty,
+        request_id = %request_id,
+        method = %method,
+        route = tracing::field::Empty,
+        project_id = tracing::field::Empty,
+        status = tracing::field::Empty,
+        latency_ms = tracing::field::Empty,
+        results = tracing::field::Empty,
+    );
+
+    let mut response = if span.is_disabled() {
+        next.run(req).await
+    } else {
+        span.record("route", route.as_deref().unwrap_or(req.uri().path()));
+        if let Some(project_id) = request_project_id(&req, route.as_deref(), params.as_ref()) {
+            span.record("project_id", project_id.as_str());
+        }
+        #[cfg(feature = "otel")]
+        otlp::export_request_span(&span, &method, route.as_deref(), req.headers());
+
+        let response = next.run(req).instrument(span.clone()).await;
+        span.record("status", response.status().as_u16());
+        span.record("latency_ms", (start.elapsed().as_secs_f64() * 1000.0 * 100.0).round() / 100.0);
+        span.in_scope(|| tracing::info!(target: "cuemap::access", "request completed"));
+        response
+    };
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+/// Record how many results a handler returns on its request span (for the access log)
+pub fn record_results(count: usize) {
+    tracing::Span::current().record("results", count);
+}
+
+#[cfg(feature = "otel")]
+mod otlp {
+    use super::*;
+    use opentelemetry::metrics::{Meter, MeterProvider as _};
+    use opentelemetry::propagation::Extractor;
+    use opentelemetry::{global, KeyValue};
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::metrics::SdkMeterProvider;
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use opentelemetry_sdk::trace::{self as sdktrace, Sampler};
+    use opentelemetry_sdk::{runtime, Resource};
+    use std::sync::atomic::Ordering;
+    use std::sync::OnceLock;
+    use std::time::Duration;
+    use tracing::Level;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+    use tracing_subscriber::filter::Targets;
+
+    static METER_PROVIDER: OnceLock<SdkMeterProvider> = OnceLock::new();
+
+    fn resource(config: &TelemetryConfig) -> Resource {
+        Resource::new(vec![
+            KeyValue::new("service.name", config.service_name.clone()),
+            KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+        ])
+    }
+
+    pub fn tracing_layer<S>(config: &TelemetryConfig, endpoint: &str) -> Result<Box<dyn Layer<S> + Send + Sync>, String>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(config.sample_ratio)));
+        let tracer = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint))
+            .with_trace_config(sdktrace::config().with_sampler(sampler).with_resource(resource(config)))
+            .install_batch(runtime::Tokio)
+            .map_err(|e| format!("Failed to start OTLP trace export to {}: {}", endpoint, e))?;
+        global::set_text_map_propagator(TraceContextPropagator::new());
+
+        // Our own spans, including the debug-level stage spans the logs leave out
+        let filter = Targets::new().with_target("cuemap", Level::DEBUG);
+        Ok(tracing_opentelemetry::layer().with_tracer(tracer).with_filter(filter).boxed())
+    }
+
+    struct HeaderExtractor<'a>(&'a axum::http::HeaderMap);
+
+    impl Extractor for HeaderExtractor<'_> {
+        fn get(&self, key: &str) -> Option<&str> {
+            self.0.get(key).and_then(|v| v.to_str().ok())
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            self.0.keys().map(|k| k.as_str()).collect()
+        }
+    }
+
+    /// Name the request span after its route (the `otel.*` fields stay out of
+    /// builds without export) and parent it on the caller's `traceparent`, if any
+    pub fn export_request_span(span: &tracing::Span, method: &axum::http::Method, route: Option<&str>, headers: &axum::http::HeaderMap) {
+        let name = match route {
+            Some(route) => format!("{} {}", method, route),
+            None => method.to_string(),
+        };
+        span.record("otel.name", name.as_str());
+        span.record("otel.kind", "server");
+        let parent = global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)));
+        span.set_parent(parent);
+    }
+
+    fn gauge(meter: &Meter, name: &'static str, description: &'static str, value: impl Fn() -> u64 + Send + Sync + 'static) {
+        meter
+            .u64_observable_gauge(name)
+            .with_description(description)
+            .with_callback(move |observer| observer.observe(value(), &[]))
+            .init();
+    }
+
+    fn gauge_f64(meter: &Meter, name: &'static str, description: &'static str, value: impl Fn() -> f64 + Send + Sync + 'static) {
+        meter
+            .f64_observable_gauge(name)
+            .with_description(description)
+            .with_callback(move |observer| observer.observe(value(), &[]))
+            .init();
+    }
+
+    fn counter(meter: &Meter, name: &'static str, description: &'static str, value: impl Fn() -> u64 + Send + Sync + 'static) {
+        meter
+            .u64_observable_counter(name)
+            .with_description(description)
+            .with_callback(move |observer| observer.observe(value(), &[]))
+            .init();
+    }
+
+    fn global_stat(mt_engine: &MultiTenantEngine, key: &str) -> u64 {
+        mt_engine.get_global_stats().get(key).and_then(|v| v.as_u64()).unwrap_or(0)
+    }
+
+    /// The same names and meanings as the Prometheus `/metrics` endpoint
+    pub fn start_metrics(
+        config: &TelemetryConfig,
+        endpoint: &str,
+        mt_engine: Arc<MultiTenantEngine>,
+        metrics: Arc<MetricsCollector>,
+        job_queue: Arc<JobQueue>,
+    ) -> Result<(), String> {
+        let provider = opentelemetry_otlp::new_pipeline()
+            .metrics(runtime::Tokio)
+            .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint))
+            .with_resource(resource(config))
+            .with_period(Duration::from_secs(config.metrics_interval_seconds))
+            .build()
+            .map_err(|e| format!("Failed to start OTLP metrics export to {}: {}", endpoint, e))?;
+        let meter = provider.meter("cuemap");
+
+        let m = metrics.clone();
+        counter(&meter, "cuemap_ingestion_rate", "Total memory ingestions since startup", move || {
+            m.ingestion_count.load(Ordering::Relaxed)
+        });
+        let m = metrics.clone();
+        counter(&meter, "cuemap_recall_requests_total", "Total recall requests since startup", move || {
+            m.recall_count.load(Ordering::Relaxed)
+        });
+        let m = metrics.clone();
+        gauge_f64(&meter, "cuemap_recall_latency_p99", "P99 recall latency in milliseconds", move || m.get_p99_latency());
+        gauge_f64(&meter, "cuemap_recall_latency_avg", "Average recall latency in milliseconds", move || {
+            metrics.get_avg_latency()
+        });
+        gauge(&meter, "cuemap_memory_usage_bytes", "Process memory usage in bytes (RSS)", crate::metrics::get_memory_usage_bytes);
+        let engine = mt_engine.clone();
+        gauge(&meter, "cuemap_total_memories", "Total memories across all projects", move || {
+            global_stat(&engine, "total_memories")
+        });
+        let engine = mt_engine.clone();
+        gauge(&meter, "cuemap_lexicon_size", "Total cues/terms in lexicon", move || global_stat(&engine, "total_cues"));
+        gauge(&meter, "cuemap_total_projects", "Number of active projects", move || {
+            global_stat(&mt_engine, "total_projects")
+        });
+        gauge(&meter, "cuemap_active_jobs", "Current pending background jobs", move || job_queue.pending_count() as u64);
+
+        let _ = METER_PROVIDER.set(provider);
+        Ok(())
+    }
+
+    pub fn shutdown() {
+        global::shutdown_tracer_provider();
+        if let Some(provider) = METER_PROVIDER.get() {
+            if let Err(e) = provider.shutdown() {
+                tracing::warn!("Failed to flush OTLP metrics: {}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Token counters for grounded recall budgets.
+//!
+//! `GroundingEngine::select_memories` packs memories until the caller's
+//! `token_budget` is spent, so the count has to match the model the context is
+//! sent to. The counter is picked from `model` on `/recall/grounded`:
+//! - `chars`: 1 token per 4 bytes (used when no model is given)
+//! - `bpe`: tiktoken-style estimate for OpenAI GPT models (`gpt-*`, `o1`, ...)
+//! - `whitespace`: 4 tokens per 3 words, for any other model
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+pub trait Tokenizer: Send + Sync {
+    /// Short name reported in the grounding proof
+    fn name(&self) -> &'static str;
+
+    fn count_tokens(&self, text: &str) -> u32;
+}
+
+/// Counter for `model`; `None` keeps the byte heuristic
+pub fn tokenizer_for_model(model: Option<&str>) -> Box<dyn Tokenizer> {
+    let Some(model) = model.map(|m| m.trim().to_lowercase()).filter(|m| !m.is_empty()) else {
+        return Box::new(CharTokenizer);
+    };
+    match model.as_str() {
+        "chars" => Box::new(CharTokenizer),
+        "bpe" => Box::new(BpeTokenizer),
+        "whitespace" => Box::new(WhitespaceTokenizer),
+        m if is_openai_model(m) => Box::new(BpeTokenizer),
+        _ => Box::new(WhitespaceTokenizer),
+    }
+}
+
+fn is_openai_model(model: &str) -> bool {
+    let model = model.rsplit('/').next().unwrap_or(model);
+    ["gpt-", "gpt4", "chatgpt", "o1", "o3", "o4", "text-embedding-", "davinci", "babbage"]
+        .iter()
+        .any(|prefix| model.starts_with(prefix))
+}
+
+/// 1 token ~= 4 bytes, the original grounding estimate
+pub struct CharTokenizer;
+
+impl Tokenizer for CharTokenizer {
+    fn name(&self) -> &'static str {
+        "chars"
+    }
+
+    fn count_tokens(&self, text: &str) -> u32 {
+        ((text.len() as f64) / 4.0).ceil() as u32
+    }
+}
+
+/// Words counted on whitespace, scaled by 4/3 for sub-word splits
+pub struct WhitespaceTokenizer;
+
+impl Tokenizer for WhitespaceTokenizer {
+    fn name(&self) -> &'static str {
+        "whitespace"
+    }
+
+    fn count_tokens(&self, text: &str) -> u32 {
+        let words = text.split_whitespace().count() as u32;
+        (words * 4).div_ceil(3)
+    }
+}
+
+/// Estimate for GPT byte-pair encodings (cl100k/o200k).
+///
+/// Text is split with the same pre-tokenizer pattern tiktoken uses
+/// (contractions, letter runs, numbers in groups of up to three digits,
+/// punctuation runs, whitespace). Merges never cross those pieces, so each
+/// piece is then costed from its shape instead of shipping the merge table:
+/// common words are a single token, long words split roughly every eight
+/// letters and non-Latin scripts cost about one token per three UTF-8 bytes.
+pub struct BpeTokenizer;
+
+fn pretokenizer() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"(?i:'s|'t|'re|'ve|'m|'ll|'d)| ?\p{L}+| ?\p{N}{1,3}| ?[^\s\p{L}\p{N}]+|\s+")
+            .expect("valid pre-tokenizer pattern")
+    })
+}
+
+impl Tokenizer for BpeTokenizer {
+    fn name(&self) -> &'static str {
+        "bpe"
+    }
+
+    fn count_tokens(&self, text: &str) -> u32 {
+        pretokenizer()
+            .find_iter(text)
+            .map(|piece| piece_tokens(piece.as_str()))
+            .sum()
+    }
+}
+
+fn piece_tokens(piece: &str) -> u32 {
+    let body = piece.strip_prefix(' ').unwrap_or(piece);
+    let Some(first) = body.chars().next() else {
+        // A lone space
+        return 1;
+    };
+    if first.is_whitespace() || first == '\'' || first.is_numeric() {
+        return 1;
+    }
+    if first.is_alphabetic() {
+        if body.is_ascii() {
+            return 1 + (body.len() as u32 - 1) / 8;
+        }
+        return (body.len() as u32).div_ceil(3);
+    }
+    (body.chars().count() as u32).div_ceil(2)
+}
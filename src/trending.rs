@@ -0,0 +1,56 @@
+//! Trending cues: lexicon entries reinforced unusually often in the last
+//! minutes, from the minute and day buckets of `LexiconStats`.
+//!
+//! `GET /trending` reports them; the heatmap sync (`[jobs]
+//! market_heatmap_interval_seconds`) turns them into market heatmap lifts.
+
+use serde::Serialize;
+
+/// Window of `GET /trending` and the heatmap sync unless configured otherwise
+pub const DEFAULT_TRENDING_WINDOW_MINS: u32 = 15;
+
+/// Minute buckets are kept for an hour, which bounds the window
+pub const MAX_TRENDING_WINDOW_MINS: u32 = 60;
+
+/// Days of daily buckets averaged into a cue's baseline
+pub const BASELINE_DAYS: u32 = 7;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TrendingCue {
+    /// Canonical cue (lexicon entry id without its `cue:` prefix)
+    pub cue: String,
+    /// Reinforcements within the window
+    pub count: u64,
+    /// Reinforcements per minute within the window
+    pub velocity: f64,
+    /// Reinforcements per minute over the previous days (up to `BASELINE_DAYS`;
+    /// 0 for cues first seen today)
+    pub baseline: f64,
+    /// `velocity / baseline`, absent without a baseline
+    pub lift: Option<f64>,
+}
+
+/// Parse a window such as `15m`, `1h` or `30` (minutes)
+pub fn parse_window(window: &str) -> Result<u32, String> {
+    let window = window.trim();
+    let (number, scale) = if let Some(hours) = window.strip_suffix('h') {
+        (hours, 60)
+    } else {
+        (window.strip_suffix('m').unwrap_or(window), 1)
+    };
+    let mins = number
+        .parse::<u32>()
+        .ok()
+        .and_then(|n| n.checked_mul(scale))
+        .ok_or_else(|| format!("Invalid window '{}' (expected e.g. 15m or 1h)", window))?;
+    if mins == 0 || mins > MAX_TRENDING_WINDOW_MINS {
+        return Err(format!("window must be between 1m and {}m", MAX_TRENDING_WINDOW_MINS));
+    }
+    Ok(mins)
+}
+
+/// Market heatmap lift for a cue reinforced `count` times in the window:
+/// log10(1 + count), capped at 2.0 so trends can't override relevance
+pub fn heatmap_lift(count: u64) -> f32 {
+    ((1.0 + count as f64).log10() as f32).min(2.0)
+}
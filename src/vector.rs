@@ -0,0 +1,168 @@
+//! Embedding index used by hybrid recall.
+//!
+//! Vectors are either supplied by the caller (any model, any dimension) or
+//! computed by the project's embedding provider (`crate::embeddings`). All vectors in one
+//! index share the dimension of the first vector stored. They are kept
+//! L2-normalized, so cosine similarity is a dot product, and searched exactly
+//! (brute force over all vectors), which is fine for per-project corpora.
+
+use ahash::RandomState;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Hybrid scoring request for `CueMapEngine::recall_weighted`
+#[derive(Debug, Clone)]
+pub struct HybridQuery {
+    pub vector: Vec<f32>,
+    /// Weight of cosine similarity in the blend; the cue score gets `1 - alpha`
+    pub alpha: f64,
+}
+
+/// On-disk form of a `VectorIndex` (`{project}_vectors.bin`)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct VectorSnapshot {
+    pub dims: usize,
+    pub vectors: HashMap<String, Vec<f32>>,
+}
+
+pub struct VectorIndex {
+    vectors: DashMap<String, Vec<f32>, RandomState>,
+    // 0 until the first vector is stored
+    dims: AtomicUsize,
+}
+
+impl Default for VectorIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VectorIndex {
+    pub fn new() -> Self {
+        Self {
+            vectors: DashMap::with_hasher(RandomState::new()),
+            dims: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn from_snapshot(snapshot: VectorSnapshot) -> Self {
+        let index = Self::new();
+        index.dims.store(snapshot.dims, Ordering::Relaxed);
+        for (id, vector) in snapshot.vectors {
+            if vector.len() == snapshot.dims {
+                index.vectors.insert(id, vector);
+            }
+        }
+        index
+    }
+
+    pub fn to_snapshot(&self) -> VectorSnapshot {
+        VectorSnapshot {
+            dims: self.dims(),
+            vectors: self.vectors.iter().map(|e| (e.key().clone(), e.value().clone())).collect(),
+        }
+    }
+
+    pub fn dims(&self) -> usize {
+        self.dims.load(Ordering::Relaxed)
+    }
+
+    pub fn len(&self) -> usize {
+        self.vectors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vectors.is_empty()
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.vectors.contains_key(id)
+    }
+
+    /// Check that `vector` could be stored or used as a query
+    pub fn validate(&self, vector: &[f32]) -> Result<(), String> {
+        normalize(vector.to_vec())?;
+        let dims = self.dims();
+        if dims != 0 && dims != vector.len() {
+            return Err(format!("Embedding has {} dimensions, index expects {}", vector.len(), dims));
+        }
+        Ok(())
+    }
+
+    /// Store (or replace) a vector. Fails on a dimension mismatch or a zero vector.
+    pub fn insert(&self, id: &str, vector: Vec<f32>) -> Result<(), String> {
+        let vector = normalize(vector)?;
+        match self.dims.compare_exchange(0, vector.len(), Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => {}
+            Err(dims) if dims == vector.len() => {}
+            Err(dims) => {
+                return Err(format!("Embedding has {} dimensions, index expects {}", vector.len(), dims));
+            }
+        }
+        self.vectors.insert(id.to_string(), vector);
+        Ok(())
+    }
+
+    pub fn remove(&self, id: &str) -> bool {
+        self.vectors.remove(id).is_some()
+    }
+
+    /// Move the vector stored for `from` to `to`
+    pub fn rename(&self, from: &str, to: &str) -> bool {
+        match self.vectors.remove(from) {
+            Some((_, vector)) => {
+                self.vectors.insert(to.to_string(), vector);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove all vectors; the next insert sets the dimension again
+    pub fn clear(&self) {
+        self.vectors.clear();
+        self.dims.store(0, Ordering::Relaxed);
+    }
+
+    /// Cosine similarity between `query` and the vector stored for `id`
+    pub fn similarity(&self, query: &[f32], id: &str) -> Option<f32> {
+        let query = normalize(query.to_vec()).ok()?;
+        let stored = self.vectors.get(id)?;
+        (stored.len() == query.len()).then(|| dot(&query, &stored))
+    }
+
+    /// The `k` most similar vectors to `query`, best first
+    pub fn search(&self, query: &[f32], k: usize) -> Result<Vec<(String, f32)>, String> {
+        self.validate(query)?;
+        let query = normalize(query.to_vec())?;
+
+        let mut scored: Vec<(String, f32)> = self
+            .vectors
+            .iter()
+            .map(|e| (e.key().clone(), dot(&query, e.value())))
+            .collect();
+        scored.sort_unstable_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(k);
+        Ok(scored)
+    }
+}
+
+fn normalize(mut vector: Vec<f32>) -> Result<Vec<f32>, String> {
+    if vector.is_empty() {
+        return Err("Embedding is empty".to_string());
+    }
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if !norm.is_finite() || norm < 1e-12 {
+        return Err("Embedding has zero or non-finite length".to_string());
+    }
+    for x in vector.iter_mut() {
+        *x /= norm;
+    }
+    Ok(vector)
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
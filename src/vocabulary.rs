@@ -0,0 +1,232 @@
+//! Lexicon and alias import/export as editable YAML.
+//!
+//! A project's vocabulary can be reviewed like code and used to seed new
+//! projects instead of relearning it from scratch:
+//!
+//! ```yaml
+//! lexicon:            # canonical cue -> tokens that resolve to it
+//!   service:payment:
+//!   - pay
+//!   - stripe
+//! ```
+//!
+//! ```yaml
+//! aliases:
+//! - from: pay
+//!   to: service:payment
+//!   weight: 0.9
+//!   status: active    # active, proposed or rejected
+//!   reason: manual
+//! ```
+
+use crate::engine::CueMapEngine;
+use crate::projects::{alias_cues, ProjectContext};
+use crate::structures::{LexiconStats, MainStats};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use uuid::Uuid;
+
+pub const ALIAS_STATUSES: [&str; 3] = ["active", "proposed", "rejected"];
+
+/// How an import treats what the project already has
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImportMode {
+    /// Add to the existing entries; tokens are only ever added
+    #[default]
+    Merge,
+    /// Drop every existing entry first
+    Replace,
+}
+
+impl ImportMode {
+    pub fn parse(mode: Option<&str>) -> Result<Self, String> {
+        match mode {
+            None | Some("merge") => Ok(ImportMode::Merge),
+            Some("replace") => Ok(ImportMode::Replace),
+            Some(other) => Err(format!("Unknown import mode '{}' (merge or replace)", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LexiconFile {
+    #[serde(default)]
+    pub lexicon: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AliasEntry {
+    pub from: String,
+    pub to: String,
+    #[serde(default = "default_alias_weight")]
+    pub weight: f64,
+    #[serde(default = "default_alias_status")]
+    pub status: String,
+    #[serde(default = "default_alias_reason")]
+    pub reason: String,
+}
+
+fn default_alias_weight() -> f64 {
+    0.85
+}
+
+fn default_alias_status() -> String {
+    "active".to_string()
+}
+
+fn default_alias_reason() -> String {
+    "import".to_string()
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AliasFile {
+    #[serde(default)]
+    pub aliases: Vec<AliasEntry>,
+}
+
+/// The lexicon grouped by canonical cue, tokens sorted
+pub fn export_lexicon(ctx: &ProjectContext) -> LexiconFile {
+    export_lexicon_engine(&ctx.lexicon)
+}
+
+/// Any lexicon engine (a project's or the organization's) grouped by canonical cue
+pub fn export_lexicon_engine(lexicon: &CueMapEngine<LexiconStats>) -> LexiconFile {
+    let key = lexicon.get_master_key();
+    let mut grouped: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for entry in lexicon.get_memories().iter() {
+        let Ok(canonical) = entry.value().access_content(key.as_deref()) else {
+            continue;
+        };
+        grouped.entry(canonical).or_default().extend(entry.value().cues.iter().cloned());
+    }
+    LexiconFile {
+        lexicon: grouped.into_iter().map(|(canonical, tokens)| (canonical, tokens.into_iter().collect())).collect(),
+    }
+}
+
+/// Load a lexicon file. The whole file is checked before anything changes.
+/// Returns the number of canonical cues written.
+pub fn import_lexicon(ctx: &ProjectContext, file: LexiconFile, mode: ImportMode) -> Result<usize, String> {
+    let count = import_lexicon_engine(&ctx.lexicon, file, mode)?;
+    if mode == ImportMode::Replace {
+        ctx.lexicon_quality.clear();
+    }
+    ctx.query_cache.clear();
+    Ok(count)
+}
+
+/// `import_lexicon` for any lexicon engine; callers clear the query caches that depend on it
+pub fn import_lexicon_engine(lexicon: &CueMapEngine<LexiconStats>, file: LexiconFile, mode: ImportMode) -> Result<usize, String> {
+    let mut entries = Vec::with_capacity(file.lexicon.len());
+    for (canonical, tokens) in file.lexicon {
+        let canonical = canonical.trim().to_lowercase();
+        let tokens: Vec<String> = tokens.iter().map(|t| t.trim().to_lowercase()).collect();
+        if canonical.is_empty() {
+            return Err("Lexicon entries need a canonical cue".to_string());
+        }
+        if tokens.is_empty() || tokens.iter().any(|t| t.is_empty()) {
+            return Err(format!("Lexicon entry '{}' needs non-empty tokens", canonical));
+        }
+        entries.push((canonical, tokens));
+    }
+
+    if mode == ImportMode::Replace {
+        let ids: Vec<String> = lexicon.get_memories().iter().map(|e| e.key().clone()).collect();
+        for id in &ids {
+            lexicon.delete_memory(id);
+        }
+    }
+
+    // Merge into the entry that already holds a canonical cue, whatever its id
+    let key = lexicon.get_master_key();
+    let existing: HashMap<String, String> = lexicon
+        .get_memories()
+        .iter()
+        .filter_map(|e| e.value().access_content(key.as_deref()).ok().map(|c| (c, e.key().clone())))
+        .collect();
+
+    let count = entries.len();
+    for (canonical, tokens) in entries {
+        let id = existing.get(&canonical).cloned().unwrap_or_else(|| canonical.clone());
+        lexicon.upsert_memory_with_id(id, canonical, tokens, None, Some(LexiconStats::default()), false, false);
+    }
+    Ok(count)
+}
+
+/// Every alias, including proposed ones and rejected tombstones, sorted by target
+pub fn export_aliases(ctx: &ProjectContext) -> AliasFile {
+    let ids: Vec<String> = ctx.aliases.get_memories().iter().map(|e| e.key().clone()).collect();
+    let mut aliases: Vec<AliasEntry> = ids
+        .iter()
+        .filter_map(|id| ctx.alias(id))
+        .filter_map(|data| {
+            Some(AliasEntry {
+                from: data.get("from")?.as_str()?.to_string(),
+                to: data.get("to")?.as_str()?.to_string(),
+                weight: data.get("downweight").and_then(|v| v.as_f64()).unwrap_or_else(default_alias_weight),
+                status: data.get("status").and_then(|v| v.as_str()).map(String::from).unwrap_or_else(default_alias_status),
+                reason: data.get("reason").and_then(|v| v.as_str()).map(String::from).unwrap_or_else(default_alias_reason),
+            })
+        })
+        .collect();
+    aliases.sort_by(|a, b| (&a.to, &a.from).cmp(&(&b.to, &b.from)));
+    aliases.dedup_by(|a, b| a.from == b.from && a.to == b.to);
+    AliasFile { aliases }
+}
+
+/// Load an alias file. The whole file is checked before anything changes.
+/// Returns the number of aliases written.
+pub fn import_aliases(ctx: &ProjectContext, file: AliasFile, mode: ImportMode) -> Result<usize, String> {
+    for alias in &file.aliases {
+        if alias.from.trim().is_empty() || alias.to.trim().is_empty() {
+            return Err("Aliases need both `from` and `to`".to_string());
+        }
+        if !(alias.weight > 0.0 && alias.weight <= 1.0) {
+            return Err(format!("Alias {} -> {}: weight must be in (0, 1]", alias.from, alias.to));
+        }
+        if !ALIAS_STATUSES.contains(&alias.status.as_str()) {
+            return Err(format!("Alias {} -> {}: unknown status '{}'", alias.from, alias.to, alias.status));
+        }
+    }
+
+    let ids: Vec<String> = ctx.aliases.get_memories().iter().map(|e| e.key().clone()).collect();
+    if mode == ImportMode::Replace {
+        for id in &ids {
+            ctx.aliases.delete_memory(id);
+        }
+    }
+
+    // Update the entry that already holds a pair, whatever its id
+    let existing: HashMap<(String, String), String> = if mode == ImportMode::Merge {
+        ids.iter()
+            .filter_map(|id| {
+                let data = ctx.alias(id)?;
+                let pair = (data.get("from")?.as_str()?.to_string(), data.get("to")?.as_str()?.to_string());
+                Some((pair, id.clone()))
+            })
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
+    let count = file.aliases.len();
+    for alias in file.aliases {
+        let pair = (alias.from.trim().to_string(), alias.to.trim().to_string());
+        // Same id as the alias job would give the pair
+        let id = existing
+            .get(&pair)
+            .cloned()
+            .unwrap_or_else(|| Uuid::new_v5(&Uuid::NAMESPACE_OID, format!("{}->{}", pair.0, pair.1).as_bytes()).to_string());
+        let content = serde_json::json!({
+            "from": pair.0,
+            "to": pair.1,
+            "downweight": alias.weight,
+            "status": alias.status,
+            "reason": alias.reason,
+        })
+        .to_string();
+        let cues = alias_cues(&id, &alias.status, &alias.reason, &pair.0, &pair.1);
+        ctx.aliases.upsert_memory_with_id(id, content, cues, None, Some(MainStats::default()), false, true);
+    }
+    Ok(count)
+}
@@ -0,0 +1,81 @@
+use cuemap::agent::chat::{chunk_conversation, parse_plain, parse_transcript, Conversation, Turn, MAX_WINDOW_TURNS, TOPIC_GAP_SECS};
+use cuemap::agent::chunker::{ChunkCategory, Chunker, ChunkerType};
+use std::path::Path;
+
+#[test]
+fn test_chatgpt_export_follows_current_branch() {
+    let export = r#"[{
+        "title": "Rust lifetimes",
+        "current_node": "c",
+        "mapping": {
+            "root": {"message": null, "parent": null},
+            "s": {"parent": "root", "message": {"author": {"role": "system"}, "content": {"parts": ["You are helpful"]}}},
+            "a": {"parent": "s", "message": {"author": {"role": "user"}, "create_time": 1767225600.5, "content": {"parts": ["Why does the borrow checker complain?"]}}},
+            "old": {"parent": "a", "message": {"author": {"role": "assistant"}, "content": {"parts": ["An abandoned answer"]}}},
+            "c": {"parent": "a", "message": {"author": {"role": "assistant"}, "create_time": 1767225610.0, "content": {"parts": ["The reference outlives its owner."]}}}
+        }
+    }]"#;
+    let path = Path::new("export/conversations.json");
+    assert_eq!(Chunker::detect_type(path), Some(ChunkerType::Chat));
+
+    let conversations = parse_transcript(path, export).unwrap();
+    assert_eq!(conversations.len(), 1);
+    let turns: Vec<&str> = conversations[0].turns.iter().map(|t| t.speaker.as_str()).collect();
+    assert_eq!(turns, vec!["User", "Assistant"]);
+
+    let chunks = Chunker::chunk_file(path, export);
+    assert_eq!(chunks.len(), 1);
+    assert!(!chunks[0].content.contains("abandoned"));
+    assert_eq!(chunks[0].category, ChunkCategory::Conversation);
+    for cue in ["platform:chatgpt", "speaker:user", "speaker:assistant", "turn:1", "date:2026-01-01", "title:Rust lifetimes"] {
+        assert!(chunks[0].structural_cues.iter().any(|c| c == cue), "missing {}", cue);
+    }
+}
+
+#[test]
+fn test_slack_export_skips_joins() {
+    let day = r#"[
+        {"type": "message", "subtype": "channel_join", "ts": "1767225600.000100", "user": "U1", "text": "<@U1> has joined the channel"},
+        {"type": "message", "ts": "1767225700.000200", "user": "U1", "user_profile": {"real_name": "Ada Lovelace"}, "text": "Deploy is blocked on the migration"},
+        {"type": "message", "ts": "1767225800.000300", "user": "U2", "text": "I'll look at it"}
+    ]"#;
+    let path = Path::new("slack-export/deploys/2026-01-01.json");
+    let chunks = Chunker::chunk_file(path, day);
+    assert_eq!(chunks.len(), 1);
+    assert_eq!(chunks[0].content, "Ada Lovelace: Deploy is blocked on the migration\nU2: I'll look at it");
+    for cue in ["platform:slack", "speaker:ada_lovelace", "speaker:u2", "title:deploys"] {
+        assert!(chunks[0].structural_cues.iter().any(|c| c == cue), "missing {}", cue);
+    }
+}
+
+#[test]
+fn test_plain_log_detection() {
+    let log = "[09:00] Alice: morning, is the build green?\nBob: not yet\nthe flaky test again\nAlice: I'll retry it\n";
+    let conversation = parse_plain(log).unwrap();
+    assert_eq!(conversation.turns.len(), 3);
+    assert_eq!(conversation.turns[1].text, "not yet\nthe flaky test again");
+
+    let chunks = Chunker::chunk_file(Path::new("standup.txt"), log);
+    assert!(chunks[0].structural_cues.iter().any(|c| c == "speaker:bob"));
+
+    // Prose with the odd colon, one speaker, and log levels aren't chats
+    assert!(parse_plain("Note: read this first.\nSome prose here.\nMore prose.\nAnd more.").is_none());
+    assert!(parse_plain("Alice: one\nAlice: two\nAlice: three").is_none());
+    assert!(parse_plain("INFO: started\nERROR: failed\nWARN: retrying\nINFO: done").is_none());
+    let log_file = Chunker::chunk_file(Path::new("server.log"), log);
+    assert!(log_file.iter().all(|c| c.category != ChunkCategory::Conversation));
+}
+
+#[test]
+fn test_windows_split_on_turn_count_and_gaps() {
+    let turn = |i: i64, time: i64| Turn { speaker: format!("p{}", i % 2), text: format!("message {}", i), time: Some(time) };
+    let mut turns: Vec<Turn> = (0..10).map(|i| turn(i, i * 60)).collect();
+    // A long pause before the last two turns
+    turns.push(turn(10, 9 * 60 + TOPIC_GAP_SECS));
+    let conversation = Conversation { title: None, platform: "chat", turns };
+
+    let chunks = chunk_conversation(&conversation);
+    let ranges: Vec<(usize, usize)> = chunks.iter().map(|c| (c.start_line, c.end_line)).collect();
+    assert_eq!(ranges, vec![(1, MAX_WINDOW_TURNS), (MAX_WINDOW_TURNS + 1, 10), (11, 11)]);
+    assert!(chunks[1].structural_cues.iter().any(|c| c == "turn:9"));
+}
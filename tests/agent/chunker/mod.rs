@@ -74,3 +74,210 @@ fn test_detect_type() {
     assert_eq!(Chunker::detect_type(&PathBuf::from("test.pdf")), Some(ChunkerType::Pdf));
     assert_eq!(Chunker::detect_type(&PathBuf::from("test.docx")), Some(ChunkerType::Office));
 }
+
+#[test]
+fn test_pdf_pages_split_by_headings_and_tables() {
+    let pages = vec![
+        "QUARTERLY REPORT\n\n1. Summary\n\nRevenue grew in every region this quarter.\nChurn stayed flat.\n\n3\n".to_string(),
+        "2. Regional Results\n\nRegion    Revenue    Growth\nEurope    1200       4%\nAsia      900        7%\n\nAsia led growth again.\n".to_string(),
+    ];
+    let chunks = Chunker::chunk_pdf_pages(&pages, "report");
+
+    let summary = chunks.iter().find(|c| c.context == "1. Summary").expect("summary section");
+    assert!(summary.content.contains("Revenue grew in every region this quarter. Churn stayed flat."));
+    assert!(!summary.content.contains("Regional"), "sections end at the next heading");
+    assert!(!summary.content.contains("\n3"), "page numbers are dropped");
+    assert!(summary.structural_cues.contains(&"page:1".to_string()));
+    assert!(summary.structural_cues.contains(&"header:1. Summary".to_string()));
+
+    let table = chunks.iter().find(|c| c.structural_cues.contains(&"type:pdf_table".to_string())).expect("table chunk");
+    assert!(table.content.contains("Region: Europe | Revenue: 1200 | Growth: 4%"));
+    assert!(table.content.contains("Region: Asia | Revenue: 900 | Growth: 7%"));
+    assert!(table.structural_cues.contains(&"table:2._regional_results".to_string()));
+    assert!(table.structural_cues.contains(&"column:revenue".to_string()));
+    assert!(table.structural_cues.contains(&"page:2".to_string()));
+
+    let results = chunks.iter().find(|c| c.context == "2. Regional Results").unwrap();
+    assert!(results.content.contains("Asia led growth again."));
+    assert!(!results.content.contains("1200"), "table rows are not repeated in the section");
+}
+
+#[test]
+fn test_pdf_sections_spanning_pages_carry_every_page() {
+    let pages = vec![
+        "Background\n\nThe migration started in March.\n".to_string(),
+        "It finished in May without downtime.\n".to_string(),
+    ];
+    let chunks = Chunker::chunk_pdf_pages(&pages, "notes");
+    assert_eq!(chunks.len(), 1);
+    let cues = &chunks[0].structural_cues;
+    assert!(cues.contains(&"page:1".to_string()) && cues.contains(&"page:2".to_string()));
+    assert_eq!(chunks[0].start_line, 1);
+    assert_eq!(chunks[0].end_line, 4);
+}
+
+#[test]
+fn test_docx_blocks_use_heading_styles_and_tables() {
+    use cuemap::agent::layout::{chunk_blocks, docx_blocks};
+    use docx_rs::{Docx, Paragraph, Run, Table, TableCell, TableRow};
+
+    let cell = |text: &str| TableCell::new().add_paragraph(Paragraph::new().add_run(Run::new().add_text(text)));
+    let docx = Docx::new()
+        .add_paragraph(Paragraph::new().style("Heading1").add_run(Run::new().add_text("Pricing")))
+        .add_paragraph(Paragraph::new().add_run(Run::new().add_text("Plans are billed monthly.")))
+        .add_table(Table::new(vec![
+            TableRow::new(vec![cell("Plan"), cell("Price")]),
+            TableRow::new(vec![cell("Team"), cell("$20")]),
+        ]))
+        .add_paragraph(Paragraph::new().style("Heading1").add_run(Run::new().add_text("Support")))
+        .add_paragraph(Paragraph::new().add_run(Run::new().add_text("Email us any time.")));
+
+    let chunks = chunk_blocks(&docx_blocks(&docx), "docx", "handbook");
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks[0].content, "Pricing\n\nPlans are billed monthly.");
+    assert!(chunks[1].content.contains("Plan: Team | Price: $20"));
+    assert!(chunks[1].structural_cues.contains(&"table:pricing".to_string()));
+    assert_eq!(chunks[2].context, "Support");
+    assert!(chunks.iter().all(|c| !c.structural_cues.iter().any(|cue| cue.starts_with("page:"))));
+}
+
+#[test]
+fn test_ocr_text_chunks() {
+    use cuemap::agent::ocr::parse_service_response;
+
+    let text = "INVOICE\n\nPayment is due within 30 days.\n\nItem      Qty    Price\nWidget    2      10.00\nGadget    1      25.00\n";
+    let chunks = Chunker::chunk_image_text(text, "scan");
+    let section = chunks.iter().find(|c| c.context == "INVOICE").unwrap();
+    assert!(section.content.contains("Payment is due within 30 days."));
+    assert!(section.structural_cues.contains(&"type:image_section".to_string()));
+    assert!(!section.structural_cues.iter().any(|c| c.starts_with("page:")));
+    let table = chunks.iter().find(|c| c.structural_cues.contains(&"type:image_table".to_string())).unwrap();
+    assert!(table.content.contains("Item: Widget | Qty: 2 | Price: 10.00"));
+
+    assert_eq!(parse_service_response(r#"{"text": "hello"}"#), "hello");
+    assert_eq!(parse_service_response("plain body"), "plain body");
+}
+
+#[test]
+fn test_images_are_only_chunked_with_ocr() {
+    use cuemap::agent::chunker::ChunkerType;
+
+    let detected = Chunker::detect_type(&PathBuf::from("screenshot.png"));
+    if cfg!(feature = "ocr") {
+        assert_eq!(detected, Some(ChunkerType::Image));
+    } else {
+        assert_eq!(detected, None, "images are skipped without the ocr feature");
+    }
+}
+
+#[test]
+fn test_transcript_chunks_by_time_window() {
+    use cuemap::agent::transcribe::{chunk_segments, format_timestamp, parse_transcript};
+
+    let body = r#"{"text": "...", "segments": [
+        {"start": 0.0, "end": 4.5, "text": " Welcome to the planning meeting."},
+        {"start": 42.0, "end": 58.2, "text": " First item is the release date."},
+        {"start": 61.3, "end": 70.0, "text": " We agreed to ship on Friday."},
+        {"start": 3725.0, "end": 3730.0, "text": " Thanks everyone."}
+    ]}"#;
+    let segments = parse_transcript(body).unwrap();
+    assert_eq!(segments.len(), 4);
+    assert_eq!(segments[0].text, "Welcome to the planning meeting.");
+
+    let chunks = chunk_segments(&segments, "standup", 60);
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks[0].content, "[00:00:00] Welcome to the planning meeting. First item is the release date.");
+    assert!(chunks[0].structural_cues.contains(&"timestamp:00:00:00".to_string()));
+    assert!(chunks[1].structural_cues.contains(&"timestamp:00:01:00".to_string()));
+    assert!(chunks[1].content.starts_with("[00:01:01] We agreed"));
+    assert!(chunks[2].structural_cues.contains(&"timestamp:01:02:00".to_string()));
+    assert_eq!((chunks[1].start_line, chunks[1].end_line), (61, 70));
+
+    assert_eq!(parse_transcript(r#"{"text": "Just text"}"#).unwrap().len(), 1);
+    assert!(parse_transcript("not json").is_err());
+    assert_eq!(format_timestamp(3725.9), "01:02:05");
+}
+
+#[test]
+fn test_limits_split_with_overlap() {
+    use cuemap::agent::chunker::{Chunk, ChunkCategory, ChunkingConfig};
+
+    let content = (0..10).map(|i| format!("line_{:02} ;", i)).collect::<Vec<_>>().join("\n");
+    let chunk = Chunk {
+        content,
+        start_line: 1,
+        end_line: 10,
+        context: "fn main".to_string(),
+        structural_cues: vec!["symbol:main".to_string()],
+        category: ChunkCategory::Code,
+    };
+    // 10 tokens = 40 bytes = four lines; the 12-byte overlap repeats one line
+    let config = ChunkingConfig { max_tokens: Some(10), overlap_tokens: Some(3), min_chunk_chars: None };
+    let pieces = Chunker::apply_limits(vec![chunk], &config);
+    let ranges: Vec<(usize, usize)> = pieces.iter().map(|c| (c.start_line, c.end_line)).collect();
+    assert_eq!(ranges, vec![(1, 4), (4, 7), (7, 10)]);
+    assert!(pieces[1].content.starts_with("line_03 ;"));
+    assert_eq!(pieces[2].context, "fn main (part 3)");
+    assert!(pieces.iter().all(|c| c.content.len() <= 40 && c.structural_cues == vec!["symbol:main".to_string()]));
+
+    // Prose is cut between sentences
+    let prose = Chunk {
+        content: "First sentence here. Second sentence here. Third sentence here.".to_string(),
+        start_line: 0,
+        end_line: 0,
+        context: "notes".to_string(),
+        structural_cues: Vec::new(),
+        category: ChunkCategory::Prose,
+    };
+    let config = ChunkingConfig { max_tokens: Some(6), ..Default::default() };
+    let pieces: Vec<String> = Chunker::apply_limits(vec![prose], &config).into_iter().map(|c| c.content).collect();
+    assert_eq!(pieces, vec!["First sentence here.", "Second sentence here.", "Third sentence here."]);
+}
+
+#[test]
+fn test_limits_merge_small_chunks() {
+    use cuemap::agent::chunker::{Chunk, ChunkCategory, ChunkingConfig};
+
+    let chunk = |content: &str, line: usize, cue: &str, category| Chunk {
+        content: content.to_string(),
+        start_line: line,
+        end_line: line,
+        context: String::new(),
+        structural_cues: vec![cue.to_string()],
+        category,
+    };
+    let chunks = vec![
+        chunk("Tiny intro.", 1, "heading:intro", ChunkCategory::Prose),
+        chunk("A paragraph that is long enough on its own.", 2, "heading:body", ChunkCategory::Prose),
+        chunk("fn x() {}", 3, "symbol:x", ChunkCategory::Code),
+    ];
+    let config = ChunkingConfig { min_chunk_chars: Some(20), ..Default::default() };
+    let merged = Chunker::apply_limits(chunks.clone(), &config);
+    assert_eq!(merged.len(), 2);
+    assert_eq!(merged[0].content, "Tiny intro.\nA paragraph that is long enough on its own.");
+    assert_eq!((merged[0].start_line, merged[0].end_line), (1, 2));
+    assert_eq!(merged[0].structural_cues, vec!["heading:intro".to_string(), "heading:body".to_string()]);
+    // Different category: stays on its own
+    assert_eq!(merged[1].content, "fn x() {}");
+
+    // A merge never exceeds max_tokens
+    let config = ChunkingConfig { max_tokens: Some(12), min_chunk_chars: Some(20), overlap_tokens: None };
+    assert_eq!(Chunker::apply_limits(chunks.clone(), &config).len(), 3);
+    assert_eq!(Chunker::apply_limits(chunks, &ChunkingConfig::default()).len(), 3);
+}
+
+#[test]
+fn test_chunking_config_merge_and_validate() {
+    use cuemap::agent::chunker::ChunkingConfig;
+
+    let project = ChunkingConfig { max_tokens: Some(512), overlap_tokens: Some(64), min_chunk_chars: Some(100) };
+    let request = ChunkingConfig { max_tokens: Some(256), ..Default::default() };
+    let merged = project.merged(&request);
+    assert_eq!(merged, ChunkingConfig { max_tokens: Some(256), overlap_tokens: Some(64), min_chunk_chars: Some(100) });
+    assert!(merged.validate().is_ok());
+
+    assert!(ChunkingConfig { max_tokens: Some(0), ..Default::default() }.validate().is_err());
+    assert!(ChunkingConfig { max_tokens: Some(100), overlap_tokens: Some(51), min_chunk_chars: None }.validate().is_err());
+    let parsed: ChunkingConfig = serde_json::from_str(r#"{"max_tokens": 300}"#).unwrap();
+    assert_eq!(parsed, ChunkingConfig { max_tokens: Some(300), ..Default::default() });
+}
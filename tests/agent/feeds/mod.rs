@@ -0,0 +1,72 @@
+use cuemap::agent::feeds::{changed_entries, looks_like_feed_url, parse_feed, FeedKind};
+use std::collections::BTreeMap;
+
+#[test]
+fn test_parse_sitemap_and_index() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>https://docs.example.com/start</loc><lastmod>2026-01-02</lastmod></url>
+  <url><loc>https://docs.example.com/api</loc></url>
+</urlset>"#;
+    let (kind, entries) = parse_feed(xml).unwrap();
+    assert_eq!(kind, FeedKind::Sitemap);
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].url, "https://docs.example.com/start");
+    assert_eq!(entries[0].updated.as_deref(), Some("2026-01-02"));
+    assert_eq!(entries[1].updated, None);
+
+    let index = r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <sitemap><loc>https://docs.example.com/sitemap-1.xml</loc></sitemap>
+</sitemapindex>"#;
+    let (kind, entries) = parse_feed(index).unwrap();
+    assert_eq!(kind, FeedKind::SitemapIndex);
+    assert_eq!(entries[0].url, "https://docs.example.com/sitemap-1.xml");
+
+    assert!(parse_feed("<html><body>page</body></html>").is_none());
+    assert!(parse_feed("not xml").is_none());
+}
+
+#[test]
+fn test_parse_rss_and_atom() {
+    let rss = r#"<rss version="2.0"><channel><title>Blog</title>
+  <item><title>Release 2.0</title><link>https://blog.example.com/2-0</link><pubDate>Tue, 03 Mar 2026 10:00:00 GMT</pubDate></item>
+  <item><title>No link</title></item>
+</channel></rss>"#;
+    let (kind, entries) = parse_feed(rss).unwrap();
+    assert_eq!(kind, FeedKind::Rss);
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].title.as_deref(), Some("Release 2.0"));
+    assert_eq!(entries[0].updated.as_deref(), Some("Tue, 03 Mar 2026 10:00:00 GMT"));
+
+    let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>Changelog</title>
+  <entry><title>Fix</title><link rel="self" href="https://example.com/self"/><link href="https://example.com/fix"/><updated>2026-02-01T00:00:00Z</updated></entry>
+</feed>"#;
+    let (kind, entries) = parse_feed(atom).unwrap();
+    assert_eq!(kind, FeedKind::Atom);
+    assert_eq!(entries[0].url, "https://example.com/fix", "the alternate link wins over rel=self");
+    assert_eq!(entries[0].updated.as_deref(), Some("2026-02-01T00:00:00Z"));
+}
+
+#[test]
+fn test_changed_entries_and_feed_urls() {
+    let xml = r#"<urlset>
+  <url><loc>https://a.example/one</loc><lastmod>2026-01-02</lastmod></url>
+  <url><loc>https://a.example/two</loc><lastmod>2026-01-05</lastmod></url>
+  <url><loc>https://a.example/three</loc></url>
+</urlset>"#;
+    let (_, entries) = parse_feed(xml).unwrap();
+    let mut seen = BTreeMap::new();
+    seen.insert("https://a.example/one".to_string(), "2026-01-02".to_string());
+    seen.insert("https://a.example/two".to_string(), "2026-01-01".to_string());
+    seen.insert("https://a.example/three".to_string(), String::new());
+
+    let changed: Vec<&str> = changed_entries(&entries, &seen).iter().map(|e| e.url.as_str()).collect();
+    assert_eq!(changed, vec!["https://a.example/two"]);
+
+    assert!(looks_like_feed_url("https://docs.example.com/sitemap.xml"));
+    assert!(looks_like_feed_url("https://docs.example.com/sitemap_index.xml"));
+    assert!(looks_like_feed_url("https://blog.example.com/feed/"));
+    assert!(looks_like_feed_url("https://blog.example.com/posts.rss"));
+    assert!(!looks_like_feed_url("https://docs.example.com/guide/sitemaps"));
+    assert!(!looks_like_feed_url("https://docs.example.com/data.xml"));
+}
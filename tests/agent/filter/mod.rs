@@ -0,0 +1,97 @@
+use cuemap::agent::filter::{IngestFilterConfig, PathFilter};
+use cuemap::config::{AgentConfig, AgentRoot};
+use std::path::Path;
+
+#[test]
+fn test_include_exclude_globs() {
+    let root = Path::new("/repo");
+    let filter = PathFilter::new(root, &IngestFilterConfig {
+        include: vec!["src/**".to_string(), "*.md".to_string()],
+        exclude: vec!["*.lock".to_string(), "src/generated/**".to_string()],
+        ..Default::default()
+    })
+    .unwrap();
+
+    assert!(filter.allows(Path::new("/repo/src/main.rs")));
+    assert!(filter.allows(Path::new("/repo/docs/guide.md")));
+    assert!(!filter.allows(Path::new("/repo/build.sh")), "not included");
+    assert!(!filter.allows(Path::new("/repo/src/Cargo.lock")), "exclusions win");
+    assert!(!filter.allows(Path::new("/repo/src/generated/api.rs")));
+
+    let everything = PathFilter::new(root, &IngestFilterConfig::default()).unwrap();
+    assert!(everything.allows(Path::new("/repo/build.sh")));
+
+    let bad = IngestFilterConfig { include: vec!["src/[".to_string()], ..Default::default() };
+    assert!(PathFilter::new(root, &bad).is_err());
+    assert!(bad.validate().is_err());
+}
+
+#[test]
+fn test_max_file_size() {
+    let root = Path::new("/repo");
+    let filter = PathFilter::new(root, &IngestFilterConfig { max_file_size: Some(1024), ..Default::default() }).unwrap();
+    assert!(filter.allows_size(1024));
+    assert!(!filter.allows_size(1025));
+    assert!(PathFilter::allow_all(root).allows_size(u64::MAX));
+}
+
+#[test]
+fn test_defaults_merge_under_agent_rules() {
+    let defaults = IngestFilterConfig {
+        include: vec!["**/*.rs".to_string()],
+        exclude: vec!["*.lock".to_string()],
+        max_file_size: Some(1_000_000),
+    };
+
+    let merged = defaults.merged(&IngestFilterConfig {
+        exclude: vec!["target/**".to_string(), "*.lock".to_string()],
+        ..Default::default()
+    });
+    assert_eq!(merged.include, defaults.include, "no includes of its own keeps the defaults");
+    assert_eq!(merged.exclude, vec!["*.lock".to_string(), "target/**".to_string()]);
+    assert_eq!(merged.max_file_size, Some(1_000_000));
+
+    let merged = defaults.merged(&IngestFilterConfig {
+        include: vec!["docs/**".to_string()],
+        max_file_size: Some(10),
+        ..Default::default()
+    });
+    assert_eq!(merged.include, vec!["docs/**".to_string()]);
+    assert_eq!(merged.max_file_size, Some(10));
+}
+
+#[test]
+fn test_agent_roots_from_config() {
+    let config: AgentConfig = toml::from_str(
+        r#"
+        enabled = true
+        throttle_ms = 50
+        watch_dir = "/legacy"
+        exclude = ["*.lock"]
+        max_file_size = 5000000
+
+        [[roots]]
+        path = "/work/api"
+        project = "api"
+        exclude = ["target/**"]
+
+        [[roots]]
+        path = "/work/web"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.filter.exclude, vec!["*.lock".to_string()]);
+    assert_eq!(config.filter.max_file_size, Some(5_000_000));
+
+    let roots = config.all_roots();
+    assert_eq!(roots.len(), 3);
+    assert_eq!(roots[0].project, "api");
+    assert_eq!(roots[0].filter.exclude, vec!["target/**".to_string()]);
+    assert_eq!(roots[1].project, "default");
+    assert_eq!(roots[2].path, "/legacy");
+
+    assert_eq!(AgentRoot::parse("/work/docs=docs").unwrap().project, "docs");
+    assert_eq!(AgentRoot::parse("/work/docs").unwrap().project, "default");
+    assert!(AgentRoot::parse("/work/docs=").is_err());
+}
@@ -0,0 +1,122 @@
+use cuemap::agent::git::{parse_log, CommitInfo, GitRepo};
+use cuemap::agent::ingester::Ingester;
+use cuemap::agent::AgentConfig;
+use cuemap::config::{CueGenStrategy, LlmConfig, TuningConfig};
+use cuemap::jobs::JobQueue;
+use cuemap::multi_tenant::MultiTenantEngine;
+use cuemap::semantic::SemanticEngine;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+
+fn commit() -> CommitInfo {
+    CommitInfo {
+        sha: "0123456789abcdef0123456789abcdef01234567".to_string(),
+        author: "Ada Lovelace".to_string(),
+        email: "ada@example.com".to_string(),
+        timestamp: 1_700_000_000,
+        subject: "Raise auth timeout to 30s".to_string(),
+        body: "Slow IdP responses caused logouts.".to_string(),
+        files: vec!["src/auth.rs".to_string()],
+    }
+}
+
+#[test]
+fn test_commit_cues_and_render() {
+    let commit = commit();
+    let cues = commit.cues(Some("Main"));
+    assert_eq!(
+        cues,
+        vec![
+            "git:branch:main".to_string(),
+            "git:author:ada_lovelace".to_string(),
+            "commit:0123456".to_string(),
+            "git:message:Raise auth timeout to 30s".to_string(),
+        ]
+    );
+    assert!(!commit.cues(None).iter().any(|c| c.starts_with("git:branch:")));
+
+    let text = commit.render();
+    assert!(text.starts_with("commit 0123456789abcdef"));
+    assert!(text.contains("Author: Ada Lovelace <ada@example.com>"));
+    assert!(text.contains("Slow IdP responses"));
+    assert!(text.contains("  src/auth.rs"));
+}
+
+#[test]
+fn test_parse_log() {
+    let output = "\x1eaaa111\x1fAda\x1fada@example.com\x1f1700000000\x1fFirst\x1f\x1f\n\nsrc/a.rs\nsrc/b.rs\n\
+                  \x1ebbb222\x1fBob\x1fbob@example.com\x1f1700000100\x1fSecond\x1fWith a body\nover lines\x1f\n";
+    let commits = parse_log(output);
+    assert_eq!(commits.len(), 2);
+    assert_eq!(commits[0].sha, "aaa111");
+    assert_eq!(commits[0].files, vec!["src/a.rs".to_string(), "src/b.rs".to_string()]);
+    assert_eq!(commits[1].author, "Bob");
+    assert_eq!(commits[1].timestamp, 1_700_000_100);
+    assert_eq!(commits[1].body, "With a body\nover lines");
+    assert!(commits[1].files.is_empty());
+}
+
+fn git(dir: &Path, args: &[&str]) -> bool {
+    Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=Ada Lovelace", "-c", "user.email=ada@example.com"])
+        .args(args)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+#[tokio::test]
+async fn test_repo_metadata() {
+    let dir = tempfile::Builder::new().prefix("gitrepo").tempdir().unwrap();
+    if !git(dir.path(), &["init", "-q", "-b", "trunk"]) {
+        eprintln!("git not available, skipping");
+        return;
+    }
+    std::fs::write(dir.path().join("auth.rs"), "const TIMEOUT: u64 = 30;\n").unwrap();
+    assert!(git(dir.path(), &["add", "auth.rs"]));
+    assert!(git(dir.path(), &["commit", "-q", "-m", "Raise auth timeout"]));
+    std::fs::write(dir.path().join("untracked.rs"), "fn main() {}\n").unwrap();
+
+    let repo = GitRepo::discover(dir.path()).expect("temp dir is a repository");
+    assert_eq!(repo.branch(), Some("trunk"));
+
+    let last = repo.last_commit(&dir.path().join("auth.rs")).unwrap();
+    assert_eq!(last.author, "Ada Lovelace");
+    assert_eq!(last.subject, "Raise auth timeout");
+    assert!(repo.last_commit(&dir.path().join("untracked.rs")).is_none());
+
+    let history = repo.history(10);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].files, vec!["auth.rs".to_string()]);
+
+    // Chunks of tracked files carry the last commit's cues
+    let snapshots = tempfile::tempdir().unwrap();
+    let engine = Arc::new(MultiTenantEngine::with_snapshots_dir(
+        snapshots.path(),
+        CueGenStrategy::Default,
+        SemanticEngine::new(None),
+        TuningConfig::default(),
+        LlmConfig::default(),
+    ));
+    let ingester = Ingester::new(
+        AgentConfig {
+            project_id: "git".to_string(),
+            watch_dir: dir.path().to_string_lossy().to_string(),
+            throttle_ms: 0,
+            state_file: None,
+            filter: Default::default(),
+            git: Default::default(),
+            chunking: Default::default(),
+            extract_facts: false,
+        },
+        Arc::new(JobQueue::new(engine, None, true)),
+    );
+    let chunks = ingester.preview_file(&dir.path().join("auth.rs")).unwrap();
+    let cues = &chunks[0].chunk.structural_cues;
+    assert!(cues.contains(&"git:branch:trunk".to_string()));
+    assert!(cues.contains(&"git:author:ada_lovelace".to_string()));
+    assert!(cues.contains(&format!("commit:{}", last.short_sha())));
+}
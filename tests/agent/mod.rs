@@ -1 +1,6 @@
+mod chat;
 mod chunker;
+mod filter;
+mod git;
+mod preview;
+mod feeds;
@@ -0,0 +1,167 @@
+use cuemap::agent::chunker::Chunker;
+use cuemap::agent::filter::IngestFilterConfig;
+use cuemap::agent::ingester::{chunk_memory_id, Ingester};
+use cuemap::agent::AgentConfig;
+use cuemap::config::{CueGenStrategy, LlmConfig, TuningConfig};
+use cuemap::jobs::{agent_chunk_cues, JobQueue};
+use cuemap::multi_tenant::MultiTenantEngine;
+use cuemap::semantic::SemanticEngine;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+fn engine(dir: &std::path::Path) -> Arc<MultiTenantEngine> {
+    Arc::new(MultiTenantEngine::with_snapshots_dir(
+        dir,
+        CueGenStrategy::Default,
+        SemanticEngine::new(None),
+        TuningConfig::default(),
+        LlmConfig::default(),
+    ))
+}
+
+#[tokio::test]
+async fn test_preview_file_applies_agent_rules() {
+    // Not a dot-prefixed temp dir: hidden paths are skipped by the agent
+    let root = tempfile::Builder::new().prefix("preview").tempdir().unwrap();
+    std::fs::create_dir(root.path().join("src")).unwrap();
+    std::fs::write(root.path().join("src/main.rs"), "fn main() {\n    println!(\"hi\");\n}\n").unwrap();
+    std::fs::write(root.path().join("Cargo.lock"), "version = 3\n").unwrap();
+    std::fs::write(root.path().join("big.txt"), "x".repeat(200)).unwrap();
+
+    let snapshots = tempfile::tempdir().unwrap();
+    let job_queue = Arc::new(JobQueue::new(engine(snapshots.path()), None, true));
+    let ingester = Ingester::new(
+        AgentConfig {
+            project_id: "preview".to_string(),
+            watch_dir: root.path().to_string_lossy().to_string(),
+            throttle_ms: 0,
+            state_file: None,
+            filter: IngestFilterConfig {
+                exclude: vec!["*.lock".to_string()],
+                max_file_size: Some(100),
+                ..Default::default()
+            },
+            git: Default::default(),
+            chunking: Default::default(),
+            extract_facts: false,
+        },
+        job_queue,
+    );
+
+    let chunks = ingester.preview_file(&root.path().join("src/main.rs")).unwrap();
+    assert!(!chunks.is_empty());
+    assert!(chunks[0].memory_id.starts_with("file:"));
+    assert!(chunks[0].file_path.ends_with("src/main.rs"));
+
+    let err = ingester.preview_file(&root.path().join("Cargo.lock")).unwrap_err();
+    assert!(err.contains("filtered"), "{}", err);
+    let err = ingester.preview_file(&root.path().join("big.txt")).unwrap_err();
+    assert!(err.contains("oversized"), "{}", err);
+}
+
+#[test]
+fn test_preview_cues_without_creating_project() {
+    let snapshots = tempfile::tempdir().unwrap();
+    let engine = engine(snapshots.path());
+
+    let ctx = engine.get_project_or_scratch(&"scratch".to_string());
+    assert!(engine.get_project(&"scratch".to_string()).is_none(), "dry runs must not register the project");
+
+    let chunks = Chunker::chunk_file(&PathBuf::from("notes.md"), "# Deploy\n\nRestart the payment service after rotating keys.");
+    assert!(!chunks.is_empty());
+    let source = "api:notes.md";
+    let chunk = &chunks[0];
+    let cues = agent_chunk_cues(&ctx, &chunk.content, source, &chunk.structural_cues, chunk.category);
+
+    assert!(cues.contains(&"path:api:notes.md".to_string()));
+    assert!(cues.contains(&"source:agent".to_string()));
+    assert!(cues.iter().any(|c| c.starts_with("category:")));
+    assert!(cues.iter().any(|c| c.contains("payment")));
+    assert_eq!(chunk_memory_id(source, &chunk.content), chunk_memory_id(source, &chunk.content));
+    assert!(chunk_memory_id(source, &chunk.content).starts_with("api:notes.md:"));
+}
+
+#[test]
+fn test_provenance_across_sources() {
+    use cuemap::agent::chunker::{Chunk, ChunkCategory};
+    use cuemap::agent::ingester::{chunk_provenance, source_provenance};
+    use cuemap::engine::CueMapEngine;
+    use cuemap::structures::MainStats;
+
+    let chunk = |category, cues: &[&str]| Chunk {
+        content: "Restart the payment service.".to_string(),
+        start_line: 12,
+        end_line: 18,
+        context: String::new(),
+        structural_cues: cues.iter().map(|c| c.to_string()).collect(),
+        category,
+    };
+
+    let file = chunk_provenance("/repo/docs/runbook.md", &chunk(ChunkCategory::Prose, &[]));
+    assert_eq!((file.source_type.as_str(), file.uri.as_str()), ("file", "/repo/docs/runbook.md"));
+    assert_eq!(file.line_range, Some((12, 18)));
+    assert_eq!(file.page_range, None);
+    assert_eq!(file.content_hash.len(), 64);
+
+    let pdf = chunk_provenance("file:report.pdf", &chunk(ChunkCategory::Prose, &["page:4", "page:3"]));
+    assert_eq!((pdf.source_type.as_str(), pdf.uri.as_str()), ("upload", "report.pdf"));
+    assert_eq!(pdf.page_range, Some((3, 4)));
+
+    // Web chunk indexes, chat turns and transcript seconds aren't lines
+    let web = chunk_provenance("url:https://example.com/a", &chunk(ChunkCategory::WebContent, &[]));
+    assert_eq!((web.source_type.as_str(), web.uri.as_str(), web.line_range), ("url", "https://example.com/a", None));
+    assert_eq!(chunk_provenance("api:chat.txt", &chunk(ChunkCategory::Conversation, &[])).line_range, None);
+    assert_eq!(chunk_provenance("file:call.mp3", &chunk(ChunkCategory::Prose, &["timestamp:00:01:00"])).line_range, None);
+
+    assert_eq!(source_provenance("api:notes.md", "x").source_type, "content");
+    assert_eq!(source_provenance("connector:issues:42", "x").uri, "issues:42");
+    assert_eq!(source_provenance("git:/repo", "x").source_type, "git");
+    assert_eq!(source_provenance("api:notes.md", "x").content_hash, file_hash("x"));
+
+    // Stored as metadata next to what's already there
+    let engine = CueMapEngine::new();
+    let id = engine.add_memory("Restart the payment service.".to_string(), vec!["payment".to_string()], None, MainStats::default(), true);
+    assert!(engine.set_pinned(&id, true));
+    assert!(engine.set_provenance(&id, &file));
+    let memory = engine.get_memory(&id).unwrap();
+    assert_eq!(memory.provenance(), Some(file.clone()));
+    assert!(memory.is_pinned());
+    assert_eq!(memory.metadata["provenance"]["line_range"], serde_json::json!([12, 18]));
+    assert!(!engine.set_provenance("missing", &file));
+}
+
+fn file_hash(content: &str) -> String {
+    use sha2::{Digest, Sha256};
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+#[tokio::test]
+async fn test_scan_reports_progress() {
+    let root = tempfile::Builder::new().prefix("scan").tempdir().unwrap();
+    std::fs::write(root.path().join("a.md"), "# A\n\nFirst file.\n").unwrap();
+    std::fs::write(root.path().join("b.md"), "# B\n\nSecond file.\n").unwrap();
+    let bytes = std::fs::metadata(root.path().join("a.md")).unwrap().len() + std::fs::metadata(root.path().join("b.md")).unwrap().len();
+
+    let snapshots = tempfile::tempdir().unwrap();
+    let job_queue = Arc::new(JobQueue::new(engine(snapshots.path()), None, true));
+    let mut ingester = Ingester::new(
+        AgentConfig {
+            project_id: "scan".to_string(),
+            watch_dir: root.path().to_string_lossy().to_string(),
+            throttle_ms: 0,
+            state_file: None,
+            filter: Default::default(),
+            git: Default::default(),
+            chunking: Default::default(),
+            extract_facts: false,
+        },
+        job_queue.clone(),
+    );
+    ingester.scan_all().await.unwrap();
+
+    let scan = job_queue.get_session("scan").unwrap().get_progress().scan.unwrap();
+    assert_eq!((scan.files_total, scan.files_scanned, scan.files_remaining, scan.files_failed), (2, 2, 0, 0));
+    assert_eq!((scan.bytes_total, scan.bytes_processed), (bytes, bytes));
+    assert_eq!((scan.current_file, scan.eta_secs, scan.done), (None, Some(0), true));
+    assert_eq!(job_queue.get_global_progress().scan.unwrap().files_scanned, 2);
+}
@@ -0,0 +1,82 @@
+use cuemap::aggregation::CountBucket;
+use cuemap::engine::{CueMapEngine, MemoryRecord, RecallFilter};
+use cuemap::structures::MainStats;
+use std::collections::HashMap;
+
+const DAY: f64 = 86_400.0;
+const OCT_14: f64 = 1_791_936_000.0;
+
+fn engine() -> CueMapEngine<MainStats> {
+    let engine = CueMapEngine::new();
+    let memories = [
+        ("m1", "Payments timed out", &["service:payments", "error:timeout", "path:a.md"][..], OCT_14 + 60.0),
+        ("m2", "Payments retried", &["service:payments", "path:a.md"][..], OCT_14 + 120.0),
+        ("m3", "Search timed out", &["service:search", "error:timeout", "path:b.md"][..], OCT_14 + DAY),
+        ("m4", "Payments recovered", &["service:payments", "path:b.md"][..], OCT_14 + 2.0 * DAY + 5.0),
+    ];
+    for (id, content, cues, created_at) in memories {
+        assert!(engine.import_record(MemoryRecord {
+            id: id.to_string(),
+            content: content.to_string(),
+            cues: cues.iter().map(|c| c.to_string()).collect(),
+            metadata: HashMap::new(),
+            stats: MainStats::default(),
+            created_at: Some(created_at),
+            last_accessed: None,
+            links: Vec::new(),
+        }));
+    }
+    engine
+}
+
+fn bucket(key: &str, count: usize) -> CountBucket {
+    CountBucket { key: key.to_string(), count }
+}
+
+fn cues(cues: &[&str]) -> Vec<String> {
+    cues.iter().map(|c| c.to_string()).collect()
+}
+
+#[test]
+fn test_count_per_cue_value_and_day() {
+    let engine = engine();
+    let report = engine.count_matching(&cues(&["service:payments"]), 1, &RecallFilter::default(), Some("path"), true, 10);
+    assert_eq!(report.count, 3);
+    assert_eq!(report.by_cue.unwrap(), vec![bucket("a.md", 2), bucket("b.md", 1)]);
+    assert_eq!(report.by_day.unwrap(), vec![bucket("2026-10-14", 2), bucket("2026-10-16", 1)]);
+
+    // Breakdowns only when asked for
+    let report = engine.count_matching(&cues(&["error:timeout"]), 1, &RecallFilter::default(), None, false, 10);
+    assert_eq!(report.count, 2);
+    assert!(report.by_cue.is_none() && report.by_day.is_none());
+}
+
+#[test]
+fn test_count_intersection_and_filters() {
+    let engine = engine();
+    let both = cues(&["service:payments", "error:timeout"]);
+    assert_eq!(engine.count_matching(&both, 1, &RecallFilter::default(), None, false, 10).count, 4);
+    assert_eq!(engine.count_matching(&both, 2, &RecallFilter::default(), None, false, 10).count, 1);
+
+    let recent = RecallFilter { created_after: Some(OCT_14 + DAY), ..Default::default() };
+    assert_eq!(engine.count_matching(&both, 1, &recent, None, false, 10).count, 2);
+
+    let phrase = RecallFilter { phrase: Some("timed out".to_string()), ..Default::default() };
+    assert_eq!(engine.count_matching(&both, 1, &phrase, None, false, 10).count, 2);
+
+    // No cues: every memory, limited buckets
+    let report = engine.count_matching(&[], 1, &RecallFilter::default(), Some("service"), false, 1);
+    assert_eq!(report.count, 4);
+    assert_eq!(report.by_cue.unwrap(), vec![bucket("payments", 3)]);
+}
+
+#[test]
+fn test_matching_memory_ids_oldest_first() {
+    let engine = engine();
+    let ids = engine.matching_memory_ids(&cues(&["service:payments", "error:timeout"]), 1, &RecallFilter::default());
+    assert_eq!(ids, vec!["m1", "m2", "m3", "m4"]);
+
+    let excluded = RecallFilter { exclude_cues: cues(&["service:search"]), ..Default::default() };
+    assert_eq!(engine.matching_memory_ids(&cues(&["error:timeout"]), 1, &excluded), vec!["m1"]);
+    assert_eq!(engine.matching_memory_ids(&[], 1, &RecallFilter::default()).len(), 4);
+}
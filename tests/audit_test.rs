@@ -0,0 +1,85 @@
+use cuemap::audit::{actor_for_key, AuditAction, AuditEntry, AuditLog, AuditQuery};
+use tempfile::tempdir;
+
+#[test]
+fn test_audit_log_append_and_query() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("audit.log");
+    let alice = actor_for_key(Some("alice-secret"));
+
+    // The raw key never reaches the log
+    assert!(alice.starts_with("key:"));
+    assert!(!alice.contains("alice-secret"));
+    assert_eq!(actor_for_key(None), "anonymous");
+
+    {
+        let log = AuditLog::open(&path);
+        log.record(AuditEntry::new(alice.clone(), AuditAction::MemoryCreated, "proj-a").with_memory("m1"));
+        log.record(AuditEntry::new(alice.clone(), AuditAction::MemoryDeleted, "proj-a").with_memory("m1"));
+        log.record(AuditEntry::new("anonymous".to_string(), AuditAction::ProjectExported, "proj-b"));
+    }
+
+    // Entries survive reopening and new ones are appended, not overwritten
+    let log = AuditLog::open(&path);
+    log.record(AuditEntry::new(alice.clone(), AuditAction::ProjectDeleted, "proj-a"));
+
+    let all = log.query(&AuditQuery::default()).unwrap();
+    assert_eq!(all.len(), 4);
+
+    let deletes = log.query(&AuditQuery {
+        memory_id: Some("m1".to_string()),
+        action: Some(AuditAction::MemoryDeleted),
+        ..AuditQuery::default()
+    }).unwrap();
+    assert_eq!(deletes.len(), 1);
+    assert_eq!(deletes[0].actor, alice);
+
+    let latest = log.query(&AuditQuery {
+        project_id: Some("proj-a".to_string()),
+        limit: Some(1),
+        ..AuditQuery::default()
+    }).unwrap();
+    assert_eq!(latest.len(), 1);
+    assert_eq!(latest[0].action, AuditAction::ProjectDeleted);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_server_deletions_are_audited() {
+    use cuemap::audit::SYSTEM_ACTOR;
+    use cuemap::config::{CueGenStrategy, LlmConfig, TuningConfig};
+    use cuemap::jobs::{Job, JobQueue};
+    use cuemap::multi_tenant::MultiTenantEngine;
+    use cuemap::semantic::SemanticEngine;
+    use std::sync::Arc;
+
+    let dir = tempdir().unwrap();
+    let engine = Arc::new(MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::Default, SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default()));
+    let ctx = engine.get_or_create_project("agent".to_string()).unwrap();
+    for id in ["file:src/a.rs:0", "file:src/a.rs:1"] {
+        ctx.main.upsert_memory_with_id(id.to_string(), "fn a() {}".to_string(), vec!["path:src/a.rs".to_string()], None, None, false, false);
+    }
+
+    // The file changed and only its first chunk is left
+    let queue = JobQueue::new(engine.clone(), None, false);
+    queue.enqueue(Job::VerifyFile {
+        project_id: "agent".to_string(),
+        file_path: "src/a.rs".to_string(),
+        valid_memory_ids: vec!["file:src/a.rs:0".to_string()],
+    }).await;
+    for _ in 0..500 {
+        if ctx.main.get_memory("file:src/a.rs:1").is_none() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+
+    let deletes = engine.audit().query(&AuditQuery {
+        project_id: Some("agent".to_string()),
+        action: Some(AuditAction::MemoryDeleted),
+        ..AuditQuery::default()
+    }).unwrap();
+    assert_eq!(deletes.len(), 1);
+    assert_eq!(deletes[0].actor, SYSTEM_ACTOR);
+    assert_eq!(deletes[0].memory_id.as_deref(), Some("file:src/a.rs:1"));
+    assert_eq!(deletes[0].detail.as_ref().unwrap()["reason"], "stale_chunk");
+}
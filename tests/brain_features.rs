@@ -88,4 +88,92 @@ fn test_systems_consolidation() {
     assert!(mem.metadata.contains_key("consolidated"));
     assert!(content.contains("report part 1"));
     assert!(content.contains("report part 2"));
+    let mut sources: Vec<String> = serde_json::from_value(mem.metadata["consolidated_from"].clone()).unwrap();
+    sources.sort();
+    let mut expected = consolidated[0].1.clone();
+    expected.sort();
+    assert_eq!(sources, expected);
+}
+
+#[test]
+fn test_pinned_memories_skip_prune_and_consolidation() {
+    let engine = CueMapEngine::new();
+
+    let mut metadata = HashMap::new();
+    metadata.insert("pinned".to_string(), serde_json::json!(true));
+    let pinned = engine.add_memory("report part 1".to_string(), vec!["type:report".to_string(), "month:jan".to_string()], Some(metadata), MainStats::default(), false);
+    let other = engine.add_memory("report part 2".to_string(), vec!["type:report".to_string(), "month:jan".to_string()], None, MainStats::default(), false);
+
+    // The only overlapping partner is pinned, so nothing merges
+    assert!(engine.consolidate_memories(0.6).is_empty());
+
+    // Everything is below the threshold, only the unpinned memory goes
+    assert_eq!(engine.prune_low_salience(100.0), 1);
+    assert!(engine.get_memory(&pinned).is_some());
+    assert!(engine.get_memory(&other).is_none());
+
+    assert!(engine.set_pinned(&pinned, false));
+    assert!(!engine.get_memory(&pinned).unwrap().is_pinned());
+    assert_eq!(engine.prune_low_salience(100.0), 1);
+    assert!(!engine.set_pinned(&pinned, true));
+}
+
+#[test]
+fn test_decay_and_prune_dry_runs() {
+    use cuemap::maintenance::prune_memories;
+
+    let engine = CueMapEngine::new();
+    let hot = engine.add_memory("hot".to_string(), vec!["a".to_string()], None, MainStats { dynamic_salience: 0.5, ..MainStats::default() }, false);
+    let demoted = engine.add_memory("demoted".to_string(), vec!["a".to_string()], None, MainStats { dynamic_salience: -0.6, ..MainStats::default() }, false);
+    let cold = engine.add_memory("cold".to_string(), vec!["a".to_string()], None, MainStats { dynamic_salience: -0.9, ..MainStats::default() }, false);
+    engine.add_memory("fresh".to_string(), vec!["a".to_string()], None, MainStats::default(), false);
+
+    // Never boosted: a dry run reports everything reaching zero but changes nothing
+    let report = engine.decay_salience_with(0.05, true);
+    assert_eq!((report.memories, report.decayed, report.zeroed), (4, 3, 3));
+    assert_eq!(engine.get_memory(&hot).unwrap().stats.dynamic_salience, 0.5);
+
+    // Lowest salience first; fresh memories sit at 1.0 and are kept
+    let report = prune_memories(&engine, 1.0, 0, true);
+    assert_eq!(report.memory_ids, vec![cold.clone(), demoted.clone()]);
+    assert!(engine.get_memory(&cold).is_some());
+    // Nothing has been idle for an hour yet
+    assert!(prune_memories(&engine, 1.0, 3600, true).memory_ids.is_empty());
+
+    assert_eq!(prune_memories(&engine, 0.2, 0, false).memory_ids, vec![cold.clone()]);
+    assert!(engine.get_memory(&cold).is_none());
+
+    engine.decay_salience(0.05);
+    assert_eq!(engine.get_memory(&demoted).unwrap().stats.dynamic_salience, 0.0);
+}
+
+#[test]
+fn test_decay_runs_do_not_compound() {
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+    let engine = CueMapEngine::new();
+    let stats = MainStats { dynamic_salience: 2.0, last_boosted_at: now - 10 * 3600, ..MainStats::default() };
+    let id = engine.add_memory("warm".to_string(), vec!["a".to_string()], None, stats, false);
+
+    engine.decay_salience(0.05);
+    let once = engine.get_memory(&id).unwrap().stats.dynamic_salience;
+    assert!((once - 2.0 * (-0.5f64).exp()).abs() < 0.01);
+    assert!(engine.salience_decayed_at() >= now);
+
+    // Running again right away has (almost) no time left to decay
+    engine.decay_salience(0.05);
+    assert!((engine.get_memory(&id).unwrap().stats.dynamic_salience - once).abs() < 1e-3);
+}
+
+#[test]
+fn test_pinned_score_floor() {
+    let tuning = cuemap::config::TuningConfig { pinned_score_floor: Some(1_000_000.0), ..Default::default() };
+    let engine = CueMapEngine::with_tuning(tuning);
+
+    let pinned = engine.add_memory("canonical fact".to_string(), vec!["a".to_string()], None, MainStats::default(), false);
+    engine.add_memory("newer and closer".to_string(), vec!["a".to_string(), "b".to_string()], None, MainStats::default(), false);
+    assert!(engine.set_pinned(&pinned, true));
+
+    let results = engine.recall(vec!["a".to_string(), "b".to_string()], 10, false, None);
+    assert_eq!(results[0].memory_id, pinned);
+    assert!(results[0].score >= 1_000_000.0);
 }
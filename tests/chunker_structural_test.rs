@@ -47,3 +47,38 @@ fn test_pdf_office_fallbacks() {
     // pdf_extract might return empty if it can't find the file, which is fine.
     // The main thing is they use ChunkerType::Pdf/Office.
 }
+
+#[test]
+fn test_symbol_cues_for_nested_items() {
+    let rust = "mod parser {\n    pub struct Parser { pos: usize }\n\n    impl Parser {\n        pub fn new() -> Self { Self { pos: 0 } }\n        pub fn parse(&mut self) {}\n    }\n}\n";
+    let chunks = Chunker::chunk_file(&PathBuf::from("lib.rs"), rust);
+    let imp = chunks.iter().find(|c| c.structural_cues.contains(&"type:impl".to_string())).expect("impl chunk");
+    assert_eq!((imp.start_line, imp.end_line), (4, 7));
+    for symbol in ["symbol:parser::Parser", "symbol:parser::Parser::new", "symbol:parser::Parser::parse"] {
+        assert!(imp.structural_cues.contains(&symbol.to_string()), "missing {} in {:?}", symbol, imp.structural_cues);
+    }
+
+    let ts = "export class Store {\n  get(key: string) { return key; }\n}\n";
+    let chunks = Chunker::chunk_file(&PathBuf::from("store.ts"), ts);
+    assert!(chunks.iter().any(|c| c.structural_cues.contains(&"symbol:Store.get".to_string())));
+
+    // Pieces of a function too big for one chunk keep its symbol
+    let body: String = (0..200).map(|i| format!("    if x == {} {{ println!(\"branch number {}\"); }}\n", i, i)).collect();
+    let big = format!("impl Machine {{\n fn step(x: u32) {{\n{}}}\n}}\n", body);
+    let chunks = Chunker::chunk_file(&PathBuf::from("machine.rs"), &big);
+    assert!(chunks.len() > 1);
+    assert!(chunks.iter().all(|c| c.structural_cues.contains(&"symbol:Machine::step".to_string())));
+}
+
+#[cfg(feature = "cpp")]
+#[test]
+fn test_cpp_chunking() {
+    let cpp = "namespace net {\nclass Socket {\n public:\n  void close();\n};\n\ntemplate <typename T>\nT clamp(T v) { return v; }\n\nvoid Socket::close() {}\n}\n";
+    let chunks = Chunker::chunk_file(&PathBuf::from("socket.cpp"), cpp);
+    let cues: Vec<String> = chunks.iter().flat_map(|c| c.structural_cues.clone()).collect();
+    for cue in ["lang:cpp", "type:class", "symbol:net::Socket", "symbol:net::Socket::close", "symbol:net::clamp"] {
+        assert!(cues.contains(&cue.to_string()), "missing {} in {:?}", cue, cues);
+    }
+    let template = chunks.iter().find(|c| c.structural_cues.contains(&"symbol:net::clamp".to_string())).unwrap();
+    assert_eq!(template.start_line, 7);
+}
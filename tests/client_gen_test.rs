@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod tests {
+    use cuemap::client_gen::{self, CLIENT_OPERATIONS};
+
+    #[test]
+    fn test_client_operations_exist_in_spec() {
+        let operations = client_gen::client_operations().expect("every SDK endpoint is in the OpenAPI spec");
+        for tag in ["memories", "recall", "ingest"] {
+            assert!(operations.iter().any(|op| op.operation.tag == tag), "no SDK method for {}", tag);
+        }
+        assert!(CLIENT_OPERATIONS.iter().any(|(_, path, _)| *path == "/recall/grounded"));
+    }
+
+    #[test]
+    fn test_generated_sdks_cover_request_fields() {
+        let operations = client_gen::client_operations().unwrap();
+        let schemas = client_gen::client_schemas(&operations);
+        assert!(schemas.contains("RecallRequest"));
+        assert!(schemas.contains("MetadataFilter"), "referenced schemas are generated too");
+
+        let ts = client_gen::typescript_client().unwrap();
+        let py = client_gen::python_client().unwrap();
+        for name in &schemas {
+            let ts_block = ts.split(&format!("export interface {} {{\n", name)).nth(1)
+                .unwrap_or_else(|| panic!("no TypeScript interface for {}", name));
+            let ts_block = &ts_block[..ts_block.find("\n}").unwrap()];
+            let py_block = py.split(&format!("class {}(TypedDict, total=False):\n", name)).nth(1)
+                .unwrap_or_else(|| panic!("no Python TypedDict for {}", name));
+            let py_block = &py_block[..py_block.find("\n\n").unwrap()];
+
+            for field in client_gen::schema_fields(name) {
+                assert!(
+                    ts_block.contains(&format!("  {}: ", field)) || ts_block.contains(&format!("  {}?: ", field)),
+                    "TypeScript {} lacks {}", name, field
+                );
+                assert!(py_block.contains(&format!("    {}: ", field)), "Python {} lacks {}", name, field);
+            }
+        }
+
+        assert!(ts.contains("  recallGrounded(body: RecallGroundedRequest)"));
+        assert!(ts.contains("  getMemory(id: string)"));
+        assert!(ts.contains("  query_text: string;"), "required fields are not optional");
+        assert!(py.contains("    def recall_grounded(self, body: RecallGroundedRequest)"));
+        assert!(py.contains("    def resolve_citation(self, recall_id: str, citation: str)"));
+        assert!(py.contains("    query_text: Required[str]"));
+    }
+}
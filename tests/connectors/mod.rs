@@ -0,0 +1,202 @@
+use cuemap::connectors::github::{next_link, parse_issue};
+use cuemap::connectors::notion::{block_markdown, blocks_markdown, page_title};
+use cuemap::connectors::{build_connector, document_chunks, validate_connector_id, ConnectorConfig, ConnectorKind, Document};
+use serde_json::json;
+
+fn config(kind: ConnectorKind) -> ConnectorConfig {
+    ConnectorConfig {
+        id: "docs".to_string(),
+        kind,
+        repo: String::new(),
+        database_id: None,
+        path: String::new(),
+        api_url: String::new(),
+        username: String::new(),
+        token_env: String::new(),
+        sync_interval_secs: None,
+        cursor: None,
+        last_synced: None,
+        last_error: None,
+    }
+}
+
+#[test]
+fn test_github_issue_document() {
+    let issue = json!({
+        "number": 42,
+        "title": "Crash on empty query",
+        "body": "Recall panics when the query is empty.",
+        "html_url": "https://github.com/acme/app/issues/42",
+        "state": "open",
+        "user": {"login": "Alice"},
+        "labels": [{"name": "bug"}, {"name": "good first issue"}],
+        "comments": 2
+    });
+    let comments = vec![
+        json!({"body": "Reproduced on main.", "user": {"login": "bob"}, "created_at": "2026-03-01T10:00:00Z"}),
+        json!({"body": "  ", "user": {"login": "carol"}, "created_at": "2026-03-02T10:00:00Z"}),
+    ];
+
+    let document = parse_issue(&issue, &comments).unwrap();
+    assert_eq!(document.id, "42");
+    assert_eq!(document.title, "#42 Crash on empty query");
+    assert!(document.content.contains("## bob commented on 2026-03-01\n\nReproduced on main."));
+    assert!(!document.content.contains("carol"), "empty comments are dropped");
+    assert_eq!(
+        document.cues,
+        vec!["document:issue", "state:open", "author:alice", "label:bug", "label:good_first_issue"]
+    );
+
+    let pull = json!({"number": 7, "title": "Fix", "pull_request": {"url": "x"}, "state": "closed"});
+    assert!(parse_issue(&pull, &[]).unwrap().cues.contains(&"document:pull_request".to_string()));
+    assert!(parse_issue(&json!({"title": "no number"}), &[]).is_none());
+
+    let link = r#"<https://api.github.com/repositories/1/issues?page=2>; rel="next", <https://api.github.com/repositories/1/issues?page=5>; rel="last""#;
+    assert_eq!(next_link(link).as_deref(), Some("https://api.github.com/repositories/1/issues?page=2"));
+    assert_eq!(next_link(r#"<https://x/issues?page=1>; rel="prev""#), None);
+}
+
+#[test]
+fn test_notion_blocks_to_markdown() {
+    let text = |s: &str| json!([{"plain_text": s}]);
+    let blocks = vec![
+        json!({"type": "heading_2", "heading_2": {"rich_text": text("Setup")}}),
+        json!({"type": "paragraph", "paragraph": {"rich_text": [{"plain_text": "Run "}, {"plain_text": "make"}]}}),
+        json!({"type": "to_do", "to_do": {"rich_text": text("Install"), "checked": true}}),
+        json!({"type": "paragraph", "paragraph": {"rich_text": []}}),
+        json!({"type": "code", "code": {"rich_text": text("make build"), "language": "shell"}}),
+        json!({"type": "divider", "divider": {}}),
+    ];
+    assert_eq!(blocks_markdown(&blocks), "## Setup\nRun make\n- [x] Install\n```shell\nmake build\n```");
+
+    let row = json!({"type": "table_row", "table_row": {"cells": [text("a"), text("b")]}});
+    assert_eq!(block_markdown(&row).as_deref(), Some("a | b"));
+
+    let page = json!({"properties": {
+        "Status": {"type": "select"},
+        "Name": {"type": "title", "title": text("Runbook")}
+    }});
+    assert_eq!(page_title(&page).as_deref(), Some("Runbook"));
+    assert_eq!(page_title(&json!({"properties": {}})), None);
+}
+
+#[test]
+fn test_document_chunks_carry_metadata() {
+    let document = Document {
+        id: "42".to_string(),
+        title: "#42 Crash on empty query".to_string(),
+        url: "https://github.com/acme/app/issues/42".to_string(),
+        content: "Recall panics.\n\n## bob commented on 2026-03-01\n\nReproduced.".to_string(),
+        cues: vec!["label:bug".to_string(), "state:open".to_string()],
+    };
+    let chunks = document_chunks("acme-issues", &document);
+    assert_eq!(chunks.len(), 2);
+    for chunk in &chunks {
+        assert!(chunk.structural_cues.contains(&"title:#42 Crash on empty query".to_string()));
+        assert!(chunk.structural_cues.contains(&"connector:acme-issues".to_string()));
+        assert!(chunk.structural_cues.contains(&"label:bug".to_string()));
+    }
+    assert!(chunks[1].structural_cues.contains(&"header:bob commented on 2026-03-01".to_string()));
+    assert_eq!(config(ConnectorKind::Github).document_source("42"), "connector:docs:42");
+}
+
+#[test]
+fn test_connector_config_validation() {
+    assert!(validate_connector_id("acme_issues-2"));
+    assert!(!validate_connector_id(""));
+    assert!(!validate_connector_id("a/b"));
+
+    let mut github = config(ConnectorKind::Github);
+    github.repo = "acme".to_string();
+    assert!(build_connector(&github).is_err());
+    github.repo = "acme/app".to_string();
+    assert_eq!(build_connector(&github).unwrap().name(), "github:acme/app");
+
+    let mut notion = config(ConnectorKind::Notion);
+    notion.token_env = "CUEMAP_TEST_UNSET_NOTION_TOKEN".to_string();
+    let err = build_connector(&notion).err().unwrap();
+    assert!(err.contains("$CUEMAP_TEST_UNSET_NOTION_TOKEN"), "{}", err);
+
+    let mut moved = github.clone();
+    moved.sync_interval_secs = Some(60);
+    assert!(github.same_source(&moved));
+    moved.repo = "acme/other".to_string();
+    assert!(!github.same_source(&moved));
+}
+
+#[test]
+fn test_email_parsing_and_cues() {
+    use cuemap::connectors::email::{email_document, parse_email, thread_subject};
+
+    let raw = "From: \"Support\" <Support@Acme.com>\r\n\
+To: Bob <bob@example.com>,\r\n carol@example.com\r\n\
+Subject: =?UTF-8?B?UmU6IEV4cG9ydA==?= =?UTF-8?Q?_f=C3=A4hlt?=\r\n\
+Message-ID: <r2@acme.com>\r\n\
+In-Reply-To: <r1@acme.com>\r\n\
+References: <q0@example.com> <r1@acme.com>\r\n\
+Content-Type: multipart/alternative; boundary=\"b1\"\r\n\
+\r\n\
+--b1\r\n\
+Content-Type: text/plain; charset=utf-8\r\n\
+Content-Transfer-Encoding: quoted-printable\r\n\
+\r\n\
+The export job retries now. Use --force =\r\n\
+to skip the check.\r\n\
+\r\n\
+On Mon, Bob wrote:\r\n\
+> Export fails with a timeout\r\n\
+--b1\r\n\
+Content-Type: text/html\r\n\
+\r\n\
+<p>The export job retries now.</p>\r\n\
+--b1--\r\n";
+
+    let email = parse_email(raw);
+    assert_eq!(email.subject, "Re: Export fählt");
+    assert_eq!(email.from, vec!["support@acme.com"]);
+    assert_eq!(email.to, vec!["bob@example.com", "carol@example.com"]);
+    assert_eq!(email.thread_id(), Some("q0@example.com"));
+    assert_eq!(email.body, "The export job retries now. Use --force to skip the check.");
+
+    let document = email_document(&email, "mbox-0");
+    assert_eq!(document.id, "r2@acme.com");
+    assert_eq!(document.url, "mid:r2@acme.com");
+    assert!(document.content.starts_with("From: support@acme.com\nTo: bob@example.com, carol@example.com\n"));
+    for cue in ["from:support@acme.com", "to:carol@example.com", "subject:export_fählt", "thread:q0@example.com"] {
+        assert!(document.cues.contains(&cue.to_string()), "missing {} in {:?}", cue, document.cues);
+    }
+
+    assert_eq!(thread_subject("RE: Fwd: AW: Invoice"), "Invoice");
+
+    // A message without Message-ID or references is its own thread
+    let plain = parse_email("Subject: Hi\n\nHello");
+    let document = email_document(&plain, "mbox-3");
+    assert_eq!(document.id, "mbox-3");
+    assert!(document.cues.contains(&"thread:mbox-3".to_string()));
+}
+
+#[test]
+fn test_mbox_and_imap_listing_helpers() {
+    use cuemap::connectors::email::{parse_search, split_mbox};
+
+    let mbox = "From alice@example.com Mon Jan  5 10:00:00 2026\n\
+Subject: One\n\n\
+>From the docs: this line was escaped\n\
+\n\
+From bob@example.com Tue Jan  6 10:00:00 2026\n\
+Subject: Two\n\nSecond\n";
+    let messages = split_mbox(mbox);
+    assert_eq!(messages.len(), 2);
+    assert!(messages[0].contains("\nFrom the docs: this line was escaped"));
+    assert!(messages[1].starts_with("Subject: Two"));
+
+    assert_eq!(parse_search("* SEARCH 4 7 9\r\nA001 OK SEARCH completed\r\n"), vec![4, 7, 9]);
+    assert!(parse_search("* SEARCH\r\n").is_empty());
+
+    let mut imap = config(ConnectorKind::Imap);
+    imap.username = "support".to_string();
+    imap.api_url = "imaps://imap.example.com/INBOX".to_string();
+    imap.token_env = "CUEMAP_TEST_UNSET_IMAP_PASSWORD".to_string();
+    assert!(build_connector(&imap).err().unwrap().contains("$CUEMAP_TEST_UNSET_IMAP_PASSWORD"));
+    assert!(build_connector(&config(ConnectorKind::Mbox)).is_err());
+}
@@ -0,0 +1,49 @@
+use cuemap::content_index::{content_hash, ContentIndex};
+use cuemap::engine::CueMapEngine;
+use cuemap::structures::MainStats;
+
+#[test]
+fn test_content_hash_is_sha256_hex() {
+    assert_eq!(content_hash(""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+    assert_eq!(content_hash("abc"), content_hash(b"abc"));
+    assert_ne!(content_hash("abc"), content_hash("abc "));
+}
+
+#[test]
+fn test_index_keeps_oldest_memory_per_hash() {
+    let index = ContentIndex::default();
+    index.insert("a", content_hash("same"));
+    index.insert("b", content_hash("same"));
+    index.insert("c", content_hash("other"));
+    assert_eq!(index.len(), 2);
+    assert_eq!(index.find(&content_hash("same")).as_deref(), Some("a"));
+
+    index.remove("a");
+    assert_eq!(index.find(&content_hash("same")).as_deref(), Some("b"));
+    index.rename("b", "b2");
+    assert_eq!(index.find(&content_hash("same")).as_deref(), Some("b2"));
+    assert_eq!(index.hash_of("b"), None);
+
+    // Re-hashing a memory moves it
+    index.insert("c", content_hash("same"));
+    assert_eq!(index.find(&content_hash("other")), None);
+    index.remove("b2");
+    assert_eq!(index.find(&content_hash("same")).as_deref(), Some("c"));
+}
+
+#[test]
+fn test_engine_tracks_identical_content() {
+    let engine = CueMapEngine::<MainStats>::new();
+    let id = engine.add_memory("Payments timed out".to_string(), vec!["payments".to_string()], None, MainStats::default(), true);
+    assert_eq!(engine.find_identical("Payments timed out").as_deref(), Some(id.as_str()));
+    assert_eq!(engine.find_identical("payments timed out"), None, "only byte-identical content matches");
+
+    engine.upsert_memory_with_id(id.clone(), "Payments recovered".to_string(), vec![], None, None, false, false);
+    assert_eq!(engine.find_identical("Payments timed out"), None);
+    assert_eq!(engine.find_identical("Payments recovered").as_deref(), Some(id.as_str()));
+
+    assert!(engine.rename_memory(&id, "incident"));
+    assert_eq!(engine.find_identical("Payments recovered").as_deref(), Some("incident"));
+    assert!(engine.delete_memory("incident"));
+    assert_eq!(engine.find_identical("Payments recovered"), None);
+}
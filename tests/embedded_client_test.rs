@@ -0,0 +1,97 @@
+use cuemap::{Client, RecallOptions};
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn test_embedded_add_and_recall() {
+    let client = Client::builder()
+        .background_jobs(false)
+        .build()
+        .expect("Should build in-memory client");
+
+    let id = client
+        .add_memory("embedded", "Payments service timed out", vec!["service:payments".to_string()], None)
+        .await
+        .expect("Should add memory");
+
+    let results = client
+        .recall_with("embedded", RecallOptions {
+            cues: vec!["service:payments".to_string()],
+            ..RecallOptions::default()
+        })
+        .await
+        .expect("Should recall");
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].memory_id, id);
+    assert!(client.recall("x", "anything", 5).await.is_err()); // invalid project id
+}
+
+#[tokio::test]
+async fn test_embedded_recall_follows_hops() {
+    let client = Client::builder().background_jobs(false).build().unwrap();
+    client.add_memory("embedded", "Payments service timed out", vec!["service:payments".to_string(), "team:core".to_string()], None).await.unwrap();
+    let linked = client.add_memory("embedded", "Core team on-call rota", vec!["team:core".to_string()], None).await.unwrap();
+
+    let recall = |depth| RecallOptions {
+        cues: vec!["service:payments".to_string()],
+        auto_reinforce: false,
+        depth,
+        ..RecallOptions::default()
+    };
+    let one_hop = client.recall_with("embedded", recall(1)).await.unwrap();
+    assert!(one_hop.iter().all(|r| r.memory_id != linked));
+
+    // The second hop recalls with the cues of the first result
+    let two_hops = client.recall_with("embedded", recall(2)).await.unwrap();
+    assert!(two_hops.iter().any(|r| r.memory_id == linked));
+}
+
+#[tokio::test]
+async fn test_embedded_persistence_roundtrip() {
+    let dir = tempdir().unwrap();
+
+    {
+        let client = Client::builder()
+            .data_dir(dir.path())
+            .snapshot_interval(None)
+            .background_jobs(false)
+            .build()
+            .unwrap();
+        client.add_memory("embedded", "persist me", vec!["save:true".to_string()], None).await.unwrap();
+        client.save_project("embedded").expect("Should save");
+    }
+
+    let client = Client::builder()
+        .data_dir(dir.path())
+        .snapshot_interval(None)
+        .background_jobs(false)
+        .build()
+        .unwrap();
+    let results = client
+        .recall_with("embedded", RecallOptions {
+            cues: vec!["save:true".to_string()],
+            auto_reinforce: false,
+            ..RecallOptions::default()
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].content, "persist me");
+}
+
+#[tokio::test]
+async fn test_embedded_ingest_file() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("notes.md");
+    std::fs::write(&file, "# Deployment\n\nRollbacks are handled by the release pipeline.\n").unwrap();
+
+    // Writes are applied by the job worker, so background jobs must stay enabled here
+    let client = Client::builder().build().unwrap();
+    let ids = client.ingest_file("embedded", &file).await.expect("Should ingest file");
+    assert!(!ids.is_empty());
+
+    client.wait_for_ingestion("embedded").await;
+    let ctx = client.engine().get_project(&"embedded".to_string()).unwrap();
+    assert!(ctx.main.get_memory(&ids[0]).is_some());
+}
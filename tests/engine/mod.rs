@@ -1,4 +1,4 @@
-use cuemap::engine::CueMapEngine;
+use cuemap::engine::{CueMapEngine, RecallParams};
 use cuemap::structures::MainStats;
 
 #[test]
@@ -102,3 +102,847 @@ fn test_log_frequency_scaling() {
     assert_eq!(res1.reinforcement_score, 2.0);
     assert_eq!(res2.reinforcement_score, 1.0);
 }
+
+#[test]
+fn test_recall_filter_time_and_metadata() {
+    use cuemap::engine::{MetadataFilter, RecallFilter};
+    use std::collections::HashMap;
+
+    let engine = CueMapEngine::new();
+    let meta = |source: &str, team: &str| {
+        let mut m = HashMap::new();
+        m.insert("source".to_string(), serde_json::json!(source));
+        m.insert("team".to_string(), serde_json::json!(team));
+        Some(m)
+    };
+    let id_docs = engine.add_memory("docs page".to_string(), vec!["topic".to_string()], meta("docs/intro.md", "infra"), MainStats::default(), true);
+    let id_code = engine.add_memory("code file".to_string(), vec!["topic".to_string()], meta("src/main.rs", "infra"), MainStats::default(), true);
+    let _id_other = engine.add_memory("other team".to_string(), vec!["topic".to_string()], meta("docs/faq.md", "web"), MainStats::default(), true);
+
+    let query = vec![("topic".to_string(), 1.0)];
+    let recall = |filter: &RecallFilter| {
+        engine.recall_weighted(query.clone(), 10, &RecallParams { disable_pattern_completion: true, disable_salience_bias: true, disable_systems_consolidation: true, filter: Some(filter), ..RecallParams::default() })
+    };
+
+    // Equality + prefix combine with AND
+    let filter = RecallFilter {
+        metadata_filters: vec![
+            MetadataFilter { key: "team".to_string(), equals: Some(serde_json::json!("infra")), prefix: None },
+            MetadataFilter { key: "source".to_string(), equals: None, prefix: Some("docs/".to_string()) },
+        ],
+        ..RecallFilter::default()
+    };
+    let results = recall(&filter);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].memory_id, id_docs);
+
+    let filter = RecallFilter {
+        metadata_filters: vec![MetadataFilter { key: "source".to_string(), equals: None, prefix: Some("src/".to_string()) }],
+        ..RecallFilter::default()
+    };
+    let results = recall(&filter);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].memory_id, id_code);
+
+    // Time range
+    let created = engine.get_memory(&id_docs).unwrap().created_at;
+    let future = RecallFilter { created_after: Some(created + 3600.0), ..RecallFilter::default() };
+    assert!(recall(&future).is_empty());
+    let window = RecallFilter { created_after: Some(created - 3600.0), created_before: Some(created + 3600.0), ..RecallFilter::default() };
+    assert_eq!(recall(&window).len(), 3);
+}
+
+#[test]
+fn test_recall_exclude_cues() {
+    use cuemap::engine::RecallFilter;
+
+    let engine = CueMapEngine::new();
+    let id_vm = engine.add_memory("deploy failed on vm".to_string(), vec!["deployment".to_string(), "issue".to_string(), "vm".to_string()], None, MainStats::default(), true);
+    let _id_k8s = engine.add_memory("deploy failed on k8s".to_string(), vec!["deployment".to_string(), "issue".to_string(), "kubernetes".to_string()], None, MainStats::default(), true);
+
+    let filter = RecallFilter { exclude_cues: vec!["kubernetes".to_string()], ..RecallFilter::default() };
+    let query = vec![("deployment".to_string(), 1.0), ("issue".to_string(), 1.0)];
+    let results = engine.recall_weighted(query, 10, &RecallParams { disable_salience_bias: true, disable_systems_consolidation: true, filter: Some(&filter), ..RecallParams::default() });
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].memory_id, id_vm);
+
+    // An excluded cue that is also queried contributes nothing
+    let query = vec![("kubernetes".to_string(), 1.0)];
+    let results = engine.recall_weighted(query, 10, &RecallParams { disable_salience_bias: true, disable_systems_consolidation: true, filter: Some(&filter), ..RecallParams::default() });
+    assert!(results.is_empty());
+
+    // A namespaced cue is excluded by its value, which recall also matches it by
+    let _id_tool = engine.add_memory("deploy failed via helm".to_string(), vec!["deployment".to_string(), "tool:kubernetes".to_string()], None, MainStats::default(), true);
+    let query = vec![("deployment".to_string(), 1.0)];
+    let results = engine.recall_weighted(query, 10, &RecallParams { disable_salience_bias: true, disable_systems_consolidation: true, filter: Some(&filter), ..RecallParams::default() });
+    assert_eq!(results.iter().map(|r| r.memory_id.clone()).collect::<Vec<_>>(), vec![id_vm]);
+}
+
+#[test]
+fn test_hybrid_recall_blends_cosine_similarity() {
+    use cuemap::vector::HybridQuery;
+
+    let engine = CueMapEngine::new();
+    let lexical = engine.add_memory("car broke down".to_string(), vec!["car".to_string()], None, MainStats::default(), true);
+    let paraphrase = engine.add_memory("vehicle stopped working".to_string(), vec!["vehicle".to_string()], None, MainStats::default(), true);
+    let unrelated = engine.add_memory("lunch menu".to_string(), vec!["lunch".to_string()], None, MainStats::default(), true);
+    engine.set_embedding(&lexical, vec![1.0, 0.1, 0.0]).unwrap();
+    engine.set_embedding(&paraphrase, vec![0.9, 0.2, 0.0]).unwrap();
+    engine.set_embedding(&unrelated, vec![0.0, 0.0, 1.0]).unwrap();
+    assert!(engine.set_embedding(&unrelated, vec![1.0, 0.0]).is_err()); // dimension mismatch
+
+    let query = vec![("car".to_string(), 1.0)];
+
+    // Pure cue recall misses the paraphrase
+    let results = engine.recall_weighted(query.clone(), 10, &RecallParams { disable_pattern_completion: true, disable_salience_bias: true, disable_systems_consolidation: true, ..RecallParams::default() });
+    assert_eq!(results.len(), 1);
+
+    let hybrid = HybridQuery { vector: vec![1.0, 0.15, 0.0], alpha: 0.5 };
+    let results = engine.recall_weighted(query.clone(), 10, &RecallParams { explain: true, disable_pattern_completion: true, disable_salience_bias: true, disable_systems_consolidation: true, hybrid: Some(&hybrid), ..RecallParams::default() });
+    let ids: Vec<&str> = results.iter().map(|r| r.memory_id.as_str()).collect();
+    assert_eq!(ids, vec![lexical.as_str(), paraphrase.as_str()]);
+    assert!(results[1].explain.as_ref().unwrap()["vector_similarity"].as_f64().unwrap() > 0.9);
+
+    // A query vector alone is enough
+    let results = engine.recall_weighted(Vec::new(), 1, &RecallParams { disable_pattern_completion: true, disable_salience_bias: true, disable_systems_consolidation: true, hybrid: Some(&hybrid), ..RecallParams::default() });
+    assert_eq!(results.len(), 1);
+
+    // Deleting a memory drops its embedding
+    engine.delete_memory(&paraphrase);
+    assert_eq!(engine.vector_index().len(), 2);
+}
+
+#[test]
+fn test_field_boosts_rank_title_matches_above_body_matches() {
+    use cuemap::config::{FieldBoosts, TuningConfig};
+
+    let build = |tuning: TuningConfig| {
+        let engine = CueMapEngine::with_tuning(tuning);
+        for i in 0..8 {
+            engine.add_memory(format!("filler {}", i), vec![format!("filler{}", i)], None, MainStats::default(), true);
+        }
+        // The titled memory is older, so without boosts recency puts the body match first
+        let titled = engine.add_memory(
+            "Invoice export runs nightly".to_string(),
+            vec!["invoice".to_string(), "export".to_string(), "title:Invoice Export".to_string()],
+            None, MainStats::default(), true,
+        );
+        let body = engine.add_memory(
+            "The report mentions an invoice once".to_string(),
+            vec!["report".to_string(), "invoice".to_string()],
+            None, MainStats::default(), true,
+        );
+        (engine, titled, body)
+    };
+    let query = vec![("invoice".to_string(), 1.0)];
+
+    let (engine, titled, body) = build(TuningConfig::default());
+    let results = engine.recall_weighted(query.clone(), 10, &RecallParams { disable_pattern_completion: true, disable_salience_bias: true, disable_systems_consolidation: true, ..RecallParams::default() });
+    assert_eq!(results[0].memory_id, titled);
+    assert_eq!(results[1].memory_id, body);
+
+    // Field tokens follow cue changes
+    engine.detach_cues(&titled, &["title:Invoice Export".to_string()]);
+    let results = engine.recall_weighted(query.clone(), 10, &RecallParams { disable_pattern_completion: true, disable_salience_bias: true, disable_systems_consolidation: true, ..RecallParams::default() });
+    assert_eq!(results[0].memory_id, body);
+
+    let uniform = TuningConfig {
+        field_boosts: FieldBoosts { title: 1.0, header: 1.0, path: 1.0, body: 1.0 },
+        ..TuningConfig::default()
+    };
+    let (engine, _, body) = build(uniform);
+    let results = engine.recall_weighted(query, 10, &RecallParams { disable_pattern_completion: true, disable_salience_bias: true, disable_systems_consolidation: true, ..RecallParams::default() });
+    assert_eq!(results[0].memory_id, body);
+}
+
+#[test]
+fn test_fuzzy_expansion_corrects_typos() {
+    let engine = CueMapEngine::new();
+    let id = engine.add_memory("Cluster upgrade notes".to_string(), vec!["kubernetes".to_string(), "upgrade".to_string(), "go".to_string()], None, MainStats::default(), true);
+
+    // Transposition is a single edit
+    let (cues, corrections) = engine.expand_fuzzy(vec![("kuberentes".to_string(), 1.0)], 2);
+    assert_eq!(corrections.len(), 1);
+    assert_eq!(corrections[0].cue, "kubernetes");
+    assert_eq!(corrections[0].distance, 1);
+    assert!(cues.iter().any(|(c, w)| c == "kubernetes" && (*w - 0.8).abs() < 1e-9));
+
+    let results = engine.recall_weighted(cues, 10, &RecallParams { disable_pattern_completion: true, disable_salience_bias: true, disable_systems_consolidation: true, ..RecallParams::default() });
+    assert_eq!(results[0].memory_id, id);
+
+    // Exact hits and very short cues are left alone
+    let (cues, corrections) = engine.expand_fuzzy(vec![("upgrade".to_string(), 1.0), ("gp".to_string(), 1.0)], 2);
+    assert!(corrections.is_empty());
+    assert_eq!(cues.len(), 2);
+
+    // Max edit distance 0 disables corrections
+    let (_, corrections) = engine.expand_fuzzy(vec![("kuberentes".to_string(), 1.0)], 0);
+    assert!(corrections.is_empty());
+}
+
+#[test]
+fn test_osa_distance_within_budget() {
+    use cuemap::fuzzy::osa_distance;
+
+    assert_eq!(osa_distance("kubernetes", "kuberentes", 2), Some(1));
+    assert_eq!(osa_distance("kubernetes", "kubernetes", 0), Some(0));
+    assert_eq!(osa_distance("deploy", "deployment", 2), None);
+    assert_eq!(osa_distance("abc", "", 3), Some(3));
+    // Edits far off the diagonal still count
+    assert_eq!(osa_distance("abcdef", "bcdefa", 2), Some(2));
+    assert_eq!(osa_distance("abcdef", "bcdefa", 1), None);
+    // Non-ASCII cues compare characters, not bytes
+    assert_eq!(osa_distance("café", "cafe", 1), Some(1));
+    assert_eq!(osa_distance("naïve", "nIave", 1), None);
+}
+
+#[test]
+fn test_recall_phrase_filter() {
+    use cuemap::engine::RecallFilter;
+
+    let engine = CueMapEngine::new();
+    let cues = || vec!["connection".to_string(), "pool".to_string(), "exhausted".to_string()];
+    let exact = engine.add_memory("Error: connection pool exhausted after retries".to_string(), cues(), None, MainStats::default(), true);
+    let loose = engine.add_memory("The connection to the pool was exhausted".to_string(), cues(), None, MainStats::default(), true);
+    let reordered = engine.add_memory("Pool exhausted, connection dropped".to_string(), cues(), None, MainStats::default(), true);
+    let query = vec![("connection".to_string(), 1.0), ("pool".to_string(), 1.0)];
+
+    let recall = |phrase: &str, window: usize| {
+        let filter = RecallFilter { phrase: Some(phrase.to_string()), phrase_window: window, ..RecallFilter::default() };
+        let mut ids: Vec<String> = engine
+            .recall_weighted(query.clone(), 10, &RecallParams { disable_pattern_completion: true, disable_salience_bias: true, disable_systems_consolidation: true, filter: Some(&filter), ..RecallParams::default() })
+            .into_iter()
+            .map(|r| r.memory_id)
+            .collect();
+        ids.sort();
+        ids
+    };
+
+    assert_eq!(recall("Connection Pool Exhausted", 0), vec![exact.clone()]);
+
+    let mut both = vec![exact.clone(), loose.clone()];
+    both.sort();
+    assert_eq!(recall("connection pool exhausted", 2), both);
+
+    // Auto-reinforce only touches the memories that passed the phrase
+    let filter = RecallFilter { phrase: Some("connection pool exhausted".to_string()), ..RecallFilter::default() };
+    let results = engine.recall_weighted(query.clone(), 10, &RecallParams { auto_reinforce: true, disable_pattern_completion: true, disable_salience_bias: true, disable_systems_consolidation: true, filter: Some(&filter), ..RecallParams::default() });
+    assert_eq!(results.len(), 1);
+    let reinforced = |id: &str| engine.get_memory(id).unwrap().stats.reinforcement_count;
+    assert!(reinforced(&exact) > reinforced(&reordered));
+    assert_eq!(reinforced(&loose), reinforced(&reordered));
+
+    let no_phrase = RecallFilter::default();
+    assert!(no_phrase.matches_phrase("anything"));
+}
+
+#[test]
+fn test_cue_prefix_and_wildcard_patterns() {
+    let engine = CueMapEngine::new();
+    let agent = engine.add_memory("Agent loop".to_string(), vec!["path:src/agent/loop.rs".to_string()], None, MainStats::default(), true);
+    engine.add_memory("Agent tools".to_string(), vec!["path:src/agent/tools.rs".to_string()], None, MainStats::default(), true);
+    engine.add_memory("API".to_string(), vec!["path:src/api.rs".to_string()], None, MainStats::default(), true);
+    let timeout = engine.add_memory("Read timed out".to_string(), vec!["error:read_timeout_exceeded".to_string()], None, MainStats::default(), true);
+    engine.add_memory("Refused".to_string(), vec!["error:connection_refused".to_string()], None, MainStats::default(), true);
+
+    assert_eq!(
+        engine.match_cue_pattern("path:src/agent/*", 100),
+        vec!["path:src/agent/loop.rs".to_string(), "path:src/agent/tools.rs".to_string()]
+    );
+    // No `*` means prefix
+    assert_eq!(engine.match_cue_pattern("path:src/agent/", 100).len(), 2);
+    assert_eq!(engine.match_cue_pattern("error:*timeout*", 100), vec!["error:read_timeout_exceeded".to_string()]);
+    assert_eq!(engine.match_cue_pattern("path:*.rs", 1).len(), 1);
+
+    let cues: Vec<(String, f64)> = engine.match_cue_pattern("error:*timeout*", 100).into_iter().map(|c| (c, 1.0)).collect();
+    let results = engine.recall_weighted(cues, 10, &RecallParams { disable_pattern_completion: true, disable_salience_bias: true, disable_systems_consolidation: true, ..RecallParams::default() });
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].memory_id, timeout);
+
+    // Removed cues leave the sorted index
+    engine.delete_memory(&agent);
+    assert_eq!(engine.match_cue_pattern("path:src/agent/*", 100), vec!["path:src/agent/tools.rs".to_string()]);
+}
+
+#[test]
+fn test_near_duplicate_detection() {
+    let engine = CueMapEngine::new();
+    let cues = |list: &[&str]| list.iter().map(|c| c.to_string()).collect::<Vec<String>>();
+    let original = engine.add_memory(
+        "Deploy failed: disk full on build agent".to_string(),
+        cues(&["deploy", "failed", "disk", "full", "build", "agent", "topic:ci", "host:runner-3", "severity:high", "team:infra"]),
+        None,
+        MainStats::default(),
+        false,
+    );
+
+    // Same text modulo case and whitespace, different cues
+    let exact = engine.find_near_duplicate("deploy FAILED:  disk full\non build agent", &cues(&["deploy"]));
+    assert_eq!(exact, Some((original.clone(), 1.0)));
+
+    // 9 of 10 cues shared (with an episode cue that is ignored)
+    let near = cues(&["deploy", "failed", "disk", "full", "build", "agent", "topic:ci", "host:runner-3", "severity:high", "episode:x"]);
+    let (id, similarity) = engine.find_near_duplicate("Build agent disk full, deploy failed", &near).unwrap();
+    assert_eq!(id, original);
+    assert!((similarity - 0.9).abs() < 1e-9);
+
+    assert!(engine.find_near_duplicate("Deploy succeeded", &cues(&["deploy", "succeeded"])).is_none());
+    assert!(engine.find_near_duplicate("anything", &[]).is_none());
+
+    // Same cues, unrelated text: not a duplicate
+    let same_cues = cues(&["deploy", "failed", "disk", "full", "build", "agent", "topic:ci", "host:runner-3", "severity:high", "team:infra"]);
+    assert!(engine.find_near_duplicate("Rotate the runner credentials before Friday", &same_cues).is_none());
+}
+
+#[test]
+fn test_memory_history_and_rollback() {
+    use cuemap::history::MemoryHistory;
+
+    let mut engine = CueMapEngine::new();
+    engine.set_history(MemoryHistory::new(2));
+    let id = "doc:1".to_string();
+    let upsert = |content: &str, cue: &str| {
+        engine.upsert_memory_with_id(id.clone(), content.to_string(), vec![cue.to_string()], None, Some(MainStats::default()), false, true);
+    };
+
+    upsert("v1", "one");
+    upsert("v1", "one"); // unchanged, not recorded
+    assert!(engine.history().versions(&id).is_empty());
+
+    upsert("v2", "two");
+    upsert("v3", "three");
+    upsert("v4", "four");
+    let versions = engine.history().versions(&id);
+    // Capped at 2: v1 was dropped
+    assert_eq!(versions.iter().map(|v| v.version).collect::<Vec<_>>(), vec![2, 3]);
+    assert_eq!(engine.version_content(&versions[0]).unwrap(), "v2");
+    assert_eq!(engine.history().current_version(&id), 4);
+
+    assert_eq!(engine.rollback_memory(&id, 2).unwrap(), 5);
+    let memory = engine.get_memory(&id).unwrap();
+    assert_eq!(memory.access_content(None).unwrap(), "v2");
+    assert_eq!(memory.cues, vec!["two".to_string()]);
+    assert!(engine.recall(vec!["four".to_string()], 10, false, None).is_empty());
+    // The replaced v4 is kept
+    assert_eq!(engine.version_content(&engine.history().get(&id, 4).unwrap()).unwrap(), "v4");
+
+    assert!(engine.rollback_memory(&id, 1).is_err());
+    engine.delete_memory(&id);
+    assert!(engine.history().versions(&id).is_empty());
+}
+
+#[test]
+fn test_typed_links_and_follow_links() {
+    let engine = CueMapEngine::new();
+    let outage = engine.add_memory("Checkout outage".to_string(), vec!["outage".to_string()], None, MainStats::default(), true);
+    let cause = engine.add_memory("Expired TLS certificate".to_string(), vec!["tls".to_string()], None, MainStats::default(), true);
+    let runbook = engine.add_memory("Certificate rotation runbook".to_string(), vec!["runbook".to_string()], None, MainStats::default(), true);
+
+    assert_eq!(engine.add_link(&outage, "caused_by", &cause), Ok(true));
+    assert_eq!(engine.add_link(&outage, "Caused_By", &cause), Ok(false));
+    assert_eq!(engine.add_link(&outage, "see_also", &runbook), Ok(true));
+    assert!(engine.add_link(&outage, "bad type", &cause).is_err());
+    assert!(engine.add_link(&outage, "part_of", &outage).is_err());
+    assert!(engine.add_link(&outage, "part_of", "missing").is_err());
+
+    assert_eq!(engine.links(&outage).len(), 2);
+    assert_eq!(engine.backlinks(&cause), vec![(outage.clone(), "caused_by".to_string())]);
+
+    let mut results = engine.recall(vec!["outage".to_string()], 10, false, None);
+    assert_eq!(results.len(), 1);
+    engine.follow_links(&mut results, &["caused_by".to_string()], 10, &cuemap::engine::RecallFilter::default());
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[1].memory_id, cause);
+    assert_eq!(results[1].metadata["linked_from"], serde_json::json!(outage));
+    assert!((results[1].score - results[0].score * cuemap::engine::LINK_SCORE_DECAY).abs() < 1e-9);
+
+    // Links show up as memory-to-memory edges in the graph
+    let graph = engine.get_graph_data(0);
+    let typed: Vec<_> = graph["links"].as_array().unwrap().iter().filter(|l| l.get("type").is_some()).collect();
+    assert_eq!(typed.len(), 2);
+
+    assert!(engine.remove_link(&outage, None, &runbook));
+    assert!(!engine.remove_link(&outage, None, &runbook));
+    engine.delete_memory(&cause);
+    assert!(engine.links(&outage).is_empty());
+}
+
+#[test]
+fn test_follow_links_applies_filter_and_limit() {
+    use cuemap::engine::RecallFilter;
+    use std::collections::HashMap;
+
+    let engine = CueMapEngine::new();
+    let outage = engine.add_memory("Checkout outage".to_string(), vec!["outage".to_string()], None, MainStats::default(), true);
+    let cause = engine.add_memory("Expired TLS certificate".to_string(), vec!["tls".to_string(), "secret".to_string()], None, MainStats::default(), true);
+    let runbook = engine.add_memory("Certificate rotation runbook".to_string(), vec!["runbook".to_string()], None, MainStats::default(), true);
+    engine.add_link(&outage, "caused_by", &cause).unwrap();
+    engine.add_link(&outage, "see_also", &runbook).unwrap();
+
+    // Excluded memories stay out when reached through a link
+    let filter = RecallFilter { exclude_cues: vec!["secret".to_string()], ..RecallFilter::default() };
+    let mut results = engine.recall(vec!["outage".to_string()], 10, false, None);
+    engine.follow_links(&mut results, &[], 10, &filter);
+    let ids: Vec<_> = results.iter().map(|r| r.memory_id.clone()).collect();
+    assert_eq!(ids, vec![outage.clone(), runbook.clone()]);
+
+    // Linked memories never push the results past the limit
+    let mut results = engine.recall(vec!["outage".to_string()], 10, false, None);
+    engine.follow_links(&mut results, &[], 2, &RecallFilter::default());
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].memory_id, outage);
+
+    // Links are not metadata: user metadata can't clash with them and replacing it keeps them
+    let metadata = HashMap::from([("links".to_string(), serde_json::json!("https://example.com"))]);
+    engine.upsert_memory_with_id(outage.clone(), "Checkout outage".to_string(), vec!["outage".to_string()], Some(metadata), None, false, false);
+    assert_eq!(engine.links(&outage).len(), 2);
+    assert_eq!(engine.get_memory(&outage).unwrap().metadata["links"], serde_json::json!("https://example.com"));
+}
+
+#[test]
+fn test_adaptive_scan_budget_widens_for_broad_cues() {
+    use cuemap::config::TuningConfig;
+    use cuemap::engine::RecallFilter;
+
+    let mut engine = CueMapEngine::new();
+    engine.set_tuning_config(TuningConfig {
+        adaptive_scan_factor: 1,
+        adaptive_scan_max: 5,
+        adaptive_scan_coverage: 1.0,
+        adaptive_scan_ceiling: 100,
+        ..TuningConfig::default()
+    });
+    // The only memory with both cues is the oldest entry of both lists
+    let target = engine.add_memory("both".to_string(), vec!["alpha".to_string(), "beta".to_string()], None, MainStats::default(), true);
+    for i in 0..30 {
+        engine.add_memory(format!("alpha {}", i), vec!["alpha".to_string()], None, MainStats::default(), true);
+        engine.add_memory(format!("beta {}", i), vec!["beta".to_string()], None, MainStats::default(), true);
+    }
+
+    // Both cues are in half the memories, so scores barely separate them;
+    // requiring both cues makes the result the candidate set itself
+    let query = vec![("alpha".to_string(), 1.0), ("beta".to_string(), 1.0)];
+    let recall = |filter: &RecallFilter| {
+        engine.recall_weighted(query.clone(), 1, &RecallParams { min_intersection: Some(2), disable_pattern_completion: true, disable_salience_bias: true, disable_systems_consolidation: true, filter: Some(filter), ..RecallParams::default() })
+            .into_iter()
+            .map(|r| r.memory_id)
+            .collect::<Vec<_>>()
+    };
+
+    // Neither list fits the base budget of 1, so it widens to cover them
+    let budgets = engine.scan_budgets(&query, 1, None);
+    assert_eq!(budgets.len(), 2);
+    assert!(budgets.iter().all(|b| b.document_frequency == 31 && b.budget == 31));
+    assert_eq!(recall(&RecallFilter::default()), vec![target.clone()]);
+
+    // A per-request override takes precedence
+    let narrow = RecallFilter { scan_limit: Some(1), ..RecallFilter::default() };
+    assert_eq!(engine.scan_budgets(&query, 1, Some(1))[0].budget, 1);
+    assert!(recall(&narrow).is_empty());
+}
+
+#[test]
+fn test_bounded_top_k_matches_full_sort() {
+    let engine = CueMapEngine::new();
+    for i in 0..200 {
+        let mut cues = vec!["service".to_string()];
+        if i % 2 == 0 {
+            cues.push("error".to_string());
+        }
+        if i % 7 == 0 {
+            cues.push("timeout".to_string());
+        }
+        engine.add_memory(format!("memory {}", i), cues, None, MainStats::default(), true);
+    }
+
+    let query = vec![("service".to_string(), 1.0), ("error".to_string(), 1.0), ("timeout".to_string(), 1.0)];
+    // min_intersection 1 keeps every candidate, so it takes the unbounded path
+    let bounded = engine.recall_weighted(query.clone(), 5, &RecallParams { disable_pattern_completion: true, disable_salience_bias: true, ..RecallParams::default() });
+    let full = engine.recall_weighted(query, 5, &RecallParams { min_intersection: Some(1), disable_pattern_completion: true, disable_salience_bias: true, ..RecallParams::default() });
+
+    let ids = |results: &[cuemap::engine::RecallResult]| results.iter().map(|r| r.memory_id.clone()).collect::<Vec<_>>();
+    assert_eq!(bounded.len(), 5);
+    assert_eq!(ids(&bounded), ids(&full));
+    assert!(bounded.windows(2).all(|w| w[0].score >= w[1].score));
+}
+
+#[test]
+fn test_hot_cue_segments_keep_one_ordered_list() {
+    use cuemap::config::TuningConfig;
+
+    let mut engine = CueMapEngine::new();
+    engine.set_tuning_config(TuningConfig { hot_cue_segment_size: 4, ..TuningConfig::default() });
+    let ids: Vec<String> = (0..10)
+        .map(|i| engine.add_memory(format!("agent note {}", i), vec!["source:agent".to_string()], None, MainStats::default(), true))
+        .collect();
+
+    let index = engine.get_cue_index();
+    {
+        let set = index.get("source:agent").unwrap();
+        assert!(set.segment_count() > 1);
+        assert_eq!(set.len(), 10);
+        // Newest first across segments, positions counted oldest first
+        let expected: Vec<String> = ids.iter().rev().cloned().collect();
+        assert_eq!(set.get_recent_owned(None), expected);
+        assert_eq!(set.get_index_of(&ids[0]), Some(0));
+        assert_eq!(set.get_index_of(&ids[9]), Some(9));
+    }
+
+    // Reinforcing an id in a sealed segment moves it to the front
+    assert!(engine.reinforce_memory(&ids[1], vec!["source:agent".to_string()]));
+    assert_eq!(index.get("source:agent").unwrap().get_recent_owned(Some(1)), vec![ids[1].clone()]);
+
+    assert!(engine.delete_memory(&ids[0]));
+    assert_eq!(index.get("source:agent").unwrap().len(), 9);
+    let results = engine.recall(vec!["source:agent".to_string()], 3, false, None);
+    assert_eq!(results.len(), 3);
+
+    // "source:agent" is also indexed under its value, "agent"
+    let stats = engine.cue_index_stats();
+    assert!(stats.shards.iter().map(|s| s.writes).sum::<u64>() >= 20);
+    assert_eq!(stats.shards.iter().map(|s| s.cues).sum::<usize>(), 2);
+    assert_eq!(stats.max_list_length, 9);
+    assert!(matches!(stats.hottest_cue.as_deref(), Some("source:agent") | Some("agent")));
+    assert_eq!(stats.segmented_cues, 2);
+    assert_eq!(stats.list_length_buckets.last(), Some(&2));
+}
+
+#[test]
+fn test_co_occurrence_neighbours_are_capped() {
+    use cuemap::config::TuningConfig;
+
+    let mut engine = CueMapEngine::<MainStats>::new();
+    engine.set_tuning_config(TuningConfig { co_occurrence_max_neighbors: 3, ..TuningConfig::default() });
+    // "hub" pairs with "strong" often, then with a stream of one-off cues
+    for _ in 0..64 {
+        engine.update_cue_co_occurrence(&["hub".to_string(), "strong".to_string()]);
+    }
+    for i in 0..10 {
+        engine.update_cue_co_occurrence(&["hub".to_string(), format!("rare{}", i)]);
+    }
+
+    let neighbours = &engine.co_occurrence_snapshot().neighbours["hub"];
+    assert!(neighbours.len() <= 3, "{:?}", neighbours);
+    // Decayed but not evicted, and the newest neighbour was admitted
+    assert!(neighbours["strong"] >= 1);
+    assert!(neighbours.contains_key("rare9"));
+}
+
+#[test]
+fn test_graph_pagination_and_neighborhood() {
+    let engine = CueMapEngine::new();
+    let k8s: Vec<String> = (0..5)
+        .map(|i| engine.add_memory(format!("k8s {}", i), vec!["kubernetes".to_string(), format!("pod{}", i)], None, MainStats::default(), true))
+        .collect();
+    let other = engine.add_memory("billing".to_string(), vec!["billing".to_string()], None, MainStats::default(), true);
+
+    // Cursor pages cover every memory exactly once
+    let mut seen = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let (graph, next) = engine.get_graph_page(2, cursor.as_deref()).unwrap();
+        for node in graph["nodes"].as_array().unwrap() {
+            if node["group"] == "memory" {
+                seen.push(node["id"].as_str().unwrap().to_string());
+            }
+        }
+        match next {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    seen.sort();
+    let mut all: Vec<String> = k8s.iter().cloned().chain(std::iter::once(other.clone())).collect();
+    all.sort();
+    assert_eq!(seen, all);
+    assert!(engine.get_graph_page(2, Some("garbage")).is_err());
+
+    // Two hops from a cue: its memories, then their cues, and nothing unrelated
+    let graph = engine.graph_neighborhood("cue:kubernetes", 2, 3).unwrap();
+    let ids: Vec<&str> = graph["nodes"].as_array().unwrap().iter().map(|n| n["id"].as_str().unwrap()).collect();
+    assert_eq!(ids.iter().filter(|id| !id.starts_with("cue:")).count(), 3);
+    assert!(ids.contains(&"cue:kubernetes"));
+    assert!(ids.contains(&"cue:pod4"));
+    assert!(!ids.contains(&other.as_str()) && !ids.contains(&"cue:billing"));
+    assert_eq!(graph["truncated"], serde_json::json!(true));
+
+    // One hop from a memory reaches only its cues
+    let graph = engine.graph_neighborhood(&k8s[0], 1, 10).unwrap();
+    assert_eq!(graph["nodes"].as_array().unwrap().len(), 3);
+    assert!(engine.graph_neighborhood("cue:missing", 1, 10).is_none());
+}
+
+#[test]
+fn test_cue_analytics() {
+    let engine = CueMapEngine::new();
+    for i in 0..4 {
+        engine.add_memory(format!("deploy {}", i), vec!["deploy".to_string(), format!("host{}", i)], None, MainStats::default(), true);
+    }
+    engine.record_cue_hits(&[("deploy".to_string(), 1.0), ("host0".to_string(), 1.0), ("unknown".to_string(), 1.0)]);
+
+    let analytics = engine.cue_analytics(2);
+    assert_eq!(analytics.total_cues, 5);
+    assert_eq!(analytics.total_memories, 4);
+    assert_eq!(analytics.top_cues.len(), 2);
+    assert_eq!(analytics.top_cues[0].cue, "deploy");
+    assert_eq!(analytics.top_cues[0].memory_count, 4);
+    assert_eq!(analytics.top_cues[0].recall_hits, 1);
+    assert_eq!(analytics.singleton_cue_count, 4);
+    assert_eq!(analytics.singleton_cues.len(), 2);
+    // host1..host3 were never part of a recall; unknown cues are not tracked
+    assert_eq!(analytics.never_recalled_count, 3);
+    assert!(analytics.never_recalled.iter().all(|c| c.cue != "host0" && c.cue != "deploy"));
+    assert_eq!(analytics.idf_histogram.iter().map(|b| b.cues).sum::<usize>(), 5);
+}
+
+#[test]
+fn test_demote_memory_lowers_salience_and_detaches_cues() {
+    let engine = CueMapEngine::new();
+    let id = engine.add_memory(
+        "old pricing".to_string(),
+        vec!["pricing".to_string(), "payments".to_string(), "service:payments".to_string()],
+        None,
+        MainStats::default(),
+        true,
+    );
+    let before = engine.get_memory(&id).unwrap().stats.salience();
+
+    let detached = engine.demote_memory(&id, 0.5, &["PRICING".to_string(), "service:payments".to_string()]).unwrap();
+    assert_eq!(detached, vec!["pricing".to_string(), "service:payments".to_string()]);
+    let memory = engine.get_memory(&id).unwrap();
+    assert_eq!(memory.cues, vec!["payments".to_string()]);
+    assert!(memory.stats.salience() < before);
+
+    assert!(engine.recall(vec!["pricing".to_string()], 10, false, None).is_empty());
+    // "payments" is still a cue of its own, so its entry survives the detached "service:payments"
+    assert_eq!(engine.recall(vec!["payments".to_string()], 10, false, None).len(), 1);
+
+    // Salience bottoms out at zero
+    engine.demote_memory(&id, 100.0, &[]).unwrap();
+    assert_eq!(engine.get_memory(&id).unwrap().stats.salience(), 0.0);
+    assert!(engine.demote_memory("missing", 0.5, &[]).is_none());
+}
+
+#[test]
+fn test_temporal_chunking_is_keyed_by_group() {
+    let engine = CueMapEngine::new();
+    let cues = || vec!["deploy".to_string(), "payments".to_string()];
+    let a1 = engine.add_memory_in_group("agent a: step 1".to_string(), cues(), None, MainStats::default(), false, Some("agent-a"));
+    let b1 = engine.add_memory_in_group("agent b: step 1".to_string(), cues(), None, MainStats::default(), false, Some("agent-b"));
+    let a2 = engine.add_memory_in_group("agent a: step 2".to_string(), cues(), None, MainStats::default(), false, Some("agent-a"));
+    let b2 = engine.add_memory_in_group("agent b: step 2".to_string(), cues(), None, MainStats::default(), false, Some("agent-b"));
+
+    let episodes = |id: &str| -> Vec<String> {
+        engine.get_memory(id).unwrap().cues.into_iter().filter(|c| c.starts_with("episode:")).collect()
+    };
+    assert!(episodes(&b1).is_empty());
+    assert_eq!(episodes(&a2), vec![format!("episode:{}", a1)]);
+    assert_eq!(episodes(&b2), vec![format!("episode:{}", b1)]);
+
+    // Ungrouped writes chain per project, apart from every group
+    let plain = engine.add_memory("no group".to_string(), cues(), None, MainStats::default(), false);
+    assert!(episodes(&plain).is_empty());
+}
+
+#[test]
+fn test_session_timeline_chains_episode() {
+    let engine = CueMapEngine::new();
+    let first = engine.add_memory_to_session("chat-1", "User asked about billing".to_string(), vec!["billing".to_string()], None, MainStats::default());
+    let other = engine.add_memory_to_session("chat-2", "Unrelated conversation".to_string(), vec!["billing".to_string()], None, MainStats::default());
+    let second = engine.add_memory_to_session("chat-1", "Refund was issued".to_string(), vec!["refund".to_string()], None, MainStats::default());
+
+    let timeline: Vec<String> = engine.session_timeline("chat-1").into_iter().map(|m| m.id).collect();
+    assert_eq!(timeline, vec![first.clone(), second.clone()]);
+
+    // Each memory links to the previous one of its own session only
+    let memory = engine.get_memory(&second).unwrap();
+    assert!(memory.cues.contains(&format!("episode:{}", first)));
+    assert!(!memory.cues.iter().any(|c| c.contains(&other)));
+    assert!(!engine.get_memory(&other).unwrap().cues.iter().any(|c| c.starts_with("episode:")));
+    assert_eq!(memory.metadata["session_id"], serde_json::json!("chat-1"));
+
+    let results = engine.recall(vec!["session:chat-1".to_string()], 10, false, None);
+    assert_eq!(results.len(), 2);
+    assert!(engine.session_timeline("missing").is_empty());
+}
+
+#[test]
+fn test_concurrent_session_writes_form_one_chain() {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    let engine = Arc::new(CueMapEngine::<MainStats>::new());
+    let handles: Vec<_> = (0..8)
+        .map(|t| {
+            let engine = engine.clone();
+            std::thread::spawn(move || {
+                for i in 0..20 {
+                    engine.add_memory_to_session("chat", format!("turn {} {}", t, i), vec!["chat".to_string()], None, MainStats::default());
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    // Every memory but the first follows a different predecessor
+    let timeline = engine.session_timeline("chat");
+    assert_eq!(timeline.len(), 160);
+    let predecessors: Vec<&String> = timeline.iter().flat_map(|m| m.cues.iter().filter(|c| c.starts_with("episode:"))).collect();
+    assert_eq!(predecessors.len(), 159);
+    assert_eq!(predecessors.iter().collect::<HashSet<_>>().len(), 159);
+}
+
+#[test]
+fn test_export_import_records_roundtrip() {
+    use std::collections::HashMap;
+
+    let source = CueMapEngine::new();
+    let mut meta = HashMap::new();
+    meta.insert("source".to_string(), serde_json::json!("notes.md"));
+    let id_old = source.add_memory("first".to_string(), vec!["topic".to_string()], Some(meta), MainStats::default(), true);
+    let id_new = source.add_memory("second".to_string(), vec!["topic".to_string(), "extra".to_string()], None, MainStats::default(), true);
+    source.reinforce_memory(&id_new, vec!["topic".to_string()]);
+
+    // Serialize through JSONL like the HTTP export does
+    let jsonl: Vec<String> = source
+        .memory_ids_by_age()
+        .iter()
+        .map(|id| serde_json::to_string(&source.export_record(id).unwrap()).unwrap())
+        .collect();
+    assert_eq!(jsonl.len(), 2);
+
+    let target = CueMapEngine::<MainStats>::new();
+    for line in &jsonl {
+        assert!(target.import_record(serde_json::from_str(line).unwrap()));
+    }
+    // Dedup by id
+    assert!(!target.import_record(serde_json::from_str(&jsonl[0]).unwrap()));
+    assert_eq!(target.total_memories(), 2);
+
+    let original = source.get_memory(&id_old).unwrap();
+    let restored = target.get_memory(&id_old).unwrap();
+    assert_eq!(restored.created_at, original.created_at);
+    assert_eq!(restored.metadata.get("source"), Some(&serde_json::json!("notes.md")));
+    assert_eq!(
+        target.get_memory(&id_new).unwrap().stats.reinforcement_count,
+        source.get_memory(&id_new).unwrap().stats.reinforcement_count
+    );
+
+    let results = target.recall(vec!["extra".to_string()], 10, false, None);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].content, "second");
+}
+
+#[test]
+fn test_compact_index_removes_stale_ids() {
+    use cuemap::structures::OrderedSet;
+
+    let engine = CueMapEngine::new();
+    let keep = engine.add_memory("keep".to_string(), vec!["topic".to_string(), "shared".to_string()], None, MainStats::default(), true);
+    let gone = engine.add_memory("gone".to_string(), vec!["topic".to_string(), "shared".to_string()], None, MainStats::default(), true);
+    engine.update_cue_co_occurrence(&["topic".to_string(), "orphan".to_string()]);
+    assert!(engine.delete_memory(&gone));
+
+    // Simulate ids left behind in posting lists
+    engine.get_cue_index().get_mut("topic").unwrap().add("ghost".to_string());
+    let mut orphan = OrderedSet::new();
+    orphan.add("ghost".to_string());
+    engine.get_cue_index().insert("orphan".to_string(), orphan);
+
+    let report = engine.compact_index();
+    assert_eq!(report.stale_ids_removed, 2);
+    assert_eq!(report.empty_cues_removed, 1);
+    assert!(report.co_occurrence_entries_removed >= 1);
+    assert!(engine.get_cue_index().get("orphan").is_none());
+
+    let results = engine.recall(vec!["topic".to_string()], 10, false, None);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].memory_id, keep);
+
+    let breakdown = engine.cue_memory_breakdown(10);
+    assert!(breakdown.iter().any(|c| c.cue == "topic" && c.memory_count == 1));
+}
+
+#[test]
+fn test_recall_full_trace_reports_pruned_candidates() {
+    use cuemap::engine::RecallFilter;
+
+    let engine = CueMapEngine::new();
+    let a = engine.add_memory("a".to_string(), vec!["topic".to_string(), "extra".to_string()], None, MainStats::default(), true);
+    let b = engine.add_memory("b".to_string(), vec!["topic".to_string()], None, MainStats::default(), true);
+    let c = engine.add_memory("c".to_string(), vec!["topic".to_string(), "banned".to_string()], None, MainStats::default(), true);
+
+    let filter = RecallFilter { exclude_cues: vec!["banned".to_string()], ..RecallFilter::default() };
+    let query = vec![("topic".to_string(), 1.0), ("extra".to_string(), 1.0)];
+    let (results, trace) = engine.recall_weighted_traced(query, 1, &RecallParams { disable_pattern_completion: true, disable_salience_bias: true, disable_systems_consolidation: true, filter: Some(&filter), ..RecallParams::default() });
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].memory_id, a);
+    assert!(results[0].explain.is_some());
+    assert_eq!(trace.cue_weights.len(), 2);
+    assert!(trace.pattern_completion_cues.is_empty());
+
+    let find = |id: &str| trace.candidates.iter().find(|t| t.memory_id == id).unwrap();
+    let top = find(&a);
+    assert!(top.included);
+    assert_eq!(top.matched_cues.len(), 2);
+    assert_eq!(find(&b).excluded_reason.as_deref(), Some("below_limit"));
+    assert!(find(&b).score.is_some());
+    assert_eq!(find(&c).excluded_reason.as_deref(), Some("filtered"));
+}
+
+#[test]
+fn test_rename_memory_keeps_stats_and_position() {
+    let engine = CueMapEngine::new();
+    let a = engine.add_memory("alpha".to_string(), vec!["shared".to_string(), "path:/src/a.rs".to_string()], None, MainStats::default(), false);
+    let b = engine.add_memory("beta".to_string(), vec!["shared".to_string()], None, MainStats::default(), false);
+    engine.reinforce_memory(&a, vec!["shared".to_string()]);
+    let salience = engine.get_memory(&a).unwrap().stats.intrinsic_salience;
+    assert!(salience > 1.0);
+    assert_eq!(engine.get_cue_index().get("shared").unwrap().get_recent_owned(None), vec![a.clone(), b.clone()]);
+
+    assert!(engine.rename_memory(&a, "file:/src/a.rs:5-9"));
+    assert!(engine.get_memory(&a).is_none());
+    let moved = engine.get_memory("file:/src/a.rs:5-9").unwrap();
+    assert_eq!(moved.id, "file:/src/a.rs:5-9");
+    assert_eq!(moved.stats.intrinsic_salience, salience);
+    assert_eq!(
+        engine.get_cue_index().get("shared").unwrap().get_recent_owned(None),
+        vec!["file:/src/a.rs:5-9".to_string(), b.clone()],
+        "the reinforced position is kept"
+    );
+    assert!(engine.get_cue_index().get("/src/a.rs").unwrap().contains("file:/src/a.rs:5-9"));
+
+    let results = engine.recall(vec!["path:/src/a.rs".to_string()], 10, false, None);
+    assert_eq!(results[0].memory_id, "file:/src/a.rs:5-9");
+
+    assert!(!engine.rename_memory("file:/src/a.rs:5-9", &b), "target exists");
+    assert!(!engine.rename_memory("missing", "other"));
+}
+
+#[test]
+fn test_rename_memories_handles_chains_and_replaced_targets() {
+    let engine = CueMapEngine::new();
+    let a = engine.add_memory("alpha".to_string(), vec!["doc".to_string()], None, MainStats::default(), false);
+    let b = engine.add_memory("beta".to_string(), vec!["doc".to_string()], None, MainStats::default(), false);
+    let c = engine.add_memory("gamma".to_string(), vec!["doc".to_string()], None, MainStats::default(), false);
+    engine.reinforce_memory(&a, vec!["doc".to_string()]);
+
+    // a shifts onto b's id while b shifts onto a free one
+    let moves = vec![(a.clone(), b.clone()), (b.clone(), "d".to_string())];
+    assert_eq!(engine.rename_memories(&moves), 2);
+    assert_eq!(engine.get_memory(&b).unwrap().access_content(None).unwrap(), "alpha");
+    assert!(engine.get_memory(&b).unwrap().stats.intrinsic_salience > 1.0);
+    assert_eq!(engine.get_memory("d").unwrap().access_content(None).unwrap(), "beta");
+    assert!(engine.get_memory(&a).is_none());
+    assert_eq!(engine.get_memory(&c).unwrap().access_content(None).unwrap(), "gamma");
+
+    // Swap
+    let moves = vec![(b.clone(), "d".to_string()), ("d".to_string(), b.clone())];
+    assert_eq!(engine.rename_memories(&moves), 2);
+    assert_eq!(engine.get_memory(&b).unwrap().access_content(None).unwrap(), "beta");
+    assert_eq!(engine.get_memory("d").unwrap().access_content(None).unwrap(), "alpha");
+    assert_eq!(engine.get_cue_index().get("doc").unwrap().len(), 3);
+}
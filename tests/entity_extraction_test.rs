@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod tests {
+    use cuemap::entities::*;
+
+    #[test]
+    fn test_named_entities_and_dates() {
+        let text = "The outage hit Acme Corp on March 15, 2024. Alice Johnson from the Bank of America team \
+                    paged AWS support. Follow-up review is due 2024-04-02, and the retro happens in May 2024.";
+        let cues = extract_entity_cues(text);
+
+        assert!(cues.contains(&"entity:acme_corp".to_string()), "{:?}", cues);
+        assert!(cues.contains(&"entity:alice_johnson".to_string()));
+        assert!(cues.contains(&"entity:bank_of_america".to_string()));
+        assert!(cues.contains(&"entity:aws".to_string()));
+        assert!(cues.contains(&"entity:2024-03-15".to_string()));
+        assert!(cues.contains(&"entity:2024-04-02".to_string()));
+        assert!(cues.contains(&"entity:2024-05".to_string()));
+
+        // Sentence-initial words and leading stopwords are not entities
+        assert!(!cues.iter().any(|c| c.starts_with("entity:the") || c == "entity:follow"));
+        // Months only appear as dates
+        assert!(!cues.contains(&"entity:march".to_string()));
+    }
+
+    #[test]
+    fn test_code_identifiers_are_split() {
+        assert_eq!(split_identifier("getUserById"), vec!["get", "user", "by", "id"]);
+        assert_eq!(split_identifier("parseJSONResponse"), vec!["parse", "json", "response"]);
+        assert_eq!(split_identifier("MAX_RETRIES"), vec!["max", "retries"]);
+
+        let cues = extract_entity_cues("Call getUserById before HttpClient retries; see MAX_RETRIES and fetch_user_profile.");
+        assert!(cues.contains(&"symbol:get_user_by_id".to_string()), "{:?}", cues);
+        assert!(cues.contains(&"symbol:http_client".to_string()));
+        assert!(cues.contains(&"symbol:max_retries".to_string()));
+        assert!(cues.contains(&"symbol:fetch_user_profile".to_string()));
+        // Parts become plain cues; short or stopword parts are dropped
+        assert!(cues.contains(&"user".to_string()));
+        assert!(cues.contains(&"profile".to_string()));
+        assert!(!cues.contains(&"by".to_string()));
+
+        // Plain words and hashes are not symbols
+        assert!(extract_symbols("Deploy the service with commit a1b2c3d4e5").is_empty());
+        assert!(extract_entity_cues("").is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+use cuemap::config::{CueGenStrategy, TuningConfig};
+use cuemap::eval::{self, EvalSuite, EvalTarget, GoldenTrace, Relevance, TraceResult};
+use indexmap::IndexMap;
+
+fn ids(ids: &[&str]) -> Vec<String> {
+    ids.iter().map(|s| s.to_string()).collect()
+}
+
+fn trace(id: &str, rank: usize, score: f64) -> TraceResult {
+    TraceResult { memory_id: id.to_string(), rank, score, intersection_count: 1 }
+}
+
+#[test]
+fn test_ranking_metrics() {
+    let relevant = Relevance::Ids(ids(&["b", "d"]));
+    let m = eval::ranking_metrics(&ids(&["a", "b", "c", "d"]), &relevant, 3).unwrap();
+    assert_eq!(m.mrr, 0.5);
+    assert_eq!(m.recall, 0.5); // "d" is past k
+    let ideal = 1.0 + 1.0 / 3f64.log2();
+    assert!((m.ndcg - (1.0 / 3f64.log2()) / ideal).abs() < 1e-9);
+
+    let perfect = eval::ranking_metrics(&ids(&["b", "d"]), &relevant, 10).unwrap();
+    assert_eq!((perfect.mrr, perfect.ndcg, perfect.recall), (1.0, 1.0, 1.0));
+
+    // Graded: the higher grade belongs first
+    let graded = Relevance::Graded(IndexMap::from([("a".to_string(), 3.0), ("b".to_string(), 1.0)]));
+    let swapped = eval::ranking_metrics(&ids(&["b", "a"]), &graded, 2).unwrap();
+    assert!(swapped.ndcg < 1.0 && swapped.mrr == 1.0);
+
+    assert!(eval::ranking_metrics(&ids(&["a"]), &Relevance::default(), 10).is_none());
+    assert_eq!(eval::ranking_metrics(&[], &relevant, 10).unwrap().mrr, 0.0);
+}
+
+#[test]
+fn test_golden_trace_diff() {
+    let golden = GoldenTrace { results: vec![trace("a", 1, 2.0), trace("b", 2, 1.0), trace("c", 3, 0.5)], ..Default::default() };
+    assert!(eval::diff_trace(&golden.results, &golden, 1e-3).is_empty());
+
+    let actual = vec![trace("a", 1, 2.5), trace("c", 2, 0.5)];
+    let diffs = eval::diff_trace(&actual, &golden, 1e-3);
+    assert_eq!(diffs.len(), 3);
+    assert!(diffs[0].contains("score 2.0000 -> 2.5000"));
+    assert!(diffs[1].contains("expected b, got c (b not returned)"));
+    assert!(diffs[2].contains("expected c, got nothing"));
+}
+
+#[tokio::test]
+async fn test_in_process_suite_run_and_golden_recording() {
+    let yaml = r#"
+name: payments
+memories:
+  - id: timeout
+    content: Payments service timed out calling the ledger
+    cues: [service:payments, error:timeout]
+  - id: deploy
+    content: Payments service deployed
+    cues: [service:payments, event:deploy]
+queries:
+  - name: payments-timeout
+    cues: [service:payments, error:timeout]
+    relevant: [timeout]
+  - name: unjudged
+    cues: [event:deploy]
+"#;
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("payments.yaml");
+    std::fs::write(&path, yaml).unwrap();
+    assert_eq!(eval::suite_files(dir.path()).unwrap(), vec![path.clone()]);
+
+    let mut suite = EvalSuite::load(&path).unwrap();
+    let target = EvalTarget::InProcess { tuning: TuningConfig::default(), cuegen_strategy: CueGenStrategy::Default };
+    let report = eval::run_suite(&suite, &target, eval::DEFAULT_EPSILON).await.unwrap();
+
+    // Results are reported under the suite's memory ids
+    assert_eq!(report.queries[0].results[0].memory_id, "timeout");
+    assert_eq!(report.queries[0].metrics.unwrap().mrr, 1.0);
+    assert!(report.queries[1].metrics.is_none());
+    assert_eq!(report.summary.judged, 1);
+    assert!(report.passed());
+
+    // Recorded traces match a fresh run
+    suite.record_golden(&report);
+    suite.save(&path).unwrap();
+    let suite = EvalSuite::load(&path).unwrap();
+    assert_eq!(suite.queries[0].golden.as_ref().unwrap().results[0].memory_id, "timeout");
+    let rerun = eval::run_suite(&suite, &target, eval::DEFAULT_EPSILON).await.unwrap();
+    assert_eq!(rerun.summary.golden_checked, 2);
+    assert!(rerun.passed(), "{:?}", rerun.queries);
+}
+
+fn report(ndcgs: &[f64]) -> eval::SuiteReport {
+    let queries = ndcgs
+        .iter()
+        .enumerate()
+        .map(|(i, ndcg)| eval::QueryReport {
+            name: format!("q{}", i),
+            metrics: Some(eval::RankingMetrics { mrr: 1.0, ndcg: *ndcg, recall: 1.0 }),
+            results: vec![trace(if *ndcg < 1.0 { "b" } else { "a" }, 1, 1.0)],
+            golden: false,
+            diffs: Vec::new(),
+        })
+        .collect();
+    eval::SuiteReport { suite: "ab".to_string(), queries, summary: Default::default() }
+}
+
+#[test]
+fn test_paired_permutation_test() {
+    // 8 same-sign differences: only the all-positive and all-negative assignments are as extreme
+    assert_eq!(eval::paired_permutation_test(&[0.1; 8]), 2.0 / 256.0);
+    assert_eq!(eval::paired_permutation_test(&[0.1, -0.1]), 1.0);
+    assert_eq!(eval::paired_permutation_test(&[0.0; 5]), 1.0);
+    // Sampled above 16 queries
+    let p = eval::paired_permutation_test(&[0.05; 40]);
+    assert!(p > 0.0 && p < 0.001);
+}
+
+#[test]
+fn test_ab_comparison_flags_significant_regressions() {
+    let baseline = report(&[1.0; 10]);
+    let worse = report(&[0.5; 10]);
+    let comparison = eval::compare_reports(&baseline, &worse, eval::DEFAULT_SIGNIFICANCE);
+    assert_eq!(comparison.judged, 10);
+    let ndcg = comparison.metrics.iter().find(|m| m.metric == "ndcg").unwrap();
+    assert_eq!((ndcg.baseline, ndcg.candidate, ndcg.delta), (1.0, 0.5, -0.5));
+    assert!(ndcg.significant);
+    assert!(comparison.metrics.iter().filter(|m| m.metric != "ndcg").all(|m| !m.significant));
+    assert!(comparison.regressed());
+    assert_eq!(comparison.changed.len(), 10);
+    assert_eq!(comparison.changed[0].ndcg_delta, Some(-0.5));
+
+    // One query moving is not enough evidence
+    let mut one = vec![1.0; 10];
+    one[3] = 0.5;
+    let comparison = eval::compare_reports(&baseline, &report(&one), eval::DEFAULT_SIGNIFICANCE);
+    assert!(!comparison.regressed());
+    assert_eq!(comparison.changed.len(), 1);
+
+    assert!(!eval::compare_reports(&worse, &baseline, eval::DEFAULT_SIGNIFICANCE).regressed());
+}
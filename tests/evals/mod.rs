@@ -4,6 +4,8 @@ use cuemap::engine::RecallResult;
 pub mod runner;
 pub mod evals;
 
+pub use cuemap::eval::{GoldenTrace, TraceResult};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EvalQuery {
     pub name: String,
@@ -27,21 +29,6 @@ pub struct NormalizedRecall {
     pub explain: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GoldenTrace {
-    pub query: String,
-    pub normalized_query: Vec<String>,
-    pub results: Vec<TraceResult>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct TraceResult {
-    pub memory_id: String,
-    pub rank: usize,
-    pub score: f64,
-    pub intersection_count: usize,
-}
-
 impl From<Vec<RecallResult>> for NormalizedRecall {
     fn from(results: Vec<RecallResult>) -> Self {
         let ordered_ids = results.iter().map(|r| r.memory_id.clone()).collect();
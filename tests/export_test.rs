@@ -0,0 +1,87 @@
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use cuemap::agent::manager::AgentManager;
+use cuemap::auth::AuthConfig;
+use cuemap::config::{CueGenStrategy, LlmConfig, TuningConfig};
+use cuemap::jobs::JobQueue;
+use cuemap::metrics::MetricsCollector;
+use cuemap::multi_tenant::MultiTenantEngine;
+use cuemap::semantic::SemanticEngine;
+use cuemap::structures::MainStats;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn test_matching_export_streams_matches_and_never_creates_projects() {
+    let dir = tempfile::tempdir().unwrap();
+    let engine = Arc::new(MultiTenantEngine::with_snapshots_dir(
+        dir.path(),
+        CueGenStrategy::Default,
+        SemanticEngine::new(None),
+        TuningConfig::default(),
+        LlmConfig::default(),
+    ));
+    let ctx = engine.get_or_create_project("acme".to_string()).unwrap();
+    // More than one blocking chunk's worth of matches
+    for i in 0..300 {
+        ctx.main.add_memory(format!("invoice {}", i), vec!["customer:acme".to_string()], None, MainStats::default(), true);
+    }
+    ctx.main.add_memory("unrelated".to_string(), vec!["customer:other".to_string()], None, MainStats::default(), true);
+
+    let job_queue = Arc::new(JobQueue::new(engine.clone(), None, true));
+    let agents = Arc::new(AgentManager::new(job_queue.clone(), engine.clone()));
+    let app = cuemap::api::routes(engine.clone(), job_queue, Arc::new(MetricsCollector::new()), AuthConfig::new(), false, None, None, agents);
+    let export = |project: &str| {
+        Request::post("/memories/search/export")
+            .header("X-Project-ID", project)
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"cues": ["customer:acme"]}"#))
+            .unwrap()
+    };
+
+    let response = app.clone().oneshot(export("acme")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let lines: Vec<serde_json::Value> = String::from_utf8(body.to_vec()).unwrap().lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(lines.len(), 300);
+    assert!(lines.iter().all(|l| l["content"].as_str().unwrap().starts_with("invoice")));
+
+    let response = app.oneshot(export("acmee")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(engine.get_project(&"acmee".to_string()).is_none());
+}
+
+#[tokio::test]
+async fn test_import_normalizes_and_validates_cues() {
+    use cuemap::taxonomy::Taxonomy;
+
+    let dir = tempfile::tempdir().unwrap();
+    let engine = Arc::new(MultiTenantEngine::with_snapshots_dir(
+        dir.path(),
+        CueGenStrategy::Default,
+        SemanticEngine::new(None),
+        TuningConfig::default(),
+        LlmConfig::default(),
+    ));
+    let ctx = engine.get_or_create_project("target".to_string()).unwrap();
+    engine
+        .set_project_taxonomy("target", Taxonomy { forbidden_patterns: vec!["secret:*".to_string()], ..Taxonomy::default() })
+        .unwrap();
+
+    let job_queue = Arc::new(JobQueue::new(engine.clone(), None, true));
+    let agents = Arc::new(AgentManager::new(job_queue.clone(), engine.clone()));
+    let app = cuemap::api::routes(engine.clone(), job_queue, Arc::new(MetricsCollector::new()), AuthConfig::new(), false, None, None, agents);
+    let line = serde_json::json!({"id": "imported", "content": "Acme renewal", "cues": ["  Customer:ACME ", "secret:token"]});
+    let request = Request::post("/projects/target/import")
+        .header("content-type", "application/x-ndjson")
+        .body(Body::from(line.to_string()))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = serde_json::from_slice(&axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+    assert_eq!(body["imported"], 1);
+    assert_eq!(body["rejected_cues"][0]["cue"], "secret:token");
+
+    let memory = ctx.main.get_memory("imported").unwrap();
+    assert_eq!(memory.cues, vec![ctx.normalize_cue("Customer:ACME")]);
+}
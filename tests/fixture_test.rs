@@ -31,3 +31,130 @@ fn test_fixture_loading_and_recall() {
     // Cleanup
     let _ = fs::remove_file(fixture_path);
 }
+
+#[test]
+fn test_snapshot_concurrent_with_writes() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("concurrent.bin");
+
+    let engine = std::sync::Arc::new(CueMapEngine::<MainStats>::new());
+    for i in 0..2000 {
+        engine.add_memory(format!("memory {}", i), vec![format!("cue:{}", i % 50)], None, MainStats::default(), true);
+    }
+
+    // Keep ingesting and deleting while the snapshot streams to disk
+    let writer = {
+        let engine = engine.clone();
+        std::thread::spawn(move || {
+            for i in 0..500 {
+                let id = engine.add_memory(format!("late {}", i), vec!["cue:late".to_string()], None, MainStats::default(), true);
+                if i % 2 == 0 {
+                    engine.delete_memory(&id);
+                }
+            }
+        })
+    };
+    PersistenceManager::save_to_path(&engine, &path).expect("Snapshot should succeed during writes");
+    writer.join().unwrap();
+
+    let (memories, cue_index) = PersistenceManager::load_from_path::<MainStats>(&path).expect("Snapshot should be readable");
+    assert!(memories.len() >= 2000 && memories.len() <= 2500);
+    assert!(cue_index.len() >= 50);
+}
+
+#[test]
+fn test_compressed_snapshots_round_trip() {
+    use cuemap::crypto::EncryptionKey;
+    use cuemap::persistence::{SnapshotCodec, SnapshotCompression};
+
+    let dir = tempfile::tempdir().unwrap();
+    let engine = CueMapEngine::<MainStats>::new();
+    for i in 0..200 {
+        engine.add_memory(format!("repeated memory text {}", i), vec!["shared".to_string(), format!("n{}", i)], None, MainStats::default(), true);
+    }
+    let raw_path = dir.path().join("raw.bin");
+    PersistenceManager::save_to_path(&engine, &raw_path).unwrap();
+    let raw_len = std::fs::metadata(&raw_path).unwrap().len();
+
+    let key = EncryptionKey::new(vec![3u8; 32]);
+    for (codec, key) in [(SnapshotCodec::Zstd, None), (SnapshotCodec::Lz4, None), (SnapshotCodec::Zstd, Some(&key))] {
+        let path = dir.path().join(format!("{:?}-{}.bin", codec, key.is_some()));
+        PersistenceManager::save_to_path_with_options(&engine, &path, key, SnapshotCompression::new(codec, 9)).unwrap();
+        let data = std::fs::read(&path).unwrap();
+        if key.is_none() {
+            assert_eq!(PersistenceManager::snapshot_codec(&data), Some(codec));
+            assert!((data.len() as u64) < raw_len, "{:?} should shrink the snapshot", codec);
+        }
+
+        let keys: Vec<EncryptionKey> = key.into_iter().cloned().collect();
+        let (memories, cue_index) = PersistenceManager::load_from_path_with_keys::<MainStats>(&path, &keys).unwrap();
+        assert_eq!(memories.len(), 200);
+        assert_eq!(cue_index.get("shared").unwrap().len(), 200);
+    }
+    // The raw file compressed snapshots are streamed from is removed
+    assert!(fs::read_dir(dir.path()).unwrap().all(|e| !e.unwrap().path().to_string_lossy().ends_with(".raw")));
+
+    // Raw snapshots gain a header when compressed for upload; sealed ones pass through
+    let raw = std::fs::read(&raw_path).unwrap();
+    let lz4 = SnapshotCompression::new(SnapshotCodec::Lz4, 0);
+    let compressed = lz4.compress(&raw).unwrap();
+    assert_eq!(PersistenceManager::snapshot_codec(&compressed), Some(SnapshotCodec::Lz4));
+    assert_eq!(lz4.compress(&compressed).unwrap(), compressed);
+    let body = PersistenceManager::checksummed_body(&compressed, &raw_path).unwrap();
+    let raw_body = PersistenceManager::checksummed_body(&raw, &raw_path).unwrap();
+    assert_eq!(PersistenceManager::decompress_snapshot(body, &raw_path).unwrap(), raw_body);
+    assert_eq!(SnapshotCompression::default().compress(&raw).unwrap(), raw);
+
+    // Configs from before compression carry `compress_snapshots = true`; only a codec turns it on
+    let config: cuemap::config::PersistenceConfig = toml::from_str("snapshot_interval_seconds = 60\nenabled = true\ncompress_snapshots = true\n").unwrap();
+    assert!(!config.snapshot_compression().is_enabled());
+    let config: cuemap::config::PersistenceConfig = toml::from_str("snapshot_interval_seconds = 60\nenabled = true\ncompress_snapshots = true\nsnapshot_codec = \"lz4\"\n").unwrap();
+    assert_eq!(config.snapshot_compression().codec, SnapshotCodec::Lz4);
+    assert!(!cuemap::config::PersistenceConfig::default().snapshot_compression().is_enabled());
+}
+
+#[test]
+fn test_corrupt_snapshots_are_detected() {
+    use cuemap::persistence::SnapshotStatus;
+
+    let dir = tempfile::tempdir().unwrap();
+    let engine = CueMapEngine::<MainStats>::new();
+    engine.add_memory("checksummed content".to_string(), vec!["cue".to_string()], None, MainStats::default(), false);
+    let good = dir.path().join("good.bin");
+    let flipped = dir.path().join("flipped.bin");
+    let truncated = dir.path().join("truncated.bin");
+    for path in [&good, &flipped, &truncated] {
+        PersistenceManager::save_to_path(&engine, path).unwrap();
+    }
+    let legacy = dir.path().join("legacy.bin");
+    let data = fs::read(&good).unwrap();
+    fs::write(&legacy, PersistenceManager::checksummed_body(&data, &good).unwrap()).unwrap();
+
+    let mut data = fs::read(&flipped).unwrap();
+    let last = data.len() - 1;
+    data[last] ^= 0xff;
+    fs::write(&flipped, &data).unwrap();
+    fs::write(&truncated, &data[..data.len() / 2]).unwrap();
+
+    for path in [&flipped, &truncated] {
+        let err = PersistenceManager::load_from_path::<MainStats>(path).unwrap_err().to_string();
+        assert!(err.contains("checksum mismatch"), "{}", err);
+    }
+    assert_eq!(PersistenceManager::load_from_path::<MainStats>(&good).unwrap().0.len(), 1);
+    // Snapshots written before checksums existed still load
+    assert_eq!(PersistenceManager::load_from_path::<MainStats>(&legacy).unwrap().0.len(), 1);
+
+    let statuses: Vec<(String, SnapshotStatus)> = PersistenceManager::verify_snapshots_in_dir(dir.path())
+        .into_iter()
+        .map(|check| (check.path.file_name().unwrap().to_string_lossy().to_string(), check.status))
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            ("flipped.bin".to_string(), SnapshotStatus::Corrupt),
+            ("good.bin".to_string(), SnapshotStatus::Ok),
+            ("legacy.bin".to_string(), SnapshotStatus::Unverified),
+            ("truncated.bin".to_string(), SnapshotStatus::Corrupt),
+        ]
+    );
+}
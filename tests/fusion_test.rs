@@ -0,0 +1,39 @@
+use cuemap::fusion::*;
+
+#[test]
+fn test_rrf_interleaves_projects_by_rank() {
+    // Project 0 scores on a much larger scale than project 1
+    let scores = vec![vec![90.0, 80.0, 70.0], vec![0.9, 0.5]];
+    let fused = fuse(&scores, FusionStrategy::Rrf, 10);
+    let order: Vec<(usize, usize)> = fused.iter().map(|h| (h.list, h.rank)).collect();
+    assert_eq!(order, vec![(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)]);
+    assert!((fused[0].fused_score - 1.0 / (RRF_K + 1.0)).abs() < 1e-12);
+
+    assert_eq!(fuse(&scores, FusionStrategy::Rrf, 2).len(), 2);
+    assert!(fuse(&[], FusionStrategy::Rrf, 10).is_empty());
+}
+
+#[test]
+fn test_zscore_ranks_standouts_first() {
+    // Project 1's top result stands out from its project far more than project 0's
+    let scores = vec![vec![10.0, 9.0, 8.0], vec![5.0, 1.0, 1.0, 1.0]];
+    let fused = fuse(&scores, FusionStrategy::ZScore, 10);
+    assert_eq!((fused[0].list, fused[0].rank), (1, 0));
+    assert_eq!((fused[1].list, fused[1].rank), (0, 0));
+    assert_eq!(fused.len(), 7);
+    for pair in fused.windows(2) {
+        assert!(pair[0].fused_score >= pair[1].fused_score);
+    }
+
+    // A single result (or equal scores) has nothing to stand out from
+    let fused = fuse(&[vec![3.0], vec![2.0, 2.0]], FusionStrategy::ZScore, 10);
+    assert!(fused.iter().all(|h| h.fused_score == 0.0));
+    assert_eq!((fused[0].list, fused[0].rank), (0, 0));
+}
+
+#[test]
+fn test_fusion_strategy_names() {
+    assert_eq!(serde_json::to_value(FusionStrategy::ZScore).unwrap(), "zscore");
+    let parsed: FusionStrategy = serde_json::from_value(serde_json::json!("rrf")).unwrap();
+    assert_eq!(parsed, FusionStrategy::default());
+}
@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod tests {
+    use cuemap::engine::RecallResult;
+    use cuemap::grounding::{create_grounding_proof, GroundingEngine};
+    use cuemap::tokenizer::tokenizer_for_model;
+    use std::collections::HashMap;
+
+    fn result(id: &str, content: &str, score: f64) -> RecallResult {
+        RecallResult {
+            memory_id: id.to_string(),
+            content: content.to_string(),
+            score,
+            match_integrity: 1.0,
+            intersection_count: 1,
+            recency_score: 0.0,
+            reinforcement_score: 0.0,
+            salience_score: 0.0,
+            created_at: 1_700_000_000.0,
+            metadata: HashMap::new(),
+            explain: None,
+        }
+    }
+
+    #[test]
+    fn test_tokenizer_is_picked_by_model() {
+        assert_eq!(tokenizer_for_model(None).name(), "chars");
+        assert_eq!(tokenizer_for_model(Some("gpt-4o")).name(), "bpe");
+        assert_eq!(tokenizer_for_model(Some("openai/o3-mini")).name(), "bpe");
+        assert_eq!(tokenizer_for_model(Some("llama3")).name(), "whitespace");
+        assert_eq!(tokenizer_for_model(Some("chars")).name(), "chars");
+
+        let bpe = tokenizer_for_model(Some("gpt-4"));
+        assert_eq!(bpe.count_tokens("hello world"), 2);
+        assert_eq!(bpe.count_tokens("12345"), 2, "numbers split into groups of three digits");
+        assert_eq!(bpe.count_tokens("don't"), 2);
+
+        let words = tokenizer_for_model(Some("mistral"));
+        assert_eq!(words.count_tokens("one two three"), 4);
+    }
+
+    #[test]
+    fn test_grounded_context_fits_budget_for_each_tokenizer() {
+        let results: Vec<RecallResult> = (0..20)
+            .map(|i| result(&format!("m{}", i), "The payments service timed out while calling the ledger API", 10.0 - i as f64))
+            .collect();
+
+        for model in [None, Some("gpt-4o"), Some("llama3")] {
+            let tokenizer = tokenizer_for_model(model);
+            let (selected, excluded, block) = GroundingEngine::select_memories(
+                "why did payments fail".to_string(),
+                vec![],
+                vec![],
+                results.clone(),
+                150,
+                tokenizer.as_ref(),
+            );
+
+            assert!(!selected.is_empty(), "{:?} selected nothing", model);
+            assert!(!excluded.is_empty(), "{:?} should not fit all 20 memories", model);
+            assert!(
+                tokenizer.count_tokens(&block) <= 150,
+                "{:?} packed {} tokens into a budget of 150",
+                model,
+                tokenizer.count_tokens(&block)
+            );
+        }
+    }
+
+    #[test]
+    fn test_grounded_context_cites_selected_memories() {
+        let results = vec![result("first", "Deploys run at noon", 2.0), result("second", "Rollbacks need approval", 1.0)];
+        let tokenizer = tokenizer_for_model(None);
+        let (selected, excluded, block) = GroundingEngine::select_memories(
+            "deploys".to_string(),
+            vec![],
+            vec![],
+            results,
+            1000,
+            tokenizer.as_ref(),
+        );
+        assert!(block.contains("[1] Deploys run at noon"));
+        assert!(block.contains("[2] Rollbacks need approval"));
+
+        let proof = create_grounding_proof("trace".to_string(), "deploys".to_string(), vec![], vec![], 1000, selected, excluded);
+        let citations: Vec<(&str, &str)> = proof.citations.iter().map(|c| (c.marker.as_str(), c.memory_id.as_str())).collect();
+        assert_eq!(citations, vec![("[1]", "first"), ("[2]", "second")]);
+
+        // An answer citing [2] only, written from this context
+        let cited = proof.citations_in("Deploys run on Fridays [2].");
+        assert_eq!(cited.len(), 1);
+        assert_eq!(cited[0].memory_id, "second");
+        assert!(proof.citations_in("No markers here, not even [12].").is_empty());
+    }
+}
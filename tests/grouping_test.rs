@@ -0,0 +1,79 @@
+use cuemap::engine::{CueMapEngine, RecallResult};
+use cuemap::grouping::{group_results, parse_namespace, GroupScore};
+use cuemap::structures::MainStats;
+use std::collections::HashMap;
+
+fn result(id: &str, score: f64) -> RecallResult {
+    RecallResult {
+        memory_id: id.to_string(),
+        content: String::new(),
+        score,
+        match_integrity: 0.0,
+        intersection_count: 1,
+        recency_score: 0.0,
+        reinforcement_score: 0.0,
+        salience_score: 0.0,
+        created_at: 0.0,
+        metadata: HashMap::new(),
+        explain: None,
+    }
+}
+
+fn path_of(id: &str) -> Option<String> {
+    match id {
+        "a1" | "a2" | "a3" => Some("docs/a.md".to_string()),
+        "b1" => Some("docs/b.md".to_string()),
+        _ => None,
+    }
+}
+
+fn results() -> Vec<RecallResult> {
+    vec![result("b1", 0.9), result("a1", 0.8), result("a2", 0.7), result("x", 0.6), result("a3", 0.5), result("y", 0.4)]
+}
+
+#[test]
+fn test_group_results_keeps_best_chunk_per_key() {
+    let groups = group_results(results(), 10, GroupScore::Max, path_of);
+    let keys: Vec<_> = groups.iter().map(|g| g.key.as_deref()).collect();
+    assert_eq!(keys, vec![Some("docs/b.md"), Some("docs/a.md"), None, None]);
+
+    let a = &groups[1];
+    assert_eq!(a.best.memory_id, "a1");
+    assert_eq!(a.hits, 3);
+    assert_eq!(a.memory_ids, vec!["a1", "a2", "a3"]);
+    assert_eq!(a.score, 0.8);
+
+    // Memories without the cue are groups of their own
+    assert_eq!(groups[2].best.memory_id, "x");
+    assert_eq!(groups[3].best.memory_id, "y");
+
+    assert_eq!(group_results(results(), 2, GroupScore::Max, path_of).len(), 2);
+}
+
+#[test]
+fn test_sum_ranks_documents_matching_throughout_first() {
+    let groups = group_results(results(), 2, GroupScore::Sum, path_of);
+    assert_eq!(groups[0].key.as_deref(), Some("docs/a.md"));
+    assert!((groups[0].score - 2.0).abs() < 1e-9);
+    assert_eq!(groups[1].key.as_deref(), Some("docs/b.md"));
+}
+
+#[test]
+fn test_namespace_and_cue_value() {
+    assert_eq!(parse_namespace("path").unwrap(), "path");
+    assert_eq!(parse_namespace(" Path: ").unwrap(), "path");
+    assert!(parse_namespace("").is_err());
+    assert!(parse_namespace("a:b").is_err());
+
+    let engine = CueMapEngine::<MainStats>::new();
+    let chunk = engine.add_memory(
+        "fn main() {}".to_string(),
+        vec!["lang:rust".to_string(), "path:src/main.rs".to_string()],
+        None,
+        MainStats::default(),
+        true,
+    );
+    assert_eq!(engine.cue_value(&chunk, "path").as_deref(), Some("src/main.rs"));
+    assert_eq!(engine.cue_value(&chunk, "section"), None);
+    assert_eq!(engine.cue_value("missing", "path"), None);
+}
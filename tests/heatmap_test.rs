@@ -0,0 +1,40 @@
+use cuemap::heatmap::{HeatmapSource, MarketHeatmap};
+use std::collections::HashMap;
+
+fn weights(pairs: &[(&str, f32)]) -> HashMap<String, f32> {
+    pairs.iter().map(|(c, w)| (c.to_string(), *w)).collect()
+}
+
+#[test]
+fn test_ttl_entries_decay_linearly_and_expire() {
+    let mut heatmap = MarketHeatmap::default();
+    assert_eq!(heatmap.apply(weights(&[("earnings", 2.0), ("ipo", -1.0)]), Some(100), false, 1000), (2, 0));
+    assert_eq!(heatmap.weights()["earnings"], 2.0);
+
+    heatmap.decay(1025);
+    assert_eq!(heatmap.weights()["earnings"], 1.5);
+    assert_eq!(heatmap.weights()["ipo"], -0.75);
+
+    assert_eq!(heatmap.decay(1100), 2);
+    assert!(heatmap.is_empty() && heatmap.weights().is_empty());
+}
+
+#[test]
+fn test_set_entries_win_over_trending() {
+    let mut heatmap = MarketHeatmap::default();
+    heatmap.apply(weights(&[("outage", 3.0), ("earnings", 1.0)]), None, false, 0);
+    heatmap.sync_trending(weights(&[("outage", 0.5), ("deploy", 0.8)]), 0);
+    assert_eq!(heatmap.weights()["outage"], 3.0);
+    assert_eq!(heatmap.entries()["deploy"].source, HeatmapSource::Trending);
+
+    // The next sync replaces the trending entries only
+    heatmap.sync_trending(weights(&[("refund", 0.3)]), 10);
+    assert!(!heatmap.entries().contains_key("deploy"));
+    assert_eq!(heatmap.weights().len(), 3);
+
+    // Replacing keeps trending entries; weight 0 removes
+    assert_eq!(heatmap.apply(weights(&[("outage", 0.0), ("ipo", 2.0)]), None, true, 20), (1, 2));
+    let mut cues: Vec<_> = heatmap.weights().keys().cloned().collect();
+    cues.sort();
+    assert_eq!(cues, vec!["ipo", "refund"]);
+}
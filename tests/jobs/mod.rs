@@ -1,4 +1,5 @@
 use cuemap::jobs::*;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[test]
 fn test_lexicon_trainability() {
@@ -13,3 +14,347 @@ fn test_lexicon_trainability() {
     assert!(!is_lexicon_trainable("source:agent"));
     assert!(!is_lexicon_trainable("file:/tmp/foo"));
 }
+
+/// Records the project of every job that starts, and holds it until `open`
+#[derive(Default)]
+struct GatedProvider {
+    open: AtomicBool,
+    started: std::sync::Mutex<Vec<String>>,
+}
+
+impl ProjectProvider for GatedProvider {
+    fn get_project(&self, project_id: &str) -> Option<std::sync::Arc<cuemap::projects::ProjectContext>> {
+        self.started.lock().unwrap().push(project_id.to_string());
+        while !self.open.load(Ordering::SeqCst) {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        None
+    }
+
+    fn save_project(&self, _project_id: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn list_active_projects(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+async fn wait_for(condition: impl Fn() -> bool) {
+    for _ in 0..500 {
+        if condition() {
+            return;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    panic!("timed out");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_worker_pool_prioritizes_and_limits_kinds() {
+    let provider = std::sync::Arc::new(GatedProvider::default());
+    let workers = WorkerPoolConfig {
+        max_workers: 1,
+        concurrency: [(JobKind::ProposeCues, 2)].into_iter().collect(),
+        ..Default::default()
+    };
+    let queue = JobQueue::with_workers(provider.clone(), None, false, workers);
+
+    for i in 0..5 {
+        queue.enqueue(Job::ProposeCues { project_id: format!("cues{}", i), memory_id: "m".to_string(), content: "c".to_string() }).await;
+    }
+    wait_for(|| provider.started.lock().unwrap().len() == 1).await;
+    queue.enqueue(Job::ReinforceMemories { project_id: "feedback".to_string(), memory_ids: Vec::new(), cues: Vec::new() }).await;
+
+    let depths = queue.queue_depths();
+    // One worker in all, though the kind may use two
+    assert_eq!(depths[&JobKind::ProposeCues], QueueDepth { queued: 4, running: 1 });
+    assert_eq!(depths[&JobKind::Reinforce], QueueDepth { queued: 1, running: 0 });
+
+    provider.open.store(true, Ordering::SeqCst);
+    wait_for(|| queue.queue_depths().values().all(|d| *d == QueueDepth::default())).await;
+    let started = provider.started.lock().unwrap().clone();
+    assert_eq!(started.len(), 6);
+    // The freed worker went to the reinforcement, ahead of the backlog
+    assert_eq!(started[1], "feedback");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_enqueue_waits_for_room_in_a_full_queue() {
+    let provider = std::sync::Arc::new(GatedProvider::default());
+    let workers = WorkerPoolConfig { max_workers: 1, max_queued: 2, ..Default::default() };
+    let queue = std::sync::Arc::new(JobQueue::with_workers(provider.clone(), None, false, workers));
+    let job = |i: usize| Job::ProposeCues { project_id: format!("cues{}", i), memory_id: "m".to_string(), content: "c".to_string() };
+
+    queue.enqueue(job(0)).await;
+    wait_for(|| provider.started.lock().unwrap().len() == 1).await;
+    queue.enqueue(job(1)).await;
+    queue.enqueue(job(2)).await;
+
+    // The queue is full, so the next job waits until a worker takes one
+    let waiting = tokio::spawn({
+        let queue = queue.clone();
+        async move { queue.enqueue(job(3)).await }
+    });
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(!waiting.is_finished());
+    assert_eq!(queue.queue_depths()[&JobKind::ProposeCues], QueueDepth { queued: 2, running: 1 });
+
+    provider.open.store(true, Ordering::SeqCst);
+    waiting.await.unwrap();
+    wait_for(|| provider.started.lock().unwrap().len() == 4).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_ingest_jobs_run_in_order_per_project() {
+    let provider = std::sync::Arc::new(GatedProvider::default());
+    let queue = JobQueue::with_workers(provider.clone(), None, false, WorkerPoolConfig::default());
+
+    for (project, memory) in [("alpha", "1"), ("alpha", "2"), ("beta", "1")] {
+        queue.enqueue(Job::DeleteMemory { project_id: project.to_string(), memory_id: memory.to_string() }).await;
+    }
+    wait_for(|| provider.started.lock().unwrap().len() == 2).await;
+    // alpha's second job waits for its first, beta's runs alongside
+    let mut started = provider.started.lock().unwrap().clone();
+    started.sort();
+    assert_eq!(started, vec!["alpha".to_string(), "beta".to_string()]);
+    assert_eq!(queue.queue_depths()[&JobKind::Ingest], QueueDepth { queued: 1, running: 2 });
+
+    provider.open.store(true, Ordering::SeqCst);
+    wait_for(|| provider.started.lock().unwrap().len() == 3).await;
+    assert_eq!(JobKind::Ingest.name(), "ingest");
+}
+
+/// Projects whose embedding provider is unreachable, counting the attempts
+struct OfflineEmbedder {
+    store: cuemap::projects::ProjectStore,
+    attempts: std::sync::atomic::AtomicUsize,
+}
+
+impl ProjectProvider for OfflineEmbedder {
+    fn get_project(&self, project_id: &str) -> Option<std::sync::Arc<cuemap::projects::ProjectContext>> {
+        Some(self.store.get_or_create(project_id))
+    }
+
+    fn save_project(&self, _project_id: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn list_active_projects(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn embedding_provider(&self, _project_id: &str) -> Result<Option<std::sync::Arc<dyn cuemap::embeddings::EmbeddingProvider>>, String> {
+        self.attempts.fetch_add(1, Ordering::SeqCst);
+        Err("connection refused".to_string())
+    }
+}
+
+fn offline_embedder() -> std::sync::Arc<OfflineEmbedder> {
+    std::sync::Arc::new(OfflineEmbedder { store: cuemap::projects::ProjectStore::new(), attempts: Default::default() })
+}
+
+fn embed_job(project_id: &str) -> Job {
+    Job::EmbedMemories { project_id: project_id.to_string(), memory_ids: Vec::new(), force: false }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_failing_jobs_retry_then_dead_letter() {
+    let provider = offline_embedder();
+    let workers = WorkerPoolConfig { max_attempts: 3, retry_backoff_ms: 5, ..Default::default() };
+    let queue = JobQueue::with_workers(provider.clone(), None, false, workers);
+
+    let id = queue.enqueue(embed_job("docs")).await;
+    wait_for(|| !queue.failed_jobs().is_empty()).await;
+    assert_eq!(provider.attempts.load(Ordering::SeqCst), 3);
+
+    let failed = queue.failed_jobs();
+    assert_eq!(failed.len(), 1);
+    assert_eq!((failed[0].id.as_str(), failed[0].kind, failed[0].project_id.as_str()), (id.as_str(), JobKind::Embed, "docs"));
+    assert_eq!(failed[0].attempts, 3);
+    assert!(failed[0].error.contains("connection refused"), "{}", failed[0].error);
+
+    // A retry gets a fresh set of attempts under the same id
+    assert!(queue.retry_job(&id));
+    assert!(!queue.retry_job("missing"));
+    wait_for(|| provider.attempts.load(Ordering::SeqCst) == 6 && queue.failed_jobs().len() == 1).await;
+    assert_eq!(queue.failed_jobs()[0].id, id);
+
+    assert_eq!(queue.cancel_job(&id), CancelOutcome::Discarded);
+    assert!(queue.failed_jobs().is_empty());
+    assert_eq!(queue.cancel_job(&id), CancelOutcome::NotFound);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_cancel_queued_and_retrying_jobs() {
+    let provider = std::sync::Arc::new(GatedProvider::default());
+    let workers = WorkerPoolConfig { max_workers: 1, ..Default::default() };
+    let queue = JobQueue::with_workers(provider.clone(), None, false, workers);
+
+    let running = queue.enqueue(Job::DeleteMemory { project_id: "first".to_string(), memory_id: "m".to_string() }).await;
+    wait_for(|| provider.started.lock().unwrap().len() == 1).await;
+    let queued = queue.enqueue(Job::DeleteMemory { project_id: "second".to_string(), memory_id: "m".to_string() }).await;
+
+    assert_eq!(queue.cancel_job(&running), CancelOutcome::Running);
+    assert_eq!(queue.cancel_job(&queued), CancelOutcome::Cancelled);
+    assert_eq!(queue.queue_depths()[&JobKind::Ingest], QueueDepth { queued: 0, running: 1 });
+
+    provider.open.store(true, Ordering::SeqCst);
+    wait_for(|| queue.queue_depths()[&JobKind::Ingest] == QueueDepth::default()).await;
+    assert_eq!(provider.started.lock().unwrap().clone(), vec!["first".to_string()]);
+
+    // A job waiting out its backoff can be cancelled before it runs again
+    let offline = offline_embedder();
+    let workers = WorkerPoolConfig { retry_backoff_ms: 60_000, ..Default::default() };
+    let queue = JobQueue::with_workers(offline.clone(), None, false, workers);
+    let id = queue.enqueue(embed_job("docs")).await;
+    wait_for(|| offline.attempts.load(Ordering::SeqCst) == 1 && queue.queue_depths()[&JobKind::Embed] == QueueDepth { queued: 1, running: 0 }).await;
+    assert_eq!(queue.cancel_job(&id), CancelOutcome::Cancelled);
+    assert_eq!(queue.queue_depths()[&JobKind::Embed], QueueDepth::default());
+    assert!(queue.failed_jobs().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_explicit_ingestion_sessions() {
+    let provider = std::sync::Arc::new(GatedProvider::default());
+    provider.open.store(true, Ordering::SeqCst);
+    let queue = JobQueue::with_workers(provider.clone(), None, false, WorkerPoolConfig::default());
+    let sessions = &queue.session_manager;
+    let propose = |memory: &str| Job::ProposeCues { project_id: "bulk".to_string(), memory_id: memory.to_string(), content: "c".to_string() };
+
+    let id = sessions.begin("bulk").await.unwrap();
+    assert!(sessions.begin("bulk").await.unwrap_err().contains(&id));
+    let session = queue.get_session("bulk").unwrap();
+    session.expect_write();
+    session.buffer_job(propose("1")).await;
+    session.write_complete();
+    assert_eq!(session.get_progress().session_id, Some(id.clone()));
+
+    // The auto-flush leaves an open session alone, however quiet it gets
+    tokio::time::sleep(std::time::Duration::from_millis(2100)).await;
+    assert!(!session.should_auto_flush().await);
+
+    assert!(!sessions.commit("bulk", "other"));
+    assert!(!sessions.commit("elsewhere", &id));
+    assert!(sessions.commit("bulk", &id));
+    wait_for(|| session.get_phase() == IngestionPhase::Done).await;
+    assert_eq!(session.get_progress().propose_cues_completed, 1);
+    assert_eq!(session.get_progress().session_id, None);
+
+    // Aborting drops the buffered jobs unrun
+    let id = sessions.begin("bulk").await.unwrap();
+    session.buffer_job(propose("2")).await;
+    session.buffer_job(Job::UpdateGraph { project_id: "bulk".to_string(), memory_id: "2".to_string() }).await;
+    assert_eq!(sessions.abort("bulk", &id).await, Some(2));
+    assert_eq!(sessions.abort("bulk", &id).await, None);
+    sessions.flush_session("bulk").await;
+    assert_eq!(session.get_progress().propose_cues_completed, 1);
+    assert_eq!(session.get_progress().update_graph_completed, 0);
+}
+
+#[test]
+fn test_scan_progress_eta() {
+    let session = IngestionSession::new("scan".to_string());
+    assert_eq!(session.get_progress().scan, None);
+
+    session.start_scan(4, 400);
+    session.scanning_file("/repo/a.rs");
+    let scan = session.scan_progress().unwrap();
+    assert_eq!(scan.current_file.as_deref(), Some("/repo/a.rs"));
+    assert_eq!((scan.files_remaining, scan.eta_secs), (4, None));
+
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    session.file_scanned(100, false);
+    session.file_scanned(100, true);
+    let scan = session.scan_progress().unwrap();
+    assert_eq!((scan.files_scanned, scan.files_remaining, scan.files_failed, scan.bytes_processed), (2, 2, 1, 200));
+    // Half the bytes took ~20ms, so the rest should too
+    assert_eq!(scan.eta_secs, Some(1));
+    assert!(!scan.done);
+
+    session.finish_scan();
+    let scan = session.scan_progress().unwrap();
+    assert_eq!((scan.eta_secs, scan.done), (Some(0), true));
+}
+
+/// Project store whose LLM answers every prompt with the same two facts
+struct FactProvider {
+    store: cuemap::projects::ProjectStore,
+    llm: std::sync::Arc<cuemap::llm::LlmClient>,
+}
+
+struct FactLlm;
+
+#[async_trait::async_trait]
+impl cuemap::llm::LlmProvider for FactLlm {
+    fn name(&self) -> String {
+        "facts/test".to_string()
+    }
+
+    async fn complete(&self, _system: &str, _prompt: &str, _max_tokens: usize) -> Result<String, String> {
+        Ok(r#"{"facts": [
+            {"subject": "Payment service", "predicate": "listens on port", "object": "8443"},
+            ["payment service", "is owned by", "billing team"]
+        ]}"#.to_string())
+    }
+}
+
+impl ProjectProvider for FactProvider {
+    fn get_project(&self, project_id: &str) -> Option<std::sync::Arc<cuemap::projects::ProjectContext>> {
+        Some(self.store.get_or_create(project_id))
+    }
+
+    fn save_project(&self, _project_id: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn list_active_projects(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn llm_client(&self, _project_id: &str) -> Option<std::sync::Arc<cuemap::llm::LlmClient>> {
+        Some(self.llm.clone())
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_extract_facts_job() {
+    let config = cuemap::config::LlmConfig { enabled: true, ..Default::default() };
+    let llm = cuemap::llm::LlmClient::with_provider(config, Ok(std::sync::Arc::new(FactLlm)));
+    let provider = std::sync::Arc::new(FactProvider { store: cuemap::projects::ProjectStore::new(), llm: std::sync::Arc::new(llm) });
+    let queue = JobQueue::with_workers(provider.clone(), None, false, WorkerPoolConfig::default());
+    let ctx = provider.get_project("facts").unwrap();
+    let chunk = "file:/srv/notes.md:1";
+
+    queue.enqueue(Job::ExtractFacts {
+        project_id: "facts".to_string(),
+        memory_id: chunk.to_string(),
+        content: "The payment service listens on port 8443. The billing team owns it.".to_string(),
+        file_path: "/srv/notes.md".to_string(),
+    }).await;
+    wait_for(|| ctx.main.get_memory(&fact_memory_id(chunk, 1)).is_some()).await;
+
+    let port = ctx.main.get_memory(&fact_memory_id(chunk, 0)).unwrap();
+    assert_eq!(port.access_content(None).unwrap(), "Payment service listens on port 8443");
+    for cue in ["subj:payment_service", "pred:listens_on_port", "obj:8443", "type:fact", "path:/srv/notes.md"] {
+        assert!(port.cues.contains(&cue.to_string()), "{} missing from {:?}", cue, port.cues);
+    }
+    assert_eq!(port.metadata["fact"]["object"], "8443");
+    assert_eq!(fact_parent_id(&fact_memory_id(chunk, 0)), chunk);
+    assert_eq!(fact_parent_id(chunk), chunk);
+
+    // A re-extraction with fewer facts drops the extra ones
+    let one = vec![cuemap::llm::Fact { subject: "a".to_string(), predicate: "b".to_string(), object: "c".to_string() }];
+    assert_eq!(store_facts(&ctx, chunk, "/srv/notes.md", &one), vec![fact_memory_id(chunk, 0)]);
+    assert!(ctx.main.get_memory(&fact_memory_id(chunk, 1)).is_none());
+
+    // Facts go with their chunk when it's verified away or deleted
+    let kept = "file:/srv/notes.md:2";
+    store_facts(&ctx, kept, "/srv/notes.md", &one);
+    queue.enqueue(Job::VerifyFile { project_id: "facts".to_string(), file_path: "/srv/notes.md".to_string(), valid_memory_ids: vec![kept.to_string()] }).await;
+    wait_for(|| ctx.main.get_memory(&fact_memory_id(chunk, 0)).is_none()).await;
+    assert!(ctx.main.get_memory(&fact_memory_id(kept, 0)).is_some());
+
+    queue.enqueue(Job::DeleteMemory { project_id: "facts".to_string(), memory_id: kept.to_string() }).await;
+    wait_for(|| ctx.main.get_memory(&fact_memory_id(kept, 0)).is_none()).await;
+}
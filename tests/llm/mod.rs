@@ -58,3 +58,260 @@ fn test_propose_cues_parsing() {
     assert!(cues3.contains(&"found:it".to_string()));
     assert!(cues3.contains(&"recovered:true".to_string()));
 }
+
+#[test]
+fn test_parse_facts_response() {
+    let reply = "Here you go:\n```json\n{\"facts\": [
+        {\"subject\": \"Auth service\", \"predicate\": \"uses port\", \"object\": \"9000\"},
+        [\"auth service\", \"is written in\", \"Go\"],
+        {\"subject\": \"Auth service\", \"predicate\": \"uses port\", \"object\": \"9000\"},
+        {\"subject\": \"\", \"predicate\": \"has\", \"object\": \"x\"},
+        [\"too short\"]
+    ]}\n```";
+    let facts = parse_facts_response(reply).unwrap();
+    assert_eq!(facts.len(), 2);
+    assert_eq!(facts[0].text(), "Auth service uses port 9000");
+    assert_eq!(facts[0].cues(), vec!["subj:auth_service", "pred:uses_port", "obj:9000"]);
+    assert_eq!(facts[1].object, "Go");
+
+    assert!(parse_facts_response(r#"{"facts": []}"#).unwrap().is_empty());
+    assert!(parse_facts_response("I found no facts.").is_err());
+    assert!(parse_facts_response(r#"{"cues": ["a:b"]}"#).is_err());
+
+    let many: Vec<String> = (0..30).map(|i| format!(r#"["s", "p", "{}"]"#, i)).collect();
+    let reply = format!(r#"{{"facts": [{}]}}"#, many.join(","));
+    assert_eq!(parse_facts_response(&reply).unwrap().len(), MAX_FACTS);
+}
+
+fn server_config(provider: &str, url: &str) -> cuemap::config::LlmConfig {
+    cuemap::config::LlmConfig {
+        enabled: true,
+        provider: provider.to_string(),
+        model: "test-model".to_string(),
+        url: url.to_string(),
+        api_key: Some("secret".to_string()),
+        ..Default::default()
+    }
+}
+
+/// Answers every prompt with a fixed cue list
+struct CannedLlm;
+
+#[async_trait::async_trait]
+impl LlmProvider for CannedLlm {
+    fn name(&self) -> String {
+        "canned/test".to_string()
+    }
+
+    async fn complete(&self, _system: &str, _prompt: &str, _max_tokens: usize) -> Result<String, String> {
+        Ok(r#"{"cues": ["topic:payments"]}"#.to_string())
+    }
+}
+
+/// Remembers the last prompt and answers with a reply far past any budget
+#[derive(Default)]
+struct VerboseLlm {
+    seen: std::sync::Mutex<Option<(String, usize)>>,
+}
+
+#[async_trait::async_trait]
+impl LlmProvider for VerboseLlm {
+    fn name(&self) -> String {
+        "verbose/test".to_string()
+    }
+
+    async fn complete(&self, _system: &str, prompt: &str, max_tokens: usize) -> Result<String, String> {
+        *self.seen.lock().unwrap() = Some((prompt.to_string(), max_tokens));
+        Ok("  Payments are refunded within five days. ".repeat(100))
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_client_rate_limit() {
+    let config = cuemap::config::LlmConfig { requests_per_minute: Some(60), ..server_config("ollama", "") };
+    let client = LlmClient::with_provider(config, Ok(std::sync::Arc::new(CannedLlm)));
+
+    let start = tokio::time::Instant::now();
+    for _ in 0..3 {
+        assert_eq!(client.propose_cues("Refund the order", &[]).await.unwrap(), vec!["topic:payments"]);
+    }
+    // The first call starts at once, each further one a second later
+    assert_eq!(start.elapsed().as_secs(), 2);
+    assert_eq!(client.name(), "canned/test");
+}
+
+#[test]
+fn test_build_provider_and_validate() {
+    assert_eq!(build_provider(&server_config("openai", "")).unwrap().name(), "openai/test-model");
+    assert_eq!(build_provider(&server_config("vllm", "http://gpu:8000/")).unwrap().name(), "vllm/test-model");
+    assert_eq!(build_provider(&server_config("anthropic", "")).unwrap().name(), "anthropic/test-model");
+    assert!(build_provider(&server_config("bard", "")).err().unwrap().contains("Unsupported LLM provider"));
+    assert!(server_config("ollama", "").validate().is_ok());
+    assert!(cuemap::config::LlmConfig { model: " ".to_string(), ..server_config("ollama", "") }.validate().is_err());
+
+    // A client with a broken config reports why on every call
+    let client = LlmClient::new(server_config("bard", ""));
+    let err = tokio::runtime::Runtime::new().unwrap().block_on(client.complete("system", "prompt", 16)).unwrap_err();
+    assert!(err.contains("bard"), "{}", err);
+
+    let config: cuemap::config::LlmConfig = serde_json::from_str(r#"{"enabled": true}"#).unwrap();
+    assert_eq!((config.summary_max_tokens, config.summary_input_tokens), (256, 3000));
+
+    // An empty url falls back to the local Ollama server
+    assert_eq!(cuemap::config::LlmConfig::default().to_legacy().ollama_url, DEFAULT_OLLAMA_URL);
+}
+
+#[tokio::test]
+async fn test_openai_and_anthropic_requests() {
+    use axum::{http::HeaderMap, routing::post, Json, Router};
+    use serde_json::{json, Value};
+
+    async fn chat(headers: HeaderMap, Json(body): Json<Value>) -> Json<Value> {
+        assert_eq!(headers["authorization"], "Bearer secret");
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["max_tokens"], 512);
+        let reply = format!(r#"{{"cues": ["model:{}"]}}"#, body["model"].as_str().unwrap());
+        Json(json!({"choices": [{"message": {"role": "assistant", "content": reply}}]}))
+    }
+    async fn messages(headers: HeaderMap, Json(body): Json<Value>) -> Json<Value> {
+        assert_eq!(headers["x-api-key"], "secret");
+        assert!(body["system"].as_str().unwrap().contains("semantic tagging"));
+        Json(json!({"content": [{"type": "text", "text": "{\"cues\": [\"vendor:anthropic\"]}"}]}))
+    }
+
+    let app = Router::new().route("/v1/chat/completions", post(chat)).route("/v1/messages", post(messages));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let openai = LlmClient::new(server_config("openai", &url));
+    assert_eq!(openai.propose_cues("Refund the order", &[]).await.unwrap(), vec!["model:test-model"]);
+    let anthropic = LlmClient::new(server_config("anthropic", &url));
+    assert_eq!(anthropic.propose_cues("Refund the order", &[]).await.unwrap(), vec!["vendor:anthropic"]);
+}
+
+#[tokio::test]
+async fn test_summarize_respects_token_caps() {
+    let config = cuemap::config::LlmConfig { summary_max_tokens: 20, summary_input_tokens: 50, ..server_config("ollama", "") };
+    let llm = std::sync::Arc::new(VerboseLlm::default());
+    let client = LlmClient::with_provider(config, Ok(llm.clone()));
+
+    let texts = vec!["Refunds take five days.".to_string(), "é".repeat(300)];
+    let summary = client.summarize(&texts).await.unwrap();
+    assert!(summary.len() <= 20 * 4, "{}", summary.len());
+    assert!(summary.starts_with("Payments are refunded"));
+
+    let (prompt, max_tokens) = llm.seen.lock().unwrap().clone().unwrap();
+    assert_eq!(max_tokens, 20);
+    assert!(prompt.starts_with("[1] Refunds take five days."));
+    assert!(prompt.len() <= 50 * 4);
+
+    // A blank reply is an error, so the caller keeps its own text
+    let empty = LlmClient::with_provider(server_config("ollama", ""), Ok(std::sync::Arc::new(BlankLlm)));
+    assert!(empty.summarize(&texts).await.is_err());
+}
+
+struct BlankLlm;
+
+#[async_trait::async_trait]
+impl LlmProvider for BlankLlm {
+    fn name(&self) -> String {
+        "blank/test".to_string()
+    }
+
+    async fn complete(&self, _system: &str, _prompt: &str, _max_tokens: usize) -> Result<String, String> {
+        Ok(" \n".to_string())
+    }
+}
+
+#[tokio::test]
+async fn test_answer_from_verified_context() {
+    let llm = std::sync::Arc::new(VerboseLlm::default());
+    let client = LlmClient::with_provider(server_config("ollama", ""), Ok(llm.clone()));
+    let context = "[VERIFIED CONTEXT]\n[1] Refunds take five days. (source=api, id=m1)\n[/VERIFIED CONTEXT]";
+
+    let answer = client.answer("  How long do refunds take? ", context, 8).await.unwrap();
+    assert!(answer.len() <= 8 * 4);
+
+    let (prompt, max_tokens) = llm.seen.lock().unwrap().clone().unwrap();
+    assert_eq!(max_tokens, 8);
+    assert_eq!(prompt, answer_prompt("How long do refunds take?", context));
+    assert!(prompt.starts_with(context) && prompt.ends_with("Question: How long do refunds take?"));
+    assert!(ANSWER_PROMPT.contains("[1]"));
+
+    let empty = LlmClient::with_provider(server_config("ollama", ""), Ok(std::sync::Arc::new(BlankLlm)));
+    assert!(empty.answer("Why?", context, 64).await.is_err());
+}
+
+#[test]
+fn test_parse_rerank_scores() {
+    let reply = "```json\n{\"scores\": [7, 0, 9.5]}\n```";
+    assert_eq!(parse_rerank_scores(reply, 3).unwrap(), vec![7.0, 0.0, 9.5]);
+    assert!(parse_rerank_scores(r#"{"scores": [7, 0]}"#, 3).is_err());
+    assert!(parse_rerank_scores(r#"{"scores": [7, "high", 1]}"#, 3).is_err());
+    assert!(parse_rerank_scores("All of them look relevant.", 3).is_err());
+}
+
+/// Rates passages mentioning refunds highest
+struct RatingLlm;
+
+#[async_trait::async_trait]
+impl LlmProvider for RatingLlm {
+    fn name(&self) -> String {
+        "rating/test".to_string()
+    }
+
+    async fn complete(&self, _system: &str, prompt: &str, _max_tokens: usize) -> Result<String, String> {
+        let scores: Vec<u32> = prompt
+            .split("\n\n")
+            .filter(|p| p.starts_with('['))
+            .map(|p| if p.contains("refund") { 9 } else { 2 })
+            .collect();
+        Ok(serde_json::json!({ "scores": scores }).to_string())
+    }
+}
+
+#[tokio::test]
+async fn test_llm_rerank_reorders_top_candidates() {
+    use cuemap::engine::RecallResult;
+    use cuemap::rerank::{build_reranker, rerank};
+
+    let result = |id: &str, content: &str| RecallResult {
+        memory_id: id.to_string(),
+        content: content.to_string(),
+        score: 1.0,
+        match_integrity: 1.0,
+        intersection_count: 1,
+        recency_score: 0.0,
+        reinforcement_score: 0.0,
+        salience_score: 0.0,
+        created_at: 1_700_000_000.0,
+        metadata: Default::default(),
+        explain: None,
+    };
+    let mut results = vec![
+        result("a", "Orders ship in two days."),
+        result("b", "Payments are captured at checkout."),
+        result("c", "A refund takes five days."),
+        result("d", "A refund past the window needs approval."),
+    ];
+
+    let client = std::sync::Arc::new(LlmClient::with_provider(server_config("ollama", ""), Ok(std::sync::Arc::new(RatingLlm))));
+    let reranker = build_reranker(&cuemap::config::RerankConfig { strategy: cuemap::config::RerankStrategy::Llm, ..Default::default() }, client)
+        .unwrap()
+        .unwrap();
+    assert_eq!(reranker.name(), "llm/rating/test");
+
+    // Only the first three are rescored; the fourth stays last
+    rerank(reranker.as_ref(), "how long do refunds take", &mut results, 3).await.unwrap();
+    let ids: Vec<&str> = results.iter().map(|r| r.memory_id.as_str()).collect();
+    assert_eq!(ids, vec!["c", "a", "b", "d"]);
+    assert_eq!(results[0].metadata["rerank_score"], serde_json::json!(9.0));
+    assert!(!results[3].metadata.contains_key("rerank_score"));
+
+    // The llm strategy needs an enabled LLM
+    let disabled = std::sync::Arc::new(LlmClient::new(cuemap::config::LlmConfig::default()));
+    let config = cuemap::config::RerankConfig { strategy: cuemap::config::RerankStrategy::Llm, ..Default::default() };
+    assert!(build_reranker(&config, disabled.clone()).is_err());
+    assert!(build_reranker(&cuemap::config::RerankConfig::default(), disabled).unwrap().is_none());
+}
@@ -2,7 +2,9 @@ use cuemap::multi_tenant::*;
 use cuemap::semantic::SemanticEngine;
 use cuemap::config::{CueGenStrategy, TuningConfig, LlmConfig};
 use cuemap::structures::MainStats;
+use cuemap::crypto::SnapshotKeyring;
 use std::fs;
+use std::sync::Arc;
 use tempfile::tempdir;
 
 #[test]
@@ -76,3 +78,963 @@ fn test_delete_project() {
     assert!(engine.delete_project(&project_id.to_string()));
     assert!(engine.get_project(&project_id.to_string()).is_none());
 }
+
+#[test]
+fn test_encrypted_snapshot_roundtrip_and_rotation() {
+    let dir = tempdir().unwrap();
+    let snapshots_dir = dir.path().join("snapshots");
+    fs::create_dir_all(&snapshots_dir).unwrap();
+    
+    let project_id = "encrypted_test".to_string();
+    
+    {
+        let mut engine = MultiTenantEngine::with_snapshots_dir(&snapshots_dir, CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+        engine.set_snapshot_keyring(Some(Arc::new(SnapshotKeyring::new("old-pass".to_string(), vec![]))));
+        let ctx = engine.get_or_create_project(project_id.clone()).unwrap();
+        ctx.main.add_memory("secret plans".to_string(), vec!["secret:true".to_string()], None, MainStats::default(), false);
+        engine.save_project(&project_id).expect("Should save successfully");
+    }
+    
+    // Snapshot must not be readable without a key
+    {
+        let engine = MultiTenantEngine::with_snapshots_dir(&snapshots_dir, CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+        assert!(engine.load_project(&project_id).is_err());
+    }
+    
+    // Rotate: new key, old key still accepted on load
+    {
+        let mut engine = MultiTenantEngine::with_snapshots_dir(&snapshots_dir, CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+        engine.set_snapshot_keyring(Some(Arc::new(SnapshotKeyring::new("new-pass".to_string(), vec!["old-pass".to_string()]))));
+        let ctx = engine.load_project(&project_id).expect("Should load with previous key");
+        assert_eq!(ctx.main.recall(vec!["secret:true".to_string()], 10, false, None).len(), 1);
+        
+        let results = engine.rotate_snapshot_keys();
+        assert!(results.values().all(|r| r.is_ok()));
+    }
+    
+    // Old key alone is no longer accepted
+    {
+        let mut engine = MultiTenantEngine::with_snapshots_dir(&snapshots_dir, CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+        engine.set_snapshot_keyring(Some(Arc::new(SnapshotKeyring::new("old-pass".to_string(), vec![]))));
+        assert!(engine.load_project(&project_id).is_err());
+        
+        engine.set_snapshot_keyring(Some(Arc::new(SnapshotKeyring::new("new-pass".to_string(), vec![]))));
+        let ctx = engine.load_project(&project_id).expect("Should load with rotated key");
+        let results = ctx.main.recall(vec!["secret:true".to_string()], 10, false, None);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content, "secret plans");
+    }
+}
+
+#[test]
+fn test_encrypted_archive_restores_under_another_id() {
+    let dir = tempdir().unwrap();
+    let mut engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    engine.set_snapshot_keyring(Some(Arc::new(SnapshotKeyring::new("pass".to_string(), vec![]))));
+    let ctx = engine.get_or_create_project("vault".to_string()).unwrap();
+    ctx.main.add_memory("secret plans".to_string(), vec!["secret:true".to_string()], None, MainStats::default(), false);
+
+    let data = engine.dump_project(&"vault".to_string()).unwrap();
+    assert!(!data.windows(b"secret plans".len()).any(|w| w == b"secret plans"));
+
+    // Keys are derived per project, so the archive is decrypted with the source project's
+    let restored = engine.restore_project(&"vault-copy".to_string(), &data).unwrap();
+    let results = restored.main.recall(vec!["secret:true".to_string()], 10, false, None);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].content, "secret plans");
+    assert!(engine.restore_project(&"vault".to_string(), &data).is_ok());
+
+    // Without the passphrase the archive stays unreadable
+    engine.set_snapshot_keyring(Some(Arc::new(SnapshotKeyring::new("other".to_string(), vec![]))));
+    assert!(engine.restore_project(&"vault-other".to_string(), &data).is_err());
+    assert!(engine.restore_project(&"vault-other".to_string(), &data[..10]).is_err());
+}
+
+#[tokio::test]
+async fn test_project_schedule_runs_due_tasks() {
+    use cuemap::jobs::{JobQueue, ProjectProvider};
+    use cuemap::scheduler::{ProjectSchedule, ScheduledTask, Scheduler};
+
+    let dir = tempdir().unwrap();
+    let engine = Arc::new(MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default()));
+    let ctx = engine.get_or_create_project("sched".to_string()).unwrap();
+    ctx.main.add_memory("scheduled".to_string(), vec!["cue".to_string()], None, MainStats::default(), true);
+
+    // Nothing is scheduled by default
+    assert_eq!(engine.get_project_schedule("sched").unwrap(), ProjectSchedule::default());
+
+    let schedule = ProjectSchedule {
+        decay_interval_secs: Some(1),
+        snapshot_interval_secs: Some(1),
+        consolidation_interval_secs: Some(3600),
+        ..ProjectSchedule::default()
+    };
+    engine.set_project_schedule("sched", schedule.clone()).unwrap();
+    assert_eq!(engine.get_project_schedule("sched").unwrap(), schedule);
+    assert!(engine.set_project_schedule("sched", ProjectSchedule { decay_rate: -1.0, ..ProjectSchedule::default() }).is_err());
+    // An interval of 0 would run the task on every tick
+    assert!(engine.set_project_schedule("sched", ProjectSchedule { prune_interval_secs: Some(0), ..ProjectSchedule::default() }).is_err());
+    assert_eq!(engine.get_project_schedule("sched").unwrap(), schedule);
+
+    // Loaded projects keep their schedule in memory rather than re-reading the meta file
+    fs::remove_file(dir.path().join("sched.meta.json")).unwrap();
+    assert_eq!(engine.get_project_schedule("sched").unwrap(), schedule);
+
+    let provider: Arc<dyn ProjectProvider> = engine.clone();
+    let queue = Arc::new(JobQueue::new(provider, None, true));
+    let scheduler = Scheduler::new(engine.clone(), queue, true);
+
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    let ran = scheduler.run_due().await;
+    assert!(ran.contains(&("sched".to_string(), ScheduledTask::Decay)));
+    assert!(ran.contains(&("sched".to_string(), ScheduledTask::Snapshot)));
+    // Hourly consolidation is not due yet
+    assert!(!ran.iter().any(|(_, t)| *t == ScheduledTask::Consolidation));
+    assert!(dir.path().join("sched.bin").exists());
+}
+
+#[tokio::test]
+async fn test_default_schedule_prunes_projects_without_their_own() {
+    use cuemap::jobs::{JobQueue, ProjectProvider};
+    use cuemap::scheduler::{ProjectSchedule, ScheduledTask, Scheduler};
+
+    let dir = tempdir().unwrap();
+    let mut engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    let defaults = ProjectSchedule { prune_interval_secs: Some(1), prune_max_idle_secs: 0, ..ProjectSchedule::default() };
+    engine.set_default_schedule(defaults.clone());
+    let engine = Arc::new(engine);
+
+    let ctx = engine.get_or_create_project("forget".to_string()).unwrap();
+    let demoted = ctx.main.add_memory("demoted".to_string(), vec!["a".to_string()], None, MainStats { dynamic_salience: -0.5, ..MainStats::default() }, false);
+    let fresh = ctx.main.add_memory("fresh".to_string(), vec!["a".to_string()], None, MainStats::default(), false);
+    engine.get_or_create_project("own".to_string()).unwrap();
+    engine.set_project_schedule("own", ProjectSchedule::default()).unwrap();
+    assert_eq!(engine.get_project_schedule("forget").unwrap(), defaults);
+
+    let provider: Arc<dyn ProjectProvider> = engine.clone();
+    let scheduler = Scheduler::new(engine.clone(), Arc::new(JobQueue::new(provider, None, true)), false);
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    let ran = scheduler.run_due().await;
+    assert!(ran.contains(&("forget".to_string(), ScheduledTask::Prune)));
+    assert!(!ran.contains(&("own".to_string(), ScheduledTask::Prune)));
+    assert!(ctx.main.get_memory(&demoted).is_none());
+    assert!(ctx.main.get_memory(&fresh).is_some());
+}
+
+#[test]
+fn test_project_feed_subscriptions_persist() {
+    use cuemap::agent::feeds::FeedSubscription;
+
+    let dir = tempdir().unwrap();
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    engine.get_or_create_project("docs".to_string()).unwrap();
+    assert!(engine.get_project_feeds("docs").unwrap().is_empty());
+
+    let mut feed = FeedSubscription {
+        url: "https://docs.example.com/sitemap.xml".to_string(),
+        refresh_interval_secs: Some(3600),
+        ..Default::default()
+    };
+    feed.entries.insert("https://docs.example.com/start".to_string(), "2026-01-02".to_string());
+    engine.set_project_feed("docs", feed.clone()).unwrap();
+
+    assert_eq!(engine.get_project_feeds("docs").unwrap(), vec![feed.clone()]);
+    assert_eq!(engine.get_project_feed("docs", &feed.url).unwrap(), Some(feed.clone()));
+    assert!(engine.remove_project_feed("docs", &feed.url).unwrap());
+    assert!(!engine.remove_project_feed("docs", &feed.url).unwrap());
+    assert_eq!(engine.get_project_feed("docs", &feed.url).unwrap(), None);
+}
+
+#[test]
+fn test_concurrent_meta_setters_keep_every_change() {
+    use cuemap::agent::feeds::FeedSubscription;
+
+    let dir = tempdir().unwrap();
+    let engine = Arc::new(MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default()));
+    engine.get_or_create_project("busy".to_string()).unwrap();
+
+    // Each setter loads, changes and saves the whole meta file
+    let handles: Vec<_> = (0..16)
+        .map(|i| {
+            let engine = engine.clone();
+            std::thread::spawn(move || {
+                let feed = FeedSubscription { url: format!("https://example.com/{}.xml", i), ..Default::default() };
+                engine.set_project_feed("busy", feed).unwrap();
+                engine.set_project_acronym("busy", &format!("a{}", i), &format!("expansion {}", i)).unwrap();
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let meta = engine.load_project_meta(&"busy".to_string()).unwrap();
+    assert_eq!(meta.feeds.len(), 16);
+    assert_eq!(meta.acronyms.len(), 16);
+}
+
+#[test]
+fn test_project_quota_limits_writes() {
+    let dir = tempdir().unwrap();
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    let ctx = engine.get_or_create_project("quota".to_string()).unwrap();
+
+    // Unlimited by default
+    assert_eq!(engine.get_project_quota("quota").unwrap(), ProjectQuota::default());
+    assert!(engine.check_quota("quota", 1000, 1 << 30).is_ok());
+
+    engine.set_project_quota("quota", ProjectQuota { max_memories: Some(2), ..ProjectQuota::default() }).unwrap();
+    ctx.main.add_memory("first".to_string(), vec!["a".to_string()], None, MainStats::default(), true);
+    assert!(engine.check_quota("quota", 1, 5).is_ok());
+    ctx.main.add_memory("second".to_string(), vec!["b".to_string()], None, MainStats::default(), true);
+
+    let err = engine.check_quota("quota", 1, 5).unwrap_err();
+    assert_eq!(err.resource, "memories");
+    assert_eq!((err.limit, err.current, err.requested), (2, 2, 1));
+
+    let usage = engine.quota_usage("quota");
+    assert_eq!(usage.memories, 2);
+    assert_eq!(usage.cues, 2);
+    assert!(usage.storage_bytes > 0);
+
+    // Storage limit is checked against stored bytes plus the incoming content
+    engine.set_project_quota("quota", ProjectQuota { max_storage_bytes: Some(usage.storage_bytes + 10), ..ProjectQuota::default() }).unwrap();
+    assert!(engine.check_quota("quota", 1, 10).is_ok());
+    assert_eq!(engine.check_quota("quota", 1, 11).unwrap_err().resource, "storage_bytes");
+
+    // Quotas persist in the project meta file
+    let reloaded = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    assert_eq!(reloaded.get_project_quota("quota").unwrap().max_storage_bytes, Some(usage.storage_bytes + 10));
+}
+
+#[test]
+fn test_project_archive_restores_derived_state() {
+    use cuemap::scheduler::ProjectSchedule;
+
+    let dir = tempdir().unwrap();
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    let ctx = engine.get_or_create_project("source".to_string()).unwrap();
+    let first = ctx.main.add_memory("deploy failed".to_string(), vec!["deploy".to_string(), "failure".to_string()], None, MainStats::default(), true);
+    ctx.main.add_memory("deploy ok".to_string(), vec!["deploy".to_string(), "success".to_string()], None, MainStats::default(), false);
+    // Deleting leaves co-occurrence counts behind, which a plain snapshot reload would not reproduce
+    ctx.main.delete_memory(&first);
+    let weights = std::collections::HashMap::from([("deploy".to_string(), 0.5)]);
+    ctx.market_heatmap.write().unwrap().apply(weights, None, false, cuemap::heatmap::now_secs());
+
+    let schedule = ProjectSchedule { decay_interval_secs: Some(60), ..ProjectSchedule::default() };
+    engine.set_project_schedule("source", schedule.clone()).unwrap();
+
+    let data = engine.dump_project(&"source".to_string()).unwrap();
+    let restored = engine.restore_project(&"copy".to_string(), &data).unwrap();
+
+    let before = ctx.main.to_archive();
+    let after = restored.main.to_archive();
+    assert_eq!(after.memories.len(), 1);
+    assert_eq!(after.cue_index, before.cue_index);
+    assert_eq!(after.co_occurrence, before.co_occurrence);
+    assert_eq!(after.last_events.len(), before.last_events.len());
+    assert_eq!(restored.market_heatmap.read().unwrap().weights().get("deploy"), Some(&0.5));
+    assert_eq!(engine.get_project_schedule("copy").unwrap(), schedule);
+
+    assert!(engine.restore_project(&"broken".to_string(), b"not an archive").is_err());
+}
+
+#[test]
+fn test_links_persist_with_snapshots_archives_and_exports() {
+    use cuemap::engine::MemoryRecord;
+
+    let dir = tempdir().unwrap();
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    let ctx = engine.get_or_create_project("linked".to_string()).unwrap();
+    let outage = ctx.main.add_memory("Checkout outage".to_string(), vec!["outage".to_string()], None, MainStats::default(), true);
+    let cause = ctx.main.add_memory("Expired certificate".to_string(), vec!["tls".to_string()], None, MainStats::default(), true);
+    ctx.main.add_link(&outage, "caused_by", &cause).unwrap();
+
+    engine.save_project(&"linked".to_string()).unwrap();
+    drop(ctx);
+    engine.delete_project(&"linked".to_string());
+    let loaded = engine.load_project(&"linked".to_string()).unwrap();
+    assert_eq!(loaded.main.links(&outage).len(), 1);
+    assert!(!loaded.main.get_memory(&outage).unwrap().metadata.contains_key("links"));
+
+    let data = engine.dump_project(&"linked".to_string()).unwrap();
+    let restored = engine.restore_project(&"linked-copy".to_string(), &data).unwrap();
+    assert_eq!(restored.main.links(&outage)[0].target, cause);
+
+    // Exports carry links; older exports kept them in metadata
+    let record = loaded.main.export_record(&outage).unwrap();
+    assert_eq!(record.links.len(), 1);
+    let target = restored.main.add_memory("Target".to_string(), vec!["t".to_string()], None, MainStats::default(), true);
+    let legacy = MemoryRecord {
+        id: "legacy".to_string(),
+        content: "Legacy".to_string(),
+        cues: vec!["legacy".to_string()],
+        metadata: std::collections::HashMap::from([("links".to_string(), serde_json::json!([{"type": "part_of", "target": target}]))]),
+        stats: MainStats::default(),
+        created_at: None,
+        last_accessed: None,
+        links: Vec::new(),
+    };
+    assert!(restored.main.import_record(legacy));
+    assert_eq!(restored.main.links("legacy")[0].kind, "part_of");
+    assert!(!restored.main.get_memory("legacy").unwrap().metadata.contains_key("links"));
+}
+
+#[test]
+fn test_embeddings_persist_with_snapshots_and_archives() {
+    let dir = tempdir().unwrap();
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    let ctx = engine.get_or_create_project("vectors".to_string()).unwrap();
+    let id = ctx.main.add_memory("embedded".to_string(), vec!["a".to_string()], None, MainStats::default(), true);
+    ctx.main.set_embedding(&id, vec![0.6, 0.8]).unwrap();
+
+    engine.save_project(&"vectors".to_string()).unwrap();
+    assert!(dir.path().join("vectors_vectors.bin").exists());
+    assert_eq!(engine.list_snapshots(), vec!["vectors".to_string()]);
+
+    engine.delete_project(&"vectors".to_string());
+    let loaded = engine.load_project(&"vectors".to_string()).unwrap();
+    assert_eq!(loaded.main.vector_index().dims(), 2);
+    assert!((loaded.main.vector_index().similarity(&[0.6, 0.8], &id).unwrap() - 1.0).abs() < 1e-6);
+
+    let data = engine.dump_project(&"vectors".to_string()).unwrap();
+    let restored = engine.restore_project(&"vectors-copy".to_string(), &data).unwrap();
+    assert!(restored.main.vector_index().contains(&id));
+}
+
+/// Deterministic provider: [content length, 1]
+struct LengthEmbedder;
+
+#[async_trait::async_trait]
+impl cuemap::embeddings::EmbeddingProvider for LengthEmbedder {
+    fn name(&self) -> String {
+        "test/length".to_string()
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        Ok(texts.iter().map(|t| vec![t.len() as f32, 1.0]).collect())
+    }
+}
+
+#[tokio::test]
+async fn test_embedding_provider_backfill_and_project_override() {
+    use cuemap::config::{EmbeddingConfig, EmbeddingStrategy};
+    use cuemap::embeddings::embed_memories;
+
+    let dir = tempdir().unwrap();
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    let ctx = engine.get_or_create_project("embeds".to_string()).unwrap();
+    let a = ctx.main.add_memory("short".to_string(), vec!["a".to_string()], None, MainStats::default(), true);
+    let b = ctx.main.add_memory("a longer memory".to_string(), vec!["b".to_string()], None, MainStats::default(), true);
+    ctx.main.set_embedding(&b, vec![0.0, 1.0]).unwrap();
+
+    // Memories that already have a vector (and unknown ids) are skipped unless forced
+    let ids = vec![a.clone(), b.clone(), "missing".to_string()];
+    assert_eq!(embed_memories(&ctx, &LengthEmbedder, &ids, false).await.unwrap(), 1);
+    assert_eq!(embed_memories(&ctx, &LengthEmbedder, &ids, true).await.unwrap(), 2);
+    assert!(ctx.main.vector_index().similarity(&[15.0, 1.0], &b).unwrap() > 0.999);
+
+    // Server default is "none"; a project override switches provider and drops stale vectors
+    assert_eq!(engine.get_project_embeddings("embeds").strategy, EmbeddingStrategy::None);
+    assert!(engine.embedding_provider("embeds").unwrap().is_none());
+
+    let glove = EmbeddingConfig { strategy: EmbeddingStrategy::Glove, ..Default::default() };
+    engine.set_project_embeddings("embeds", Some(glove)).unwrap();
+    assert!(ctx.main.vector_index().is_empty());
+    assert_eq!(engine.embedding_provider("embeds").unwrap().unwrap().name(), "glove/50d");
+    let meta = engine.load_project_meta(&"embeds".to_string()).unwrap();
+    assert_eq!(meta.embeddings.unwrap().strategy, EmbeddingStrategy::Glove);
+
+    engine.set_project_embeddings("embeds", None).unwrap();
+    assert!(engine.embedding_provider("embeds").unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_replicator_uploads_only_changed_projects() {
+    use cuemap::persistence::{CloudBackupConfig, CloudBackupManager};
+    use cuemap::replication::{fetch_manifest, Replicator};
+
+    let dir = tempdir().unwrap();
+    let bucket = tempdir().unwrap();
+    let engine = Arc::new(MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default()));
+    let config = CloudBackupConfig::from_args(Some("local"), bucket.path().to_str(), None, None, "", false).unwrap();
+    let cloud = Arc::new(CloudBackupManager::new(config).await.unwrap());
+    let replicator = Replicator::new(engine.clone(), cloud.clone());
+
+    let ctx = engine.get_or_create_project("replicated".to_string()).unwrap();
+    ctx.main.add_memory("first".to_string(), vec!["a".to_string()], None, MainStats::default(), true);
+
+    let results = replicator.replicate_changed().await;
+    assert_eq!(results.len(), 1);
+    assert!(results[0].1.is_ok());
+    assert!(bucket.path().join("replicated.bin").exists());
+    let first = fetch_manifest(&cloud, "replicated").await.unwrap().expect("manifest uploaded");
+    assert_eq!(first.total_memories, 1);
+
+    // Nothing changed: nothing to upload
+    assert!(replicator.replicate_changed().await.is_empty());
+
+    ctx.main.add_memory("second".to_string(), vec!["b".to_string()], None, MainStats::default(), true);
+    assert_eq!(replicator.replicate_changed().await.len(), 1);
+    let second = fetch_manifest(&cloud, "replicated").await.unwrap().unwrap();
+    assert_eq!(second.total_memories, 2);
+    assert!(second.replicated_at >= first.replicated_at);
+}
+
+#[tokio::test]
+async fn test_follower_pulls_replicated_projects_from_bucket() {
+    use cuemap::follower::{FollowSource, Follower};
+    use cuemap::persistence::{CloudBackupConfig, CloudBackupManager};
+    use cuemap::replication::Replicator;
+
+    let primary_dir = tempdir().unwrap();
+    let follower_dir = tempdir().unwrap();
+    let bucket = tempdir().unwrap();
+    let primary = Arc::new(MultiTenantEngine::with_snapshots_dir(primary_dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default()));
+    let replica = Arc::new(MultiTenantEngine::with_snapshots_dir(follower_dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default()));
+    let config = CloudBackupConfig::from_args(Some("local"), bucket.path().to_str(), None, None, "", false).unwrap();
+    let cloud = Arc::new(CloudBackupManager::new(config).await.unwrap());
+    let replicator = Replicator::new(primary.clone(), cloud.clone());
+    let follower = Follower::new(replica.clone(), FollowSource::Bucket(cloud.clone()));
+
+    let ctx = primary.get_or_create_project("followed".to_string()).unwrap();
+    ctx.main.add_memory("first".to_string(), vec!["a".to_string()], None, MainStats::default(), true);
+    replicator.replicate_changed().await;
+
+    assert_eq!(follower.sync().await.unwrap(), vec!["followed".to_string()]);
+    assert_eq!(replica.get_project(&"followed".to_string()).unwrap().main.get_memories().len(), 1);
+
+    // Unchanged on the source: nothing pulled
+    assert!(follower.sync().await.unwrap().is_empty());
+
+    ctx.main.add_memory("second".to_string(), vec!["b".to_string()], None, MainStats::default(), true);
+    replicator.replicate_changed().await;
+    assert_eq!(follower.sync().await.unwrap().len(), 1);
+    assert_eq!(replica.get_project(&"followed".to_string()).unwrap().main.get_memories().len(), 2);
+    // Pulled files are renamed into place, leaving no temp files behind
+    assert!(fs::read_dir(follower_dir.path()).unwrap().flatten().all(|e| !e.file_name().to_string_lossy().ends_with(".tmp")));
+
+    // Removed on the source: dropped from the replica
+    cloud.delete_snapshot("followed").await.unwrap();
+    assert!(follower.sync().await.unwrap().is_empty());
+    assert!(replica.get_project(&"followed".to_string()).is_none());
+}
+
+#[tokio::test]
+async fn test_live_feed_publishes_main_engine_changes() {
+    use cuemap::live::LiveEvent;
+
+    let dir = tempdir().unwrap();
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    let mut rx = engine.subscribe_live();
+
+    let ctx = engine.get_or_create_project("live".to_string()).unwrap();
+    let id = ctx.main.add_memory("hello".to_string(), vec!["greeting".to_string()], None, MainStats::default(), true);
+    ctx.main.attach_cues(&id, vec!["salutation".to_string()]);
+    ctx.main.reinforce_memory(&id, vec!["greeting".to_string()]);
+    ctx.main.delete_memory(&id);
+    // Lexicon changes are not part of the graph feed
+    ctx.lexicon.add_memory("token".to_string(), vec!["token".to_string()], None, Default::default(), true);
+
+    match rx.try_recv().unwrap() {
+        LiveEvent::MemoryAdded { project_id, memory_id, cues, .. } => {
+            assert_eq!(project_id, "live");
+            assert_eq!(memory_id, id);
+            assert_eq!(cues, vec!["greeting".to_string()]);
+        }
+        other => panic!("unexpected event: {:?}", other),
+    }
+    assert!(matches!(rx.try_recv().unwrap(), LiveEvent::CuesAttached { ref cues, .. } if cues == &vec!["salutation".to_string()]));
+    assert!(matches!(rx.try_recv().unwrap(), LiveEvent::MemoryReinforced { reinforcement_count: 1, .. }));
+    assert!(matches!(rx.try_recv().unwrap(), LiveEvent::MemoryDeleted { ref memory_id, .. } if memory_id == &id));
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn test_project_recall_profile_persists() {
+    use cuemap::engine::RecallProfile;
+
+    let dir = tempdir().unwrap();
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    let ctx = engine.get_or_create_project("profiles".to_string()).unwrap();
+    assert_eq!(*ctx.recall_profile.read().unwrap(), RecallProfile::Thorough);
+
+    engine.set_project_recall_profile("profiles", RecallProfile::Balanced).unwrap();
+    assert_eq!(*ctx.recall_profile.read().unwrap(), RecallProfile::Balanced);
+    assert_eq!(serde_json::to_value(RecallProfile::Balanced).unwrap(), serde_json::json!("balanced"));
+
+    // A fresh engine picks the default up from the project meta
+    let reopened = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    assert_eq!(reopened.get_project_recall_profile("profiles").unwrap(), RecallProfile::Balanced);
+    let ctx = reopened.get_or_create_project("profiles".to_string()).unwrap();
+    assert_eq!(*ctx.recall_profile.read().unwrap(), RecallProfile::Balanced);
+}
+
+#[test]
+fn test_project_chunking_persists() {
+    use cuemap::agent::chunker::ChunkingConfig;
+
+    let dir = tempdir().unwrap();
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    engine.get_or_create_project("chunks".to_string()).unwrap();
+    assert_eq!(engine.get_project_chunking("chunks").unwrap(), ChunkingConfig::default());
+
+    let chunking = ChunkingConfig { max_tokens: Some(200), overlap_tokens: Some(20), min_chunk_chars: None };
+    engine.set_project_chunking("chunks", chunking).unwrap();
+    assert!(engine.set_project_chunking("chunks", ChunkingConfig { max_tokens: Some(10), overlap_tokens: Some(8), ..chunking }).is_err());
+
+    let reopened = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    assert_eq!(reopened.get_project_chunking("chunks").unwrap(), chunking);
+}
+
+#[test]
+fn test_project_llm_override_persists() {
+    let dir = tempdir().unwrap();
+    let server = LlmConfig { enabled: true, requests_per_minute: Some(30), ..LlmConfig::default() };
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), server.clone());
+    engine.get_or_create_project("llm".to_string()).unwrap();
+    assert_eq!(engine.get_project_llm("llm"), server);
+
+    let custom = LlmConfig { provider: "vllm".to_string(), model: "qwen2.5".to_string(), url: "http://gpu:8000".to_string(), ..server.clone() };
+    engine.set_project_llm("llm", Some(custom.clone())).unwrap();
+    assert_eq!(engine.llm_client("llm").name(), "vllm/qwen2.5");
+    assert!(engine.set_project_llm("llm", Some(LlmConfig { provider: "bard".to_string(), ..custom.clone() })).is_err());
+    assert_eq!(engine.get_project_llm("other"), server);
+
+    let reopened = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), server.clone());
+    assert_eq!(reopened.get_project_llm("llm"), custom);
+    assert_eq!(reopened.set_project_llm("llm", None).unwrap(), server);
+}
+
+#[test]
+fn test_project_rerank_persists() {
+    use cuemap::config::{RerankConfig, RerankStrategy};
+
+    let dir = tempdir().unwrap();
+    let server = LlmConfig { enabled: true, ..LlmConfig::default() };
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), server.clone());
+    engine.get_or_create_project("rerank".to_string()).unwrap();
+    assert_eq!(engine.get_project_rerank("rerank"), RerankConfig::default());
+    assert!(engine.reranker("rerank").unwrap().is_none());
+
+    let config = RerankConfig { enabled: true, strategy: RerankStrategy::Llm, top_n: 10, ..Default::default() };
+    engine.set_project_rerank("rerank", config.clone()).unwrap();
+    assert_eq!(engine.reranker("rerank").unwrap().unwrap().name(), "llm/ollama/mistral");
+    assert!(engine.set_project_rerank("rerank", RerankConfig { top_n: 0, ..config.clone() }).is_err());
+    assert!(engine.set_project_rerank("rerank", RerankConfig { strategy: RerankStrategy::None, ..config.clone() }).is_err());
+
+    let reopened = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), server);
+    assert_eq!(reopened.get_project_rerank("rerank"), config);
+
+    // Without an LLM the llm strategy can't be set
+    let offline = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    offline.get_or_create_project("plain".to_string()).unwrap();
+    assert!(offline.set_project_rerank("plain", config).is_err());
+}
+
+#[test]
+fn test_project_taxonomy_persists_and_applies() {
+    use cuemap::taxonomy::Taxonomy;
+
+    let dir = tempdir().unwrap();
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    let ctx = engine.get_or_create_project("tax".to_string()).unwrap();
+    assert_eq!(engine.get_project_taxonomy("tax").unwrap(), Taxonomy::default());
+
+    let taxonomy = Taxonomy {
+        forbidden_patterns: vec!["Secret:*".to_string()],
+        max_cue_length: Some(20),
+        ..Default::default()
+    };
+    let saved = engine.set_project_taxonomy("tax", taxonomy).unwrap();
+    assert_eq!(saved.forbidden_patterns, vec!["secret:*"]);
+    assert!(engine.set_project_taxonomy("tax", Taxonomy { max_cue_length: Some(0), ..Default::default() }).is_err());
+
+    // The loaded project picks up the new taxonomy
+    let report = ctx.validate_cues(vec!["secret:token".to_string(), "topic:rust".to_string()]);
+    assert_eq!(report.accepted, vec!["topic:rust"]);
+
+    let reopened = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    assert_eq!(reopened.get_project_taxonomy("tax").unwrap(), saved);
+    let ctx = reopened.get_or_create_project("tax".to_string()).unwrap();
+    assert_eq!(*ctx.taxonomy.read().unwrap(), saved);
+}
+
+#[test]
+fn test_co_occurrence_persists_with_snapshot() {
+    let dir = tempdir().unwrap();
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    let project_id = "cooc".to_string();
+    let ctx = engine.get_or_create_project(project_id.clone()).unwrap();
+    let id = ctx.main.add_memory("deploy notes".to_string(), vec!["deploy".to_string(), "k8s".to_string()], None, MainStats::default(), true);
+    // Reinforcement cues count as co-occurring but are not on the memory, so a rebuild would lose them
+    ctx.main.reinforce_memory(&id, vec!["deploy".to_string(), "rollback".to_string()]);
+    let before = ctx.main.co_occurrence_snapshot();
+
+    engine.save_project(&project_id).unwrap();
+    assert!(dir.path().join("cooc_cooccurrence.bin").exists());
+    assert_eq!(engine.list_snapshots(), vec![project_id.clone()]);
+
+    engine.delete_project(&project_id);
+    let loaded = engine.load_project(&project_id).unwrap();
+    assert_eq!(loaded.main.co_occurrence_snapshot().neighbours, before.neighbours);
+    assert!(loaded.main.co_occurrence_snapshot().neighbours["deploy"].contains_key("rollback"));
+    assert_eq!(loaded.main.get_stats()["co_occurrence_entries"], serde_json::json!(before.neighbours.values().map(|n| n.len()).sum::<usize>()));
+}
+
+#[test]
+fn test_lexicon_quality_persists_with_snapshot() {
+    use cuemap::lexicon_quality::record_expansions;
+    use cuemap::structures::LexiconStats;
+
+    let dir = tempdir().unwrap();
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    let project_id = "lexq".to_string();
+    let ctx = engine.get_or_create_project(project_id.clone()).unwrap();
+    let memory = ctx.main.add_memory("refund issued".to_string(), vec!["refund".to_string()], None, MainStats::default(), true);
+    let entry = ctx.lexicon.add_memory("refund".to_string(), vec!["refunds".to_string()], None, LexiconStats::default(), true);
+    record_expansions(&ctx, std::slice::from_ref(&entry), &[memory]);
+
+    engine.save_project(&project_id).unwrap();
+    assert!(dir.path().join("lexq_lexicon_quality.bin").exists());
+    assert_eq!(engine.list_snapshots(), vec![project_id.clone()]);
+
+    engine.delete_project(&project_id);
+    let loaded = engine.load_project(&project_id).unwrap();
+    assert_eq!(loaded.lexicon_quality.get(&entry).unwrap().hits, 1.0);
+}
+
+#[test]
+fn test_heatmap_persists_with_snapshot() {
+    use cuemap::heatmap::{now_secs, HeatmapSource};
+    use std::collections::HashMap;
+
+    let dir = tempdir().unwrap();
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    let project_id = "heat".to_string();
+    let ctx = engine.get_or_create_project(project_id.clone()).unwrap();
+    let now = now_secs();
+    {
+        let mut heatmap = ctx.market_heatmap.write().unwrap();
+        heatmap.apply(HashMap::from([("earnings".to_string(), 2.0)]), Some(3600), false, now);
+        heatmap.sync_trending(HashMap::from([("outage".to_string(), 0.5)]), now);
+    }
+
+    engine.save_project(&project_id).unwrap();
+    assert!(dir.path().join("heat_heatmap.bin").exists());
+    assert_eq!(engine.list_snapshots(), vec![project_id.clone()]);
+
+    engine.delete_project(&project_id);
+    let loaded = engine.load_project(&project_id).unwrap();
+    let heatmap = loaded.market_heatmap.read().unwrap();
+    let earnings = &heatmap.entries()["earnings"];
+    assert_eq!(earnings.expires_at, Some(now + 3600));
+    assert_eq!(earnings.source, HeatmapSource::Manual);
+    assert_eq!(heatmap.entries()["outage"].source, HeatmapSource::Trending);
+    assert!(heatmap.weights()["earnings"] > 1.9);
+    drop(heatmap);
+
+    // Emptied heatmaps drop their sidecar
+    loaded.market_heatmap.write().unwrap().apply(HashMap::from([("earnings".to_string(), 0.0), ("outage".to_string(), 0.0)]), None, false, now);
+    engine.save_project(&project_id).unwrap();
+    assert!(!dir.path().join("heat_heatmap.bin").exists());
+}
+
+#[test]
+fn test_access_stats_persist_with_snapshot() {
+    let dir = tempdir().unwrap();
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    let project_id = "access".to_string();
+    let ctx = engine.get_or_create_project(project_id.clone()).unwrap();
+    let recalled = ctx.main.add_memory("Recalled".to_string(), vec!["a".to_string()], None, MainStats::default(), true);
+    let idle = ctx.main.add_memory("Idle".to_string(), vec!["b".to_string()], None, MainStats::default(), true);
+
+    engine.save_project(&project_id).unwrap();
+    assert!(!dir.path().join("access_access.bin").exists(), "nothing recalled yet");
+
+    ctx.log_recall(vec![recalled.clone()], vec!["a".to_string()]);
+    ctx.log_recall(vec![recalled.clone()], vec!["a".to_string()]);
+    let access = ctx.main.memory_access(&recalled);
+    assert_eq!(access.recall_count, 2);
+    assert!(access.last_recalled_at.is_some());
+    assert_eq!(ctx.main.memory_access(&idle).recall_count, 0);
+
+    let analytics = ctx.main.memory_analytics(10, 0);
+    assert_eq!((analytics.total_memories, analytics.recalled_count, analytics.never_recalled_count), (2, 1, 1));
+    assert_eq!(analytics.never_recalled[0].memory_id, idle);
+    assert_eq!(ctx.main.memory_analytics(10, 3600).never_recalled_count, 0, "too new to count as dead weight");
+
+    engine.save_project(&project_id).unwrap();
+    assert!(dir.path().join("access_access.bin").exists());
+    drop(ctx);
+    engine.delete_project(&project_id);
+    let loaded = engine.load_project(&project_id).unwrap();
+    assert_eq!(loaded.main.memory_access(&recalled), access);
+
+    // Deleting the memory drops its stats
+    assert!(loaded.main.delete_memory(&recalled));
+    assert_eq!(loaded.main.memory_access(&recalled).recall_count, 0);
+}
+
+#[test]
+fn test_content_index_rebuilt_on_load() {
+    let dir = tempdir().unwrap();
+    let mut engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    // Content is encrypted at rest, so the index can only be rebuilt once the key is set
+    engine.set_master_key(Some(Arc::new(cuemap::crypto::EncryptionKey::new(vec![5u8; 32]))));
+    let project_id = "hashes".to_string();
+    let ctx = engine.get_or_create_project(project_id.clone()).unwrap();
+    let id = ctx.main.add_memory("Deploys run at noon".to_string(), vec!["deploy".to_string()], None, MainStats::default(), true);
+
+    engine.save_project(&project_id).unwrap();
+    drop(ctx);
+    engine.delete_project(&project_id);
+    let loaded = engine.load_project(&project_id).unwrap();
+    assert_eq!(loaded.main.find_identical("Deploys run at noon"), Some(id));
+}
+
+#[test]
+fn test_normalization_stored_with_project_and_applied_to_existing_cues() {
+    use cuemap::normalization::NormalizationConfig;
+
+    let dir = tempdir().unwrap();
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    let project_id = "normalized".to_string();
+    let ctx = engine.get_or_create_project(project_id.clone()).unwrap();
+    let id = ctx.main.add_memory("Menu".to_string(), vec!["topic:crème".to_string(), "path:café/menu.md".to_string()], None, MainStats::default(), false);
+    assert_eq!(ctx.normalize_cue("Topic:Crème"), "topic:crème");
+
+    let config = NormalizationConfig { strip_accents: true, ..NormalizationConfig::default() };
+    let (stored, updated) = engine.set_project_normalization(&project_id, config.clone()).unwrap();
+    assert_eq!((stored, updated), (config.clone(), 1));
+
+    // Queries and existing memories agree on the new spelling; identifiers are untouched
+    assert_eq!(ctx.normalize_cue("Topic:Crème"), "topic:creme");
+    let cues = ctx.main.get_memory(&id).unwrap().cues;
+    assert!(cues.contains(&"topic:creme".to_string()));
+    assert!(!cues.contains(&"topic:crème".to_string()));
+    assert!(cues.contains(&"path:café/menu.md".to_string()));
+    assert_eq!(ctx.main.recall(vec!["topic:creme".to_string()], 10, false, None)[0].memory_id, id);
+
+    assert_eq!(engine.get_project_normalization(&project_id).unwrap(), config);
+    engine.save_project(&project_id).unwrap();
+    drop(ctx);
+    engine.delete_project(&project_id);
+    let loaded = engine.load_project(&project_id).unwrap();
+    assert_eq!(loaded.normalize_cue("crème"), "creme");
+}
+
+#[test]
+fn test_org_lexicon_shared_and_persisted() {
+    use cuemap::structures::LexiconStats;
+
+    let dir = tempdir().unwrap();
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    let ctx = engine.get_or_create_project("org-a".to_string()).unwrap();
+    assert!(ctx.resolve_cues_from_text("kafka cluster", false).0.contains(&"kafka".to_string()));
+
+    engine.org_lexicon().upsert_memory_with_id("cue:messaging".to_string(), "messaging".to_string(), vec!["kafka".to_string()], None, Some(LexiconStats::default()), false, false);
+    engine.org_lexicon_changed();
+
+    // Tokens the project lexicon doesn't know resolve through the org lexicon
+    let (cues, lexicon_ids, _) = ctx.resolve_cues_from_text("kafka cluster", false);
+    assert!(cues.contains(&"messaging".to_string()));
+    assert!(cues.contains(&"cluster".to_string()));
+    assert!(!cues.contains(&"kafka".to_string()));
+    assert!(lexicon_ids.is_empty());
+
+    // The project's own lexicon takes precedence
+    let other = engine.get_or_create_project("org-b".to_string()).unwrap();
+    other.lexicon.upsert_memory_with_id("streaming".to_string(), "streaming".to_string(), vec!["kafka".to_string()], None, Some(LexiconStats::default()), false, false);
+    let cues = other.resolve_cues_from_text("kafka", false).0;
+    assert_eq!(cues, vec!["streaming".to_string()]);
+
+    engine.save_all();
+    assert!(dir.path().join("org").join("lexicon.bin").exists());
+    assert_eq!(engine.list_snapshots(), vec!["org-a".to_string(), "org-b".to_string()]);
+
+    let restarted = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    restarted.load_all();
+    assert_eq!(restarted.org_lexicon().total_memories(), 1);
+    let loaded = restarted.get_project(&"org-a".to_string()).unwrap();
+    assert!(loaded.resolve_cues_from_text("kafka", false).0.contains(&"messaging".to_string()));
+}
+
+#[test]
+fn test_idle_projects_archived_and_reloaded() {
+    use std::sync::atomic::Ordering;
+
+    let dir = tempdir().unwrap();
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    let ctx = engine.get_or_create_project("cold".to_string()).unwrap();
+    let memory_id = ctx.main.add_memory("quarterly report".to_string(), vec!["report".to_string()], None, MainStats::default(), true);
+    ctx.last_activity.store(0, Ordering::Relaxed);
+    let warm = engine.get_or_create_project("warm".to_string()).unwrap();
+    warm.last_activity.store(0, Ordering::Relaxed);
+
+    // A project still held somewhere stays loaded
+    drop(ctx);
+    assert_eq!(engine.archive_idle_projects(3600), vec!["cold".to_string()]);
+    assert!(engine.get_project(&"cold".to_string()).is_none());
+    assert!(engine.get_project(&"warm".to_string()).is_some());
+
+    let archived = engine.archived_projects();
+    assert_eq!(archived.len(), 1);
+    assert!(archived[0].archived);
+    assert_eq!(archived[0].total_memories, 1);
+    assert_eq!(engine.get_global_stats()["archive"]["evictions"], 1);
+
+    // First access reloads the snapshot instead of creating an empty project
+    let reloaded = engine.get_or_create_project("cold".to_string()).unwrap();
+    assert!(reloaded.main.get_memory(&memory_id).is_some());
+    assert!(engine.archived_projects().is_empty());
+    assert_eq!(engine.archive_stats().reloads.load(Ordering::Relaxed), 1);
+
+    // Recently used projects are kept
+    drop(reloaded);
+    drop(warm);
+    assert!(engine.archive_idle_projects(3600).contains(&"warm".to_string()));
+    assert!(engine.get_project(&"cold".to_string()).is_some());
+    assert!(engine.get_or_load_project(&"warm".to_string()).is_some());
+    assert!(engine.get_or_load_project(&"missing".to_string()).is_none());
+
+    // Jobs reload archived projects but don't recreate deleted ones
+    let provider: &dyn cuemap::jobs::ProjectProvider = &engine;
+    assert!(engine.unload_project(&"warm".to_string()).unwrap());
+    assert!(provider.get_or_load_project("warm").is_some());
+    assert!(provider.get_or_load_project("missing").is_none());
+    assert!(engine.get_project(&"missing".to_string()).is_none());
+}
+
+#[test]
+fn test_resident_project_cap_evicts_lru() {
+    use std::sync::atomic::Ordering;
+
+    let dir = tempdir().unwrap();
+    let mut engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    engine.set_residency_limit(2, true);
+
+    let oldest = engine.get_or_create_project("lru-a".to_string()).unwrap();
+    oldest.main.add_memory("first".to_string(), vec!["first".to_string()], None, MainStats::default(), true);
+    oldest.last_activity.store(0, Ordering::Relaxed);
+    drop(oldest);
+    let b = engine.get_or_create_project("lru-b".to_string()).unwrap();
+
+    // The least recently used project makes room
+    let c = engine.get_or_create_project("lru-c".to_string()).unwrap();
+    assert!(engine.get_project(&"lru-a".to_string()).is_none());
+    assert_eq!(engine.archived_projects()[0].project_id, "lru-a");
+
+    // Nothing can be unloaded while every resident project is in use
+    let err = engine.get_or_create_project("lru-d".to_string()).err().expect("capacity is exhausted");
+    assert!(err.contains("Capacity reached"), "{}", err);
+    assert!(err.contains("/projects/lru-b/unload") || err.contains("/projects/lru-c/unload"), "{}", err);
+    assert_eq!(engine.archive_stats().rejections.load(Ordering::Relaxed), 1);
+    assert!(engine.get_or_create_project("lru-a".to_string()).is_err());
+
+    drop(c);
+    let a = engine.get_or_create_project("lru-a".to_string()).unwrap();
+    assert_eq!(a.main.total_memories(), 1);
+    assert!(engine.get_project(&"lru-c".to_string()).is_none());
+    drop(b);
+    assert!(engine.unload_project(&"lru-b".to_string()).unwrap());
+    assert!(!engine.unload_project(&"lru-a".to_string()).unwrap());
+    assert_eq!(engine.get_global_stats()["archive"]["resident_projects"], 1);
+}
+
+#[test]
+fn test_disk_backed_project_keeps_payloads_on_disk() {
+    use cuemap::payload_store::StorageBackend;
+
+    let dir = tempdir().unwrap();
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+
+    let ctx = engine.create_project("large".to_string(), StorageBackend::Disk).unwrap();
+    let id = ctx.main.add_memory("kept on disk".to_string(), vec!["disk".to_string()], None, MainStats::default(), true);
+    ctx.main.upsert_memory_with_id("fixed".to_string(), "second payload".to_string(), vec!["disk".to_string()], None, None, false, false);
+    assert!(ctx.main.get_memory(&id).unwrap().content.is_stored());
+    assert_eq!(ctx.main.recall(vec!["disk".to_string()], 10, false, None).len(), 2);
+    assert!(ctx.main.get_stats().contains_key("payload_store"));
+    assert!(dir.path().join("large_payloads.log").exists());
+    assert_eq!(engine.list_projects()[0].storage, StorageBackend::Disk);
+
+    // The backend is fixed at creation; other projects stay in memory
+    assert!(engine.create_project("large".to_string(), StorageBackend::Memory).is_err());
+    assert!(engine.create_project("large".to_string(), StorageBackend::Disk).is_ok());
+    let small = engine.create_project("small".to_string(), StorageBackend::Memory).unwrap();
+    small.main.add_memory("in memory".to_string(), vec!["ram".to_string()], None, MainStats::default(), true);
+    assert!(small.main.payload_store().is_none());
+
+    // Snapshots carry the payloads; reloading moves them back to a fresh log
+    drop(ctx);
+    assert!(engine.unload_project(&"large".to_string()).unwrap());
+    assert_eq!(engine.project_storage(&"large".to_string()), Some(StorageBackend::Disk));
+    let ctx = engine.get_or_create_project("large".to_string()).unwrap();
+    let memory = ctx.main.get_memory("fixed").unwrap();
+    assert!(memory.content.is_stored());
+    assert_eq!(memory.access_content(None).unwrap(), "second payload");
+    assert_eq!(memory.content.resident_bytes(), 0);
+}
+
+#[test]
+fn test_restore_snapshot_generation() {
+    let dir = tempdir().unwrap();
+    let mut engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    engine.set_snapshot_generations(2, 0);
+    let project = "rollback".to_string();
+
+    let ctx = engine.get_or_create_project(project.clone()).unwrap();
+    for i in 0..3 {
+        ctx.main.add_memory(format!("memory {}", i), vec!["batch".to_string()], None, MainStats::default(), true);
+        engine.save_project(&project).unwrap();
+    }
+    // Three saves, two generations kept (newest first)
+    let generations = engine.list_generations(&project);
+    assert_eq!(generations.len(), 2);
+    assert!(generations[0].created_at >= generations[1].created_at);
+    assert!(generations.iter().all(|g| g.files >= 2 && g.bytes > 0));
+
+    // A bad ingestion, then a rollback to the generation with two memories
+    ctx.main.add_memory("bad data".to_string(), vec!["batch".to_string()], None, MainStats::default(), true);
+    drop(ctx);
+    let target = generations[1].generation.clone();
+    let (ctx, previous) = engine.restore_generation(&project, &target).unwrap();
+    assert_eq!(ctx.main.total_memories(), 2);
+    assert_eq!(engine.get_project(&project).unwrap().main.total_memories(), 2);
+
+    // The replaced state was kept, so the restore itself can be undone
+    let previous = previous.expect("state before the restore should be kept");
+    drop(ctx);
+    let (ctx, _) = engine.restore_generation(&project, &previous).unwrap();
+    assert_eq!(ctx.main.total_memories(), 4);
+
+    assert!(engine.restore_generation(&project, "../other").is_err());
+    assert!(engine.restore_generation(&project, "12345").is_err());
+
+    engine.delete_project(&project);
+    engine.delete_snapshot(&project).unwrap();
+    assert!(engine.list_generations(&project).is_empty());
+    // The snapshot goes with every sidecar, aliases and lexicon included
+    let left: Vec<_> = fs::read_dir(dir.path()).unwrap().flatten().filter(|e| e.file_name().to_string_lossy().starts_with(project.as_str())).collect();
+    assert!(left.is_empty(), "{:?}", left);
+}
+
+#[test]
+fn test_existing_projects_keep_their_tokenizer() {
+    use cuemap::nl::Tokenizer;
+
+    let dir = tempdir().unwrap();
+    let engine = MultiTenantEngine::with_snapshots_dir(dir.path(), CueGenStrategy::default(), SemanticEngine::new(None), TuningConfig::default(), LlmConfig::default());
+    let fresh = engine.get_or_create_project("fresh".to_string()).unwrap();
+    assert_eq!(fresh.tokenizer(), Tokenizer::Unicode);
+    assert_eq!(engine.load_project_meta(&"fresh".to_string()).unwrap().tokenizer, Tokenizer::Unicode);
+
+    // Meta written before tokenizers were recorded
+    let mut meta = serde_json::to_value(ProjectMeta::new("older".to_string())).unwrap();
+    meta.as_object_mut().unwrap().remove("tokenizer");
+    fs::write(dir.path().join("older.meta.json"), meta.to_string()).unwrap();
+    assert_eq!(engine.load_project_meta(&"older".to_string()).unwrap().tokenizer, Tokenizer::Ascii);
+    let older = engine.get_or_create_project("older".to_string()).unwrap();
+    assert_eq!(older.tokenizer(), Tokenizer::Ascii);
+    assert!(older.tokenize("Launch day 🚀").iter().all(|c| !c.starts_with("emoji:")));
+}
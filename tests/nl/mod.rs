@@ -82,3 +82,109 @@ fn test_stemming() {
     assert!(cues1.contains(&"add_comment".to_string()));
     assert!(cues2.contains(&"add_comment".to_string()));
 }
+
+#[test]
+fn test_language_detection() {
+    use cuemap::multilingual::{detect_language, TextLanguage};
+
+    assert_eq!(detect_language("The database is not reachable and the server does not answer"), TextLanguage::English);
+    assert_eq!(detect_language("Die Datenbank ist nicht erreichbar und der Server antwortet nicht"), TextLanguage::German);
+    assert_eq!(detect_language("La base de données est injoignable et le serveur ne répond pas"), TextLanguage::French);
+    assert_eq!(detect_language("La base de datos no está disponible y el servidor no responde"), TextLanguage::Spanish);
+    assert_eq!(detect_language("数据库连接失败"), TextLanguage::Cjk);
+    // Thin evidence stays English
+    assert_eq!(detect_language("datenbank"), TextLanguage::English);
+}
+
+#[test]
+fn test_multilingual_tokenization() {
+    use cuemap::multilingual::TextLanguage;
+
+    let de = tokenize_to_cues_in("Die Verbindungen zur Datenbank", Language::Default, TextLanguage::German);
+    let de_singular = tokenize_to_cues_in("Eine Verbindung zu den Datenbanken", Language::Default, TextLanguage::German);
+    assert!(!de.iter().any(|c| c == "die" || c == "zur"));
+    for cue in &de_singular {
+        if !cue.contains('_') {
+            assert!(de.contains(cue), "{} missing from {:?}", cue, de);
+        }
+    }
+
+    let fr = tokenize_to_cues_in("Les échecs de connexion", Language::Default, TextLanguage::French);
+    assert_eq!(TextLanguage::French.stem("échecs"), TextLanguage::French.stem("échec"));
+    assert!(fr.contains(&TextLanguage::French.stem("échec")));
+    // Accented words survive tokenization
+    assert!(fr.iter().any(|c| c.starts_with("éch")));
+
+    let es = tokenize_to_cues_in("Los servidores de producción", Language::Default, TextLanguage::Spanish);
+    assert!(es.contains(&TextLanguage::Spanish.stem("servidor")));
+    assert!(!es.contains(&"los".to_string()));
+
+    let zh = tokenize_to_cues_in("数据库连接失败 after deploy", Language::Default, TextLanguage::Cjk);
+    assert!(zh.contains(&"数据".to_string()));
+    assert!(zh.contains(&"连接".to_string()));
+    assert!(zh.contains(&"deploy".to_string()));
+
+    // Particles split runs instead of forming bigrams
+    let ja = tokenize_to_cues_in("東京の天気", Language::Default, TextLanguage::Cjk);
+    assert!(ja.contains(&"東京".to_string()));
+    assert!(ja.contains(&"天気".to_string()));
+    assert!(!ja.contains(&"京の".to_string()));
+
+    // Auto detection picks the pipeline
+    assert_eq!(
+        tokenize_to_cues_in("Die Verbindungen zur Datenbank sind fehlgeschlagen", Language::Default, TextLanguage::Auto),
+        tokenize_to_cues_in("Die Verbindungen zur Datenbank sind fehlgeschlagen", Language::Default, TextLanguage::German)
+    );
+    // Without a language, text is English
+    assert_eq!(TextLanguage::default(), TextLanguage::English);
+    assert_eq!(
+        tokenize_to_cues("Die Verbindungen zur Datenbank sind fehlgeschlagen"),
+        tokenize_to_cues_in("Die Verbindungen zur Datenbank sind fehlgeschlagen", Language::Default, TextLanguage::English)
+    );
+}
+
+#[test]
+fn test_unicode_words_emoji_and_hashtags() {
+    use cuemap::multilingual::TextLanguage;
+    let english = |text: &str, emoji: EmojiMode| tokenize_to_cues_with(text, Language::Default, TextLanguage::English, emoji);
+
+    // Accents are folded instead of cutting words short
+    let cafe = english("Naïve café owners", EmojiMode::Drop);
+    assert!(cafe.contains(&"cafe".to_string()));
+    assert!(cafe.contains(&stem_word("naive")));
+    assert!(!cafe.contains(&"caf".to_string()));
+    assert_eq!(fold_accents("Crème Brûlée"), "Creme Brulee");
+
+    // Contractions are whole words, so they match stopwords
+    assert!(!english("don't panic", EmojiMode::Drop).contains(&"don".to_string()));
+
+    // Hashtags are kept whole, even when they look like ids
+    let tagged = english("Rollout done #DevOps2024", EmojiMode::Drop);
+    assert!(tagged.contains(&"devops2024".to_string()));
+    assert!(english("!!! @@@ ###", EmojiMode::Shortcode).is_empty());
+
+    // Emoji
+    let rocket = english("Launch day 🚀", EmojiMode::Shortcode);
+    assert!(rocket.contains(&"emoji:rocket".to_string()));
+    assert!(rocket.contains(&"launch".to_string()));
+    assert!(english("Launch day 🚀", EmojiMode::Keep).contains(&"🚀".to_string()));
+    assert!(english("Launch day 🚀", EmojiMode::Drop).iter().all(|c| !c.contains("emoji") && c != "🚀"));
+    assert_eq!(tokenize_to_cues("🚀"), vec!["emoji:rocket".to_string()]);
+
+    // Skin tones and text-style variants are the same emoji
+    assert_eq!(english("👍🏽", EmojiMode::Shortcode), english("👍", EmojiMode::Shortcode));
+    assert_eq!(english("❤", EmojiMode::Keep), english("❤️", EmojiMode::Keep));
+    assert!(english("© 2024", EmojiMode::Shortcode).is_empty());
+}
+
+#[test]
+fn test_ascii_tokenizer_keeps_earlier_cues() {
+    use cuemap::multilingual::TextLanguage;
+    let ascii = |text: &str| tokenize_to_cues_by(text, Language::Default, TextLanguage::English, Tokenizer::Ascii, EmojiMode::Shortcode);
+
+    // Accented words are cut at the accent, as projects tokenized before the Unicode rules expect
+    assert!(ascii("Naïve café owners").contains(&stem_word("caf")));
+    assert!(ascii("Launch day 🚀").iter().all(|c| !c.starts_with("emoji:")));
+    assert!(!ascii("Rollout done #DevOps2024").contains(&"devops2024".to_string()));
+    assert_eq!(ascii("The quick brown fox"), tokenize_to_cues("The quick brown fox"));
+}
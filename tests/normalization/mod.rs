@@ -1,3 +1,4 @@
+use cuemap::multilingual::TextLanguage;
 use cuemap::normalization::*;
 
 #[test]
@@ -19,6 +20,7 @@ fn test_rewrite_rules() {
                 replace: "service:$1".to_string(),
             },
         ],
+        ..NormalizationConfig::default()
     };
 
     let (normalized, trace) = normalize_cue("Payments-Service", &config);
@@ -43,6 +45,7 @@ fn test_rewrite_chaining() {
                 replace: "tag:$1".to_string(),
             },
         ],
+        ..NormalizationConfig::default()
     };
 
     // Input: "My-Value"
@@ -64,3 +67,58 @@ fn test_prefix_deduplication() {
     let (normalized2, _) = normalize_cue("topic:payments:payments", &config);
     assert_eq!(normalized2, "topic:payments");
 }
+
+#[test]
+fn test_unicode_and_accent_folding() {
+    let config = NormalizationConfig { unicode_nfkc: true, strip_accents: true, ..NormalizationConfig::default() };
+    let (normalized, trace) = normalize_cue("Ｃａｆé", &config);
+    assert_eq!(normalized, "cafe");
+    assert_eq!(trace.applied_rules, vec!["nfkc", "strip_accents"]);
+
+    assert_eq!(normalize_cue("topic:Crème Brûlée", &config).0, "topic:creme brulee");
+    assert_eq!(normalize_cue("café", &NormalizationConfig::default()).0, "café", "folding is opt-in");
+}
+
+#[test]
+fn test_number_canonicalization() {
+    let config = NormalizationConfig { canonicalize_numbers: true, ..NormalizationConfig::default() };
+    assert_eq!(normalize_cue("count:1,000", &config).0, "count:1000");
+    assert_eq!(normalize_cue("007", &config).0, "7");
+    assert_eq!(normalize_cue("price:0042.50", &config).0, "price:42.50");
+    assert_eq!(normalize_cue("python:3.10", &config).0, "python:3.10");
+    // Not numbers as a whole: left alone
+    assert_eq!(normalize_cue("date:2024-01-05", &config).0, "date:2024-01-05");
+    assert_eq!(normalize_cue("code:12,34", &config).0, "code:12,34");
+    assert_eq!(normalize_cue("v1.2.3", &config).0, "v1.2.3");
+}
+
+#[test]
+fn test_stemming_keeps_namespace_and_verbatim_cues() {
+    let config = NormalizationConfig { stemming: true, ..NormalizationConfig::default() };
+    let spanish = TextLanguage::Spanish;
+    let (normalized, trace) = normalize_cue_in("Service:Pagos Rápidos", &config, spanish);
+    assert_eq!(normalized, format!("service:{} {}", spanish.stem("pagos"), spanish.stem("rápidos")));
+    assert_eq!(trace.applied_rules, vec!["stem"]);
+    assert_eq!(normalize_cue_in("pagos", &config, spanish).0, normalize_cue_in("pago", &config, spanish).0);
+
+    // Identifier namespaces are only trimmed and lowercased
+    assert_eq!(normalize_cue_in("path:Src/Pagos.rs", &config, spanish).0, "path:src/pagos.rs");
+}
+
+#[test]
+fn test_config_validation() {
+    let mut config = NormalizationConfig {
+        verbatim_namespaces: vec![" Path: ".to_string(), "path".to_string(), "".to_string()],
+        ..NormalizationConfig::default()
+    };
+    config.validate().unwrap();
+    assert_eq!(config.verbatim_namespaces, vec!["path"]);
+
+    config.rewrite_rules.push(RewriteRule { name: "broken".to_string(), pattern: "(".to_string(), replace: String::new() });
+    assert!(config.validate().is_err());
+
+    // Partial configs keep the defaults of the fields they leave out
+    let parsed: NormalizationConfig = serde_json::from_str(r#"{"stemming": true}"#).unwrap();
+    assert!(parsed.lowercase && parsed.trim && parsed.stemming);
+    assert!(parsed.verbatim_namespaces.contains(&"path".to_string()));
+}
@@ -0,0 +1,194 @@
+#[cfg(test)]
+mod tests {
+    use cuemap::agent::chunker::ChunkingConfig;
+    use cuemap::agent::filter::IngestFilterConfig;
+    use cuemap::aggregation::CountRequest;
+    use cuemap::api::*;
+    use cuemap::config::{EmbeddingConfig, LlmConfig, RerankConfig};
+    use cuemap::engine::MetadataFilter;
+    use cuemap::heatmap::HeatmapUpdateRequest;
+    use cuemap::lexicon_quality::LexiconPruneRequest;
+    use cuemap::maintenance::{DecayRequest, PruneRequest};
+    use cuemap::multi_tenant::ProjectQuota;
+    use cuemap::normalization::{NormalizationConfig, RewriteRule};
+    use cuemap::openapi::{self, Body, OPERATIONS};
+    use cuemap::scheduler::ProjectSchedule;
+    use cuemap::taxonomy::Taxonomy;
+    use serde::{de::DeserializeOwned, Serialize};
+    use serde_json::{json, Value};
+    use std::collections::BTreeSet;
+
+    /// (method, path) of every route registered in `api::routes`
+    fn registered_routes() -> BTreeSet<(String, String)> {
+        let source = include_str!("../src/api.rs");
+        let route = regex::Regex::new(r#"\.route\("([^"]+)",\s*(.+)\)\s*$"#).unwrap();
+        let method = regex::Regex::new(r"\b(get|post|put|patch|delete)\(\w+\)").unwrap();
+        let mut routes = BTreeSet::new();
+        for line in source.lines() {
+            if let Some(caps) = route.captures(line.trim()) {
+                for m in method.captures_iter(&caps[2]) {
+                    routes.insert((m[1].to_string(), caps[1].to_string()));
+                }
+            }
+        }
+        routes
+    }
+
+    #[test]
+    fn test_every_route_is_documented() {
+        let routes = registered_routes();
+        assert!(routes.len() > 50, "route parsing broke: {} routes", routes.len());
+
+        let documented: BTreeSet<(String, String)> = OPERATIONS
+            .iter()
+            .map(|op| (op.method.to_string(), op.path.to_string()))
+            .collect();
+        let missing: Vec<_> = routes.difference(&documented).collect();
+        let stale: Vec<_> = documented.difference(&routes).collect();
+        assert!(missing.is_empty(), "routes missing from openapi::OPERATIONS: {:?}", missing);
+        assert!(stale.is_empty(), "documented routes that no longer exist: {:?}", stale);
+
+        let ids: BTreeSet<String> = OPERATIONS.iter().map(openapi::operation_id).collect();
+        assert_eq!(ids.len(), OPERATIONS.len(), "operation ids must be unique");
+    }
+
+    #[test]
+    fn test_spec_references_resolve() {
+        let spec = openapi::spec();
+        let schemas = spec["components"]["schemas"].as_object().unwrap();
+        for op in OPERATIONS {
+            if let Body::Json(name) = op.body {
+                assert!(schemas.contains_key(name), "{} {} uses unknown schema {}", op.method, op.path, name);
+            }
+        }
+        assert_eq!(spec["paths"]["/memories/{id}"]["get"]["parameters"][0]["name"], "id");
+        assert!(spec["paths"]["/openapi.json"]["get"].is_object());
+    }
+
+    /// A value for every property of `schema`, non-null even where nullable
+    fn example(schema: &Value, schemas: &serde_json::Map<String, Value>) -> Value {
+        if let Some(name) = schema["$ref"].as_str() {
+            let name = name.trim_start_matches("#/components/schemas/");
+            return example(&schemas[name], schemas);
+        }
+        if let Some(values) = schema["enum"].as_array() {
+            return values[0].clone();
+        }
+        match schema["type"].as_str() {
+            Some("string") => json!("x"),
+            Some("integer") => json!(1),
+            Some("number") => json!(0.5),
+            Some("boolean") => json!(true),
+            Some("array") => json!([example(&schema["items"], schemas)]),
+            Some("object") => {
+                let mut object = serde_json::Map::new();
+                if let Some(properties) = schema["properties"].as_object() {
+                    for (key, property) in properties {
+                        object.insert(key.clone(), example(property, schemas));
+                    }
+                }
+                Value::Object(object)
+            }
+            _ => json!("x"),
+        }
+    }
+
+    /// The schema lists exactly the struct's fields, and `required` exactly the
+    /// fields without a serde default
+    fn check_schema<T: DeserializeOwned + Serialize>(name: &str) {
+        let spec = openapi::spec();
+        let schemas = spec["components"]["schemas"].as_object().unwrap();
+        let schema = &schemas[name];
+        let properties: BTreeSet<String> = schema["properties"].as_object().unwrap().keys().cloned().collect();
+        let required: BTreeSet<String> = schema["required"]
+            .as_array()
+            .map(|r| r.iter().map(|v| v.as_str().unwrap().to_string()).collect())
+            .unwrap_or_default();
+
+        let full = example(schema, schemas);
+        let parsed: T = serde_json::from_value(full.clone())
+            .unwrap_or_else(|e| panic!("{}: example {} does not parse: {}", name, full, e));
+        let fields: BTreeSet<String> = serde_json::to_value(parsed).unwrap().as_object().unwrap().keys().cloned().collect();
+        assert_eq!(fields, properties, "{}: schema properties differ from the struct's fields", name);
+
+        for field in &properties {
+            let mut partial = full.clone();
+            partial.as_object_mut().unwrap().remove(field);
+            let parses = serde_json::from_value::<T>(partial).is_ok();
+            assert_eq!(parses, !required.contains(field), "{}: `{}` required-ness is wrong", name, field);
+        }
+    }
+
+    macro_rules! check_schemas {
+        ($($ty:ident),* $(,)?) => {
+            $( check_schema::<$ty>(stringify!($ty)); )*
+            let checked: BTreeSet<&str> = [$(stringify!($ty)),*].into_iter().collect();
+            let documented: BTreeSet<String> = openapi::request_schemas().keys().cloned().collect();
+            for name in &documented {
+                assert!(checked.contains(name.as_str()), "schema {} has no struct check", name);
+            }
+        };
+    }
+
+    #[test]
+    fn test_request_schemas_match_structs() {
+        check_schemas!(
+            AddMemoryRequest,
+            MetadataFilter,
+            RecallRequest,
+            RecallGroundedRequest,
+            AnswerRequest,
+            RecallWebRequest,
+            RecallFeedbackRequest,
+            ReinforceRequest,
+            DemoteRequest,
+            SetEmbeddingRequest,
+            PinMemoryRequest,
+            RollbackRequest,
+            MemoryLinkRequest,
+            CreateSessionRequest,
+            ContextExpandRequest,
+            CreateProjectRequest,
+            SetWatchDirRequest,
+            IngestFilterConfig,
+            RestoreGenerationRequest,
+            SetSnapshotEncryptionRequest,
+            ProjectSchedule,
+            ProjectQuota,
+            EmbeddingConfig,
+            LlmConfig,
+            RerankConfig,
+            Taxonomy,
+            RewriteRule,
+            NormalizationConfig,
+            ValidateCuesRequest,
+            AddAcronymRequest,
+            ProjectLanguageRequest,
+            ProjectRecallProfileRequest,
+            ChunkingConfig,
+            AddAliasRequest,
+            MergeAliasRequest,
+            ApproveAliasRequest,
+            LexiconPruneRequest,
+            HeatmapUpdateRequest,
+            DecayRequest,
+            PruneRequest,
+            CountRequest,
+            MemoryExportRequest,
+            WireLexiconRequest,
+            IngestUrlRequest,
+            IngestContentRequest,
+            IngestPreviewRequest,
+            ConnectorRequest,
+            BackupRequest,
+        );
+    }
+
+    #[test]
+    fn test_request_defaults_match_empty_body() {
+        let recall: RecallRequest = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(serde_json::to_value(RecallRequest::default()).unwrap(), serde_json::to_value(recall).unwrap());
+        let add: AddMemoryRequest = serde_json::from_value(json!({"content": "", "cues": []})).unwrap();
+        assert_eq!(serde_json::to_value(AddMemoryRequest::default()).unwrap(), serde_json::to_value(add).unwrap());
+    }
+}
@@ -0,0 +1,64 @@
+use cuemap::payload_store::*;
+use std::sync::Arc;
+use tempfile::tempdir;
+
+#[test]
+fn test_payload_store_reads_through_cache() {
+    let dir = tempdir().unwrap();
+    let store = PayloadStore::create(dir.path().join("p.log"), 1).unwrap();
+    let a = store.put(b"alpha").unwrap();
+    let b = store.put(b"beta").unwrap();
+    assert_eq!((a, b, store.file_bytes()), (0, 5, 9));
+
+    assert_eq!(store.get(b, 4).unwrap().as_slice(), b"beta");
+    assert_eq!(store.get(b, 4).unwrap().as_slice(), b"beta");
+    // A one-entry cache evicts `b` when `a` is read
+    assert_eq!(store.get(a, 5).unwrap().as_slice(), b"alpha");
+    let stats = store.stats();
+    assert_eq!((stats["cache_hits"].as_u64(), stats["cache_misses"].as_u64()), (Some(1), Some(2)));
+
+    // Creating the store again starts an empty log
+    let fresh = PayloadStore::create(dir.path().join("p.log"), 1).unwrap();
+    assert_eq!(fresh.file_bytes(), 0);
+}
+
+#[test]
+fn test_stored_payload_serializes_like_inline_bytes() {
+    let dir = tempdir().unwrap();
+    let store = Arc::new(PayloadStore::create(dir.path().join("p.log"), 8).unwrap());
+    let offset = store.put(b"payload").unwrap();
+    let stored = Payload::Stored { store, offset, len: 7 };
+    let inline = Payload::from(b"payload".to_vec());
+
+    assert_eq!(bincode::serialize(&stored).unwrap(), bincode::serialize(&inline).unwrap());
+    assert_eq!(bincode::serialize(&inline).unwrap(), bincode::serialize(&b"payload".to_vec()).unwrap());
+    let restored: Payload = bincode::deserialize(&bincode::serialize(&stored).unwrap()).unwrap();
+    assert!(!restored.is_stored());
+    assert_eq!((restored.bytes().unwrap().as_ref(), stored.resident_bytes()), (&b"payload"[..], 0));
+}
+
+#[test]
+fn test_loading_a_snapshot_streams_payloads_into_the_store() {
+    use cuemap::engine::CueMapEngine;
+    use cuemap::persistence::{PersistenceManager, SnapshotCodec, SnapshotCompression};
+    use cuemap::structures::MainStats;
+
+    let dir = tempdir().unwrap();
+    let engine = CueMapEngine::<MainStats>::new();
+    for i in 0..50 {
+        engine.add_memory(format!("payload {}", i), vec!["shared".to_string()], None, MainStats::default(), true);
+    }
+    for (name, codec) in [("raw.bin", SnapshotCodec::None), ("zstd.bin", SnapshotCodec::Zstd), ("lz4.bin", SnapshotCodec::Lz4)] {
+        let path = dir.path().join(name);
+        PersistenceManager::save_to_path_with_options(&engine, &path, None, SnapshotCompression::new(codec, 3)).unwrap();
+
+        let store = Arc::new(PayloadStore::create(dir.path().join(format!("{}.log", name)), 4).unwrap());
+        let (memories, cue_index) = PersistenceManager::load_from_path_into_store::<MainStats>(&path, &[], Some(&store)).unwrap();
+        assert_eq!((memories.len(), cue_index.get("shared").unwrap().len()), (50, 50));
+        assert!(memories.iter().all(|m| m.content.is_stored()));
+        assert!(store.file_bytes() > 0);
+        let id = engine.get_memories().iter().next().unwrap().key().clone();
+        let expected = engine.get_memory(&id).unwrap().access_content(None).unwrap();
+        assert_eq!(memories.get(&id).unwrap().access_content(None).unwrap(), expected);
+    }
+}
@@ -47,3 +47,141 @@ fn test_context_isolation() {
     // Verify they are different objects in memory (Arc pointers)
     assert!(!Arc::ptr_eq(&ctx1, &ctx2));
 }
+
+#[test]
+fn test_recall_feedback_reinforces_useful_and_penalizes_rest() {
+    let store = ProjectStore::new();
+    let ctx = store.get_or_create("feedback");
+    let good = ctx.main.add_memory("good answer".to_string(), vec!["billing".to_string()], None, MainStats::default(), true);
+    let bad = ctx.main.add_memory("stale answer".to_string(), vec!["billing".to_string()], None, MainStats::default(), true);
+
+    let recall_id = ctx.log_recall(vec![good.clone(), bad.clone()], vec!["billing".to_string()]);
+    let record = ctx.take_recall(&recall_id).unwrap();
+    let (reinforced, penalized) = ctx.main.apply_recall_feedback(&record.memory_ids, &record.cues, &[good.clone(), "unrelated".to_string()], 0.1);
+    assert_eq!(reinforced, vec![good.clone()]);
+    assert_eq!(penalized, vec![bad.clone()]);
+
+    let good_stats = ctx.main.get_memory(&good).unwrap().stats;
+    let bad_stats = ctx.main.get_memory(&bad).unwrap().stats;
+    assert_eq!(good_stats.reinforcement_count, 1);
+    assert!(bad_stats.dynamic_salience < 0.0);
+    assert!(good_stats.salience() > bad_stats.salience());
+
+    // Feedback is accepted once per recall, but the recall still resolves citations
+    assert!(ctx.take_recall(&recall_id).is_none());
+    assert_eq!(ctx.get_recall(&recall_id).unwrap().memory_ids, vec![good, bad]);
+    assert!(ctx.get_recall("unknown").is_none());
+}
+
+#[test]
+fn test_query_rewrite_acronyms_and_spelling() {
+    use cuemap::query_rewrite::{extract_acronyms, rewrite_query};
+
+    let text = "We track each Service Level Objective (SLO) on Kubernetes (K8s). \
+                CI (continuous integration) runs on every push; see the FAQ (below).";
+    assert_eq!(
+        extract_acronyms(text),
+        vec![
+            ("slo".to_string(), "service level objective".to_string()),
+            ("k8s".to_string(), "kubernetes".to_string()),
+            ("ci".to_string(), "continuous integration".to_string()),
+        ]
+    );
+    assert!(extract_acronyms("Restart it (again) after (Step 2)").is_empty());
+
+    let store = ProjectStore::new();
+    let ctx = store.get_or_create("rewrite");
+    let content = "Restart the payment service after rotating kubernetes secrets";
+    ctx.main.add_memory(content.to_string(), ctx.tokenize(content), None, MainStats::default(), false);
+    assert_eq!(ctx.learn_acronyms(text), 3);
+    assert_eq!(ctx.learn_acronyms(text), 0);
+
+    let rewrite = rewrite_query(&ctx, "k8s paymnet secrets");
+    assert!(rewrite.changed());
+    assert_eq!(rewrite.rewritten, "kubernetes payment secrets");
+    assert_eq!((rewrite.acronyms[0].acronym.as_str(), rewrite.acronyms[0].expansion.as_str()), ("k8s", "kubernetes"));
+    assert_eq!(rewrite.corrections.len(), 1);
+    assert_eq!((rewrite.corrections[0].word.as_str(), rewrite.corrections[0].distance), ("paymnet", 1));
+
+    // Known words and stopwords are left alone
+    assert!(!rewrite_query(&ctx, "restart the payment service").changed());
+}
+
+#[test]
+fn test_proposed_alias_approve_and_reject() {
+    let store = ProjectStore::new();
+    let ctx = store.get_or_create("aliases");
+    ctx.main.add_memory("postgres outage".to_string(), vec!["postgres".to_string()], None, MainStats::default(), false);
+    ctx.main.add_memory("pg failover".to_string(), vec!["pg".to_string()], None, MainStats::default(), false);
+
+    // As registered by the propose_aliases job
+    let propose = |id: &str, from: &str, to: &str| {
+        let content = serde_json::json!({
+            "from": from, "to": to, "downweight": 0.7, "status": "proposed", "reason": "overlap_analysis"
+        });
+        let cues = vec!["type:alias".to_string(), "status:proposed".to_string(), format!("alias_id:{}", id)];
+        ctx.aliases.upsert_memory_with_id(id.to_string(), content.to_string(), cues, None, Some(MainStats::default()), false, false);
+    };
+    propose("a1", "pg", "postgres");
+    propose("a2", "postgres", "pg");
+    assert_eq!(ctx.proposed_aliases().len(), 2);
+
+    // Proposals don't expand recalls until approved
+    let pg = vec!["pg".to_string()];
+    assert_eq!(ctx.expand_query_cues(pg.clone(), &pg), vec![("pg".to_string(), 1.0)]);
+
+    assert!(ctx.approve_alias("a1", Some(1.5)).is_err());
+    let approved = ctx.approve_alias("a1", Some(0.9)).unwrap();
+    assert_eq!(approved["status"], "active");
+    assert_eq!(approved["downweight"], 0.9);
+    assert!(ctx.expand_query_cues(pg.clone(), &pg).contains(&("postgres".to_string(), 0.9)));
+
+    let rejected = ctx.reject_alias("a2").unwrap();
+    assert_eq!(rejected["status"], "rejected");
+    assert!(ctx.proposed_aliases().is_empty());
+    // The tombstone keeps the id cue the job checks before proposing
+    assert!(ctx.aliases.get_cue_index().contains_key("alias_id:a2"));
+    let postgres = vec!["postgres".to_string()];
+    assert_eq!(ctx.expand_query_cues(postgres.clone(), &postgres), vec![("postgres".to_string(), 1.0)]);
+
+    // Reviewed aliases can't be reviewed again
+    assert!(ctx.approve_alias("a2", None).is_err());
+    assert!(ctx.reject_alias("missing").is_err());
+}
+
+#[test]
+fn test_lexicon_quality_scoring_and_prune() {
+    use cuemap::lexicon_quality::{prune_lexicon, record_expansions, LexiconPruneRequest};
+    use cuemap::structures::LexiconStats;
+
+    let store = ProjectStore::new();
+    let ctx = store.get_or_create("lexicon");
+    let memory = ctx.main.add_memory("card payment failed".to_string(), vec!["payment".to_string()], None, MainStats::default(), true);
+    let good = ctx.lexicon.add_memory("payment".to_string(), vec!["pay".to_string()], None, LexiconStats::default(), true);
+    let bad = ctx.lexicon.add_memory("paycheck".to_string(), vec!["pay".to_string()], None, LexiconStats::default(), true);
+
+    for _ in 0..6 {
+        record_expansions(&ctx, &[good.clone(), bad.clone()], std::slice::from_ref(&memory));
+    }
+    assert_eq!(ctx.lexicon_quality.get(&good).unwrap().hits, 6.0);
+    assert_eq!(ctx.lexicon_quality.get(&bad).unwrap().false_expansions, 6.0);
+    assert!(ctx.lexicon_quality.get(&bad).unwrap().score() < 0.2);
+
+    // A dry run reports the candidate and changes nothing
+    let dry = prune_lexicon(&ctx, &LexiconPruneRequest { dry_run: true, ..Default::default() });
+    assert_eq!((dry.evaluated, dry.pruned), (2, 1));
+    assert_eq!(dry.candidates[0].id, bad);
+    assert_eq!(dry.candidates[0].reason, "low_score");
+    assert_eq!(ctx.lexicon.total_memories(), 2);
+    assert_eq!(ctx.lexicon_quality.get(&good).unwrap().hits, 6.0);
+
+    let report = prune_lexicon(&ctx, &LexiconPruneRequest::default());
+    assert_eq!(report.pruned, 1);
+    assert!(ctx.lexicon.get_memory(&bad).is_none());
+    assert!(!ctx.lexicon_quality.contains_key(&bad));
+    // Kept entries have their tallies decayed
+    assert!((ctx.lexicon_quality.get(&good).unwrap().hits - 5.4).abs() < 1e-9);
+
+    assert!(LexiconPruneRequest { decay: 0.0, ..Default::default() }.validate().is_err());
+    assert!(LexiconPruneRequest { min_score: 1.5, ..Default::default() }.validate().is_err());
+}
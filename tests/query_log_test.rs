@@ -0,0 +1,57 @@
+use cuemap::query_log::{self, LoggedQuery, QueryLog};
+use cuemap::Client;
+
+fn logged(project: &str, cues: &[&str], result_count: usize) -> LoggedQuery {
+    LoggedQuery {
+        limit: 5,
+        result_count,
+        latency_ms: 2.0,
+        ..LoggedQuery::new(project.to_string(), cues.iter().map(|c| c.to_string()).collect())
+    }
+}
+
+#[test]
+fn test_query_log_roundtrip_and_rotation() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(query_log::QUERY_LOG_FILE);
+
+    let log = QueryLog::open(&path, 0, 1.0);
+    let queries = vec![logged("alpha", &["service:payments"], 1), logged("beta", &["error:timeout"], 0)];
+    for query in &queries {
+        log.record(query);
+    }
+    assert_eq!(query_log::read_query_log(&path).unwrap(), queries);
+
+    // Sampled out entirely
+    let off = QueryLog::open(dir.path().join("off.jsonl"), 0, 0.0);
+    off.record(&logged("alpha", &["a"], 1));
+    assert!(query_log::read_query_log(&dir.path().join("off.jsonl")).unwrap().is_empty());
+
+    // A cap smaller than two lines rotates on the second write
+    let small = dir.path().join("small.jsonl");
+    let line_len = serde_json::to_string(&logged("alpha", &["a"], 1)).unwrap().len() as u64 + 1;
+    let log = QueryLog::open(&small, line_len + 10, 1.0);
+    log.record(&logged("alpha", &["a"], 1));
+    log.record(&logged("alpha", &["b"], 1));
+    assert_eq!(query_log::read_query_log(&small).unwrap()[0].cues, vec!["b"]);
+    assert_eq!(query_log::read_query_log(&dir.path().join("small.jsonl.1")).unwrap()[0].cues, vec!["a"]);
+}
+
+#[tokio::test]
+async fn test_replay_against_client() {
+    let client = Client::builder().background_jobs(false).build().unwrap();
+    client.add_memory("replay", "Payments service timed out", vec!["service:payments".to_string()], None).await.unwrap();
+
+    let queries = vec![
+        logged("replay", &["service:payments"], 1),
+        logged("replay", &["error:missing"], 2), // found results when logged, none now
+        logged("x", &["service:payments"], 1),   // invalid project id
+    ];
+    let summary = query_log::replay(&client, &queries).await;
+    assert_eq!(summary.queries, 3);
+    assert_eq!(summary.failed, 1);
+    assert_eq!(summary.errors.len(), 1);
+    assert_eq!(summary.now_empty, 1);
+    assert_eq!(summary.mean_replay_results, 0.5);
+    assert_eq!(summary.logged_latency.p99, 2.0);
+}
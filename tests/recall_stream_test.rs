@@ -0,0 +1,74 @@
+use axum::body::Body;
+use axum::http::Request;
+use axum::Router;
+use cuemap::agent::manager::AgentManager;
+use cuemap::auth::AuthConfig;
+use cuemap::config::{CueGenStrategy, LlmConfig, TuningConfig};
+use cuemap::jobs::JobQueue;
+use cuemap::metrics::MetricsCollector;
+use cuemap::multi_tenant::MultiTenantEngine;
+use cuemap::semantic::SemanticEngine;
+use cuemap::structures::MainStats;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+fn app(engine: Arc<MultiTenantEngine>) -> Router {
+    let job_queue = Arc::new(JobQueue::new(engine.clone(), None, true));
+    let agents = Arc::new(AgentManager::new(job_queue.clone(), engine.clone()));
+    cuemap::api::routes(engine, job_queue, Arc::new(MetricsCollector::new()), AuthConfig::new(), false, None, None, agents)
+}
+
+async fn post(app: &Router, path: &str, body: serde_json::Value) -> String {
+    let req = Request::post(path)
+        .header("X-Project-ID", "stream")
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(req).await.unwrap();
+    assert!(response.status().is_success(), "{}", response.status());
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn test_recall_stream_matches_recall() {
+    let dir = tempfile::tempdir().unwrap();
+    let engine = Arc::new(MultiTenantEngine::with_snapshots_dir(
+        dir.path(),
+        CueGenStrategy::Default,
+        SemanticEngine::new(None),
+        TuningConfig::default(),
+        LlmConfig::default(),
+    ));
+    let ctx = engine.get_or_create_project("stream".to_string()).unwrap();
+    ctx.main.add_memory("deploy runbook".to_string(), vec!["deploy".to_string(), "ops".to_string()], None, MainStats::default(), false);
+    ctx.main.add_memory("deploy rollback".to_string(), vec!["deploy".to_string(), "rollback".to_string()], None, MainStats::default(), false);
+    ctx.main.add_memory("ops pager rota".to_string(), vec!["ops".to_string(), "pager".to_string()], None, MainStats::default(), false);
+    let app = app(engine);
+
+    let request = serde_json::json!({"cues": ["deploy"], "depth": 2, "limit": 10, "explain": true, "auto_reinforce": false});
+    let recall: serde_json::Value = serde_json::from_str(&post(&app, "/recall", request.clone()).await).unwrap();
+    let mut expected: Vec<&str> = recall["results"].as_array().unwrap().iter().map(|r| r["memory_id"].as_str().unwrap()).collect();
+    assert!(expected.len() >= 2);
+
+    // One `result` event per result (in hop order rather than by score), then a single `done`
+    let stream = post(&app, "/recall/stream", request).await;
+    let events: Vec<(&str, serde_json::Value)> = stream
+        .split("\n\n")
+        .filter_map(|block| {
+            let event = block.lines().find_map(|l| l.strip_prefix("event:"))?.trim();
+            let data = block.lines().find_map(|l| l.strip_prefix("data:"))?.trim();
+            Some((event, serde_json::from_str(data).unwrap()))
+        })
+        .collect();
+    let mut streamed: Vec<&str> = events.iter().filter(|(e, _)| *e == "result").map(|(_, r)| r["memory_id"].as_str().unwrap()).collect();
+    streamed.sort();
+    expected.sort();
+    assert_eq!(streamed, expected);
+
+    let (last, done) = events.last().unwrap();
+    assert_eq!(*last, "done");
+    assert_eq!(events.iter().filter(|(e, _)| *e == "done").count(), 1);
+    assert_eq!(done["count"], expected.len());
+    assert_eq!(done["explain"]["expanded_cues"], recall["explain"]["expanded_cues"]);
+}
@@ -60,6 +60,10 @@ async fn test_recursive_crawl_depth_1() {
         watch_dir: String::new(),
         throttle_ms: 100, // Throttle to be polite
         state_file: None,
+        filter: Default::default(),
+        git: Default::default(),
+        chunking: Default::default(),
+        extract_facts: false,
     };
     
     let mut ingester = Ingester::new(config, job_queue.clone());
@@ -132,6 +136,10 @@ async fn test_job_phase_ordering() {
         watch_dir: String::new(),
         throttle_ms: 0,
         state_file: None,
+        filter: Default::default(),
+        git: Default::default(),
+        chunking: Default::default(),
+        extract_facts: false,
     };
     
     let mut ingester = Ingester::new(config, job_queue.clone());
@@ -0,0 +1,43 @@
+use cuemap::slow_queries::{SlowQuery, SlowQueryLog, StageTimer};
+
+fn query(project: &str, total_ms: f64) -> SlowQuery {
+    SlowQuery { project_id: Some(project.to_string()), ..SlowQuery::new(total_ms, Default::default()) }
+}
+
+#[test]
+fn test_slow_query_ring_buffer() {
+    let log = SlowQueryLog::new(100, 2);
+    log.record(query("alpha", 99.0));
+    assert!(log.recent(None, 10).is_empty());
+
+    log.record(query("alpha", 150.0));
+    log.record(query("beta", 300.0));
+    log.record(SlowQuery { projects: vec!["alpha".to_string(), "gamma".to_string()], ..SlowQuery::new(120.0, Default::default()) });
+
+    // Oldest dropped, newest first
+    let totals: Vec<f64> = log.recent(None, 10).iter().map(|q| q.total_ms).collect();
+    assert_eq!(totals, vec![120.0, 300.0]);
+    assert_eq!(log.recent(None, 1).len(), 1);
+
+    // Cross-project recalls count for each of their projects
+    assert_eq!(log.recent(Some("alpha"), 10).len(), 1);
+    assert_eq!(log.recent(Some("beta"), 10)[0].total_ms, 300.0);
+
+    let off = SlowQueryLog::new(0, 0);
+    off.record(query("alpha", 1000.0));
+    assert!(off.recent(None, 10).is_empty());
+}
+
+#[test]
+fn test_stage_timer_keeps_stage_order() {
+    let mut timer = StageTimer::new();
+    timer.lap("tokenize");
+    timer.lap("search");
+    timer.lap("tokenize");
+    let stages = timer.into_stages();
+    assert_eq!(stages.keys().copied().collect::<Vec<_>>(), vec!["tokenize", "search"]);
+    assert!(stages.values().all(|ms| *ms >= 0.0));
+
+    let clipped = SlowQuery::clip_query_text(&"é".repeat(300));
+    assert_eq!(clipped.chars().count(), 256);
+}
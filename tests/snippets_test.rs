@@ -0,0 +1,48 @@
+use cuemap::multilingual::TextLanguage;
+use cuemap::snippets::{snippets, SnippetTerms};
+
+const CONTENT: &str = "The payments service retries failed charges. After three retries it gives up and alerts the on-call engineer. Unrelated text follows here.";
+
+fn highlighted(text: &str, highlights: &[(usize, usize)]) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    highlights.iter().map(|&(s, e)| chars[s..e].iter().collect()).collect()
+}
+
+#[test]
+fn test_snippets_highlight_matched_words() {
+    let terms = SnippetTerms::from_cues(["service:payments", "retries"], TextLanguage::English);
+    let found = snippets(CONTENT, &terms, 20, 3);
+    assert_eq!(found.len(), 2);
+
+    // Nearby matches share a snippet, cut at word boundaries
+    assert_eq!(found[0].text, "The payments service retries failed charges.");
+    assert_eq!((found[0].start, found[0].end), (0, 44));
+    assert_eq!(highlighted(&found[0].text, &found[0].highlights), vec!["payments", "retries"]);
+
+    assert_eq!(found[1].text, "After three retries it gives up and");
+    assert_eq!(&CONTENT[found[1].start..found[1].end], found[1].text);
+    assert_eq!(highlighted(&found[1].text, &found[1].highlights), vec!["retries"]);
+}
+
+#[test]
+fn test_snippets_prefer_windows_with_most_matches() {
+    let terms = SnippetTerms::from_cues(["engineer", "retries", "charges"], TextLanguage::English);
+    assert_eq!(snippets(CONTENT, &terms, 15, 3).len(), 2);
+
+    let found = snippets(CONTENT, &terms, 15, 1);
+    assert_eq!(found.len(), 1);
+    assert_eq!(highlighted(&found[0].text, &found[0].highlights), vec!["retries", "charges", "retries"]);
+
+    assert!(snippets(CONTENT, &terms, 15, 0).is_empty());
+    assert!(snippets(CONTENT, &SnippetTerms::from_cues(["invoice"], TextLanguage::English), 15, 3).is_empty());
+}
+
+#[test]
+fn test_snippet_offsets_count_characters() {
+    let content = "Café über alles: the refund arrived";
+    let terms = SnippetTerms::from_cues(["refund"], TextLanguage::English);
+    let found = snippets(content, &terms, 100, 3);
+    assert_eq!(highlighted(&found[0].text, &found[0].highlights), vec!["refund"]);
+    assert_eq!(found[0].highlights[0].0, 21);
+    assert_eq!(found[0].end, content.chars().count());
+}
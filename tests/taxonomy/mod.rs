@@ -38,6 +38,7 @@ fn test_value_validation() {
         allowed_keys: vec!["status".to_string(), "user".to_string()],
         allowed_values,
         allowed_value_prefixes,
+        ..Default::default()
     };
 
     let cues = vec![
@@ -53,3 +54,38 @@ fn test_value_validation() {
     assert_eq!(report.rejected[0].code, "unknown_value"); // status:unknown
     assert_eq!(report.rejected[1].code, "unknown_value"); // user:admin
 }
+
+#[test]
+fn test_length_and_forbidden_patterns() {
+    let taxonomy = Taxonomy {
+        forbidden_patterns: vec!["secret:*".to_string(), "*password*".to_string()],
+        max_cue_length: Some(12),
+        ..Default::default()
+    };
+    let cues = vec![
+        "status:active".to_string(),  // 13 characters
+        "secret:key".to_string(),
+        "mypassword1".to_string(),
+        "topic:rust".to_string(),
+        "secrets".to_string(),
+    ];
+    let report = validate_cues(cues, &taxonomy);
+
+    assert_eq!(report.accepted, vec!["topic:rust", "secrets"]);
+    assert_eq!(report.rejected.len(), 3);
+    assert_eq!(report.rejected[0].code, "too_long");
+    assert_eq!(report.rejected[1].code, "forbidden_pattern");
+    assert_eq!(report.rejected[2].code, "forbidden_pattern");
+}
+
+#[test]
+fn test_taxonomy_validate() {
+    assert!(Taxonomy::default().validate().is_ok());
+    assert!(Taxonomy { max_cue_length: Some(0), ..Default::default() }.validate().is_err());
+    assert!(Taxonomy { forbidden_patterns: vec!["**".to_string()], ..Default::default() }.validate().is_err());
+    assert!(Taxonomy { forbidden_patterns: vec!["x".repeat(MAX_PATTERN_LEN + 1)], ..Default::default() }.validate().is_err());
+
+    let mut allowed_values = HashMap::new();
+    allowed_values.insert("a:b".to_string(), vec!["c".to_string()]);
+    assert!(Taxonomy { allowed_values, ..Default::default() }.validate().is_err());
+}
@@ -0,0 +1,55 @@
+use axum::body::Body;
+use axum::http::Request;
+use axum::routing::get;
+use axum::Router;
+use cuemap::config::{LogFormat, ServerConfig, TelemetryConfig};
+use cuemap::telemetry::{self, REQUEST_ID_HEADER};
+use tower::ServiceExt;
+use tracing_subscriber::Registry;
+
+#[test]
+fn test_telemetry_config_defaults_and_validation() {
+    let config: ServerConfig = toml::from_str("[telemetry]\notlp_endpoint = \"http://localhost:4317\"\n").unwrap();
+    assert_eq!(config.telemetry.otlp_endpoint.as_deref(), Some("http://localhost:4317"));
+    assert_eq!(config.telemetry.service_name, "cuemap");
+    assert_eq!(config.telemetry.sample_ratio, 1.0);
+    assert_eq!(config.telemetry.metrics_interval_seconds, 60);
+
+    // No endpoint: export stays off, whatever else is set
+    let off = TelemetryConfig { sample_ratio: 2.0, ..Default::default() };
+    assert!(telemetry::tracing_layer::<Registry>(&off).unwrap().is_none());
+
+    let bad = TelemetryConfig { otlp_endpoint: Some("http://localhost:4317".to_string()), sample_ratio: 2.0, ..Default::default() };
+    assert!(telemetry::tracing_layer::<Registry>(&bad).err().expect("invalid sample ratio is rejected").contains("sample_ratio"));
+}
+
+#[tokio::test]
+async fn test_request_ids_are_echoed_or_generated() {
+    let app = Router::new()
+        .route("/projects/:id/stats", get(|| async { "ok" }))
+        .layer(axum::middleware::from_fn(telemetry::trace_request));
+    let request_id = |req: Request<Body>| {
+        let app = app.clone();
+        async move {
+            let response = app.oneshot(req).await.unwrap();
+            response.headers()[REQUEST_ID_HEADER].to_str().unwrap().to_string()
+        }
+    };
+
+    let req = Request::get("/projects/demo/stats").header(REQUEST_ID_HEADER, "client-42").body(Body::empty()).unwrap();
+    assert_eq!(request_id(req).await, "client-42");
+
+    // Missing or unsafe ids are replaced with a fresh one
+    let generated = request_id(Request::get("/projects/demo/stats").body(Body::empty()).unwrap()).await;
+    assert_eq!(generated.len(), 32);
+    let req = Request::get("/projects/demo/stats").header(REQUEST_ID_HEADER, "a\"b").body(Body::empty()).unwrap();
+    let replaced = request_id(req).await;
+    assert!(replaced != "a\"b" && replaced != generated);
+}
+
+#[test]
+fn test_log_format_config() {
+    assert_eq!(ServerConfig::default().server.log_format, LogFormat::Text);
+    let config: ServerConfig = toml::from_str("[server]\nport = 8080\nhost = \"0.0.0.0\"\ndata_dir = \"data\"\nlog_level = \"info\"\nlog_format = \"json\"\nread_only = false\n").unwrap();
+    assert_eq!(config.server.log_format, LogFormat::Json);
+}
@@ -0,0 +1,45 @@
+use cuemap::engine::CueMapEngine;
+use cuemap::structures::LexiconStats;
+use cuemap::trending::{self, parse_window};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn entry(engine: &CueMapEngine<LexiconStats>, cue: &str, stats: LexiconStats) -> String {
+    engine.upsert_memory_with_id(format!("cue:{}", cue), cue.to_string(), vec![cue.to_string()], None, Some(stats), false, false)
+}
+
+#[test]
+fn test_parse_window() {
+    assert_eq!(parse_window("15m"), Ok(15));
+    assert_eq!(parse_window("1h"), Ok(60));
+    assert_eq!(parse_window("30"), Ok(30));
+    assert!(parse_window("2h").is_err());
+    assert!(parse_window("0m").is_err());
+    assert!(parse_window("soon").is_err());
+}
+
+#[test]
+fn test_trending_cues_with_velocity_and_baseline() {
+    let engine = CueMapEngine::<LexiconStats>::new();
+    let today = (SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() / 86400) as u32;
+
+    // Two days of history averaging one reinforcement per minute; today's bucket is not baseline
+    let history = LexiconStats { daily_stats: HashMap::from([(today - 2, 2880), (today, 5000)]), ..Default::default() };
+    let outage = entry(&engine, "outage", history);
+    let deploy = entry(&engine, "deploy", LexiconStats::default());
+    entry(&engine, "idle", LexiconStats::default());
+    engine.reinforce_tiered(&outage, 30);
+    engine.reinforce_tiered(&deploy, 3);
+
+    let trending = engine.trending_cues(15, 10);
+    assert_eq!(trending.iter().map(|t| t.cue.as_str()).collect::<Vec<_>>(), vec!["outage", "deploy"]);
+    assert_eq!(trending[0].count, 30);
+    assert_eq!(trending[0].velocity, 2.0);
+    assert_eq!(trending[0].baseline, 1.0);
+    assert_eq!(trending[0].lift, Some(2.0));
+    assert_eq!((trending[1].baseline, trending[1].lift), (0.0, None));
+
+    assert_eq!(engine.trending_cues(15, 1).len(), 1);
+    assert_eq!(trending::heatmap_lift(9), 1.0);
+    assert_eq!(trending::heatmap_lift(10_000), 2.0);
+}
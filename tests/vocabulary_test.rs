@@ -0,0 +1,70 @@
+use cuemap::projects::ProjectStore;
+use cuemap::structures::LexiconStats;
+use cuemap::vocabulary::*;
+
+#[test]
+fn test_lexicon_yaml_round_trip() {
+    let store = ProjectStore::new();
+    let source = store.get_or_create("source");
+    source.lexicon.upsert_memory_with_id("payment".to_string(), "payment".to_string(), vec!["pay".to_string()], None, Some(LexiconStats::default()), false, false);
+    // Wired entries use another id for the same canonical cue
+    source.lexicon.upsert_memory_with_id("cue:payment".to_string(), "payment".to_string(), vec!["stripe".to_string()], None, Some(LexiconStats::default()), false, false);
+    source.lexicon.upsert_memory_with_id("refund".to_string(), "refund".to_string(), vec!["refund".to_string()], None, Some(LexiconStats::default()), false, false);
+
+    let exported = export_lexicon(&source);
+    assert_eq!(exported.lexicon["payment"], vec!["pay", "stripe"]);
+    let yaml = serde_yaml::to_string(&exported).unwrap();
+
+    let target = store.get_or_create("target");
+    let file: LexiconFile = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(import_lexicon(&target, file.clone(), ImportMode::Merge).unwrap(), 2);
+    assert_eq!(export_lexicon(&target), file);
+    // Importing again merges instead of duplicating
+    import_lexicon(&target, file.clone(), ImportMode::Merge).unwrap();
+    assert_eq!(target.lexicon.total_memories(), 2);
+
+    let edited: LexiconFile = serde_yaml::from_str("lexicon:\n  Payment: [PAY]\n").unwrap();
+    import_lexicon(&target, edited, ImportMode::Replace).unwrap();
+    assert_eq!(export_lexicon(&target).lexicon.into_iter().collect::<Vec<_>>(), vec![("payment".to_string(), vec!["pay".to_string()])]);
+
+    let empty: LexiconFile = serde_yaml::from_str("lexicon:\n  payment: []\n").unwrap();
+    assert!(import_lexicon(&target, empty, ImportMode::Replace).is_err());
+    assert_eq!(target.lexicon.total_memories(), 1);
+    assert!(ImportMode::parse(Some("append")).is_err());
+}
+
+#[test]
+fn test_alias_yaml_round_trip() {
+    let store = ProjectStore::new();
+    let ctx = store.get_or_create("aliases");
+    let yaml = "
+aliases:
+- from: pay
+  to: service:payment
+  weight: 0.9
+- from: bill
+  to: service:payment
+  status: rejected
+";
+    let file: AliasFile = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(file.aliases[1].weight, 0.85);
+    assert_eq!(import_aliases(&ctx, file.clone(), ImportMode::Merge).unwrap(), 2);
+    assert_eq!(import_aliases(&ctx, file, ImportMode::Merge).unwrap(), 2);
+    assert_eq!(ctx.aliases.total_memories(), 2);
+
+    let exported = export_aliases(&ctx);
+    assert_eq!(exported.aliases.len(), 2);
+    assert_eq!((exported.aliases[0].from.as_str(), exported.aliases[0].status.as_str()), ("bill", "rejected"));
+    assert_eq!((exported.aliases[1].from.as_str(), exported.aliases[1].weight), ("pay", 0.9));
+
+    // Only active aliases expand recalls
+    ctx.main.add_memory("card payment".to_string(), vec!["service:payment".to_string()], None, Default::default(), true);
+    let pay = vec!["pay".to_string()];
+    assert!(ctx.expand_query_cues(pay.clone(), &pay).contains(&("service:payment".to_string(), 0.9)));
+    let bill = vec!["bill".to_string()];
+    assert_eq!(ctx.expand_query_cues(bill.clone(), &bill).len(), 0);
+
+    let bad: AliasFile = serde_yaml::from_str("aliases:\n- from: a\n  to: b\n  status: maybe\n").unwrap();
+    assert!(import_aliases(&ctx, bad, ImportMode::Replace).is_err());
+    assert_eq!(ctx.aliases.total_memories(), 2);
+}